@@ -53,4 +53,12 @@ fn main() {
 
     // Tell Cargo that if the given file changes, to rerun this build script.
     println!("cargo:rerun-if-changed={}", PROTO_SRC);
+
+    // Generate the types and the services of the gRPC queries into the
+    // `OUT_DIR`, from which they are included by the gRPC server
+    tonic_build::configure()
+        .build_client(false)
+        .protoc_arg("--experimental_allow_proto3_optional")
+        .compile(&[format!("{}/query.proto", PROTO_SRC)], &[PROTO_SRC])
+        .unwrap();
 }
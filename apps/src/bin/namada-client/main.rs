@@ -1,6 +1,7 @@
 use color_eyre::eyre::Result;
-use namada_apps::cli::api::{CliApi, CliIo};
-use namada_apps::facade::tendermint_rpc::HttpClient;
+use namada_apps::cli::api::{CliApi, CliIo, CliJsonIo};
+use namada_apps::cli::args::OutputFormat;
+use namada_apps::client::failover::FailoverClient;
 use namada_apps::{cli, logging};
 use tracing_subscriber::filter::LevelFilter;
 
@@ -13,10 +14,17 @@ async fn main() -> Result<()> {
     let _log_guard = logging::init_from_env_or(LevelFilter::INFO)?;
 
     // run the CLI
-    CliApi::handle_client_command::<HttpClient, _>(
-        None,
-        cli::namada_client_cli()?,
-        CliIo,
-    )
-    .await
+    let cmd = cli::namada_client_cli()?;
+    match cmd.global_args().output {
+        OutputFormat::Text => {
+            CliApi::handle_client_command::<FailoverClient, _>(None, cmd, CliIo)
+                .await
+        }
+        OutputFormat::Json => {
+            CliApi::handle_client_command::<FailoverClient, _>(
+                None, cmd, CliJsonIo,
+            )
+            .await
+        }
+    }
 }
@@ -3,16 +3,44 @@
 use eyre::{Context, Result};
 use namada::types::time::{DateTimeUtc, Utc};
 use namada_apps::cli::{self, cmds};
-use namada_apps::config::ValidatorLocalConfig;
+use namada_apps::client::utils;
+use namada_apps::config::{self, Config, ValidatorLocalConfig};
+use namada_apps::logging;
 use namada_apps::node::ledger;
+use tracing_subscriber::filter::LevelFilter;
 
 pub fn main() -> Result<()> {
     let (cmd, mut ctx) = cli::namada_node_cli()?;
+
+    // init logging, once the log format and the log levels of modules from
+    // the config are known
+    let log_format = match &cmd {
+        cmds::NamadaNode::Ledger(cmds::Ledger::Run(cmds::LedgerRun(args))) => {
+            args.log_format
+        }
+        cmds::NamadaNode::Ledger(cmds::Ledger::RunUntil(
+            cmds::LedgerRunUntil(args),
+        )) => args.log_format,
+        _ => None,
+    };
+    let log_levels = ctx
+        .chain
+        .as_ref()
+        .map(|chain_ctx| chain_ctx.config.ledger.shell.log_levels.clone())
+        .unwrap_or_default();
+    let _log_guard =
+        logging::init_with(LevelFilter::INFO, log_format, &log_levels)?;
+
     match cmd {
         cmds::NamadaNode::Ledger(sub) => match sub {
             cmds::Ledger::Run(cmds::LedgerRun(args)) => {
-                let chain_ctx = ctx.take_chain_or_exit();
+                let mut chain_ctx = ctx.take_chain_or_exit();
                 let wasm_dir = chain_ctx.wasm_dir();
+                if let Some(mode) = args.mode {
+                    chain_ctx.config.ledger.set_node_role(mode, args.peers);
+                }
+                chain_ctx.config.ledger.shell.upgrade_ready =
+                    args.upgrade_ready;
                 sleep_until(args.start_time);
                 ledger::run(chain_ctx.config.ledger, wasm_dir);
             }
@@ -38,6 +66,17 @@ pub fn main() -> Result<()> {
                 ledger::rollback(chain_ctx.config.ledger)
                     .wrap_err("Failed to rollback the Namada node")?;
             }
+            cmds::Ledger::Replay(cmds::LedgerReplay(args)) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                let wasm_dir = chain_ctx.wasm_dir();
+                ledger::replay(chain_ctx.config.ledger, wasm_dir, args)
+                    .wrap_err("Failed to replay the blocks")?;
+            }
+            cmds::Ledger::Export(cmds::LedgerExport(args)) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                ledger::export(chain_ctx.config.ledger, args)
+                    .wrap_err("Failed to export the state")?;
+            }
         },
         cmds::NamadaNode::Config(sub) => match sub {
             cmds::Config::Gen(cmds::ConfigGen) => {
@@ -76,6 +115,20 @@ pub fn main() -> Result<()> {
                 std::fs::write(config_path, updated_config).unwrap();
             }
         },
+        cmds::NamadaNode::Dev(cmds::Dev(args)) => {
+            let wasm_dir = std::env::current_dir()?.join(
+                ctx.global_args
+                    .wasm_dir
+                    .clone()
+                    .unwrap_or_else(|| config::DEFAULT_WASM_DIR.into()),
+            );
+            let (base_dir, chain_id) = tokio::runtime::Runtime::new()?
+                .block_on(utils::init_dev_network(ctx.global_args, args));
+            let mut config = Config::load(&base_dir, &chain_id, None);
+            // Reload the wasms from the build dir when they're rebuilt
+            config.ledger.shell.dev_wasm_reload = true;
+            ledger::run(config.ledger, wasm_dir);
+        }
     }
     Ok(())
 }
@@ -1,11 +1,19 @@
 use color_eyre::eyre::Result;
 use namada_apps::cli;
-use namada_apps::cli::api::{CliApi, CliIo};
+use namada_apps::cli::api::{CliApi, CliIo, CliJsonIo};
+use namada_apps::cli::args::OutputFormat;
 
 #[tokio::main]
 pub async fn main() -> Result<()> {
     color_eyre::install()?;
     let (cmd, ctx) = cli::namada_wallet_cli()?;
     // run the CLI
-    CliApi::handle_wallet_command(cmd, ctx, &CliIo).await
+    match ctx.global_args.output {
+        OutputFormat::Text => {
+            CliApi::handle_wallet_command(cmd, ctx, &CliIo).await
+        }
+        OutputFormat::Json => {
+            CliApi::handle_wallet_command(cmd, ctx, &CliJsonIo).await
+        }
+    }
 }
@@ -392,6 +392,7 @@ impl BenchShell {
             tx,
             &mut self.inner.vp_wasm_cache,
             &mut self.inner.tx_wasm_cache,
+            &mut None,
         )
         .unwrap();
     }
@@ -746,8 +747,10 @@ impl Default for BenchShieldedCtx {
         let ctx = Context::new::<StdIo>(crate::cli::args::Global {
             is_pre_genesis: false,
             chain_id: Some(shell.inner.chain_id.clone()),
+            profile: None,
             base_dir,
             wasm_dir: Some(WASM_DIR.into()),
+            output: crate::cli::args::OutputFormat::Text,
         })
         .unwrap();
 
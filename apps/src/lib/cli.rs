@@ -123,17 +123,21 @@ pub mod cmds {
     pub enum NamadaNode {
         Ledger(Ledger),
         Config(Config),
+        Dev(Dev),
     }
 
     impl Cmd for NamadaNode {
         fn add_sub(app: App) -> App {
-            app.subcommand(Ledger::def()).subcommand(Config::def())
+            app.subcommand(Ledger::def())
+                .subcommand(Config::def())
+                .subcommand(Dev::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             let ledger = SubCmd::parse(matches).map(Self::Ledger);
             let config = SubCmd::parse(matches).map(Self::Config);
-            ledger.or(config)
+            let dev = SubCmd::parse(matches).map(Self::Dev);
+            ledger.or(config).or(dev)
         }
     }
     impl SubCmd for NamadaNode {
@@ -215,6 +219,11 @@ pub mod cmds {
                 // Simple transactions
                 .subcommand(TxCustom::def().display_order(1))
                 .subcommand(TxTransfer::def().display_order(1))
+                .subcommand(TxBatch::def().display_order(1))
+                .subcommand(FaucetRequest::def().display_order(1))
+                .subcommand(TxApprove::def().display_order(1))
+                .subcommand(TxTransferFrom::def().display_order(1))
+                .subcommand(TxEstimate::def().display_order(1))
                 .subcommand(TxIbcTransfer::def().display_order(1))
                 .subcommand(TxUpdateAccount::def().display_order(1))
                 .subcommand(TxInitAccount::def().display_order(1))
@@ -231,8 +240,10 @@ pub mod cmds {
                 .subcommand(Bond::def().display_order(2))
                 .subcommand(Unbond::def().display_order(2))
                 .subcommand(Withdraw::def().display_order(2))
+                .subcommand(AutoWithdraw::def().display_order(2))
                 .subcommand(Redelegate::def().display_order(2))
                 .subcommand(ClaimRewards::def().display_order(2))
+                .subcommand(AutoCompound::def().display_order(2))
                 .subcommand(TxCommissionRateChange::def().display_order(2))
                 .subcommand(TxChangeConsensusKey::def().display_order(2))
                 .subcommand(TxMetadataChange::def().display_order(2))
@@ -243,20 +254,26 @@ pub mod cmds {
                 .subcommand(TxResignSteward::def().display_order(4))
                 // Queries
                 .subcommand(QueryEpoch::def().display_order(5))
+                .subcommand(QueryNextEpoch::def().display_order(5))
+                .subcommand(QueryInternalAddresses::def().display_order(5))
                 .subcommand(QueryAccount::def().display_order(5))
+                .subcommand(QueryStorageUsage::def().display_order(5))
                 .subcommand(QueryTransfers::def().display_order(5))
                 .subcommand(QueryConversions::def().display_order(5))
                 .subcommand(QueryBlock::def().display_order(5))
                 .subcommand(QueryBalance::def().display_order(5))
+                .subcommand(QueryTotalSupply::def().display_order(5))
                 .subcommand(QueryBonds::def().display_order(5))
                 .subcommand(QueryBondedStake::def().display_order(5))
                 .subcommand(QuerySlashes::def().display_order(5))
                 .subcommand(QueryDelegations::def().display_order(5))
                 .subcommand(QueryFindValidator::def().display_order(5))
                 .subcommand(QueryResult::def().display_order(5))
+                .subcommand(WaitTx::def().display_order(5))
                 .subcommand(QueryRawBytes::def().display_order(5))
                 .subcommand(QueryProposal::def().display_order(5))
                 .subcommand(QueryProposalResult::def().display_order(5))
+                .subcommand(QueryProposalVotes::def().display_order(5))
                 .subcommand(QueryProtocolParameters::def().display_order(5))
                 .subcommand(QueryPgf::def().display_order(5))
                 .subcommand(QueryValidatorState::def().display_order(5))
@@ -274,6 +291,12 @@ pub mod cmds {
             use NamadaClientWithContext::*;
             let tx_custom = Self::parse_with_ctx(matches, TxCustom);
             let tx_transfer = Self::parse_with_ctx(matches, TxTransfer);
+            let tx_batch = Self::parse_with_ctx(matches, TxBatch);
+            let faucet_request = Self::parse_with_ctx(matches, FaucetRequest);
+            let tx_approve = Self::parse_with_ctx(matches, TxApprove);
+            let tx_transfer_from =
+                Self::parse_with_ctx(matches, TxTransferFrom);
+            let tx_estimate = Self::parse_with_ctx(matches, TxEstimate);
             let tx_ibc_transfer = Self::parse_with_ctx(matches, TxIbcTransfer);
             let tx_update_account =
                 Self::parse_with_ctx(matches, TxUpdateAccount);
@@ -306,15 +329,25 @@ pub mod cmds {
             let bond = Self::parse_with_ctx(matches, Bond);
             let unbond = Self::parse_with_ctx(matches, Unbond);
             let withdraw = Self::parse_with_ctx(matches, Withdraw);
+            let auto_withdraw = Self::parse_with_ctx(matches, AutoWithdraw);
             let redelegate = Self::parse_with_ctx(matches, Redelegate);
             let claim_rewards = Self::parse_with_ctx(matches, ClaimRewards);
+            let auto_compound = Self::parse_with_ctx(matches, AutoCompound);
             let query_epoch = Self::parse_with_ctx(matches, QueryEpoch);
+            let query_next_epoch =
+                Self::parse_with_ctx(matches, QueryNextEpoch);
+            let query_internal_addresses =
+                Self::parse_with_ctx(matches, QueryInternalAddresses);
             let query_account = Self::parse_with_ctx(matches, QueryAccount);
+            let query_storage_usage =
+                Self::parse_with_ctx(matches, QueryStorageUsage);
             let query_transfers = Self::parse_with_ctx(matches, QueryTransfers);
             let query_conversions =
                 Self::parse_with_ctx(matches, QueryConversions);
             let query_block = Self::parse_with_ctx(matches, QueryBlock);
             let query_balance = Self::parse_with_ctx(matches, QueryBalance);
+            let query_total_supply =
+                Self::parse_with_ctx(matches, QueryTotalSupply);
             let query_bonds = Self::parse_with_ctx(matches, QueryBonds);
             let query_bonded_stake =
                 Self::parse_with_ctx(matches, QueryBondedStake);
@@ -325,10 +358,13 @@ pub mod cmds {
             let query_find_validator =
                 Self::parse_with_ctx(matches, QueryFindValidator);
             let query_result = Self::parse_with_ctx(matches, QueryResult);
+            let wait_tx = Self::parse_with_ctx(matches, WaitTx);
             let query_raw_bytes = Self::parse_with_ctx(matches, QueryRawBytes);
             let query_proposal = Self::parse_with_ctx(matches, QueryProposal);
             let query_proposal_result =
                 Self::parse_with_ctx(matches, QueryProposalResult);
+            let query_proposal_votes =
+                Self::parse_with_ctx(matches, QueryProposalVotes);
             let query_protocol_parameters =
                 Self::parse_with_ctx(matches, QueryProtocolParameters);
             let query_pgf = Self::parse_with_ctx(matches, QueryPgf);
@@ -345,6 +381,11 @@ pub mod cmds {
             let utils = SubCmd::parse(matches).map(Self::WithoutContext);
             tx_custom
                 .or(tx_transfer)
+                .or(tx_batch)
+                .or(faucet_request)
+                .or(tx_approve)
+                .or(tx_transfer_from)
+                .or(tx_estimate)
                 .or(tx_ibc_transfer)
                 .or(tx_update_account)
                 .or(tx_init_account)
@@ -362,16 +403,21 @@ pub mod cmds {
                 .or(bond)
                 .or(unbond)
                 .or(withdraw)
+                .or(auto_withdraw)
                 .or(redelegate)
                 .or(claim_rewards)
+                .or(auto_compound)
                 .or(add_to_eth_bridge_pool)
                 .or(tx_update_steward_commission)
                 .or(tx_resign_steward)
                 .or(query_epoch)
+                .or(query_next_epoch)
+                .or(query_internal_addresses)
                 .or(query_transfers)
                 .or(query_conversions)
                 .or(query_block)
                 .or(query_balance)
+                .or(query_total_supply)
                 .or(query_bonds)
                 .or(query_bonded_stake)
                 .or(query_slashes)
@@ -379,15 +425,18 @@ pub mod cmds {
                 .or(query_delegations)
                 .or(query_find_validator)
                 .or(query_result)
+                .or(wait_tx)
                 .or(query_raw_bytes)
                 .or(query_proposal)
                 .or(query_proposal_result)
+                .or(query_proposal_votes)
                 .or(query_protocol_parameters)
                 .or(query_pgf)
                 .or(query_validator_state)
                 .or(query_commission)
                 .or(query_metadata)
                 .or(query_account)
+                .or(query_storage_usage)
                 .or(sign_tx)
                 .or(gen_ibc_shielded)
                 .or(utils)
@@ -429,8 +478,14 @@ pub mod cmds {
         // Ledger cmds
         TxCustom(TxCustom),
         TxTransfer(TxTransfer),
+        TxBatch(TxBatch),
+        FaucetRequest(FaucetRequest),
+        TxApprove(TxApprove),
+        TxTransferFrom(TxTransferFrom),
+        TxEstimate(TxEstimate),
         TxIbcTransfer(TxIbcTransfer),
         QueryResult(QueryResult),
+        WaitTx(WaitTx),
         TxUpdateAccount(TxUpdateAccount),
         TxInitAccount(TxInitAccount),
         TxBecomeValidator(TxBecomeValidator),
@@ -447,17 +502,23 @@ pub mod cmds {
         Bond(Bond),
         Unbond(Unbond),
         Withdraw(Withdraw),
+        AutoWithdraw(AutoWithdraw),
         ClaimRewards(ClaimRewards),
+        AutoCompound(AutoCompound),
         Redelegate(Redelegate),
         AddToEthBridgePool(AddToEthBridgePool),
         TxUpdateStewardCommission(TxUpdateStewardCommission),
         TxResignSteward(TxResignSteward),
         QueryEpoch(QueryEpoch),
+        QueryNextEpoch(QueryNextEpoch),
+        QueryInternalAddresses(QueryInternalAddresses),
         QueryAccount(QueryAccount),
+        QueryStorageUsage(QueryStorageUsage),
         QueryTransfers(QueryTransfers),
         QueryConversions(QueryConversions),
         QueryBlock(QueryBlock),
         QueryBalance(QueryBalance),
+        QueryTotalSupply(QueryTotalSupply),
         QueryBonds(QueryBonds),
         QueryBondedStake(QueryBondedStake),
         QueryCommissionRate(QueryCommissionRate),
@@ -468,6 +529,7 @@ pub mod cmds {
         QueryRawBytes(QueryRawBytes),
         QueryProposal(QueryProposal),
         QueryProposalResult(QueryProposalResult),
+        QueryProposalVotes(QueryProposalVotes),
         QueryProtocolParameters(QueryProtocolParameters),
         QueryPgf(QueryPgf),
         QueryValidatorState(QueryValidatorState),
@@ -485,6 +547,10 @@ pub mod cmds {
         Address(WalletAddress),
         /// MASP key, address management commands
         Masp(WalletMasp),
+        /// The history of the submitted txs
+        History(WalletHistory),
+        /// Offline derivation of an implicit address
+        DeriveAddress(WalletDeriveAddress),
     }
 
     impl Cmd for NamadaWallet {
@@ -492,13 +558,18 @@ pub mod cmds {
             app.subcommand(WalletKey::def())
                 .subcommand(WalletAddress::def())
                 .subcommand(WalletMasp::def())
+                .subcommand(WalletHistory::def())
+                .subcommand(WalletDeriveAddress::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             let key = SubCmd::parse(matches).map(Self::Key);
             let address = SubCmd::parse(matches).map(Self::Address);
             let masp = SubCmd::parse(matches).map(Self::Masp);
-            key.or(address).or(masp)
+            let history = SubCmd::parse(matches).map(Self::History);
+            let derive_address =
+                SubCmd::parse(matches).map(Self::DeriveAddress);
+            key.or(address).or(masp).or(history).or(derive_address)
         }
     }
 
@@ -980,6 +1051,53 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct WalletHistory(pub args::WalletHistory);
+
+    impl SubCmd for WalletHistory {
+        const CMD: &'static str = "history";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                WalletHistory(args::WalletHistory::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "List the transactions submitted from this wallet, with \
+                     their last known status.",
+                )
+                .add_args::<args::WalletHistory>()
+        }
+    }
+
+    /// Derive an implicit address offline
+    #[derive(Clone, Debug)]
+    pub struct WalletDeriveAddress(pub args::WalletDeriveAddress);
+
+    impl SubCmd for WalletDeriveAddress {
+        const CMD: &'static str = "derive-address";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                WalletDeriveAddress(args::WalletDeriveAddress::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Print the implicit address of the given public key, or \
+                     of the public key derived from a mnemonic code and HD \
+                     derivation path. Neither the wallet nor the chain are \
+                     accessed, so this can be used in a cold environment.",
+                )
+                .add_args::<args::WalletDeriveAddress>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Ledger {
         Run(LedgerRun),
@@ -987,6 +1105,8 @@ pub mod cmds {
         Reset(LedgerReset),
         DumpDb(LedgerDumpDb),
         RollBack(LedgerRollBack),
+        Replay(LedgerReplay),
+        Export(LedgerExport),
     }
 
     impl SubCmd for Ledger {
@@ -999,13 +1119,21 @@ pub mod cmds {
                 let dump_db = SubCmd::parse(matches).map(Self::DumpDb);
                 let rollback = SubCmd::parse(matches).map(Self::RollBack);
                 let run_until = SubCmd::parse(matches).map(Self::RunUntil);
+                let replay = SubCmd::parse(matches).map(Self::Replay);
+                let export = SubCmd::parse(matches).map(Self::Export);
                 run.or(reset)
                     .or(dump_db)
                     .or(rollback)
                     .or(run_until)
+                    .or(replay)
+                    .or(export)
                     // The `run` command is the default if no sub-command given
                     .or(Some(Self::Run(LedgerRun(args::LedgerRun {
                         start_time: None,
+                        log_format: None,
+                        mode: None,
+                        peers: vec![],
+                        upgrade_ready: false,
                     }))))
             })
         }
@@ -1021,6 +1149,8 @@ pub mod cmds {
                 .subcommand(LedgerReset::def())
                 .subcommand(LedgerDumpDb::def())
                 .subcommand(LedgerRollBack::def())
+                .subcommand(LedgerReplay::def())
+                .subcommand(LedgerExport::def())
         }
     }
 
@@ -1122,6 +1252,55 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerReplay(pub args::LedgerReplay);
+
+    impl SubCmd for LedgerReplay {
+        const CMD: &'static str = "replay";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerReplay::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Re-execute historical blocks on top of the local DB and \
+                     check that they reproduce the app hashes and events of \
+                     the chain. The local DB must be at the height preceding \
+                     the first block and is advanced to the last one, so the \
+                     replay should be run on a copy of the node's base \
+                     directory.",
+                )
+                .add_args::<args::LedgerReplay>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerExport(pub args::LedgerExport);
+
+    impl SubCmd for LedgerExport {
+        const CMD: &'static str = "export";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerExport::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Export the typed state of the given modules at a block \
+                     height into a JSON file, e.g. to compare the state \
+                     across upgrades.",
+                )
+                .add_args::<args::LedgerExport>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Config {
         Gen(ConfigGen),
@@ -1185,6 +1364,29 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Dev(pub args::Dev);
+
+    impl SubCmd for Dev {
+        const CMD: &'static str = "dev";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::Dev::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Run a single validator development chain with pre-funded \
+                     test accounts and short epochs. The tx and VP wasms are \
+                     reloaded from the wasm directory when they are rebuilt.",
+                )
+                .add_args::<args::Dev>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryResult(pub args::QueryResult<args::CliTypes>);
 
@@ -1204,6 +1406,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct WaitTx(pub args::WaitTx<args::CliTypes>);
+
+    impl SubCmd for WaitTx {
+        const CMD: &'static str = "wait-tx";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| WaitTx(args::WaitTx::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Wait for a transaction to be applied, e.g. after \
+                     submitting it with `--broadcast-only`.",
+                )
+                .add_args::<args::WaitTx<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryProposal(pub args::QueryProposal<args::CliTypes>);
 
@@ -1250,6 +1474,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryProposalVotes(pub args::QueryProposalVotes<args::CliTypes>);
+
+    impl SubCmd for QueryProposalVotes {
+        const CMD: &'static str = "query-proposal-votes";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryProposalVotes(args::QueryProposalVotes::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the votes cast on a proposal.")
+                .add_args::<args::QueryProposalVotes<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryProtocolParameters(
         pub args::QueryProtocolParameters<args::CliTypes>,
@@ -1336,6 +1582,119 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct FaucetRequest(pub args::FaucetRequest<args::CliTypes>);
+
+    impl SubCmd for FaucetRequest {
+        const CMD: &'static str = "faucet-request";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                FaucetRequest(args::FaucetRequest::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Withdraw tokens from the testnet faucet, solving its PoW \
+                     challenge if it requires one.",
+                )
+                .add_args::<args::FaucetRequest<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxApprove(pub args::TxApprove<args::CliTypes>);
+
+    impl SubCmd for TxApprove {
+        const CMD: &'static str = "approve";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxApprove(args::TxApprove::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to allow a spender to transfer \
+                     tokens out of the owner's balance.",
+                )
+                .add_args::<args::TxApprove<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxTransferFrom(pub args::TxTransferFrom<args::CliTypes>);
+
+    impl SubCmd for TxTransferFrom {
+        const CMD: &'static str = "transfer-from";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxTransferFrom(args::TxTransferFrom::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to transfer tokens out of the \
+                     allowance given to the spender by the source.",
+                )
+                .add_args::<args::TxTransferFrom<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxBatch(pub args::TxBatchFile);
+
+    impl SubCmd for TxBatch {
+        const CMD: &'static str = "batch";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxBatch(args::TxBatchFile::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Submit a batch of transfers and bonds read from a JSON \
+                     file, one transaction after the other.",
+                )
+                .add_args::<args::TxBatchFile>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxEstimate(pub args::TxEstimate<args::CliTypes>);
+
+    impl SubCmd for TxEstimate {
+        const CMD: &'static str = "estimate";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxEstimate(args::TxEstimate::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Estimate the gas, the fee and the size of a transaction \
+                     by dry-running it against the latest state, without \
+                     submitting it.",
+                )
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .add_args::<args::TxEstimate<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxIbcTransfer(pub args::TxIbcTransfer<args::CliTypes>);
 
@@ -1567,6 +1926,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct AutoWithdraw(pub args::AutoWithdraw<args::CliTypes>);
+
+    impl SubCmd for AutoWithdraw {
+        const CMD: &'static str = "auto-withdraw";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| AutoWithdraw(args::AutoWithdraw::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Periodically withdraw the matured unbonds of an owner's \
+                     PoS bonds.",
+                )
+                .add_args::<args::AutoWithdraw<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct ClaimRewards(pub args::ClaimRewards<args::CliTypes>);
 
@@ -1589,6 +1970,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct AutoCompound(pub args::AutoCompound<args::CliTypes>);
+
+    impl SubCmd for AutoCompound {
+        const CMD: &'static str = "auto-compound";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| AutoCompound(args::AutoCompound::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Opt a bond in or out of the automatic bonding of its \
+                     rewards to the same validator at every new epoch.",
+                )
+                .add_args::<args::AutoCompound<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct Redelegate(pub args::Redelegate<args::CliTypes>);
 
@@ -1630,75 +2033,161 @@ pub mod cmds {
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryAccount(pub args::QueryAccount<args::CliTypes>);
+    pub struct QueryInternalAddresses(pub args::Query<args::CliTypes>);
 
-    impl SubCmd for QueryAccount {
-        const CMD: &'static str = "query-account";
+    impl SubCmd for QueryInternalAddresses {
+        const CMD: &'static str = "internal-addresses";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches
-                .subcommand_matches(Self::CMD)
-                .map(|matches| QueryAccount(args::QueryAccount::parse(matches)))
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryInternalAddresses(args::Query::parse(matches))
+            })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
                 .about(
-                    "Query the substorage space of a specific enstablished \
-                     address.",
+                    "Query the internal addresses of the system accounts with \
+                     their stable names.",
                 )
-                .add_args::<args::QueryAccount<args::CliTypes>>()
+                .add_args::<args::Query<args::CliTypes>>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryConversions(pub args::QueryConversions<args::CliTypes>);
+    pub struct QueryNextEpoch(pub args::QueryNextEpoch<args::CliTypes>);
 
-    impl SubCmd for QueryConversions {
-        const CMD: &'static str = "conversions";
+    impl SubCmd for QueryNextEpoch {
+        const CMD: &'static str = "next-epoch";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches.subcommand_matches(Self::CMD).map(|matches| {
-                QueryConversions(args::QueryConversions::parse(matches))
+                QueryNextEpoch(args::QueryNextEpoch::parse(matches))
             })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query currently applicable conversions.")
-                .add_args::<args::QueryConversions<args::CliTypes>>()
+                .about(
+                    "Estimate the height and the time of the start of the \
+                     next epoch, or of a later epoch, based on the recent \
+                     block times and the epoch duration parameters.",
+                )
+                .add_args::<args::QueryNextEpoch<args::CliTypes>>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryBlock(pub args::Query<args::CliTypes>);
+    pub struct QueryAccount(pub args::QueryAccount<args::CliTypes>);
 
-    impl SubCmd for QueryBlock {
-        const CMD: &'static str = "block";
+    impl SubCmd for QueryAccount {
+        const CMD: &'static str = "query-account";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| QueryBlock(args::Query::parse(matches)))
+                .map(|matches| QueryAccount(args::QueryAccount::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query the last committed block.")
-                .add_args::<args::Query<args::CliTypes>>()
+                .about(
+                    "Query the substorage space of a specific enstablished \
+                     address.",
+                )
+                .add_args::<args::QueryAccount<args::CliTypes>>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryBalance(pub args::QueryBalance<args::CliTypes>);
+    pub struct QueryStorageUsage(pub args::QueryStorageUsage<args::CliTypes>);
 
-    impl SubCmd for QueryBalance {
-        const CMD: &'static str = "balance";
+    impl SubCmd for QueryStorageUsage {
+        const CMD: &'static str = "storage-usage";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches
-                .subcommand_matches(Self::CMD)
-                .map(|matches| QueryBalance(args::QueryBalance::parse(matches)))
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryStorageUsage(args::QueryStorageUsage::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the number of bytes of state owned by an address, \
+                     or by all the addresses.",
+                )
+                .add_args::<args::QueryStorageUsage<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryTotalSupply(pub args::QueryTotalSupply<args::CliTypes>);
+
+    impl SubCmd for QueryTotalSupply {
+        const CMD: &'static str = "total-supply";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryTotalSupply(args::QueryTotalSupply::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the total supply of a token.")
+                .add_args::<args::QueryTotalSupply<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryConversions(pub args::QueryConversions<args::CliTypes>);
+
+    impl SubCmd for QueryConversions {
+        const CMD: &'static str = "conversions";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryConversions(args::QueryConversions::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query currently applicable conversions.")
+                .add_args::<args::QueryConversions<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryBlock(pub args::Query<args::CliTypes>);
+
+    impl SubCmd for QueryBlock {
+        const CMD: &'static str = "block";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryBlock(args::Query::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the last committed block.")
+                .add_args::<args::Query<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryBalance(pub args::QueryBalance<args::CliTypes>);
+
+    impl SubCmd for QueryBalance {
+        const CMD: &'static str = "balance";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryBalance(args::QueryBalance::parse(matches)))
         }
 
         fn def() -> App {
@@ -2209,6 +2698,7 @@ pub mod cmds {
         EpochSleep(EpochSleep),
         ValidateGenesisTemplates(ValidateGenesisTemplates),
         SignGenesisTxs(SignGenesisTxs),
+        MergeSignatures(MergeSignatures),
     }
 
     impl SubCmd for Utils {
@@ -2240,6 +2730,8 @@ pub mod cmds {
                     SubCmd::parse(matches).map(Self::ValidateGenesisTemplates);
                 let genesis_tx =
                     SubCmd::parse(matches).map(Self::SignGenesisTxs);
+                let merge_signatures =
+                    SubCmd::parse(matches).map(Self::MergeSignatures);
                 join_network
                     .or(fetch_wasms)
                     .or(validate_wasm)
@@ -2253,6 +2745,7 @@ pub mod cmds {
                     .or(epoch_sleep)
                     .or(validate_genesis_templates)
                     .or(genesis_tx)
+                    .or(merge_signatures)
             })
         }
 
@@ -2272,6 +2765,7 @@ pub mod cmds {
                 .subcommand(EpochSleep::def())
                 .subcommand(ValidateGenesisTemplates::def())
                 .subcommand(SignGenesisTxs::def())
+                .subcommand(MergeSignatures::def())
                 .subcommand_required(true)
                 .arg_required_else_help(true)
         }
@@ -2310,7 +2804,10 @@ pub mod cmds {
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Ensure pre-built wasms are present")
+                .about(
+                    "Download the wasms of the chain's manifest that are \
+                     missing and verify their hashes.",
+                )
                 .add_args::<args::FetchWasms>()
         }
     }
@@ -2908,6 +3405,31 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct MergeSignatures(pub args::MergeSignatures);
+
+    impl SubCmd for MergeSignatures {
+        const CMD: &'static str = "merge-signatures";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::MergeSignatures::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Merge the signatures of the copies of a partially signed \
+                     transaction made by different signers, e.g. of a \
+                     multisig account, into one partially signed \
+                     transaction. The merged file can be given to \
+                     `--signatures` to submit the transaction.",
+                )
+                .add_args::<args::MergeSignatures>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct DefaultBaseDir(pub args::DefaultBaseDir);
 
@@ -2939,8 +3461,10 @@ pub mod args {
     use std::net::SocketAddr;
     use std::path::PathBuf;
     use std::str::FromStr;
+    use std::time::Duration as StdDuration;
 
     use namada::ibc::core::host::types::identifiers::{ChannelId, PortId};
+    use namada::types::account::VestingSchedule;
     use namada::types::address::{Address, EstablishedAddress};
     use namada::types::chain::{ChainId, ChainIdPrefix};
     use namada::types::dec::Dec;
@@ -2955,34 +3479,45 @@ pub mod args {
     use namada::types::transaction::GasLimit;
     pub use namada_sdk::args::*;
     pub use namada_sdk::tx::{
-        TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM, TX_BRIDGE_POOL_WASM,
-        TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
-        TX_CHANGE_METADATA_WASM, TX_CLAIM_REWARDS_WASM,
-        TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
+        TX_APPROVE_WASM, TX_AUTO_COMPOUND_WASM, TX_BECOME_VALIDATOR_WASM,
+        TX_BOND_WASM, TX_BRIDGE_POOL_WASM, TX_CHANGE_COMMISSION_WASM,
+        TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_METADATA_WASM,
+        TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM,
+        TX_FAUCET_WITHDRAW_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
         TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM, TX_REDELEGATE_WASM,
-        TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM,
-        TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
-        TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-        VP_USER_WASM,
+        TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_FROM_WASM,
+        TX_TRANSFER_WASM, TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM,
+        TX_UPDATE_ACCOUNT_WASM, TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL,
+        TX_WITHDRAW_WASM, VP_USER_WASM, VP_VESTING_WASM,
     };
 
     use super::context::*;
     use super::utils::*;
     use super::{ArgGroup, ArgMatches};
+    use crate::client::failover::LedgerAddresses;
     use crate::client::utils::PRE_GENESIS_DIR;
     use crate::config::genesis::GenesisAddress;
     use crate::config::{self, Action, ActionAtHeight};
     use crate::facade::tendermint::Timeout;
     use crate::facade::tendermint_config::net::Address as TendermintAddress;
+    use crate::logging;
+    use crate::node::ledger::export;
 
     pub const ADDRESS: Arg<WalletAddress> = arg("address");
     pub const ALIAS_OPT: ArgOpt<String> = ALIAS.opt();
     pub const ALIAS: Arg<String> = arg("alias");
     pub const ALIAS_FORCE: ArgFlag = flag("alias-force");
     pub const ALIAS_MANY: ArgMulti<String, GlobPlus> = arg_multi("aliases");
+    pub const ALLOWED_TXS: ArgMulti<PathBuf, GlobStar> =
+        arg_multi("allowed-txs");
+    pub const ALLOW_ALL_TXS: ArgFlag = flag("allow-all-txs");
     pub const ALLOW_DUPLICATE_IP: ArgFlag = flag("allow-duplicate-ip");
     pub const AMOUNT: Arg<token::DenominatedAmount> = arg("amount");
     pub const ARCHIVE_DIR: ArgOpt<PathBuf> = arg_opt("archive-dir");
+    pub const AUTO_WITHDRAW_INTERVAL: ArgDefault<Duration> = arg_default(
+        "interval",
+        DefaultFn(|| Duration(StdDuration::from_secs(60 * 60))),
+    );
     pub const BALANCE_OWNER: ArgOpt<WalletBalanceOwner> = arg_opt("owner");
     pub const BASE_DIR: ArgDefault<PathBuf> = arg_default(
         "base-dir",
@@ -2991,8 +3526,11 @@ pub mod args {
             Err(_) => config::get_default_namada_folder(),
         }),
     );
+    pub const BATCH_FILE: Arg<PathBuf> = arg("file");
     pub const BLOCK_HEIGHT: Arg<BlockHeight> = arg("block-height");
+    pub const BLOCK_HEIGHT_FROM: Arg<BlockHeight> = arg("from");
     pub const BLOCK_HEIGHT_OPT: ArgOpt<BlockHeight> = arg_opt("height");
+    pub const BLOCK_HEIGHT_TO: Arg<BlockHeight> = arg("to");
     pub const BRIDGE_POOL_GAS_AMOUNT: ArgDefault<token::DenominatedAmount> =
         arg_default(
             "pool-gas-amount",
@@ -3006,10 +3544,11 @@ pub mod args {
     pub const BRIDGE_POOL_GAS_TOKEN: ArgDefaultFromCtx<WalletAddress> =
         arg_default_from_ctx(
             "pool-gas-token",
-            DefaultFn(|| "NAM".parse().unwrap()),
+            DefaultFn(|| NATIVE_TOKEN_ARG.parse().unwrap()),
         );
     pub const BRIDGE_POOL_TARGET: Arg<EthAddress> = arg("target");
     pub const BROADCAST_ONLY: ArgFlag = flag("broadcast-only");
+    pub const CANCEL_VP_UPDATE: ArgFlag = flag("cancel-vp-update");
     pub const CHAIN_ID: Arg<ChainId> = arg("chain-id");
     pub const CHAIN_ID_OPT: ArgOpt<ChainId> = CHAIN_ID.opt();
     pub const CHAIN_ID_PREFIX: Arg<ChainIdPrefix> = arg("chain-prefix");
@@ -3031,6 +3570,11 @@ pub mod args {
     pub const DATA_PATH: Arg<PathBuf> = arg("data-path");
     pub const DECRYPT: ArgFlag = flag("decrypt");
     pub const DESCRIPTION_OPT: ArgOpt<String> = arg_opt("description");
+    pub const DEV_TEMPLATES_PATH: ArgDefault<PathBuf> =
+        arg_default("templates-path", DefaultFn(|| "genesis/localnet".into()));
+    pub const EPOCH_DURATION: ArgDefault<u64> =
+        arg_default("epoch-duration", DefaultFn(|| 10));
+    pub const DISABLE: ArgFlag = flag("disable");
     pub const DISPOSABLE_SIGNING_KEY: ArgFlag = flag("disposable-gas-payer");
     pub const DESTINATION_VALIDATOR: Arg<WalletAddress> =
         arg("destination-validator");
@@ -3039,6 +3583,7 @@ pub mod args {
     pub const DONT_PREFETCH_WASM: ArgFlag = flag("dont-prefetch-wasm");
     pub const DRY_RUN_TX: ArgFlag = flag("dry-run");
     pub const DRY_RUN_WRAPPER_TX: ArgFlag = flag("dry-run-wrapper");
+    pub const DUMP_BROADCAST_TX: ArgFlag = flag("dump-broadcast-tx");
     pub const DUMP_TX: ArgFlag = flag("dump-tx");
     pub const EPOCH: ArgOpt<Epoch> = arg_opt("epoch");
     pub const ERC20: Arg<EthAddress> = arg("erc20");
@@ -3064,7 +3609,10 @@ pub mod args {
     pub const GAS_LIMIT: ArgDefault<GasLimit> =
         arg_default("gas-limit", DefaultFn(|| GasLimit::from(25_000)));
     pub const FEE_TOKEN: ArgDefaultFromCtx<WalletAddress> =
-        arg_default_from_ctx("gas-token", DefaultFn(|| "NAM".parse().unwrap()));
+        arg_default_from_ctx(
+            "gas-token",
+            DefaultFn(|| NATIVE_TOKEN_ARG.parse().unwrap()),
+        );
     pub const FEE_PAYER: Arg<WalletAddress> = arg("fee-payer");
     pub const FEE_AMOUNT: ArgDefault<token::DenominatedAmount> = arg_default(
         "fee-amount",
@@ -3086,23 +3634,34 @@ pub mod args {
         arg_default("hd-path", DefaultFn(|| "default".to_string()));
     pub const HISTORIC: ArgFlag = flag("historic");
     pub const IBC_TRANSFER_MEMO_PATH: ArgOpt<PathBuf> = arg_opt("memo-path");
+    pub const IF_MATURED: ArgFlag = flag("if-matured");
     pub const INPUT_OPT: ArgOpt<PathBuf> = arg_opt("input");
     pub const LEDGER_ADDRESS_ABOUT: &str =
         "Address of a ledger node as \"{scheme}://{host}:{port}\". If the \
-         scheme is not supplied, it is assumed to be TCP.";
-    pub const LEDGER_ADDRESS_DEFAULT: ArgDefault<TendermintAddress> =
+         scheme is not supplied, it is assumed to be TCP. Multiple \
+         comma-separated addresses can be given to fail over to the next \
+         node when a node cannot be reached.";
+    pub const LEDGER_ADDRESS_DEFAULT: ArgDefault<LedgerAddresses> =
         LEDGER_ADDRESS.default(DefaultFn(|| {
             let raw = "127.0.0.1:26657";
-            TendermintAddress::from_str(raw).unwrap()
+            TendermintAddress::from_str(raw).unwrap().into()
         }));
 
-    pub const LEDGER_ADDRESS: Arg<TendermintAddress> = arg("node");
+    pub const LEDGER_ADDRESS: Arg<LedgerAddresses> = arg("node");
+    pub const LEDGER_ADDRESS_OPT: ArgOpt<LedgerAddresses> =
+        LEDGER_ADDRESS.opt();
     pub const LOCALHOST: ArgFlag = flag("localhost");
+    pub const LOG_FORMAT: ArgOpt<logging::Fmt> = arg_opt("log-format");
+    pub const LOG_FORMAT_ABOUT: &str =
+        "The format of the logs, \"full\", \"pretty\" or \"json\". This \
+         takes precedence over the `NAMADA_LOG_FMT` environment variable.";
     pub const MASP_VALUE: Arg<MaspValue> = arg("value");
     pub const MAX_COMMISSION_RATE_CHANGE: Arg<Dec> =
         arg("max-commission-rate-change");
     pub const MAX_ETH_GAS: ArgOpt<u64> = arg_opt("max_eth-gas");
-    pub const MODE: ArgOpt<String> = arg_opt("mode");
+    pub const MODE: ArgOpt<config::NodeRole> = arg_opt("mode");
+    pub const MODULES: ArgMulti<export::Module, GlobPlus> =
+        arg_multi("modules");
     pub const NET_ADDRESS: Arg<SocketAddr> = arg("net-address");
     pub const NAMADA_START_TIME: ArgOpt<DateTimeUtc> = arg_opt("time");
     pub const NO_CONVERSIONS: ArgFlag = flag("no-conversions");
@@ -3111,15 +3670,24 @@ pub mod args {
     pub const OUTPUT: ArgOpt<PathBuf> = arg_opt("output");
     pub const OUTPUT_FOLDER_PATH: ArgOpt<PathBuf> =
         arg_opt("output-folder-path");
+    pub const OUTPUT_FORMAT: ArgDefault<OutputFormat> =
+        arg_default("output", DefaultFn(|| OutputFormat::Text));
     pub const OWNER: Arg<WalletAddress> = arg("owner");
     pub const OWNER_OPT: ArgOpt<WalletAddress> = OWNER.opt();
+    pub const PARTIALLY_SIGNED_TXS: ArgMulti<PathBuf, GlobPlus> =
+        arg_multi("partially-signed-txs");
+    pub const PAGINATE: ArgFlag = flag("paginate");
     pub const PATH: Arg<PathBuf> = arg("path");
+    pub const PEERS: ArgMulti<TendermintAddress, GlobStar> = arg_multi("peers");
     pub const PIN: ArgFlag = flag("pin");
+    pub const POLICY_PATH: ArgOpt<PathBuf> = arg_opt("policy-path");
     pub const PORT_ID: ArgDefault<PortId> = arg_default(
         "port-id",
         DefaultFn(|| PortId::from_str("transfer").unwrap()),
     );
     pub const PRE_GENESIS: ArgFlag = flag("pre-genesis");
+    pub const PRINT_SIGN_DOC: ArgFlag = flag("print-sign-doc");
+    pub const PROFILE: ArgOpt<String> = arg_opt("profile");
     pub const PROPOSAL_ETH: ArgFlag = flag("eth");
     pub const PROPOSAL_PGF_STEWARD: ArgFlag = flag("pgf-stewards");
     pub const PROPOSAL_PGF_FUNDING: ArgFlag = flag("pgf-funding");
@@ -3141,13 +3709,17 @@ pub mod args {
     pub const RAW_PUBLIC_KEY_OPT: ArgOpt<common::PublicKey> =
         arg_opt("public-key");
     pub const RECEIVER: Arg<String> = arg("receiver");
+    pub const RECOVERY_KEY: ArgOpt<WalletPublicKey> = arg_opt("recovery-key");
     pub const RELAYER: Arg<Address> = arg("relayer");
+    pub const RESET: ArgFlag = flag("reset");
     pub const SAFE_MODE: ArgFlag = flag("safe-mode");
+    pub const SALT: ArgOpt<String> = arg_opt("salt");
     pub const SCHEME: ArgDefault<SchemeType> =
         arg_default("scheme", DefaultFn(|| SchemeType::Ed25519));
     pub const SELF_BOND_AMOUNT: Arg<token::DenominatedAmount> =
         arg("self-bond-amount");
     pub const SENDER: Arg<String> = arg("sender");
+    pub const SEQUENCE: ArgOpt<u64> = arg_opt("sequence");
     pub const SIGNER: ArgOpt<WalletAddress> = arg_opt("signer");
     pub const SIGNING_KEYS: ArgMulti<WalletPublicKey, GlobStar> =
         arg_multi("signing-keys");
@@ -3156,8 +3728,10 @@ pub mod args {
     pub const SOURCE_OPT: ArgOpt<WalletAddress> = SOURCE.opt();
     pub const STEWARD: Arg<WalletAddress> = arg("steward");
     pub const SOURCE_VALIDATOR: Arg<WalletAddress> = arg("source-validator");
+    pub const SPENDER: Arg<WalletAddress> = arg("spender");
     pub const STORAGE_KEY: Arg<storage::Key> = arg("storage-key");
     pub const SUSPEND_ACTION: ArgFlag = flag("suspend");
+    pub const TARGET: Arg<WalletAddress> = arg("target");
     pub const TEMPLATES_PATH: Arg<PathBuf> = arg("templates-path");
     pub const TIMEOUT_HEIGHT: ArgOpt<u64> = arg_opt("timeout-height");
     pub const TIMEOUT_SEC_OFFSET: ArgOpt<u64> = arg_opt("timeout-sec-offset");
@@ -3170,6 +3744,7 @@ pub mod args {
     pub const THRESHOLD: ArgOpt<u8> = arg_opt("threshold");
     pub const UNSAFE_DONT_ENCRYPT: ArgFlag = flag("unsafe-dont-encrypt");
     pub const UNSAFE_SHOW_SECRET: ArgFlag = flag("unsafe-show-secret");
+    pub const UPGRADE_READY: ArgFlag = flag("upgrade-ready");
     pub const USE_DEVICE: ArgFlag = flag("use-device");
     pub const VALIDATOR: Arg<WalletAddress> = arg("validator");
     pub const VALIDATOR_OPT: ArgOpt<WalletAddress> = VALIDATOR.opt();
@@ -3186,22 +3761,60 @@ pub mod args {
     pub const VALIDATOR_ETH_HOT_KEY: ArgOpt<WalletPublicKey> =
         arg_opt("eth-hot-key");
     pub const VALUE: ArgOpt<String> = arg_opt("value");
+    pub const VESTING_AMOUNT: ArgOpt<token::DenominatedAmount> =
+        arg_opt("vesting-amount");
+    pub const VESTING_CLIFF_EPOCH: ArgOpt<Epoch> =
+        arg_opt("vesting-cliff-epoch");
+    pub const VESTING_END_EPOCH: ArgOpt<Epoch> = arg_opt("vesting-end-epoch");
+    pub const VESTING_START_EPOCH: ArgOpt<Epoch> =
+        arg_opt("vesting-start-epoch");
     pub const VIEWING_KEY: Arg<WalletViewingKey> = arg("key");
     pub const VP: ArgOpt<String> = arg_opt("vp");
+    pub const VP_UPDATE_DELAY: ArgOpt<u64> = arg_opt("vp-update-delay");
+    pub const WAIT_TIMEOUT: ArgDefault<Duration> = arg_default(
+        "timeout",
+        DefaultFn(|| Duration(StdDuration::from_secs(60))),
+    );
     pub const WALLET_ALIAS_FORCE: ArgFlag = flag("wallet-alias-force");
     pub const WASM_CHECKSUMS_PATH: Arg<PathBuf> = arg("wasm-checksums-path");
     pub const WASM_DIR: ArgOpt<PathBuf> = arg_opt("wasm-dir");
     pub const WEBSITE_OPT: ArgOpt<String> = arg_opt("website");
+    pub const YES: ArgFlag = flag("yes");
     pub const TX_PATH: Arg<PathBuf> = arg("tx-path");
     pub const TX_PATH_OPT: ArgOpt<PathBuf> = TX_PATH.opt();
 
+    /// The format of the output of the commands
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum OutputFormat {
+        /// Human-readable text
+        Text,
+        /// JSON results on stdout, with the human-readable text on stderr
+        Json,
+    }
+
+    impl FromStr for OutputFormat {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "text" => Ok(Self::Text),
+                "json" => Ok(Self::Json),
+                _ => Err(format!(
+                    "Unknown output format {s}, expected \"text\" or \"json\""
+                )),
+            }
+        }
+    }
+
     /// Global command arguments
     #[derive(Clone, Debug)]
     pub struct Global {
         pub is_pre_genesis: bool,
         pub chain_id: Option<ChainId>,
+        pub profile: Option<String>,
         pub base_dir: PathBuf,
         pub wasm_dir: Option<PathBuf>,
+        pub output: OutputFormat,
     }
 
     impl Global {
@@ -3209,13 +3822,17 @@ pub mod args {
         pub fn parse(matches: &ArgMatches) -> Self {
             let is_pre_genesis = PRE_GENESIS.parse(matches);
             let chain_id = CHAIN_ID_OPT.parse(matches);
+            let profile = PROFILE.parse(matches);
             let base_dir = BASE_DIR.parse(matches);
             let wasm_dir = WASM_DIR.parse(matches);
+            let output = OUTPUT_FORMAT.parse(matches);
             Global {
                 is_pre_genesis,
                 chain_id,
+                profile,
                 base_dir,
                 wasm_dir,
+                output,
             }
         }
 
@@ -3223,6 +3840,12 @@ pub mod args {
         /// command.
         pub fn def(app: App) -> App {
             app.arg(CHAIN_ID_OPT.def().help("The chain ID."))
+                .arg(PROFILE.def().help(
+                    "The name of a profile from the global config. The \
+                     profile's chain ID is used and its ledger address and \
+                     gas settings are used for the arguments that are not \
+                     given explicitly.",
+                ))
                 .arg(BASE_DIR.def().help(
                     "The base directory is where the nodes, client and wallet \
                      configuration and state is stored. This value can also \
@@ -3244,18 +3867,37 @@ pub mod args {
                         .def()
                         .help("Dispatch pre-genesis specific logic."),
                 )
+                .arg(OUTPUT_FORMAT.def().help(
+                    "The output format, \"text\" or \"json\". With \"json\", \
+                     the results are printed as JSON on stdout and the \
+                     human-readable messages on stderr. Defaults to \"text\".",
+                ))
         }
     }
 
     #[derive(Clone, Debug)]
     pub struct LedgerRun {
         pub start_time: Option<DateTimeUtc>,
+        pub log_format: Option<logging::Fmt>,
+        pub mode: Option<config::NodeRole>,
+        pub peers: Vec<TendermintAddress>,
+        pub upgrade_ready: bool,
     }
 
     impl Args for LedgerRun {
         fn parse(matches: &ArgMatches) -> Self {
             let start_time = NAMADA_START_TIME.parse(matches);
-            Self { start_time }
+            let log_format = LOG_FORMAT.parse(matches);
+            let mode = MODE.parse(matches);
+            let peers = PEERS.parse(matches);
+            let upgrade_ready = UPGRADE_READY.parse(matches);
+            Self {
+                start_time,
+                log_format,
+                mode,
+                peers,
+                upgrade_ready,
+            }
         }
 
         fn def(app: App) -> App {
@@ -3267,6 +3909,24 @@ pub mod args {
                  equivalent:\n2023-01-20T12:12:12Z\n2023-01-20 \
                  12:12:12Z\n2023-  01-20T12:  12:12Z",
             ))
+            .arg(LOG_FORMAT.def().help(LOG_FORMAT_ABOUT))
+            .arg(MODE.def().help(
+                "The role of the node in a sentry architecture, \"seed\", \
+                 \"sentry\" or \"validator\". Pre-configures the CometBFT \
+                 P2P settings of the role, overriding the ones of the config \
+                 file.",
+            ))
+            .arg(PEERS.def().requires(MODE.name).help(
+                "The addresses of the validators behind a sentry or of the \
+                 sentries of a validator, in the form \
+                 \"tcp://<node-id>@<host>:<port>\". A sentry keeps them \
+                 private and a validator only connects to them.",
+            ))
+            .arg(UPGRADE_READY.def().help(
+                "Apply the upgrade of the chain scheduled by governance when \
+                 its height is reached. The version of this binary must \
+                 match the name of the upgrade.",
+            ))
         }
     }
 
@@ -3274,12 +3934,14 @@ pub mod args {
     pub struct LedgerRunUntil {
         pub time: Option<DateTimeUtc>,
         pub action_at_height: ActionAtHeight,
+        pub log_format: Option<logging::Fmt>,
     }
 
     impl Args for LedgerRunUntil {
         fn parse(matches: &ArgMatches) -> Self {
             Self {
                 time: NAMADA_START_TIME.parse(matches),
+                log_format: LOG_FORMAT.parse(matches),
                 action_at_height: ActionAtHeight {
                     height: BLOCK_HEIGHT.parse(matches),
                     action: if HALT_ACTION.parse(matches) {
@@ -3304,6 +3966,7 @@ pub mod args {
                     .def()
                     .help("Suspend consensus at the given block height"),
             )
+            .arg(LOG_FORMAT.def().help(LOG_FORMAT_ABOUT))
             .group(
                 ArgGroup::new("find_flags")
                     .args([HALT_ACTION.name, SUSPEND_ACTION.name])
@@ -3353,6 +4016,153 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerReplay {
+        pub from: BlockHeight,
+        pub to: BlockHeight,
+        pub ledger_address: Option<LedgerAddresses>,
+    }
+
+    impl Args for LedgerReplay {
+        fn parse(matches: &ArgMatches) -> Self {
+            Self {
+                from: BLOCK_HEIGHT_FROM.parse(matches),
+                to: BLOCK_HEIGHT_TO.parse(matches),
+                ledger_address: LEDGER_ADDRESS_OPT.parse(matches),
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                BLOCK_HEIGHT_FROM
+                    .def()
+                    .help("The height of the first block to replay."),
+            )
+            .arg(
+                BLOCK_HEIGHT_TO
+                    .def()
+                    .help("The height of the last block to replay."),
+            )
+            .arg(LEDGER_ADDRESS_OPT.def().help(
+                "The CometBFT RPC of the node to fetch the blocks from, e.g. \
+                 an archive node. Defaults to the RPC address of the local \
+                 node's config.",
+            ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerExport {
+        pub modules: Vec<export::Module>,
+        pub block_height: Option<BlockHeight>,
+        pub out_file_path: PathBuf,
+    }
+
+    impl Args for LedgerExport {
+        fn parse(matches: &ArgMatches) -> Self {
+            let modules = MODULES.parse(matches);
+            let block_height = BLOCK_HEIGHT_OPT.parse(matches);
+            let out_file_path = OUT_FILE_PATH_OPT
+                .parse(matches)
+                .unwrap_or_else(|| PathBuf::from("state_export".to_string()));
+
+            Self {
+                modules,
+                block_height,
+                out_file_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(MODULES.def().help(
+                "Comma-separated list of the modules to export, out of \
+                 \"pos\", \"governance\", \"parameters\" and \"pgf\".",
+            ))
+            .arg(BLOCK_HEIGHT_OPT.def().help(
+                "The block height of the exported state. Defaults to the last \
+                 committed block.",
+            ))
+            .arg(OUT_FILE_PATH_OPT.def().help(
+                "Path for the output file (omitting file extension). Defaults \
+                 to \"state_export_{block_height}.json\" in the current \
+                 working directory.",
+            ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct WalletHistory {
+        pub owner: Option<WalletAddress>,
+        pub ledger_address: Option<LedgerAddresses>,
+    }
+
+    impl Args for WalletHistory {
+        fn parse(matches: &ArgMatches) -> Self {
+            Self {
+                owner: OWNER_OPT.parse(matches),
+                ledger_address: LEDGER_ADDRESS_OPT.parse(matches),
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                OWNER_OPT
+                    .def()
+                    .help("Only list the transactions involving this address."),
+            )
+            .arg(LEDGER_ADDRESS_OPT.def().help(
+                "Refresh the status of the pending transactions from this \
+                 node before listing them.",
+            ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct WalletDeriveAddress {
+        pub public_key: Option<common::PublicKey>,
+        pub scheme: SchemeType,
+        pub derivation_path: String,
+    }
+
+    impl Args for WalletDeriveAddress {
+        fn parse(matches: &ArgMatches) -> Self {
+            Self {
+                public_key: RAW_PUBLIC_KEY_OPT.parse(matches),
+                scheme: SCHEME.parse(matches),
+                derivation_path: HD_WALLET_DERIVATION_PATH.parse(matches),
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(RAW_PUBLIC_KEY_OPT.def().help(
+                "The public key to derive the address of. If none provided, \
+                 the public key is derived from a mnemonic code read from the \
+                 standard input.",
+            ))
+            .arg(
+                SCHEME
+                    .def()
+                    .help(
+                        "The type of key derived from the mnemonic code. \
+                         Argument must be either ed25519 or secp256k1. If \
+                         none provided, the default key scheme is ed25519.",
+                    )
+                    .conflicts_with(RAW_PUBLIC_KEY_OPT.name),
+            )
+            .arg(
+                HD_WALLET_DERIVATION_PATH
+                    .def()
+                    .help(
+                        "HD key derivation path of the key derived from the \
+                         mnemonic code. Use keyword `default` to refer to a \
+                         scheme default path. If none is specified, the \
+                         scheme default path is used.",
+                    )
+                    .conflicts_with(RAW_PUBLIC_KEY_OPT.name),
+            )
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct UpdateLocalConfig {
         pub config_path: PathBuf,
@@ -3372,6 +4182,49 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Dev {
+        pub reset: bool,
+        pub templates_path: PathBuf,
+        pub epoch_duration: u64,
+        pub consensus_timeout_commit: Timeout,
+    }
+
+    impl Args for Dev {
+        fn parse(matches: &ArgMatches) -> Self {
+            let reset = RESET.parse(matches);
+            let templates_path = DEV_TEMPLATES_PATH.parse(matches);
+            let epoch_duration = EPOCH_DURATION.parse(matches);
+            let consensus_timeout_commit =
+                CONSENSUS_TIMEOUT_COMMIT.parse(matches);
+            Self {
+                reset,
+                templates_path,
+                epoch_duration,
+                consensus_timeout_commit,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(RESET.def().help(
+                "Delete the development chain, if any, and start a new one.",
+            ))
+            .arg(DEV_TEMPLATES_PATH.def().help(
+                "Path to the directory with the genesis templates of the \
+                 development chain, including the pre-genesis wallet with \
+                 the test accounts in `src/pre-genesis`.",
+            ))
+            .arg(EPOCH_DURATION.def().help(
+                "The minimum duration of an epoch in seconds. Only used \
+                 when a new chain is started.",
+            ))
+            .arg(CONSENSUS_TIMEOUT_COMMIT.def().help(
+                "The Tendermint consensus timeout_commit configuration as \
+                 e.g. `1s` or `1000ms`. Defaults to 1 second.",
+            ))
+        }
+    }
+
     /// Convert CLI args to SDK args, with contextual data.
     pub trait CliToSdk<SDK>: Args {
         /// Convert CLI args to SDK args, with contextual data.
@@ -3411,11 +4264,45 @@ pub mod args {
         }
 
         fn def(app: App) -> App {
-            app.add_args::<Query<CliTypes>>().arg(
-                TX_HASH
-                    .def()
-                    .help("The hash of the transaction being looked up."),
-            )
+            app.add_args::<Query<CliTypes>>().arg(TX_HASH.def().help(
+                "The hash of the transaction being looked up. For applied \
+                 transactions, this can be the hash of the inner transaction.",
+            ))
+        }
+    }
+
+    impl CliToSdk<WaitTx<SdkTypes>> for WaitTx<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> WaitTx<SdkTypes> {
+            WaitTx::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                tx_hash: self.tx_hash,
+                timeout: self.timeout,
+            }
+        }
+    }
+
+    impl Args for WaitTx<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let tx_hash = TX_HASH.parse(matches);
+            let timeout = WAIT_TIMEOUT.parse(matches).0;
+            Self {
+                query,
+                tx_hash,
+                timeout,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(TX_HASH.def().help(
+                    "The hash of the inner transaction to wait for, as \
+                     printed when the transaction was broadcast.",
+                ))
+                .arg(WAIT_TIMEOUT.def().help(
+                    "How long to wait for the transaction to be applied, \
+                     e.g. \"60s\". Defaults to one minute.",
+                ))
         }
     }
 
@@ -3969,53 +4856,376 @@ pub mod args {
         }
     }
 
-    impl CliToSdk<TxTransfer<SdkTypes>> for TxTransfer<CliTypes> {
-        fn to_sdk(self, ctx: &mut Context) -> TxTransfer<SdkTypes> {
-            let tx = self.tx.to_sdk(ctx);
-            let chain_ctx = ctx.borrow_mut_chain_or_exit();
-            TxTransfer::<SdkTypes> {
-                tx,
-                source: chain_ctx.get_cached(&self.source),
-                target: chain_ctx.get(&self.target),
-                token: chain_ctx.get(&self.token),
-                amount: self.amount,
-                native_token: chain_ctx.native_token.clone(),
-                tx_code_path: self.tx_code_path.to_path_buf(),
+    impl CliToSdk<TxTransfer<SdkTypes>> for TxTransfer<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxTransfer<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            TxTransfer::<SdkTypes> {
+                tx,
+                source: chain_ctx.get_cached(&self.source),
+                target: chain_ctx.get(&self.target),
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                native_token: chain_ctx.native_token.clone(),
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for TxTransfer<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = TRANSFER_SOURCE.parse(matches);
+            let target = TRANSFER_TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
+            let tx_code_path = PathBuf::from(TX_TRANSFER_WASM);
+            Self {
+                tx,
+                source,
+                target,
+                token,
+                amount,
+                tx_code_path,
+                native_token: (),
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(TRANSFER_SOURCE.def().help(
+                    "The source account address. The source's key may be used \
+                     to produce the signature.",
+                ))
+                .arg(TRANSFER_TARGET.def().help(
+                    "The target account address. The target's key may be used \
+                     to produce the signature.",
+                ))
+                .arg(TOKEN.def().help("The transfer token."))
+                .arg(AMOUNT.def().help("The amount to transfer in decimal."))
+        }
+    }
+
+    impl CliToSdk<FaucetRequest<SdkTypes>> for FaucetRequest<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> FaucetRequest<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            FaucetRequest::<SdkTypes> {
+                tx,
+                target: chain_ctx.get(&self.target),
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for FaucetRequest<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
+            let tx_code_path = PathBuf::from(TX_FAUCET_WITHDRAW_WASM);
+            Self {
+                tx,
+                target,
+                token,
+                amount,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(TARGET.def().help(
+                    "The target account address. The target's key is used to \
+                     pay the fees.",
+                ))
+                .arg(TOKEN.def().help("The token to withdraw."))
+                .arg(AMOUNT.def().help("The amount to withdraw in decimal."))
+        }
+    }
+
+    impl CliToSdk<TxApprove<SdkTypes>> for TxApprove<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxApprove<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            TxApprove::<SdkTypes> {
+                tx,
+                owner: chain_ctx.get(&self.owner),
+                spender: chain_ctx.get(&self.spender),
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for TxApprove<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let spender = SPENDER.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
+            let tx_code_path = PathBuf::from(TX_APPROVE_WASM);
+            Self {
+                tx,
+                owner,
+                spender,
+                token,
+                amount,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(OWNER.def().help(
+                    "The owner of the tokens. The owner's key is used to sign \
+                     the transaction.",
+                ))
+                .arg(SPENDER.def().help("The address allowed to spend."))
+                .arg(TOKEN.def().help("The approved token."))
+                .arg(AMOUNT.def().help(
+                    "The allowed amount in decimal. It replaces any previous \
+                     allowance.",
+                ))
+        }
+    }
+
+    impl CliToSdk<TxTransferFrom<SdkTypes>> for TxTransferFrom<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxTransferFrom<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            TxTransferFrom::<SdkTypes> {
+                tx,
+                spender: chain_ctx.get(&self.spender),
+                source: chain_ctx.get(&self.source),
+                target: chain_ctx.get(&self.target),
+                token: chain_ctx.get(&self.token),
+                amount: self.amount,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for TxTransferFrom<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let spender = SPENDER.parse(matches);
+            let source = SOURCE.parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
+            let tx_code_path = PathBuf::from(TX_TRANSFER_FROM_WASM);
+            Self {
+                tx,
+                spender,
+                source,
+                target,
+                token,
+                amount,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(SPENDER.def().help(
+                    "The address spending the allowance. The spender's key is \
+                     used to sign the transaction.",
+                ))
+                .arg(SOURCE.def().help("The source account address."))
+                .arg(TARGET.def().help("The target account address."))
+                .arg(TOKEN.def().help("The transfer token."))
+                .arg(AMOUNT.def().help("The amount to transfer in decimal."))
+        }
+    }
+
+    /// A transaction of a batch file
+    #[derive(serde::Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    enum BatchFileTx {
+        Transfer {
+            source: String,
+            target: String,
+            token: String,
+            amount: String,
+        },
+        Bond {
+            validator: String,
+            source: Option<String>,
+            amount: String,
+        },
+    }
+
+    impl BatchFileTx {
+        /// Convert to the arguments of the transaction, with the given common
+        /// tx arguments
+        fn into_args(
+            self,
+            tx: &Tx<CliTypes>,
+        ) -> color_eyre::eyre::Result<BatchTx<CliTypes>> {
+            let parse_amount = |amount: String| {
+                token::DenominatedAmount::from_str(&amount).map_err(|e| {
+                    color_eyre::eyre::eyre!(
+                        "Could not parse amount {amount}: {:?}",
+                        e
+                    )
+                })
+            };
+            Ok(match self {
+                Self::Transfer {
+                    source,
+                    target,
+                    token,
+                    amount,
+                } => BatchTx::Transfer(TxTransfer {
+                    tx: tx.clone(),
+                    source: FromContext::new(source),
+                    target: FromContext::new(target),
+                    token: FromContext::new(token),
+                    amount: InputAmount::Unvalidated(parse_amount(amount)?),
+                    native_token: (),
+                    tx_code_path: PathBuf::from(TX_TRANSFER_WASM),
+                }),
+                Self::Bond {
+                    validator,
+                    source,
+                    amount,
+                } => {
+                    let amount = parse_amount(amount)?
+                        .canonical()
+                        .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
+                        .map_err(|e| {
+                            color_eyre::eyre::eyre!(
+                                "Could not parse bond amount: {:?}",
+                                e
+                            )
+                        })?
+                        .amount;
+                    BatchTx::Bond(Bond {
+                        tx: tx.clone(),
+                        validator: FromContext::new(validator),
+                        amount,
+                        source: source.map(FromContext::new),
+                        native_token: (),
+                        tx_code_path: PathBuf::from(TX_BOND_WASM),
+                    })
+                }
+            })
+        }
+    }
+
+    /// Batch of transactions arguments, with the path to the file of the
+    /// transactions that is read by the command handler
+    #[derive(Clone, Debug)]
+    pub struct TxBatchFile {
+        /// Common tx arguments, shared by all the transactions
+        pub tx: Tx<CliTypes>,
+        /// Path to the JSON file with the transactions
+        pub file: PathBuf,
+    }
+
+    impl TxBatchFile {
+        /// Read and parse the transactions of the batch file
+        pub fn read(self) -> color_eyre::eyre::Result<TxBatch<CliTypes>> {
+            let file = std::fs::read(&self.file).map_err(|e| {
+                color_eyre::eyre::eyre!(
+                    "Could not read the batch file {}: {}",
+                    self.file.to_string_lossy(),
+                    e
+                )
+            })?;
+            let batch: Vec<BatchFileTx> = serde_json::from_slice(&file)
+                .map_err(|e| {
+                    color_eyre::eyre::eyre!(
+                        "Could not parse the batch file: {}",
+                        e
+                    )
+                })?;
+            let txs = batch
+                .into_iter()
+                .map(|item| item.into_args(&self.tx))
+                .collect::<color_eyre::eyre::Result<_>>()?;
+            Ok(TxBatch { tx: self.tx, txs })
+        }
+    }
+
+    impl CliToSdk<TxBatch<SdkTypes>> for TxBatch<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxBatch<SdkTypes> {
+            TxBatch::<SdkTypes> {
+                tx: self.tx.to_sdk(ctx),
+                txs: self
+                    .txs
+                    .into_iter()
+                    .map(|tx| match tx {
+                        BatchTx::Transfer(args) => {
+                            BatchTx::Transfer(args.to_sdk(ctx))
+                        }
+                        BatchTx::Bond(args) => BatchTx::Bond(args.to_sdk(ctx)),
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    impl Args for TxBatchFile {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let file = BATCH_FILE.parse(matches);
+            Self { tx, file }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>().arg(BATCH_FILE.def().help(
+                "Path to a JSON file with the list of transactions to submit. \
+                 Each transaction is an object with a \"type\" that is either \
+                 \"transfer\", with the \"source\", \"target\", \"token\" \
+                 and \"amount\" fields, or \"bond\", with the \"validator\", \
+                 \"amount\" and optional \"source\" fields.",
+            ))
+        }
+    }
+
+    impl CliToSdk<TxEstimate<SdkTypes>> for TxEstimate<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> TxEstimate<SdkTypes> {
+            TxEstimate::<SdkTypes> {
+                tx: match self.tx {
+                    BatchTx::Transfer(args) => {
+                        BatchTx::Transfer(args.to_sdk(ctx))
+                    }
+                    BatchTx::Bond(args) => BatchTx::Bond(args.to_sdk(ctx)),
+                },
             }
         }
     }
 
-    impl Args for TxTransfer<CliTypes> {
+    impl Args for TxEstimate<CliTypes> {
         fn parse(matches: &ArgMatches) -> Self {
-            let tx = Tx::parse(matches);
-            let source = TRANSFER_SOURCE.parse(matches);
-            let target = TRANSFER_TARGET.parse(matches);
-            let token = TOKEN.parse(matches);
-            let amount = InputAmount::Unvalidated(AMOUNT.parse(matches));
-            let tx_code_path = PathBuf::from(TX_TRANSFER_WASM);
-            Self {
-                tx,
-                source,
-                target,
-                token,
-                amount,
-                tx_code_path,
-                native_token: (),
-            }
+            let tx = match matches.subcommand() {
+                Some(("transfer", matches)) => {
+                    BatchTx::Transfer(TxTransfer::parse(matches))
+                }
+                Some(("bond", matches)) => BatchTx::Bond(Bond::parse(matches)),
+                _ => unreachable!("A transaction type is required"),
+            };
+            Self { tx }
         }
 
         fn def(app: App) -> App {
-            app.add_args::<Tx<CliTypes>>()
-                .arg(TRANSFER_SOURCE.def().help(
-                    "The source account address. The source's key may be used \
-                     to produce the signature.",
-                ))
-                .arg(TRANSFER_TARGET.def().help(
-                    "The target account address. The target's key may be used \
-                     to produce the signature.",
-                ))
-                .arg(TOKEN.def().help("The transfer token."))
-                .arg(AMOUNT.def().help("The amount to transfer in decimal."))
+            app.subcommand(
+                App::new("transfer")
+                    .about("Estimate the costs of a transfer.")
+                    .add_args::<TxTransfer<CliTypes>>(),
+            )
+            .subcommand(
+                App::new("bond")
+                    .about("Estimate the costs of a bond.")
+                    .add_args::<Bond<CliTypes>>(),
+            )
         }
     }
 
@@ -4111,6 +5321,8 @@ pub mod args {
                     .map(|pk| chain_ctx.get(pk))
                     .collect(),
                 threshold: self.threshold,
+                salt: self.salt,
+                vesting: self.vesting,
             }
         }
     }
@@ -4118,18 +5330,46 @@ pub mod args {
     impl Args for TxInitAccount<CliTypes> {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
-            let vp_code_path = CODE_PATH_OPT
-                .parse(matches)
-                .unwrap_or_else(|| PathBuf::from(VP_USER_WASM));
+            let vesting = VESTING_AMOUNT.parse(matches).map(|amount| {
+                let total = amount
+                    .canonical()
+                    .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
+                    .unwrap_or_else(|e| {
+                        eprintln!("Could not parse vesting amount: {:?}", e);
+                        safe_exit(1);
+                    })
+                    .amount;
+                let cliff_epoch = VESTING_CLIFF_EPOCH.parse(matches).unwrap();
+                VestingSchedule {
+                    total,
+                    start_epoch: VESTING_START_EPOCH
+                        .parse(matches)
+                        .unwrap_or(cliff_epoch),
+                    cliff_epoch,
+                    end_epoch: VESTING_END_EPOCH.parse(matches).unwrap(),
+                }
+            });
+            // A vesting account uses the vesting VP, unless overridden
+            let vp_code_path =
+                CODE_PATH_OPT.parse(matches).unwrap_or_else(|| {
+                    if vesting.is_some() {
+                        PathBuf::from(VP_VESTING_WASM)
+                    } else {
+                        PathBuf::from(VP_USER_WASM)
+                    }
+                });
             let tx_code_path = PathBuf::from(TX_INIT_ACCOUNT_WASM);
             let public_keys = PUBLIC_KEYS.parse(matches);
             let threshold = THRESHOLD.parse(matches);
+            let salt = SALT.parse(matches);
             Self {
                 tx,
                 vp_code_path,
                 public_keys,
                 threshold,
                 tx_code_path,
+                salt,
+                vesting,
             }
         }
 
@@ -4149,6 +5389,35 @@ pub mod args {
                      authorization. Must be less then the maximum number of \
                      public keys provided.",
                 ))
+                .arg(SALT.def().help(
+                    "An optional salt to derive a different address for an \
+                     account initialized with the same keys, VP and \
+                     threshold.",
+                ))
+                .arg(
+                    VESTING_AMOUNT
+                        .def()
+                        .help(
+                            "Initialize a vesting account with this amount of \
+                             native tokens locked. The locked tokens are \
+                             released linearly from the start epoch until the \
+                             end epoch and can't be spent before the cliff \
+                             epoch. Uses the vesting VP if no VP is specified.",
+                        )
+                        .requires(VESTING_CLIFF_EPOCH.name)
+                        .requires(VESTING_END_EPOCH.name),
+                )
+                .arg(VESTING_START_EPOCH.def().help(
+                    "The epoch from which the vested tokens are released. \
+                     Defaults to the cliff epoch.",
+                ))
+                .arg(VESTING_CLIFF_EPOCH.def().help(
+                    "The first epoch in which the released vested tokens can \
+                     be spent.",
+                ))
+                .arg(VESTING_END_EPOCH.def().help(
+                    "The epoch from which all the vested tokens are released.",
+                ))
         }
     }
 
@@ -4428,6 +5697,11 @@ pub mod args {
                     .map(|pk| chain_ctx.get(pk))
                     .collect(),
                 threshold: self.threshold,
+                vp_update_delay: self.vp_update_delay,
+                recovery_key: self.recovery_key.map(|pk| chain_ctx.get(&pk)),
+                cancel_vp_update: self.cancel_vp_update,
+                allowed_txs: self.allowed_txs,
+                policy: self.policy,
             }
         }
     }
@@ -4440,6 +5714,21 @@ pub mod args {
             let tx_code_path = PathBuf::from(TX_UPDATE_ACCOUNT_WASM);
             let public_keys = PUBLIC_KEYS.parse(matches);
             let threshold = THRESHOLD.parse(matches);
+            let vp_update_delay = VP_UPDATE_DELAY.parse(matches);
+            let recovery_key = RECOVERY_KEY.parse(matches);
+            let cancel_vp_update = CANCEL_VP_UPDATE.parse(matches);
+            let allowed_txs = if ALLOW_ALL_TXS.parse(matches) {
+                Some(vec![])
+            } else {
+                Some(ALLOWED_TXS.parse(matches))
+                    .filter(|allowed_txs| !allowed_txs.is_empty())
+            };
+            let policy = POLICY_PATH.parse(matches).map(|path| {
+                let file = std::fs::read(path)
+                    .expect("Expected a file with the account policy");
+                serde_json::from_slice(&file)
+                    .expect("Failed to parse the account policy")
+            });
             Self {
                 tx,
                 vp_code_path,
@@ -4447,6 +5736,11 @@ pub mod args {
                 tx_code_path,
                 public_keys,
                 threshold,
+                vp_update_delay,
+                recovery_key,
+                cancel_vp_update,
+                allowed_txs,
+                policy,
             }
         }
 
@@ -4470,6 +5764,49 @@ pub mod args {
                      authorization. Must be less then the maximum number of \
                      public keys provided.",
                 ))
+                .arg(
+                    VP_UPDATE_DELAY
+                        .def()
+                        .help(
+                            "Set a timelock on the VP updates of the account: \
+                             an update is first scheduled and can only be \
+                             applied by submitting it again after this number \
+                             of epochs. Changing an existing timelock must be \
+                             signed with its recovery key.",
+                        )
+                        .requires(RECOVERY_KEY.name),
+                )
+                .arg(RECOVERY_KEY.def().help(
+                    "The key that can cancel a scheduled VP update. Required \
+                     with the timelock.",
+                ))
+                .arg(CANCEL_VP_UPDATE.def().help(
+                    "Cancel the VP update scheduled under the timelock of the \
+                     account. Must be signed with the recovery key.",
+                ))
+                .arg(ALLOWED_TXS.def().help(
+                    "Restrict the txs allowed to operate on the account to \
+                     the ones with the given WASM codes, e.g. \
+                     tx_transfer.wasm. Any other tx can only credit the \
+                     account. Include tx_update_account.wasm to be able to \
+                     change the restriction later.",
+                ))
+                .arg(POLICY_PATH.def().help(
+                    "The path to a JSON file with the authorization policy to \
+                     set on the account: the spend limits per epoch, the \
+                     allowed counterparties and the high value thresholds. \
+                     An empty policy lifts the restrictions. Must be signed \
+                     with all the keys of the account.",
+                ))
+                .arg(
+                    ALLOW_ALL_TXS
+                        .def()
+                        .help(
+                            "Lift the restriction of the txs allowed to \
+                             operate on the account.",
+                        )
+                        .conflicts_with(ALLOWED_TXS.name),
+                )
         }
     }
 
@@ -4916,6 +6253,39 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<QueryProposalVotes<SdkTypes>> for QueryProposalVotes<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> QueryProposalVotes<SdkTypes> {
+            QueryProposalVotes::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                proposal_id: self.proposal_id,
+                paginate: self.paginate,
+            }
+        }
+    }
+
+    impl Args for QueryProposalVotes<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let proposal_id = PROPOSAL_ID.parse(matches);
+            let paginate = PAGINATE.parse(matches);
+
+            Self {
+                query,
+                proposal_id,
+                paginate,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>()
+                .arg(PROPOSAL_ID.def().help("The proposal identifier."))
+                .arg(PAGINATE.def().help(
+                    "Show the votes one page at a time, waiting for a key \
+                     press between the pages.",
+                ))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryProposalResult<C: NamadaTypes = SdkTypes> {
         /// Common query args
@@ -5071,6 +6441,53 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<AutoWithdraw<SdkTypes>> for AutoWithdraw<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> AutoWithdraw<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            AutoWithdraw::<SdkTypes> {
+                tx,
+                owner: chain_ctx.get(&self.owner),
+                interval: self.interval,
+                if_matured: self.if_matured,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for AutoWithdraw<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let interval = AUTO_WITHDRAW_INTERVAL.parse(matches);
+            let if_matured = IF_MATURED.parse(matches);
+            let tx_code_path = PathBuf::from(TX_WITHDRAW_WASM);
+            Self {
+                tx,
+                owner,
+                interval,
+                if_matured,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(OWNER.def().help(
+                    "The owner of the bonds whose matured unbonds are \
+                     withdrawn, i.e. the delegator or the validator for its \
+                     self-bonds.",
+                ))
+                .arg(AUTO_WITHDRAW_INTERVAL.def().help(
+                    "The amount of time to sleep between the checks of the \
+                     unbonds. Defaults to 1h.",
+                ))
+                .arg(IF_MATURED.def().help(
+                    "Only withdraw the unbonds that are matured now and exit.",
+                ))
+        }
+    }
+
     impl CliToSdk<ClaimRewards<SdkTypes>> for ClaimRewards<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> ClaimRewards<SdkTypes> {
             let tx = self.tx.to_sdk(ctx);
@@ -5108,6 +6525,49 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<AutoCompound<SdkTypes>> for AutoCompound<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> AutoCompound<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            AutoCompound::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                source: self.source.map(|x| chain_ctx.get(&x)),
+                enabled: self.enabled,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for AutoCompound<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let source = SOURCE_OPT.parse(matches);
+            let enabled = !DISABLE.parse(matches);
+            let tx_code_path = PathBuf::from(TX_AUTO_COMPOUND_WASM);
+            Self {
+                tx,
+                validator,
+                source,
+                enabled,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help("Validator address."))
+                .arg(SOURCE_OPT.def().help(
+                    "Source address of the bond. For self-bonds, the \
+                     validator is also the source.",
+                ))
+                .arg(DISABLE.def().help(
+                    "Opt the bond out, leaving its rewards to be claimed.",
+                ))
+        }
+    }
+
     impl CliToSdk<QueryConversions<SdkTypes>> for QueryConversions<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> QueryConversions<SdkTypes> {
             QueryConversions::<SdkTypes> {
@@ -5171,6 +6631,58 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<QueryStorageUsage<SdkTypes>> for QueryStorageUsage<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> QueryStorageUsage<SdkTypes> {
+            let query = self.query.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            QueryStorageUsage::<SdkTypes> {
+                query,
+                owner: self.owner.map(|owner| chain_ctx.get(&owner)),
+            }
+        }
+    }
+
+    impl Args for QueryStorageUsage<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let owner = OWNER_OPT.parse(matches);
+            Self { query, owner }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>().arg(OWNER_OPT.def().help(
+                "The address whose storage usage is queried. Defaults to the \
+                 total usage of all the addresses.",
+            ))
+        }
+    }
+
+    impl CliToSdk<QueryTotalSupply<SdkTypes>> for QueryTotalSupply<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> QueryTotalSupply<SdkTypes> {
+            QueryTotalSupply::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                token: ctx.borrow_chain_or_exit().get(&self.token),
+            }
+        }
+    }
+
+    impl Args for QueryTotalSupply<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let token = TOKEN.parse(matches);
+            Self { query, token }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>().arg(
+                TOKEN
+                    .def()
+                    .help("The token address whose total supply to query.")
+                    .required(true),
+            )
+        }
+    }
+
     impl CliToSdk<QueryBalance<SdkTypes>> for QueryBalance<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> QueryBalance<SdkTypes> {
             let query = self.query.to_sdk(ctx);
@@ -5292,6 +6804,30 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<QueryNextEpoch<SdkTypes>> for QueryNextEpoch<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> QueryNextEpoch<SdkTypes> {
+            QueryNextEpoch::<SdkTypes> {
+                query: self.query.to_sdk(ctx),
+                epoch: self.epoch,
+            }
+        }
+    }
+
+    impl Args for QueryNextEpoch<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let epoch = EPOCH.parse(matches);
+            Self { query, epoch }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query<CliTypes>>().arg(EPOCH.def().help(
+                "The epoch whose start to estimate (the next epoch, if not \
+                 specified).",
+            ))
+        }
+    }
+
     impl CliToSdk<QueryBondedStake<SdkTypes>> for QueryBondedStake<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> QueryBondedStake<SdkTypes> {
             QueryBondedStake::<SdkTypes> {
@@ -5614,6 +7150,7 @@ pub mod args {
                 tx: self.tx.to_sdk(ctx),
                 tx_data: std::fs::read(self.tx_data).expect(""),
                 owner: ctx.borrow_chain_or_exit().get(&self.owner),
+                print_sign_doc: self.print_sign_doc,
             }
         }
     }
@@ -5623,10 +7160,12 @@ pub mod args {
             let tx = Tx::parse(matches);
             let tx_path = TX_PATH.parse(matches);
             let owner = OWNER.parse(matches);
+            let print_sign_doc = PRINT_SIGN_DOC.parse(matches);
             Self {
                 tx,
                 tx_data: tx_path,
                 owner,
+                print_sign_doc,
             }
         }
 
@@ -5638,6 +7177,10 @@ pub mod args {
                     ),
                 )
                 .arg(OWNER.def().help("The address of the account owner"))
+                .arg(PRINT_SIGN_DOC.def().help(
+                    "Print the canonical document of the signature, with the \
+                     decoded data of the transaction.",
+                ))
         }
     }
 
@@ -5910,7 +7453,7 @@ pub mod args {
         type Keypair = WalletKeypair;
         type NativeAddress = ();
         type PublicKey = WalletPublicKey;
-        type TendermintAddress = TendermintAddress;
+        type TendermintAddress = LedgerAddresses;
         type TransferSource = WalletTransferSource;
         type TransferTarget = WalletTransferTarget;
         type ViewingKey = WalletViewingKey;
@@ -5923,6 +7466,7 @@ pub mod args {
                 dry_run: self.dry_run,
                 dry_run_wrapper: self.dry_run_wrapper,
                 dump_tx: self.dump_tx,
+                dump_broadcast_tx: self.dump_broadcast_tx,
                 output_folder: self.output_folder,
                 force: self.force,
                 broadcast_only: self.broadcast_only,
@@ -5953,7 +7497,9 @@ pub mod args {
                     .chain_id
                     .or_else(|| Some(ctx.config.ledger.chain_id.clone())),
                 wrapper_fee_payer: self.wrapper_fee_payer.map(|x| ctx.get(&x)),
+                sequence: self.sequence,
                 use_device: self.use_device,
+                skip_confirmation: self.skip_confirmation,
             }
         }
     }
@@ -5976,6 +7522,26 @@ pub mod args {
                     .conflicts_with(DRY_RUN_TX.name),
             )
             .arg(DUMP_TX.def().help("Dump transaction bytes to a file."))
+            .arg(
+                DUMP_BROADCAST_TX
+                    .def()
+                    .help(
+                        "Sign the transaction and dump it as the body of a \
+                         CometBFT `broadcast_tx_sync` JSON-RPC request, to a \
+                         file in the output folder or to screen, instead of \
+                         submitting it. The request can then be sent to any \
+                         node's RPC, e.g. with `curl --data @<file> <node>`. \
+                         A transaction revealing the public key of the \
+                         source, if needed, is dumped too and must be sent \
+                         first.",
+                    )
+                    .conflicts_with_all([
+                        DUMP_TX.name,
+                        DRY_RUN_TX.name,
+                        DRY_RUN_WRAPPER_TX.name,
+                        BROADCAST_ONLY.name,
+                    ]),
+            )
             .arg(FORCE.def().help(
                 "Submit the transaction even if it doesn't pass client checks.",
             ))
@@ -6043,6 +7609,7 @@ pub mod args {
                     .def()
                     .help(
                         "List of file paths containing a serialized signature \
+                         or partially signed transaction whose signatures are \
                          to be attached to a transaction. Requires to provide \
                          a gas payer.",
                     )
@@ -6063,16 +7630,27 @@ pub mod args {
                     )
                     .conflicts_with(DISPOSABLE_SIGNING_KEY.name),
             )
+            .arg(SEQUENCE.def().help(
+                "The sequence number of the gas payer to put in the wrapper \
+                 transaction. It must be the next sequence number of the gas \
+                 payer for the transaction to be included in a block.",
+            ))
             .arg(USE_DEVICE.def().help(
                 "Use an attached hardware wallet device to sign the \
                  transaction.",
             ))
+            .arg(YES.def().help(
+                "Sign the transaction without showing its summary and \
+                 asking for confirmation. This can also be set with the \
+                 `NAMADA_SKIP_CONFIRMATION` environment variable.",
+            ))
         }
 
         fn parse(matches: &ArgMatches) -> Self {
             let dry_run = DRY_RUN_TX.parse(matches);
             let dry_run_wrapper = DRY_RUN_WRAPPER_TX.parse(matches);
             let dump_tx = DUMP_TX.parse(matches);
+            let dump_broadcast_tx = DUMP_BROADCAST_TX.parse(matches);
             let force = FORCE.parse(matches);
             let broadcast_only = BROADCAST_ONLY.parse(matches);
             let ledger_address = LEDGER_ADDRESS_DEFAULT.parse(matches);
@@ -6092,12 +7670,16 @@ pub mod args {
             let chain_id = CHAIN_ID_OPT.parse(matches);
             let password = None;
             let wrapper_fee_payer = FEE_PAYER_OPT.parse(matches);
+            let sequence = SEQUENCE.parse(matches);
             let output_folder = OUTPUT_FOLDER_PATH.parse(matches);
             let use_device = USE_DEVICE.parse(matches);
+            let skip_confirmation = YES.parse(matches)
+                || env::var(ENV_VAR_SKIP_CONFIRMATION).is_ok();
             Self {
                 dry_run,
                 dry_run_wrapper,
                 dump_tx,
+                dump_broadcast_tx,
                 force,
                 broadcast_only,
                 ledger_address,
@@ -6115,8 +7697,10 @@ pub mod args {
                 password,
                 chain_id,
                 wrapper_fee_payer,
+                sequence,
                 output_folder,
                 use_device,
+                skip_confirmation,
             }
         }
     }
@@ -6597,6 +8181,34 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct MergeSignatures {
+        pub partially_signed_txs: Vec<PathBuf>,
+        pub output_folder: Option<PathBuf>,
+    }
+
+    impl Args for MergeSignatures {
+        fn parse(matches: &ArgMatches) -> Self {
+            let partially_signed_txs = PARTIALLY_SIGNED_TXS.parse(matches);
+            let output_folder = OUTPUT_FOLDER_PATH.parse(matches);
+            Self {
+                partially_signed_txs,
+                output_folder,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(PARTIALLY_SIGNED_TXS.def().help(
+                "Comma-separated list of the paths of the partially signed \
+                 transaction files to merge, e.g. produced by `sign-tx`.",
+            ))
+            .arg(OUTPUT_FOLDER_PATH.def().help(
+                "The output folder path where the merged partially signed \
+                 transaction will be stored.",
+            ))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct DefaultBaseDir {}
 
@@ -7006,9 +8618,20 @@ pub enum NamadaClient {
     WithContext(Box<(cmds::NamadaClientWithContext, Context)>),
 }
 
+impl NamadaClient {
+    /// The global arguments of the command
+    pub fn global_args(&self) -> &args::Global {
+        match self {
+            Self::WithoutContext(_, global_args) => global_args,
+            Self::WithContext(cmd_box) => &cmd_box.1.global_args,
+        }
+    }
+}
+
 pub fn namada_client_cli() -> Result<NamadaClient> {
     let app = namada_client_app();
     let matches = app.clone().get_matches();
+    let matches = apply_profile(&app, matches);
     match Cmd::parse(&matches) {
         Some(cmd) => {
             let global_args = args::Global::parse(&matches);
@@ -7030,6 +8653,45 @@ pub fn namada_client_cli() -> Result<NamadaClient> {
     }
 }
 
+/// If a profile is selected, parse the command line again with the profile's
+/// settings added for the arguments that were not given explicitly
+fn apply_profile(app: &App, matches: ArgMatches) -> ArgMatches {
+    let global_args = args::Global::parse(&matches);
+    let global_config = context::read_or_try_new_global_config(&global_args);
+    let Some(profile) =
+        context::find_profile_or_exit(&global_args, &global_config)
+    else {
+        return matches;
+    };
+    // The arguments are defined on the innermost subcommand
+    let mut sub_matches = &matches;
+    while let Some((_, matches)) = sub_matches.subcommand() {
+        sub_matches = matches;
+    }
+    let profile_args: Vec<String> = [
+        (args::LEDGER_ADDRESS.name, &profile.ledger_address),
+        (args::FEE_TOKEN.name, &profile.fee_token),
+        (args::FEE_AMOUNT_OPT.name, &profile.gas_price),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        let value = value.as_ref()?;
+        // Only add the arguments that the command accepts and that are
+        // not given
+        matches!(sub_matches.try_contains_id(name), Ok(false))
+            .then(|| [format!("--{name}"), value.clone()])
+    })
+    .flatten()
+    .collect();
+    if profile_args.is_empty() {
+        return matches;
+    }
+    app.clone().get_matches_from(
+        std::env::args_os()
+            .chain(profile_args.into_iter().map(std::ffi::OsString::from)),
+    )
+}
+
 pub fn namada_wallet_cli() -> Result<(cmds::NamadaWallet, Context)> {
     let app = namada_wallet_app();
     cmds::NamadaWallet::parse_or_print_help(app)
@@ -1,16 +1,18 @@
 use namada::tendermint_rpc::HttpClient;
-use namada::types::io::Io;
+use namada::types::io::{prompt_aux, Io};
 use namada_sdk::error::Error;
 use namada_sdk::queries::Client;
 use namada_sdk::rpc::wait_until_node_is_synched;
-use tendermint_config::net::Address as TendermintAddress;
 
+use crate::client::failover::{FailoverClient, LedgerAddresses};
 use crate::client::utils;
 
 /// Trait for clients that can be used with the CLI.
 #[async_trait::async_trait(?Send)]
 pub trait CliClient: Client + Sync {
-    fn from_tendermint_address(address: &mut TendermintAddress) -> Self;
+    fn from_tendermint_address(
+        address: &mut LedgerAddresses,
+    ) -> Result<Self, Error>;
     async fn wait_until_node_is_synced(
         &self,
         io: &impl Io,
@@ -19,8 +21,32 @@ pub trait CliClient: Client + Sync {
 
 #[async_trait::async_trait(?Send)]
 impl CliClient for HttpClient {
-    fn from_tendermint_address(address: &mut TendermintAddress) -> Self {
-        HttpClient::new(utils::take_config_address(address)).unwrap()
+    /// Only the first of the given addresses is used
+    fn from_tendermint_address(
+        address: &mut LedgerAddresses,
+    ) -> Result<Self, Error> {
+        let address = address.0.first_mut().ok_or_else(|| {
+            Error::Other("At least one ledger address is required".to_string())
+        })?;
+        HttpClient::new(utils::take_config_address(address))
+            .map_err(|err| Error::Other(err.to_string()))
+    }
+
+    async fn wait_until_node_is_synced(
+        &self,
+        io: &impl Io,
+    ) -> Result<(), Error> {
+        wait_until_node_is_synched(self, io).await
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl CliClient for FailoverClient {
+    fn from_tendermint_address(
+        address: &mut LedgerAddresses,
+    ) -> Result<Self, Error> {
+        FailoverClient::new(std::mem::take(address))
+            .map_err(|err| Error::Other(err.to_string()))
     }
 
     async fn wait_until_node_is_synced(
@@ -36,4 +62,56 @@ pub struct CliIo;
 #[async_trait::async_trait(?Send)]
 impl Io for CliIo {}
 
+/// I/O handler for the JSON output format. Only the JSON results are printed
+/// on stdout, all the human-readable output goes to stderr.
+pub struct CliJsonIo;
+
+#[async_trait::async_trait(?Send)]
+impl Io for CliJsonIo {
+    fn print(&self, output: impl AsRef<str>) {
+        eprint!("{}", output.as_ref());
+    }
+
+    fn flush(&self) {
+        use std::io::Write;
+        std::io::stderr().flush().unwrap();
+    }
+
+    fn println(&self, output: impl AsRef<str>) {
+        eprintln!("{}", output.as_ref());
+    }
+
+    fn write<W: std::io::Write>(
+        &self,
+        _writer: W,
+        output: impl AsRef<str>,
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+        write!(std::io::stderr(), "{}", output.as_ref())
+    }
+
+    fn writeln<W: std::io::Write>(
+        &self,
+        _writer: W,
+        output: impl AsRef<str>,
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+        writeln!(std::io::stderr(), "{}", output.as_ref())
+    }
+
+    fn print_json(
+        &self,
+        output: &impl serde::Serialize,
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+        let json = serde_json::to_string(output)?;
+        writeln!(std::io::stdout(), "{json}")
+    }
+
+    async fn prompt(&self, question: impl AsRef<str>) -> String {
+        prompt_aux(tokio::io::stdin(), tokio::io::stderr(), question.as_ref())
+            .await
+    }
+}
+
 pub struct CliApi;
@@ -24,11 +24,11 @@ impl CliApi {
                 match cmd {
                     // Ledger cmds
                     Sub::TxCustom(TxCustom(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -49,44 +49,105 @@ impl CliApi {
                         }
                     }
                     Sub::TxTransfer(TxTransfer(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_transfer(&namada, args).await?;
                     }
+                    Sub::FaucetRequest(FaucetRequest(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_faucet_request(&namada, args).await?;
+                    }
+                    Sub::TxApprove(TxApprove(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_approve(&namada, args).await?;
+                    }
+                    Sub::TxTransferFrom(TxTransferFrom(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_transfer_from(&namada, args).await?;
+                    }
+                    Sub::TxBatch(TxBatch(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.read()?.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_batch(&namada, args).await?;
+                    }
+                    Sub::TxEstimate(TxEstimate(mut args)) => {
+                        let ledger_address = match &mut args.tx {
+                            cli::args::BatchTx::Transfer(args) => {
+                                &mut args.tx.ledger_address
+                            }
+                            cli::args::BatchTx::Bond(args) => {
+                                &mut args.tx.ledger_address
+                            }
+                        };
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(ledger_address)
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::estimate_tx(&namada, args).await?;
+                    }
                     Sub::TxIbcTransfer(TxIbcTransfer(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_ibc_transfer(&namada, args).await?;
                     }
                     Sub::TxUpdateAccount(TxUpdateAccount(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_update_account(&namada, args).await?;
                     }
                     Sub::TxInitAccount(TxInitAccount(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -107,11 +168,11 @@ impl CliApi {
                         }
                     }
                     Sub::TxBecomeValidator(TxBecomeValidator(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let cli::context::ChainContext {
@@ -131,11 +192,11 @@ impl CliApi {
                             .await?;
                     }
                     Sub::TxInitValidator(TxInitValidator(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let cli::context::ChainContext {
@@ -155,88 +216,110 @@ impl CliApi {
                             .await?;
                     }
                     Sub::TxInitProposal(TxInitProposal(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_init_proposal(&namada, args).await?;
                     }
                     Sub::TxVoteProposal(TxVoteProposal(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_vote_proposal(&namada, args).await?;
                     }
                     Sub::TxRevealPk(TxRevealPk(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_reveal_pk(&namada, args).await?;
                     }
                     Sub::Bond(Bond(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_bond(&namada, args).await?;
                     }
                     Sub::Unbond(Unbond(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_unbond(&namada, args).await?;
                     }
                     Sub::Withdraw(Withdraw(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_withdraw(&namada, args).await?;
                     }
+                    Sub::AutoWithdraw(AutoWithdraw(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_auto_withdraw(&namada, args).await?;
+                    }
                     Sub::ClaimRewards(ClaimRewards(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_claim_rewards(&namada, args).await?;
                     }
+                    Sub::AutoCompound(AutoCompound(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_auto_compound(&namada, args).await?;
+                    }
                     Sub::Redelegate(Redelegate(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -245,11 +328,11 @@ impl CliApi {
                     Sub::TxCommissionRateChange(TxCommissionRateChange(
                         mut args,
                     )) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -259,11 +342,11 @@ impl CliApi {
                     Sub::TxChangeConsensusKey(TxChangeConsensusKey(
                         mut args,
                     )) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let cli::context::ChainContext {
@@ -287,11 +370,11 @@ impl CliApi {
                         .await?;
                     }
                     Sub::TxMetadataChange(TxMetadataChange(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -301,22 +384,22 @@ impl CliApi {
                     // Eth bridge
                     Sub::AddToEthBridgePool(args) => {
                         let mut args = args.0;
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_bridge_pool_tx(&namada, args).await?;
                     }
                     Sub::TxUnjailValidator(TxUnjailValidator(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -325,11 +408,11 @@ impl CliApi {
                     Sub::TxDeactivateValidator(TxDeactivateValidator(
                         mut args,
                     )) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -338,11 +421,11 @@ impl CliApi {
                     Sub::TxReactivateValidator(TxReactivateValidator(
                         mut args,
                     )) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -351,11 +434,11 @@ impl CliApi {
                     Sub::TxUpdateStewardCommission(
                         TxUpdateStewardCommission(mut args),
                     ) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -363,11 +446,11 @@ impl CliApi {
                             .await?;
                     }
                     Sub::TxResignSteward(TxResignSteward(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -375,19 +458,40 @@ impl CliApi {
                     }
                     // Ledger queries
                     Sub::QueryEpoch(QueryEpoch(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(&mut args.ledger_address)
-                        });
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_and_print_last_epoch(&namada).await;
+                    }
+                    Sub::QueryInternalAddresses(QueryInternalAddresses(
+                        mut args,
+                    )) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(&mut args.ledger_address)
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_internal_addresses(&namada).await;
+                    }
+                    Sub::QueryNextEpoch(QueryNextEpoch(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.query.ledger_address,
+                            )
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
-                        rpc::query_and_print_epoch(&namada).await;
+                        rpc::query_next_epoch(&namada, args).await;
                     }
                     Sub::QueryValidatorState(QueryValidatorState(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -395,52 +499,52 @@ impl CliApi {
                             .await;
                     }
                     Sub::QueryTransfers(QueryTransfers(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_transfers(&namada, args).await;
                     }
                     Sub::QueryConversions(QueryConversions(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_conversions(&namada, args).await;
                     }
                     Sub::QueryBlock(QueryBlock(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(&mut args.ledger_address)
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_block(&namada).await;
                     }
                     Sub::QueryBalance(QueryBalance(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_balance(&namada, args).await;
                     }
                     Sub::QueryBonds(QueryBonds(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -449,22 +553,22 @@ impl CliApi {
                             .expect("expected successful query of bonds");
                     }
                     Sub::QueryBondedStake(QueryBondedStake(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_bonded_stake(&namada, args).await;
                     }
                     Sub::QueryCommissionRate(QueryCommissionRate(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -472,145 +576,189 @@ impl CliApi {
                             .await;
                     }
                     Sub::QueryMetaData(QueryMetaData(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_and_print_metadata(&namada, args).await;
                     }
                     Sub::QuerySlashes(QuerySlashes(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_slashes(&namada, args).await;
                     }
                     Sub::QueryRewards(QueryRewards(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_and_print_rewards(&namada, args).await;
                     }
                     Sub::QueryDelegations(QueryDelegations(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_delegations(&namada, args).await;
                     }
                     Sub::QueryFindValidator(QueryFindValidator(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_find_validator(&namada, args).await;
                     }
                     Sub::QueryResult(QueryResult(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_result(&namada, args).await;
                     }
+                    Sub::WaitTx(WaitTx(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.query.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::wait_tx(&namada, args).await;
+                    }
                     Sub::QueryRawBytes(QueryRawBytes(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_raw_bytes(&namada, args).await;
                     }
                     Sub::QueryProposal(QueryProposal(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_proposal(&namada, args).await;
                     }
                     Sub::QueryProposalResult(QueryProposalResult(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_proposal_result(&namada, args).await;
                     }
+                    Sub::QueryProposalVotes(QueryProposalVotes(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.query.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_proposal_votes(&namada, args).await;
+                    }
                     Sub::QueryProtocolParameters(QueryProtocolParameters(
                         mut args,
                     )) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_protocol_parameters(&namada, args).await;
                     }
                     Sub::QueryPgf(QueryPgf(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_pgf(&namada, args).await;
                     }
+                    Sub::QueryTotalSupply(QueryTotalSupply(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.query.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_total_supply(&namada, args).await;
+                    }
                     Sub::QueryAccount(QueryAccount(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
                         rpc::query_account(&namada, args).await;
                     }
+                    Sub::QueryStorageUsage(QueryStorageUsage(mut args)) => {
+                        let client = client.map(Ok).unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.query.ledger_address,
+                            )
+                        })?;
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        rpc::query_storage_usage(&namada, args).await;
+                    }
                     Sub::SignTx(SignTx(mut args)) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.tx.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -619,11 +767,11 @@ impl CliApi {
                     Sub::GenIbcShieldedTransafer(GenIbcShieldedTransafer(
                         mut args,
                     )) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -668,7 +816,7 @@ impl CliApi {
                         .expect("expected to construct a context");
                     let mut ledger_address = args.ledger_address.clone();
                     let client =
-                        C::from_tendermint_address(&mut ledger_address);
+                        C::from_tendermint_address(&mut ledger_address)?;
                     client.wait_until_node_is_synced(&io).await?;
                     let args = args.to_sdk(&mut ctx);
                     let namada = ctx.to_sdk(client, io);
@@ -680,6 +828,9 @@ impl CliApi {
                 Utils::SignGenesisTxs(SignGenesisTxs(args)) => {
                     utils::sign_genesis_tx(global_args, args).await
                 }
+                Utils::MergeSignatures(MergeSignatures(args)) => {
+                    tx::merge_signatures(&io, args)
+                }
             },
         }
         Ok(())
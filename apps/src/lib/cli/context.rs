@@ -21,7 +21,7 @@ use namada_sdk::{Namada, NamadaImpl};
 
 use super::args;
 use crate::cli::utils;
-use crate::config::global::GlobalConfig;
+use crate::config::global::{GlobalConfig, Profile};
 use crate::config::{genesis, Config};
 use crate::wallet::CliWalletUtils;
 use crate::{wallet, wasm_loader};
@@ -32,6 +32,13 @@ pub const ENV_VAR_WASM_DIR: &str = "NAMADA_WASM_DIR";
 /// Env. var to read the Namada chain id from
 pub const ENV_VAR_CHAIN_ID: &str = "NAMADA_CHAIN_ID";
 
+/// Env. var to sign transactions without asking for confirmation
+pub const ENV_VAR_SKIP_CONFIRMATION: &str = "NAMADA_SKIP_CONFIRMATION";
+
+/// The raw value of an address argument that is resolved to the native token
+/// of the chain, unless it's an alias found in the wallet
+pub const NATIVE_TOKEN_ARG: &str = "native";
+
 /// A raw address (bech32m encoding) or an alias of an address that may be found
 /// in the wallet
 pub type WalletAddress = FromContext<Address>;
@@ -95,12 +102,14 @@ pub struct ChainContext {
 impl Context {
     pub fn new<IO: Io>(global_args: args::Global) -> Result<Self> {
         let global_config = read_or_try_new_global_config(&global_args);
+        let profile = find_profile_or_exit(&global_args, &global_config);
 
         let env_var_chain_id = std::env::var(ENV_VAR_CHAIN_ID)
             .ok()
             .and_then(|chain_id| ChainId::from_str(&chain_id).ok());
-        let chain_id = env_var_chain_id
-            .as_ref()
+        let chain_id = profile
+            .map(|profile| &profile.chain_id)
+            .or(env_var_chain_id.as_ref())
             .or(global_args.chain_id.as_ref())
             .or(global_config.default_chain_id.as_ref());
 
@@ -269,6 +278,31 @@ pub fn read_or_try_new_global_config(
     })
 }
 
+/// Find the profile selected with the `--profile` argument, if any. Exits if
+/// the profile doesn't exist or if its chain ID conflicts with the
+/// `--chain-id` argument.
+pub fn find_profile_or_exit<'a>(
+    global_args: &args::Global,
+    global_config: &'a GlobalConfig,
+) -> Option<&'a Profile> {
+    let name = global_args.profile.as_ref()?;
+    let profile = global_config.profile(name).unwrap_or_else(|| {
+        eprintln!("No profile named \"{name}\" found in the global config.");
+        super::safe_exit(1)
+    });
+    if let Some(chain_id) = global_args.chain_id.as_ref() {
+        if chain_id != &profile.chain_id {
+            eprintln!(
+                "The chain ID {chain_id} doesn't match the chain ID {} of the \
+                 profile \"{name}\".",
+                profile.chain_id
+            );
+            super::safe_exit(1)
+        }
+    }
+    Some(profile)
+}
+
 /// Argument that can be given raw or found in the [`Context`].
 #[derive(Debug, Clone)]
 pub struct FromContext<T> {
@@ -405,6 +439,12 @@ impl ArgFromContext for Address {
                     .map(|x| x.into_owned())
                     .ok_or(Skip)
             })
+            // Or the native token of the chain
+            .or_else(|_| {
+                (raw == NATIVE_TOKEN_ARG)
+                    .then(|| ctx.native_token.clone())
+                    .ok_or(Skip)
+            })
             .map_err(|_| format!("Unknown address {raw}"))
     }
 }
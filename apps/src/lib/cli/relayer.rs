@@ -24,11 +24,11 @@ impl CliApi {
                     EthBridgePoolWithCtx::RecommendBatch(RecommendBatch(
                         mut args,
                     )) => {
-                        let client = client.unwrap_or_else(|| {
+                        let client = client.map(Ok).unwrap_or_else(|| {
                             C::from_tendermint_address(
                                 &mut args.query.ledger_address,
                             )
-                        });
+                        })?;
                         client.wait_until_node_is_synced(&io).await?;
                         let args = args.to_sdk(&mut ctx);
                         let namada = ctx.to_sdk(client, io);
@@ -40,21 +40,21 @@ impl CliApi {
                 EthBridgePoolWithoutCtx::ConstructProof(ConstructProof(
                     mut args,
                 )) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(
                             &mut args.query.ledger_address,
                         )
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     let args = args.to_sdk_ctxless();
                     bridge_pool::construct_proof(&client, &io, args).await?;
                 }
                 EthBridgePoolWithoutCtx::RelayProof(RelayProof(mut args)) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(
                             &mut args.query.ledger_address,
                         )
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     let eth_client =
                         get_eth_rpc_client(&args.eth_rpc_endpoint).await;
@@ -67,27 +67,27 @@ impl CliApi {
                 EthBridgePoolWithoutCtx::QueryPool(QueryEthBridgePool(
                     mut query,
                 )) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(&mut query.ledger_address)
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     bridge_pool::query_bridge_pool(&client, &io).await?;
                 }
                 EthBridgePoolWithoutCtx::QuerySigned(
                     QuerySignedBridgePool(mut query),
                 ) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(&mut query.ledger_address)
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     bridge_pool::query_signed_bridge_pool(&client, &io).await?;
                 }
                 EthBridgePoolWithoutCtx::QueryRelays(QueryRelayProgress(
                     mut query,
                 )) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(&mut query.ledger_address)
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     bridge_pool::query_relay_progress(&client, &io).await?;
                 }
@@ -96,11 +96,11 @@ impl CliApi {
                 ValidatorSet::BridgeValidatorSet(BridgeValidatorSet(
                     mut args,
                 )) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(
                             &mut args.query.ledger_address,
                         )
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     let args = args.to_sdk_ctxless();
                     validator_set::query_bridge_validator_set(
@@ -111,11 +111,11 @@ impl CliApi {
                 ValidatorSet::GovernanceValidatorSet(
                     GovernanceValidatorSet(mut args),
                 ) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(
                             &mut args.query.ledger_address,
                         )
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     let args = args.to_sdk_ctxless();
                     validator_set::query_governnace_validator_set(
@@ -126,11 +126,11 @@ impl CliApi {
                 ValidatorSet::ValidatorSetProof(ValidatorSetProof(
                     mut args,
                 )) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(
                             &mut args.query.ledger_address,
                         )
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     let args = args.to_sdk_ctxless();
                     validator_set::query_validator_set_update_proof(
@@ -141,11 +141,11 @@ impl CliApi {
                 ValidatorSet::ValidatorSetUpdateRelay(
                     ValidatorSetUpdateRelay(mut args),
                 ) => {
-                    let client = client.unwrap_or_else(|| {
+                    let client = client.map(Ok).unwrap_or_else(|| {
                         C::from_tendermint_address(
                             &mut args.query.ledger_address,
                         )
-                    });
+                    })?;
                     client.wait_until_node_is_synced(&io).await?;
                     let eth_client =
                         get_eth_rpc_client(&args.eth_rpc_endpoint).await;
@@ -1,5 +1,6 @@
 //! Namada Wallet CLI.
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, Write};
 use std::str::FromStr;
@@ -17,16 +18,19 @@ use namada::types::io::Io;
 use namada::types::key::*;
 use namada::types::masp::{MaspValue, PaymentAddress};
 use namada_sdk::masp::find_valid_diversifier;
+use namada_sdk::rpc::{self, TxEventQuery};
 use namada_sdk::wallet::{
-    DecryptionError, DerivationPath, DerivationPathError, FindKeyError, Wallet,
+    derive_public_key_from_mnemonic_code, DecryptionError, DerivationPath,
+    DerivationPathError, FindKeyError, TxStatus, Wallet,
 };
-use namada_sdk::{display, display_line, edisplay_line};
+use namada_sdk::{display, display_json, display_line, edisplay_line};
 use rand_core::OsRng;
 
 use crate::cli;
 use crate::cli::api::CliApi;
 use crate::cli::args::CliToSdk;
 use crate::cli::{args, cmds, Context};
+use crate::client::failover::FailoverClient;
 use crate::client::utils::PRE_GENESIS_DIR;
 use crate::wallet::{
     self, read_and_confirm_encryption_password, CliWalletUtils,
@@ -92,6 +96,12 @@ impl CliApi {
                     address_key_find(ctx, io, args)
                 }
             },
+            cmds::NamadaWallet::History(cmds::WalletHistory(args)) => {
+                tx_history(ctx, io, args).await?
+            }
+            cmds::NamadaWallet::DeriveAddress(cmds::WalletDeriveAddress(
+                args,
+            )) => derive_address(io, args),
         }
         Ok(())
     }
@@ -367,6 +377,40 @@ pub fn decode_derivation_path(
     Ok(parsed_derivation_path)
 }
 
+/// Derives the implicit address of a public key, or of the public key derived
+/// from a mnemonic code, without accessing the wallet or the chain.
+fn derive_address(
+    io: &impl Io,
+    args::WalletDeriveAddress {
+        public_key,
+        scheme,
+        derivation_path,
+    }: args::WalletDeriveAddress,
+) {
+    let public_key = match public_key {
+        Some(public_key) => public_key,
+        None => {
+            let derivation_path =
+                decode_derivation_path(scheme, derivation_path).unwrap_or_else(
+                    |err| {
+                        edisplay_line!(io, "{}", err);
+                        cli::safe_exit(1)
+                    },
+                );
+            derive_public_key_from_mnemonic_code::<CliWalletUtils>(
+                scheme,
+                derivation_path,
+            )
+            .unwrap_or_else(|err| {
+                edisplay_line!(io, "{}", err);
+                cli::safe_exit(1)
+            })
+        }
+    };
+    display_line!(io, "Public key: {}", public_key);
+    display_line!(io, "Implicit address: {}", Address::from(&public_key));
+}
+
 /// Derives a keypair and an implicit address from the mnemonic code in the
 /// wallet.
 async fn key_and_address_derive(
@@ -670,7 +714,7 @@ fn address_list(ctx: Context, io: &impl Io) {
         let stdout = io::stdout();
         let mut w = stdout.lock();
         display_line!(io, &mut w; "Known addresses:").unwrap();
-        for (alias, address) in sorted(known_addresses) {
+        for (alias, address) in sorted(known_addresses.iter()) {
             display_line!(io,
                 &mut w;
                 "  \"{}\": {}", alias, address.to_pretty_string(),
@@ -678,6 +722,7 @@ fn address_list(ctx: Context, io: &impl Io) {
             .unwrap();
         }
     }
+    display_json!(io, &known_addresses);
 }
 
 /// Find address (alias) by its alias (address).
@@ -695,6 +740,13 @@ fn address_or_alias_find(
     } else if alias.is_some() {
         if let Some(address) = wallet.find_address(alias.as_ref().unwrap()) {
             display_line!(io, "Found address {}", address.to_pretty_string());
+            display_json!(
+                io,
+                &serde_json::json!({
+                    "alias": alias,
+                    "address": address,
+                })
+            );
         } else {
             display_line!(
                 io,
@@ -706,6 +758,13 @@ fn address_or_alias_find(
     } else if address.is_some() {
         if let Some(alias) = wallet.find_alias(address.as_ref().unwrap()) {
             display_line!(io, "Found alias {}", alias);
+            display_json!(
+                io,
+                &serde_json::json!({
+                    "alias": alias,
+                    "address": address,
+                })
+            );
         } else {
             display_line!(
                 io,
@@ -745,6 +804,109 @@ fn address_add(
     );
 }
 
+/// List the history of the submitted txs, optionally refreshing the status of
+/// the pending txs from a ledger node first.
+async fn tx_history(
+    ctx: Context,
+    io: &impl Io,
+    args::WalletHistory {
+        owner,
+        ledger_address,
+    }: args::WalletHistory,
+) -> Result<()> {
+    let owner = owner.map(|owner| ctx.borrow_chain_or_exit().get(&owner));
+    let wallet = load_wallet(ctx);
+
+    if let Some(ledger_address) = ledger_address {
+        let client = FailoverClient::new(ledger_address)?;
+        let pending: Vec<_> = wallet
+            .tx_history()?
+            .records
+            .into_iter()
+            .filter(|record| record.status == TxStatus::Pending)
+            .collect();
+        // The new statuses of the pending txs. A tx is rejected if its
+        // wrapper was rejected, as its inner tx is then never applied.
+        let mut statuses = HashMap::new();
+        for record in pending {
+            let applied = TxEventQuery::Applied(record.hash.as_str());
+            let accepted = TxEventQuery::Accepted(record.wrapper_hash.as_str());
+            let event = match rpc::query_tx_events(&client, applied).await {
+                // Only a rejected wrapper settles the status of the tx
+                Ok(None) => rpc::query_tx_events(&client, accepted)
+                    .await
+                    .map(|event| event.filter(|event| event["code"] != "0")),
+                applied => applied,
+            };
+            match event {
+                Ok(Some(event)) => {
+                    let status = if event["code"] == "0" {
+                        TxStatus::Applied
+                    } else {
+                        TxStatus::Rejected
+                    };
+                    statuses.insert(record.hash, status);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    edisplay_line!(
+                        io,
+                        "Failed to query the status of the transaction {}: \
+                         {err}",
+                        record.hash
+                    );
+                }
+            }
+        }
+        // The history may have been updated by another client while the
+        // statuses were queried, so only the statuses are updated
+        wallet.update_tx_history(|history| {
+            for record in history.records.iter_mut() {
+                if let Some(status) = statuses.get(&record.hash) {
+                    record.status = *status;
+                }
+            }
+        })?;
+    }
+
+    let history = wallet.tx_history()?;
+    let records: Vec<_> = match &owner {
+        Some(owner) => history.records_of(owner).collect(),
+        None => history.records.iter().collect(),
+    };
+    if records.is_empty() {
+        display_line!(io, "No transactions found.");
+        return Ok(());
+    }
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    for record in &records {
+        display_line!(
+            io,
+            &mut w;
+            "{} {} ({}): {}",
+            record.submitted_at.to_rfc3339(),
+            record.tx_type,
+            record.status,
+            record.hash
+        )?;
+        for party in &record.parties {
+            display_line!(io, &mut w; "  {}", wallet.format_address(party))?;
+        }
+        for amount in &record.amounts {
+            display_line!(
+                io,
+                &mut w;
+                "  {} {}",
+                amount.amount,
+                wallet.format_address(&amount.token)
+            )?;
+        }
+    }
+    display_json!(io, &records);
+    Ok(())
+}
+
 /// Load wallet for chain when `ctx.chain.is_some()` or pre-genesis wallet when
 /// `ctx.global_args.is_pre_genesis`.
 fn load_wallet(ctx: Context) -> Wallet<CliWalletUtils> {
@@ -0,0 +1,157 @@
+//! A ledger RPC client that fails over between several nodes.
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::facade::tendermint_config::net::Address as TendermintAddress;
+use crate::facade::tendermint_rpc::error::{Error, ErrorDetail};
+use crate::facade::tendermint_rpc::{Client, HttpClient, SimpleRequest};
+
+/// The number of times all the nodes are tried before giving up on a request
+pub const MAX_RETRIES: u32 = 3;
+/// The delay before the first retry, doubled on every subsequent retry
+pub const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// One or more comma-separated addresses of ledger nodes, in order of
+/// preference
+#[derive(Clone, Debug, Default)]
+pub struct LedgerAddresses(pub Vec<TendermintAddress>);
+
+impl FromStr for LedgerAddresses {
+    type Err = <TendermintAddress as FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|addr| TendermintAddress::from_str(addr.trim()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+}
+
+impl From<TendermintAddress> for LedgerAddresses {
+    fn from(address: TendermintAddress) -> Self {
+        Self(vec![address])
+    }
+}
+
+/// A client that sends each request to the last node that answered and fails
+/// over to the other nodes if it cannot be reached. When none of the nodes
+/// can be reached, the request is retried with an exponential backoff.
+///
+/// Only failures to reach a node are retried, errors returned by a node are
+/// passed to the caller. This makes retries safe for queries and for
+/// broadcasts, as the mempool rejects a tx it already contains.
+#[derive(Debug)]
+pub struct FailoverClient {
+    clients: Vec<HttpClient>,
+    /// The index of the client that last served a request
+    active: AtomicUsize,
+}
+
+impl FailoverClient {
+    /// Create a client for the given ledger nodes. Fails if no address is
+    /// given or if any of them is invalid.
+    pub fn new(addresses: LedgerAddresses) -> Result<Self, Error> {
+        if addresses.0.is_empty() {
+            return Err(Error::invalid_params(
+                "At least one ledger address is required".to_string(),
+            ));
+        }
+        let clients = addresses
+            .0
+            .into_iter()
+            .map(HttpClient::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            clients,
+            active: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Client for FailoverClient {
+    async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        // Requests cannot be cloned, keep a serialized copy to rebuild them
+        // for every attempt
+        let request = serde_json::to_value(request).map_err(Error::serde)?;
+        let mut delay = RETRY_BASE_DELAY;
+        let mut retries = 0;
+        loop {
+            let active = self.active.load(Ordering::Relaxed);
+            let mut last_err = None;
+            for offset in 0..self.clients.len() {
+                let index = (active + offset) % self.clients.len();
+                let request: R = rebuild(&request)?;
+                match self.clients[index].perform(request).await {
+                    Ok(response) => {
+                        self.active.store(index, Ordering::Relaxed);
+                        return Ok(response);
+                    }
+                    Err(err) if is_unreachable(&err) => {
+                        tracing::debug!(
+                            "Ledger node #{index} could not be reached: {err}"
+                        );
+                        last_err = Some(err);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            let err = last_err.expect("There is at least one ledger node");
+            if retries == MAX_RETRIES {
+                return Err(err);
+            }
+            tracing::info!(
+                "No ledger node could be reached, retrying in {delay:?}: {err}"
+            );
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            retries += 1;
+        }
+    }
+}
+
+/// Deserialize a request from its serialized copy
+fn rebuild<R: DeserializeOwned>(
+    request: &serde_json::Value,
+) -> Result<R, Error> {
+    serde_json::from_value(request.clone()).map_err(Error::serde)
+}
+
+/// Check if an error was caused by a failure to reach the node, rather than
+/// by the node's response to the request
+fn is_unreachable(err: &Error) -> bool {
+    !matches!(err.detail(), ErrorDetail::Response(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that a comma-separated list of ledger addresses is parsed in
+    /// order
+    #[test]
+    fn test_parse_ledger_addresses() {
+        let addresses =
+            LedgerAddresses::from_str("127.0.0.1:26657, tcp://10.0.0.1:26657")
+                .expect("Test failed");
+        assert_eq!(
+            addresses.0,
+            vec![
+                TendermintAddress::from_str("127.0.0.1:26657").unwrap(),
+                TendermintAddress::from_str("tcp://10.0.0.1:26657").unwrap(),
+            ]
+        );
+        assert!(LedgerAddresses::from_str("127.0.0.1:26657,").is_err());
+
+        let client = FailoverClient::new(addresses).expect("Test failed");
+        assert_eq!(client.clients.len(), 2);
+        assert!(FailoverClient::new(LedgerAddresses::default()).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod failover;
 pub mod rpc;
 pub mod tx;
 pub mod utils;
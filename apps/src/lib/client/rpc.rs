@@ -25,7 +25,8 @@ use namada::core::ledger::governance::storage::proposal::{
     StoragePgfFunding, StorageProposal,
 };
 use namada::core::ledger::governance::utils::{
-    compute_proposal_result, ProposalVotes, TallyType, TallyVote, VotePower,
+    compute_proposal_result, ProposalResult, ProposalVotes, TallyType,
+    TallyVote, Vote, VotePower,
 };
 use namada::core::ledger::pgf::parameters::PgfParameters;
 use namada::core::ledger::pgf::storage::steward::StewardDetail;
@@ -33,12 +34,16 @@ use namada::ledger::events::Event;
 use namada::ledger::ibc::storage::{
     ibc_denom_key, ibc_denom_key_prefix, is_ibc_denom_key,
 };
-use namada::ledger::parameters::{storage as param_storage, EpochDuration};
+use namada::ledger::parameters::{
+    storage as param_storage, EpochDuration, WasmLimits,
+};
 use namada::ledger::pos::types::{CommissionPair, Slash};
 use namada::ledger::pos::PosParams;
 use namada::ledger::queries::RPC;
 use namada::proof_of_stake::types::{ValidatorState, WeightedValidator};
 use namada::types::address::{Address, InternalAddress, MASP};
+use namada::types::control_flow::time;
+use namada::types::dec::Dec;
 use namada::types::hash::Hash;
 use namada::types::ibc::{is_ibc_denom, IbcTokenHash};
 use namada::types::io::Io;
@@ -53,8 +58,10 @@ use namada_sdk::proof_of_stake::types::ValidatorMetaData;
 use namada_sdk::rpc::{
     self, enriched_bonds_and_unbonds, query_epoch, TxResponse,
 };
-use namada_sdk::wallet::AddressVpType;
-use namada_sdk::{display, display_line, edisplay_line, error, prompt, Namada};
+use namada_sdk::wallet::{AddressVpType, Wallet};
+use namada_sdk::{
+    display, display_json, display_line, edisplay_line, error, prompt, Namada,
+};
 use tokio::time::Instant;
 
 use crate::cli::{self, args};
@@ -82,6 +89,62 @@ pub async fn query_and_print_epoch(context: &impl Namada) -> Epoch {
     epoch
 }
 
+/// Query and print the epoch of the last committed block as the result of the
+/// epoch query. Other queries print the epoch with [`query_and_print_epoch`],
+/// which only prints it for humans, so that their JSON output is a single
+/// value.
+pub async fn query_and_print_last_epoch(context: &impl Namada) {
+    let epoch = query_and_print_epoch(context).await;
+    display_json!(context.io(), &serde_json::json!({ "epoch": epoch.0 }));
+}
+
+/// Query and print the internal addresses of the system accounts with their
+/// stable names
+pub async fn query_internal_addresses(context: &impl Namada) {
+    let addresses = rpc::query_internal_addresses(context.client())
+        .await
+        .unwrap();
+    for (name, address) in &addresses {
+        display_line!(context.io(), "{name}: {address}");
+    }
+    display_json!(context.io(), &addresses);
+}
+
+/// Query and print the estimated start of the next epoch, or of the given
+/// later epoch
+pub async fn query_next_epoch(
+    context: &impl Namada,
+    args: args::QueryNextEpoch,
+) {
+    let estimate = match args.epoch {
+        Some(epoch) => {
+            rpc::query_epoch_start_estimate(context.client(), epoch).await
+        }
+        None => rpc::query_next_epoch_estimate(context.client()).await,
+    }
+    .unwrap_or_else(|err| {
+        edisplay_line!(context.io(), "Failed to estimate the epoch: {err}");
+        cli::safe_exit(1)
+    });
+    display_line!(
+        context.io(),
+        "Last committed epoch: {}, block height: {}",
+        estimate.current_epoch,
+        estimate.last_block_height
+    );
+    display_line!(
+        context.io(),
+        "Epoch {} estimated to start in {} blocks, at height {} and time {} \
+         (average block time: {} ms)",
+        estimate.epoch,
+        estimate.blocks_remaining,
+        estimate.start_height,
+        estimate.start_time.to_rfc3339(),
+        estimate.average_block_time_ms
+    );
+    display_json!(context.io(), &estimate);
+}
+
 /// Query the last committed block
 pub async fn query_block(context: &impl Namada) {
     let block = namada_sdk::rpc::query_block(context.client())
@@ -96,9 +159,18 @@ pub async fn query_block(context: &impl Namada) {
                 block.height,
                 block.time
             );
+            display_json!(
+                context.io(),
+                &serde_json::json!({
+                    "hash": block.hash.to_string(),
+                    "height": block.height.0,
+                    "time": block.time.to_string(),
+                })
+            );
         }
         None => {
             display_line!(context.io(), "No block has been committed yet.");
+            display_json!(context.io(), &serde_json::Value::Null);
         }
     }
 }
@@ -143,6 +215,8 @@ pub async fn query_transfers(
         .values()
         .map(|fvk| (ExtendedFullViewingKey::from(*fvk).fvk.vk, fvk))
         .collect();
+    // The transfers for the JSON output
+    let mut transfers_json = Vec::new();
     // Now display historical shielded and transparent transactions
     for ((height, idx), (epoch, tfer_delta, tx_delta)) in transfers {
         // Check if this transfer pertains to the supplied owner
@@ -200,6 +274,7 @@ pub async fn query_transfers(
             height,
             idx
         );
+        let mut changes_json = Vec::new();
         // Display the transparent changes first
         for (account, MaspChange { ref asset, change }) in tfer_delta {
             if account != MASP {
@@ -211,13 +286,13 @@ pub async fn query_transfers(
                     Ordering::Less => "-",
                     Ordering::Equal => "",
                 };
-                display!(
-                    context.io(),
-                    " {}{} {}",
-                    sign,
-                    context.format_amount(asset, change.into()).await,
-                    token_alias
-                );
+                let amount = context.format_amount(asset, change.into()).await;
+                display!(context.io(), " {}{} {}", sign, amount, token_alias);
+                changes_json.push(serde_json::json!({
+                    "owner": account.to_string(),
+                    "token": token_alias,
+                    "change": format!("{sign}{amount}"),
+                }));
             }
             display_line!(context.io(), "");
         }
@@ -235,18 +310,31 @@ pub async fn query_transfers(
                         Ordering::Less => "-",
                         Ordering::Equal => "",
                     };
+                    let amount =
+                        context.format_amount(&token_addr, val.into()).await;
                     display!(
                         context.io(),
                         " {}{} {}",
                         sign,
-                        context.format_amount(&token_addr, val.into()).await,
+                        amount,
                         token_alias,
                     );
+                    changes_json.push(serde_json::json!({
+                        "owner": fvk_map[&account].to_string(),
+                        "token": token_alias,
+                        "change": format!("{sign}{amount}"),
+                    }));
                 }
                 display_line!(context.io(), "");
             }
         }
+        transfers_json.push(serde_json::json!({
+            "height": height.0,
+            "index": idx.0,
+            "changes": changes_json,
+        }));
     }
+    display_json!(context.io(), &transfers_json);
 }
 
 /// Query the raw bytes of given storage key
@@ -265,26 +353,30 @@ pub async fn query_raw_bytes<N: Namada>(
             )
             .await,
     );
-    if !response.data.is_empty() {
-        display_line!(
-            context.io(),
-            "Found data: 0x{}",
-            HEXLOWER.encode(&response.data)
-        );
-    } else {
-        display_line!(
+    let data =
+        (!response.data.is_empty()).then(|| HEXLOWER.encode(&response.data));
+    match &data {
+        Some(data) => display_line!(context.io(), "Found data: 0x{}", data),
+        None => display_line!(
             context.io(),
             "No data found for key {}",
             args.storage_key
-        );
+        ),
     }
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "key": args.storage_key.to_string(),
+            "data": data,
+        })
+    );
 }
 
 /// Query token balance(s)
 pub async fn query_balance(context: &impl Namada, args: args::QueryBalance) {
     // Query the balances of shielded or transparent account types depending on
     // the CLI arguments
-    match &args.owner {
+    let balances = match &args.owner {
         Some(BalanceOwner::FullViewingKey(_viewing_key)) => {
             query_shielded_balance(context, args).await
         }
@@ -296,24 +388,47 @@ pub async fn query_balance(context: &impl Namada, args: args::QueryBalance) {
         }
         None => {
             // Print pinned balance
-            query_pinned_balance(context, args.clone()).await;
+            let mut balances =
+                query_pinned_balance(context, args.clone()).await;
             // Print shielded balance
-            query_shielded_balance(context, args.clone()).await;
+            balances
+                .extend(query_shielded_balance(context, args.clone()).await);
             // Then print transparent balance
-            query_transparent_balance(context, args).await;
+            balances.extend(query_transparent_balance(context, args).await);
+            balances
         }
     };
+    display_json!(context.io(), &balances);
 }
 
-/// Query token balance(s)
+/// A balance printed by a balance query, for the JSON output
+fn balance_json(
+    owner: impl ToString,
+    token: impl ToString,
+    amount: impl ToString,
+    epoch: Option<Epoch>,
+) -> serde_json::Value {
+    let mut balance = serde_json::json!({
+        "owner": owner.to_string(),
+        "token": token.to_string(),
+        "amount": amount.to_string(),
+    });
+    if let Some(epoch) = epoch {
+        balance["epoch"] = epoch.0.into();
+    }
+    balance
+}
+
+/// Query token balance(s). Returns the printed balances for the JSON output.
 pub async fn query_transparent_balance(
     context: &impl Namada,
     args: args::QueryBalance,
-) {
+) -> Vec<serde_json::Value> {
     let prefix = Key::from(
         Address::Internal(namada::types::address::InternalAddress::Multitoken)
             .to_db_key(),
     );
+    let mut balances_json = Vec::new();
     match (args.token, args.owner) {
         (Some(base_token), Some(owner)) => {
             let owner = owner.address().unwrap();
@@ -336,6 +451,12 @@ pub async fn query_transparent_balance(
                             token_alias,
                             balance
                         );
+                        balances_json.push(balance_json(
+                            &owner,
+                            token_alias,
+                            balance,
+                            None,
+                        ));
                     }
                     Err(e) => {
                         display_line!(context.io(), "Querying error: {e}");
@@ -351,14 +472,44 @@ pub async fn query_transparent_balance(
         }
         (None, Some(owner)) => {
             let owner = owner.address().unwrap();
-            let tokens = query_tokens(context, None, Some(&owner)).await;
-            for (token_alias, token) in tokens {
-                let balance =
-                    get_token_balance(context.client(), &token, &owner).await;
-                if !balance.is_zero() {
-                    let balance = context.format_amount(&token, balance).await;
-                    display_line!(context.io(), "{}: {}", token_alias, balance);
+            // The balances of all the known tokens, including the IBC tokens
+            // unknown to the wallet, are found in a single query
+            let tokens: BTreeMap<Address, String> =
+                query_tokens(context, None, Some(&owner))
+                    .await
+                    .into_iter()
+                    .map(|(alias, token)| (token, alias))
+                    .collect();
+            let token_addresses: Vec<Address> =
+                tokens.keys().cloned().collect();
+            let balances = rpc::get_token_balances(
+                context.client(),
+                &owner,
+                &token_addresses,
+            )
+            .await;
+            let balances = match balances {
+                Ok(balances) => balances,
+                Err(e) => {
+                    edisplay_line!(context.io(), "Querying error: {e}");
+                    return balances_json;
                 }
+            };
+            if balances.is_empty() {
+                let owner = context.wallet().await.format_address(&owner);
+                display_line!(context.io(), "No balances owned by {}", owner);
+                return balances_json;
+            }
+            for (token, balance) in balances {
+                let token_alias = tokens[&token].clone();
+                let balance = context.format_amount(&token, balance).await;
+                display_line!(context.io(), "{}: {}", token_alias, balance);
+                balances_json.push(balance_json(
+                    &owner,
+                    token_alias,
+                    balance,
+                    None,
+                ));
             }
         }
         (Some(base_token), None) => {
@@ -369,24 +520,31 @@ pub async fn query_transparent_balance(
                     query_storage_prefix::<token::Amount>(context, &prefix)
                         .await;
                 if let Some(balances) = balances {
-                    print_balances(context, balances, Some(&token), None).await;
+                    balances_json.extend(
+                        print_balances(context, balances, Some(&token), None)
+                            .await,
+                    );
                 }
             }
         }
         (None, None) => {
             let balances = query_storage_prefix(context, &prefix).await;
             if let Some(balances) = balances {
-                print_balances(context, balances, None, None).await;
+                balances_json.extend(
+                    print_balances(context, balances, None, None).await,
+                );
             }
         }
     }
+    balances_json
 }
 
-/// Query the token pinned balance(s)
+/// Query the token pinned balance(s). Returns the printed balances for the
+/// JSON output.
 pub async fn query_pinned_balance(
     context: &impl Namada,
     args: args::QueryBalance,
-) {
+) -> Vec<serde_json::Value> {
     // Map addresses to token names
     let wallet = context.wallet().await;
     let owners = if let Some(pa) = args.owner.and_then(|x| x.payment_address())
@@ -406,6 +564,7 @@ pub async fn query_pinned_balance(
         .map(|fvk| ExtendedFullViewingKey::from(*fvk).fvk.vk)
         .collect();
     let _ = context.shielded_mut().await.load().await;
+    let mut balances_json = Vec::new();
     // Print the token balances by payment address
     for owner in owners {
         let mut balance =
@@ -500,6 +659,12 @@ pub async fn query_pinned_balance(
                             formatted,
                             token_alias,
                         );
+                        balances_json.push(balance_json(
+                            owner,
+                            token_alias,
+                            formatted,
+                            Some(epoch),
+                        ));
                     }
                 }
             }
@@ -531,6 +696,12 @@ pub async fn query_pinned_balance(
                         token_alias,
                         formatted,
                     );
+                    balances_json.push(balance_json(
+                        owner,
+                        token_alias,
+                        formatted,
+                        Some(epoch),
+                    ));
                 }
                 if !found_any {
                     display_line!(
@@ -544,35 +715,36 @@ pub async fn query_pinned_balance(
             }
         }
     }
+    balances_json
 }
 
+/// Print the given balances. Returns the printed balances for the JSON
+/// output.
 async fn print_balances(
     context: &impl Namada,
     balances: impl Iterator<Item = (storage::Key, token::Amount)>,
     token: Option<&Address>,
     target: Option<&Address>,
-) {
+) -> Vec<serde_json::Value> {
     let stdout = io::stdout();
     let mut w = stdout.lock();
     let wallet = context.wallet().await;
 
+    let mut balances_json = Vec::new();
     let mut print_num = 0;
     let mut print_token = None;
     for (key, balance) in balances {
         // Get the token, the owner, and the balance with the token and the
         // owner
-        let (t, o, s) = match token::is_any_token_balance_key(&key) {
+        let (t, o, amount) = match token::is_any_token_balance_key(&key) {
             Some([tok, owner]) => (
                 tok.clone(),
                 owner.clone(),
-                format!(
-                    ": {}, owned by {}",
-                    context.format_amount(tok, balance).await,
-                    wallet.lookup_alias(owner)
-                ),
+                context.format_amount(tok, balance).await,
             ),
             None => continue,
         };
+        let s = format!(": {}, owned by {}", amount, wallet.lookup_alias(&o));
         let token_alias = lookup_token_alias(context, &t, &o).await;
         // Get the token and the balance
         let (t, s) = match (token, target) {
@@ -603,6 +775,12 @@ async fn print_balances(
         }
         // Print the balance
         display_line!(context.io(), &mut w; "{}", s).unwrap();
+        balances_json.push(balance_json(
+            wallet.lookup_alias(&o),
+            &token_alias,
+            amount,
+            None,
+        ));
         print_num += 1;
     }
 
@@ -624,6 +802,7 @@ async fn print_balances(
             }
         }
     }
+    balances_json
 }
 
 async fn lookup_token_alias(
@@ -725,6 +904,23 @@ async fn get_ibc_denom_alias(
         .unwrap_or(ibc_denom.as_ref().to_string())
 }
 
+/// A proposal printed by a query, for the JSON output
+fn proposal_json(
+    proposal: &StorageProposal,
+    current_epoch: Epoch,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": proposal.id,
+        "type": proposal.r#type.to_string(),
+        "author": proposal.author.to_string(),
+        "content": proposal.content,
+        "voting_start_epoch": proposal.voting_start_epoch.0,
+        "voting_end_epoch": proposal.voting_end_epoch.0,
+        "grace_epoch": proposal.grace_epoch.0,
+        "status": proposal.get_status(current_epoch).to_string(),
+    })
+}
+
 /// Query Proposals
 pub async fn query_proposal(context: &impl Namada, args: args::QueryProposal) {
     let current_epoch = query_and_print_epoch(context).await;
@@ -738,8 +934,13 @@ pub async fn query_proposal(context: &impl Namada, args: args::QueryProposal) {
                 "{}",
                 proposal.to_string_with_status(current_epoch)
             );
+            display_json!(
+                context.io(),
+                &proposal_json(&proposal, current_epoch)
+            );
         } else {
             edisplay_line!(context.io(), "No proposal found with id: {}", id);
+            display_json!(context.io(), &serde_json::Value::Null);
         }
     } else {
         let last_proposal_id_key = governance_storage::get_counter_key();
@@ -756,13 +957,16 @@ pub async fn query_proposal(context: &impl Namada, args: args::QueryProposal) {
 
         display_line!(context.io(), "id: {}", last_proposal_id);
 
+        let mut proposals_json = Vec::new();
         for id in from_id..last_proposal_id {
             let proposal = query_proposal_by_id(context.client(), id)
                 .await
                 .unwrap()
                 .expect("Proposal should be written to storage.");
             display_line!(context.io(), "{}", proposal);
+            proposals_json.push(proposal_json(&proposal, current_epoch));
         }
+        display_json!(context.io(), &proposals_json);
     }
 }
 
@@ -774,11 +978,12 @@ pub async fn query_proposal_by_id<C: namada::ledger::queries::Client + Sync>(
     namada_sdk::rpc::query_proposal_by_id(client, proposal_id).await
 }
 
-/// Query token shielded balance(s)
+/// Query token shielded balance(s). Returns the printed balances for the JSON
+/// output.
 pub async fn query_shielded_balance(
     context: &impl Namada,
     args: args::QueryBalance,
-) {
+) -> Vec<serde_json::Value> {
     // Used to control whether balances for all keys or a specific key are
     // printed
     let owner = args.owner.and_then(|x| x.full_viewing_key());
@@ -809,6 +1014,7 @@ pub async fn query_shielded_balance(
     }
     // The epoch is required to identify timestamped tokens
     let epoch = query_and_print_epoch(context).await;
+    let mut balances_json = Vec::new();
     // Map addresses to token names
     match (args.token, owner.is_some()) {
         // Here the user wants to know the balance for a specific token
@@ -856,17 +1062,24 @@ pub async fn query_shielded_balance(
                         token_alias
                     );
                 } else {
+                    let formatted = context
+                        .format_amount(
+                            &token,
+                            token::Amount::from(total_balance),
+                        )
+                        .await;
                     display_line!(
                         context.io(),
                         "{}: {}",
                         token_alias,
-                        context
-                            .format_amount(
-                                &token,
-                                token::Amount::from(total_balance),
-                            )
-                            .await
+                        formatted
                     );
+                    balances_json.push(balance_json(
+                        viewing_keys[0],
+                        token_alias,
+                        formatted,
+                        None,
+                    ));
                 }
             }
         }
@@ -942,6 +1155,7 @@ pub async fn query_shielded_balance(
                     formatted,
                     fvk
                 );
+                balances_json.push(balance_json(fvk, alias, formatted, None));
             }
         }
         // Here the user wants to know the balance for a specific token across
@@ -998,6 +1212,12 @@ pub async fn query_shielded_balance(
                             formatted,
                             fvk
                         );
+                        balances_json.push(balance_json(
+                            fvk,
+                            &token_alias,
+                            formatted,
+                            None,
+                        ));
                     }
                 }
                 if !found_any {
@@ -1023,7 +1243,14 @@ pub async fn query_shielded_balance(
                     .unwrap()
                     .expect("context should contain viewing key");
                 // Print balances by human-readable token names
-                print_decoded_balance_with_epoch(context, balance).await;
+                balances_json.extend(
+                    print_decoded_balance_with_epoch(
+                        context,
+                        &viewing_keys[0],
+                        balance,
+                    )
+                    .await,
+                );
             } else {
                 let balance = context
                     .shielded_mut()
@@ -1038,17 +1265,30 @@ pub async fn query_shielded_balance(
                     .unwrap()
                     .expect("context should contain viewing key");
                 // Print balances by human-readable token names
-                print_decoded_balance(context, balance, epoch).await;
+                balances_json.extend(
+                    print_decoded_balance(
+                        context,
+                        &viewing_keys[0],
+                        balance,
+                        epoch,
+                    )
+                    .await,
+                );
             }
         }
     }
+    balances_json
 }
 
+/// Print the balance of the given viewing key. Returns the printed balances
+/// for the JSON output.
 pub async fn print_decoded_balance(
     context: &impl Namada,
+    viewing_key: &ExtendedViewingKey,
     decoded_balance: MaspAmount,
     epoch: Epoch,
-) {
+) -> Vec<serde_json::Value> {
+    let mut balances_json = Vec::new();
     if decoded_balance.is_empty() {
         display_line!(context.io(), "No shielded balance found for given key");
     } else {
@@ -1056,20 +1296,28 @@ pub async fn print_decoded_balance(
             .iter()
             .filter(|((token_epoch, _), _)| *token_epoch == epoch)
         {
-            display_line!(
-                context.io(),
-                "{} : {}",
-                lookup_token_alias(context, token_addr, &MASP).await,
-                context.format_amount(token_addr, (*amount).into()).await,
-            );
+            let alias = lookup_token_alias(context, token_addr, &MASP).await;
+            let formatted =
+                context.format_amount(token_addr, (*amount).into()).await;
+            display_line!(context.io(), "{} : {}", alias, formatted);
+            balances_json.push(balance_json(
+                viewing_key,
+                alias,
+                formatted,
+                None,
+            ));
         }
     }
+    balances_json
 }
 
+/// Print the balance of the given viewing key by epoch. Returns the printed
+/// balances for the JSON output.
 pub async fn print_decoded_balance_with_epoch(
     context: &impl Namada,
+    viewing_key: &ExtendedViewingKey,
     decoded_balance: MaspAmount,
-) {
+) -> Vec<serde_json::Value> {
     let tokens = context
         .wallet()
         .await
@@ -1077,20 +1325,23 @@ pub async fn print_decoded_balance_with_epoch(
     if decoded_balance.is_empty() {
         display_line!(context.io(), "No shielded balance found for given key");
     }
+    let mut balances_json = Vec::new();
     for ((epoch, token_addr), value) in decoded_balance.iter() {
         let asset_value = (*value).into();
         let alias = tokens
             .get(token_addr)
             .map(|a| a.to_string())
             .unwrap_or_else(|| token_addr.to_string());
-        display_line!(
-            context.io(),
-            "{} | {} : {}",
+        let formatted = context.format_amount(token_addr, asset_value).await;
+        display_line!(context.io(), "{} | {} : {}", alias, epoch, formatted);
+        balances_json.push(balance_json(
+            viewing_key,
             alias,
-            epoch,
-            context.format_amount(token_addr, asset_value).await,
-        );
+            formatted,
+            Some(*epoch),
+        ));
     }
+    balances_json
 }
 
 /// Query token amount of owner.
@@ -1104,6 +1355,152 @@ pub async fn get_token_balance<C: namada::ledger::queries::Client + Sync>(
         .unwrap()
 }
 
+/// The number of votes shown on a page of the proposal votes query
+const PROPOSAL_VOTES_PAGE_SIZE: u64 = 20;
+
+/// Query the votes cast on a proposal, optionally one page at a time
+pub async fn query_proposal_votes(
+    context: &impl Namada,
+    args: args::QueryProposalVotes,
+) {
+    let proposal_id = args.proposal_id;
+    match query_proposal_by_id(context.client(), proposal_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            edisplay_line!(context.io(), "Proposal {} not found.", proposal_id);
+            display_json!(context.io(), &serde_json::Value::Null);
+            return;
+        }
+        Err(err) => {
+            edisplay_line!(
+                context.io(),
+                "Failed to query the proposal {}: {}",
+                proposal_id,
+                err
+            );
+            return;
+        }
+    }
+    let vote_json = |vote: &Vote| {
+        serde_json::json!({
+            "validator": vote.validator.to_string(),
+            "delegator": vote.delegator.to_string(),
+            "vote": vote.data.to_string(),
+        })
+    };
+    let display_vote = |wallet: &Wallet<_>, vote: &Vote| {
+        if vote.is_validator() {
+            display_line!(
+                context.io(),
+                "  {}: {} (validator)",
+                wallet.format_address(&vote.validator),
+                vote.data
+            );
+        } else {
+            display_line!(
+                context.io(),
+                "  {}: {} (delegated to {})",
+                wallet.format_address(&vote.delegator),
+                vote.data,
+                wallet.format_address(&vote.validator)
+            );
+        }
+    };
+
+    if !args.paginate {
+        let votes = match namada_sdk::rpc::query_proposal_votes(
+            context.client(),
+            proposal_id,
+        )
+        .await
+        {
+            Ok(votes) => votes,
+            Err(err) => {
+                edisplay_line!(
+                    context.io(),
+                    "Failed to query the votes of the proposal {}: {}",
+                    proposal_id,
+                    err
+                );
+                return;
+            }
+        };
+        display_line!(
+            context.io(),
+            "Proposal {} has {} votes",
+            proposal_id,
+            votes.len()
+        );
+        let wallet = context.wallet().await;
+        votes.iter().for_each(|vote| display_vote(&wallet, vote));
+        display_json!(
+            context.io(),
+            &votes.iter().map(vote_json).collect::<Vec<_>>()
+        );
+        return;
+    }
+
+    // The votes of all the pages that have been shown
+    let mut votes_json = Vec::new();
+    let mut offset = 0;
+    loop {
+        let votes = match namada_sdk::rpc::query_proposal_votes_page(
+            context.client(),
+            proposal_id,
+            offset,
+            PROPOSAL_VOTES_PAGE_SIZE,
+        )
+        .await
+        {
+            Ok(votes) => votes,
+            Err(err) => {
+                edisplay_line!(
+                    context.io(),
+                    "Failed to query the votes of the proposal {}: {}",
+                    proposal_id,
+                    err
+                );
+                break;
+            }
+        };
+        if votes.is_empty() {
+            if offset == 0 {
+                display_line!(
+                    context.io(),
+                    "Proposal {} has no votes",
+                    proposal_id
+                );
+            }
+            break;
+        }
+        display_line!(
+            context.io(),
+            "Votes {} to {} of proposal {}:",
+            offset + 1,
+            offset + votes.len() as u64,
+            proposal_id
+        );
+        {
+            let wallet = context.wallet().await;
+            votes.iter().for_each(|vote| display_vote(&wallet, vote));
+        }
+        votes_json.extend(votes.iter().map(vote_json));
+        if (votes.len() as u64) < PROPOSAL_VOTES_PAGE_SIZE {
+            break;
+        }
+        offset += PROPOSAL_VOTES_PAGE_SIZE;
+        let answer = prompt!(
+            context.io(),
+            "Press enter for the next page or q to quit: "
+        )
+        .await;
+        if answer.trim().eq_ignore_ascii_case("q") {
+            break;
+        }
+    }
+    display_json!(context.io(), &votes_json);
+}
+
 pub async fn query_proposal_result(
     context: &impl Namada,
     args: args::QueryProposalResult,
@@ -1119,6 +1516,7 @@ pub async fn query_proposal_result(
             proposal
         } else {
             edisplay_line!(context.io(), "Proposal {} not found.", proposal_id);
+            display_json!(context.io(), &serde_json::Value::Null);
             return;
         };
 
@@ -1158,6 +1556,10 @@ pub async fn query_proposal_result(
 
         display_line!(context.io(), "Proposal Id: {} ", proposal_id);
         display_line!(context.io(), "{:4}{}", "", proposal_result);
+        display_json!(
+            context.io(),
+            &proposal_result_json(proposal_id.to_string(), &proposal_result)
+        );
     } else {
         let proposal_folder = args.proposal_folder.expect(
             "The argument --proposal-folder is required with --offline.",
@@ -1241,28 +1643,173 @@ pub async fn query_proposal_result(
         );
         display_line!(context.io(), "Parsed {} votes.", votes.len());
         display_line!(context.io(), "{:4}{}", "", proposal_result);
+        display_json!(
+            context.io(),
+            &proposal_result_json(
+                proposal.proposal.hash().to_string(),
+                &proposal_result
+            )
+        );
     }
 }
 
+/// The result of a proposal printed by a query, for the JSON output
+fn proposal_result_json(
+    proposal: String,
+    result: &ProposalResult,
+) -> serde_json::Value {
+    serde_json::json!({
+        "proposal": proposal,
+        "result": result.result.to_string(),
+        "total_voting_power": result.total_voting_power.to_string_native(),
+        "total_yay_power": result.total_yay_power.to_string_native(),
+        "total_nay_power": result.total_nay_power.to_string_native(),
+        "total_abstain_power": result.total_abstain_power.to_string_native(),
+    })
+}
+
 pub async fn query_account(context: &impl Namada, args: args::QueryAccount) {
     let account = rpc::get_account_info(context.client(), &args.owner)
         .await
         .unwrap();
+    let wallet = context.wallet().await;
     if let Some(account) = account {
-        display_line!(context.io(), "Address: {}", account.address);
+        display_line!(
+            context.io(),
+            "Address: {}",
+            wallet.format_address(&account.address)
+        );
         display_line!(context.io(), "Threshold: {}", account.threshold);
         display_line!(context.io(), "Public keys:");
+        let mut public_keys = Vec::new();
         for (public_key, _) in account.public_keys_map.pk_to_idx {
             display_line!(context.io(), "- {}", public_key);
+            public_keys.push(public_key.to_string());
         }
+        display_json!(
+            context.io(),
+            &serde_json::json!({
+                "address": account.address.to_string(),
+                "threshold": account.threshold,
+                "public_keys": public_keys,
+            })
+        );
     } else {
-        display_line!(context.io(), "No account exists for {}", args.owner);
+        display_line!(
+            context.io(),
+            "No account exists for {}",
+            wallet.format_address(&args.owner)
+        );
+        display_json!(context.io(), &serde_json::Value::Null);
     }
 }
 
+/// Query the storage usage of an address, or of all the addresses
+pub async fn query_storage_usage<N: Namada>(
+    context: &N,
+    args: args::QueryStorageUsage,
+) {
+    let usage = unwrap_client_response::<N::Client, u64>(
+        RPC.shell()
+            .storage_usage(context.client(), &args.owner)
+            .await,
+    );
+    match &args.owner {
+        Some(owner) => display_line!(
+            context.io(),
+            "Storage usage of {}: {usage} bytes",
+            context.wallet().await.format_address(owner)
+        ),
+        None => {
+            display_line!(context.io(), "Total storage usage: {usage} bytes")
+        }
+    }
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "owner": args.owner.map(|owner| owner.to_string()),
+            "bytes": usage,
+        })
+    );
+}
+
+/// Query the total supply of a token
+pub async fn query_total_supply<N: Namada>(
+    context: &N,
+    args: args::QueryTotalSupply,
+) {
+    let total_supply = unwrap_client_response::<N::Client, token::Amount>(
+        RPC.vp()
+            .token()
+            .total_supply(context.client(), &args.token)
+            .await,
+    );
+    let token_alias = context.wallet().await.lookup_alias(&args.token);
+    let metadata = unwrap_client_response::<N::Client, Option<token::Metadata>>(
+        RPC.vp()
+            .token()
+            .metadata(context.client(), &args.token)
+            .await,
+    );
+    let amount = context.format_amount(&args.token, total_supply).await;
+    match &metadata {
+        Some(token::Metadata {
+            symbol,
+            display_name,
+        }) => display_line!(
+            context.io(),
+            "Total supply of {} ({}): {} {}",
+            display_name,
+            token_alias,
+            amount,
+            symbol
+        ),
+        None => display_line!(
+            context.io(),
+            "Total supply of {}: {}",
+            token_alias,
+            amount
+        ),
+    }
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "token": token_alias,
+            "symbol": metadata.as_ref().map(|metadata| &metadata.symbol),
+            "total_supply": amount,
+        })
+    );
+}
+
 pub async fn query_pgf(context: &impl Namada, _args: args::QueryPgf) {
     let stewards = query_pgf_stewards(context.client()).await;
     let fundings = query_pgf_fundings(context.client()).await;
+    let pgf_json = serde_json::json!({
+        "stewards": stewards
+            .iter()
+            .map(|steward| {
+                serde_json::json!({
+                    "address": steward.address.to_string(),
+                    "reward_distribution": steward
+                        .reward_distribution
+                        .iter()
+                        .map(|(address, percentage)| {
+                            (address.to_string(), percentage.to_string())
+                        })
+                        .collect::<BTreeMap<_, _>>(),
+                })
+            })
+            .collect::<Vec<_>>(),
+        "fundings": fundings
+            .iter()
+            .map(|funding| {
+                serde_json::json!({
+                    "target": funding.detail.target.to_string(),
+                    "amount": funding.detail.amount.to_string_native(),
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
 
     match stewards.is_empty() {
         true => {
@@ -1272,9 +1819,15 @@ pub async fn query_pgf(context: &impl Namada, _args: args::QueryPgf) {
             )
         }
         false => {
+            let wallet = context.wallet().await;
             display_line!(context.io(), "Pgf stewards:");
             for steward in stewards {
-                display_line!(context.io(), "{:4}- {}", "", steward.address);
+                display_line!(
+                    context.io(),
+                    "{:4}- {}",
+                    "",
+                    wallet.format_address(&steward.address)
+                );
                 display_line!(context.io(), "{:4}  Reward distribution:", "");
                 for (address, percentage) in steward.reward_distribution {
                     display_line!(
@@ -1282,7 +1835,7 @@ pub async fn query_pgf(context: &impl Namada, _args: args::QueryPgf) {
                         "{:6}- {} to {}",
                         "",
                         percentage,
-                        address
+                        wallet.format_address(&address)
                     );
                 }
             }
@@ -1297,18 +1850,20 @@ pub async fn query_pgf(context: &impl Namada, _args: args::QueryPgf) {
             )
         }
         false => {
+            let wallet = context.wallet().await;
             display_line!(context.io(), "Pgf fundings:");
             for funding in fundings {
                 display_line!(
                     context.io(),
                     "{:4}- {} for {}",
                     "",
-                    funding.detail.target,
+                    wallet.format_address(&funding.detail.target),
                     funding.detail.amount.to_string_native()
                 );
             }
         }
     }
+    display_json!(context.io(), &pgf_json);
 }
 
 pub async fn query_protocol_parameters(
@@ -1354,6 +1909,18 @@ pub async fn query_protocol_parameters(
         "",
         governance_parameters.min_proposal_grace_epochs
     );
+    display_line!(
+        context.io(),
+        "{:4}Votes retention epochs: {}",
+        "",
+        governance_parameters.votes_retention_epochs
+    );
+    display_line!(
+        context.io(),
+        "{:4}Max. epoch hooks gas: {}",
+        "",
+        governance_parameters.max_epoch_hooks_gas
+    );
 
     let pgf_parameters = query_pgf_parameters(context.client()).await;
     display_line!(context.io(), "Public Goods Funding Parameters\n");
@@ -1423,6 +1990,44 @@ pub async fn query_protocol_parameters(
         .expect("Parameter should be defined.");
     display_line!(context.io(), "{:4}Max block gas: {:?}", "", max_block_gas);
 
+    // The block space shares default to the allotment of the chains that
+    // predate them
+    let key = param_storage::get_max_encrypted_txs_share_key();
+    let max_encrypted_txs_share: Dec =
+        query_storage_value(context.client(), &key)
+            .await
+            .unwrap_or_else(|_| Dec::one() / 3);
+    display_line!(
+        context.io(),
+        "{:4}Max encrypted txs block space share: {}",
+        "",
+        max_encrypted_txs_share
+    );
+
+    let key = param_storage::get_max_decrypted_txs_share_key();
+    let max_decrypted_txs_share: Dec =
+        query_storage_value(context.client(), &key)
+            .await
+            .unwrap_or_else(|_| Dec::one());
+    display_line!(
+        context.io(),
+        "{:4}Max decrypted txs block space share: {}",
+        "",
+        max_decrypted_txs_share
+    );
+
+    let key = param_storage::get_min_protocol_txs_share_key();
+    let min_protocol_txs_share: Dec =
+        query_storage_value(context.client(), &key)
+            .await
+            .unwrap_or_default();
+    display_line!(
+        context.io(),
+        "{:4}Min protocol txs block space share: {}",
+        "",
+        min_protocol_txs_share
+    );
+
     let key = param_storage::get_fee_unshielding_gas_limit_key();
     let fee_unshielding_gas_limit: u64 =
         query_storage_value(context.client(), &key)
@@ -1447,14 +2052,64 @@ pub async fn query_protocol_parameters(
         fee_unshielding_descriptions_limit
     );
 
+    let key = param_storage::get_wasm_limits_key();
+    let wasm_limits: WasmLimits = query_storage_value(context.client(), &key)
+        .await
+        .expect("Parameter should be defined.");
+    display_line!(context.io(), "{:4}WASM limits: {:?}", "", wasm_limits);
+
+    let key = param_storage::get_storage_deposit_per_byte_key();
+    let storage_deposit_per_byte: token::Amount =
+        query_storage_value(context.client(), &key)
+            .await
+            .unwrap_or_default();
+    display_line!(
+        context.io(),
+        "{:4}Storage deposit per byte: {}",
+        "",
+        storage_deposit_per_byte.to_string_native()
+    );
+
+    let key = param_storage::get_blocked_addresses_key();
+    let blocked_addresses =
+        query_storage_value::<_, BTreeSet<Address>>(context.client(), &key)
+            .await
+            .ok();
+    match &blocked_addresses {
+        Some(blocked_addresses) => {
+            let wallet = context.wallet().await;
+            display_line!(context.io(), "{:4}Blocked addresses:", "");
+            for address in blocked_addresses {
+                display_line!(
+                    context.io(),
+                    "{:8}{}",
+                    "",
+                    wallet.format_address(address)
+                );
+            }
+        }
+        None => {
+            display_line!(context.io(), "{:4}Blocked addresses: disabled", "")
+        }
+    }
+
     let key = param_storage::get_gas_cost_key();
     let gas_cost_table: BTreeMap<Address, token::Amount> =
         query_storage_value(context.client(), &key)
             .await
             .expect("Parameter should be defined.");
     display_line!(context.io(), "{:4}Gas cost table:", "");
-    for (token, gas_cost) in gas_cost_table {
-        display_line!(context.io(), "{:8}{}: {:?}", "", token, gas_cost);
+    {
+        let wallet = context.wallet().await;
+        for (token, gas_cost) in &gas_cost_table {
+            display_line!(
+                context.io(),
+                "{:8}{}: {:?}",
+                "",
+                wallet.format_address(token),
+                gas_cost
+            );
+        }
     }
 
     display_line!(context.io(), "PoS parameters");
@@ -1507,6 +2162,78 @@ pub async fn query_protocol_parameters(
         "",
         pos_params.tm_votes_per_token
     );
+
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "governance": {
+                "min_proposal_fund":
+                    governance_parameters.min_proposal_fund.to_string_native(),
+                "max_proposal_code_size":
+                    governance_parameters.max_proposal_code_size,
+                "min_proposal_voting_period":
+                    governance_parameters.min_proposal_voting_period,
+                "max_proposal_period":
+                    governance_parameters.max_proposal_period,
+                "max_proposal_content_size":
+                    governance_parameters.max_proposal_content_size,
+                "min_proposal_grace_epochs":
+                    governance_parameters.min_proposal_grace_epochs,
+                "votes_retention_epochs":
+                    governance_parameters.votes_retention_epochs,
+                "max_epoch_hooks_gas":
+                    governance_parameters.max_epoch_hooks_gas,
+            },
+            "pgf": {
+                "pgf_inflation_rate":
+                    pgf_parameters.pgf_inflation_rate.to_string(),
+                "stewards_inflation_rate":
+                    pgf_parameters.stewards_inflation_rate.to_string(),
+            },
+            "protocol": {
+                "min_epoch_duration": epoch_duration.min_duration.0,
+                "min_num_of_blocks": epoch_duration.min_num_of_blocks,
+                "max_block_duration": max_block_duration,
+                "vp_whitelist": vp_whitelist,
+                "tx_whitelist": tx_whitelist,
+                "max_block_gas": max_block_gas,
+                "max_encrypted_txs_share": max_encrypted_txs_share.to_string(),
+                "max_decrypted_txs_share": max_decrypted_txs_share.to_string(),
+                "min_protocol_txs_share": min_protocol_txs_share.to_string(),
+                "fee_unshielding_gas_limit": fee_unshielding_gas_limit,
+                "fee_unshielding_descriptions_limit":
+                    fee_unshielding_descriptions_limit,
+                "wasm_limits": wasm_limits,
+                "storage_deposit_per_byte":
+                    storage_deposit_per_byte.to_string_native(),
+                "blocked_addresses": blocked_addresses.map(|addresses| {
+                    addresses
+                        .iter()
+                        .map(|address| address.to_string())
+                        .collect::<Vec<_>>()
+                }),
+                "gas_cost_table": gas_cost_table
+                    .iter()
+                    .map(|(token, gas_cost)| {
+                        (token.to_string(), gas_cost.to_string_native())
+                    })
+                    .collect::<BTreeMap<_, _>>(),
+            },
+            "pos": {
+                "block_proposer_reward":
+                    pos_params.block_proposer_reward.to_string(),
+                "block_vote_reward": pos_params.block_vote_reward.to_string(),
+                "duplicate_vote_min_slash_rate":
+                    pos_params.duplicate_vote_min_slash_rate.to_string(),
+                "light_client_attack_min_slash_rate":
+                    pos_params.light_client_attack_min_slash_rate.to_string(),
+                "max_validator_slots": pos_params.max_validator_slots,
+                "pipeline_len": pos_params.pipeline_len,
+                "unbonding_len": pos_params.unbonding_len,
+                "tm_votes_per_token": pos_params.tm_votes_per_token.to_string(),
+            },
+        })
+    );
 }
 
 pub async fn query_bond<C: namada::ledger::queries::Client + Sync>(
@@ -1646,14 +2373,47 @@ pub async fn query_bonds(
     .await
     .unwrap();
 
+    let wallet = context.wallet().await;
+    let mut bonds_json = Vec::new();
     for (bond_id, details) in &bonds_and_unbonds.data {
+        bonds_json.push(serde_json::json!({
+            "source": bond_id.source.to_string(),
+            "validator": bond_id.validator.to_string(),
+            "bonds": details
+                .data
+                .bonds
+                .iter()
+                .map(|bond| {
+                    serde_json::json!({
+                        "start": bond.start.0,
+                        "amount": bond.amount.to_string_native(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+            "unbonds": details
+                .data
+                .unbonds
+                .iter()
+                .map(|unbond| {
+                    serde_json::json!({
+                        "start": unbond.start.0,
+                        "withdraw": unbond.withdraw.0,
+                        "amount": unbond.amount.to_string_native(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+            "bonds_total": details.bonds_total.to_string_native(),
+            "bonds_total_active":
+                details.bonds_total_active().to_string_native(),
+            "unbonds_total": details.unbonds_total.to_string_native(),
+            "total_withdrawable": details.total_withdrawable.to_string_native(),
+        }));
+        let source = wallet.format_address(&bond_id.source);
+        let validator = wallet.format_address(&bond_id.validator);
         let bond_type = if bond_id.source == bond_id.validator {
-            format!("Self-bonds from {}", bond_id.validator)
+            format!("Self-bonds from {}", validator)
         } else {
-            format!(
-                "Delegations from {} to {}",
-                bond_id.source, bond_id.validator
-            )
+            format!("Delegations from {} to {}", source, validator)
         };
         display_line!(context.io(), &mut w; "{}:", bond_type)?;
         for bond in &details.data.bonds {
@@ -1678,9 +2438,9 @@ pub async fn query_bonds(
 
         if !details.data.unbonds.is_empty() {
             let bond_type = if bond_id.source == bond_id.validator {
-                format!("Unbonded self-bonds from {}", bond_id.validator)
+                format!("Unbonded self-bonds from {}", validator)
             } else {
-                format!("Unbonded delegations from {}", bond_id.source)
+                format!("Unbonded delegations from {}", source)
             };
             display_line!(context.io(), &mut w; "{}:", bond_type)?;
             for unbond in &details.data.unbonds {
@@ -1745,7 +2505,18 @@ pub async fn query_bonds(
         "All unbonds total withdrawable: {}",
         bonds_and_unbonds.total_withdrawable.to_string_native()
     )?;
-    Ok(())
+    context.io().print_json(&serde_json::json!({
+        "epoch": epoch.0,
+        "bonds": bonds_json,
+        "bonds_total": bonds_and_unbonds.bonds_total.to_string_native(),
+        "bonds_total_active":
+            bonds_and_unbonds.bonds_total_active().to_string_native(),
+        "unbonds_total": bonds_and_unbonds.unbonds_total.to_string_native(),
+        "unbonds_total_active":
+            bonds_and_unbonds.unbonds_total_active().to_string_native(),
+        "total_withdrawable":
+            bonds_and_unbonds.total_withdrawable.to_string_native(),
+    }))
 }
 
 /// Query PoS bonded stake
@@ -1758,12 +2529,17 @@ pub async fn query_bonded_stake<N: Namada>(
         None => query_and_print_epoch(context).await,
     };
 
+    let mut stake_json = serde_json::json!({ "epoch": epoch.0 });
     match args.validator {
         Some(validator) => {
             let validator = validator;
             // Find bonded stake for the given validator
             let stake =
                 get_validator_stake(context.client(), epoch, &validator).await;
+            stake_json["validator"] = validator.to_string().into();
+            stake_json["stake"] =
+                stake.as_ref().map(|stake| stake.to_string_native()).into();
+            let validator = context.wallet().await.format_address(&validator);
             match stake {
                 Some(stake) => {
                     // TODO: show if it's in consensus set, below capacity, or
@@ -1801,7 +2577,24 @@ pub async fn query_bonded_stake<N: Namada>(
                         .await,
                 );
 
+            let validators_json = |validators: &BTreeSet<WeightedValidator>| {
+                validators
+                    .iter()
+                    .rev()
+                    .map(|val| {
+                        serde_json::json!({
+                            "address": val.address.to_string(),
+                            "stake": val.bonded_stake.to_string_native(),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            };
+            stake_json["consensus"] = validators_json(&consensus).into();
+            stake_json["below_capacity"] =
+                validators_json(&below_capacity).into();
+
             // Iterate all validators
+            let wallet = context.wallet().await;
             let stdout = io::stdout();
             let mut w = stdout.lock();
 
@@ -1812,7 +2605,7 @@ pub async fn query_bonded_stake<N: Namada>(
                     context.io(),
                     &mut w;
                     "  {}: {}",
-                    val.address.encode(),
+                    wallet.format_address(&val.address),
                     val.bonded_stake.to_string_native()
                 )
                 .unwrap();
@@ -1825,7 +2618,7 @@ pub async fn query_bonded_stake<N: Namada>(
                         context.io(),
                         &mut w;
                         "  {}: {}",
-                        val.address.encode(),
+                        wallet.format_address(&val.address),
                         val.bonded_stake.to_string_native()
                     )
                     .unwrap();
@@ -1841,6 +2634,8 @@ pub async fn query_bonded_stake<N: Namada>(
         "Total bonded stake: {}",
         total_staked_tokens.to_string_native()
     );
+    stake_json["total"] = total_staked_tokens.to_string_native().into();
+    display_json!(context.io(), &stake_json);
 }
 
 /// Query and return validator's commission rate and max commission rate change
@@ -1902,9 +2697,17 @@ pub async fn query_and_print_validator_state(
     context: &impl Namada,
     args: args::QueryValidatorState,
 ) {
-    let validator = args.validator;
     let state: Option<ValidatorState> =
-        query_validator_state(context.client(), &validator, args.epoch).await;
+        query_validator_state(context.client(), &args.validator, args.epoch)
+            .await;
+    let validator = context.wallet().await.format_address(&args.validator);
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "validator": args.validator.to_string(),
+            "state": state.as_ref().map(|state| format!("{state:?}")),
+        })
+    );
 
     match state {
         Some(state) => match state {
@@ -1948,9 +2751,17 @@ pub async fn query_and_print_commission_rate(
     args: args::QueryCommissionRate,
 ) {
     let validator = args.validator;
+    let validator_name = context.wallet().await.format_address(&validator);
 
     let info: Option<CommissionPair> =
         query_commission_rate(context.client(), &validator, args.epoch).await;
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "validator": validator.to_string(),
+            "commission": info.as_ref().map(commission_json),
+        })
+    );
     match info {
         Some(CommissionPair {
             commission_rate: rate,
@@ -1959,7 +2770,7 @@ pub async fn query_and_print_commission_rate(
             display_line!(
                 context.io(),
                 "Validator {} commission rate: {}, max change per epoch: {}",
-                validator.encode(),
+                validator_name,
                 rate,
                 change
             );
@@ -1969,21 +2780,39 @@ pub async fn query_and_print_commission_rate(
                 context.io(),
                 "Address {} is not a validator (did not find commission rate \
                  and max change)",
-                validator.encode(),
+                validator_name,
             );
         }
     }
 }
 
+/// A commission rate printed by a query, for the JSON output
+fn commission_json(commission: &CommissionPair) -> serde_json::Value {
+    serde_json::json!({
+        "rate": commission.commission_rate.to_string(),
+        "max_change_per_epoch":
+            commission.max_commission_change_per_epoch.to_string(),
+    })
+}
+
 /// Query PoS validator's metadata
 pub async fn query_and_print_metadata(
     context: &impl Namada,
     args: args::QueryMetaData,
 ) {
     let validator = args.validator;
+    let validator_name = context.wallet().await.format_address(&validator);
 
     let metadata: Option<ValidatorMetaData> =
         query_metadata(context.client(), &validator).await;
+    let metadata_json = metadata.as_ref().map(|metadata| {
+        serde_json::json!({
+            "email": metadata.email,
+            "description": metadata.description,
+            "website": metadata.website,
+            "discord_handle": metadata.discord_handle,
+        })
+    });
 
     match metadata {
         Some(ValidatorMetaData {
@@ -1995,7 +2824,7 @@ pub async fn query_and_print_metadata(
             display_line!(
                 context.io(),
                 "Validator {} metadata:\nEmail: {}",
-                validator.encode(),
+                validator_name,
                 email
             );
             if let Some(description) = description {
@@ -2021,13 +2850,21 @@ pub async fn query_and_print_metadata(
         None => display_line!(
             context.io(),
             "Validator {} does not have an email set and may not exist",
-            validator.encode()
+            validator_name
         ),
     }
 
     // Get commission rate info for the current epoch
     let info: Option<CommissionPair> =
         query_commission_rate(context.client(), &validator, None).await;
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "validator": validator.to_string(),
+            "metadata": metadata_json,
+            "commission": info.as_ref().map(commission_json),
+        })
+    );
     match info {
         Some(CommissionPair {
             commission_rate: rate,
@@ -2036,7 +2873,7 @@ pub async fn query_and_print_metadata(
             display_line!(
                 context.io(),
                 "Validator {} commission rate: {}, max change per epoch: {}",
-                validator.encode(),
+                validator_name,
                 rate,
                 change
             );
@@ -2046,17 +2883,40 @@ pub async fn query_and_print_metadata(
                 context.io(),
                 "Address {} is not a validator (did not find commission rate \
                  and max change)",
-                validator.encode(),
+                validator_name,
             );
         }
     }
 }
 
+/// A slash printed by a query, for the JSON output
+fn slash_json(
+    validator: &Address,
+    slash: &Slash,
+    processing_epoch: Option<Epoch>,
+) -> serde_json::Value {
+    let mut slash_json = serde_json::json!({
+        "validator": validator.to_string(),
+        "epoch": slash.epoch.0,
+        "block_height": slash.block_height,
+        "type": slash.r#type.to_string(),
+        "rate": slash.rate.to_string(),
+    });
+    if let Some(epoch) = processing_epoch {
+        slash_json["processing_epoch"] = epoch.0.into();
+    }
+    slash_json
+}
+
 /// Query PoS slashes
 pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
+    // The processed and enqueued slashes for the JSON output
+    let mut processed_json = Vec::new();
+    let mut enqueued_json = Vec::new();
     match args.validator {
         Some(validator) => {
-            let validator = validator;
+            let validator_name =
+                context.wallet().await.format_address(&validator);
             // Find slashes for the given validator
             let slashes: Vec<Slash> = unwrap_client_response::<N::Client, _>(
                 RPC.vp()
@@ -2064,6 +2924,11 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
                     .validator_slashes(context.client(), &validator)
                     .await,
             );
+            processed_json.extend(
+                slashes
+                    .iter()
+                    .map(|slash| slash_json(&validator, slash, None)),
+            );
             if !slashes.is_empty() {
                 display_line!(context.io(), "Processed slashes:");
                 let stdout = io::stdout();
@@ -2085,7 +2950,7 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
                 display_line!(
                     context.io(),
                     "No processed slashes found for {}",
-                    validator.encode()
+                    validator_name
                 )
             }
             // Find enqueued slashes to be processed in the future for the given
@@ -2097,6 +2962,13 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
                 RPC.vp().pos().enqueued_slashes(context.client()).await,
             );
             let enqueued_slashes = enqueued_slashes.get(&validator).cloned();
+            for (epoch, slashes) in enqueued_slashes.iter().flatten() {
+                enqueued_json.extend(
+                    slashes.iter().map(|slash| {
+                        slash_json(&validator, slash, Some(*epoch))
+                    }),
+                );
+            }
             if let Some(enqueued) = enqueued_slashes {
                 display_line!(
                     context.io(),
@@ -2124,7 +2996,7 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
                 display_line!(
                     context.io(),
                     "No enqueued slashes found for {}",
-                    validator.encode()
+                    validator_name
                 )
             }
         }
@@ -2133,8 +3005,16 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
                 unwrap_client_response::<N::Client, _>(
                     RPC.vp().pos().slashes(context.client()).await,
                 );
+            for (validator, slashes) in &all_slashes {
+                processed_json.extend(
+                    slashes
+                        .iter()
+                        .map(|slash| slash_json(validator, slash, None)),
+                );
+            }
 
             if !all_slashes.is_empty() {
+                let wallet = context.wallet().await;
                 let stdout = io::stdout();
                 let mut w = stdout.lock();
                 display_line!(context.io(), "Processed slashes:");
@@ -2149,7 +3029,7 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
                             slash.block_height,
                             slash.rate,
                             slash.r#type,
-                            validator,
+                            wallet.format_address(&validator),
                         )
                         .unwrap();
                     }
@@ -2166,7 +3046,15 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
             > = unwrap_client_response::<N::Client, _>(
                 RPC.vp().pos().enqueued_slashes(context.client()).await,
             );
+            for (validator, slashes_by_epoch) in &enqueued_slashes {
+                for (epoch, slashes) in slashes_by_epoch {
+                    enqueued_json.extend(slashes.iter().map(|slash| {
+                        slash_json(validator, slash, Some(*epoch))
+                    }));
+                }
+            }
             if !enqueued_slashes.is_empty() {
+                let wallet = context.wallet().await;
                 display_line!(
                     context.io(),
                     "\nEnqueued slashes for future processing"
@@ -2189,7 +3077,7 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
                                 slash.epoch,
                                 slash.block_height,
                                 slash.r#type,
-                                validator
+                                wallet.format_address(&validator)
                             )
                             .unwrap();
                         }
@@ -2203,6 +3091,13 @@ pub async fn query_slashes<N: Namada>(context: &N, args: args::QuerySlashes) {
             }
         }
     }
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "processed": processed_json,
+            "enqueued": enqueued_json,
+        })
+    );
 }
 
 pub async fn query_and_print_rewards<N: Namada>(
@@ -2217,6 +3112,14 @@ pub async fn query_and_print_rewards<N: Namada>(
         "Current rewards available for claim: {} NAM",
         rewards.to_string_native()
     );
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "source": source.map(|source| source.to_string()),
+            "validator": validator.to_string(),
+            "rewards": rewards.to_string_native(),
+        })
+    );
 }
 
 pub async fn query_delegations<N: Namada>(
@@ -2232,10 +3135,37 @@ pub async fn query_delegations<N: Namada>(
     );
     if delegations.is_empty() {
         display_line!(context.io(), "No delegations found");
+        display_json!(context.io(), &serde_json::json!([]));
     } else {
+        let auto_compound: BTreeSet<Address> =
+            unwrap_client_response::<N::Client, _>(
+                RPC.vp().pos().auto_compound(context.client(), &owner).await,
+            );
+        display_json!(
+            context.io(),
+            &delegations
+                .iter()
+                .map(|delegation| {
+                    serde_json::json!({
+                        "validator": delegation.to_string(),
+                        "auto_compound": auto_compound.contains(delegation),
+                    })
+                })
+                .collect::<Vec<_>>()
+        );
+        let wallet = context.wallet().await;
         display_line!(context.io(), "Found delegations to:");
         for delegation in delegations {
-            display_line!(context.io(), "  {delegation}");
+            let auto_compound_note = if auto_compound.contains(&delegation) {
+                " (rewards auto-compounded)"
+            } else {
+                ""
+            };
+            display_line!(
+                context.io(),
+                "  {}{auto_compound_note}",
+                wallet.format_address(&delegation)
+            );
         }
     }
 }
@@ -2260,8 +3190,16 @@ pub async fn query_find_validator<N: Namada>(
             .validator_by_tm_addr(context.client(), &tm_addr)
             .await,
     );
+    display_json!(
+        context.io(),
+        &serde_json::json!({
+            "tm_address": tm_addr,
+            "validator": validator.as_ref().map(|address| address.to_string()),
+        })
+    );
     match validator {
         Some(address) => {
+            let address = context.wallet().await.format_address(&address);
             display_line!(
                 context.io(),
                 "Found validator address \"{address}\"."
@@ -2368,6 +3306,8 @@ pub async fn query_conversions(
         .expect("Conversions should be defined");
     // Track whether any non-sentinel conversions are found
     let mut conversions_found = false;
+    // The conversions for the JSON output
+    let mut conversions_json = Vec::new();
     for (addr, epoch, amt) in conversions.values() {
         // If the user has specified any targets, then meet them
         // If we have a sentinel conversion, then skip printing
@@ -2387,10 +3327,16 @@ pub async fn query_conversions(
         );
         // Now print out the components of the allowed conversion
         let mut prefix = "";
+        let mut components_json = Vec::new();
         for (asset_type, val) in amt.components() {
             // Look up the address and epoch of asset to facilitate pretty
             // printing
             let (addr, epoch, _) = &conversions[asset_type];
+            components_json.push(serde_json::json!({
+                "token": addr.to_string(),
+                "epoch": epoch.0,
+                "value": val.to_string(),
+            }));
             // Now print out this component of the conversion
             display!(
                 context.io(),
@@ -2405,6 +3351,11 @@ pub async fn query_conversions(
         }
         // Allowed conversions are always implicit equations
         display_line!(context.io(), " = 0");
+        conversions_json.push(serde_json::json!({
+            "token": addr.to_string(),
+            "epoch": epoch.0,
+            "components": components_json,
+        }));
     }
     if !conversions_found {
         display_line!(
@@ -2412,6 +3363,7 @@ pub async fn query_conversions(
             "No conversions found satisfying specified criteria."
         );
     }
+    display_json!(context.io(), &conversions_json);
 }
 
 /// Query a conversion.
@@ -2510,7 +3462,25 @@ pub async fn query_tx_response<C: namada::ledger::queries::Client + Sync>(
 /// Lookup the results of applying the specified transaction to the
 /// blockchain.
 pub async fn query_result(context: &impl Namada, args: args::QueryResult) {
-    // First try looking up application event pertaining to given hash.
+    // First try looking up the result indexed by the node, which is available
+    // for the inner hash of the applied transactions.
+    if let Ok(tx_hash) = args.tx_hash.parse::<Hash>() {
+        if let Ok(Some(event)) =
+            RPC.shell().tx_result(context.client(), &tx_hash).await
+        {
+            if let Ok(result) = TxResponse::try_from(event) {
+                display_line!(
+                    context.io(),
+                    "Transaction was applied with result: {}",
+                    serde_json::to_string_pretty(&result).unwrap()
+                );
+                display_json!(context.io(), &result);
+                return;
+            }
+        }
+    }
+
+    // Otherwise try looking up application event pertaining to given hash.
     let tx_response = query_tx_response(
         context.client(),
         namada_sdk::rpc::TxEventQuery::Applied(&args.tx_hash),
@@ -2522,7 +3492,8 @@ pub async fn query_result(context: &impl Namada, args: args::QueryResult) {
                 context.io(),
                 "Transaction was applied with result: {}",
                 serde_json::to_string_pretty(&result).unwrap()
-            )
+            );
+            display_json!(context.io(), &result);
         }
         Err(err1) => {
             // If this fails then instead look for an acceptance event.
@@ -2532,11 +3503,14 @@ pub async fn query_result(context: &impl Namada, args: args::QueryResult) {
             )
             .await;
             match tx_response {
-                Ok(result) => display_line!(
-                    context.io(),
-                    "Transaction was accepted with result: {}",
-                    serde_json::to_string_pretty(&result).unwrap()
-                ),
+                Ok(result) => {
+                    display_line!(
+                        context.io(),
+                        "Transaction was accepted with result: {}",
+                        serde_json::to_string_pretty(&result).unwrap()
+                    );
+                    display_json!(context.io(), &result);
+                }
                 Err(err2) => {
                     // Print the errors that caused the lookups to fail
                     edisplay_line!(context.io(), "{}\n{}", err1, err2);
@@ -2547,6 +3521,33 @@ pub async fn query_result(context: &impl Namada, args: args::QueryResult) {
     }
 }
 
+/// Wait until the given inner transaction is applied or the timeout elapses.
+/// Exits with an error if the transaction is not applied in time or if it was
+/// rejected.
+pub async fn wait_tx(context: &impl Namada, args: args::WaitTx) {
+    let deadline = time::Instant::now() + args.timeout;
+    let event = rpc::query_tx_status(
+        context,
+        namada_sdk::rpc::TxEventQuery::Applied(&args.tx_hash),
+        deadline,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        edisplay_line!(context.io(), "{}", err);
+        cli::safe_exit(1)
+    });
+    let result = TxResponse::from_event(event);
+    display_line!(
+        context.io(),
+        "Transaction was applied with result: {}",
+        serde_json::to_string_pretty(&result).unwrap()
+    );
+    display_json!(context.io(), &result);
+    if result.code != 0.to_string() {
+        cli::safe_exit(1)
+    }
+}
+
 pub async fn epoch_sleep(context: &impl Namada, _args: args::Query) {
     let start_epoch = query_and_print_epoch(context).await;
     loop {
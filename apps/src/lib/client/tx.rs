@@ -14,7 +14,9 @@ use namada::core::ledger::governance::cli::onchain::{
     DefaultProposal, PgfFundingProposal, PgfStewardProposal, ProposalVote,
 };
 use namada::ibc::apps::transfer::types::Memo;
-use namada::proto::{CompressedSignature, Section, Signer, Tx};
+use namada::proto::{
+    CompressedSignature, PartiallySignedTx, Section, SignDoc, Signer, Tx,
+};
 use namada::types::address::{Address, ImplicitAddress};
 use namada::types::dec::Dec;
 use namada::types::io::Io;
@@ -23,7 +25,9 @@ use namada::types::transaction::pos::{BecomeValidator, ConsensusKeyChange};
 use namada_sdk::rpc::{TxBroadcastData, TxResponse};
 use namada_sdk::wallet::alias::validator_consensus_key;
 use namada_sdk::wallet::{Wallet, WalletIo};
-use namada_sdk::{display_line, edisplay_line, error, signing, tx, Namada};
+use namada_sdk::{
+    display_line, edisplay_line, error, prompt, signing, tx, Namada,
+};
 use rand::rngs::OsRng;
 use tokio::sync::RwLock;
 
@@ -160,6 +164,10 @@ pub async fn sign<N: Namada>(
     args: &args::Tx,
     signing_data: SigningTxData,
 ) -> Result<(), error::Error> {
+    // Dry-runs are not broadcasted, so they don't need a confirmation
+    if !(args.skip_confirmation || args.dry_run || args.dry_run_wrapper) {
+        confirm_tx(context, tx).await?;
+    }
     // Setup a reusable context for signing transactions using the Ledger
     if args.use_device {
         // Setup a reusable context for signing transactions using the Ledger
@@ -194,6 +202,26 @@ pub async fn sign<N: Namada>(
     Ok(())
 }
 
+/// Display a summary of the given transaction and ask the user to confirm
+/// that it should be signed
+async fn confirm_tx(
+    context: &impl Namada,
+    tx: &Tx,
+) -> Result<(), error::Error> {
+    let summary = signing::to_tx_summary(context, tx).await?;
+    display_line!(context.io(), "Transaction summary:");
+    for line in summary {
+        display_line!(context.io(), "  {}", line);
+    }
+    let answer = prompt!(context.io(), "Sign this transaction? [y/N]: ").await;
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(error::Error::Other(
+            "The transaction was not confirmed".to_string(),
+        )),
+    }
+}
+
 // Build a transaction to reveal the signer of the given transaction.
 pub async fn submit_reveal_aux(
     context: &impl Namada,
@@ -214,9 +242,10 @@ pub async fn submit_reveal_aux(
         if tx::is_reveal_pk_needed(context.client(), address, args.force)
             .await?
         {
-            println!(
-                "Submitting a tx to reveal the public key for address \
-                 {address}..."
+            display_line!(
+                context.io(),
+                "Submitting a tx to reveal the public key for address {}...",
+                context.wallet().await.format_address(address)
             );
             let (mut tx, signing_data, _epoch) =
                 tx::build_reveal_pk(context, &args, &public_key).await?;
@@ -318,6 +347,13 @@ where
     let (mut tx, signing_data, _epoch) =
         tx::build_init_account(namada, &args).await?;
 
+    if let Some(address) = tx::init_account_address(&tx) {
+        display_line!(
+            namada.io(),
+            "The account will be initialized at address {address}."
+        );
+    }
+
     signing::generate_test_vector(namada, &tx).await?;
 
     if args.tx.dump_tx {
@@ -509,12 +545,13 @@ pub async fn submit_become_validator(
             .or_else(|| Some(config.ledger.chain_id.clone())),
         ..tx_args.clone()
     };
+    let address_name = namada.wallet().await.format_address(&address);
 
     // Check that the address is established
     if !address.is_established() {
         edisplay_line!(
             namada.io(),
-            "The given address {address} is not established. Only an \
+            "The given address {address_name} is not established. Only an \
              established address can become a validator.",
         );
         if !tx_args.force {
@@ -526,7 +563,7 @@ pub async fn submit_become_validator(
     if rpc::is_validator(namada.client(), &address).await {
         edisplay_line!(
             namada.io(),
-            "The given address {address} is already a validator",
+            "The given address {address_name} is already a validator",
         );
         if !tx_args.force {
             safe_exit(1)
@@ -539,9 +576,9 @@ pub async fn submit_become_validator(
     if rpc::has_bonds(namada.client(), &address).await {
         edisplay_line!(
             namada.io(),
-            "The given address {address} has delegations and therefore cannot \
-             become a validator. To become a validator, you have to unbond \
-             your delegations first.",
+            "The given address {address_name} has delegations and therefore \
+             cannot become a validator. To become a validator, you have to \
+             unbond your delegations first.",
         );
         if !tx_args.force {
             safe_exit(1)
@@ -744,7 +781,7 @@ pub async fn submit_become_validator(
         .unwrap_or_else(|| {
             edisplay_line!(
                 namada.io(),
-                "Unable to query account keys for address {address}."
+                "Unable to query account keys for address {address_name}."
             );
             safe_exit(1)
         });
@@ -884,6 +921,8 @@ pub async fn submit_init_validator(
             tx_code_path: tx_init_account_code_path,
             public_keys: account_keys,
             threshold,
+            salt: None,
+            vesting: None,
         },
     )
     .await?;
@@ -1233,6 +1272,7 @@ pub async fn sign_tx<N: Namada>(
         tx: tx_args,
         tx_data,
         owner,
+        print_sign_doc,
     }: args::SignTx,
 ) -> Result<(), error::Error>
 where
@@ -1244,6 +1284,14 @@ where
         edisplay_line!(namada.io(), "Couldn't decode the transaction.");
         safe_exit(1)
     };
+    if print_sign_doc {
+        let sign_doc = SignDoc::new(&tx).to_canonical_bytes();
+        display_line!(
+            namada.io(),
+            "Signing the transaction with the document: {}",
+            String::from_utf8_lossy(&sign_doc)
+        );
+    }
     let default_signer = Some(owner.clone());
     let signing_data =
         aux_signing_data(namada, &tx_args, Some(owner.clone()), default_signer)
@@ -1304,10 +1352,99 @@ where
                 output_path.display()
             );
         }
+
+        // The signatures can also be shared with the other signers of the
+        // account in a partially signed tx, to be merged with theirs
+        let mut partially_signed = PartiallySignedTx::new(tx.clone());
+        partially_signed
+            .add_signatures(signatures)
+            .expect("The signatures of the tx should be valid");
+        let filename = format!("partially_signed_{}.psnt", tx.header_hash());
+        let output_path = match &tx_args.output_folder {
+            Some(path) => path.join(filename),
+            None => filename.into(),
+        };
+        write_partially_signed_tx(&output_path, &partially_signed);
+        display_line!(
+            namada.io(),
+            "Partially signed transaction with {} signatures serialized at {}",
+            partially_signed.signatures.len(),
+            output_path.display()
+        );
     }
     Ok(())
 }
 
+/// Write a partially signed tx to a file
+pub fn write_partially_signed_tx(
+    path: &std::path::Path,
+    partially_signed: &PartiallySignedTx,
+) {
+    let file = File::create(path)
+        .expect("Should be able to create the partially signed tx file.");
+    serde_json::to_writer_pretty(file, &partially_signed.serialize())
+        .expect("Partially signed tx should be serializable.");
+}
+
+/// Merge the signatures of the copies of a partially signed tx made by
+/// different signers into one
+pub fn merge_signatures(
+    io: &impl Io,
+    args::MergeSignatures {
+        partially_signed_txs,
+        output_folder,
+    }: args::MergeSignatures,
+) {
+    let mut merged: Option<PartiallySignedTx> = None;
+    for path in partially_signed_txs {
+        let partially_signed = std::fs::read(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                PartiallySignedTx::deserialize(&bytes)
+                    .map_err(|err| err.to_string())
+            })
+            .unwrap_or_else(|err| {
+                edisplay_line!(
+                    io,
+                    "Couldn't read the partially signed transaction {}: {}",
+                    path.display(),
+                    err
+                );
+                safe_exit(1)
+            });
+        match merged.as_mut() {
+            Some(merged) => {
+                merged.merge(partially_signed).unwrap_or_else(|err| {
+                    edisplay_line!(
+                        io,
+                        "Couldn't merge the signatures of {}: {}",
+                        path.display(),
+                        err
+                    );
+                    safe_exit(1)
+                })
+            }
+            None => merged = Some(partially_signed),
+        }
+    }
+    let merged = merged.unwrap_or_else(|| {
+        edisplay_line!(io, "No partially signed transaction was given.");
+        safe_exit(1)
+    });
+    let filename = format!("merged_{}.psnt", merged.tx.header_hash());
+    let output_path = match output_folder {
+        Some(path) => path.join(filename),
+        None => filename.into(),
+    };
+    write_partially_signed_tx(&output_path, &merged);
+    display_line!(
+        io,
+        "Merged {} signatures into {}",
+        merged.signatures.len(),
+        output_path.display()
+    );
+}
+
 pub async fn submit_reveal_pk<N: Namada>(
     namada: &N,
     args: args::RevealPk,
@@ -1320,6 +1457,188 @@ where
     Ok(())
 }
 
+/// Submit the transactions of a batch one after the other and report the
+/// outcome of each of them. A failed transaction doesn't stop the batch.
+pub async fn submit_batch<N: Namada>(
+    namada: &N,
+    args: args::TxBatch,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let total = args.txs.len();
+    let mut failed = 0;
+    let mut report = Vec::with_capacity(total);
+    for (index, batch_tx) in args.txs.into_iter().enumerate() {
+        let (hash, error) = match submit_batch_tx(namada, batch_tx).await {
+            Ok(ProcessTxResponse::Applied(resp)) => {
+                if resp.code == 0.to_string() {
+                    (Some(resp.hash), None)
+                } else {
+                    (Some(resp.hash), Some(resp.reason.unwrap_or(resp.info)))
+                }
+            }
+            Ok(_) => (None, None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        match &error {
+            None => display_line!(
+                namada.io(),
+                "Batch transaction #{index} succeeded."
+            ),
+            Some(err) => {
+                failed += 1;
+                edisplay_line!(
+                    namada.io(),
+                    "Batch transaction #{index} failed: {err}"
+                )
+            }
+        }
+        report.push(serde_json::json!({
+            "index": index,
+            "success": error.is_none(),
+            "hash": hash,
+            "error": error,
+        }));
+    }
+
+    namada.io().print_json(&report).map_err(|err| {
+        error::Error::Other(format!("Failed to print the JSON output: {err}"))
+    })?;
+    if failed == 0 {
+        display_line!(
+            namada.io(),
+            "All the {total} batch transactions succeeded."
+        );
+        Ok(())
+    } else {
+        Err(error::Error::Other(format!(
+            "{failed} of the {total} batch transactions failed"
+        )))
+    }
+}
+
+/// Build, sign and submit a transaction of a batch
+async fn submit_batch_tx<N: Namada>(
+    namada: &N,
+    batch_tx: args::BatchTx,
+) -> Result<ProcessTxResponse, error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, tx_args) = match batch_tx {
+        args::BatchTx::Transfer(mut args) => {
+            submit_reveal_aux(
+                namada,
+                args.tx.clone(),
+                &args.source.effective_address(),
+            )
+            .await?;
+            let (tx, signing_data, _tx_epoch) = args.build(namada).await?;
+            (tx, signing_data, args.tx)
+        }
+        args::BatchTx::Bond(args) => {
+            let default_address =
+                args.source.clone().unwrap_or(args.validator.clone());
+            submit_reveal_aux(namada, args.tx.clone(), &default_address)
+                .await?;
+            let (tx, signing_data, _fee_unshield_epoch) =
+                args.build(namada).await?;
+            (tx, signing_data, args.tx)
+        }
+    };
+
+    if tx_args.dump_tx {
+        signing::generate_test_vector(namada, &tx).await?;
+        tx::dump_tx(namada.io(), &tx_args, tx);
+        Ok(ProcessTxResponse::Dump)
+    } else {
+        sign(namada, &mut tx, &tx_args, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &tx_args).await
+    }
+}
+
+/// Estimate the gas, the fee and the size of a transaction by dry-running its
+/// signed wrapper against the latest state. The public key of the fee payer
+/// is not revealed, so the costs of a reveal are not included.
+pub async fn estimate_tx<N: Namada>(
+    namada: &N,
+    args: args::TxEstimate,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, tx_args) = match args.tx {
+        args::BatchTx::Transfer(mut args) => {
+            let (tx, signing_data, _tx_epoch) = args.build(namada).await?;
+            (tx, signing_data, args.tx)
+        }
+        args::BatchTx::Bond(args) => {
+            let (tx, signing_data, _fee_unshield_epoch) =
+                args.build(namada).await?;
+            (tx, signing_data, args.tx)
+        }
+    };
+    // The estimated tx is never broadcasted, so it doesn't need a confirmation
+    let tx_args = args::Tx {
+        skip_confirmation: true,
+        ..tx_args
+    };
+    sign(namada, &mut tx, &tx_args, signing_data).await?;
+
+    let tx_bytes = tx.to_bytes();
+    let size = tx_bytes.len();
+    let wrapper = tx.header().wrapper().ok_or_else(|| {
+        error::Error::Other("Expected a wrapper transaction".to_string())
+    })?;
+    let result =
+        namada_sdk::rpc::query_dry_run_tx(namada.client(), tx_bytes).await?;
+    let gas_used = result.gas_used.get_whole_gas_units();
+    let fee = wrapper
+        .fee
+        .amount_per_gas_unit
+        .checked_mul(gas_used.into())
+        .ok_or_else(|| {
+            error::Error::Other("Overflow in the fee estimation".to_string())
+        })?;
+    let fee = namada_sdk::rpc::format_denominated_amount(
+        namada.client(),
+        namada.io(),
+        &wrapper.fee.token,
+        fee,
+    )
+    .await;
+
+    if !result.is_accepted() {
+        edisplay_line!(
+            namada.io(),
+            "The transaction would be rejected: {}",
+            result.vps_result.rejection_reason()
+        );
+    }
+    display_line!(namada.io(), "Estimated gas: {gas_used}");
+    display_line!(namada.io(), "Estimated fee: {fee} {}", wrapper.fee.token);
+    display_line!(namada.io(), "Transaction size: {size} bytes");
+    namada
+        .io()
+        .print_json(&serde_json::json!({
+            "accepted": result.is_accepted(),
+            "gas": gas_used,
+            "gas_limit": u64::from(wrapper.gas_limit),
+            "fee": fee,
+            "fee_token": wrapper.fee.token,
+            "size": size,
+        }))
+        .map_err(|err| {
+            error::Error::Other(format!(
+                "Failed to print the JSON output: {err}"
+            ))
+        })
+}
+
 pub async fn submit_bond<N: Namada>(
     namada: &N,
     args: args::Bond,
@@ -1397,6 +1716,139 @@ where
     Ok(())
 }
 
+/// Withdraw the matured unbonds of the bonds of the given owner, periodically
+/// or only once with `if_matured`. The failure of a withdrawal is reported
+/// and retried on the next check.
+pub async fn submit_auto_withdraw<N: Namada>(
+    namada: &N,
+    args: args::AutoWithdraw,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    loop {
+        let epoch = namada_sdk::rpc::query_epoch(namada.client()).await?;
+        let bonds = namada_sdk::rpc::bonds_and_unbonds(
+            namada.client(),
+            &Some(args.owner.clone()),
+            &None,
+        )
+        .await?;
+        let mut num_withdrawn = 0_u64;
+        for (bond_id, details) in bonds {
+            let matured = details
+                .unbonds
+                .iter()
+                .any(|unbond| unbond.withdraw <= epoch);
+            if !matured {
+                continue;
+            }
+            display_line!(
+                namada.io(),
+                "Withdrawing the matured unbonds of {} from validator {}...",
+                bond_id.source,
+                bond_id.validator
+            );
+            let withdraw = args::Withdraw {
+                tx: args.tx.clone(),
+                source: (bond_id.source != bond_id.validator)
+                    .then_some(bond_id.source),
+                validator: bond_id.validator,
+                tx_code_path: args.tx_code_path.clone(),
+            };
+            match submit_withdraw(namada, withdraw).await {
+                Ok(()) => num_withdrawn += 1,
+                Err(err) => edisplay_line!(
+                    namada.io(),
+                    "Failed to withdraw the matured unbonds: {err}"
+                ),
+            }
+        }
+        if num_withdrawn == 0 {
+            display_line!(
+                namada.io(),
+                "No matured unbonds to withdraw at epoch {epoch}."
+            );
+        }
+        if args.if_matured {
+            return Ok(());
+        }
+        tokio::time::sleep(args.interval.0).await;
+    }
+}
+
+pub async fn submit_faucet_request<N: Namada>(
+    namada: &N,
+    args: args::FaucetRequest,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, _fee_unshield_epoch) =
+        args.build(namada).await?;
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_approve<N: Namada>(
+    namada: &N,
+    args: args::TxApprove,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, _fee_unshield_epoch) =
+        args.build(namada).await?;
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_transfer_from<N: Namada>(
+    namada: &N,
+    args: args::TxTransferFrom,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, _fee_unshield_epoch) =
+        args.build(namada).await?;
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn submit_claim_rewards<N: Namada>(
     namada: &N,
     args: args::ClaimRewards,
@@ -1421,6 +1873,30 @@ where
     Ok(())
 }
 
+pub async fn submit_auto_compound<N: Namada>(
+    namada: &N,
+    args: args::AutoCompound,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, _fee_unshield_epoch) =
+        args.build(namada).await?;
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn submit_redelegate<N: Namada>(
     namada: &N,
     args: args::Redelegate,
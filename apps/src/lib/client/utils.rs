@@ -11,11 +11,10 @@ use flate2::write::GzEncoder;
 use flate2::Compression;
 use itertools::Either;
 use namada::core::types::string_encoding::StringEncoded;
-use namada::types::chain::ChainId;
+use namada::types::chain::{ChainId, ChainIdPrefix};
 use namada::types::dec::Dec;
 use namada::types::key::*;
-use namada::types::token;
-use namada::types::uint::Uint;
+use namada::types::time::DateTimeUtc;
 use namada::vm::validate_untrusted_wasm;
 use namada_sdk::wallet::{alias, Wallet};
 use prost::bytes::Bytes;
@@ -50,6 +49,8 @@ const DEFAULT_NETWORK_CONFIGS_SERVER: &str =
 
 /// We do pre-genesis validator set up in this directory
 pub const PRE_GENESIS_DIR: &str = "pre-genesis";
+/// The development chain is set up in this directory of the base directory
+pub const DEV_DIR: &str = "dev";
 
 /// Configure Namada to join an existing network. The chain must be released in
 /// the <https://github.com/heliaxdev/anoma-network-config> repository.
@@ -347,13 +348,37 @@ pub async fn fetch_wasms(
 
 pub async fn fetch_wasms_aux(base_dir: &Path, chain_id: &ChainId) {
     println!("Fetching wasms for chain ID {}...", chain_id);
+    let chain_dir = base_dir.join(chain_id.as_str());
+    // The wasm dir of the chain's config, which may not be the default one
     let wasm_dir = {
-        let mut path = base_dir.to_owned();
-        path.push(chain_id.as_str());
-        path.push("wasm");
-        path
+        let base_dir = base_dir.to_owned();
+        let chain_id = chain_id.clone();
+        tokio::task::spawn_blocking(move || {
+            Config::load(&base_dir, &chain_id, None).wasm_dir
+        })
+        .await
+        .unwrap()
     };
-    wasm_loader::pre_fetch_wasm(&wasm_dir).await;
+    let wasm_dir = chain_dir.join(wasm_dir);
+
+    let manifest =
+        wasm_loader::WasmManifest::read_or_init(&chain_dir, &wasm_dir)
+            .unwrap_or_else(|err| {
+                eprintln!("{err}");
+                safe_exit(1)
+            });
+    if let Err(err) = manifest.fetch_missing(&chain_dir).await {
+        eprintln!("{err}");
+        safe_exit(1)
+    }
+    let errors = manifest.validate(&chain_dir, &wasm_dir);
+    if !errors.is_empty() {
+        for err in errors {
+            eprintln!("{err}");
+        }
+        safe_exit(1)
+    }
+    println!("All the wasms of the chain's manifest are valid.");
 }
 
 pub fn validate_wasm(args::ValidateWasm { code_path }: args::ValidateWasm) {
@@ -407,51 +432,19 @@ pub fn init_network(
         archive_dir,
     }: args::InitNetwork,
 ) {
-    // Load and validate the templates
-    let templates = genesis::templates::load_and_validate(&templates_path)
-        .unwrap_or_else(|| {
-            eprintln!("Invalid templates, aborting.");
+    // Build the genesis config from the templates to derive the chain ID
+    let genesis = genesis::builder::GenesisBuilder::from_dir(&templates_path)
+        .and_then(|builder| {
+            builder.build(
+                chain_id_prefix,
+                genesis_time,
+                consensus_timeout_commit,
+            )
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("{err}, aborting.");
             safe_exit(1)
         });
-
-    // In addition to standard templates validation, check that there is at
-    // least one validator account.
-    if !templates.transactions.has_at_least_one_validator() {
-        eprintln!("No validator genesis transaction found, aborting.");
-        safe_exit(1)
-    }
-
-    // Also check that at least one validator account has positive voting power.
-    let tm_votes_per_token = templates.parameters.pos_params.tm_votes_per_token;
-    if !templates
-        .transactions
-        .has_validator_with_positive_voting_power(tm_votes_per_token)
-    {
-        let min_stake = token::Amount::from_uint(
-            if tm_votes_per_token > Dec::from(1) {
-                Uint::one()
-            } else {
-                (Dec::from(1) / tm_votes_per_token).ceil().abs()
-            },
-            token::NATIVE_MAX_DECIMAL_PLACES,
-        )
-        .unwrap();
-        eprintln!(
-            "No validator with positive voting power, aborting. The minimum \
-             staked tokens amount required to run the network is {}, because \
-             there are {tm_votes_per_token} votes per NAMNAM tokens.",
-            min_stake.to_string_native(),
-        );
-        safe_exit(1)
-    }
-
-    // Finalize the genesis config to derive the chain ID
-    let genesis = genesis::chain::finalize(
-        templates,
-        chain_id_prefix,
-        genesis_time,
-        consensus_timeout_commit,
-    );
     let chain_id = &genesis.metadata.chain_id;
     let chain_dir = global_args.base_dir.join(chain_id.as_str());
 
@@ -582,6 +575,121 @@ pub fn init_network(
     }
 }
 
+/// Set up a single validator development chain from the given genesis
+/// templates in the `dev` directory of the base directory, or reuse the chain
+/// set up before unless `reset` is set. The chain's validator is the
+/// pre-genesis `validator-0` of the templates and the test accounts of their
+/// pre-genesis wallet are added to the chain's wallet. Returns the base
+/// directory and the ID of the chain.
+pub async fn init_dev_network(
+    global_args: args::Global,
+    args::Dev {
+        reset,
+        templates_path,
+        epoch_duration,
+        consensus_timeout_commit,
+    }: args::Dev,
+) -> (PathBuf, ChainId) {
+    const SECONDS_PER_YEAR: u64 = 60 * 60 * 24 * 365;
+    const DEV_CHAIN_ID_PREFIX: &str = "dev";
+    const DEV_VALIDATOR: &str = "validator-0";
+
+    let dev_dir = global_args.base_dir.join(DEV_DIR);
+    if reset && dev_dir.exists() {
+        println!("Deleting the development chain in {}", dev_dir.display());
+        fs::remove_dir_all(&dev_dir).unwrap();
+    }
+    let existing_chain_id = GlobalConfig::read(&dev_dir)
+        .ok()
+        .and_then(|config| config.default_chain_id)
+        .filter(|chain_id| dev_dir.join(chain_id.as_str()).is_dir());
+    if let Some(chain_id) = existing_chain_id {
+        println!("Using the development chain {chain_id}");
+        return (dev_dir, chain_id);
+    }
+
+    // Shorten the epochs of the templates
+    let builder = genesis::builder::GenesisBuilder::from_dir(&templates_path)
+        .unwrap_or_else(|err| {
+            eprintln!("{err}, aborting.");
+            safe_exit(1)
+        });
+    let mut chain_params = builder.templates().parameters.parameters.clone();
+    chain_params.min_num_of_blocks = 1;
+    chain_params.min_epoch_duration = Some(epoch_duration.max(1) as i64);
+    chain_params.epochs_per_year = SECONDS_PER_YEAR / epoch_duration.max(1);
+    let templates = builder.chain_params(chain_params).templates().clone();
+
+    // Initialize the network in a temporary base dir and join it with the
+    // pre-genesis validator, in the same way as a local network is set up
+    let init_dir = dev_dir.join("init");
+    let init_templates_path = init_dir.join("templates");
+    fs::create_dir_all(&init_templates_path).unwrap();
+    templates
+        .write_toml_files(&init_templates_path)
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to write the genesis templates: {err}");
+            safe_exit(1)
+        });
+    let wasm_dir = global_args
+        .wasm_dir
+        .clone()
+        .unwrap_or_else(|| config::DEFAULT_WASM_DIR.into());
+    init_network(
+        args::Global {
+            base_dir: init_dir.clone(),
+            ..global_args.clone()
+        },
+        args::InitNetwork {
+            templates_path: init_templates_path,
+            wasm_checksums_path: wasm_dir
+                .join(config::DEFAULT_WASM_CHECKSUMS_FILE),
+            chain_id_prefix: ChainIdPrefix::from_str(DEV_CHAIN_ID_PREFIX)
+                .unwrap(),
+            genesis_time: DateTimeUtc::now(),
+            consensus_timeout_commit,
+            dont_archive: false,
+            archive_dir: Some(init_dir.clone()),
+        },
+    );
+    let chain_id = GlobalConfig::read(&init_dir)
+        .ok()
+        .and_then(|config| config.default_chain_id)
+        .expect("The initialized network must have a chain ID");
+    env::set_var(ENV_VAR_NETWORK_CONFIGS_DIR, &init_dir);
+    let pre_genesis_dir = templates_path.join("src").join(PRE_GENESIS_DIR);
+    join_network(
+        args::Global {
+            base_dir: dev_dir.clone(),
+            wasm_dir: None,
+            ..global_args
+        },
+        args::JoinNetwork {
+            chain_id: chain_id.clone(),
+            genesis_validator: None,
+            pre_genesis_path: Some(pre_genesis_dir.join(DEV_VALIDATOR)),
+            dont_prefetch_wasm: true,
+            allow_duplicate_ip: true,
+        },
+    )
+    .await;
+    env::remove_var(ENV_VAR_NETWORK_CONFIGS_DIR);
+    fs::remove_dir_all(&init_dir).unwrap();
+
+    // Add the pre-funded test accounts to the chain's wallet
+    let chain_dir = dev_dir.join(chain_id.as_str());
+    if let Some(test_accounts) = crate::wallet::load(&pre_genesis_dir) {
+        let mut wallet = crate::wallet::load_or_new(&chain_dir);
+        wallet.extend(test_accounts);
+        crate::wallet::save(&wallet).unwrap();
+    }
+    println!(
+        "Use `--base-dir {}` to interact with the development chain.",
+        dev_dir.display()
+    );
+    (dev_dir, chain_id)
+}
+
 pub fn pk_to_tm_address(
     _global_args: args::Global,
     args::PkToTmAddress { public_key }: args::PkToTmAddress,
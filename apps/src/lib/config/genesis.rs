@@ -1,5 +1,6 @@
 //! The parameters used for the chain's genesis
 
+pub mod builder;
 pub mod chain;
 pub mod templates;
 pub mod transactions;
@@ -271,6 +272,12 @@ pub struct Parameters {
     pub max_proposal_bytes: ProposalBytes,
     /// Max block gas
     pub max_block_gas: u64,
+    /// Max share of the proposal space that encrypted txs can take up
+    pub max_encrypted_txs_share: Dec,
+    /// Max share of the proposal space that decrypted txs can take up
+    pub max_decrypted_txs_share: Dec,
+    /// Min share of the proposal space reserved for protocol txs
+    pub min_protocol_txs_share: Dec,
     /// Epoch duration
     pub epoch_duration: EpochDuration,
     /// Maximum expected time per block
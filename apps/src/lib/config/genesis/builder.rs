@@ -0,0 +1,240 @@
+//! A library to build the genesis of a network from the typed templates.
+//!
+//! The templates can be read from a directory and amended with the builder's
+//! methods. [`GenesisBuilder::build`] validates the templates and their
+//! cross-references and finalizes them into a chain genesis config. For the
+//! same templates and metadata, the finalized config, and hence the chain ID
+//! derived from it, is always the same.
+
+use std::path::Path;
+
+use namada::types::chain::ChainIdPrefix;
+use namada::types::dec::Dec;
+use namada::types::time::DateTimeUtc;
+use namada::types::token::{self, DenominatedAmount};
+use namada::types::uint::Uint;
+use thiserror::Error;
+
+use super::chain::{self, Finalized};
+use super::templates::{
+    self, All, ChainParams, GovernanceParams, PosParams, RawTokenBalances,
+    TokenConfig, Unvalidated, Validated, WasmVpConfig,
+};
+use super::transactions::Transactions;
+use super::GenesisAddress;
+use crate::wallet::Alias;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum BuildError {
+    #[error("Failed to read the genesis templates: {0}")]
+    Read(String),
+    #[error("The genesis templates are invalid")]
+    Invalid,
+    #[error("No validator genesis transaction found")]
+    NoValidator,
+    #[error(
+        "No validator with positive voting power. The minimum staked tokens \
+         amount required to run the network is {min_stake}, because there \
+         are {votes_per_token} votes per NAMNAM tokens"
+    )]
+    NoVotingPower {
+        min_stake: String,
+        votes_per_token: Dec,
+    },
+}
+
+/// Builds the genesis config of a network from its templates
+#[derive(Clone, Debug)]
+pub struct GenesisBuilder {
+    templates: All<Unvalidated>,
+}
+
+impl GenesisBuilder {
+    /// Start from the given templates. Their transactions are sorted and
+    /// deduplicated.
+    pub fn new(mut templates: All<Unvalidated>) -> Self {
+        let txs = std::mem::take(&mut templates.transactions);
+        templates.transactions.merge(txs);
+        Self { templates }
+    }
+
+    /// Start from the templates in the given directory
+    pub fn from_dir(templates_dir: &Path) -> Result<Self, BuildError> {
+        All::read_toml_files(templates_dir)
+            .map(Self::new)
+            .map_err(|err| BuildError::Read(format!("{err:?}")))
+    }
+
+    /// The templates built so far
+    pub fn templates(&self) -> &All<Unvalidated> {
+        &self.templates
+    }
+
+    /// Add a wasm validity predicate, replacing any with the same name
+    pub fn vp(mut self, name: impl Into<String>, config: WasmVpConfig) -> Self {
+        self.templates.vps.wasm.insert(name.into(), config);
+        self
+    }
+
+    /// Add a token, replacing any with the same alias
+    pub fn token(mut self, alias: Alias, config: TokenConfig) -> Self {
+        self.templates.tokens.token.insert(alias, config);
+        self
+    }
+
+    /// Set the balance of a token of an owner
+    pub fn balance(
+        mut self,
+        token: Alias,
+        owner: GenesisAddress,
+        amount: DenominatedAmount,
+    ) -> Self {
+        self.templates
+            .balances
+            .token
+            .entry(token)
+            .or_insert_with(|| RawTokenBalances(Default::default()))
+            .0
+            .insert(owner, amount);
+        self
+    }
+
+    /// Set the chain parameters
+    pub fn chain_params(mut self, params: ChainParams<Unvalidated>) -> Self {
+        self.templates.parameters.parameters = params;
+        self
+    }
+
+    /// Set the PoS parameters
+    pub fn pos_params(mut self, params: PosParams) -> Self {
+        self.templates.parameters.pos_params = params;
+        self
+    }
+
+    /// Set the governance parameters
+    pub fn gov_params(mut self, params: GovernanceParams) -> Self {
+        self.templates.parameters.gov_params = params;
+        self
+    }
+
+    /// Add signed genesis transactions. The transactions are kept sorted and
+    /// deduplicated, so their order doesn't affect the chain ID.
+    pub fn transactions(mut self, txs: Transactions<Unvalidated>) -> Self {
+        self.templates.transactions.merge(txs);
+        self
+    }
+
+    /// Validate the templates and check that the network has at least one
+    /// validator with positive voting power. The validation issues of the
+    /// templates are printed to stderr.
+    pub fn validate(self) -> Result<All<Validated>, BuildError> {
+        let templates =
+            templates::validate(self.templates).ok_or(BuildError::Invalid)?;
+        if !templates.transactions.has_at_least_one_validator() {
+            return Err(BuildError::NoValidator);
+        }
+        let votes_per_token =
+            templates.parameters.pos_params.tm_votes_per_token;
+        if !templates
+            .transactions
+            .has_validator_with_positive_voting_power(votes_per_token)
+        {
+            let min_stake = token::Amount::from_uint(
+                if votes_per_token > Dec::from(1) {
+                    Uint::one()
+                } else {
+                    (Dec::from(1) / votes_per_token).ceil().abs()
+                },
+                token::NATIVE_MAX_DECIMAL_PLACES,
+            )
+            .unwrap();
+            return Err(BuildError::NoVotingPower {
+                min_stake: min_stake.to_string_native(),
+                votes_per_token,
+            });
+        }
+        Ok(templates)
+    }
+
+    /// Validate the templates and finalize them into the genesis config of
+    /// the network
+    pub fn build(
+        self,
+        chain_id_prefix: ChainIdPrefix,
+        genesis_time: DateTimeUtc,
+        consensus_timeout_commit: crate::facade::tendermint::Timeout,
+    ) -> Result<Finalized, BuildError> {
+        let templates = self.validate()?;
+        Ok(chain::finalize(
+            templates,
+            chain_id_prefix,
+            genesis_time,
+            consensus_timeout_commit,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn localnet_builder() -> GenesisBuilder {
+        let templates_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join("genesis/localnet");
+        GenesisBuilder::from_dir(&templates_dir).expect("Test failed")
+    }
+
+    /// Test that the localnet templates build into the same genesis config
+    /// regardless of duplicate transactions, and that the cross-references of
+    /// the amended templates are validated
+    #[test]
+    fn test_build_localnet_genesis() {
+        let chain_id_prefix = ChainIdPrefix::from_str("test").unwrap();
+        let genesis_time =
+            DateTimeUtc::from_str("2021-12-31T00:00:00Z").unwrap();
+        let timeout = crate::facade::tendermint::Timeout::from_str("1s")
+            .expect("Test failed");
+
+        let build = |builder: GenesisBuilder| {
+            builder
+                .build(chain_id_prefix.clone(), genesis_time, timeout)
+                .expect("Test failed")
+        };
+        // Adding the same transactions again doesn't change the genesis
+        let txs = localnet_builder().templates().transactions.clone();
+        assert_eq!(
+            build(localnet_builder()).metadata.chain_id,
+            build(localnet_builder().transactions(txs))
+                .metadata
+                .chain_id
+        );
+
+        // A balance of an unknown token is rejected
+        let owner = localnet_builder()
+            .templates()
+            .balances
+            .token
+            .values()
+            .next()
+            .and_then(|balances| balances.0.keys().next().cloned())
+            .unwrap();
+        let builder = localnet_builder().balance(
+            Alias::from("UnknownToken"),
+            owner,
+            DenominatedAmount::native(token::Amount::from(1)),
+        );
+        assert!(matches!(builder.validate(), Err(BuildError::Invalid)));
+
+        // A network without validators is rejected
+        let mut builder = localnet_builder();
+        builder.templates.transactions.validator_account = None;
+        builder.templates.transactions.bond = None;
+        assert!(builder.validate().is_err());
+    }
+}
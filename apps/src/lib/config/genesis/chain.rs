@@ -267,14 +267,21 @@ impl Finalized {
             tx_whitelist,
             implicit_vp,
             epochs_per_year,
+            min_epoch_duration,
             pos_gain_p,
             pos_gain_d,
             max_signatures_per_transaction,
             fee_unshielding_gas_limit,
             fee_unshielding_descriptions_limit,
             max_block_gas,
+            max_encrypted_txs_share,
+            max_decrypted_txs_share,
+            min_protocol_txs_share,
             minimum_gas_price,
             max_tx_bytes,
+            wasm_limits,
+            storage_deposit_per_byte,
+            blocked_addresses,
             ..
         } = self.parameters.parameters.clone();
 
@@ -289,7 +296,8 @@ impl Finalized {
                 .expect("Implicit VP WASM code couldn't get read");
         let implicit_vp_code_hash = Hash::sha256(implicit_vp);
 
-        let min_duration: i64 = 60 * 60 * 24 * 365 / (epochs_per_year as i64);
+        let min_duration: i64 = min_epoch_duration
+            .unwrap_or(60 * 60 * 24 * 365 / (epochs_per_year as i64));
         let epoch_duration = EpochDuration {
             min_num_of_blocks,
             min_duration: namada::types::time::Duration::seconds(min_duration)
@@ -320,6 +328,9 @@ impl Finalized {
             fee_unshielding_gas_limit,
             fee_unshielding_descriptions_limit,
             max_block_gas,
+            max_encrypted_txs_share,
+            max_decrypted_txs_share,
+            min_protocol_txs_share,
             minimum_gas_price: minimum_gas_price
                 .iter()
                 .map(|(token, amt)| {
@@ -329,6 +340,11 @@ impl Finalized {
                     )
                 })
                 .collect(),
+            wasm_limits,
+            storage_deposit_per_byte: storage_deposit_per_byte
+                .map(|amount| amount.amount)
+                .unwrap_or_default(),
+            blocked_addresses,
         }
     }
 
@@ -350,6 +366,8 @@ impl Finalized {
             validator_stake_threshold,
             liveness_window_check,
             liveness_threshold,
+            validator_min_self_bond,
+            max_stake_to_self_bond_ratio,
         } = self.parameters.pos_params.clone();
 
         namada::proof_of_stake::parameters::PosParams {
@@ -368,6 +386,8 @@ impl Finalized {
                 validator_stake_threshold,
                 liveness_window_check,
                 liveness_threshold,
+                validator_min_self_bond,
+                max_stake_to_self_bond_ratio,
             },
             max_proposal_period: self.parameters.gov_params.max_proposal_period,
         }
@@ -384,6 +404,8 @@ impl Finalized {
             max_proposal_period,
             max_proposal_content_size,
             min_proposal_grace_epochs,
+            votes_retention_epochs,
+            max_epoch_hooks_gas,
         } = self.parameters.gov_params.clone();
         namada::core::ledger::governance::parameters::GovernanceParameters {
             min_proposal_fund: Amount::native_whole(min_proposal_fund),
@@ -392,6 +414,8 @@ impl Finalized {
             max_proposal_content_size,
             min_proposal_grace_epochs,
             min_proposal_voting_period,
+            votes_retention_epochs,
+            max_epoch_hooks_gas,
         }
     }
 
@@ -629,6 +653,8 @@ pub struct FinalizedTransactions {
     pub established_account: Option<Vec<FinalizedEstablishedAccountTx>>,
     pub validator_account: Option<Vec<FinalizedValidatorAccountTx>>,
     pub bond: Option<Vec<transactions::BondTx<Validated>>>,
+    pub vesting: Option<Vec<transactions::VestingTx>>,
+    pub faucet: Option<Vec<transactions::FaucetTx>>,
 }
 
 impl FinalizedTransactions {
@@ -639,6 +665,8 @@ impl FinalizedTransactions {
             established_account,
             validator_account,
             bond,
+            vesting,
+            faucet,
         } = transactions;
         let established_account = established_account.map(|txs| {
             txs.into_iter()
@@ -657,6 +685,8 @@ impl FinalizedTransactions {
             established_account,
             validator_account,
             bond,
+            vesting,
+            faucet,
         }
     }
 
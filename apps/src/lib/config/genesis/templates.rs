@@ -9,6 +9,7 @@ use namada::core::types::{ethereum_structs, token};
 use namada::eth_bridge::parameters::{
     Contracts, Erc20WhitelistEntry, MinimumConfirmations,
 };
+use namada::ledger::parameters::WasmLimits;
 use namada::types::address::Address;
 use namada::types::chain::ProposalBytes;
 use namada::types::dec::Dec;
@@ -209,6 +210,9 @@ pub struct Tokens {
 )]
 pub struct TokenConfig {
     pub denom: Denomination,
+    /// Optional symbol and display name used by clients to render amounts
+    #[serde(default)]
+    pub metadata: Option<token::Metadata>,
     pub parameters: token::Parameters,
 }
 
@@ -276,6 +280,11 @@ pub struct ChainParams<T: TemplateValidation> {
     pub implicit_vp: String,
     /// Expected number of epochs per year
     pub epochs_per_year: u64,
+    /// Minimum duration of an epoch (in seconds). An epoch ends once both
+    /// this duration and `min_num_of_blocks` have passed. Defaults to a year
+    /// divided by `epochs_per_year`.
+    #[serde(default)]
+    pub min_epoch_duration: Option<i64>,
     /// PoS gain p
     pub pos_gain_p: Dec,
     /// PoS gain d
@@ -284,12 +293,74 @@ pub struct ChainParams<T: TemplateValidation> {
     pub max_signatures_per_transaction: u8,
     /// Max gas for block
     pub max_block_gas: u64,
+    /// Max share of the proposal space that encrypted txs can take up.
+    /// Decrypted txs take up at most the same share in the following block.
+    pub max_encrypted_txs_share: Dec,
+    /// Max share of the proposal space that decrypted txs can take up. Must
+    /// not be lower than the max encrypted txs share, so that the txs
+    /// decrypted from a block always fit in the next one. Defaults to the
+    /// whole proposal space.
+    #[serde(default = "Dec::one")]
+    pub max_decrypted_txs_share: Dec,
+    /// Min share of the proposal space reserved for protocol txs, which also
+    /// get any space left by the other txs. Defaults to zero.
+    #[serde(default)]
+    pub min_protocol_txs_share: Dec,
     /// Fee unshielding gas limit
     pub fee_unshielding_gas_limit: u64,
     /// Fee unshielding descriptions limit
     pub fee_unshielding_descriptions_limit: u64,
     /// Map of the cost per gas unit for every token allowed for fee payment
     pub minimum_gas_price: T::GasMinimums,
+    /// Limits enforced on every tx and VP WASM execution
+    #[serde(default)]
+    pub wasm_limits: WasmLimits,
+    /// Native token deposit locked per byte of state added to an account.
+    /// `None` value or zero disables the storage deposits.
+    #[serde(default)]
+    pub storage_deposit_per_byte: Option<DenominatedAmount>,
+    /// The addresses that can neither send nor receive tokens, maintained by
+    /// governance. `None` value disables the blocking of the addresses.
+    #[serde(default)]
+    pub blocked_addresses: Option<BTreeSet<Address>>,
+}
+
+/// Check that the shares of the block space of the txs can all be honored: a
+/// proposal full of encrypted txs must leave room for the reserved protocol
+/// txs and for the txs decrypted from the previous block.
+fn validate_block_space_shares(
+    max_encrypted_txs_share: Dec,
+    max_decrypted_txs_share: Dec,
+    min_protocol_txs_share: Dec,
+) -> Result<(), String> {
+    let shares = [
+        ("max encrypted txs", max_encrypted_txs_share),
+        ("max decrypted txs", max_decrypted_txs_share),
+        ("min protocol txs", min_protocol_txs_share),
+    ];
+    for (name, share) in shares {
+        if share.is_negative() || share > Dec::one() {
+            return Err(format!(
+                "The {name} share of the block space must be between zero \
+                 and one, found {share}"
+            ));
+        }
+    }
+    if max_decrypted_txs_share < max_encrypted_txs_share {
+        return Err(format!(
+            "The max decrypted txs share of the block space must not be lower \
+             than the max encrypted txs share, found \
+             {max_decrypted_txs_share} < {max_encrypted_txs_share}"
+        ));
+    }
+    if max_encrypted_txs_share * 2_u64 + min_protocol_txs_share > Dec::one() {
+        return Err(format!(
+            "Twice the max encrypted txs share of the block space plus the min \
+             protocol txs share must not be greater than one, found \
+             {max_encrypted_txs_share} and {min_protocol_txs_share}"
+        ));
+    }
+    Ok(())
 }
 
 impl ChainParams<Unvalidated> {
@@ -307,14 +378,33 @@ impl ChainParams<Unvalidated> {
             tx_whitelist,
             implicit_vp,
             epochs_per_year,
+            min_epoch_duration,
             pos_gain_p,
             pos_gain_d,
             max_signatures_per_transaction,
             max_block_gas,
+            max_encrypted_txs_share,
+            max_decrypted_txs_share,
+            min_protocol_txs_share,
             fee_unshielding_gas_limit,
             fee_unshielding_descriptions_limit,
             minimum_gas_price,
+            wasm_limits,
+            storage_deposit_per_byte,
+            blocked_addresses,
         } = self;
+        if matches!(min_epoch_duration, Some(duration) if duration <= 0) {
+            eprintln!("The min epoch duration must be positive");
+            return Err(eyre::eyre!("The min epoch duration must be positive"));
+        }
+        if let Err(err) = validate_block_space_shares(
+            max_encrypted_txs_share,
+            max_decrypted_txs_share,
+            min_protocol_txs_share,
+        ) {
+            eprintln!("{err}");
+            return Err(eyre::eyre!(err));
+        }
         let mut min_gas_prices = BTreeMap::default();
         for (token, amount) in minimum_gas_price.into_iter() {
             let denom = if let Some(TokenConfig { denom, .. }) =
@@ -343,6 +433,33 @@ impl ChainParams<Unvalidated> {
             })?;
             min_gas_prices.insert(token, amount);
         }
+        let storage_deposit_per_byte = match storage_deposit_per_byte {
+            Some(amount) => {
+                let denom = tokens
+                    .token
+                    .get(&native_token)
+                    .map(|TokenConfig { denom, .. }| *denom)
+                    .ok_or_else(|| {
+                        eprintln!(
+                            "The native token {native_token} is not in the \
+                             `tokens.toml` file"
+                        );
+                        eyre::eyre!(
+                            "The native token {native_token} is not in the \
+                             `tokens.toml` file"
+                        )
+                    })?;
+                Some(amount.increase_precision(denom).map_err(|e| {
+                    eprintln!(
+                        "The storage deposit per byte in the parameters.toml \
+                         file was incorrectly formatted:\n{}",
+                        e
+                    );
+                    e
+                })?)
+            }
+            None => None,
+        };
 
         Ok(ChainParams {
             max_tx_bytes,
@@ -354,13 +471,20 @@ impl ChainParams<Unvalidated> {
             tx_whitelist,
             implicit_vp,
             epochs_per_year,
+            min_epoch_duration,
             pos_gain_p,
             pos_gain_d,
             max_signatures_per_transaction,
             max_block_gas,
+            max_encrypted_txs_share,
+            max_decrypted_txs_share,
+            min_protocol_txs_share,
             fee_unshielding_gas_limit,
             fee_unshielding_descriptions_limit,
             minimum_gas_price: min_gas_prices,
+            wasm_limits,
+            storage_deposit_per_byte,
+            blocked_addresses,
         })
     }
 }
@@ -410,6 +534,13 @@ pub struct PosParams {
     /// The minimum required activity of consensus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The minimum amount of tokens that a validator must self-bond to
+    /// receive delegations
+    pub validator_min_self_bond: token::Amount,
+    /// The maximum ratio of a validator's total stake to its self-bond, if
+    /// any
+    #[serde(default)]
+    pub max_stake_to_self_bond_ratio: Option<Dec>,
 }
 
 #[derive(
@@ -435,6 +566,12 @@ pub struct GovernanceParams {
     pub max_proposal_content_size: u64,
     /// Minimum number of epoch between end and grace epoch
     pub min_proposal_grace_epochs: u64,
+    /// Number of epochs after the grace epoch of a proposal during which its
+    /// votes are kept in storage
+    pub votes_retention_epochs: u64,
+    /// Maximum gas, in whole units, that all the epoch hooks can use at an
+    /// epoch boundary
+    pub max_epoch_hooks_gas: u64,
 }
 
 #[derive(
@@ -788,6 +925,58 @@ pub fn load_and_validate(templates_dir: &Path) -> Option<All<Validated>> {
     }
 }
 
+/// Validate genesis templates that have all been loaded. Returns `None` when
+/// there are some validation issues, which are printed to stderr.
+///
+/// Like [`load_and_validate`], this doesn't enforce that there is at least
+/// one validator with positive voting power.
+pub fn validate(templates: All<Unvalidated>) -> Option<All<Validated>> {
+    let All {
+        vps,
+        tokens,
+        balances,
+        parameters,
+        transactions,
+    } = templates;
+    let mut is_valid = validate_vps(&vps);
+    if tokens.token.is_empty() {
+        is_valid = false;
+        eprintln!("Tokens are invalid. There has to be at least one token.");
+    }
+    let tokens = Some(tokens);
+    let transactions = Some(transactions);
+    let parameters =
+        validate_parameters(parameters, &tokens, &transactions, Some(&vps));
+    let tokens = tokens?;
+    let balances = match balances.denominate(&tokens) {
+        Ok(balances) => {
+            validate_balances(&balances, Some(&tokens)).then_some(balances)
+        }
+        Err(err) => {
+            eprintln!("Balances are invalid: {err}");
+            None
+        }
+    };
+    let transactions = transactions::validate(
+        transactions?,
+        Some(&vps),
+        balances.as_ref(),
+        parameters.as_ref(),
+    );
+    match (balances, parameters, transactions) {
+        (Some(balances), Some(parameters), Some(transactions)) if is_valid => {
+            Some(All {
+                vps,
+                tokens,
+                balances,
+                parameters,
+                transactions,
+            })
+        }
+        _ => None,
+    }
+}
+
 pub fn validate_vps(vps: &ValidityPredicates) -> bool {
     let mut is_valid = true;
     vps.wasm.iter().for_each(|(name, config)| {
@@ -937,6 +1126,30 @@ mod tests {
 
     use super::*;
 
+    /// Check the constraints on the shares of the block space of the txs.
+    #[test]
+    fn test_validate_block_space_shares() {
+        let third = Dec::one() / 3;
+        assert!(validate_block_space_shares(third, third, third).is_ok());
+        assert!(
+            validate_block_space_shares(third, Dec::one(), Dec::zero()).is_ok()
+        );
+        // decrypted txs must fit the txs encrypted in the previous block
+        assert!(
+            validate_block_space_shares(third, third / 2, Dec::zero()).is_err()
+        );
+        // protocol txs must not be starved by a full block of encrypted txs
+        assert!(
+            validate_block_space_shares(third, third, Dec::one() / 2).is_err()
+        );
+        assert!(validate_block_space_shares(
+            Dec::two(),
+            Dec::two(),
+            Dec::zero()
+        )
+        .is_err());
+    }
+
     /// Validate the `genesis/localnet` genesis templates.
     #[test]
     fn test_validate_localnet_genesis_templates() {
@@ -10,12 +10,13 @@ use itertools::{Either, Itertools};
 use ledger_namada_rs::NamadaApp;
 use ledger_transport_hid::hidapi::HidApi;
 use ledger_transport_hid::TransportNativeHID;
-use namada::core::types::account::AccountPublicKeysMap;
+use namada::core::types::account::{AccountPublicKeysMap, VestingSchedule};
 use namada::core::types::address::{Address, EstablishedAddress};
 use namada::core::types::chain::ChainId;
 use namada::core::types::string_encoding::StringEncoded;
 use namada::ledger::pos::common::PublicKey;
 use namada::ledger::pos::types::ValidatorMetaData;
+use namada::ledger::testnet_pow;
 use namada::proto::{
     verify_standalone_sig, Code, Commitment, Data, Section, SerializeWithBorsh,
     SignatureIndex, Tx,
@@ -23,6 +24,7 @@ use namada::proto::{
 use namada::types::address::nam;
 use namada::types::dec::Dec;
 use namada::types::key::{common, ed25519, RefTo, SigScheme};
+use namada::types::storage::Epoch;
 use namada::types::time::DateTimeUtc;
 use namada::types::token;
 use namada::types::token::{DenominatedAmount, NATIVE_MAX_DECIMAL_PLACES};
@@ -68,6 +70,7 @@ fn get_tx_args(use_device: bool) -> TxArgs {
         dry_run: false,
         dry_run_wrapper: false,
         dump_tx: false,
+        dump_broadcast_tx: false,
         output_folder: None,
         force: false,
         broadcast_only: false,
@@ -76,6 +79,7 @@ fn get_tx_args(use_device: bool) -> TxArgs {
         wallet_alias_force: false,
         fee_amount: None,
         wrapper_fee_payer: None,
+        sequence: None,
         fee_token: nam(),
         fee_unshield: None,
         gas_limit: Default::default(),
@@ -87,6 +91,7 @@ fn get_tx_args(use_device: bool) -> TxArgs {
         tx_reveal_code_path: Default::default(),
         password: None,
         use_device,
+        skip_confirmation: false,
     }
 }
 
@@ -211,6 +216,8 @@ pub async fn sign_txs(
         established_account,
         validator_account,
         bond,
+        vesting: None,
+        faucet: None,
     }
 }
 
@@ -450,6 +457,8 @@ pub struct Transactions<T: TemplateValidation> {
     pub established_account: Option<Vec<EstablishedAccountTx>>,
     pub validator_account: Option<Vec<SignedValidatorAccountTx>>,
     pub bond: Option<Vec<T::BondTx>>,
+    pub vesting: Option<Vec<VestingTx>>,
+    pub faucet: Option<Vec<FaucetTx>>,
 }
 
 impl<T: TemplateValidation> Transactions<T> {
@@ -488,6 +497,28 @@ impl<T: TemplateValidation> Transactions<T> {
             })
             .or(other.bond)
             .map(|txs| txs.into_iter().sorted().dedup().collect());
+        self.vesting = self
+            .vesting
+            .take()
+            .map(|mut txs| {
+                if let Some(new_txs) = other.vesting.as_mut() {
+                    txs.append(new_txs);
+                }
+                txs
+            })
+            .or(other.vesting)
+            .map(|txs| txs.into_iter().sorted().dedup().collect());
+        self.faucet = self
+            .faucet
+            .take()
+            .map(|mut txs| {
+                if let Some(new_txs) = other.faucet.as_mut() {
+                    txs.append(new_txs);
+                }
+                txs
+            })
+            .or(other.faucet)
+            .map(|txs| txs.into_iter().sorted().dedup().collect());
     }
 }
 
@@ -497,6 +528,8 @@ impl<T: TemplateValidation> Default for Transactions<T> {
             established_account: None,
             validator_account: None,
             bond: None,
+            vesting: None,
+            faucet: None,
         }
     }
 }
@@ -961,6 +994,93 @@ impl<T: TemplateValidation> From<BondTx<T>> for SignedBondTx<T> {
     }
 }
 
+/// A vesting schedule of the native tokens of an established account
+#[derive(
+    Clone,
+    Debug,
+    Deserialize,
+    Serialize,
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub struct VestingTx {
+    /// The established account whose native tokens are locked. It should
+    /// use the vesting VP for the schedule to be enforced.
+    pub owner: Address,
+    /// The amount of native tokens locked at the start of the schedule
+    pub total: DenominatedAmount,
+    /// The epoch from which the tokens are released
+    pub start_epoch: Epoch,
+    /// The first epoch in which the released tokens can be spent
+    pub cliff_epoch: Epoch,
+    /// The epoch from which all the tokens are released
+    pub end_epoch: Epoch,
+}
+
+impl VestingTx {
+    /// The vesting schedule to write under the owner's account
+    pub fn schedule(&self) -> VestingSchedule {
+        VestingSchedule {
+            total: self.total.amount,
+            start_epoch: self.start_epoch,
+            cliff_epoch: self.cliff_epoch,
+            end_epoch: self.end_epoch,
+        }
+    }
+}
+
+/// The settings of the testnet faucet
+#[derive(
+    Clone,
+    Debug,
+    Deserialize,
+    Serialize,
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub struct FaucetTx {
+    /// The difficulty of the PoW challenge of every withdrawal, if the faucet
+    /// requires one
+    pub pow_difficulty: Option<u8>,
+    /// The tokens that can be withdrawn from the faucet
+    pub tokens: Vec<FaucetTokenTx>,
+}
+
+/// The initial balance and withdrawal limits of a token of the testnet faucet
+#[derive(
+    Clone,
+    Debug,
+    Deserialize,
+    Serialize,
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub struct FaucetTokenTx {
+    /// The alias of the token
+    pub token: Alias,
+    /// The balance of the token credited to the faucet, in the base units of
+    /// the token
+    pub balance: token::Amount,
+    /// The amount of the token that can be withdrawn by a target per epoch,
+    /// in the base units of the token
+    pub withdrawal_limit: token::Amount,
+    /// The amount of the token that can be withdrawn by all the targets per
+    /// epoch, in the base units of the token, if it's limited
+    pub global_withdrawal_limit: Option<token::Amount>,
+}
+
 #[derive(
     Clone,
     Debug,
@@ -997,6 +1117,8 @@ pub fn validate(
         ref established_account,
         ref validator_account,
         bond,
+        vesting,
+        faucet,
     } = transactions;
 
     if let Some(txs) = established_account {
@@ -1085,6 +1207,38 @@ pub fn validate(
         None
     };
 
+    let validated_vesting = vesting.map(|txs| {
+        let mut owners = BTreeSet::new();
+        txs.into_iter()
+            .filter_map(|tx| {
+                let validated =
+                    validate_vesting(tx, &established_accounts, &mut owners);
+                if validated.is_none() {
+                    is_valid = false;
+                }
+                validated
+            })
+            .collect()
+    });
+
+    let validated_faucet = faucet.map(|txs| {
+        if txs.len() > 1 {
+            eprintln!(
+                "Invalid faucet tx. There can only be one testnet faucet."
+            );
+            is_valid = false;
+        }
+        txs.into_iter()
+            .filter_map(|tx| {
+                let validated = validate_faucet(tx, balances);
+                if validated.is_none() {
+                    is_valid = false;
+                }
+                validated
+            })
+            .collect()
+    });
+
     is_valid.then_some(Transactions {
         established_account: transactions.established_account,
         validator_account: transactions.validator_account.map(
@@ -1113,6 +1267,8 @@ pub fn validate(
             },
         ),
         bond: validated_bonds,
+        vesting: validated_vesting,
+        faucet: validated_faucet,
     })
 }
 
@@ -1214,6 +1370,90 @@ fn validate_bond(
     is_valid.then_some(validated_bond)
 }
 
+fn validate_vesting(
+    tx: VestingTx,
+    established_accounts: &BTreeMap<Address, (Vec<common::PublicKey>, u8)>,
+    owners: &mut BTreeSet<Address>,
+) -> Option<VestingTx> {
+    let mut is_valid = true;
+    let owner = &tx.owner;
+    if !established_accounts.contains_key(owner) {
+        eprintln!(
+            "Invalid vesting tx. The owner \"{owner}\" is not an established \
+             account."
+        );
+        is_valid = false;
+    }
+    if !owners.insert(owner.clone()) {
+        eprintln!(
+            "Invalid vesting tx. The owner \"{owner}\" has more than one \
+             vesting schedule."
+        );
+        is_valid = false;
+    }
+
+    // Make sure the native token amount is denominated correctly
+    let total = tx
+        .total
+        .increase_precision(NATIVE_MAX_DECIMAL_PLACES.into())
+        .map_err(|e| {
+            eprintln!(
+                "A vesting amount in the transactions.toml file was \
+                 incorrectly formatted:\n{}",
+                e
+            );
+            e
+        })
+        .ok()?;
+    let validated_vesting = VestingTx { total, ..tx };
+    if !validated_vesting.schedule().is_valid() {
+        eprintln!(
+            "Invalid vesting tx. The epochs of the schedule of \"{owner}\" \
+             must be in order."
+        );
+        is_valid = false;
+    }
+
+    is_valid.then_some(validated_vesting)
+}
+
+fn validate_faucet(
+    tx: FaucetTx,
+    balances: Option<&DenominatedBalances>,
+) -> Option<FaucetTx> {
+    let mut is_valid = true;
+    if let Some(difficulty) = tx.pow_difficulty {
+        if testnet_pow::Difficulty::try_new(difficulty).is_none() {
+            eprintln!(
+                "Invalid faucet tx. The PoW difficulty must not be above {}.",
+                testnet_pow::Difficulty::MAX
+            );
+            is_valid = false;
+        }
+    }
+    let mut tokens = BTreeSet::new();
+    for FaucetTokenTx { token, .. } in &tx.tokens {
+        if !tokens.insert(token) {
+            eprintln!(
+                "Invalid faucet tx. The token \"{token}\" has more than one \
+                 settings."
+            );
+            is_valid = false;
+        }
+        if let Some(balances) = balances {
+            if !balances.token.contains_key(token) {
+                eprintln!(
+                    "Invalid faucet tx. The token \"{token}\" is not found \
+                     in the genesis balances."
+                );
+                is_valid = false;
+            }
+        }
+    }
+
+    is_valid.then_some(tx)
+}
+
 #[derive(Clone, Debug)]
 pub struct TokenBalancesForValidation {
     /// Accumulator for tokens transferred to accounts
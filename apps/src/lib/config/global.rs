@@ -1,5 +1,6 @@
 //! Global configuration
 
+use std::collections::BTreeMap;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -28,16 +29,39 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct GlobalConfig {
     /// The default chain ID
     pub default_chain_id: Option<ChainId>,
+    /// Named profiles selectable with the `--profile` argument
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
     // NOTE: There will be sub-chains in here in future
 }
 
+/// A named set of client settings for a network. A profile's settings are
+/// only used for the arguments that are not given explicitly.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// The chain ID of the network
+    pub chain_id: ChainId,
+    /// One or more comma-separated addresses of ledger nodes
+    pub ledger_address: Option<String>,
+    /// The token for paying the gas
+    pub fee_token: Option<String>,
+    /// The amount paid per gas unit
+    pub gas_price: Option<String>,
+}
+
 impl GlobalConfig {
     pub fn new(default_chain_id: ChainId) -> Self {
         Self {
             default_chain_id: Some(default_chain_id),
+            profiles: BTreeMap::default(),
         }
     }
 
+    /// Find a profile by its name
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
+    }
+
     /// Try to read the global config from a file. Returns a config without
     /// a `default_chain_id` if none exists.
     pub fn read(base_dir: impl AsRef<Path>) -> Result<Self> {
@@ -72,3 +96,29 @@ impl GlobalConfig {
         base_dir.as_ref().join(FILENAME)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the profiles are written to and read from the global config
+    #[test]
+    fn test_global_config_profiles() {
+        let base_dir = tempfile::tempdir().unwrap();
+        let mut config = GlobalConfig::default();
+        let profile = Profile {
+            chain_id: ChainId::default(),
+            ledger_address: Some("127.0.0.1:26657,10.0.0.1:26657".to_string()),
+            fee_token: Some("NAM".to_string()),
+            gas_price: None,
+        };
+        config
+            .profiles
+            .insert("testnet".to_string(), profile.clone());
+        config.write(base_dir.path()).unwrap();
+
+        let config = GlobalConfig::read(base_dir.path()).unwrap();
+        assert_eq!(config.profile("testnet"), Some(&profile));
+        assert_eq!(config.profile("mainnet"), None);
+    }
+}
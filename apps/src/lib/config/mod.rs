@@ -5,10 +5,12 @@ pub mod genesis;
 pub mod global;
 pub mod utils;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::{create_dir_all, File};
 use std::io::Write;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use directories::ProjectDirs;
 use namada::types::chain::ChainId;
@@ -18,6 +20,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::cli;
+use crate::facade::tendermint_config::net::Address as TendermintAddress;
 use crate::facade::tendermint_config::{
     TendermintConfig, TxIndexConfig, TxIndexer,
 };
@@ -29,6 +32,9 @@ pub const DEFAULT_WASM_DIR: &str = "wasm";
 /// The WASM checksums file contains the hashes of built WASMs. It is inside the
 /// WASM dir.
 pub const DEFAULT_WASM_CHECKSUMS_FILE: &str = "checksums.json";
+/// The WASM manifest pins the code name, hash and file path of every WASM
+/// artifact of a chain. Nested in chain dirs.
+pub const DEFAULT_WASM_MANIFEST_FILE: &str = "wasm_manifest.json";
 /// Chain-specific Namada configuration. Nested in chain dirs.
 pub const FILENAME: &str = "config.toml";
 /// Chain-specific CometBFT configuration. Nested in chain dirs.
@@ -46,6 +52,10 @@ pub struct Config {
 pub struct ValidatorLocalConfig {
     pub accepted_gas_tokens:
         HashMap<namada::types::address::Address, namada::types::token::Amount>,
+    /// The fee payers whose txs are proposed ahead of the others, regardless
+    /// of the fee they pay
+    #[serde(default)]
+    pub priority_addresses: BTreeSet<namada::types::address::Address>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -65,6 +75,49 @@ impl TendermintMode {
     }
 }
 
+/// The role of a node in a sentry architecture, in which the validator nodes
+/// are only connected to their sentry nodes and the sentries connect them to
+/// the rest of the network. The role pre-configures the P2P settings of
+/// CometBFT.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeRole {
+    /// A node that crawls the network for peers and shares them with the
+    /// other nodes, without indexing txs
+    Seed,
+    /// A full node in front of the given validators, which it doesn't gossip
+    /// to the network
+    Sentry,
+    /// A validator only connected to the given sentries
+    Validator,
+}
+
+impl NodeRole {
+    /// The CometBFT mode of a node with this role
+    pub fn tendermint_mode(&self) -> TendermintMode {
+        match self {
+            NodeRole::Seed => TendermintMode::Seed,
+            NodeRole::Sentry => TendermintMode::Full,
+            NodeRole::Validator => TendermintMode::Validator,
+        }
+    }
+}
+
+impl FromStr for NodeRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "seed" => Ok(Self::Seed),
+            "sentry" => Ok(Self::Sentry),
+            "validator" => Ok(Self::Validator),
+            _ => Err(format!(
+                "Unrecognized node mode {s}. Expecting one of: seed, sentry, \
+                 validator."
+            )),
+        }
+    }
+}
+
 /// An action to be performed at a
 /// certain block height.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +139,27 @@ pub struct ActionAtHeight {
     pub action: Action,
 }
 
+/// The access policy of the node's HTTP services, i.e. the REST gateway, the
+/// health endpoints and the event subscriptions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HttpAccess {
+    /// The origins from which browsers may make cross-origin requests, e.g.
+    /// `"https://wallet.example.com"`, or `"*"` for any origin. When empty,
+    /// cross-origin requests are not allowed.
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+    /// The maximum number of requests per second that a client IP address
+    /// can make to an endpoint, when the endpoint has no limit of its own.
+    /// Unlimited when not set.
+    pub default_rate_limit: Option<u32>,
+    /// The maximum number of requests per second that a client IP address
+    /// can make to the given endpoints, e.g. `balance = 10`. The endpoints
+    /// are `balance`, `validators`, `proposals`, `openapi`, `health`, `ready`
+    /// and `subscribe`.
+    #[serde(default)]
+    pub rate_limits: BTreeMap<String, u32>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Ledger {
     pub genesis_time: Rfc3339String,
@@ -111,6 +185,29 @@ pub struct Shell {
     /// When set, will limit the how many block heights in the past can the
     /// storage be queried for reading values.
     pub storage_read_past_height_limit: Option<u64>,
+    /// When set, will limit how many wrapper transactions paid by the same
+    /// fee payer can be pending in the mempool at the same time. Reloaded on
+    /// `SIGHUP`.
+    pub max_pending_txs_per_fee_payer: Option<u64>,
+    /// When not empty, only the wrapper txs paying their fees in the given
+    /// tokens, at least at the given gas prices, are accepted into the
+    /// mempool. The fee tokens and gas prices of the protocol parameters are
+    /// enforced regardless. Reloaded on `SIGHUP`.
+    #[serde(default)]
+    pub mempool_fee_tokens:
+        BTreeMap<namada::types::address::Address, namada::types::token::Amount>,
+    /// When set, will serve the subscriptions to the events emitted by the
+    /// ledger over WebSocket on the given address.
+    pub event_subscriptions_address: Option<SocketAddr>,
+    /// When set, will serve the typed gRPC query services on the given
+    /// address.
+    pub grpc_address: Option<SocketAddr>,
+    /// When set, will serve the REST gateway for the common queries on the
+    /// given address.
+    pub rest_address: Option<SocketAddr>,
+    /// When set, will serve the `/health` and `/ready` endpoints for the
+    /// probes of orchestrators and load balancers on the given address.
+    pub health_address: Option<SocketAddr>,
     /// Use the [`Ledger::db_dir()`] method to read the value.
     db_dir: PathBuf,
     /// Use the [`Ledger::cometbft_dir()`] method to read the value.
@@ -119,6 +216,25 @@ pub struct Shell {
     pub action_at_height: Option<ActionAtHeight>,
     /// Specify if tendermint is started as validator, fullnode or seednode
     pub tendermint_mode: TendermintMode,
+    /// The log levels of the given modules, e.g. `"namada::ledger::protocol"
+    /// = "debug"`. These take precedence over the `NAMADA_LOG` env var.
+    /// Reloaded on `SIGHUP`.
+    #[serde(default)]
+    pub log_levels: BTreeMap<String, String>,
+    /// The CORS and rate limits of the HTTP services. The rate limits are
+    /// reloaded on `SIGHUP`.
+    #[serde(default)]
+    pub http_access: HttpAccess,
+    /// Reload the tx and VP wasms from the wasm directory when they are
+    /// rebuilt. Only for single validator development chains, as the
+    /// reloaded code isn't agreed on by the other validators.
+    #[serde(default)]
+    pub dev_wasm_reload: bool,
+    /// Apply the scheduled upgrade of the chain when its height is reached,
+    /// if the version of this binary matches its name. Set by the
+    /// `--upgrade-ready` flag, not persisted.
+    #[serde(skip)]
+    pub upgrade_ready: bool,
 }
 
 impl Ledger {
@@ -143,16 +259,73 @@ impl Ledger {
                 tx_wasm_compilation_cache_bytes: None,
                 // Default corresponds to 1 hour of past blocks at 1 block/sec
                 storage_read_past_height_limit: Some(3600),
+                max_pending_txs_per_fee_payer: Some(100),
+                mempool_fee_tokens: BTreeMap::default(),
+                event_subscriptions_address: None,
+                grpc_address: None,
+                rest_address: None,
+                health_address: None,
                 db_dir: DB_DIR.into(),
                 cometbft_dir: COMETBFT_DIR.into(),
                 action_at_height: None,
                 tendermint_mode: mode,
+                log_levels: BTreeMap::default(),
+                http_access: HttpAccess::default(),
+                dev_wasm_reload: false,
+                upgrade_ready: false,
             },
             cometbft: tendermint_config,
             ethereum_bridge: ethereum_bridge::ledger::Config::default(),
         }
     }
 
+    /// Set the role of the node and pre-configure its CometBFT P2P settings.
+    /// The `peers` are the validators behind a sentry or the sentries of a
+    /// validator and they are ignored by a seed.
+    pub fn set_node_role(
+        &mut self,
+        role: NodeRole,
+        peers: Vec<TendermintAddress>,
+    ) {
+        self.shell.tendermint_mode = role.tendermint_mode();
+        let peer_ids: Vec<_> = peers
+            .iter()
+            .filter_map(|peer| match peer {
+                TendermintAddress::Tcp { peer_id, .. } => *peer_id,
+                TendermintAddress::Unix { .. } => None,
+            })
+            .collect();
+        let p2p = &mut self.cometbft.p2p;
+        match role {
+            NodeRole::Seed => {
+                p2p.seed_mode = true;
+                p2p.pex = true;
+                self.cometbft.tx_index = TxIndexConfig {
+                    indexer: TxIndexer::Null,
+                };
+            }
+            NodeRole::Sentry => {
+                p2p.seed_mode = false;
+                p2p.pex = true;
+                // The validators are always connected and never gossiped
+                p2p.persistent_peers = peers;
+                p2p.unconditional_peer_ids = peer_ids.clone();
+                p2p.private_peer_ids = peer_ids;
+                // The validators are typically on a private network
+                p2p.addr_book_strict = false;
+            }
+            NodeRole::Validator => {
+                p2p.seed_mode = false;
+                // Only the sentries are dialed, the validator's address isn't
+                // shared with the network
+                p2p.pex = false;
+                p2p.persistent_peers = peers;
+                p2p.unconditional_peer_ids = peer_ids;
+                p2p.addr_book_strict = false;
+            }
+        }
+    }
+
     /// Get the chain directory path
     pub fn chain_dir(&self) -> PathBuf {
         self.shell.base_dir.join(self.chain_id.as_str())
@@ -845,11 +1018,45 @@ namespace = "cometbft"
 
 #[cfg(test)]
 mod tests {
-    use super::DEFAULT_COMETBFT_CONFIG;
-    use crate::facade::tendermint_config::TendermintConfig;
+    use namada::types::chain::ChainId;
+
+    use super::*;
 
     #[test]
     fn test_default_cometbft_config() {
         assert!(TendermintConfig::parse_toml(DEFAULT_COMETBFT_CONFIG).is_ok());
     }
+
+    /// Test that the sentries keep their validators private and that the
+    /// validators only connect to their sentries
+    #[test]
+    fn test_set_node_role() {
+        let peer: TendermintAddress =
+            "tcp://a2b0c6b4fd7b2e8f4e1e5a6d7c8b9a0f1e2d3c4b@10.0.0.1:26656"
+                .parse()
+                .unwrap();
+        let new_config =
+            || Ledger::new("base", ChainId::default(), TendermintMode::Full);
+
+        let mut config = new_config();
+        config.set_node_role(NodeRole::Sentry, vec![peer.clone()]);
+        assert_eq!(config.shell.tendermint_mode, TendermintMode::Full);
+        assert!(config.cometbft.p2p.pex);
+        assert_eq!(config.cometbft.p2p.persistent_peers, vec![peer.clone()]);
+        assert_eq!(config.cometbft.p2p.private_peer_ids.len(), 1);
+        assert_eq!(config.cometbft.p2p.unconditional_peer_ids.len(), 1);
+
+        let mut config = new_config();
+        config.set_node_role(NodeRole::Validator, vec![peer.clone()]);
+        assert_eq!(config.shell.tendermint_mode, TendermintMode::Validator);
+        assert!(!config.cometbft.p2p.pex);
+        assert_eq!(config.cometbft.p2p.persistent_peers, vec![peer]);
+        assert!(config.cometbft.p2p.private_peer_ids.is_empty());
+
+        let mut config = new_config();
+        config.set_node_role(NodeRole::Seed, vec![]);
+        assert_eq!(config.shell.tendermint_mode, TendermintMode::Seed);
+        assert!(config.cometbft.p2p.seed_mode);
+        assert!(config.cometbft.p2p.pex);
+    }
 }
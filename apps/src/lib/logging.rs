@@ -1,8 +1,11 @@
 //! A module for anything related to logging
+use std::collections::BTreeMap;
 use std::env;
+use std::str::FromStr;
 
 use color_eyre::eyre::Result;
-use eyre::WrapErr;
+use eyre::{eyre, WrapErr};
+use once_cell::sync::OnceCell;
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_log::LogTracer;
 use tracing_subscriber::filter::{Directive, EnvFilter};
@@ -21,8 +24,15 @@ const ROLLING_ENV_KEY: &str = "NAMADA_LOG_ROLLING";
 
 const LOG_FILE_NAME_PREFIX: &str = "namada.log";
 
-#[derive(Clone, Debug)]
-enum Fmt {
+/// Replaces the filter of the global log subscriber
+type FilterReload = Box<dyn Fn(EnvFilter) -> Result<()> + Send + Sync>;
+
+/// The filter reload of the global log subscriber, once it's set
+static FILTER_RELOAD: OnceCell<FilterReload> = OnceCell::new();
+
+/// The format of the log lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fmt {
     Full,
     Json,
     Pretty,
@@ -34,6 +44,22 @@ impl Default for Fmt {
     }
 }
 
+impl FromStr for Fmt {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "json" => Ok(Self::Json),
+            "pretty" => Ok(Self::Pretty),
+            _ => Err(format!(
+                "Unrecognized log format {s}. Expecting one of: full, json, \
+                 pretty."
+            )),
+        }
+    }
+}
+
 /// When logging to a file is enabled, returns a guard that handles flushing of
 /// remaining logs on termination.
 ///
@@ -48,6 +74,53 @@ pub fn init_from_env_or(
     Ok(guard)
 }
 
+/// Like [`init_from_env_or`], but the given format takes precedence over the
+/// format from the env var and the given log levels of modules are added to
+/// the filter.
+pub fn init_with(
+    default: impl Into<Directive>,
+    format: Option<Fmt>,
+    log_levels: &BTreeMap<String, String>,
+) -> Result<Option<WorkerGuard>> {
+    let filter = filter_with_log_levels(default, log_levels)?;
+    let guard = set_subscriber_with_format(
+        filter,
+        format.unwrap_or_else(format_from_env),
+    )?;
+    init_log_tracer()?;
+    Ok(guard)
+}
+
+/// Replace the log levels of modules of the global log subscriber set by
+/// [`init_with`], e.g. when the config is reloaded.
+pub fn reload_log_levels(
+    default: impl Into<Directive>,
+    log_levels: &BTreeMap<String, String>,
+) -> Result<()> {
+    let filter = filter_with_log_levels(default, log_levels)?;
+    let reload = FILTER_RELOAD
+        .get()
+        .ok_or_else(|| eyre!("The log subscriber isn't set"))?;
+    reload(filter)
+}
+
+/// The filter from env var or the default, with the given log levels of
+/// modules added
+fn filter_with_log_levels(
+    default: impl Into<Directive>,
+    log_levels: &BTreeMap<String, String>,
+) -> Result<EnvFilter> {
+    let mut filter = filter_from_env_or(default);
+    for (module, level) in log_levels {
+        let directive =
+            format!("{module}={level}").parse().wrap_err_with(|| {
+                format!("Invalid log level {level} of module {module}")
+            })?;
+        filter = filter.add_directive(directive);
+    }
+    Ok(filter)
+}
+
 pub fn filter_from_env_or(default: impl Into<Directive>) -> EnvFilter {
     env::var(ENV_KEY)
         .map(EnvFilter::new)
@@ -59,20 +132,26 @@ pub fn init_log_tracer() -> Result<()> {
 }
 
 pub fn set_subscriber(filter: EnvFilter) -> Result<Option<WorkerGuard>> {
+    set_subscriber_with_format(filter, format_from_env())
+}
+
+/// Get the log format from env var or default to `Full`.
+fn format_from_env() -> Fmt {
+    env::var(FMT_ENV_KEY)
+        .ok()
+        .and_then(|val| Fmt::from_str(&val).ok())
+        .unwrap_or_default()
+}
+
+fn set_subscriber_with_format(
+    filter: EnvFilter,
+    format: Fmt,
+) -> Result<Option<WorkerGuard>> {
     let with_color = if let Ok(val) = env::var(COLOR_ENV_KEY) {
         val.to_ascii_lowercase() != "false"
     } else {
         true
     };
-    let format = env::var(FMT_ENV_KEY)
-        .ok()
-        .and_then(|val| match val.to_ascii_lowercase().as_str() {
-            "full" => Some(Fmt::Full),
-            "json" => Some(Fmt::Json),
-            "pretty" => Some(Fmt::Pretty),
-            _ => None,
-        })
-        .unwrap_or_default();
     let log_dir = env::var(DIR_ENV_KEY).ok();
 
     let builder = Subscriber::builder()
@@ -84,7 +163,14 @@ pub fn set_subscriber(filter: EnvFilter) -> Result<Option<WorkerGuard>> {
     macro_rules! finish {
         ($($builder:tt)*) => {
             {
-                let my_collector = $($builder)*.finish();
+                let builder = $($builder)*.with_filter_reloading();
+                let handle = builder.reload_handle();
+                let _ = FILTER_RELOAD.set(Box::new(move |filter| {
+                    handle
+                        .reload(filter)
+                        .wrap_err("Failed to reload the log filter")
+                }));
+                let my_collector = builder.finish();
                 tracing::subscriber::set_global_default(my_collector)
                     .wrap_err("Failed to set log subscriber")
             }
@@ -1,7 +1,9 @@
 use std::future::Future;
 use std::pin::Pin;
 
-use namada::types::control_flow::{install_shutdown_signal, ShutdownSignal};
+use namada::types::control_flow::{
+    install_shutdown_signal_without_hangup, ShutdownSignal,
+};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 
@@ -35,7 +37,8 @@ impl Default for AbortableSpawner {
 impl AbortableSpawner {
     /// Creates a new [`AbortableSpawner`].
     pub fn new() -> Self {
-        let shutdown_recv = install_shutdown_signal();
+        // A hangup signal reloads the config instead of shutting down
+        let shutdown_recv = install_shutdown_signal_without_hangup();
         let (abort_send, abort_recv) = mpsc::unbounded_channel();
         Self {
             abort_send,
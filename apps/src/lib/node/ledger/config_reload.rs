@@ -0,0 +1,132 @@
+//! Reload of the node config on a hangup signal (`SIGHUP`), to apply the
+//! changes of the settings that don't affect consensus without restarting the
+//! node.
+//!
+//! The reloaded settings are the log levels of the modules, the rate limits of
+//! the HTTP services, the limit of pending txs per fee payer and the fee tokens
+//! accepted into the mempool. The changes of the other settings, including the
+//! CometBFT config and its Prometheus metrics, are only applied on restart.
+
+use std::collections::BTreeMap;
+
+use namada::types::address::Address;
+use namada::types::token;
+use tokio::sync::watch;
+
+use crate::config::{self, HttpAccess};
+use crate::node::ledger::http_access::RateLimiter;
+
+/// The settings of the node config that can be reloaded
+#[derive(Clone, Debug)]
+pub struct ReloadableConfig {
+    /// The log levels of the modules
+    pub log_levels: BTreeMap<String, String>,
+    /// The access policy of the HTTP services. Only its rate limits are
+    /// reloaded.
+    pub http_access: HttpAccess,
+    /// The limit of pending txs per fee payer in the mempool
+    pub max_pending_txs_per_fee_payer: Option<u64>,
+    /// The fee tokens accepted into the mempool with their min gas prices
+    pub mempool_fee_tokens: BTreeMap<Address, token::Amount>,
+}
+
+impl From<&config::Ledger> for ReloadableConfig {
+    fn from(config: &config::Ledger) -> Self {
+        Self {
+            log_levels: config.shell.log_levels.clone(),
+            http_access: config.shell.http_access.clone(),
+            max_pending_txs_per_fee_payer: config
+                .shell
+                .max_pending_txs_per_fee_payer,
+            mempool_fee_tokens: config.shell.mempool_fee_tokens.clone(),
+        }
+    }
+}
+
+/// Spawn a task that reloads the node config on every hangup signal and
+/// applies the reloaded log levels and rate limits. The returned receiver
+/// gets the reloadable settings of every reloaded config, for the settings
+/// applied by the shell.
+pub fn spawn(
+    config: &config::Ledger,
+    limiter: RateLimiter,
+) -> watch::Receiver<ReloadableConfig> {
+    let (sender, receiver) = watch::channel(ReloadableConfig::from(config));
+    #[cfg(unix)]
+    tokio::spawn(unix::reload_on_hangup(
+        config.shell.base_dir.clone(),
+        config.chain_id.clone(),
+        config.shell.tendermint_mode.clone(),
+        sender,
+        limiter,
+    ));
+    #[cfg(not(unix))]
+    {
+        // There are no hangup signals, the config is only read on start
+        drop((sender, limiter));
+    }
+    receiver
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::path::PathBuf;
+
+    use namada::types::chain::ChainId;
+    use tokio::signal::unix::{signal, SignalKind};
+    use tokio::sync::watch;
+    use tracing_subscriber::filter::LevelFilter;
+
+    use super::ReloadableConfig;
+    use crate::config::{Config, TendermintMode};
+    use crate::logging;
+    use crate::node::ledger::http_access::RateLimiter;
+
+    /// Reload the config from the given base dir on every hangup signal
+    pub async fn reload_on_hangup(
+        base_dir: PathBuf,
+        chain_id: ChainId,
+        mode: TendermintMode,
+        sender: watch::Sender<ReloadableConfig>,
+        limiter: RateLimiter,
+    ) {
+        let mut sighup = match signal(SignalKind::hangup()) {
+            Ok(sighup) => sighup,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to listen for the hangup signal, the config \
+                     cannot be reloaded: {err}"
+                );
+                return;
+            }
+        };
+        while sighup.recv().await.is_some() {
+            tracing::info!("Received hangup signal, reloading the config...");
+            let config =
+                match Config::read(&base_dir, &chain_id, Some(mode.clone())) {
+                    Ok(config) => ReloadableConfig::from(&config.ledger),
+                    Err(err) => {
+                        tracing::error!(
+                            "Failed to reload the config, keeping the \
+                             current one: {err}"
+                        );
+                        continue;
+                    }
+                };
+            // The default log level is the one the node is started with
+            if let Err(err) = logging::reload_log_levels(
+                LevelFilter::INFO,
+                &config.log_levels,
+            ) {
+                tracing::error!("Failed to reload the log levels: {err}");
+            }
+            limiter.update(&config.http_access);
+            sender.send_replace(config);
+            tracing::info!(
+                "Reloaded the log levels, the rate limits, the max pending \
+                 txs per fee payer and the mempool fee tokens. The changes \
+                 to the other settings are applied on restart."
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! A WebSocket server on which clients can subscribe to the events emitted
+//! by the ledger.
+//!
+//! A client opens a WebSocket connection to the [`SUBSCRIBE_ENDPOINT`],
+//! passing an [`EventFilter`] as query parameters, e.g.
+//! `ws://127.0.0.1:26660/subscribe?event_type=applied&tx_hash=<hash>`. Then,
+//! every event that matches the filter is pushed to the client as a JSON
+//! text message as soon as the block that emitted it is finalized.
+
+use std::net::SocketAddr;
+
+use futures::{SinkExt, StreamExt};
+use namada::ledger::events::filter::EventFilter;
+use namada::ledger::events::Event;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::{Receiver, Sender};
+use tokio::sync::oneshot;
+use warp::ws::{Message, WebSocket, Ws};
+use warp::Filter;
+
+use crate::config::HttpAccess;
+use crate::node::ledger::http_access::{self, rate_limit, RateLimiter};
+
+/// The endpoint on which clients can subscribe to events.
+pub const SUBSCRIBE_ENDPOINT: &str = "subscribe";
+
+/// Starts a [`warp::Server`] that forwards the events published on the
+/// `event_bus` to the subscribed clients. It shuts down if a signal is sent on
+/// the `abort_recv` channel.
+pub async fn serve(
+    listen_addr: SocketAddr,
+    event_bus: Sender<Event>,
+    access: HttpAccess,
+    limiter: RateLimiter,
+    abort_recv: oneshot::Receiver<()>,
+) {
+    let subscribe = warp::path(SUBSCRIBE_ENDPOINT)
+        .and(rate_limit(limiter, SUBSCRIBE_ENDPOINT))
+        .and(warp::ws())
+        .and(warp::query::<EventFilter>())
+        .map(move |ws: Ws, filter: EventFilter| {
+            let events = event_bus.subscribe();
+            ws.on_upgrade(move |socket| forward_events(socket, events, filter))
+        });
+
+    http_access::serve(
+        "event subscriptions",
+        subscribe,
+        listen_addr,
+        &access,
+        abort_recv,
+    )
+    .await
+}
+
+/// Forward the events that match the given filter to a subscribed client,
+/// until it disconnects.
+async fn forward_events(
+    socket: WebSocket,
+    mut events: Receiver<Event>,
+    filter: EventFilter,
+) {
+    let (mut sink, mut stream) = socket.split();
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if !filter.matches(&event) {
+                        continue;
+                    }
+                    let msg = Message::text(event.to_json().to_string());
+                    if sink.send(msg).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(
+                        "An event subscriber fell behind, skipped {} events",
+                        skipped
+                    );
+                }
+                Err(RecvError::Closed) => break,
+            },
+            msg = stream.next() => match msg {
+                // Messages from the client are ignored
+                Some(Ok(msg)) if !msg.is_close() => {}
+                _ => break,
+            },
+        }
+    }
+    let _ = sink.close().await;
+}
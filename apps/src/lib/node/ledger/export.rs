@@ -0,0 +1,573 @@
+//! Export of the typed state of the ledger's modules.
+//!
+//! Rather than dumping raw storage keys, like `dump-db` does, the state of
+//! every exported module is read with the module's own storage readers and
+//! written as a structured JSON snapshot. This lets auditors compare the state
+//! of the modules across upgrades, which may change the storage layout but
+//! not the meaning of the state.
+//!
+//! The storage schemas of the modules are registered in a [`SchemaRegistry`].
+//! Next to its typed state, every module's export has the number of keys in
+//! each of its registered sub-spaces and the keys found outside of them, so
+//! that a layout change that's not reflected in the typed state still shows.
+//!
+//! The state at a past height is obtained by reverting the module's keys to
+//! their values at that height from the diffs stored in the DB, so the export
+//! of a past height requires the diffs of all the following heights.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use borsh::BorshDeserialize;
+use namada::core::ledger::governance::storage::keys as governance_storage;
+use namada::core::ledger::{governance, pgf};
+use namada::ledger::parameters;
+use namada::ledger::storage::write_log::{self, WriteLog};
+use namada::ledger::storage::{self as ledger_storage, DBIter, WlStorage, DB};
+use namada::ledger::storage_api::collections::SchemaRegistry;
+use namada::ledger::storage_api::{self, StorageRead};
+use namada::ledger::upgrade;
+use namada::proof_of_stake::{self, types::WeightedValidator};
+use namada::types::address::{self, Address};
+use namada::types::io::StdIo;
+use namada::types::storage::{BlockHeight, Epoch, Key, KeySeg};
+use namada_sdk::display_line;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use super::storage::{
+    PersistentDB, PersistentStorage, PersistentStorageHasher,
+};
+use crate::cli::args;
+use crate::config;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid module {0}, expected one of {1}")]
+    InvalidModule(String, String),
+    #[error("The height {height} is above the last committed height {last}")]
+    HeightAboveLast {
+        height: BlockHeight,
+        last: BlockHeight,
+    },
+    #[error("The epoch of the height {0} is unknown")]
+    UnknownEpoch(BlockHeight),
+    #[error("Failed to read the DB: {0}")]
+    Db(ledger_storage::Error),
+    #[error("Failed to revert the state to a past height: {0}")]
+    WriteLog(write_log::Error),
+    #[error("Failed to read the state of the module {0}: {1}")]
+    StorageApi(Module, storage_api::Error),
+    #[error("Failed to write the export: {0}")]
+    Io(std::io::Error),
+}
+
+/// A module whose state can be exported
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Module {
+    /// Proof of stake
+    Pos,
+    /// Governance
+    Governance,
+    /// Protocol parameters
+    Parameters,
+    /// Public goods funding
+    Pgf,
+}
+
+impl Module {
+    /// All the exportable modules
+    pub const ALL: [Module; 4] = [
+        Module::Pos,
+        Module::Governance,
+        Module::Parameters,
+        Module::Pgf,
+    ];
+
+    /// The address under which the module's state is stored
+    fn address(&self) -> Address {
+        match self {
+            Module::Pos => proof_of_stake::ADDRESS,
+            Module::Governance => governance::ADDRESS,
+            Module::Parameters => parameters::ADDRESS,
+            Module::Pgf => pgf::ADDRESS,
+        }
+    }
+
+    /// The key segments of the sub-spaces of the module's storage
+    fn storage_keys(&self) -> Vec<&'static str> {
+        match self {
+            Module::Pos => proof_of_stake::storage::storage_keys().to_vec(),
+            Module::Governance => governance_storage::storage_keys().to_vec(),
+            Module::Parameters => parameters::storage::storage_keys(),
+            Module::Pgf => pgf::storage::keys::storage_keys().to_vec(),
+        }
+    }
+}
+
+/// The registry of the storage schemas of all the exportable modules
+fn schema_registry() -> SchemaRegistry {
+    let mut registry = SchemaRegistry::default();
+    for module in Module::ALL {
+        registry.register(module.address(), &module.storage_keys());
+    }
+    registry
+}
+
+impl Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Module::Pos => write!(f, "pos"),
+            Module::Governance => write!(f, "governance"),
+            Module::Parameters => write!(f, "parameters"),
+            Module::Pgf => write!(f, "pgf"),
+        }
+    }
+}
+
+impl FromStr for Module {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Module::ALL
+            .into_iter()
+            .find(|module| module.to_string() == s)
+            .ok_or_else(|| {
+                let expected = Module::ALL
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Error::InvalidModule(s.to_owned(), expected)
+            })
+    }
+}
+
+type ExportStorage = WlStorage<PersistentDB, PersistentStorageHasher>;
+
+/// Export the state of the given modules at the given height, defaulting to
+/// the last committed height, into a JSON file
+pub fn run(
+    config: config::Ledger,
+    args::LedgerExport {
+        modules,
+        block_height,
+        out_file_path,
+    }: args::LedgerExport,
+) -> Result<(), Error> {
+    let chain_id = config.chain_id;
+    let db_path = config.shell.db_dir(&chain_id);
+    let mut storage =
+        PersistentStorage::open(db_path, chain_id, address::nam(), None, None);
+    storage.load_last_state().map_err(Error::Db)?;
+    let (native_token, _gas) = storage
+        .read(&parameters::storage::get_native_token_key())
+        .map_err(Error::Db)?;
+    if let Some(native_token) = native_token {
+        storage.native_token = Address::try_from_slice(&native_token)
+            .expect("The native token should be a valid address");
+    }
+
+    let last_height = storage.get_last_block_height();
+    let height = block_height.unwrap_or(last_height);
+    if height > last_height {
+        return Err(Error::HeightAboveLast {
+            height,
+            last: last_height,
+        });
+    }
+    let epoch = storage
+        .block
+        .pred_epochs
+        .get_epoch(height)
+        .ok_or(Error::UnknownEpoch(height))?;
+    storage.block.height = height;
+    storage.block.epoch = epoch;
+
+    let mut wl_storage = WlStorage::new(WriteLog::default(), storage);
+    let registry = schema_registry();
+    // The modules read each other's state, e.g. the PoS parameters include a
+    // governance parameter, so the state of all of them is reverted
+    revert_to_height(&mut wl_storage, &registry, height, last_height)?;
+
+    let modules: BTreeSet<Module> = modules.into_iter().collect();
+    let mut export = serde_json::Map::new();
+    export.insert("height".to_owned(), json!(height.0));
+    export.insert("epoch".to_owned(), json!(epoch.0));
+    for module in modules {
+        let state = export_module(&wl_storage, &registry, module, epoch)
+            .map_err(|err| Error::StorageApi(module, err))?;
+        export.insert(module.to_string(), state);
+    }
+
+    let out_file_path = out_file_path
+        .with_file_name(format!(
+            "{}_{height}",
+            out_file_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "state_export".to_string())
+        ))
+        .with_extension("json");
+    let out = serde_json::to_string_pretty(&Value::Object(export))
+        .expect("Serializing JSON values shouldn't fail");
+    std::fs::write(&out_file_path, out).map_err(Error::Io)?;
+    display_line!(
+        StdIo,
+        "Exported the state at height {height} to {}.",
+        out_file_path.to_string_lossy()
+    );
+    Ok(())
+}
+
+/// Revert the keys of all the registered modules that changed after the given
+/// height to their values at that height in the write log
+fn revert_to_height(
+    wl_storage: &mut ExportStorage,
+    registry: &SchemaRegistry,
+    height: BlockHeight,
+    last_height: BlockHeight,
+) -> Result<(), Error> {
+    let mut keys: BTreeSet<Key> = BTreeSet::new();
+    for owner in registry.owners() {
+        let prefix = Key::from(owner.to_db_key());
+        for changed_height in (height.0 + 1)..=last_height.0 {
+            let changed_height = BlockHeight(changed_height);
+            let db = &wl_storage.storage.db;
+            let old_diffs = db.iter_old_diffs(changed_height, Some(&prefix));
+            let new_diffs = db.iter_new_diffs(changed_height, Some(&prefix));
+            for (key, _value, _gas) in old_diffs.chain(new_diffs) {
+                let key = Key::parse(key)
+                    .expect("The keys of the diffs should be valid");
+                keys.insert(key);
+            }
+        }
+    }
+
+    for key in keys {
+        let value = wl_storage
+            .storage
+            .db
+            .read_subspace_val_with_height(&key, height, last_height)
+            .map_err(Error::Db)?;
+        match value {
+            Some(value) => wl_storage.write_log.protocol_write(&key, value),
+            None => wl_storage.write_log.protocol_delete(&key),
+        }
+        .map_err(Error::WriteLog)?;
+    }
+    Ok(())
+}
+
+/// Read the typed state of a module, with the summary of its storage schema
+fn export_module<S>(
+    storage: &S,
+    registry: &SchemaRegistry,
+    module: Module,
+    epoch: Epoch,
+) -> storage_api::Result<Value>
+where
+    S: StorageRead,
+{
+    let mut state = match module {
+        Module::Pos => export_pos(storage, epoch),
+        Module::Governance => export_governance(storage),
+        Module::Parameters => export_parameters(storage),
+        Module::Pgf => export_pgf(storage),
+    }?;
+    state["schema"] = export_schema(storage, registry, module)?;
+    Ok(state)
+}
+
+/// Count the keys of each of the registered sub-spaces of a module and list
+/// the module's keys that don't belong to any of them
+fn export_schema<S>(
+    storage: &S,
+    registry: &SchemaRegistry,
+    module: Module,
+) -> storage_api::Result<Value>
+where
+    S: StorageRead,
+{
+    let address = module.address();
+    let mut sub_spaces: BTreeMap<&str, u64> = registry
+        .schema(&address)
+        .into_iter()
+        .flatten()
+        .map(|sub_space| (*sub_space, 0))
+        .collect();
+    let mut unregistered_keys = vec![];
+    let prefix = Key::from(address.to_db_key());
+    for entry in storage_api::iter_prefix_bytes(storage, &prefix)? {
+        let (key, _value) = entry?;
+        match registry.sub_space(&key) {
+            Some(sub_space) => *sub_spaces.entry(sub_space).or_default() += 1,
+            None => unregistered_keys.push(key.to_string()),
+        }
+    }
+
+    Ok(json!({
+        "sub_spaces": sub_spaces,
+        "unregistered_keys": unregistered_keys,
+    }))
+}
+
+fn export_pos<S>(storage: &S, epoch: Epoch) -> storage_api::Result<Value>
+where
+    S: StorageRead,
+{
+    let params = proof_of_stake::read_pos_params(storage)?;
+    let total_stake =
+        proof_of_stake::read_total_stake(storage, &params, epoch)?;
+    let consensus_set: BTreeSet<Address> =
+        proof_of_stake::read_consensus_validator_set_addresses_with_stake(
+            storage, epoch,
+        )?
+        .into_iter()
+        .map(|WeightedValidator { address, .. }| address)
+        .collect();
+    let validators: BTreeSet<Address> =
+        proof_of_stake::read_all_validator_addresses(storage, epoch)?
+            .into_iter()
+            .collect();
+    let mut exported_validators = vec![];
+    for validator in validators {
+        let stake = proof_of_stake::read_validator_stake(
+            storage, &params, &validator, epoch,
+        )?;
+        let state =
+            proof_of_stake::read_validator_state(storage, &validator, epoch)?;
+        let commission_rate =
+            proof_of_stake::validator_commission_rate_handle(&validator)
+                .get(storage, epoch, &params)?;
+        exported_validators.push(json!({
+            "address": validator.to_string(),
+            "stake": stake.to_string_native(),
+            "state": state.map(|state| format!("{state:?}")),
+            "commission_rate": commission_rate.map(|rate| rate.to_string()),
+            "in_consensus_set": consensus_set.contains(&validator),
+        }));
+    }
+
+    Ok(json!({
+        "params": {
+            "max_validator_slots": params.max_validator_slots,
+            "pipeline_len": params.pipeline_len,
+            "unbonding_len": params.unbonding_len,
+            "tm_votes_per_token": params.tm_votes_per_token.to_string(),
+            "block_proposer_reward": params.block_proposer_reward.to_string(),
+            "block_vote_reward": params.block_vote_reward.to_string(),
+            "max_inflation_rate": params.max_inflation_rate.to_string(),
+            "target_staked_ratio": params.target_staked_ratio.to_string(),
+            "duplicate_vote_min_slash_rate":
+                params.duplicate_vote_min_slash_rate.to_string(),
+            "light_client_attack_min_slash_rate":
+                params.light_client_attack_min_slash_rate.to_string(),
+            "cubic_slashing_window_length":
+                params.cubic_slashing_window_length,
+            "validator_stake_threshold":
+                params.validator_stake_threshold.to_string_native(),
+            "liveness_window_check": params.liveness_window_check,
+            "liveness_threshold": params.liveness_threshold.to_string(),
+            "validator_min_self_bond":
+                params.validator_min_self_bond.to_string_native(),
+            "max_stake_to_self_bond_ratio": params
+                .max_stake_to_self_bond_ratio
+                .map(|ratio| ratio.to_string()),
+        },
+        "total_stake": total_stake.to_string_native(),
+        "validators": exported_validators,
+    }))
+}
+
+fn export_governance<S>(storage: &S) -> storage_api::Result<Value>
+where
+    S: StorageRead,
+{
+    let params = storage_api::governance::get_parameters(storage)?;
+    let counter: u64 = storage
+        .read(&governance_storage::get_counter_key())?
+        .unwrap_or_default();
+    let mut proposals = vec![];
+    for id in 0..counter {
+        if let Some(proposal) =
+            storage_api::governance::get_proposal_by_id(storage, id)?
+        {
+            proposals.push(json!({
+                "id": proposal.id,
+                "author": proposal.author.to_string(),
+                "type": proposal.r#type.to_string(),
+                "content": proposal.content,
+                "voting_start_epoch": proposal.voting_start_epoch.0,
+                "voting_end_epoch": proposal.voting_end_epoch.0,
+                "grace_epoch": proposal.grace_epoch.0,
+            }));
+        }
+    }
+    let epoch_hooks: Vec<Value> =
+        storage_api::governance::get_epoch_hooks(storage)?
+            .into_iter()
+            .map(|(id, hook)| {
+                json!({
+                    "proposal_id": id,
+                    "code_hash": hook.code_hash().to_string(),
+                    "gas_limit": hook.gas_limit,
+                })
+            })
+            .collect();
+    let upgrade_plan = upgrade::read_plan(storage)?;
+
+    Ok(json!({
+        "params": {
+            "min_proposal_fund": params.min_proposal_fund.to_string_native(),
+            "max_proposal_code_size": params.max_proposal_code_size,
+            "min_proposal_voting_period": params.min_proposal_voting_period,
+            "max_proposal_period": params.max_proposal_period,
+            "max_proposal_content_size": params.max_proposal_content_size,
+            "min_proposal_grace_epochs": params.min_proposal_grace_epochs,
+            "votes_retention_epochs": params.votes_retention_epochs,
+            "max_epoch_hooks_gas": params.max_epoch_hooks_gas,
+        },
+        "proposals": proposals,
+        "epoch_hooks": epoch_hooks,
+        "upgrade_plan": upgrade_plan,
+    }))
+}
+
+fn export_parameters<S>(storage: &S) -> storage_api::Result<Value>
+where
+    S: StorageRead,
+{
+    let params = parameters::read(storage)?;
+    let minimum_gas_price: serde_json::Map<String, Value> = params
+        .minimum_gas_price
+        .iter()
+        .map(|(token, price)| (token.to_string(), json!(price.to_string())))
+        .collect();
+
+    Ok(json!({
+        "max_tx_bytes": params.max_tx_bytes,
+        "epoch_duration": {
+            "min_num_of_blocks": params.epoch_duration.min_num_of_blocks,
+            "min_duration": params.epoch_duration.min_duration.0,
+        },
+        "max_expected_time_per_block": params.max_expected_time_per_block.0,
+        "max_proposal_bytes": params.max_proposal_bytes,
+        "max_block_gas": params.max_block_gas,
+        "max_encrypted_txs_share": params.max_encrypted_txs_share.to_string(),
+        "max_decrypted_txs_share": params.max_decrypted_txs_share.to_string(),
+        "min_protocol_txs_share": params.min_protocol_txs_share.to_string(),
+        "vp_whitelist": params.vp_whitelist,
+        "tx_whitelist": params.tx_whitelist,
+        "implicit_vp_code_hash": params.implicit_vp_code_hash.to_string(),
+        "epochs_per_year": params.epochs_per_year,
+        "max_signatures_per_transaction":
+            params.max_signatures_per_transaction,
+        "pos_gain_p": params.pos_gain_p.to_string(),
+        "pos_gain_d": params.pos_gain_d.to_string(),
+        "staked_ratio": params.staked_ratio.to_string(),
+        "pos_inflation_amount": params.pos_inflation_amount.to_string_native(),
+        "fee_unshielding_gas_limit": params.fee_unshielding_gas_limit,
+        "fee_unshielding_descriptions_limit":
+            params.fee_unshielding_descriptions_limit,
+        "minimum_gas_price": minimum_gas_price,
+        "wasm_limits": params.wasm_limits,
+        "storage_deposit_per_byte":
+            params.storage_deposit_per_byte.to_string_native(),
+        "blocked_addresses": params.blocked_addresses,
+    }))
+}
+
+fn export_pgf<S>(storage: &S) -> storage_api::Result<Value>
+where
+    S: StorageRead,
+{
+    let params = storage_api::pgf::get_parameters(storage)?;
+    let stewards: Vec<Value> = storage_api::pgf::get_stewards(storage)?
+        .into_iter()
+        .map(|steward| {
+            let reward_distribution: std::collections::BTreeMap<_, _> = steward
+                .reward_distribution
+                .into_iter()
+                .map(|(address, rate)| (address.to_string(), rate.to_string()))
+                .collect();
+            json!({
+                "address": steward.address.to_string(),
+                "reward_distribution": reward_distribution,
+            })
+        })
+        .collect();
+    let payments = storage_api::pgf::get_payments(storage)?;
+
+    Ok(json!({
+        "params": params,
+        "stewards": stewards,
+        "payments": payments,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use namada::core::ledger::governance::parameters::GovernanceParameters;
+    use namada::core::ledger::pgf::parameters::PgfParameters;
+    use namada::core::ledger::storage::testing::TestWlStorage;
+    use namada::ledger::storage_api::StorageWrite;
+    use namada::types::address::testing::established_address_1;
+
+    use super::*;
+
+    /// Test that the modules are parsed from their names
+    #[test]
+    fn test_module_from_str() {
+        for module in Module::ALL {
+            assert_eq!(module.to_string().parse::<Module>().unwrap(), module);
+        }
+        assert!(matches!(
+            "ibc".parse::<Module>(),
+            Err(Error::InvalidModule(module, _)) if module == "ibc"
+        ));
+    }
+
+    /// Test the export of the typed state of the modules with the summary of
+    /// their registered storage schemas
+    #[test]
+    fn test_export_module() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let gov_params = GovernanceParameters::default();
+        gov_params.init_storage(&mut storage)?;
+        let steward = established_address_1();
+        PgfParameters {
+            stewards: BTreeSet::from([steward.clone()]),
+            ..Default::default()
+        }
+        .init_storage(&mut storage)?;
+        // A key outside of the registered governance sub-spaces
+        let stray_key = Key::from(governance::ADDRESS.to_db_key())
+            .push(&"stray".to_owned())
+            .unwrap();
+        storage.write(&stray_key, 1_u64)?;
+        let registry = schema_registry();
+        let epoch = Epoch::default();
+
+        let governance =
+            export_module(&storage, &registry, Module::Governance, epoch)?;
+        assert_eq!(
+            governance["params"]["max_epoch_hooks_gas"],
+            json!(gov_params.max_epoch_hooks_gas)
+        );
+        assert_eq!(governance["proposals"], json!([]));
+        let schema = &governance["schema"];
+        assert_eq!(schema["sub_spaces"]["counter"], json!(1));
+        assert_eq!(schema["sub_spaces"]["proposal"], json!(0));
+        assert_eq!(schema["unregistered_keys"], json!([stray_key.to_string()]));
+
+        let pgf = export_module(&storage, &registry, Module::Pgf, epoch)?;
+        assert_eq!(pgf["stewards"][0]["address"], json!(steward.to_string()));
+        let schema = &pgf["schema"];
+        assert_eq!(schema["sub_spaces"]["stewards"], json!(1));
+        assert_eq!(schema["unregistered_keys"], json!([]));
+        Ok(())
+    }
+}
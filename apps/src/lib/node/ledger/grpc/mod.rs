@@ -0,0 +1,254 @@
+//! A gRPC server exposing typed query services for the balances, the
+//! proof-of-stake system, the governance proposals and the IBC tokens, so
+//! that they can be integrated with the same tooling as Cosmos SDK chains.
+//!
+//! The services are defined in `proto/query.proto`, from which their types
+//! are generated at build time. They answer the queries through the ABCI
+//! query path of the node's CometBFT RPC, so they see the same state as the
+//! queries of the client.
+
+use std::net::SocketAddr;
+
+use borsh::BorshDeserialize;
+
+use namada::ledger::ibc::storage::ibc_denom_key;
+use namada::types::address::{Address, InternalAddress};
+use namada::types::storage::Epoch;
+use namada_sdk::error::Error;
+use namada_sdk::rpc;
+use tokio::sync::oneshot;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+use self::query::bank_server::{Bank, BankServer};
+use self::query::governance_server::{Governance, GovernanceServer};
+use self::query::ibc_server::{Ibc, IbcServer};
+use self::query::pos_server::{Pos, PosServer};
+use self::query::*;
+use crate::facade::tendermint_rpc::HttpClient;
+
+/// The types and the services generated from `proto/query.proto`
+#[allow(missing_docs)]
+pub mod query {
+    tonic::include_proto!("query");
+}
+
+/// The implementation of all the query services
+#[derive(Clone, Debug)]
+pub struct QueryServices {
+    /// A client of the node's CometBFT RPC
+    client: HttpClient,
+}
+
+impl QueryServices {
+    /// Create the services for the node with the given CometBFT RPC address
+    pub fn new(rpc_address: SocketAddr) -> Result<Self, Error> {
+        let client =
+            HttpClient::new(format!("http://{}", rpc_address).as_str())
+                .map_err(|err| {
+                    Error::Other(format!(
+                        "Invalid CometBFT RPC address {rpc_address}: {err}"
+                    ))
+                })?;
+        Ok(Self { client })
+    }
+
+    /// Get the given epoch, or the current one if none is given
+    async fn epoch_or_current(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<Epoch, Status> {
+        match epoch {
+            Some(epoch) => Ok(Epoch(epoch)),
+            None => rpc::query_epoch(&self.client).await.map_err(query_error),
+        }
+    }
+}
+
+/// Decode an address of a request
+fn parse_address(address: &str) -> Result<Address, Status> {
+    Address::decode(address).map_err(|err| {
+        Status::invalid_argument(format!("Invalid address {address}: {err}"))
+    })
+}
+
+/// Convert a failed query to a gRPC status
+fn query_error(err: Error) -> Status {
+    Status::internal(err.to_string())
+}
+
+#[tonic::async_trait]
+impl Bank for QueryServices {
+    async fn balance(
+        &self,
+        request: Request<BalanceRequest>,
+    ) -> Result<Response<BalanceResponse>, Status> {
+        let request = request.into_inner();
+        let owner = parse_address(&request.owner)?;
+        let token = parse_address(&request.token)?;
+        let amount = rpc::get_token_balance(&self.client, &token, &owner)
+            .await
+            .map_err(query_error)?;
+        Ok(Response::new(BalanceResponse {
+            amount: amount.raw_amount().to_string(),
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl Pos for QueryServices {
+    async fn epoch(
+        &self,
+        _request: Request<EpochRequest>,
+    ) -> Result<Response<EpochResponse>, Status> {
+        let epoch = self.epoch_or_current(None).await?;
+        Ok(Response::new(EpochResponse { epoch: epoch.0 }))
+    }
+
+    async fn validators(
+        &self,
+        request: Request<ValidatorsRequest>,
+    ) -> Result<Response<ValidatorsResponse>, Status> {
+        let epoch = self.epoch_or_current(request.into_inner().epoch).await?;
+        let mut validators: Vec<String> =
+            rpc::get_all_validators(&self.client, epoch)
+                .await
+                .map_err(query_error)?
+                .into_iter()
+                .map(|validator| validator.encode())
+                .collect();
+        validators.sort();
+        Ok(Response::new(ValidatorsResponse { validators }))
+    }
+
+    async fn validator_stake(
+        &self,
+        request: Request<ValidatorStakeRequest>,
+    ) -> Result<Response<ValidatorStakeResponse>, Status> {
+        let request = request.into_inner();
+        let validator = parse_address(&request.validator)?;
+        let epoch = self.epoch_or_current(request.epoch).await?;
+        let stake = rpc::get_validator_stake(&self.client, epoch, &validator)
+            .await
+            .map_err(query_error)?;
+        Ok(Response::new(ValidatorStakeResponse {
+            stake: stake.raw_amount().to_string(),
+        }))
+    }
+
+    async fn bond(
+        &self,
+        request: Request<BondRequest>,
+    ) -> Result<Response<BondResponse>, Status> {
+        let request = request.into_inner();
+        let source = parse_address(&request.source)?;
+        let validator = parse_address(&request.validator)?;
+        let epoch = self.epoch_or_current(request.epoch).await?;
+        let amount =
+            rpc::query_bond(&self.client, &source, &validator, Some(epoch))
+                .await
+                .map_err(query_error)?;
+        Ok(Response::new(BondResponse {
+            amount: amount.raw_amount().to_string(),
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl Governance for QueryServices {
+    async fn proposal(
+        &self,
+        request: Request<ProposalRequest>,
+    ) -> Result<Response<ProposalResponse>, Status> {
+        let id = request.into_inner().id;
+        let proposal = rpc::query_proposal_by_id(&self.client, id)
+            .await
+            .map_err(query_error)?
+            .ok_or_else(|| {
+                Status::not_found(format!("Proposal {id} not found"))
+            })?;
+        Ok(Response::new(ProposalResponse {
+            id: proposal.id,
+            author: proposal.author.encode(),
+            r#type: proposal.r#type.to_string(),
+            content: proposal.content.into_iter().collect(),
+            voting_start_epoch: proposal.voting_start_epoch.0,
+            voting_end_epoch: proposal.voting_end_epoch.0,
+            grace_epoch: proposal.grace_epoch.0,
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl Ibc for QueryServices {
+    async fn denom(
+        &self,
+        request: Request<DenomRequest>,
+    ) -> Result<Response<DenomResponse>, Status> {
+        let request = request.into_inner();
+        let hash = match parse_address(&request.token)? {
+            Address::Internal(InternalAddress::IbcToken(hash)) => hash,
+            _ => {
+                return Err(Status::invalid_argument(format!(
+                    "{} is not an IBC token",
+                    request.token
+                )));
+            }
+        };
+        let owner = parse_address(&request.owner)?;
+        let key = ibc_denom_key(owner.to_string(), hash.to_string());
+        let (bytes, _proof) =
+            rpc::query_storage_value_bytes(&self.client, &key, None, false)
+                .await
+                .map_err(query_error)?;
+        let bytes = bytes.ok_or_else(|| {
+            Status::not_found(format!(
+                "No denomination of {} found for {}",
+                request.token, request.owner
+            ))
+        })?;
+        let denom = String::try_from_slice(&bytes).map_err(|err| {
+            Status::internal(format!(
+                "Failed to decode the denomination of {}: {err}",
+                request.token
+            ))
+        })?;
+        Ok(Response::new(DenomResponse { denom }))
+    }
+}
+
+/// Starts a gRPC server with all the query services. It shuts down if a signal
+/// is sent on the `abort_recv` channel.
+pub async fn serve(
+    listen_addr: SocketAddr,
+    rpc_address: SocketAddr,
+    abort_recv: oneshot::Receiver<()>,
+) {
+    let services = match QueryServices::new(rpc_address) {
+        Ok(services) => services,
+        Err(err) => {
+            tracing::error!("Failed to start the gRPC server: {}", err);
+            return;
+        }
+    };
+    tracing::info!(?listen_addr, "gRPC server starting");
+    let res = Server::builder()
+        .add_service(BankServer::new(services.clone()))
+        .add_service(PosServer::new(services.clone()))
+        .add_service(GovernanceServer::new(services.clone()))
+        .add_service(IbcServer::new(services))
+        .serve_with_shutdown(listen_addr, async move {
+            let _ = abort_recv.await;
+        })
+        .await;
+    match res {
+        Ok(()) => tracing::info!("gRPC server is no longer running"),
+        Err(err) => {
+            tracing::error!(
+                ?listen_addr,
+                "Failed to run the gRPC server: {}",
+                err
+            );
+        }
+    }
+}
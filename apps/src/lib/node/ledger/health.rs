@@ -0,0 +1,201 @@
+//! The health and readiness endpoints of the node, for the probes of
+//! orchestrators and load balancers.
+//!
+//! The health endpoint reports whether the node's CometBFT RPC and DB can be
+//! reached. The readiness endpoint additionally requires the node to be
+//! caught up with the network and to have recently committed a block, so that
+//! the traffic is routed around the nodes that are syncing or wedged.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use namada::ledger::parameters::storage as params_storage;
+use namada::types::time::{DateTimeUtc, Utc};
+use namada_sdk::rpc;
+use serde::Serialize;
+use tokio::sync::oneshot;
+use warp::http::StatusCode;
+use warp::reply::{self, Reply};
+use warp::Filter;
+
+use crate::config::HttpAccess;
+use crate::facade::tendermint_rpc::{Client, HttpClient};
+use crate::node::ledger::http_access::{self, rate_limit, RateLimiter};
+
+/// A node whose last committed block is older than this is not ready
+pub const MAX_BLOCK_AGE: Duration = Duration::from_secs(60);
+
+/// The status reported by both endpoints
+#[derive(Debug, Default, Serialize)]
+struct HealthStatus {
+    /// Whether the node's CometBFT RPC could be reached
+    rpc_available: bool,
+    /// Whether the node's DB could be read
+    db_available: bool,
+    /// Whether the node is catching up with the network
+    catching_up: Option<bool>,
+    /// The height of the last committed block
+    last_block_height: Option<u64>,
+    /// The time of the last committed block, in RFC3339 format
+    last_block_time: Option<String>,
+    /// The age of the last committed block, in seconds
+    last_block_age_secs: Option<u64>,
+    /// The number of peers the node is connected to
+    peers: Option<u64>,
+}
+
+impl HealthStatus {
+    /// Whether the node is running and its DB is available
+    fn is_healthy(&self) -> bool {
+        self.rpc_available && self.db_available
+    }
+
+    /// Whether the node is healthy, caught up and committing blocks
+    fn is_ready(&self) -> bool {
+        self.is_healthy()
+            && self.catching_up == Some(false)
+            && matches!(
+                self.last_block_age_secs,
+                Some(age) if age <= MAX_BLOCK_AGE.as_secs()
+            )
+    }
+}
+
+/// Starts a [`warp::Server`] with the `/health` and `/ready` endpoints. It
+/// shuts down if a signal is sent on the `abort_recv` channel.
+pub async fn serve(
+    listen_addr: SocketAddr,
+    rpc_address: SocketAddr,
+    access: HttpAccess,
+    limiter: RateLimiter,
+    abort_recv: oneshot::Receiver<()>,
+) {
+    let Some(client) = http_access::rpc_client(rpc_address, "health") else {
+        return;
+    };
+    let with_client = warp::any().map(move || client.clone());
+
+    let health = warp::path!("health")
+        .and(rate_limit(limiter.clone(), "health"))
+        .and(with_client.clone())
+        .and_then(health);
+    let ready = warp::path!("ready")
+        .and(rate_limit(limiter, "ready"))
+        .and(with_client)
+        .and_then(ready);
+    let routes = warp::get().and(health.or(ready));
+
+    http_access::serve("health", routes, listen_addr, &access, abort_recv).await
+}
+
+/// Whether the node is running and its DB is available
+async fn health(client: HttpClient) -> Result<reply::Response, Infallible> {
+    let status = health_status(&client).await;
+    Ok(status_reply(status.is_healthy(), &status))
+}
+
+/// Whether the node is healthy, caught up and committing blocks
+async fn ready(client: HttpClient) -> Result<reply::Response, Infallible> {
+    let status = health_status(&client).await;
+    Ok(status_reply(status.is_ready(), &status))
+}
+
+/// Query the status of the node. The fields that couldn't be queried are
+/// left empty.
+async fn health_status(client: &HttpClient) -> HealthStatus {
+    let mut status = HealthStatus::default();
+    match client.status().await {
+        Ok(response) => {
+            let sync_info = response.sync_info;
+            status.rpc_available = true;
+            status.catching_up = Some(sync_info.catching_up);
+            status.last_block_height =
+                Some(sync_info.latest_block_height.value());
+            let last_block_time = sync_info.latest_block_time.to_rfc3339();
+            // A block time ahead of the local clock counts as no age
+            status.last_block_age_secs =
+                DateTimeUtc::from_str(&last_block_time).ok().map(|time| {
+                    Utc::now()
+                        .signed_duration_since(time.0)
+                        .to_std()
+                        .unwrap_or_default()
+                        .as_secs()
+                });
+            status.last_block_time = Some(last_block_time);
+        }
+        Err(err) => {
+            tracing::debug!("Failed to query the status of the node: {err}");
+            return status;
+        }
+    }
+    match client.net_info().await {
+        Ok(response) => status.peers = Some(response.n_peers),
+        Err(err) => {
+            tracing::debug!("Failed to query the peers of the node: {err}")
+        }
+    }
+    // The parameters are always present in the storage of a running chain
+    let key = params_storage::get_epoch_duration_storage_key();
+    match rpc::query_has_storage_key(client, &key).await {
+        Ok(has_key) => status.db_available = has_key,
+        Err(err) => tracing::debug!("Failed to read from the DB: {err}"),
+    }
+    status
+}
+
+/// A JSON response with the status, which fails if the check didn't pass
+fn status_reply(passed: bool, status: &HealthStatus) -> reply::Response {
+    let code = if passed {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    reply::with_status(reply::json(status), code).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that a node is only ready when it's healthy, caught up and has
+    /// recently committed a block
+    #[test]
+    fn test_health_status_readiness() {
+        let status = HealthStatus {
+            rpc_available: true,
+            db_available: true,
+            catching_up: Some(false),
+            last_block_height: Some(10),
+            last_block_time: None,
+            last_block_age_secs: Some(5),
+            peers: Some(0),
+        };
+        assert!(status.is_healthy());
+        assert!(status.is_ready());
+
+        let syncing = HealthStatus {
+            catching_up: Some(true),
+            ..status
+        };
+        assert!(syncing.is_healthy());
+        assert!(!syncing.is_ready());
+
+        let wedged = HealthStatus {
+            catching_up: Some(false),
+            last_block_age_secs: Some(MAX_BLOCK_AGE.as_secs() + 1),
+            ..syncing
+        };
+        assert!(wedged.is_healthy());
+        assert!(!wedged.is_ready());
+
+        let no_db = HealthStatus {
+            db_available: false,
+            last_block_age_secs: Some(5),
+            ..wedged
+        };
+        assert!(!no_db.is_healthy());
+        assert!(!no_db.is_ready());
+    }
+}
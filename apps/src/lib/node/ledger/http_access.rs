@@ -0,0 +1,267 @@
+//! The CORS policy and the per-endpoint rate limits of the node's HTTP
+//! services, so that the query endpoints can be exposed publicly without a
+//! proxy in front of them.
+//!
+//! The requests are counted per client IP address in windows of
+//! [`RATE_LIMIT_WINDOW`]. A request over the limit is rejected with a
+//! `429 Too Many Requests` response. The limits can be updated while the
+//! services are running, when the config is reloaded.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot;
+use warp::http::StatusCode;
+use warp::reply::{self, Reply};
+use warp::{Filter, Rejection};
+
+use crate::config::HttpAccess;
+use crate::facade::tendermint_rpc::HttpClient;
+
+/// The window in which the requests of a client are counted
+pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Counts the requests of the clients to each endpoint. Thread-safe. The
+/// clones of a rate limiter share its limits and counts.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    /// The limits of the endpoints
+    limits: Arc<RwLock<Limits>>,
+    /// The current window of each client of an endpoint
+    windows: Arc<Mutex<HashMap<(&'static str, IpAddr), Window>>>,
+}
+
+/// The rate limits of the config
+#[derive(Debug, Default)]
+struct Limits {
+    /// The limits of the endpoints that have their own
+    endpoints: BTreeMap<String, u32>,
+    /// The limit of the other endpoints
+    default: Option<u32>,
+}
+
+impl From<&HttpAccess> for Limits {
+    fn from(config: &HttpAccess) -> Self {
+        Self {
+            endpoints: config.rate_limits.clone(),
+            default: config.default_rate_limit,
+        }
+    }
+}
+
+/// The requests of a client to an endpoint in the current window
+#[derive(Debug)]
+struct Window {
+    start: Instant,
+    requests: u32,
+}
+
+/// A request was rejected because its client is over the rate limit
+#[derive(Debug)]
+struct RateLimited;
+
+impl warp::reject::Reject for RateLimited {}
+
+impl RateLimiter {
+    /// Create a rate limiter with the limits of the config
+    pub fn new(config: &HttpAccess) -> Self {
+        Self {
+            limits: Arc::new(RwLock::new(config.into())),
+            windows: Default::default(),
+        }
+    }
+
+    /// Replace the limits with the ones of the config. The requests already
+    /// counted in the current windows are kept.
+    pub fn update(&self, config: &HttpAccess) {
+        *self.limits.write().unwrap() = config.into();
+    }
+
+    /// Count a request of a client to an endpoint and check if it's within
+    /// the limit
+    fn check(&self, endpoint: &'static str, client: IpAddr) -> bool {
+        let limit = {
+            let limits = self.limits.read().unwrap();
+            match limits.endpoints.get(endpoint).copied().or(limits.default) {
+                Some(limit) => limit,
+                None => return true,
+            }
+        };
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        // Forget the clients whose window has ended
+        windows.retain(|_, window| {
+            now.duration_since(window.start) < RATE_LIMIT_WINDOW
+        });
+        let window = windows.entry((endpoint, client)).or_insert(Window {
+            start: now,
+            requests: 0,
+        });
+        window.requests = window.requests.saturating_add(1);
+        window.requests <= limit
+    }
+}
+
+/// A filter that rejects the requests to the endpoint of the clients that
+/// are over its rate limit. Use [`with_access`] to reply to the rejections.
+pub fn rate_limit(
+    limiter: RateLimiter,
+    endpoint: &'static str,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |addr: Option<SocketAddr>| {
+            let limiter = limiter.clone();
+            async move {
+                match addr {
+                    Some(addr) if !limiter.check(endpoint, addr.ip()) => {
+                        Err(warp::reject::custom(RateLimited))
+                    }
+                    _ => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Reply to the requests rejected by [`rate_limit`]. The other rejections are
+/// passed through.
+async fn recover(err: Rejection) -> Result<reply::Response, Rejection> {
+    if err.find::<RateLimited>().is_some() {
+        Ok(reply::with_status(
+            reply::json(&serde_json::json!({"error": "Too many requests"})),
+            StatusCode::TOO_MANY_REQUESTS,
+        )
+        .into_response())
+    } else {
+        Err(err)
+    }
+}
+
+/// The CORS policy of the config. Only `GET` requests are allowed.
+fn cors(config: &HttpAccess) -> warp::cors::Builder {
+    let cors = warp::cors().allow_method("GET");
+    if config
+        .cors_allowed_origins
+        .iter()
+        .any(|origin| origin == "*")
+    {
+        cors.allow_any_origin()
+    } else {
+        cors.allow_origins(
+            config.cors_allowed_origins.iter().map(String::as_str),
+        )
+    }
+}
+
+/// Reply to the requests rejected by [`rate_limit`] and wrap the replies in
+/// the CORS policy of the config
+pub fn with_access<F>(
+    routes: F,
+    config: &HttpAccess,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone
+where
+    F: Filter<Error = Rejection> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    routes.recover(recover).with(cors(config))
+}
+
+/// Starts a [`warp::Server`] with the routes and the access policy of the
+/// config. It shuts down if a signal is sent on the `abort_recv` channel. The
+/// `name` of the server is used in its logs.
+pub async fn serve<F>(
+    name: &str,
+    routes: F,
+    listen_addr: SocketAddr,
+    config: &HttpAccess,
+    abort_recv: oneshot::Receiver<()>,
+) where
+    F: Filter<Error = Rejection> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    let server = warp::serve(with_access(routes, config))
+        .try_bind_with_graceful_shutdown(listen_addr, async move {
+            let _ = abort_recv.await;
+        });
+    match server {
+        Ok((addr, future)) => {
+            tracing::info!(?addr, "Started the {name} server");
+            future.await;
+            tracing::info!("The {name} server is no longer running");
+        }
+        Err(err) => {
+            tracing::error!(
+                ?listen_addr,
+                "Failed to start the {name} server: {}",
+                err
+            );
+        }
+    }
+}
+
+/// A client of the node's CometBFT RPC at the given address, or `None` if it
+/// can't be created, in which case the error is logged
+pub fn rpc_client(rpc_address: SocketAddr, name: &str) -> Option<HttpClient> {
+    HttpClient::new(format!("http://{}", rpc_address).as_str())
+        .map_err(|err| {
+            tracing::error!(
+                ?rpc_address,
+                "Failed to create the RPC client of the {name} server: {}",
+                err
+            );
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the requests of a client over the limit of an endpoint are
+    /// rejected, without affecting the other endpoints and clients
+    #[test]
+    fn test_rate_limiter() {
+        let config = HttpAccess {
+            cors_allowed_origins: vec![],
+            default_rate_limit: Some(2),
+            rate_limits: BTreeMap::from([("health".to_string(), 1)]),
+        };
+        let limiter = RateLimiter::new(&config);
+        let client: IpAddr = [127, 0, 0, 1].into();
+        let other_client: IpAddr = [127, 0, 0, 2].into();
+
+        assert!(limiter.check("health", client));
+        assert!(!limiter.check("health", client));
+        assert!(limiter.check("health", other_client));
+
+        assert!(limiter.check("balance", client));
+        assert!(limiter.check("balance", client));
+        assert!(!limiter.check("balance", client));
+
+        // Without any limit, every request is allowed
+        let unlimited = RateLimiter::new(&HttpAccess::default());
+        for _ in 0..10 {
+            assert!(unlimited.check("balance", client));
+        }
+    }
+
+    /// Test that the updated limits apply to the clones of a rate limiter
+    #[test]
+    fn test_rate_limiter_update() {
+        let limiter = RateLimiter::new(&HttpAccess::default());
+        let clone = limiter.clone();
+        let client: IpAddr = [127, 0, 0, 1].into();
+
+        assert!(clone.check("health", client));
+        limiter.update(&HttpAccess {
+            cors_allowed_origins: vec![],
+            default_rate_limit: None,
+            rate_limits: BTreeMap::from([("health".to_string(), 1)]),
+        });
+        assert!(clone.check("health", client));
+        assert!(!clone.check("health", client));
+        assert!(clone.check("balance", client));
+    }
+}
@@ -1,6 +1,14 @@
 mod abortable;
 mod broadcaster;
+pub mod config_reload;
 pub mod ethereum_oracle;
+pub mod event_subscriptions;
+pub mod export;
+pub mod grpc;
+pub mod health;
+pub mod http_access;
+pub mod replay;
+pub mod rest;
 pub mod shell;
 pub mod shims;
 pub mod storage;
@@ -20,12 +28,14 @@ use namada::types::storage::Key;
 use namada_sdk::tendermint::abci::request::CheckTxKind;
 use once_cell::unsync::Lazy;
 use sysinfo::{RefreshKind, System, SystemExt};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio::task;
 use tower::ServiceBuilder;
 
 use self::abortable::AbortableSpawner;
+use self::config_reload::ReloadableConfig;
 use self::ethereum_oracle::last_processed_block;
+use self::http_access::RateLimiter;
 use self::shell::EthereumOracleChannels;
 use self::shims::abcipp_shim::AbciService;
 use crate::cli::args;
@@ -144,7 +154,7 @@ impl Shell {
                     CheckTxKind::Recheck => MempoolTxType::RecheckTransaction,
                 };
                 let r#type = mempool_tx_type;
-                Ok(Response::CheckTx(self.mempool_validate(&tx.tx, r#type)))
+                Ok(Response::CheckTx(self.check_tx(&tx.tx, r#type)))
             }
             Request::ListSnapshots => {
                 Ok(Response::ListSnapshots(Default::default()))
@@ -228,6 +238,29 @@ pub fn rollback(config: config::Ledger) -> Result<(), shell::Error> {
     shell::rollback(config)
 }
 
+/// Replay historical blocks on top of the local DB, checking that they
+/// reproduce the app hashes of the chain
+pub fn replay(
+    config: config::Ledger,
+    wasm_dir: PathBuf,
+    args: args::LedgerReplay,
+) -> Result<(), replay::Error> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(replay::run(config, wasm_dir, args))
+}
+
+/// Export the typed state of the ledger's modules at a block height into a
+/// JSON file
+pub fn export(
+    config: config::Ledger,
+    args: args::LedgerExport,
+) -> Result<(), export::Error> {
+    export::run(config, args)
+}
+
 /// Runs and monitors a few concurrent tasks.
 ///
 /// This includes:
@@ -238,6 +271,8 @@ pub fn rollback(config: config::Ledger) -> Result<(), shell::Error> {
 ///   - An Ethereum full node.
 ///   - An oracle, to receive events from the Ethereum full node, and forward
 ///     them to the ledger.
+///   - A gRPC server for the typed query services, if enabled.
+///   - A REST gateway for the common queries, if enabled.
 ///
 /// All must be alive for correct functioning.
 async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
@@ -250,6 +285,12 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
     // Start Tendermint node
     let tendermint_node = start_tendermint(&mut spawner, &config);
 
+    // Reload the non-consensus settings of the config on hangup signals. The
+    // rate limiter is shared by the HTTP services, so that their limits are
+    // reloaded.
+    let rate_limiter = RateLimiter::new(&config.shell.http_access);
+    let config_reload = config_reload::spawn(&config, rate_limiter.clone());
+
     // Start oracle if necessary
     let (eth_oracle_channels, eth_oracle) =
         match maybe_start_ethereum_oracle(&mut spawner, &config).await {
@@ -259,28 +300,51 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
             }
         };
 
+    // Start the gRPC server if enabled
+    let grpc_server = maybe_start_grpc_server(&mut spawner, &config);
+
+    // Start the REST gateway if enabled
+    let rest_server =
+        maybe_start_rest_server(&mut spawner, &config, rate_limiter.clone());
+
+    // Start the health and readiness endpoints if enabled
+    let health_server =
+        maybe_start_health_server(&mut spawner, &config, rate_limiter.clone());
+
     tracing::info!("Loading MASP verifying keys.");
     let _ = namada_sdk::masp::preload_verifying_keys();
     tracing::info!("Done loading MASP verifying keys.");
 
-    // Start ABCI server and broadcaster (the latter only if we are a validator
-    // node)
-    let (abci, broadcaster, shell_handler) = start_abci_broadcaster_shell(
-        &mut spawner,
-        eth_oracle_channels,
-        wasm_dir,
-        setup_data,
-        config,
-    );
+    // Start ABCI server, broadcaster (only if we are a validator node) and
+    // event subscriptions server (only if enabled)
+    let (abci, broadcaster, event_subscriptions, shell_handler) =
+        start_abci_broadcaster_shell(
+            &mut spawner,
+            eth_oracle_channels,
+            wasm_dir,
+            setup_data,
+            config,
+            rate_limiter,
+            config_reload,
+        );
 
     // Wait for interrupt signal or abort message
     let aborted = spawner.wait_for_abort().await.child_terminated();
 
     // Wait for all managed tasks to finish.
-    let res = tokio::try_join!(tendermint_node, abci, eth_oracle, broadcaster);
+    let res = tokio::try_join!(
+        tendermint_node,
+        abci,
+        eth_oracle,
+        broadcaster,
+        event_subscriptions,
+        grpc_server,
+        rest_server,
+        health_server
+    );
 
     match res {
-        Ok((tendermint_res, abci_res, _, _)) => {
+        Ok((tendermint_res, abci_res, _, _, _, _, _, _)) => {
             // we ignore errors on user-initiated shutdown
             if aborted {
                 if let Err(err) = tendermint_res {
@@ -321,8 +385,14 @@ async fn run_aux_setup(
     config: &config::Ledger,
     wasm_dir: &PathBuf,
 ) -> RunAuxSetup {
-    // Prefetch needed wasm artifacts
-    wasm_loader::pre_fetch_wasm(wasm_dir).await;
+    // Fetch the missing wasm artifacts and check them against the manifest
+    // of the chain
+    wasm_loader::prepare_wasm(
+        config.chain_dir(),
+        wasm_dir,
+        config.shell.dev_wasm_reload,
+    )
+    .await;
 
     // Find the system available memory
     let available_memory_bytes = Lazy::new(|| {
@@ -409,18 +479,21 @@ async fn run_aux_setup(
     }
 }
 
-/// This function spawns an ABCI server and a [`Broadcaster`] into the
-/// asynchronous runtime. Additionally, it executes a shell in
-/// a new OS thread, to drive the ABCI server.
+/// This function spawns an ABCI server, a [`Broadcaster`] and an event
+/// subscriptions server into the asynchronous runtime. Additionally, it
+/// executes a shell in a new OS thread, to drive the ABCI server.
 fn start_abci_broadcaster_shell(
     spawner: &mut AbortableSpawner,
     eth_oracle: Option<EthereumOracleChannels>,
     wasm_dir: PathBuf,
     setup_data: RunAuxSetup,
     config: config::Ledger,
+    rate_limiter: RateLimiter,
+    config_reload: watch::Receiver<ReloadableConfig>,
 ) -> (
     task::JoinHandle<shell::Result<()>>,
     task::JoinHandle<()>,
+    task::JoinHandle<()>,
     thread::JoinHandle<()>,
 ) {
     let rpc_address =
@@ -469,8 +542,10 @@ fn start_abci_broadcaster_shell(
     let tendermint_mode = config.shell.tendermint_mode.clone();
     let proxy_app_address =
         convert_tm_addr_to_socket_addr(&config.cometbft.proxy_app);
+    let event_subscriptions_address = config.shell.event_subscriptions_address;
+    let http_access = config.shell.http_access.clone();
 
-    let (shell, abci_service, service_handle) = AbcippShim::new(
+    let (mut shell, abci_service, service_handle) = AbcippShim::new(
         config,
         wasm_dir,
         broadcaster_sender,
@@ -479,6 +554,37 @@ fn start_abci_broadcaster_shell(
         vp_wasm_compilation_cache,
         tx_wasm_compilation_cache,
     );
+    shell.watch_config_reload(config_reload);
+
+    // Start the event subscriptions server
+    let event_subscriptions = match event_subscriptions_address {
+        Some(listen_addr) => {
+            let event_bus = shell.event_bus();
+            let (es_abort_send, es_abort_recv) =
+                tokio::sync::oneshot::channel::<()>();
+
+            spawner
+                .spawn_abortable(
+                    "Event subscriptions",
+                    move |aborter| async move {
+                        event_subscriptions::serve(
+                            listen_addr,
+                            event_bus,
+                            http_access,
+                            rate_limiter,
+                            es_abort_recv,
+                        )
+                        .await;
+
+                        drop(aborter);
+                    },
+                )
+                .with_cleanup(async move {
+                    let _ = es_abort_send.send(());
+                })
+        }
+        None => spawn_dummy_task(()),
+    };
 
     // Channel for signalling shut down to ABCI server
     let (abci_abort_send, abci_abort_recv) = tokio::sync::oneshot::channel();
@@ -518,7 +624,7 @@ fn start_abci_broadcaster_shell(
         })
         .expect("Must be able to start a thread for the shell");
 
-    (abci, broadcaster, shell_handler)
+    (abci, broadcaster, event_subscriptions, shell_handler)
 }
 
 /// Runs the an asynchronous ABCI server with four sub-components for consensus,
@@ -630,6 +736,102 @@ fn start_tendermint(
         })
 }
 
+/// Potentially starts a gRPC server for the typed query services, which
+/// forwards the queries to the CometBFT RPC of the node.
+fn maybe_start_grpc_server(
+    spawner: &mut AbortableSpawner,
+    config: &config::Ledger,
+) -> task::JoinHandle<()> {
+    let listen_addr = match config.shell.grpc_address {
+        Some(listen_addr) => listen_addr,
+        None => return spawn_dummy_task(()),
+    };
+    let rpc_address =
+        convert_tm_addr_to_socket_addr(&config.cometbft.rpc.laddr);
+    let (grpc_abort_send, grpc_abort_recv) =
+        tokio::sync::oneshot::channel::<()>();
+
+    spawner
+        .spawn_abortable("gRPC", move |aborter| async move {
+            grpc::serve(listen_addr, rpc_address, grpc_abort_recv).await;
+
+            drop(aborter);
+        })
+        .with_cleanup(async move {
+            let _ = grpc_abort_send.send(());
+        })
+}
+
+/// Potentially starts a REST gateway for the common queries, which forwards
+/// the queries to the CometBFT RPC of the node.
+fn maybe_start_rest_server(
+    spawner: &mut AbortableSpawner,
+    config: &config::Ledger,
+    rate_limiter: RateLimiter,
+) -> task::JoinHandle<()> {
+    let listen_addr = match config.shell.rest_address {
+        Some(listen_addr) => listen_addr,
+        None => return spawn_dummy_task(()),
+    };
+    let rpc_address =
+        convert_tm_addr_to_socket_addr(&config.cometbft.rpc.laddr);
+    let access = config.shell.http_access.clone();
+    let (rest_abort_send, rest_abort_recv) =
+        tokio::sync::oneshot::channel::<()>();
+
+    spawner
+        .spawn_abortable("REST", move |aborter| async move {
+            rest::serve(
+                listen_addr,
+                rpc_address,
+                access,
+                rate_limiter,
+                rest_abort_recv,
+            )
+            .await;
+
+            drop(aborter);
+        })
+        .with_cleanup(async move {
+            let _ = rest_abort_send.send(());
+        })
+}
+
+/// Potentially starts the health and readiness endpoints, which check the
+/// status of the node through its CometBFT RPC.
+fn maybe_start_health_server(
+    spawner: &mut AbortableSpawner,
+    config: &config::Ledger,
+    rate_limiter: RateLimiter,
+) -> task::JoinHandle<()> {
+    let listen_addr = match config.shell.health_address {
+        Some(listen_addr) => listen_addr,
+        None => return spawn_dummy_task(()),
+    };
+    let rpc_address =
+        convert_tm_addr_to_socket_addr(&config.cometbft.rpc.laddr);
+    let access = config.shell.http_access.clone();
+    let (health_abort_send, health_abort_recv) =
+        tokio::sync::oneshot::channel::<()>();
+
+    spawner
+        .spawn_abortable("Health", move |aborter| async move {
+            health::serve(
+                listen_addr,
+                rpc_address,
+                access,
+                rate_limiter,
+                health_abort_recv,
+            )
+            .await;
+
+            drop(aborter);
+        })
+        .with_cleanup(async move {
+            let _ = health_abort_send.send(());
+        })
+}
+
 /// Represents a [`tokio::task`] in which an Ethereum oracle may be running, and
 /// if so, channels for communicating with it.
 enum EthereumOracleTask {
@@ -0,0 +1,365 @@
+//! Replay of historical blocks.
+//!
+//! The blocks are fetched from the RPC of a CometBFT node, e.g. an archive
+//! node, and re-executed on top of the local DB, which must be at the height
+//! preceding the first replayed block. After every block, the app hash is
+//! checked against the one the chain committed to in the header of the next
+//! block, and the events are compared with the chain's block results. This
+//! checks that a new version of the ledger reproduces the state of the version
+//! that produced the blocks.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use namada::ledger::events::Event;
+use namada::types::storage::BlockHeight;
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+use super::shell::{self, Shell};
+use super::shims::abcipp_shim::finalize_block_request;
+use super::shims::abcipp_shim_types::shim::TxBytes;
+use crate::cli::args;
+use crate::client::failover::{FailoverClient, LedgerAddresses};
+use crate::config::{self, TendermintMode};
+use crate::facade::tendermint::abci::types::{
+    BlockSignatureInfo, CommitInfo, Misbehavior, MisbehaviorKind, Validator,
+    VoteInfo,
+};
+use crate::facade::tendermint::block::{Block, BlockIdFlag, CommitSig, Height};
+use crate::facade::tendermint::evidence::Evidence;
+use crate::facade::tendermint::v0_37::abci::request;
+use crate::facade::tendermint::{abci, account};
+use crate::facade::tendermint_rpc::{self, Client, Paging};
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid replay range: {from} is above {to}")]
+    InvalidRange { from: BlockHeight, to: BlockHeight },
+    #[error(
+        "The local DB is at height {last}, so the replay must start from \
+         height {expected}"
+    )]
+    StartHeight {
+        last: BlockHeight,
+        expected: BlockHeight,
+    },
+    #[error("Failed to query the block {0} from the node: {1}")]
+    Rpc(BlockHeight, tendermint_rpc::Error),
+    #[error(
+        "The app hash after block {height} is {replayed}, but the chain \
+         committed to {expected}"
+    )]
+    AppHashMismatch {
+        height: BlockHeight,
+        replayed: String,
+        expected: String,
+    },
+    #[error("Failed to finalize the block {0}: {1}")]
+    FinalizeBlock(BlockHeight, shell::Error),
+}
+
+/// The timing of a replayed block
+#[derive(Debug)]
+struct BlockTiming {
+    height: BlockHeight,
+    num_txs: usize,
+    elapsed: Duration,
+}
+
+/// Replay the blocks in the given range on top of the local DB
+pub async fn run(
+    mut config: config::Ledger,
+    wasm_dir: PathBuf,
+    args::LedgerReplay {
+        from,
+        to,
+        ledger_address,
+    }: args::LedgerReplay,
+) -> Result<(), Error> {
+    if from > to {
+        return Err(Error::InvalidRange { from, to });
+    }
+    let addresses = ledger_address.unwrap_or_else(|| {
+        LedgerAddresses::from(config.cometbft.rpc.laddr.clone())
+    });
+    let client =
+        FailoverClient::new(addresses).map_err(|err| Error::Rpc(from, err))?;
+
+    let setup_data = super::run_aux_setup(&config, &wasm_dir).await;
+    let db_cache = rocksdb::Cache::new_lru_cache(
+        setup_data.db_block_cache_size_bytes as usize,
+    );
+    // The replay doesn't take part in consensus, so it runs as a full node
+    config.shell.tendermint_mode = TendermintMode::Full;
+    let (broadcaster_sender, _broadcaster_receiver) = mpsc::unbounded_channel();
+    let mut shell: Shell = Shell::new(
+        config,
+        wasm_dir,
+        broadcaster_sender,
+        None,
+        Some(&db_cache),
+        setup_data.vp_wasm_compilation_cache,
+        setup_data.tx_wasm_compilation_cache,
+    );
+
+    let last_height = shell.wl_storage.storage.get_last_block_height();
+    if last_height.next_height() != from {
+        return Err(Error::StartHeight {
+            last: last_height,
+            expected: last_height.next_height(),
+        });
+    }
+
+    let mut block = fetch_block(&client, from).await?;
+    // The state the blocks are replayed on must be the one of the chain
+    check_app_hash(&shell, last_height, &block)?;
+
+    let mut timings = Vec::new();
+    let mut num_event_mismatches = 0_u64;
+    let mut height = from;
+    loop {
+        let txs: Vec<TxBytes> =
+            block.data.iter().cloned().map(Into::into).collect();
+        let num_txs = txs.len();
+        let begin_block = begin_block_request(&client, block).await?;
+
+        let start = Instant::now();
+        let req = finalize_block_request(&shell, begin_block, txs);
+        let response = shell
+            .finalize_block(req)
+            .map_err(|err| Error::FinalizeBlock(height, err))?;
+        shell.commit();
+        let elapsed = start.elapsed();
+        tracing::info!(
+            %height,
+            num_txs,
+            elapsed_ms = elapsed.as_millis() as u64,
+            "Replayed block"
+        );
+        timings.push(BlockTiming {
+            height,
+            num_txs,
+            elapsed,
+        });
+
+        let expected_events = client
+            .block_results(to_tm_height(height))
+            .await
+            .map_err(|err| Error::Rpc(height, err))?
+            .end_block_events
+            .unwrap_or_default();
+        if !events_match(&response.events, &expected_events) {
+            num_event_mismatches += 1;
+            tracing::warn!(
+                %height,
+                "The events of the replayed block differ from the chain's \
+                 block results"
+            );
+        }
+
+        // The app hash of a block is committed to in the header of the next
+        // one, which may not have been produced yet for the last block
+        let next_height = height.next_height();
+        let next_block = if height < to {
+            Some(fetch_block(&client, next_height).await?)
+        } else {
+            fetch_block(&client, next_height).await.ok()
+        };
+        match next_block {
+            Some(next_block) => {
+                check_app_hash(&shell, height, &next_block)?;
+                block = next_block;
+            }
+            None => {
+                tracing::warn!(
+                    %height,
+                    "The app hash of the last replayed block cannot be \
+                     checked, as the next block hasn't been produced yet"
+                );
+                break;
+            }
+        }
+        if height == to {
+            break;
+        }
+        height = next_height;
+    }
+
+    print_summary(&timings, num_event_mismatches);
+    Ok(())
+}
+
+/// Fetch the block at the given height
+async fn fetch_block(
+    client: &FailoverClient,
+    height: BlockHeight,
+) -> Result<Block, Error> {
+    client
+        .block(to_tm_height(height))
+        .await
+        .map(|response| response.block)
+        .map_err(|err| Error::Rpc(height, err))
+}
+
+/// Rebuild the begin block request that CometBFT sent for the given block
+async fn begin_block_request(
+    client: &FailoverClient,
+    block: Block,
+) -> Result<request::BeginBlock, Error> {
+    let height = BlockHeight(block.header.height.value());
+    // The votes of the last commit are made by the validators of the
+    // previous height, in the same order as their signatures
+    let (round, votes) = match &block.last_commit {
+        Some(commit) if commit.height.value() > 0 => {
+            let validators = client
+                .validators(commit.height, Paging::All)
+                .await
+                .map_err(|err| Error::Rpc(height, err))?
+                .validators;
+            let votes = commit
+                .signatures
+                .iter()
+                .zip(validators)
+                .map(|(signature, validator)| VoteInfo {
+                    validator: Validator {
+                        address: raw_address(&validator.address),
+                        power: validator.power,
+                    },
+                    sig_info: BlockSignatureInfo::Flag(match signature {
+                        CommitSig::BlockIdFlagAbsent => BlockIdFlag::Absent,
+                        CommitSig::BlockIdFlagCommit { .. } => {
+                            BlockIdFlag::Commit
+                        }
+                        CommitSig::BlockIdFlagNil { .. } => BlockIdFlag::Nil,
+                    }),
+                })
+                .collect();
+            (commit.round, votes)
+        }
+        _ => (Default::default(), vec![]),
+    };
+    let byzantine_validators = block
+        .evidence
+        .iter()
+        .flat_map(|evidence| match evidence {
+            Evidence::DuplicateVote(evidence) => vec![Misbehavior {
+                kind: MisbehaviorKind::DuplicateVote,
+                validator: Validator {
+                    address: raw_address(&evidence.vote_a.validator_address),
+                    power: evidence.validator_power,
+                },
+                height: evidence.vote_a.height,
+                time: evidence.timestamp,
+                total_voting_power: evidence.total_voting_power,
+            }],
+            Evidence::LightClientAttack(evidence) => evidence
+                .byzantine_validators
+                .iter()
+                .map(|validator| Misbehavior {
+                    kind: MisbehaviorKind::LightClientAttack,
+                    validator: Validator {
+                        address: raw_address(&validator.address),
+                        power: validator.power,
+                    },
+                    height: evidence.common_height,
+                    time: evidence.timestamp,
+                    total_voting_power: evidence.total_voting_power,
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(request::BeginBlock {
+        hash: block.header.hash(),
+        header: block.header,
+        last_commit_info: CommitInfo { round, votes },
+        byzantine_validators,
+    })
+}
+
+/// Check that the app hash of the local state at the given height is the one
+/// committed to by the chain in the header of the next block
+fn check_app_hash(
+    shell: &Shell,
+    height: BlockHeight,
+    next_block: &Block,
+) -> Result<(), Error> {
+    let replayed = shell.wl_storage.storage.merkle_root().to_string();
+    let expected = next_block.header.app_hash.to_string();
+    if replayed.eq_ignore_ascii_case(&expected) {
+        Ok(())
+    } else {
+        Err(Error::AppHashMismatch {
+            height,
+            replayed,
+            expected,
+        })
+    }
+}
+
+/// Check that the events of a replayed block are the end block events of the
+/// block on chain. The order of the attributes of an event is ignored.
+fn events_match(replayed: &[Event], expected: &[abci::Event]) -> bool {
+    let replayed = replayed.iter().map(|event| {
+        (
+            event.event_type.to_string(),
+            event
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    });
+    let expected = expected.iter().map(|event| {
+        (
+            event.kind.clone(),
+            event
+                .attributes
+                .iter()
+                .map(|attr| (attr.key.clone(), attr.value.clone()))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    });
+    replayed.eq(expected)
+}
+
+/// Print the number of replayed blocks and their timings
+fn print_summary(timings: &[BlockTiming], num_event_mismatches: u64) {
+    let total: Duration = timings.iter().map(|timing| timing.elapsed).sum();
+    let num_txs: usize = timings.iter().map(|timing| timing.num_txs).sum();
+    println!(
+        "Replayed {} blocks with {num_txs} txs in {:.3}s.",
+        timings.len(),
+        total.as_secs_f64()
+    );
+    if let Some(slowest) = timings.iter().max_by_key(|timing| timing.elapsed) {
+        println!(
+            "Mean block time: {:.3}ms, slowest block: {} with {} txs in \
+             {:.3}ms.",
+            total.as_secs_f64() * 1000.0 / timings.len() as f64,
+            slowest.height,
+            slowest.num_txs,
+            slowest.elapsed.as_secs_f64() * 1000.0
+        );
+    }
+    println!("All the checked app hashes match the chain's.");
+    if num_event_mismatches > 0 {
+        println!(
+            "The events of {num_event_mismatches} blocks differ from the \
+             chain's block results."
+        );
+    }
+}
+
+fn to_tm_height(height: BlockHeight) -> Height {
+    Height::try_from(height.0).expect("The block height should be valid")
+}
+
+fn raw_address(address: &account::Id) -> [u8; 20] {
+    address
+        .as_bytes()
+        .try_into()
+        .expect("A CometBFT account ID should have 20 bytes")
+}
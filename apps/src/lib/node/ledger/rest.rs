@@ -0,0 +1,384 @@
+//! A REST gateway for the common queries, so that web frontends can read the
+//! balances, the validators and the governance proposals without a custom
+//! indexer.
+//!
+//! Besides these endpoints, every query of the RPC router is served under
+//! [`RPC_PREFIX`] at the path of its route, with its borsh-encoded response
+//! data. The endpoints are described by the OpenAPI document served on
+//! [`OPENAPI_ENDPOINT`], whose RPC paths are generated from the routes of the
+//! router. Like the gRPC services, they answer the queries through the ABCI
+//! query path of the node's CometBFT RPC. Amounts are the decimal strings of
+//! the raw token amounts, i.e. without denomination.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use data_encoding::HEXLOWER;
+use namada::ledger::queries::{Client, Route, RouteSegment, RPC};
+use namada::types::address::Address;
+use namada::types::storage::Epoch;
+use namada_sdk::rpc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+use warp::http::StatusCode;
+use warp::path::Tail;
+use warp::reply::{self, Reply};
+use warp::Filter;
+
+use crate::config::HttpAccess;
+use crate::facade::tendermint_rpc::HttpClient;
+use crate::node::ledger::http_access::{self, rate_limit, RateLimiter};
+
+/// The endpoint of the OpenAPI document, under the API prefix
+pub const OPENAPI_ENDPOINT: &str = "/api/v1/openapi.json";
+
+/// The prefix of the paths of the queries of the RPC router
+pub const RPC_PREFIX: &str = "/api/v1/rpc";
+
+/// The query parameters of the endpoints that take an epoch
+#[derive(Debug, Default, Deserialize)]
+struct EpochQuery {
+    /// Defaults to the current epoch
+    epoch: Option<u64>,
+}
+
+/// The body of an error response
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// The response of a handler
+type HandlerResult = Result<reply::Response, Infallible>;
+
+/// Starts a [`warp::Server`] with all the REST endpoints. It shuts down if a
+/// signal is sent on the `abort_recv` channel.
+pub async fn serve(
+    listen_addr: SocketAddr,
+    rpc_address: SocketAddr,
+    access: HttpAccess,
+    limiter: RateLimiter,
+    abort_recv: oneshot::Receiver<()>,
+) {
+    let Some(client) = http_access::rpc_client(rpc_address, "REST") else {
+        return;
+    };
+    let with_client = warp::any().map(move || client.clone());
+
+    let balance = warp::path!("api" / "v1" / "balance" / String / String)
+        .and(rate_limit(limiter.clone(), "balance"))
+        .and(with_client.clone())
+        .and_then(balance);
+    let validators = warp::path!("api" / "v1" / "validators")
+        .and(rate_limit(limiter.clone(), "validators"))
+        .and(warp::query::<EpochQuery>())
+        .and(with_client.clone())
+        .and_then(validators);
+    let proposal = warp::path!("api" / "v1" / "proposals" / u64)
+        .and(rate_limit(limiter.clone(), "proposals"))
+        .and(with_client.clone())
+        .and_then(proposal);
+    let rpc_query = warp::path!("api" / "v1" / "rpc" / ..)
+        .and(warp::path::tail())
+        .and(rate_limit(limiter.clone(), "rpc"))
+        .and(with_client)
+        .and_then(rpc_query);
+    let openapi = warp::path!("api" / "v1" / "openapi.json")
+        .and(rate_limit(limiter, "openapi"))
+        .map(|| reply::json(&openapi_document()));
+    let routes = warp::get().and(
+        balance
+            .or(validators)
+            .or(proposal)
+            .or(rpc_query)
+            .or(openapi),
+    );
+
+    http_access::serve("REST", routes, listen_addr, &access, abort_recv).await
+}
+
+/// The balance of a token held by an owner
+async fn balance(
+    token: String,
+    owner: String,
+    client: HttpClient,
+) -> HandlerResult {
+    let (token, owner) = match (parse_address(&token), parse_address(&owner)) {
+        (Ok(token), Ok(owner)) => (token, owner),
+        (Err(err), _) | (_, Err(err)) => return Ok(err),
+    };
+    Ok(
+        match rpc::get_token_balance(&client, &token, &owner).await {
+            Ok(amount) => json_reply(&serde_json::json!({
+                "token": token,
+                "owner": owner,
+                "amount": amount.raw_amount().to_string(),
+            })),
+            Err(err) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, err),
+        },
+    )
+}
+
+/// The addresses of all the validators in an epoch
+async fn validators(query: EpochQuery, client: HttpClient) -> HandlerResult {
+    let epoch = match query.epoch {
+        Some(epoch) => Epoch(epoch),
+        None => match rpc::query_epoch(&client).await {
+            Ok(epoch) => epoch,
+            Err(err) => {
+                return Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, err));
+            }
+        },
+    };
+    Ok(match rpc::get_all_validators(&client, epoch).await {
+        Ok(validators) => {
+            let mut validators: Vec<String> =
+                validators.iter().map(Address::encode).collect();
+            validators.sort();
+            json_reply(&serde_json::json!({
+                "epoch": epoch.0,
+                "validators": validators,
+            }))
+        }
+        Err(err) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, err),
+    })
+}
+
+/// A governance proposal by its id
+async fn proposal(id: u64, client: HttpClient) -> HandlerResult {
+    Ok(match rpc::query_proposal_by_id(&client, id).await {
+        Ok(Some(proposal)) => json_reply(&serde_json::json!({
+            "id": proposal.id,
+            "author": proposal.author,
+            "type": proposal.r#type.to_string(),
+            "content": proposal.content,
+            "voting_start_epoch": proposal.voting_start_epoch.0,
+            "voting_end_epoch": proposal.voting_end_epoch.0,
+            "grace_epoch": proposal.grace_epoch.0,
+        })),
+        Ok(None) => error_reply(
+            StatusCode::NOT_FOUND,
+            format!("Proposal {id} not found"),
+        ),
+        Err(err) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, err),
+    })
+}
+
+/// A query of the RPC router at the given path, with the hex encoded borsh
+/// data of the response
+async fn rpc_query(path: Tail, client: HttpClient) -> HandlerResult {
+    let path = format!("/{}", path.as_str());
+    Ok(match client.request(path, None, None, false).await {
+        Ok(response) => json_reply(&serde_json::json!({
+            "data": HEXLOWER.encode(&response.data),
+            "info": response.info,
+        })),
+        Err(err) => error_reply(StatusCode::BAD_REQUEST, err),
+    })
+}
+
+/// Decode an address of a path, or reply with an error
+fn parse_address(address: &str) -> Result<Address, reply::Response> {
+    Address::decode(address).map_err(|err| {
+        error_reply(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid address {address}: {err}"),
+        )
+    })
+}
+
+/// A successful JSON response
+fn json_reply(body: &impl Serialize) -> reply::Response {
+    reply::json(body).into_response()
+}
+
+/// A JSON response with the given error
+fn error_reply(status: StatusCode, err: impl ToString) -> reply::Response {
+    let body = ErrorBody {
+        error: err.to_string(),
+    };
+    reply::with_status(reply::json(&body), status).into_response()
+}
+
+/// The OpenAPI paths of the queries of the RPC router, generated from its
+/// routes. A path is generated for each combination of the optional arguments
+/// of a route, as these are omitted from the path when not given.
+fn rpc_paths(
+    routes: Vec<Route>,
+    error: &serde_json::Value,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut paths = serde_json::Map::new();
+    for route in routes {
+        let optional = route
+            .args()
+            .filter(|arg| {
+                matches!(arg, RouteSegment::Arg { optional: true, .. })
+            })
+            .count();
+        for included in 0..(1_u32 << optional) {
+            let mut path = RPC_PREFIX.to_owned();
+            let mut parameters = vec![];
+            let mut optional_index = 0;
+            for segment in &route.segments {
+                match segment {
+                    RouteSegment::Literal(literal) => {
+                        path.push('/');
+                        path.push_str(literal);
+                    }
+                    RouteSegment::Arg { name, ty, optional } => {
+                        if *optional {
+                            optional_index += 1;
+                            if included & (1 << (optional_index - 1)) == 0 {
+                                continue;
+                            }
+                        }
+                        path.push_str(&format!("/{{{name}}}"));
+                        parameters.push(serde_json::json!({
+                            "name": name,
+                            "in": "path",
+                            "required": true,
+                            "description": format!("A `{ty}`"),
+                            "schema": {"type": "string"},
+                        }));
+                    }
+                }
+            }
+            let operation = serde_json::json!({"get": {
+                "summary": format!("The `{}` query", route.handler),
+                "parameters": parameters,
+                "responses": {
+                    "200": {
+                        "description": format!(
+                            "The hex encoded borsh data of a `{}`",
+                            route.return_type
+                        ),
+                        "content": {"application/json": {"schema": {
+                            "$ref": "#/components/schemas/RpcResponse"
+                        }}},
+                    },
+                    "400": error,
+                },
+            }});
+            paths.insert(path, operation);
+        }
+    }
+    paths
+}
+
+/// The OpenAPI document describing the endpoints
+fn openapi_document() -> serde_json::Value {
+    let address = serde_json::json!({
+        "type": "string",
+        "description": "A bech32m encoded address",
+    });
+    let error = serde_json::json!({
+        "description": "The query failed",
+        "content": {"application/json": {"schema": {
+            "$ref": "#/components/schemas/Error"
+        }}},
+    });
+    let ok = |schema: serde_json::Value| {
+        serde_json::json!({
+            "description": "The query succeeded",
+            "content": {"application/json": {"schema": schema}},
+        })
+    };
+    let mut document = serde_json::json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Namada REST API",
+            "version": crate::cli::namada_version(),
+        },
+        "paths": {
+            "/api/v1/balance/{token}/{owner}": {"get": {
+                "summary": "The balance of a token held by an owner",
+                "parameters": [
+                    {"name": "token", "in": "path", "required": true,
+                     "schema": address},
+                    {"name": "owner", "in": "path", "required": true,
+                     "schema": address},
+                ],
+                "responses": {
+                    "200": ok(serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "token": address,
+                            "owner": address,
+                            "amount": {"type": "string"},
+                        },
+                    })),
+                    "400": error,
+                    "500": error,
+                },
+            }},
+            "/api/v1/validators": {"get": {
+                "summary": "The addresses of all the validators in an epoch",
+                "parameters": [
+                    {"name": "epoch", "in": "query", "required": false,
+                     "description": "Defaults to the current epoch",
+                     "schema": {"type": "integer", "format": "int64"}},
+                ],
+                "responses": {
+                    "200": ok(serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "epoch": {"type": "integer", "format": "int64"},
+                            "validators": {"type": "array", "items": address},
+                        },
+                    })),
+                    "500": error,
+                },
+            }},
+            "/api/v1/proposals/{id}": {"get": {
+                "summary": "A governance proposal by its id",
+                "parameters": [
+                    {"name": "id", "in": "path", "required": true,
+                     "schema": {"type": "integer", "format": "int64"}},
+                ],
+                "responses": {
+                    "200": ok(serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": "integer", "format": "int64"},
+                            "author": address,
+                            "type": {"type": "string"},
+                            "content": {
+                                "type": "object",
+                                "additionalProperties": {"type": "string"},
+                            },
+                            "voting_start_epoch":
+                                {"type": "integer", "format": "int64"},
+                            "voting_end_epoch":
+                                {"type": "integer", "format": "int64"},
+                            "grace_epoch":
+                                {"type": "integer", "format": "int64"},
+                        },
+                    })),
+                    "404": error,
+                    "500": error,
+                },
+            }},
+            OPENAPI_ENDPOINT: {"get": {
+                "summary": "This OpenAPI document",
+                "responses": {"200": {"description": "The OpenAPI document"}},
+            }},
+        },
+        "components": {"schemas": {
+            "Error": {
+                "type": "object",
+                "properties": {"error": {"type": "string"}},
+            },
+            "RpcResponse": {
+                "type": "object",
+                "properties": {
+                    "data": {"type": "string"},
+                    "info": {"type": "string"},
+                },
+            },
+        }},
+    });
+    if let Some(paths) = document["paths"].as_object_mut() {
+        paths.extend(rpc_paths(RPC.routes(), &error));
+    }
+    document
+}
@@ -17,17 +17,24 @@
 //! in the following order of preference:
 //!
 //! - First, we allot space for DKG encrypted txs. We allow DKG encrypted txs to
-//!   take up at most 1/3 of the total block space.
-//! - Next, we allot space for DKG decrypted txs. Decrypted txs take up as much
-//!   space as needed. We will see, shortly, why in practice this is fine.
-//! - Finally, we allot space for protocol txs. Protocol txs get half of the
-//!   remaining block space allotted to them.
+//!   take up at most the share of the total block space set by the
+//!   `max_encrypted_txs_share` protocol parameter (e.g. 1/3).
+//! - Next, we allot space for DKG decrypted txs. Decrypted txs take up at most
+//!   the share of the total block space set by the `max_decrypted_txs_share`
+//!   protocol parameter, without eating into the space reserved for protocol
+//!   txs. We will see, shortly, why in practice this is fine.
+//! - Finally, we allot the remaining space to protocol txs, which is at least
+//!   the share of the total block space set by the `min_protocol_txs_share`
+//!   protocol parameter.
 //!
 //! Since at some fixed height `H` decrypted txs only take up as
 //! much space as the encrypted txs from height `H - 1`, and we
-//! restrict the space of encrypted txs to at most 1/3 of the
-//! total block space, we roughly divide the Tendermint block
-//! space in 3, for each major type of tx.
+//! restrict the space of encrypted txs to at most a share `S` of the
+//! total block space, the decrypted txs always fit in a block as long as
+//! their share is not lower than `S` and `2S` plus the share of protocol
+//! txs does not exceed the block. These constraints are checked in genesis.
+//! With shares of 1/3, we roughly divide the Tendermint block space in 3,
+//! for each major type of tx.
 //!
 //! # How gas is allocated
 //!
@@ -58,6 +65,8 @@ use std::marker::PhantomData;
 use namada::core::ledger::storage::{self, WlStorage};
 use namada::proof_of_stake::pos_queries::PosQueries;
 
+use self::threshold::BlockSpaceShares;
+
 #[allow(unused_imports)]
 use crate::facade::tendermint_proto::abci::RequestPrepareProposal;
 
@@ -139,6 +148,10 @@ pub struct BlockAllocator<State> {
     encrypted_txs: EncryptedTxsBins,
     /// The current space utilized by DKG decrypted transactions.
     decrypted_txs: TxBin<BlockSpace>,
+    /// The max space DKG decrypted transactions may take up.
+    max_decrypted_txs_space: u64,
+    /// The space reserved for protocol transactions.
+    min_protocol_txs_space: u64,
 }
 
 impl<D, H, M> From<&WlStorage<D, H>>
@@ -149,9 +162,10 @@ where
 {
     #[inline]
     fn from(storage: &WlStorage<D, H>) -> Self {
-        Self::init(
+        Self::init_with_shares(
             storage.pos_queries().get_max_proposal_bytes().get(),
             namada::core::ledger::gas::get_max_block_gas(storage).unwrap(),
+            threshold::read_block_space_shares(storage),
         )
     }
 }
@@ -160,18 +174,42 @@ impl<M> BlockAllocator<states::BuildingEncryptedTxBatch<M>> {
     /// Construct a new [`BlockAllocator`], with an upper bound
     /// on the max size of all txs in a block defined by Tendermint and an upper
     /// bound on the max gas in a block.
+    ///
+    /// Encrypted txs may take up at most 1/3 of the block space, decrypted
+    /// txs take up as much space as needed and protocol txs get the rest.
     #[inline]
     pub fn init(
         tendermint_max_block_space_in_bytes: u64,
         max_block_gas: u64,
+    ) -> Self {
+        Self::init_with_shares(
+            tendermint_max_block_space_in_bytes,
+            max_block_gas,
+            BlockSpaceShares::default(),
+        )
+    }
+
+    /// Construct a new [`BlockAllocator`], like [`BlockAllocator::init`],
+    /// where each kind of tx is allotted the given share of the block space.
+    #[inline]
+    pub fn init_with_shares(
+        tendermint_max_block_space_in_bytes: u64,
+        max_block_gas: u64,
+        shares: BlockSpaceShares,
     ) -> Self {
         let max = tendermint_max_block_space_in_bytes;
         Self {
             _state: PhantomData,
             block: TxBin::init(max),
             protocol_txs: TxBin::default(),
-            encrypted_txs: EncryptedTxsBins::new(max, max_block_gas),
+            encrypted_txs: EncryptedTxsBins::new(
+                max,
+                max_block_gas,
+                shares.max_encrypted_txs,
+            ),
             decrypted_txs: TxBin::default(),
+            max_decrypted_txs_space: shares.max_decrypted_txs.over(max),
+            min_protocol_txs_space: shares.min_protocol_txs.over(max),
         }
     }
 }
@@ -262,8 +300,12 @@ pub struct EncryptedTxsBins {
 }
 
 impl EncryptedTxsBins {
-    pub fn new(max_bytes: u64, max_gas: u64) -> Self {
-        let allotted_space_in_bytes = threshold::ONE_THIRD.over(max_bytes);
+    pub fn new(
+        max_bytes: u64,
+        max_gas: u64,
+        max_share: threshold::Threshold,
+    ) -> Self {
+        let allotted_space_in_bytes = max_share.over(max_bytes);
         Self {
             space: TxBin::init(allotted_space_in_bytes),
             gas: TxBin::init(max_gas),
@@ -276,8 +318,9 @@ impl EncryptedTxsBins {
                 "No more space left in the block for wrapper txs".to_string()
             }
             AllocFailure::OverflowsBin { .. } => "The given wrapper tx is \
-                                                  larger than 1/3 of the \
-                                                  available block space"
+                                                  larger than the share of \
+                                                  the block space allotted \
+                                                  to wrapper txs"
                 .to_string(),
         })?;
         self.gas.try_dump(gas).map_err(|e| match e {
@@ -296,6 +339,10 @@ impl EncryptedTxsBins {
 pub mod threshold {
     //! Transaction allotment thresholds.
 
+    use namada::core::ledger::parameters;
+    use namada::core::ledger::storage::{self, WlStorage};
+    use namada::types::dec::{Dec, POS_DECIMAL_PRECISION};
+    use namada::types::uint::Uint;
     use num_rational::Ratio;
 
     /// Threshold over a portion of block space.
@@ -316,8 +363,86 @@ pub mod threshold {
         }
     }
 
+    impl From<Dec> for Threshold {
+        /// Convert a decimal share of the free space into a [`Threshold`].
+        /// Negative shares are mapped to zero.
+        fn from(share: Dec) -> Self {
+            let denom = 10u64.pow(POS_DECIMAL_PRECISION as u32);
+            if share.is_negative() {
+                return Self::new(0, denom);
+            }
+            let numer = share.abs().min(Uint::from(denom)).as_u64();
+            Self::new(numer, denom)
+        }
+    }
+
     /// Divide free space in three.
     pub const ONE_THIRD: Threshold = Threshold::new(1, 3);
+
+    /// The whole free space.
+    pub const ONE: Threshold = Threshold::new(1, 1);
+
+    /// None of the free space.
+    pub const ZERO: Threshold = Threshold::new(0, 1);
+
+    /// The shares of the block space allotted to each kind of tx.
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    pub struct BlockSpaceShares {
+        /// Max share of encrypted txs
+        pub max_encrypted_txs: Threshold,
+        /// Max share of decrypted txs
+        pub max_decrypted_txs: Threshold,
+        /// Min share of protocol txs
+        pub min_protocol_txs: Threshold,
+    }
+
+    impl Default for BlockSpaceShares {
+        /// At most 1/3 of the block space for encrypted txs, no limit on
+        /// decrypted txs and no space reserved for protocol txs.
+        fn default() -> Self {
+            Self {
+                max_encrypted_txs: ONE_THIRD,
+                max_decrypted_txs: ONE,
+                min_protocol_txs: ZERO,
+            }
+        }
+    }
+
+    /// Read the max share of the block space that encrypted txs can take
+    /// up from the protocol parameters.
+    pub fn read_max_encrypted_txs_share<D, H>(
+        storage: &WlStorage<D, H>,
+    ) -> Threshold
+    where
+        D: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+        H: 'static + storage::StorageHasher,
+    {
+        parameters::read_max_encrypted_txs_share(storage)
+            .expect("Reading the max encrypted txs share should not fail")
+            .into()
+    }
+
+    /// Read the shares of the block space of every kind of tx from the
+    /// protocol parameters.
+    pub fn read_block_space_shares<D, H>(
+        storage: &WlStorage<D, H>,
+    ) -> BlockSpaceShares
+    where
+        D: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+        H: 'static + storage::StorageHasher,
+    {
+        BlockSpaceShares {
+            max_encrypted_txs: read_max_encrypted_txs_share(storage),
+            max_decrypted_txs: parameters::read_max_decrypted_txs_share(
+                storage,
+            )
+            .expect("Reading the max decrypted txs share should not fail")
+            .into(),
+            min_protocol_txs: parameters::read_min_protocol_txs_share(storage)
+                .expect("Reading the min protocol txs share should not fail")
+                .into(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -325,6 +450,7 @@ mod tests {
     use std::cell::RefCell;
 
     use assert_matches::assert_matches;
+    use namada::types::dec::Dec;
     use proptest::prelude::*;
 
     use super::states::{
@@ -354,6 +480,73 @@ mod tests {
         decrypted_txs: Vec<TxBytes>,
     }
 
+    /// Check that the allotted encrypted txs space follows the configured
+    /// share of the block space.
+    #[test]
+    fn test_encrypted_txs_configurable_share() {
+        const BLOCK_SIZE: u64 = 100;
+        const BLOCK_GAS: u64 = 1_000;
+
+        let shares = threshold::BlockSpaceShares {
+            max_encrypted_txs: Dec::new(25, 2).unwrap().into(),
+            ..Default::default()
+        };
+        let mut alloc =
+            BsaWrapperTxs::init_with_shares(BLOCK_SIZE, BLOCK_GAS, shares);
+        assert_eq!(alloc.encrypted_txs.space.allotted, 25);
+        assert!(alloc.try_alloc(BlockResources::new(&[0; 25], 0)).is_ok());
+        assert_matches!(
+            alloc.try_alloc(BlockResources::new(&[0; 1], 0)),
+            Err(AllocFailure::Rejected { .. })
+        );
+
+        // shares greater than the whole block are capped
+        let shares = threshold::BlockSpaceShares {
+            max_encrypted_txs: Dec::two().into(),
+            ..Default::default()
+        };
+        let alloc =
+            BsaWrapperTxs::init_with_shares(BLOCK_SIZE, BLOCK_GAS, shares);
+        assert_eq!(alloc.encrypted_txs.space.allotted, BLOCK_SIZE);
+    }
+
+    /// Check that decrypted txs can't take up more than their share of the
+    /// block space, nor the space reserved for protocol txs.
+    #[test]
+    fn test_decrypted_and_protocol_txs_configurable_shares() {
+        const BLOCK_SIZE: u64 = 100;
+        const BLOCK_GAS: u64 = 1_000;
+
+        let shares = threshold::BlockSpaceShares {
+            max_encrypted_txs: Dec::new(2, 1).unwrap().into(),
+            max_decrypted_txs: Dec::new(5, 1).unwrap().into(),
+            min_protocol_txs: Dec::new(4, 1).unwrap().into(),
+        };
+        let mut alloc =
+            BsaWrapperTxs::init_with_shares(BLOCK_SIZE, BLOCK_GAS, shares);
+        assert!(alloc.try_alloc(BlockResources::new(&[0; 20], 0)).is_ok());
+
+        // decrypted txs are left with the space not reserved for protocol
+        // txs, below their own share
+        let mut alloc = alloc.next_state();
+        assert_eq!(alloc.decrypted_txs.allotted, 40);
+        assert_matches!(
+            alloc.try_alloc(&[0; 41]),
+            Err(AllocFailure::OverflowsBin { .. })
+        );
+        assert!(alloc.try_alloc(&[0; 10]).is_ok());
+
+        // protocol txs get the reserved space and what decrypted txs left
+        let alloc = alloc.next_state();
+        assert_eq!(alloc.protocol_txs.allotted, 70);
+
+        // without encrypted txs, decrypted txs are capped by their share
+        let alloc =
+            BsaNoWrapperTxs::init_with_shares(BLOCK_SIZE, BLOCK_GAS, shares);
+        let alloc = alloc.next_state();
+        assert_eq!(alloc.decrypted_txs.allotted, 50);
+    }
+
     /// Check that at most 1/3 of the block space is
     /// reserved for each kind of tx type, in the
     /// allocator's common path.
@@ -34,6 +34,8 @@ impl NextStateImpl for BlockAllocator<BuildingDecryptedTxBatch> {
             protocol_txs,
             encrypted_txs,
             decrypted_txs,
+            max_decrypted_txs_space,
+            min_protocol_txs_space,
             ..
         } = self;
 
@@ -43,6 +45,8 @@ impl NextStateImpl for BlockAllocator<BuildingDecryptedTxBatch> {
             protocol_txs,
             encrypted_txs,
             decrypted_txs,
+            max_decrypted_txs_space,
+            min_protocol_txs_space,
         }
     }
 }
@@ -65,10 +65,14 @@ fn next_state<Mode>(
 ) -> BlockAllocator<BuildingDecryptedTxBatch> {
     alloc.encrypted_txs.space.shrink_to_fit();
 
-    // decrypted txs can use as much space as they need - which
-    // in practice will only be, at most, 1/3 of the block space
+    // decrypted txs can use as much space as they need, up to their
+    // share and without the space reserved for protocol txs - which
+    // in practice will only be, at most, the share of the block space
     // used by encrypted txs at the prev height
-    let remaining_free_space = alloc.uninitialized_space_in_bytes();
+    let remaining_free_space = alloc
+        .uninitialized_space_in_bytes()
+        .saturating_sub(alloc.min_protocol_txs_space)
+        .min(alloc.max_decrypted_txs_space);
     alloc.decrypted_txs = TxBin::init(remaining_free_space);
 
     // cast state
@@ -77,6 +81,8 @@ fn next_state<Mode>(
         protocol_txs,
         encrypted_txs,
         decrypted_txs,
+        max_decrypted_txs_space,
+        min_protocol_txs_space,
         ..
     } = alloc;
 
@@ -86,6 +92,8 @@ fn next_state<Mode>(
         protocol_txs,
         encrypted_txs,
         decrypted_txs,
+        max_decrypted_txs_space,
+        min_protocol_txs_space,
     }
 }
 
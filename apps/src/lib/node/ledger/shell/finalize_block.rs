@@ -3,14 +3,18 @@
 use data_encoding::HEXUPPER;
 use namada::core::ledger::inflation;
 use namada::core::ledger::masp_conversions::update_allowed_conversions;
+use namada::core::ledger::oracle;
 use namada::core::ledger::pgf::ADDRESS as pgf_address;
-use namada::ledger::events::EventType;
+use namada::core::ledger::upgrade;
+use namada::ledger::events::{EventLevel, EventType};
 use namada::ledger::gas::{GasMetering, TxGasMeter};
+use namada::ledger::native_vp::multitoken::blocked_balance_owners;
+use namada::ledger::parameters::read_blocked_addresses;
 use namada::ledger::parameters::storage as params_storage;
 use namada::ledger::pos::{namada_proof_of_stake, staking_token_address};
 use namada::ledger::protocol;
 use namada::ledger::storage::wl_storage::WriteLogAndStorage;
-use namada::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
+use namada::ledger::storage::{LastBlock, EPOCH_SWITCH_BLOCKS_DELAY};
 use namada::ledger::storage_api::token::credit_tokens;
 use namada::ledger::storage_api::{pgf, StorageRead, StorageWrite};
 use namada::proof_of_stake::{
@@ -18,6 +22,7 @@ use namada::proof_of_stake::{
     read_pos_params, read_total_stake, write_last_block_proposer_address,
 };
 use namada::types::dec::Dec;
+use namada::types::hash::Hash;
 use namada::types::key::tm_raw_hash_to_string;
 use namada::types::storage::{BlockHash, BlockResults, Epoch, Header};
 use namada::types::transaction::protocol::{
@@ -25,11 +30,19 @@ use namada::types::transaction::protocol::{
 };
 use namada::types::vote_extensions::ethereum_events::MultiSignedEthEvent;
 
-use super::governance::execute_governance_proposals;
+use super::governance::{
+    emit_proposal_tallies, execute_epoch_hooks, execute_governance_proposals,
+    proposal_tx_events,
+};
+use super::migrations;
 use super::*;
 use crate::facade::tendermint::abci::types::{Misbehavior, VoteInfo};
 use crate::node::ledger::shell::stats::InternalStats;
 
+/// Maximum number of bonds whose rewards are auto-compounded in a block. The
+/// remaining bonds are compounded in the following blocks of the epoch.
+pub const MAX_AUTO_COMPOUNDS_PER_BLOCK: usize = 100;
+
 impl<D, H> Shell<D, H>
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
@@ -61,9 +74,22 @@ where
     ) -> Result<shim::response::FinalizeBlock> {
         let mut response = shim::response::FinalizeBlock::default();
 
+        // Derive the randomness of the new block from the last one, before
+        // it's replaced by the new block
+        let randomness = block_randomness(
+            self.wl_storage.storage.last_block.as_ref(),
+            &req.header.hash,
+            &req.votes,
+        );
+
         // Begin the new block and check if a new epoch has begun
         let (height, new_epoch) =
             self.update_state(req.header, req.hash, req.byzantine_validators);
+        self.wl_storage.storage.set_block_randomness(randomness);
+
+        // Halt at the height of a scheduled upgrade, unless this is the
+        // upgraded binary
+        self.check_upgrade_plan(height)?;
 
         let (current_epoch, _gas) = self.wl_storage.storage.get_current_epoch();
         let update_for_tendermint = matches!(
@@ -71,16 +97,25 @@ where
             Some(EPOCH_SWITCH_BLOCKS_DELAY)
         );
 
-        tracing::info!(
-            "Block height: {height}, epoch: {current_epoch}, is new epoch: \
-             {new_epoch}."
-        );
+        // All the logs of this block, including those of its txs and VPs, are
+        // recorded in this span
+        let _block_span = tracing::info_span!(
+            "block",
+            height = height.0,
+            epoch = current_epoch.0
+        )
+        .entered();
+        tracing::info!(new_epoch, "Finalizing block");
         tracing::debug!(
             "New epoch block delay for updating the Tendermint validator set: \
              {:?}",
             self.wl_storage.storage.update_epoch_blocks_delay
         );
 
+        // Store the rebuilt wasms of a development chain before they're used
+        // by the txs of this block
+        self.reload_wasms();
+
         // Finalize the transactions' hashes from the previous block
         for hash in self.wl_storage.storage.iter_replay_protection() {
             self.wl_storage
@@ -89,6 +124,29 @@ where
                 .expect("Failed tx hashes finalization")
         }
 
+        // Prune the hashes of the transactions that expired before the last
+        // block: these can no longer be replayed and, since they must have
+        // been included before the last block, they have already been
+        // finalized
+        if let Some(last_block) = self.wl_storage.storage.last_block.as_ref() {
+            for (expiration, hash) in self
+                .wl_storage
+                .storage
+                .iter_replay_protection_expirations()
+                .take_while(|(expiration, _)| *expiration < last_block.time)
+            {
+                self.wl_storage
+                    .write_log
+                    .prune_tx_hash(hash, expiration)
+                    .map_err(|e| {
+                        Error::StorageApi(storage_api::Error::new(e))
+                    })?;
+            }
+        }
+
+        // Forget the txs that have been pending in the mempool for too long
+        self.pending_txs.prune(height);
+
         let pos_params =
             namada_proof_of_stake::read_pos_params(&self.wl_storage)?;
 
@@ -97,6 +155,33 @@ where
 
             execute_governance_proposals(self, &mut response)?;
 
+            // Report the participation in the proposals still being voted on
+            emit_proposal_tallies(self, &mut response)?;
+
+            // Run the recurring jobs registered by governance, including the
+            // ones registered by the proposals just executed
+            execute_epoch_hooks(self, &mut response)?;
+
+            // Remove the governance and PoS data that is no longer needed,
+            // after the proposals of this epoch have been executed
+            self.prune_expired_data(current_epoch, &mut response)?;
+
+            // Summarize the storage usage at the end of the last epoch, as
+            // the basis of a future state rent
+            let total_bytes = self
+                .wl_storage
+                .storage
+                .read_storage_usage(None)
+                .expect("Must be able to read the storage usage");
+            let mut event = Event {
+                event_type: EventType::StorageUsage,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["epoch"] = current_epoch.prev().to_string();
+            event["total_bytes"] = total_bytes.to_string();
+            response.events.push(event);
+
             // Copy the new_epoch + pipeline_len - 1 validator set into
             // new_epoch + pipeline_len
             namada_proof_of_stake::copy_validator_sets_and_positions(
@@ -139,7 +224,12 @@ where
             // the rewards in the current epoch.
             self.process_slashes();
             self.apply_inflation(current_epoch)?;
+            #[cfg(debug_assertions)]
+            self.check_native_token_supply();
         }
+        // Re-bond the rewards of the bonds that opted in, once they're updated
+        // with the inflation of the last epoch
+        self.compound_rewards(current_epoch)?;
 
         // Consensus set liveness check
         if !votes.is_empty() {
@@ -210,11 +300,25 @@ where
                 );
                 continue;
             };
+            let _tx_span = tracing::info_span!(
+                "tx",
+                index = tx_index,
+                hash = %tx.header_hash()
+            )
+            .entered();
+            // An unknown result code is handled as a rejection of the tx
+            let result_code = ErrorCodes::from_u32(processed_tx.result.code)
+                .unwrap_or_else(|| {
+                    tracing::error!(
+                        "Internal logic error: FinalizeBlock received a tx \
+                         with the unknown result code {}",
+                        processed_tx.result.code
+                    );
+                    ErrorCodes::InvalidTx
+                });
             // If [`process_proposal`] rejected a Tx due to invalid signature,
             // emit an event here and move on to next tx.
-            if ErrorCodes::from_u32(processed_tx.result.code).unwrap()
-                == ErrorCodes::InvalidSig
-            {
+            if result_code == ErrorCodes::InvalidSig {
                 let mut tx_event = match tx.header().tx_type {
                     TxType::Wrapper(_) | TxType::Protocol(_) => {
                         Event::new_tx_event(&tx, height.0)
@@ -230,8 +334,11 @@ where
                     }
                 };
                 tx_event["code"] = processed_tx.result.code.to_string();
+                tx_event[ResultCode::EVENT_ATTRIBUTE] =
+                    ResultCode::from(result_code).to_event_value();
                 tx_event["info"] =
                     format!("Tx rejected: {}", &processed_tx.result.info);
+                tx_event["reason"] = processed_tx.result.info.clone();
                 tx_event["gas_used"] = "0".into();
                 response.events.push(tx_event);
                 continue;
@@ -247,13 +354,14 @@ where
             let tx_header = tx.header();
             // If [`process_proposal`] rejected a Tx, emit an event here and
             // move on to next tx
-            if ErrorCodes::from_u32(processed_tx.result.code).unwrap()
-                != ErrorCodes::Ok
-            {
+            if result_code != ErrorCodes::Ok {
                 let mut tx_event = Event::new_tx_event(&tx, height.0);
                 tx_event["code"] = processed_tx.result.code.to_string();
+                tx_event[ResultCode::EVENT_ATTRIBUTE] =
+                    ResultCode::from(result_code).to_event_value();
                 tx_event["info"] =
                     format!("Tx rejected: {}", &processed_tx.result.info);
+                tx_event["reason"] = processed_tx.result.info.clone();
                 tx_event["gas_used"] = "0".into();
                 response.events.push(tx_event);
                 // if the rejected tx was decrypted, remove it
@@ -273,6 +381,8 @@ where
                 match &tx_header.tx_type {
                     TxType::Wrapper(wrapper) => {
                         stats.increment_wrapper_txs();
+                        // The tx is no longer pending in the mempool
+                        self.pending_txs.remove(&tx.raw_header_hash());
                         let tx_event = Event::new_tx_event(&tx, height.0);
                         let gas_meter = TxGasMeter::new(wrapper.gas_limit);
                         (tx_event, None, gas_meter, Some(tx.clone()))
@@ -310,6 +420,8 @@ where
                                     .into();
                                 event["code"] =
                                     ErrorCodes::Undecryptable.into();
+                                event[ResultCode::EVENT_ATTRIBUTE] =
+                                    ResultCode::Undecryptable.to_event_value();
                                 response.events.push(event);
                                 continue;
                             }
@@ -329,73 +441,89 @@ where
                         );
                         continue;
                     }
-                    TxType::Protocol(protocol_tx) => match protocol_tx.tx {
-                        ProtocolTxType::BridgePoolVext
-                        | ProtocolTxType::BridgePool
-                        | ProtocolTxType::ValSetUpdateVext
-                        | ProtocolTxType::ValidatorSetUpdate => (
-                            Event::new_tx_event(&tx, height.0),
-                            None,
-                            TxGasMeter::new_from_sub_limit(0.into()),
-                            None,
-                        ),
-                        ProtocolTxType::EthEventsVext => {
-                            let ext =
-                            ethereum_tx_data_variants::EthEventsVext::try_from(
-                                &tx,
-                            )
-                            .unwrap();
-                            if self
-                                .mode
-                                .get_validator_address()
-                                .map(|validator| {
-                                    validator == &ext.data.validator_addr
-                                })
-                                .unwrap_or(false)
-                            {
-                                for event in ext.data.ethereum_events.iter() {
-                                    self.mode.dequeue_eth_event(event);
-                                }
-                            }
-                            (
+                    TxType::Protocol(protocol_tx) => {
+                        // Protocol txs are signed by the validators and pay
+                        // no fees, so they are exempt from gas. Their block
+                        // space is bounded by the protocol txs bin instead.
+                        let gas_meter =
+                            TxGasMeter::new_from_sub_limit(0.into());
+                        match protocol_tx.tx {
+                            ProtocolTxType::BridgePoolVext
+                            | ProtocolTxType::BridgePool
+                            | ProtocolTxType::ValSetUpdateVext
+                            | ProtocolTxType::ValidatorSetUpdate => (
                                 Event::new_tx_event(&tx, height.0),
                                 None,
-                                TxGasMeter::new_from_sub_limit(0.into()),
+                                gas_meter,
                                 None,
-                            )
-                        }
-                        ProtocolTxType::EthereumEvents => {
-                            let digest =
-                            ethereum_tx_data_variants::EthereumEvents::try_from(
-                                &tx,
-                            ).unwrap();
-                            if let Some(address) =
-                                self.mode.get_validator_address().cloned()
-                            {
-                                let this_signer = &(
-                                    address,
-                                    self.wl_storage
-                                        .storage
-                                        .get_last_block_height(),
-                                );
-                                for MultiSignedEthEvent { event, signers } in
-                                    &digest.events
+                            ),
+                            ProtocolTxType::EthEventsVext => {
+                                let ext =
+                                ethereum_tx_data_variants::EthEventsVext::try_from(
+                                    &tx,
+                                )
+                                .unwrap();
+                                if self
+                                    .mode
+                                    .get_validator_address()
+                                    .map(|validator| {
+                                        validator == &ext.data.validator_addr
+                                    })
+                                    .unwrap_or(false)
                                 {
-                                    if signers.contains(this_signer) {
+                                    for event in ext.data.ethereum_events.iter()
+                                    {
                                         self.mode.dequeue_eth_event(event);
                                     }
                                 }
+                                (
+                                    Event::new_tx_event(&tx, height.0),
+                                    None,
+                                    gas_meter,
+                                    None,
+                                )
+                            }
+                            ProtocolTxType::EthereumEvents => {
+                                let digest =
+                                ethereum_tx_data_variants::EthereumEvents::try_from(
+                                    &tx,
+                                ).unwrap();
+                                if let Some(address) =
+                                    self.mode.get_validator_address().cloned()
+                                {
+                                    let this_signer = &(
+                                        address,
+                                        self.wl_storage
+                                            .storage
+                                            .get_last_block_height(),
+                                    );
+                                    for MultiSignedEthEvent {
+                                        event,
+                                        signers,
+                                    } in &digest.events
+                                    {
+                                        if signers.contains(this_signer) {
+                                            self.mode.dequeue_eth_event(event);
+                                        }
+                                    }
+                                }
+                                (
+                                    Event::new_tx_event(&tx, height.0),
+                                    None,
+                                    gas_meter,
+                                    None,
+                                )
                             }
-                            (
-                                Event::new_tx_event(&tx, height.0),
-                                None,
-                                TxGasMeter::new_from_sub_limit(0.into()),
-                                None,
-                            )
                         }
-                    },
+                    }
                 };
 
+            // The storage deposits of an inner tx are paid by the fee payer
+            // of its wrapper
+            let storage_deposit_payer = embedding_wrapper
+                .as_ref()
+                .and_then(|wrapper| wrapper.header().wrapper())
+                .map(|wrapper| wrapper.fee_payer());
             match protocol::dispatch_tx(
                 tx,
                 processed_tx.tx.as_ref(),
@@ -409,6 +537,7 @@ where
                 &mut self.vp_wasm_cache,
                 &mut self.tx_wasm_cache,
                 Some(&native_block_proposer_address),
+                storage_deposit_payer.as_ref(),
             )
             .map_err(Error::TxApply)
             {
@@ -434,6 +563,12 @@ where
                             changed_keys
                                 .extend(result.changed_keys.iter().cloned());
                             stats.increment_successful_txs();
+                            response.events.extend(proposal_tx_events(
+                                &self.wl_storage,
+                                &result.changed_keys,
+                                &tx_event["hash"],
+                                height,
+                            )?);
                             if let Some(wrapper) = embedding_wrapper {
                                 self.commit_inner_tx_hash(wrapper);
                             }
@@ -441,6 +576,8 @@ where
                         self.wl_storage.commit_tx();
                         if !tx_event.contains_key("code") {
                             tx_event["code"] = ErrorCodes::Ok.into();
+                            tx_event[ResultCode::EVENT_ATTRIBUTE] =
+                                ResultCode::Ok.to_event_value();
                             self.wl_storage
                                 .storage
                                 .block
@@ -486,12 +623,42 @@ where
                             }
                         }
 
+                        if result.vps_result.rejected_vps.contains(
+                            &Address::Internal(
+                                address::InternalAddress::Multitoken,
+                            ),
+                        ) {
+                            if let Some(event) = self.transfer_blocked_event(
+                                &result.changed_keys,
+                                &tx_event["hash"],
+                                height,
+                            ) {
+                                response.events.push(event);
+                            }
+                        }
+
                         stats.increment_rejected_txs();
                         self.wl_storage.drop_tx();
                         tx_event["code"] = ErrorCodes::InvalidTx.into();
+                        tx_event[ResultCode::EVENT_ATTRIBUTE] =
+                            result.vps_result.result_code().to_event_value();
+                        tx_event["reason"] =
+                            result.vps_result.rejection_reason();
                     }
                     tx_event["gas_used"] = result.gas_used.to_string();
                     tx_event["info"] = result.to_string();
+                    match serde_json::to_string(&result.vps_result.verifiers())
+                    {
+                        Ok(verifiers) => {
+                            tx_event["verifiers"] = verifiers;
+                        }
+                        Err(err) => {
+                            tracing::error!(
+                                "Failed to serialize the verifiers: {}",
+                                err
+                            );
+                        }
+                    }
                 }
                 Err(msg) => {
                     tracing::info!(
@@ -537,7 +704,12 @@ where
                     tx_event["gas_used"] =
                         tx_gas_meter.get_tx_consumed_gas().to_string();
                     tx_event["info"] = msg.to_string();
-                    if let EventType::Accepted = tx_event.event_type {
+                    tx_event["reason"] = msg.to_string();
+                    let is_wrapper =
+                        matches!(tx_event.event_type, EventType::Accepted);
+                    tx_event[ResultCode::EVENT_ATTRIBUTE] =
+                        error_result_code(&msg, is_wrapper).to_event_value();
+                    if is_wrapper {
                         // If wrapper, invalid tx error code
                         tx_event["code"] = ErrorCodes::InvalidTx.into();
                     } else {
@@ -560,6 +732,10 @@ where
         tracing::info!("{}", stats);
         tracing::info!("{}", stats.format_tx_executed());
 
+        // Update the values of the oracle feeds with the submissions of this
+        // block
+        self.update_oracle_values(&mut response)?;
+
         if update_for_tendermint {
             self.update_epoch(&mut response);
             // send the latest oracle configs. These may have changed due to
@@ -572,12 +748,34 @@ where
             native_block_proposer_address,
         )?;
 
+        self.index_tx_results(&response.events);
         self.event_log_mut().log_events(response.events.clone());
+        self.publish_events(&response.events);
         tracing::debug!("End finalize_block {height} of epoch {current_epoch}");
 
         Ok(response)
     }
 
+    /// Index the results of the applied txs by their hash, to be written to
+    /// the DB on commit
+    fn index_tx_results(&mut self, events: &[Event]) {
+        for event in events.iter().filter(|event| {
+            event.level == EventLevel::Tx
+                && event.event_type == EventType::Applied
+        }) {
+            let Some(hash) =
+                event.get("hash").and_then(|hash| hash.parse::<Hash>().ok())
+            else {
+                tracing::error!("Missing tx hash in event {:?}", event);
+                continue;
+            };
+            self.wl_storage
+                .storage
+                .indexed_tx_results
+                .insert(hash, event.serialize_to_vec());
+        }
+    }
+
     /// Sets the metadata necessary for a new block, including
     /// the hash, height, validator changes, and evidence of
     /// byzantine behavior. Applies slashes if necessary.
@@ -630,6 +828,62 @@ where
             .expect("Must be able to update validator set");
     }
 
+    /// Check that the total supply of the native token is equal to the sum
+    /// of its balances. This iterates over all the balances, so it is only
+    /// run in debug builds.
+    #[cfg(debug_assertions)]
+    fn check_native_token_supply(&self) {
+        let native_token = &self.wl_storage.storage.native_token;
+        if let Err(err) = storage_api::token::check_total_supply_invariant(
+            &self.wl_storage,
+            native_token,
+        ) {
+            tracing::error!("Native token supply invariant violated: {}", err);
+        }
+    }
+
+    /// Re-bond the rewards of the next bonds that opted into auto-compounding,
+    /// at most [`MAX_AUTO_COMPOUNDS_PER_BLOCK`] of them. Every bond is
+    /// compounded in the write log of its own tx, so that a bond whose rewards
+    /// fail to be claimed or bonded leaves no partial change and doesn't block
+    /// the other bonds nor the block.
+    fn compound_rewards(&mut self, current_epoch: Epoch) -> Result<()> {
+        let bond_ids = namada_proof_of_stake::take_auto_compound_bonds(
+            &mut self.wl_storage,
+            current_epoch,
+            MAX_AUTO_COMPOUNDS_PER_BLOCK,
+        )?;
+        if bond_ids.is_empty() {
+            return Ok(());
+        }
+        let pos_params = read_pos_params(&self.wl_storage)?;
+        for bond_id in bond_ids {
+            match self.wl_storage.apply_tx_scoped(|storage| {
+                namada_proof_of_stake::compound_bond_rewards(
+                    storage,
+                    &pos_params,
+                    &bond_id,
+                    current_epoch,
+                )
+            }) {
+                Ok(Some(amount)) => tracing::debug!(
+                    "Auto-compounded {} of rewards of {} bonded to {}",
+                    amount.to_string_native(),
+                    bond_id.source,
+                    bond_id.validator
+                ),
+                Ok(None) => {}
+                Err(err) => tracing::error!(
+                    "Failed to auto-compound the rewards of {} bonded to {}: \
+                     {err}",
+                    bond_id.source,
+                    bond_id.validator
+                ),
+            }
+        }
+        Ok(())
+    }
+
     /// Calculate the new inflation rate, mint the new tokens to the PoS
     /// account, then update the reward products of the validators. This is
     /// executed while finalizing the first block of a new epoch and is applied
@@ -863,11 +1117,177 @@ where
         self.wl_storage
             .write_tx_hash(wrapper_tx.raw_header_hash())
             .expect("Error while writing tx hash to storage");
+        if let Some(expiration) = wrapper_tx.header.expiration {
+            self.wl_storage
+                .write_log
+                .write_tx_hash_expiration(
+                    wrapper_tx.raw_header_hash(),
+                    expiration,
+                );
+        }
 
         self.wl_storage
             .delete_tx_hash(wrapper_tx.header_hash())
             .expect("Error while deleting tx hash from storage");
     }
+
+    /// Check the scheduled upgrade of the chain, if any. The previous binary
+    /// halts at the height of the upgrade, before applying its block. The
+    /// upgraded binary, started with the `--upgrade-ready` flag, applies the
+    /// block if its version matches the name of the upgrade and marks the
+    /// upgrade as applied.
+    fn check_upgrade_plan(&mut self, height: BlockHeight) -> Result<()> {
+        let plan = match upgrade::read_plan(&self.wl_storage)? {
+            Some(plan) => plan,
+            None => return Ok(()),
+        };
+        let version = crate::cli::namada_version();
+        let halt = |msg: String| {
+            tracing::error!("{msg}");
+            Err(Error::UpgradeHalt(msg))
+        };
+        if height < plan.height {
+            if self.upgrade_ready && version == plan.name {
+                return halt(format!(
+                    "The upgrade \"{}\" is scheduled at height {}, the \
+                     previous binary must be run until then",
+                    plan.name, plan.height
+                ));
+            }
+            return Ok(());
+        }
+        if !self.upgrade_ready {
+            return halt(format!(
+                "UPGRADE \"{}\" NEEDED at height {}: install the binary of \
+                 version {} with the artifact hash {} and restart the node \
+                 with the `--upgrade-ready` flag",
+                plan.name, plan.height, plan.name, plan.artifact_hash
+            ));
+        }
+        if version != plan.name {
+            return halt(format!(
+                "The version {version} of this binary doesn't match the \
+                 upgrade \"{}\" scheduled at height {}",
+                plan.name, plan.height
+            ));
+        }
+        // Migrate the storage written by the previous binary, before it's
+        // used by the txs of this block
+        for migration in migrations::migrations(&plan.name) {
+            migration(&mut self.wl_storage)?;
+        }
+        upgrade::mark_applied(&mut self.wl_storage, &plan)?;
+        tracing::info!(
+            "Applied the upgrade \"{}\" at height {height}",
+            plan.name
+        );
+        Ok(())
+    }
+
+    /// Update the values of the oracle feeds to the medians of the values
+    /// submitted in this block and emit an event for every updated feed
+    fn update_oracle_values(
+        &mut self,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        for (feed_id, num_submissions, observation) in
+            oracle::update_values(&mut self.wl_storage)?
+        {
+            tracing::debug!(
+                "Updated the oracle feed {feed_id} to {} with \
+                 {num_submissions} submissions",
+                observation.value
+            );
+            let mut event = Event {
+                event_type: EventType::OracleUpdate,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["feed_id"] = feed_id;
+            event["value"] = observation.value.to_string();
+            event["height"] = observation.height.to_string();
+            event["submissions"] = num_submissions.to_string();
+            response.events.push(event);
+        }
+        Ok(())
+    }
+
+    /// An event of a tx rejected for changing the balances of the addresses
+    /// blocked by governance, if any of its changed keys did
+    fn transfer_blocked_event(
+        &self,
+        changed_keys: &BTreeSet<Key>,
+        hash: &str,
+        height: BlockHeight,
+    ) -> Option<Event> {
+        let blocked = read_blocked_addresses(&self.wl_storage)
+            .expect("Must be able to read the blocked addresses")?;
+        let owners = blocked_balance_owners(changed_keys, &blocked);
+        if owners.is_empty() {
+            return None;
+        }
+        let mut event = Event {
+            event_type: EventType::TransferBlocked,
+            level: EventLevel::Tx,
+            attributes: Default::default(),
+        };
+        event["hash"] = hash.to_string();
+        event["height"] = height.to_string();
+        event["addresses"] = owners
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        Some(event)
+    }
+}
+
+/// The result code of a tx whose application failed with the given error
+fn error_result_code(err: &Error, is_wrapper: bool) -> ResultCode {
+    match err {
+        Error::TxApply(protocol::Error::GasError(_)) => ResultCode::OutOfGas,
+        Error::TxApply(protocol::Error::ReplayAttempt(_)) => {
+            ResultCode::Replayed
+        }
+        Error::TxApply(
+            protocol::Error::FeeError(_)
+            | protocol::Error::FeeUnshieldingError(_),
+        ) => ResultCode::FeeError,
+        Error::TxApply(protocol::Error::InvalidTxSignature) => {
+            ResultCode::InvalidSig
+        }
+        _ if is_wrapper => ResultCode::InvalidTx,
+        _ => ResultCode::WasmRuntimeError,
+    }
+}
+
+/// Derive the randomness of a block from the hash and height of the last
+/// block, the app hash of its state and the addresses of the validators of its
+/// commit with whether each of them signed it. The signatures themselves are
+/// not hashed. The proposer of the last block and the validators choosing
+/// whether to sign it can bias this value and it is known before the txs of
+/// the block are applied, so it is only weakly random and must not be used
+/// where an unpredictable value is needed.
+fn block_randomness(
+    last_block: Option<&LastBlock>,
+    app_hash: &Hash,
+    votes: &[VoteInfo],
+) -> Hash {
+    let mut data = vec![];
+    if let Some(last_block) = last_block {
+        data.extend(last_block.height.0.to_le_bytes());
+        data.extend(last_block.hash.0);
+    }
+    data.extend(app_hash.0);
+    for VoteInfo {
+        validator,
+        sig_info,
+    } in votes
+    {
+        data.extend(validator.address);
+        data.push(sig_info.is_signed() as u8);
+    }
+    Hash::sha256(data)
 }
 
 /// Convert ABCI vote info to PoS vote info. Any info which fails the conversion
@@ -943,12 +1363,16 @@ mod test_finalize_block {
 
     use data_encoding::HEXUPPER;
     use namada::core::ledger::eth_bridge::storage::wrapped_erc20s;
-    use namada::core::ledger::governance::storage::keys::get_proposal_execution_key;
+    use namada::core::ledger::governance::storage::epoch_hook::EpochHook;
+    use namada::core::ledger::governance::storage::keys::{
+        self as gov_storage, get_proposal_execution_key,
+    };
     use namada::core::ledger::governance::storage::proposal::ProposalType;
     use namada::core::ledger::governance::storage::vote::{
         StorageProposalVote, VoteType,
     };
     use namada::core::ledger::replay_protection;
+    use namada::core::ledger::storage_api::governance::register_epoch_hook;
     use namada::core::types::storage::KeySeg;
     use namada::eth_bridge::storage::bridge_pool::{
         self, get_key_from_hash, get_nonce_key, get_signed_root_key,
@@ -978,7 +1402,6 @@ mod test_finalize_block {
     use namada::proto::{Code, Data, Section, Signature};
     use namada::types::dec::POS_DECIMAL_PRECISION;
     use namada::types::ethereum_events::{EthAddress, Uint as ethUint};
-    use namada::types::hash::Hash;
     use namada::types::keccak::KeccakHash;
     use namada::types::key::testing::common_sk_from_simple_seed;
     use namada::types::key::tm_consensus_key_raw_hash;
@@ -1152,6 +1575,12 @@ mod test_finalize_block {
             assert_eq!(event.event_type.to_string(), String::from("accepted"));
             let code = event.attributes.get("code").expect("Test failed");
             assert_eq!(code, &index.rem_euclid(2).to_string());
+            assert!(event.attributes.contains_key("inner_hash"));
+            // only the rejected txs carry a rejection reason
+            assert_eq!(
+                event.attributes.contains_key("reason"),
+                index.rem_euclid(2) == 1
+            );
         }
         // verify that the queue of wrapper txs to be processed is correct
         let mut valid_tx = valid_wrappers.iter();
@@ -1267,6 +1696,12 @@ mod test_finalize_block {
             assert_eq!(event.event_type.to_string(), String::from("applied"));
             let code = event.attributes.get("code").expect("Test failed");
             assert_eq!(code, &String::from(ErrorCodes::Undecryptable));
+            let result_code = event
+                .attributes
+                .get(ResultCode::EVENT_ATTRIBUTE)
+                .and_then(|value| ResultCode::from_event_value(value))
+                .expect("Test failed");
+            assert_eq!(result_code, ResultCode::Undecryptable);
             let log = event.attributes.get("log").expect("Test failed");
             assert!(log.contains("Transaction could not be decrypted."))
         }
@@ -1519,6 +1954,13 @@ mod test_finalize_block {
         assert_eq!(result.event_type.to_string(), String::from("applied"));
         let code = result.attributes.get("code").expect("Test failed").as_str();
         assert_eq!(code, String::from(ErrorCodes::Ok).as_str());
+        // protocol txs are exempt from gas
+        let gas_used = result
+            .attributes
+            .get("gas_used")
+            .expect("Test failed")
+            .as_str();
+        assert_eq!(gas_used, "0");
 
         // --- The event is removed from the queue
         assert!(shell.new_ethereum_events().is_empty());
@@ -5396,4 +5838,148 @@ mod test_finalize_block {
             control_receiver.recv().await.expect("Test failed");
         assert_eq!(u64::from(cmd.min_confirmations), 42);
     }
+
+    /// Register the given epoch hooks and run them
+    fn run_epoch_hooks(
+        shell: &mut TestShell,
+        hooks: impl IntoIterator<Item = (u64, TestWasms, u64)>,
+    ) -> Vec<Event> {
+        for (id, wasm, gas_limit) in hooks {
+            let hook = EpochHook {
+                code: wasm.read_bytes(),
+                gas_limit,
+            };
+            register_epoch_hook(&mut shell.wl_storage, id, &hook)
+                .expect("Test failed");
+        }
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_epoch_hooks(&mut shell.shell, &mut response)
+            .expect("Test failed");
+        response.events
+    }
+
+    /// Test that the epoch hooks run with the authority of the proposals that
+    /// registered them only for their own execution, and that the changes of
+    /// a failed hook are dropped
+    #[test]
+    fn test_epoch_hooks() {
+        let (mut shell, _, _, _) = setup();
+        let grace_epoch_key = gov_storage::get_min_proposal_grace_epoch_key();
+        let events = run_epoch_hooks(
+            &mut shell,
+            [
+                (0, TestWasms::TxFail, 1_000_000),
+                (1, TestWasms::TxProposalCode, 1_000_000),
+            ],
+        );
+
+        assert_eq!(events.len(), 2);
+        for (event, (id, success)) in events.iter().zip([(0, false), (1, true)])
+        {
+            assert_eq!(event.event_type, EventType::EpochHook);
+            assert_eq!(event["id"], id.to_string());
+            assert_eq!(event["success"], success.to_string());
+            assert_ne!(event["gas_used"], "0");
+        }
+        // The governance parameter is written by the accepted hook
+        let grace_epochs: u64 = shell
+            .wl_storage
+            .read(&grace_epoch_key)
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(grace_epochs, 9);
+        // The authority of the proposals is revoked after the hooks
+        for id in [0, 1] {
+            assert!(!shell
+                .wl_storage
+                .has_key(&get_proposal_execution_key(id))
+                .expect("Test failed"));
+        }
+    }
+
+    /// Test that the epoch hooks are skipped once the gas pool of the epoch
+    /// is exhausted
+    #[test]
+    fn test_epoch_hooks_gas_pool() {
+        let (mut shell, _, _, _) = setup();
+        shell
+            .wl_storage
+            .write(&gov_storage::get_max_epoch_hooks_gas_key(), 0_u64)
+            .expect("Test failed");
+        let grace_epoch_key = gov_storage::get_min_proposal_grace_epoch_key();
+        let grace_epochs_pre: Option<u64> = shell
+            .wl_storage
+            .read(&grace_epoch_key)
+            .expect("Test failed");
+
+        let events = run_epoch_hooks(
+            &mut shell,
+            [(0, TestWasms::TxProposalCode, 1_000_000)],
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["success"], "false");
+        assert_eq!(events[0]["gas_used"], "0");
+        let grace_epochs_post: Option<u64> = shell
+            .wl_storage
+            .read(&grace_epoch_key)
+            .expect("Test failed");
+        assert_eq!(grace_epochs_pre, grace_epochs_post);
+    }
+
+    /// Test that the block randomness is determined by the last block, the
+    /// app hash and the commit's validators and signed flags only
+    #[test]
+    fn test_block_randomness_is_deterministic() {
+        let last_block = LastBlock {
+            height: BlockHeight(1),
+            hash: BlockHash([1; 32]),
+            time: DateTimeUtc::now(),
+        };
+        let app_hash = Hash([2; 32]);
+        let vote = |address: u8, sig_info| VoteInfo {
+            validator: Validator {
+                address: vec![address; 20].try_into().unwrap(),
+                power: 1_u64.try_into().unwrap(),
+            },
+            sig_info,
+        };
+        let signed = tendermint::abci::types::BlockSignatureInfo::LegacySigned;
+        let absent = tendermint::abci::types::BlockSignatureInfo::Flag(
+            tendermint::block::BlockIdFlag::Absent,
+        );
+        let votes = vec![vote(1, signed), vote(2, absent)];
+
+        let randomness = block_randomness(Some(&last_block), &app_hash, &votes);
+        // The same inputs, e.g. on another node, give the same randomness,
+        // whatever the time of the last block
+        let other_node_block = LastBlock {
+            time: DateTimeUtc::now() + DurationSecs(1),
+            ..last_block.clone()
+        };
+        assert_eq!(
+            block_randomness(Some(&other_node_block), &app_hash, &votes),
+            randomness
+        );
+
+        // Any hashed input changes it
+        let next_block = LastBlock {
+            height: BlockHeight(2),
+            ..last_block.clone()
+        };
+        let changed = [
+            block_randomness(Some(&next_block), &app_hash, &votes),
+            block_randomness(None, &app_hash, &votes),
+            block_randomness(Some(&last_block), &Hash([3; 32]), &votes),
+            block_randomness(
+                Some(&last_block),
+                &app_hash,
+                &[vote(1, signed), vote(2, signed)],
+            ),
+            block_randomness(Some(&last_block), &app_hash, &votes[..1]),
+        ];
+        for other in changed {
+            assert_ne!(other, randomness);
+        }
+    }
 }
@@ -4,15 +4,18 @@ use namada::core::ledger::governance::storage::keys as gov_storage;
 use namada::core::ledger::governance::storage::proposal::{
     AddRemove, PGFAction, ProposalType, StoragePgfFunding,
 };
+use namada::core::ledger::governance::storage::vote::StorageProposalVote;
 use namada::core::ledger::governance::utils::{
-    compute_proposal_result, ProposalVotes, TallyResult, TallyType, TallyVote,
-    VotePower,
+    compute_proposal_result, ProposalResult, ProposalVotes, TallyResult,
+    TallyType, TallyVote, VotePower,
 };
 use namada::core::ledger::governance::ADDRESS as gov_address;
 use namada::core::ledger::pgf::storage::keys as pgf_storage;
 use namada::core::ledger::pgf::storage::steward::StewardDetail;
 use namada::core::ledger::pgf::ADDRESS;
 use namada::core::ledger::storage_api::governance as gov_api;
+use namada::ledger::events::{EventLevel, EventType};
+use namada::ledger::gas::GasMetering;
 use namada::ledger::governance::utils::ProposalEvent;
 use namada::ledger::pos::BondId;
 use namada::ledger::protocol;
@@ -23,7 +26,7 @@ use namada::proof_of_stake::parameters::PosParams;
 use namada::proof_of_stake::{bond_amount, read_total_stake};
 use namada::proto::{Code, Data};
 use namada::types::address::Address;
-use namada::types::storage::Epoch;
+use namada::types::storage::{BlockHeight, Epoch, Key};
 
 use super::utils::force_read;
 use super::*;
@@ -78,6 +81,12 @@ where
         shell
             .wl_storage
             .write(&proposal_result_key, proposal_result)?;
+        response.events.push(proposal_tally_event(
+            id,
+            proposal_end_epoch,
+            &proposal_result,
+            true,
+        ));
 
         let transfer_address = match proposal_result.result {
             TallyResult::Passed => {
@@ -199,6 +208,186 @@ where
     Ok(proposals_result)
 }
 
+/// Emit the intermediate tally of the votes of every proposal whose voting
+/// period is ongoing in the current epoch, weighted by the stake of this epoch
+pub fn emit_proposal_tallies<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let current_epoch = shell.wl_storage.storage.block.epoch;
+    let params = read_pos_params(&shell.wl_storage)?;
+    // A proposal being voted on has a grace epoch after the current epoch and
+    // at most the max proposal period after its voting start epoch, so it's
+    // found in the index of the committing proposals of these epochs
+    let max_proposal_period =
+        gov_api::get_max_proposal_period(&shell.wl_storage)?;
+    let mut ids = BTreeSet::new();
+    for grace_epoch in
+        (current_epoch.0 + 1)..=(current_epoch.0 + max_proposal_period)
+    {
+        ids.extend(gov_api::get_proposal_ids_by_grace_epoch(
+            &shell.wl_storage,
+            Epoch(grace_epoch),
+        )?);
+    }
+    for id in ids {
+        let voting_start_epoch: Epoch = force_read(
+            &shell.wl_storage,
+            &gov_storage::get_voting_start_epoch_key(id),
+        )?;
+        let voting_end_epoch: Epoch = force_read(
+            &shell.wl_storage,
+            &gov_storage::get_voting_end_epoch_key(id),
+        )?;
+        if current_epoch < voting_start_epoch
+            || current_epoch >= voting_end_epoch
+        {
+            continue;
+        }
+        let proposal_type: ProposalType = force_read(
+            &shell.wl_storage,
+            &gov_storage::get_proposal_type_key(id),
+        )?;
+        let proposal_author: Address =
+            force_read(&shell.wl_storage, &gov_storage::get_author_key(id))?;
+        let is_steward = pgf::is_steward(&shell.wl_storage, &proposal_author)?;
+        let tally_type = TallyType::from(proposal_type, is_steward);
+        let total_voting_power =
+            read_total_stake(&shell.wl_storage, &params, current_epoch)?;
+        let votes = compute_proposal_votes(
+            &shell.wl_storage,
+            &params,
+            id,
+            current_epoch,
+        )?;
+        let proposal_result =
+            compute_proposal_result(votes, total_voting_power, tally_type);
+        response.events.push(proposal_tally_event(
+            id,
+            current_epoch,
+            &proposal_result,
+            false,
+        ));
+    }
+    Ok(())
+}
+
+/// An event of the tally of the votes of a proposal at an epoch, which is
+/// final at the end of its voting period
+fn proposal_tally_event(
+    id: u64,
+    epoch: Epoch,
+    proposal_result: &ProposalResult,
+    is_final: bool,
+) -> Event {
+    let mut event = Event {
+        event_type: EventType::ProposalTally,
+        level: EventLevel::Block,
+        attributes: Default::default(),
+    };
+    event["proposal_id"] = id.to_string();
+    event["epoch"] = epoch.to_string();
+    event["final"] = is_final.to_string();
+    event["tally_result"] = proposal_result.result.to_string();
+    event["total_voting_power"] =
+        proposal_result.total_voting_power.to_string();
+    event["yay_power"] = proposal_result.total_yay_power.to_string();
+    event["nay_power"] = proposal_result.total_nay_power.to_string();
+    event["abstain_power"] = proposal_result.total_abstain_power.to_string();
+    event
+}
+
+/// The events of the proposals created and of the votes cast by an applied
+/// tx, found from the keys that it changed
+pub fn proposal_tx_events<S>(
+    storage: &S,
+    changed_keys: &BTreeSet<Key>,
+    hash: &str,
+    height: BlockHeight,
+) -> Result<Vec<Event>>
+where
+    S: StorageRead,
+{
+    let mut events = vec![];
+    for key in changed_keys {
+        if gov_storage::is_proposal_type_key(key) {
+            let id = gov_storage::get_proposal_id(key)
+                .expect("A proposal key must have an id");
+            let author: Address =
+                force_read(storage, &gov_storage::get_author_key(id))?;
+            let proposal_type: ProposalType = force_read(storage, key)?;
+            let voting_start_epoch: Epoch = force_read(
+                storage,
+                &gov_storage::get_voting_start_epoch_key(id),
+            )?;
+            let voting_end_epoch: Epoch = force_read(
+                storage,
+                &gov_storage::get_voting_end_epoch_key(id),
+            )?;
+            let grace_epoch: Epoch =
+                force_read(storage, &gov_storage::get_grace_epoch_key(id))?;
+            // The content is only checked against its hash, so it may not be
+            // a valid map of the discussion metadata
+            let content: BTreeMap<String, String> = storage
+                .read(&gov_storage::get_content_key(id))
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let mut event = Event {
+                event_type: EventType::ProposalCreated,
+                level: EventLevel::Tx,
+                attributes: Default::default(),
+            };
+            event["hash"] = hash.to_string();
+            event["height"] = height.to_string();
+            event["proposal_id"] = id.to_string();
+            event["author"] = author.to_string();
+            event["proposal_type"] = proposal_type.to_string();
+            event["voting_start_epoch"] = voting_start_epoch.to_string();
+            event["voting_end_epoch"] = voting_end_epoch.to_string();
+            event["grace_epoch"] = grace_epoch.to_string();
+            // The discussion metadata of the proposal content
+            for field in ["title", "authors", "discussions-to", "created"] {
+                if let Some(value) = content.get(field) {
+                    event[field.replace('-', "_").as_str()] = value.clone();
+                }
+            }
+            events.push(event);
+        } else if gov_storage::is_vote_key(key) {
+            let vote = match storage.read::<StorageProposalVote>(key)? {
+                Some(vote) => vote,
+                None => continue,
+            };
+            let (id, validator, voter) = (
+                gov_storage::get_proposal_id(key),
+                gov_storage::get_vote_delegation_address(key),
+                gov_storage::get_voter_address(key),
+            );
+            if let (Some(id), Some(validator), Some(voter)) =
+                (id, validator, voter)
+            {
+                let mut event = Event {
+                    event_type: EventType::ProposalVote,
+                    level: EventLevel::Tx,
+                    attributes: Default::default(),
+                };
+                event["hash"] = hash.to_string();
+                event["height"] = height.to_string();
+                event["proposal_id"] = id.to_string();
+                event["voter"] = voter.to_string();
+                event["validator"] = validator.to_string();
+                event["vote"] = vote.to_string();
+                events.push(event);
+            }
+        }
+    }
+    Ok(events)
+}
+
 fn compute_proposal_votes<S>(
     storage: &S,
     params: &PosParams,
@@ -286,6 +475,7 @@ where
             &mut shell.vp_wasm_cache,
             &mut shell.tx_wasm_cache,
             None,
+            None,
         );
         shell
             .wl_storage
@@ -315,6 +505,102 @@ where
     }
 }
 
+/// Run the epoch hooks registered by governance, in the order in which they
+/// were registered, with the gas left in the pool of the epoch. A hook runs
+/// with the authority of the proposal that registered it, which is only
+/// granted for the execution of the hook.
+pub fn execute_epoch_hooks<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let hooks = gov_api::get_epoch_hooks(&shell.wl_storage)?;
+    if hooks.is_empty() {
+        return Ok(());
+    }
+    let mut available_gas =
+        gov_api::get_parameters(&shell.wl_storage)?.max_epoch_hooks_gas;
+    let current_epoch = shell.wl_storage.storage.block.epoch;
+
+    for (id, hook) in hooks {
+        let code_hash = hook.code_hash();
+        let gas_limit = hook.gas_limit.min(available_gas);
+        let (success, gas_used) = if gas_limit == 0 {
+            tracing::warn!(
+                "Skipped the epoch hook {id}: no gas left in the pool of the \
+                 epoch {current_epoch}"
+            );
+            (false, 0)
+        } else {
+            let pending_execution_key =
+                gov_storage::get_proposal_execution_key(id);
+            shell.wl_storage.write(&pending_execution_key, ())?;
+
+            let mut tx =
+                Tx::from_type(TxType::Decrypted(DecryptedTx::Decrypted));
+            tx.header.chain_id = shell.chain_id.clone();
+            tx.set_data(Data::new(encode(&id)));
+            tx.set_code(Code::new(hook.code, None));
+
+            let mut gas_meter = TxGasMeter::new_from_sub_limit(
+                Gas::from_whole_units(gas_limit),
+            );
+            let tx_result = protocol::dispatch_tx(
+                tx,
+                &[],
+                TxIndex::default(),
+                &mut gas_meter,
+                &mut shell.wl_storage,
+                &mut shell.vp_wasm_cache,
+                &mut shell.tx_wasm_cache,
+                None,
+                None,
+            );
+            // Revoke the authority of the proposal, whatever the result of
+            // the hook
+            shell.wl_storage.delete(&pending_execution_key)?;
+            let gas_used = gas_meter
+                .get_tx_consumed_gas()
+                .get_whole_gas_units()
+                .min(gas_limit);
+            let success = match tx_result {
+                Ok(tx_result) if tx_result.is_accepted() => {
+                    shell.wl_storage.commit_tx();
+                    true
+                }
+                Ok(_) => {
+                    tracing::warn!("The epoch hook {id} was rejected");
+                    shell.wl_storage.drop_tx();
+                    false
+                }
+                Err(err) => {
+                    tracing::warn!("The epoch hook {id} failed: {err}");
+                    shell.wl_storage.drop_tx();
+                    false
+                }
+            };
+            (success, gas_used)
+        };
+        available_gas = available_gas.saturating_sub(gas_used);
+
+        let mut event = Event {
+            event_type: EventType::EpochHook,
+            level: EventLevel::Block,
+            attributes: Default::default(),
+        };
+        event["id"] = id.to_string();
+        event["epoch"] = current_epoch.to_string();
+        event["code_hash"] = code_hash.to_string();
+        event["success"] = success.to_string();
+        event["gas_used"] = gas_used.to_string();
+        response.events.push(event);
+    }
+    Ok(())
+}
+
 fn execute_pgf_steward_proposal<S>(
     storage: &mut S,
     stewards: HashSet<AddRemove<Address>>,
@@ -407,3 +693,147 @@ where
 
     Ok(true)
 }
+
+#[cfg(test)]
+mod test_governance {
+    use namada::core::ledger::governance::storage::vote::VoteType;
+    use namada::core::ledger::governance::utils::Vote;
+    use namada::types::transaction::governance::VoteProposalData;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::setup;
+    use crate::wallet::defaults;
+
+    /// Write a proposal with the given epochs, indexed by its grace epoch
+    fn write_proposal<S>(
+        storage: &mut S,
+        id: u64,
+        voting_start_epoch: Epoch,
+        voting_end_epoch: Epoch,
+        grace_epoch: Epoch,
+    ) where
+        S: StorageWrite,
+    {
+        storage
+            .write(
+                &gov_storage::get_proposal_type_key(id),
+                ProposalType::Default(None),
+            )
+            .unwrap();
+        storage
+            .write(&gov_storage::get_author_key(id), defaults::albert_address())
+            .unwrap();
+        storage
+            .write(
+                &gov_storage::get_voting_start_epoch_key(id),
+                voting_start_epoch,
+            )
+            .unwrap();
+        storage
+            .write(&gov_storage::get_voting_end_epoch_key(id), voting_end_epoch)
+            .unwrap();
+        storage
+            .write(&gov_storage::get_grace_epoch_key(id), grace_epoch)
+            .unwrap();
+        storage
+            .write(
+                &gov_storage::get_committing_proposals_key(id, grace_epoch.0),
+                (),
+            )
+            .unwrap();
+    }
+
+    /// Vote yay on a proposal with a delegation to the default validator
+    fn vote<S>(storage: &mut S, id: u64, voter: Address)
+    where
+        S: StorageRead + StorageWrite,
+    {
+        gov_api::vote_proposal(
+            storage,
+            VoteProposalData {
+                id,
+                vote: StorageProposalVote::Yay(VoteType::Default),
+                voter,
+                delegations: vec![defaults::validator_address()],
+            },
+        )
+        .unwrap();
+    }
+
+    /// Test that an intermediate tally is only emitted for the proposals being
+    /// voted on in the current epoch
+    #[test]
+    fn test_emit_proposal_tallies() {
+        let (mut shell, _, _, _) = setup();
+        let current_epoch = shell.wl_storage.storage.block.epoch;
+        // Being voted on
+        write_proposal(
+            &mut shell.wl_storage,
+            0,
+            current_epoch,
+            current_epoch + 2,
+            current_epoch + 3,
+        );
+        // Not yet voted on
+        write_proposal(
+            &mut shell.wl_storage,
+            1,
+            current_epoch + 1,
+            current_epoch + 2,
+            current_epoch + 3,
+        );
+        vote(&mut shell.wl_storage, 0, defaults::validator_address());
+
+        let mut response = shim::response::FinalizeBlock::default();
+        emit_proposal_tallies(&mut shell.shell, &mut response).unwrap();
+        let tallies: Vec<_> = response
+            .events
+            .iter()
+            .filter(|event| event.event_type == EventType::ProposalTally)
+            .collect();
+        assert_eq!(tallies.len(), 1);
+        let tally = tallies[0];
+        assert_eq!(tally["proposal_id"], "0");
+        assert_eq!(tally["epoch"], current_epoch.to_string());
+        assert_eq!(tally["final"], "false");
+        assert_ne!(tally["yay_power"], "0");
+        assert_eq!(tally["nay_power"], "0");
+    }
+
+    /// Test that a page of the votes of a proposal is the matching slice of
+    /// all its votes
+    #[test]
+    fn test_proposal_votes_page() {
+        let (mut shell, _, _, _) = setup();
+        let id = 0;
+        for voter in [
+            defaults::albert_address(),
+            defaults::bertha_address(),
+            defaults::christel_address(),
+        ] {
+            vote(&mut shell.wl_storage, id, voter);
+        }
+        let voters = |votes: Vec<Vote>| -> Vec<Address> {
+            votes.into_iter().map(|vote| vote.delegator).collect()
+        };
+        let all =
+            voters(gov_api::get_proposal_votes(&shell.wl_storage, id).unwrap());
+        assert_eq!(all.len(), 3);
+
+        let page = |offset, limit| {
+            voters(
+                gov_api::get_proposal_votes_page(
+                    &shell.wl_storage,
+                    id,
+                    offset,
+                    limit,
+                )
+                .unwrap(),
+            )
+        };
+        assert_eq!(page(0, 2), all[..2]);
+        assert_eq!(page(1, 1), all[1..2]);
+        assert_eq!(page(2, 5), all[2..]);
+        assert!(page(3, 1).is_empty());
+    }
+}
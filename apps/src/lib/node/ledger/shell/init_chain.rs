@@ -5,8 +5,11 @@ use std::hash::Hash;
 use namada::ledger::parameters::Parameters;
 use namada::ledger::storage::traits::StorageHasher;
 use namada::ledger::storage::{DBIter, DB};
-use namada::ledger::storage_api::token::{credit_tokens, write_denom};
+use namada::ledger::storage_api::token::{
+    credit_tokens, write_denom, write_metadata,
+};
 use namada::ledger::storage_api::StorageWrite;
+use namada::ledger::testnet_pow;
 use namada::ledger::{ibc, pos};
 use namada::proof_of_stake::BecomeValidator;
 use namada::types::address::Address;
@@ -14,6 +17,7 @@ use namada::types::hash::Hash as CodeHash;
 use namada::types::key::*;
 use namada::types::time::{DateTimeUtc, TimeZone, Utc};
 use namada::vm::validate_untrusted_wasm;
+use namada::vm::wasm::run::inject_gas_metering;
 use namada_sdk::eth_bridge::EthBridgeStatus;
 use namada_sdk::proof_of_stake::PosParams;
 
@@ -24,7 +28,8 @@ use crate::config::genesis::chain::{
 };
 use crate::config::genesis::templates::{TokenBalances, TokenConfig};
 use crate::config::genesis::transactions::{
-    BondTx, EstablishedAccountTx, Signed as SignedTx, ValidatorAccountTx,
+    BondTx, EstablishedAccountTx, FaucetTokenTx, Signed as SignedTx,
+    ValidatorAccountTx,
 };
 use crate::facade::tendermint::v0_37::abci::{request, response};
 use crate::facade::tendermint_proto::google::protobuf;
@@ -75,15 +80,9 @@ where
             let chain_dir = self.base_dir.join(chain_id);
             genesis::make_dev_genesis(_num_validators, chain_dir)
         };
-        #[cfg(all(
-            any(test, feature = "benches"),
-            not(feature = "integration")
-        ))]
-        {
-            // update the native token from the genesis file
-            let native_token = genesis.get_native_token().clone();
-            self.wl_storage.storage.native_token = native_token;
-        }
+        // update the native token from the genesis file
+        let native_token = genesis.get_native_token().clone();
+        self.wl_storage.storage.native_token = native_token;
 
         let ts: protobuf::Timestamp = init.time.into();
         let initial_height = init
@@ -101,6 +100,10 @@ where
         let parameters = genesis.get_chain_parameters(&self.wasm_dir);
         self.store_wasms(&parameters)?;
         parameters.init_storage(&mut self.wl_storage)?;
+        parameters::init_native_token(
+            &mut self.wl_storage,
+            genesis.get_native_token(),
+        )?;
 
         // Initialize governance parameters
         let gov_params = genesis.get_gov_params();
@@ -147,6 +150,8 @@ where
         self.init_token_accounts(&genesis);
         self.init_token_balances(&genesis);
         self.apply_genesis_txs_established_account(&genesis, &mut vp_cache);
+        self.apply_genesis_txs_vesting(&genesis);
+        self.apply_genesis_txs_faucet(&genesis);
         self.apply_genesis_txs_validator_account(
             &genesis,
             &mut vp_cache,
@@ -214,8 +219,6 @@ where
             let code = wasm_loader::read_wasm(&self.wasm_dir, name)
                 .map_err(Error::ReadingWasm)?;
             let code_hash = CodeHash::sha256(&code);
-            let code_len = u64::try_from(code.len())
-                .map_err(|e| Error::LoadingWasm(e.to_string()))?;
 
             let elements = full_name.split('.').collect::<Vec<&str>>();
             let checksum = elements.get(1).ok_or_else(|| {
@@ -232,28 +235,10 @@ where
                 || tx_whitelist.contains(&code_hash.to_string().to_lowercase())
                 || vp_whitelist.contains(&code_hash.to_string().to_lowercase())
             {
-                validate_untrusted_wasm(&code)
-                    .map_err(|e| Error::LoadingWasm(e.to_string()))?;
-
-                #[cfg(not(test))]
-                if name.starts_with("tx_") {
-                    self.tx_wasm_cache.pre_compile(&code);
-                } else if name.starts_with("vp_") {
-                    self.vp_wasm_cache.pre_compile(&code);
-                }
-
-                let code_key = Key::wasm_code(&code_hash);
-                let code_len_key = Key::wasm_code_len(&code_hash);
-                let hash_key = Key::wasm_hash(name);
-                let code_name_key = Key::wasm_code_name(name.to_owned());
-
-                self.wl_storage.write_bytes(&code_key, code)?;
-                self.wl_storage.write(&code_len_key, code_len)?;
-                self.wl_storage.write_bytes(&hash_key, code_hash)?;
+                self.store_wasm(name, code, code_hash)?;
                 if &code_hash == implicit_vp_code_hash {
                     is_implicit_vp_stored = true;
                 }
-                self.wl_storage.write_bytes(&code_name_key, code_hash)?;
             } else {
                 tracing::warn!("The wasm {name} isn't whitelisted.");
             }
@@ -268,6 +253,48 @@ where
         Ok(())
     }
 
+    /// Validate, instrument and store the code of a wasm under its name
+    pub(super) fn store_wasm(
+        &mut self,
+        name: &str,
+        code: Vec<u8>,
+        code_hash: CodeHash,
+    ) -> Result<()> {
+        let code_len = u64::try_from(code.len())
+            .map_err(|e| Error::LoadingWasm(e.to_string()))?;
+        validate_untrusted_wasm(&code)
+            .map_err(|e| Error::LoadingWasm(e.to_string()))?;
+        // Inject the gas metering once at deploy time, the raw code
+        // is kept for auditing
+        let instrumented_code = inject_gas_metering(&code)
+            .map_err(|e| Error::LoadingWasm(e.to_string()))?;
+        let instrumented_hash = CodeHash::sha256(&instrumented_code);
+
+        #[cfg(not(test))]
+        if name.starts_with("tx_") {
+            self.tx_wasm_cache.pre_compile(&code);
+        } else if name.starts_with("vp_") {
+            self.vp_wasm_cache.pre_compile(&code);
+        }
+
+        let code_key = Key::wasm_code(&code_hash);
+        let code_len_key = Key::wasm_code_len(&code_hash);
+        let hash_key = Key::wasm_hash(name);
+        let code_name_key = Key::wasm_code_name(name.to_owned());
+        let instrumented_code_key = Key::wasm_instrumented_code(&code_hash);
+        let instrumented_hash_key = Key::wasm_instrumented_hash(&code_hash);
+
+        self.wl_storage.write_bytes(&code_key, code)?;
+        self.wl_storage.write(&code_len_key, code_len)?;
+        self.wl_storage.write_bytes(&hash_key, code_hash)?;
+        self.wl_storage
+            .write_bytes(&instrumented_code_key, instrumented_code)?;
+        self.wl_storage
+            .write_bytes(&instrumented_hash_key, instrumented_hash)?;
+        self.wl_storage.write_bytes(&code_name_key, code_hash)?;
+        Ok(())
+    }
+
     /// Init genesis token accounts
     fn init_token_accounts(&mut self, genesis: &genesis::chain::Finalized) {
         let masp_rewards = address::tokens();
@@ -276,14 +303,26 @@ where
 
             let FinalizedTokenConfig {
                 address,
-                config: TokenConfig { denom, parameters },
+                config:
+                    TokenConfig {
+                        denom,
+                        metadata,
+                        parameters,
+                    },
             } = token;
             // associate a token with its denomination.
             write_denom(&mut self.wl_storage, address, *denom).unwrap();
+            if let Some(metadata) = metadata {
+                write_metadata(&mut self.wl_storage, address, metadata)
+                    .unwrap();
+            }
             parameters.init_storage(address, &mut self.wl_storage);
             // add token addresses to the masp reward conversions lookup table.
+            // The native token is always rewarded, whatever its alias.
             let alias = alias.to_string();
-            if masp_rewards.contains_key(&alias.as_str()) {
+            if masp_rewards.contains_key(&alias.as_str())
+                || address == genesis.get_native_token()
+            {
                 self.wl_storage
                     .storage
                     .conversion_state
@@ -380,6 +419,78 @@ where
         }
     }
 
+    /// Apply genesis txs to set the vesting schedules of established accounts
+    fn apply_genesis_txs_vesting(
+        &mut self,
+        genesis: &genesis::chain::Finalized,
+    ) {
+        if let Some(txs) = &genesis.transactions.vesting {
+            for tx in txs {
+                tracing::debug!(
+                    "Applying genesis tx to set the vesting schedule of {}",
+                    tx.owner
+                );
+                storage_api::account::write_vesting_schedule(
+                    &mut self.wl_storage,
+                    &tx.owner,
+                    &tx.schedule(),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    /// Apply genesis txs to initialize the storage of the testnet faucet
+    fn apply_genesis_txs_faucet(
+        &mut self,
+        genesis: &genesis::chain::Finalized,
+    ) {
+        if let Some(txs) = &genesis.transactions.faucet {
+            for tx in txs {
+                tracing::debug!("Applying genesis tx to initialize the faucet");
+                testnet_pow::init_faucet_storage(
+                    &mut self.wl_storage,
+                    tx.pow_difficulty
+                        .and_then(testnet_pow::Difficulty::try_new),
+                )
+                .unwrap();
+                for FaucetTokenTx {
+                    token,
+                    balance,
+                    withdrawal_limit,
+                    global_withdrawal_limit,
+                } in &tx.tokens
+                {
+                    let token_address = &genesis
+                        .tokens
+                        .token
+                        .get(token)
+                        .expect("Token of the faucet not found in genesis.")
+                        .address;
+                    tracing::info!(
+                        "Crediting {} {} tokens to the faucet",
+                        balance,
+                        token,
+                    );
+                    credit_tokens(
+                        &mut self.wl_storage,
+                        token_address,
+                        &testnet_pow::ADDRESS,
+                        *balance,
+                    )
+                    .expect("Couldn't credit the balance of the faucet");
+                    testnet_pow::write_withdrawal_limits(
+                        &mut self.wl_storage,
+                        token_address,
+                        *withdrawal_limit,
+                        *global_withdrawal_limit,
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+
     /// Apply genesis txs to initialize validator accounts
     fn apply_genesis_txs_validator_account(
         &mut self,
@@ -518,6 +629,7 @@ where
 mod test {
     use std::collections::BTreeMap;
 
+    use namada::ledger::parameters;
     use namada::ledger::storage::DBIter;
 
     use crate::node::ledger::shell::test_utils::{self, TestShell};
@@ -553,4 +665,17 @@ mod test {
             storage_state.iter(),
         );
     }
+
+    /// Test that the native token of the genesis is stored in the parameters
+    #[test]
+    fn test_init_chain_stores_native_token() {
+        let (shell, _recv, _, _) = test_utils::setup();
+
+        let native_token = parameters::read_native_token(&shell.wl_storage)
+            .expect("Test failed");
+        assert_eq!(
+            native_token.as_ref(),
+            Some(&shell.wl_storage.storage.native_token)
+        );
+    }
 }
@@ -0,0 +1,215 @@
+//! Bookkeeping of the wrapper transactions admitted to the mempool.
+//!
+//! CometBFT only filters out byte-identical txs from its mempool. This
+//! module keeps track of the inner tx hashes of the wrappers that passed
+//! `CheckTx`, so that different wrappers of the same inner tx can be
+//! rejected before being gossiped, and caps the number of pending wrappers
+//! that a single fee payer can have in the mempool.
+
+use std::collections::HashMap;
+
+use namada::types::hash::Hash;
+use namada::types::key::common;
+use namada::types::storage::BlockHeight;
+use thiserror::Error;
+
+/// Number of blocks after which a pending tx that was neither included in a
+/// block nor rechecked is forgotten. This bounds the memory used by txs that
+/// CometBFT evicted from its mempool without notifying the application.
+pub const PENDING_TX_TTL_BLOCKS: u64 = 100;
+
+/// Errors returned when a wrapper tx cannot be admitted to the mempool.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PendingTxError {
+    #[error("Inner transaction hash {0} is already pending in the mempool")]
+    Duplicate(Hash),
+    #[error(
+        "Fee payer {0} has reached the limit of {1} pending transactions in \
+         the mempool"
+    )]
+    TooManyPending(common::PublicKey, u64),
+}
+
+/// A wrapper tx admitted to the mempool.
+#[derive(Debug, Clone)]
+struct PendingTx {
+    /// The public key of the fee payer
+    fee_payer: common::PublicKey,
+    /// The last block height at which the tx was (re)checked
+    last_checked: BlockHeight,
+}
+
+/// The wrapper txs admitted to the mempool, indexed by their inner tx hash.
+#[derive(Debug, Default)]
+pub struct PendingTxs {
+    /// Max number of pending txs per fee payer. No limit if `None`.
+    max_per_fee_payer: Option<u64>,
+    /// The pending txs
+    txs: HashMap<Hash, PendingTx>,
+    /// Number of pending txs of each fee payer
+    per_fee_payer: HashMap<common::PublicKey, u64>,
+}
+
+impl PendingTxs {
+    /// Create a new, empty set of pending txs, with an optional limit of
+    /// pending txs per fee payer.
+    pub fn new(max_per_fee_payer: Option<u64>) -> Self {
+        Self {
+            max_per_fee_payer,
+            ..Default::default()
+        }
+    }
+
+    /// Change the limit of pending txs per fee payer. The txs already pending
+    /// are kept, even if they are over the new limit.
+    pub fn set_max_per_fee_payer(&mut self, max_per_fee_payer: Option<u64>) {
+        self.max_per_fee_payer = max_per_fee_payer;
+    }
+
+    /// Check if a new wrapper tx with the given inner tx hash and fee payer
+    /// can be admitted to the mempool.
+    pub fn check(
+        &self,
+        inner_tx_hash: &Hash,
+        fee_payer: &common::PublicKey,
+    ) -> Result<(), PendingTxError> {
+        if self.txs.contains_key(inner_tx_hash) {
+            return Err(PendingTxError::Duplicate(*inner_tx_hash));
+        }
+        if let Some(max) = self.max_per_fee_payer {
+            let pending = self
+                .per_fee_payer
+                .get(fee_payer)
+                .copied()
+                .unwrap_or_default();
+            if pending >= max {
+                return Err(PendingTxError::TooManyPending(
+                    fee_payer.clone(),
+                    max,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Record a wrapper tx admitted to the mempool, or refresh the height at
+    /// which it was last checked.
+    pub fn insert(
+        &mut self,
+        inner_tx_hash: Hash,
+        fee_payer: common::PublicKey,
+        height: BlockHeight,
+    ) {
+        match self.txs.get_mut(&inner_tx_hash) {
+            Some(pending) => pending.last_checked = height,
+            None => {
+                *self.per_fee_payer.entry(fee_payer.clone()).or_default() += 1;
+                self.txs.insert(
+                    inner_tx_hash,
+                    PendingTx {
+                        fee_payer,
+                        last_checked: height,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Forget a pending tx, either because it was included in a block or
+    /// because it was evicted from the mempool.
+    pub fn remove(&mut self, inner_tx_hash: &Hash) {
+        if let Some(PendingTx { fee_payer, .. }) =
+            self.txs.remove(inner_tx_hash)
+        {
+            if let Some(count) = self.per_fee_payer.get_mut(&fee_payer) {
+                *count -= 1;
+                if *count == 0 {
+                    self.per_fee_payer.remove(&fee_payer);
+                }
+            }
+        }
+    }
+
+    /// Forget the pending txs that have not been checked in the last
+    /// [`PENDING_TX_TTL_BLOCKS`] blocks.
+    pub fn prune(&mut self, height: BlockHeight) {
+        let expired: Vec<Hash> = self
+            .txs
+            .iter()
+            .filter(|(_, pending)| {
+                pending.last_checked.0 + PENDING_TX_TTL_BLOCKS < height.0
+            })
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in expired {
+            self.remove(&hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada::types::key::testing::{keypair_1, keypair_2};
+    use namada::types::key::RefTo;
+
+    use super::*;
+
+    /// Test that duplicate inner txs are rejected until they are removed
+    #[test]
+    fn test_duplicate_pending_tx() {
+        let mut pending = PendingTxs::new(None);
+        let hash = Hash::sha256(b"tx");
+        let pk = keypair_1().ref_to();
+
+        assert!(pending.check(&hash, &pk).is_ok());
+        pending.insert(hash, pk.clone(), BlockHeight(1));
+        assert_eq!(
+            pending.check(&hash, &pk),
+            Err(PendingTxError::Duplicate(hash))
+        );
+
+        pending.remove(&hash);
+        assert!(pending.txs.is_empty());
+        assert!(pending.per_fee_payer.is_empty());
+        assert!(pending.check(&hash, &pk).is_ok());
+    }
+
+    /// Test the limit of pending txs per fee payer
+    #[test]
+    fn test_pending_txs_per_fee_payer() {
+        let mut pending = PendingTxs::new(Some(2));
+        let pk_1 = keypair_1().ref_to();
+        let pk_2 = keypair_2().ref_to();
+
+        pending.insert(Hash::sha256(b"tx1"), pk_1.clone(), BlockHeight(1));
+        pending.insert(Hash::sha256(b"tx2"), pk_1.clone(), BlockHeight(1));
+        assert_eq!(
+            pending.check(&Hash::sha256(b"tx3"), &pk_1),
+            Err(PendingTxError::TooManyPending(pk_1.clone(), 2))
+        );
+        // other fee payers are not affected
+        assert!(pending.check(&Hash::sha256(b"tx3"), &pk_2).is_ok());
+
+        pending.remove(&Hash::sha256(b"tx1"));
+        assert!(pending.check(&Hash::sha256(b"tx3"), &pk_1).is_ok());
+    }
+
+    /// Test that txs that have not been checked for too long are pruned
+    #[test]
+    fn test_prune_pending_txs() {
+        let mut pending = PendingTxs::new(None);
+        let pk = keypair_1().ref_to();
+        let old = Hash::sha256(b"old");
+        let recent = Hash::sha256(b"recent");
+
+        pending.insert(old, pk.clone(), BlockHeight(1));
+        pending.insert(recent, pk.clone(), BlockHeight(1));
+        // a recheck refreshes the tx
+        pending.insert(recent, pk.clone(), BlockHeight(50));
+
+        pending.prune(BlockHeight(PENDING_TX_TTL_BLOCKS + 2));
+        assert_eq!(pending.txs.len(), 1);
+        assert!(pending.check(&old, &pk).is_ok());
+        assert!(pending.check(&recent, &pk).is_err());
+    }
+}
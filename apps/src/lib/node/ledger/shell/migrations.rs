@@ -0,0 +1,56 @@
+//! The storage migrations of the chain upgrades.
+//!
+//! A migration converts the storage written by the previous binary before
+//! it's used by the first block applied by the upgraded one. Migrations are
+//! registered by the name of the upgrade plan they belong to and run only
+//! when that plan is applied.
+
+use namada::core::ledger::masp_commitment_tree;
+use namada::ledger::storage::{DBIter, StorageHasher, WlStorage, DB};
+use namada::ledger::storage_api;
+
+/// The name of the upgrade from which the MASP note commitment tree and the
+/// nullifier set are kept in storage
+pub const MASP_NOTES_UPGRADE: &str = "v0.29.0";
+
+/// A migration of the storage
+pub type Migration<D, H> = fn(&mut WlStorage<D, H>) -> storage_api::Result<()>;
+
+/// The migrations of the upgrade with the given name, in the order in which
+/// they must run
+pub fn migrations<D, H>(name: &str) -> Vec<Migration<D, H>>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    match name {
+        MASP_NOTES_UPGRADE => {
+            vec![
+                masp_commitment_tree::seed_from_recorded_txs as Migration<D, H>,
+            ]
+        }
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod test_migrations {
+    use namada::ledger::storage::mockdb::MockDB;
+    use namada::ledger::storage::testing::TestWlStorage;
+    use namada::ledger::storage::Sha256Hasher;
+
+    use super::*;
+
+    /// Test that only the upgrades with registered migrations run any
+    #[test]
+    fn test_registered_migrations() {
+        assert!(migrations::<MockDB, Sha256Hasher>("v0.0.0").is_empty());
+        let masp_migrations =
+            migrations::<MockDB, Sha256Hasher>(MASP_NOTES_UPGRADE);
+        assert!(!masp_migrations.is_empty());
+        let mut wl_storage = TestWlStorage::default();
+        for migration in masp_migrations {
+            migration(&mut wl_storage).expect("The migration must succeed");
+        }
+    }
+}
@@ -9,17 +9,23 @@ pub mod block_alloc;
 mod finalize_block;
 mod governance;
 mod init_chain;
+mod mempool;
+pub mod migrations;
 pub mod prepare_proposal;
 pub mod process_proposal;
+mod pruning;
 pub(super) mod queries;
+#[cfg(test)]
+mod simulation;
 mod stats;
 #[cfg(any(test, feature = "testing"))]
 #[allow(dead_code)]
 pub mod testing;
 pub mod utils;
 mod vote_extensions;
+mod wasm_reload;
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::mem;
 use std::path::{Path, PathBuf};
@@ -34,7 +40,6 @@ use namada::core::ledger::eth_bridge;
 use namada::ledger::events::log::EventLog;
 use namada::ledger::events::Event;
 use namada::ledger::gas::{Gas, TxGasMeter};
-use namada::ledger::pos::into_tm_voting_power;
 use namada::ledger::pos::namada_proof_of_stake::types::{
     ConsensusValidator, ValidatorSetUpdate,
 };
@@ -52,7 +57,7 @@ use namada::ledger::storage_api::tx::validate_tx_bytes;
 use namada::ledger::storage_api::{self, StorageRead};
 use namada::ledger::{parameters, pos, protocol};
 use namada::proof_of_stake::{self, process_slashes, read_pos_params, slash};
-use namada::proto::{self, Section, Tx};
+use namada::proto::{self, Commitment, Section, Tx};
 use namada::types::address::Address;
 use namada::types::chain::ChainId;
 use namada::types::ethereum_events::EthereumEvent;
@@ -60,8 +65,8 @@ use namada::types::internal::{ExpiredTx, TxInQueue};
 use namada::types::key::*;
 use namada::types::storage::{BlockHeight, Key, TxIndex};
 use namada::types::time::DateTimeUtc;
-use namada::types::transaction::protocol::EthereumTxData;
-use namada::types::transaction::{DecryptedTx, TxType, WrapperTx};
+use namada::types::transaction::protocol::{EthereumTxData, ProtocolTx};
+use namada::types::transaction::{DecryptedTx, ResultCode, TxType, WrapperTx};
 use namada::types::{address, token};
 use namada::vm::wasm::{TxCache, VpCache};
 use namada::vm::{WasmCacheAccess, WasmCacheRwAccess};
@@ -71,7 +76,11 @@ use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
 use thiserror::Error;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
+use tokio::sync::{broadcast, watch};
 
+use self::mempool::{PendingTxError, PendingTxs};
+use self::wasm_reload::WasmReload;
+use super::config_reload::ReloadableConfig;
 use super::ethereum_oracle::{self as oracle, last_processed_block};
 use crate::config::{self, genesis, TendermintMode, ValidatorLocalConfig};
 use crate::facade::tendermint::abci::types::{Misbehavior, MisbehaviorKind};
@@ -97,6 +106,10 @@ fn key_to_tendermint(
     }
 }
 
+/// Max number of events buffered on the event bus for each subscriber. A
+/// subscriber that falls behind by more than this will miss events.
+const EVENT_BUS_CAPACITY: usize = 1024;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Error removing the DB data: {0}")]
@@ -125,6 +138,12 @@ pub enum Error {
     StorageApi(#[from] storage_api::Error),
     #[error("Transaction replay attempt: {0}")]
     ReplayAttempt(String),
+    #[error("Transaction code is not whitelisted: {0}")]
+    TxNotWhitelisted(String),
+    #[error("Transaction code with hash {0} is not stored on chain")]
+    MissingTxCode(String),
+    #[error("{0}")]
+    UpgradeHalt(String),
 }
 
 impl From<Error> for TxResult {
@@ -156,6 +175,9 @@ pub enum ErrorCodes {
     FeeError = 12,
     InvalidVoteExtension = 13,
     TooLarge = 14,
+    DuplicateTx = 15,
+    TooManyPendingTxs = 16,
+    TxNotWhitelisted = 17,
 }
 
 impl ErrorCodes {
@@ -170,7 +192,31 @@ impl ErrorCodes {
             InvalidTx | InvalidSig | InvalidOrder | ExtraTxs
             | Undecryptable | AllocationError | ReplayTx | InvalidChainId
             | ExpiredTx | TxGasLimit | FeeError | InvalidVoteExtension
-            | TooLarge => false,
+            | TooLarge | DuplicateTx | TooManyPendingTxs | TxNotWhitelisted => {
+                false
+            }
+        }
+    }
+}
+
+impl From<ErrorCodes> for ResultCode {
+    fn from(code: ErrorCodes) -> Self {
+        use ErrorCodes::*;
+        match code {
+            Ok => ResultCode::Ok,
+            WasmRuntimeError => ResultCode::WasmRuntimeError,
+            InvalidSig => ResultCode::InvalidSig,
+            Undecryptable => ResultCode::Undecryptable,
+            ReplayTx | DuplicateTx => ResultCode::Replayed,
+            InvalidChainId => ResultCode::InvalidChainId,
+            ExpiredTx => ResultCode::Expired,
+            TxGasLimit => ResultCode::InvalidGasLimit,
+            FeeError => ResultCode::FeeError,
+            TxNotWhitelisted => ResultCode::NotAllowlisted,
+            InvalidTx | InvalidOrder | ExtraTxs | AllocationError
+            | InvalidVoteExtension | TooLarge | TooManyPendingTxs => {
+                ResultCode::InvalidTx
+            }
         }
     }
 }
@@ -414,6 +460,22 @@ where
     pub proposal_data: HashSet<u64>,
     /// Log of events emitted by `FinalizeBlock` ABCI calls.
     event_log: EventLog,
+    /// Wrapper txs admitted to the mempool by `CheckTx`
+    pending_txs: PendingTxs,
+    /// The fee tokens accepted into the mempool with their min gas prices,
+    /// on top of the protocol ones. All the allowed tokens when empty.
+    mempool_fee_tokens: BTreeMap<Address, token::Amount>,
+    /// Bus on which the events emitted by `FinalizeBlock` ABCI calls are
+    /// published to subscribers
+    event_bus: broadcast::Sender<Event>,
+    /// The state of the wasm directory, when the wasms are reloaded on
+    /// rebuilds
+    wasm_reload: Option<WasmReload>,
+    /// The reloadable settings of the node config, when the config is
+    /// reloaded while the node is running
+    config_reload: Option<watch::Receiver<ReloadableConfig>>,
+    /// Whether this binary applies the scheduled upgrade of the chain
+    upgrade_ready: bool,
 }
 
 /// Channels for communicating with an Ethereum oracle.
@@ -461,27 +523,22 @@ where
         let mode = config.shell.tendermint_mode;
         let storage_read_past_height_limit =
             config.shell.storage_read_past_height_limit;
+        let max_pending_txs_per_fee_payer =
+            config.shell.max_pending_txs_per_fee_payer;
+        let mempool_fee_tokens = config.shell.mempool_fee_tokens;
+        let wasm_reload =
+            config.shell.dev_wasm_reload.then(WasmReload::default);
+        let upgrade_ready = config.shell.upgrade_ready;
         if !Path::new(&base_dir).is_dir() {
             std::fs::create_dir(&base_dir)
                 .expect("Creating directory for Namada should not fail");
         }
-        let native_token = if cfg!(feature = "integration")
-            || (!cfg!(test) && !cfg!(feature = "benches"))
-        {
-            let chain_dir = base_dir.join(chain_id.as_str());
-            let genesis =
-                genesis::chain::Finalized::read_toml_files(&chain_dir)
-                    .expect("Missing genesis files");
-            genesis.get_native_token().clone()
-        } else {
-            address::nam()
-        };
 
-        // load last state from storage
+        // load last state from storage. The native token is set below.
         let mut storage = Storage::open(
             db_path,
             chain_id.clone(),
-            native_token,
+            address::nam(),
             db_cache,
             config.shell.storage_read_past_height_limit,
         );
@@ -491,6 +548,26 @@ where
                 tracing::error!("Cannot load the last state from the DB {}", e);
             })
             .expect("PersistentStorage cannot be initialized");
+        // The native token of an initialized chain is read from its
+        // parameters. Otherwise, it's read from the genesis files, which are
+        // only required before the chain is initialized.
+        let (stored_native_token, _gas) = storage
+            .read(&parameters::storage::get_native_token_key())
+            .expect("Reading the native token shouldn't fail");
+        storage.native_token = match stored_native_token {
+            Some(bytes) => Address::try_from_slice(&bytes)
+                .expect("The native token should be a valid address"),
+            None if cfg!(feature = "integration")
+                || (!cfg!(test) && !cfg!(feature = "benches")) =>
+            {
+                let chain_dir = base_dir.join(chain_id.as_str());
+                let genesis =
+                    genesis::chain::Finalized::read_toml_files(&chain_dir)
+                        .expect("Missing genesis files");
+                genesis.get_native_token().clone()
+            }
+            None => address::nam(),
+        };
         let vp_wasm_cache_dir =
             base_dir.join(chain_id.as_str()).join("vp_wasm_cache");
         let tx_wasm_cache_dir =
@@ -582,6 +659,12 @@ where
             proposal_data: HashSet::new(),
             // TODO: config event log params
             event_log: EventLog::default(),
+            pending_txs: PendingTxs::new(max_pending_txs_per_fee_payer),
+            mempool_fee_tokens,
+            event_bus: broadcast::channel(EVENT_BUS_CAPACITY).0,
+            wasm_reload,
+            config_reload: None,
+            upgrade_ready,
         };
         shell.update_eth_oracle(&Default::default());
         shell
@@ -599,6 +682,60 @@ where
         &mut self.event_log
     }
 
+    /// Return a handle to the event bus, to subscribe to the events emitted
+    /// by the ledger.
+    #[inline]
+    pub fn event_bus(&self) -> broadcast::Sender<Event> {
+        self.event_bus.clone()
+    }
+
+    /// Apply the reloadable settings of the node config received on the given
+    /// channel, whenever the config is reloaded
+    pub fn watch_config_reload(
+        &mut self,
+        config_reload: watch::Receiver<ReloadableConfig>,
+    ) {
+        self.config_reload = Some(config_reload);
+    }
+
+    /// Apply the reloadable settings of the node config, if they changed
+    /// since they were last applied
+    fn apply_config_reload(&mut self) {
+        if let Some(config_reload) = self.config_reload.as_mut() {
+            if config_reload.has_changed().unwrap_or_default() {
+                let config = config_reload.borrow_and_update();
+                self.pending_txs.set_max_per_fee_payer(
+                    config.max_pending_txs_per_fee_payer,
+                );
+                self.mempool_fee_tokens = config.mempool_fee_tokens.clone();
+            }
+        }
+    }
+
+    /// Publish the given events to the subscribers of the event bus.
+    fn publish_events(&self, events: &[Event]) {
+        for event in events {
+            // An error only means that there are no subscribers
+            let _ = self.event_bus.send(event.clone());
+        }
+    }
+
+    /// Persist the state held in memory before the node exits. Must only be
+    /// called once no more requests will be handled by the shell.
+    ///
+    /// The DB memtables and WAL are flushed to disk and the indices of the
+    /// WASM compilation caches are written, so that the compiled modules can
+    /// be trusted on the next start.
+    pub fn shutdown(&mut self) {
+        tracing::info!("Flushing the DB to disk...");
+        if let Err(err) = self.wl_storage.storage.db.flush(true) {
+            tracing::error!("Failed to flush the DB: {}", err);
+        }
+        tracing::info!("Persisting the WASM compilation caches...");
+        self.vp_wasm_cache.persist_index();
+        self.tx_wasm_cache.persist_index();
+    }
+
     /// Iterate over the wrapper txs in order
     #[allow(dead_code)]
     fn iter_tx_queue(&mut self) -> impl Iterator<Item = &TxInQueue> {
@@ -853,6 +990,16 @@ where
         );
         response.data = root.0.to_vec().into();
 
+        let (tx_stats, vp_stats) =
+            (self.tx_wasm_cache.stats(), self.vp_wasm_cache.stats());
+        tracing::debug!(
+            tx_cache_hits = tx_stats.hits,
+            tx_cache_misses = tx_stats.misses,
+            vp_cache_hits = vp_stats.hits,
+            vp_cache_misses = vp_stats.misses,
+            "WASM compilation cache stats"
+        );
+
         self.bump_last_processed_eth_block();
         self.broadcast_queued_txs();
 
@@ -957,8 +1104,9 @@ where
     }
 
     /// Checks that neither the wrapper nor the inner transaction have already
-    /// been applied. Requires a [`TempWlStorage`] to perform the check during
-    /// block construction and validation
+    /// been applied and that the optional sequence number of the wrapper is
+    /// the next one of its fee payer. Requires a [`TempWlStorage`] to perform
+    /// the check during block construction and validation
     pub fn replay_protection_checks(
         &self,
         wrapper: &Tx,
@@ -988,12 +1136,84 @@ where
             )));
         }
 
+        // The sequence numbers of the wrappers of a fee payer must follow each
+        // other in the block. The next one is written to the tx write log, so
+        // that it is dropped together with a rejected wrapper.
+        if let Some(header) = wrapper.header().wrapper() {
+            if let Some(sequence) = header.sequence {
+                let fee_payer = header.fee_payer();
+                let expected = storage_api::account::sequence(
+                    &*temp_wl_storage,
+                    &fee_payer,
+                )
+                .expect("Error while reading the sequence number");
+                if sequence != expected {
+                    return Err(Error::ReplayAttempt(format!(
+                        "Wrapper transaction sequence number {sequence} of \
+                         {fee_payer} doesn't match the expected {expected}"
+                    )));
+                }
+                temp_wl_storage
+                    .write_log
+                    .write(
+                        &sequence_key(&fee_payer),
+                        (expected + 1).serialize_to_vec(),
+                    )
+                    .map_err(|e| Error::ReplayAttempt(e.to_string()))?;
+            }
+        }
+
         // Write wrapper hash to WAL
         temp_wl_storage
             .write_tx_hash(wrapper_hash)
             .map_err(|e| Error::ReplayAttempt(e.to_string()))
     }
 
+    /// Checks that the code of the inner transaction of a wrapper is allowed
+    /// by the `tx_whitelist` protocol parameter. An empty whitelist allows any
+    /// transaction.
+    pub fn tx_whitelist_check(&self, wrapper: &Tx) -> Result<()> {
+        let whitelist: Vec<String> = self
+            .wl_storage
+            .read(&parameters::storage::get_tx_whitelist_storage_key())?
+            .unwrap_or_default();
+        if whitelist.is_empty() {
+            return Ok(());
+        }
+        let code_hash = wrapper
+            .get_section(wrapper.code_sechash())
+            .and_then(|section| Section::code_sec(section.as_ref()))
+            .map(|code| code.code.hash());
+        match code_hash {
+            Some(hash)
+                if whitelist.contains(&hash.to_string().to_lowercase()) =>
+            {
+                Ok(())
+            }
+            Some(hash) => Err(Error::TxNotWhitelisted(hash.to_string())),
+            None => Err(Error::TxNotWhitelisted(
+                "missing transaction code".to_string(),
+            )),
+        }
+    }
+
+    /// Checks that the inner transaction code of a wrapper that is only
+    /// referenced by its hash is stored on chain, so that the wrapper is not
+    /// admitted to the mempool only to fail for a missing code.
+    pub fn tx_code_check(&self, wrapper: &Tx) -> Result<()> {
+        let code = wrapper
+            .get_section(wrapper.code_sechash())
+            .and_then(|section| Section::code_sec(section.as_ref()));
+        match code.map(|code| code.code) {
+            Some(Commitment::Hash(hash))
+                if !self.wl_storage.has_key(&Key::wasm_code(&hash))? =>
+            {
+                Err(Error::MissingTxCode(hash.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// If a handle to an Ethereum oracle was provided to the [`Shell`], attempt
     /// to send it an updated configuration, using a configuration
     /// based on Ethereum bridge parameters in blockchain storage.
@@ -1104,7 +1324,7 @@ where
     pub fn mempool_validate(
         &self,
         tx_bytes: &[u8],
-        r#_type: MempoolTxType,
+        r#type: MempoolTxType,
     ) -> response::CheckTx {
         use namada::types::transaction::protocol::{
             ethereum_tx_data_variants, ProtocolTxType,
@@ -1190,6 +1410,23 @@ where
             };
         }
 
+        // Protocol txs lane checks
+        if let TxType::Protocol(protocol_tx) = &tx_type.tx_type {
+            if !protocol_tx.tx.is_allowed_in_mempool() {
+                response.code = ErrorCodes::InvalidTx.into();
+                response.log = format!(
+                    "{INVALID_MSG}: The given protocol tx cannot be added to \
+                     the mempool"
+                );
+                return response;
+            }
+            if let Err(err) = self.validate_protocol_tx_signer(protocol_tx) {
+                response.code = ErrorCodes::InvalidSig.into();
+                response.log = format!("{INVALID_MSG}: {err}");
+                return response;
+            }
+        }
+
         match tx_type.tx_type {
             TxType::Protocol(protocol_tx) => match protocol_tx.tx {
                 ProtocolTxType::EthEventsVext => {
@@ -1341,6 +1578,61 @@ where
                     return response;
                 }
 
+                // Sequence number check. A wrapper with a future sequence
+                // number is kept in the mempool until the previous ones are
+                // applied.
+                if let Some(sequence) = wrapper.sequence {
+                    let fee_payer = wrapper.fee_payer();
+                    let next = storage_api::account::sequence(
+                        &self.wl_storage,
+                        &fee_payer,
+                    )
+                    .expect("Error while reading the sequence number");
+                    if sequence < next {
+                        response.code = ErrorCodes::ReplayTx.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Wrapper transaction sequence \
+                             number {sequence} of {fee_payer} has already \
+                             been used, the next one is {next}"
+                        );
+                        return response;
+                    }
+                }
+
+                // Tx code whitelist check
+                if let Err(e) = self.tx_whitelist_check(&tx) {
+                    response.code = ErrorCodes::TxNotWhitelisted.into();
+                    response.log = format!("{INVALID_MSG}: {e}");
+                    return response;
+                }
+
+                // Tx code by hash check
+                if let Err(e) = self.tx_code_check(&tx) {
+                    response.code = ErrorCodes::InvalidTx.into();
+                    response.log = format!("{INVALID_MSG}: {e}");
+                    return response;
+                }
+
+                // Duplicates and spam check, only for txs that have not
+                // already been admitted to the mempool
+                if let MempoolTxType::NewTransaction = r#type {
+                    if let Err(e) =
+                        self.pending_txs.check(&inner_tx_hash, &wrapper.pk)
+                    {
+                        response.code = match e {
+                            PendingTxError::Duplicate(_) => {
+                                ErrorCodes::DuplicateTx
+                            }
+                            PendingTxError::TooManyPending(..) => {
+                                ErrorCodes::TooManyPendingTxs
+                            }
+                        }
+                        .into();
+                        response.log = format!("{INVALID_MSG}: {e}");
+                        return response;
+                    }
+                }
+
                 // Validate wrapper fees
                 if let Err(e) = self.wrapper_fee_check(
                     &wrapper,
@@ -1355,6 +1647,13 @@ where
                     response.log = format!("{INVALID_MSG}: {e}");
                     return response;
                 }
+
+                // The node's own fee rules, on top of the protocol ones
+                if let Err(e) = self.mempool_fee_check(&wrapper) {
+                    response.code = ErrorCodes::FeeError.into();
+                    response.log = format!("{INVALID_MSG}: {e}");
+                    return response;
+                }
             }
             TxType::Raw => {
                 response.code = ErrorCodes::InvalidTx.into();
@@ -1377,6 +1676,95 @@ where
         response
     }
 
+    /// Check that a protocol tx is signed by the protocol key of a consensus
+    /// validator, either of the current epoch or of the epoch of the last
+    /// committed block.
+    pub fn validate_protocol_tx_signer(
+        &self,
+        protocol_tx: &ProtocolTx,
+    ) -> std::result::Result<(), String> {
+        let pos_queries = self.wl_storage.pos_queries();
+        let current_epoch = self.wl_storage.storage.get_current_epoch().0;
+        let last_epoch = self.wl_storage.storage.last_epoch;
+        let is_validator =
+            [last_epoch, current_epoch].into_iter().any(|epoch| {
+                pos_queries
+                    .get_validator_from_protocol_pk(
+                        &protocol_tx.pk,
+                        Some(epoch),
+                    )
+                    .is_ok()
+            });
+        if is_validator {
+            Ok(())
+        } else {
+            Err(format!(
+                "Protocol tx signer {} is not the protocol key of a consensus \
+                 validator",
+                protocol_tx.pk
+            ))
+        }
+    }
+
+    /// Validate a transaction request with [`Shell::mempool_validate`] and
+    /// keep track of the wrapper txs pending in the mempool.
+    pub fn check_tx(
+        &mut self,
+        tx_bytes: &[u8],
+        r#type: MempoolTxType,
+    ) -> response::CheckTx {
+        self.apply_config_reload();
+        let response = self.mempool_validate(tx_bytes, r#type.clone());
+
+        let wrapper = Tx::try_from(tx_bytes).ok().and_then(|tx| {
+            match tx.header().tx_type {
+                TxType::Wrapper(wrapper) => {
+                    Some((tx.raw_header_hash(), wrapper.pk))
+                }
+                _ => None,
+            }
+        });
+        if let Some((inner_tx_hash, fee_payer)) = wrapper {
+            if response.code == ErrorCodes::Ok.into() {
+                let height = self.wl_storage.storage.get_last_block_height();
+                self.pending_txs.insert(inner_tx_hash, fee_payer, height);
+            } else if let MempoolTxType::RecheckTransaction = r#type {
+                // The tx is evicted from the mempool
+                self.pending_txs.remove(&inner_tx_hash);
+            }
+        }
+
+        response
+    }
+
+    /// Check that the fee token of the wrapper is accepted into the mempool by
+    /// the node's config, at least at the configured gas price
+    fn mempool_fee_check(&self, wrapper: &WrapperTx) -> Result<()> {
+        if self.mempool_fee_tokens.is_empty() {
+            return Ok(());
+        }
+        let minimum_gas_price = self
+            .mempool_fee_tokens
+            .get(&wrapper.fee.token)
+            .ok_or_else(|| {
+                Error::TxApply(protocol::Error::FeeError(format!(
+                    "The provided {} token is not accepted by this node for \
+                     fee payment",
+                    wrapper.fee.token
+                )))
+            })?;
+        if wrapper.fee.amount_per_gas_unit < *minimum_gas_price {
+            return Err(Error::TxApply(protocol::Error::FeeError(format!(
+                "Fee amount {:?} do not match the minimum amount {:?} \
+                 accepted by this node for token {}",
+                wrapper.fee.amount_per_gas_unit,
+                minimum_gas_price,
+                wrapper.fee.token
+            ))));
+        }
+        Ok(())
+    }
+
     /// Check that the Wrapper's signer has enough funds to pay fees. If a block
     /// proposer is provided, updates the balance of the fee payer
     #[allow(clippy::too_many_arguments)]
@@ -1594,6 +1982,7 @@ mod test_utils {
     use namada::tendermint::abci::types::VoteInfo;
     use namada::types::address;
     use namada::types::chain::ChainId;
+    use namada::types::dec::Dec;
     use namada::types::ethereum_events::Uint;
     use namada::types::hash::Hash;
     use namada::types::keccak::KeccakHash;
@@ -2152,6 +2541,9 @@ mod test_utils {
             max_expected_time_per_block: DurationSecs(3600),
             max_proposal_bytes: Default::default(),
             max_block_gas: 100,
+            max_encrypted_txs_share: Dec::one() / 3,
+            max_decrypted_txs_share: Dec::one() / 3,
+            min_protocol_txs_share: Dec::one() / 3,
             vp_whitelist: vec![],
             tx_whitelist: vec![],
             implicit_vp_code_hash: Default::default(),
@@ -2164,6 +2556,9 @@ mod test_utils {
             fee_unshielding_gas_limit: 0,
             fee_unshielding_descriptions_limit: 0,
             minimum_gas_price: Default::default(),
+            wasm_limits: Default::default(),
+            storage_deposit_per_byte: Default::default(),
+            blocked_addresses: None,
         };
         params
             .init_storage(&mut shell.wl_storage)
@@ -2555,6 +2950,43 @@ mod shell_tests {
         assert_eq!(rsp.code, 0.into());
     }
 
+    /// Test that protocol txs which are not signed by the protocol key of a
+    /// validator are rejected, even if the vote extensions they carry are
+    /// valid.
+    #[test]
+    fn test_mempool_protocol_tx_non_validator_signer() {
+        const LAST_HEIGHT: BlockHeight = BlockHeight(3);
+
+        let (shell, _recv, _, _) = test_utils::setup_at_height(LAST_HEIGHT);
+
+        let (protocol_key, _) = wallet::defaults::validator_keys();
+        let validator_addr = wallet::defaults::validator_address();
+
+        let ext = ethereum_events::Vext {
+            validator_addr,
+            block_height: LAST_HEIGHT,
+            ethereum_events: vec![EthereumEvent::TransfersToNamada {
+                nonce: 0u64.into(),
+                transfers: vec![],
+            }],
+        }
+        .sign(&protocol_key);
+        let signer = wallet::defaults::bertha_keypair();
+        let tx = EthereumTxData::EthEventsVext(ext)
+            .sign(&signer, shell.chain_id.clone())
+            .to_bytes();
+        let rsp = shell.mempool_validate(&tx, Default::default());
+        assert_eq!(rsp.code, ErrorCodes::InvalidSig.into());
+        assert_eq!(
+            rsp.log,
+            format!(
+                "Mempool validation failed: Protocol tx signer {} is not the \
+                 protocol key of a consensus validator",
+                signer.ref_to()
+            )
+        );
+    }
+
     /// Test if Ethereum events validation fails, if the underlying
     /// protocol transaction type is different from the vote extension
     /// contained in the transaction's data field.
@@ -2819,6 +3251,113 @@ mod shell_tests {
         )
     }
 
+    /// A signed wrapper tx with the given sequence number
+    fn sequenced_wrapper(
+        shell: &test_utils::TestShell,
+        keypair: &common::SecretKey,
+        sequence: u64,
+    ) -> Tx {
+        let mut wrapper = Tx::from_type(TxType::Wrapper(Box::new(
+            WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: 0.into(),
+                    token: shell.wl_storage.storage.native_token.clone(),
+                },
+                keypair.ref_to(),
+                Epoch(0),
+                GAS_LIMIT_MULTIPLIER.into(),
+                None,
+            )
+            .with_sequence(sequence),
+        )));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+        wrapper.set_data(Data::new(sequence.to_le_bytes().to_vec()));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, keypair.clone())].into_iter().collect(),
+            None,
+        )));
+        wrapper
+    }
+
+    /// Mempool validation must reject wrappers with an already used sequence
+    /// number and keep the ones with a future sequence number
+    #[test]
+    fn test_mempool_sequence_check() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let keypair = super::test_utils::gen_keypair();
+        let fee_payer = Address::from(&keypair.ref_to());
+        for _ in 0..2 {
+            storage_api::account::increment_sequence(
+                &mut shell.wl_storage,
+                &fee_payer,
+            )
+            .expect("Test failed");
+        }
+
+        let wrapper = sequenced_wrapper(&shell, &keypair, 1);
+        let result = shell.mempool_validate(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::ReplayTx.into());
+        assert_eq!(
+            result.log,
+            format!(
+                "Mempool validation failed: Wrapper transaction sequence \
+                 number 1 of {fee_payer} has already been used, the next one \
+                 is 2"
+            )
+        );
+
+        for sequence in [2, 3] {
+            let wrapper = sequenced_wrapper(&shell, &keypair, sequence);
+            let result = shell.mempool_validate(
+                wrapper.to_bytes().as_ref(),
+                MempoolTxType::NewTransaction,
+            );
+            assert_ne!(result.code, ErrorCodes::ReplayTx.into());
+        }
+    }
+
+    /// The sequence numbers of the wrappers of a fee payer included in a block
+    /// must follow each other
+    #[test]
+    fn test_block_sequence_check() {
+        let (shell, _recv, _, _) = test_utils::setup();
+        let keypair = super::test_utils::gen_keypair();
+        let mut temp_wl_storage = TempWlStorage::new(&shell.wl_storage.storage);
+
+        let first = sequenced_wrapper(&shell, &keypair, 0);
+        shell
+            .replay_protection_checks(&first, &mut temp_wl_storage)
+            .expect("Test failed");
+        temp_wl_storage.write_log.commit_tx();
+
+        // A wrapper reusing the sequence number with a different hash
+        let mut reused = sequenced_wrapper(&shell, &keypair, 0);
+        reused.set_data(Data::new("other data".as_bytes().to_owned()));
+        assert!(matches!(
+            shell.replay_protection_checks(&reused, &mut temp_wl_storage),
+            Err(Error::ReplayAttempt(_))
+        ));
+        temp_wl_storage.write_log.drop_tx();
+
+        // A wrapper skipping a sequence number
+        let skipping = sequenced_wrapper(&shell, &keypair, 2);
+        assert!(matches!(
+            shell.replay_protection_checks(&skipping, &mut temp_wl_storage),
+            Err(Error::ReplayAttempt(_))
+        ));
+        temp_wl_storage.write_log.drop_tx();
+
+        let second = sequenced_wrapper(&shell, &keypair, 1);
+        shell
+            .replay_protection_checks(&second, &mut temp_wl_storage)
+            .expect("Test failed");
+    }
+
     /// Check that a transaction with a wrong chain id gets discarded
     #[test]
     fn test_wrong_chain_id() {
@@ -3007,6 +3546,65 @@ mod shell_tests {
         assert_eq!(result.code, ErrorCodes::FeeError.into());
     }
 
+    // Check that a wrapper paying less than the node's own minimum gas price
+    // is rejected from the mempool, even if the protocol accepts it
+    #[test]
+    fn test_mempool_fee_tokens() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let chain_id = shell.chain_id.clone();
+
+        let make_wrapper = |amount_per_gas_unit: u64| {
+            let mut wrapper =
+                Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                    Fee {
+                        amount_per_gas_unit: amount_per_gas_unit.into(),
+                        token: native_token.clone(),
+                    },
+                    crate::wallet::defaults::albert_keypair().ref_to(),
+                    Epoch(0),
+                    GAS_LIMIT_MULTIPLIER.into(),
+                    None,
+                ))));
+            wrapper.header.chain_id = chain_id.clone();
+            wrapper
+                .set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+            wrapper
+                .set_data(Data::new("transaction data".as_bytes().to_owned()));
+            wrapper.add_section(Section::Signature(Signature::new(
+                wrapper.sechashes(),
+                [(0, crate::wallet::defaults::albert_keypair())]
+                    .into_iter()
+                    .collect(),
+                None,
+            )));
+            wrapper
+        };
+        shell.mempool_fee_tokens =
+            BTreeMap::from([(native_token.clone(), 200.into())]);
+
+        let result = shell.mempool_validate(
+            make_wrapper(100).to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::FeeError.into());
+
+        let result = shell.mempool_validate(
+            make_wrapper(200).to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::Ok.into());
+
+        // A token that isn't listed is rejected
+        shell.mempool_fee_tokens =
+            BTreeMap::from([(address::apfel(), 0.into())]);
+        let result = shell.mempool_validate(
+            make_wrapper(200).to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::FeeError.into());
+    }
+
     // Check that a wrapper transactions whose fees cannot be paid is rejected
     #[test]
     fn test_insufficient_balance_for_fee() {
@@ -3041,6 +3639,71 @@ mod shell_tests {
         assert_eq!(result.code, ErrorCodes::FeeError.into());
     }
 
+    /// Mempool validation must reject a wrapper of an inner tx that is
+    /// already pending in the mempool, even if the wrapper itself differs
+    #[test]
+    fn test_duplicate_pending_tx() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+
+        let make_wrapper = |amount_per_gas_unit: u64| {
+            let mut wrapper =
+                Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                    Fee {
+                        amount_per_gas_unit: amount_per_gas_unit.into(),
+                        token: shell.wl_storage.storage.native_token.clone(),
+                    },
+                    crate::wallet::defaults::albert_keypair().ref_to(),
+                    Epoch(0),
+                    GAS_LIMIT_MULTIPLIER.into(),
+                    None,
+                ))));
+            wrapper.header.chain_id = shell.chain_id.clone();
+            wrapper
+                .set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+            wrapper
+                .set_data(Data::new("transaction data".as_bytes().to_owned()));
+            wrapper.add_section(Section::Signature(Signature::new(
+                wrapper.sechashes(),
+                [(0, crate::wallet::defaults::albert_keypair())]
+                    .into_iter()
+                    .collect(),
+                None,
+            )));
+            wrapper
+        };
+        let wrapper = make_wrapper(100);
+        let other_wrapper = make_wrapper(200);
+        assert_ne!(wrapper.header_hash(), other_wrapper.header_hash());
+
+        let result = shell.check_tx(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::Ok.into());
+
+        // A different wrapper of the same inner tx is rejected
+        let result = shell.check_tx(
+            other_wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::DuplicateTx.into());
+        assert_eq!(
+            result.log,
+            format!(
+                "Mempool validation failed: Inner transaction hash {} is \
+                 already pending in the mempool",
+                wrapper.raw_header_hash()
+            )
+        );
+
+        // Rechecking the pending wrapper is still fine
+        let result = shell.check_tx(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::RecheckTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::Ok.into());
+    }
+
     // Check that a fee overflow in the wrapper transaction is rejected
     #[test]
     fn test_wrapper_fee_overflow() {
@@ -3075,6 +3738,44 @@ mod shell_tests {
         assert_eq!(result.code, ErrorCodes::FeeError.into());
     }
 
+    /// Test that a wrapper whose inner tx code is referenced by the hash of a
+    /// code that is not stored on chain is rejected
+    #[test]
+    fn test_missing_code_hash_rejected() {
+        let (shell, _recv, _, _) = test_utils::setup();
+        let keypair = crate::wallet::defaults::albert_keypair();
+
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: 100.into(),
+                    token: shell.wl_storage.storage.native_token.clone(),
+                },
+                keypair.ref_to(),
+                Epoch(0),
+                GAS_LIMIT_MULTIPLIER.into(),
+                None,
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::from_hash(
+            namada::types::hash::Hash::sha256(b"unknown_code"),
+            None,
+        ));
+        wrapper.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, keypair)].into_iter().collect(),
+            None,
+        )));
+
+        let result = shell.mempool_validate(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::InvalidTx.into());
+        assert!(result.log.contains("is not stored on chain"));
+    }
+
     /// Test max tx bytes parameter in CheckTx
     #[test]
     fn test_max_tx_bytes_check_tx() {
@@ -1,17 +1,24 @@
 //! Implementation of the [`RequestPrepareProposal`] ABCI++ method for the Shell
 
+use std::collections::BTreeMap;
+
 use namada::core::hints;
 use namada::core::ledger::gas::TxGasMeter;
+use namada::ledger::parameters;
 use namada::ledger::pos::PosQueries;
 use namada::ledger::protocol::get_fee_unshielding_transaction;
 use namada::ledger::storage::{DBIter, StorageHasher, TempWlStorage, DB};
+use namada::ledger::storage_api::tx::validate_tx_bytes;
+use namada::ledger::storage_api::StorageRead;
 use namada::proof_of_stake::find_validator_by_raw_hash;
 use namada::proto::Tx;
 use namada::types::address::Address;
 use namada::types::internal::TxInQueue;
 use namada::types::key::tm_raw_hash_to_string;
 use namada::types::time::DateTimeUtc;
-use namada::types::transaction::{DecryptedTx, TxType};
+use namada::types::token;
+use namada::types::transaction::{DecryptedTx, Fee, TxType};
+use namada::types::uint::Uint;
 use namada::vm::wasm::{TxCache, VpCache};
 use namada::vm::WasmCacheAccess;
 
@@ -142,8 +149,9 @@ where
         let mut vp_wasm_cache = self.vp_wasm_cache.clone();
         let mut tx_wasm_cache = self.tx_wasm_cache.clone();
 
-        let txs = txs
-            .iter()
+        let txs = self
+            .order_mempool_txs(txs)
+            .into_iter()
             .filter_map(|tx_bytes| {
                 match self.validate_wrapper_bytes(tx_bytes, block_time, &mut temp_wl_storage, &mut vp_wasm_cache, &mut tx_wasm_cache, block_proposer) {
                     Ok(gas) => {
@@ -193,6 +201,112 @@ where
         (txs, alloc)
     }
 
+    /// Order the txs retrieved from the mempool by priority. The txs whose
+    /// fee payer is in the validator's `priority_addresses` come first, then
+    /// the txs that pay a higher fee per gas unit. The txs with the same
+    /// priority are kept in their order of arrival in the mempool.
+    ///
+    /// The txs of a fee payer that carry a sequence number are kept together
+    /// in sequence order, as a later sequence number could not be applied
+    /// before an earlier one. The group is ordered by its best priority.
+    ///
+    /// Fee prices in different tokens are compared relatively to the minimum
+    /// gas price of their token, as accepted by this validator or else as
+    /// set in the protocol parameters. The txs paying in a token without a
+    /// minimum gas price come last.
+    fn order_mempool_txs<'txs>(
+        &self,
+        txs: &'txs [TxBytes],
+    ) -> Vec<&'txs TxBytes> {
+        let local_config = match &self.mode {
+            ShellMode::Validator {
+                local_config: Some(local_config),
+                ..
+            } => Some(local_config),
+            _ => None,
+        };
+        let priority_addresses =
+            local_config.map(|config| &config.priority_addresses);
+        let min_gas_prices: BTreeMap<Address, token::Amount> =
+            match local_config {
+                Some(config) => config
+                    .accepted_gas_tokens
+                    .iter()
+                    .map(|(token, price)| (token.clone(), *price))
+                    .collect(),
+                None => self
+                    .wl_storage
+                    .read(&parameters::storage::get_gas_cost_key())
+                    .expect("Must be able to read gas cost parameter")
+                    .unwrap_or_default(),
+            };
+        // The fee price relative to the min gas price of its token, with 18
+        // decimal places
+        let relative_price = |fee: &Fee| -> Option<Uint> {
+            let min_price = min_gas_prices.get(&fee.token)?.raw_amount();
+            Some(
+                fee.amount_per_gas_unit
+                    .raw_amount()
+                    .checked_mul_div(Uint::exp10(18), min_price)
+                    .map(|(price, _)| price)
+                    .unwrap_or(Uint::MAX),
+            )
+        };
+        // Each group holds either a tx without a sequence number or all the
+        // txs of a fee payer with a sequence number, at the position of the
+        // first one to arrive
+        let mut groups: Vec<(_, Vec<(Option<u64>, &'txs TxBytes)>)> = vec![];
+        let mut payer_groups: BTreeMap<Address, usize> = BTreeMap::new();
+        for tx_bytes in txs {
+            let wrapper = Tx::try_from(tx_bytes.as_ref()).ok().and_then(|tx| {
+                match tx.header().tx_type {
+                    TxType::Wrapper(wrapper) => Some(wrapper),
+                    _ => None,
+                }
+            });
+            let priority = wrapper
+                .as_ref()
+                .map(|wrapper| {
+                    let is_prioritized = priority_addresses
+                        .map(|addresses| {
+                            addresses.contains(&wrapper.fee_payer())
+                        })
+                        .unwrap_or_default();
+                    (is_prioritized, relative_price(&wrapper.fee))
+                })
+                .unwrap_or_default();
+            match wrapper.and_then(|wrapper| {
+                wrapper
+                    .sequence
+                    .map(|sequence| (wrapper.fee_payer(), sequence))
+            }) {
+                Some((fee_payer, sequence)) => {
+                    let index =
+                        *payer_groups.entry(fee_payer).or_insert_with(|| {
+                            groups.push((priority, vec![]));
+                            groups.len() - 1
+                        });
+                    let (group_priority, group_txs) = &mut groups[index];
+                    *group_priority = (*group_priority).max(priority);
+                    group_txs.push((Some(sequence), tx_bytes));
+                }
+                None => groups.push((priority, vec![(None, tx_bytes)])),
+            }
+        }
+        for (_, group_txs) in groups.iter_mut() {
+            group_txs.sort_by_key(|(sequence, _)| *sequence);
+        }
+        // n.b. the sort is stable, so the arrival order breaks the ties
+        groups.sort_by(|(priority_a, _), (priority_b, _)| {
+            priority_b.cmp(priority_a)
+        });
+        groups
+            .into_iter()
+            .flat_map(|(_, group_txs)| group_txs)
+            .map(|(_, tx_bytes)| tx_bytes)
+            .collect()
+    }
+
     /// Validity checks on a wrapper tx
     #[allow(clippy::too_many_arguments)]
     fn validate_wrapper_bytes<CA>(
@@ -207,6 +321,13 @@ where
     where
         CA: 'static + WasmCacheAccess + Sync,
     {
+        // Txs above the max tx size would be rejected by `ProcessProposal`
+        if !validate_tx_bytes(&self.wl_storage, tx_bytes.len())
+            .expect("Failed to get max tx bytes param from storage")
+        {
+            return Err(());
+        }
+
         let tx = Tx::try_from(tx_bytes).map_err(|_| ())?;
 
         // If tx doesn't have an expiration it is valid. If time cannot be
@@ -229,6 +350,8 @@ where
             self.replay_protection_checks(&tx, temp_wl_storage)
                 .map_err(|_| ())?;
 
+            self.tx_whitelist_check(&tx).map_err(|_| ())?;
+
             // Check fees
             match self.wrapper_fee_check(
                 &wrapper,
@@ -1079,6 +1202,58 @@ mod test_prepare_proposal {
         assert!(result.txs.is_empty());
     }
 
+    /// Check that a tx above the max tx size is not included in the block
+    #[test]
+    fn test_exceeding_max_tx_bytes_tx() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+
+        let keypair = crate::wallet::defaults::daewon_keypair();
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: 1.into(),
+                    token: shell.wl_storage.storage.native_token.clone(),
+                },
+                keypair.ref_to(),
+                Epoch(0),
+                GAS_LIMIT_MULTIPLIER.into(),
+                None,
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+        wrapper.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, keypair)].into_iter().collect(),
+            None,
+        )));
+        let tx_bytes = wrapper.to_bytes();
+
+        // The tx fits in the max tx size
+        namada::ledger::parameters::update_max_tx_bytes_parameter(
+            &mut shell.wl_storage,
+            tx_bytes.len() as u32,
+        )
+        .unwrap();
+        let req = RequestPrepareProposal {
+            txs: vec![tx_bytes.clone().into()],
+            ..Default::default()
+        };
+        assert_eq!(shell.prepare_proposal(req).txs.len(), 1);
+
+        // The max tx size was lowered by governance
+        namada::ledger::parameters::update_max_tx_bytes_parameter(
+            &mut shell.wl_storage,
+            tx_bytes.len() as u32 - 1,
+        )
+        .unwrap();
+        let req = RequestPrepareProposal {
+            txs: vec![tx_bytes.into()],
+            ..Default::default()
+        };
+        assert!(shell.prepare_proposal(req).txs.is_empty());
+    }
+
     // Check that a wrapper requiring more gas than its limit is not included in
     // the block
     #[test]
@@ -1132,6 +1307,7 @@ mod test_prepare_proposal {
                     namada::core::types::address::nam(),
                     Amount::from(1),
                 )]),
+                priority_addresses: Default::default(),
             });
         }
 
@@ -1224,6 +1400,7 @@ mod test_prepare_proposal {
                     namada::core::types::address::nam(),
                     Amount::from(100),
                 )]),
+                priority_addresses: Default::default(),
             });
         }
 
@@ -1489,4 +1666,118 @@ mod test_prepare_proposal {
             assert_eq!(found_event, event2);
         }
     }
+
+    /// Make a wrapper tx with the given fee per gas unit and optional
+    /// sequence number, for the mempool ordering tests
+    fn make_ordering_wrapper(
+        shell: &TestShell,
+        fee: u64,
+        token: &Address,
+        keypair: &common::SecretKey,
+        sequence: Option<u64>,
+    ) -> TxBytes {
+        let mut wrapper = WrapperTx::new(
+            Fee {
+                amount_per_gas_unit: fee.into(),
+                token: token.clone(),
+            },
+            keypair.ref_to(),
+            Epoch(0),
+            GAS_LIMIT_MULTIPLIER.into(),
+            None,
+        );
+        wrapper.sequence = sequence;
+        let mut tx = Tx::from_type(TxType::Wrapper(Box::new(wrapper)));
+        tx.header.chain_id = shell.chain_id.clone();
+        tx.set_data(Data::new(fee.to_le_bytes().to_vec()));
+        tx.to_bytes().into()
+    }
+
+    /// Test that the txs from the mempool are ordered by priority address,
+    /// then by fee price, then by arrival
+    #[test]
+    fn test_mempool_txs_ordering() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let bertha = crate::wallet::defaults::bertha_keypair();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let other_token = address::testing::established_address_1();
+        if let ShellMode::Validator { local_config, .. } = &mut shell.mode {
+            *local_config = Some(ValidatorLocalConfig {
+                accepted_gas_tokens: std::collections::HashMap::from([
+                    (native_token.clone(), 1.into()),
+                    (other_token.clone(), 100.into()),
+                ]),
+                priority_addresses: BTreeSet::from([Address::from(
+                    &bertha.ref_to(),
+                )]),
+            });
+        }
+
+        let make_wrapper =
+            |fee: u64, token: &Address, keypair: &common::SecretKey| {
+                make_ordering_wrapper(&shell, fee, token, keypair, None)
+            };
+        let albert = crate::wallet::defaults::albert_keypair();
+        let low_fee = make_wrapper(1, &native_token, &albert);
+        let high_fee = make_wrapper(10, &native_token, &albert);
+        let low_fee_later = make_wrapper(1, &native_token, &gen_keypair());
+        let prioritized = make_wrapper(1, &native_token, &bertha);
+        // a larger raw amount of a token with a higher min gas price is a
+        // lower fee price
+        let other_token_fee = make_wrapper(50, &other_token, &albert);
+        // a token without min gas price comes last
+        let unknown_token_fee = make_wrapper(
+            1_000,
+            &address::testing::established_address_2(),
+            &albert,
+        );
+        let txs = vec![
+            unknown_token_fee.clone(),
+            low_fee.clone(),
+            other_token_fee.clone(),
+            high_fee.clone(),
+            low_fee_later.clone(),
+            prioritized.clone(),
+        ];
+
+        let ordered: Vec<TxBytes> =
+            shell.order_mempool_txs(&txs).into_iter().cloned().collect();
+        assert_eq!(
+            ordered,
+            vec![
+                prioritized,
+                high_fee,
+                low_fee,
+                low_fee_later,
+                other_token_fee,
+                unknown_token_fee
+            ]
+        );
+    }
+
+    /// Test that the txs of a fee payer with sequence numbers are kept in
+    /// sequence order, at the priority of its best fee price
+    #[test]
+    fn test_mempool_txs_ordering_keeps_sequence() {
+        let (shell, _recv, _, _) = test_utils::setup();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let albert = crate::wallet::defaults::albert_keypair();
+        let bertha = crate::wallet::defaults::bertha_keypair();
+
+        let first_low_fee =
+            make_ordering_wrapper(&shell, 1, &native_token, &albert, Some(0));
+        let second_high_fee =
+            make_ordering_wrapper(&shell, 10, &native_token, &albert, Some(1));
+        let other_payer =
+            make_ordering_wrapper(&shell, 5, &native_token, &bertha, Some(0));
+        let txs = vec![
+            other_payer.clone(),
+            second_high_fee.clone(),
+            first_low_fee.clone(),
+        ];
+
+        let ordered: Vec<TxBytes> =
+            shell.order_mempool_txs(&txs).into_iter().cloned().collect();
+        assert_eq!(ordered, vec![first_low_fee, second_high_fee, other_payer]);
+    }
 }
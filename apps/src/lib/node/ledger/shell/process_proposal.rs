@@ -9,13 +9,14 @@ use namada::ledger::protocol::get_fee_unshielding_transaction;
 use namada::ledger::storage::TempWlStorage;
 use namada::ledger::storage_api::tx::validate_tx_bytes;
 use namada::proof_of_stake::find_validator_by_raw_hash;
+use namada::proto::VerifiedSigs;
 use namada::types::internal::TxInQueue;
 use namada::types::transaction::protocol::{
     ethereum_tx_data_variants, ProtocolTxType,
 };
 use namada_sdk::eth_bridge::{EthBridgeQueries, SendValsetUpd};
 
-use super::block_alloc::{BlockSpace, EncryptedTxsBins};
+use super::block_alloc::{threshold, BlockSpace, EncryptedTxsBins};
 use super::*;
 use crate::facade::tendermint_proto::v0_37::abci::RequestProcessProposal;
 use crate::node::ledger::shell::block_alloc::{AllocFailure, TxBin};
@@ -39,6 +40,8 @@ pub struct ValidationMeta {
     pub decrypted_queue_has_remaining_txs: bool,
     /// Check if a block has decrypted txs.
     pub has_decrypted_txs: bool,
+    /// The wrapper signatures that were verified in a batch.
+    pub verified_sigs: VerifiedSigs,
 }
 
 impl<D, H> From<&WlStorage<D, H>> for ValidationMeta
@@ -51,14 +54,20 @@ where
             wl_storage.pos_queries().get_max_proposal_bytes().get();
         let max_block_gas =
             namada::core::ledger::gas::get_max_block_gas(wl_storage).unwrap();
-        let encrypted_txs_bin =
-            EncryptedTxsBins::new(max_proposal_bytes, max_block_gas);
+        let max_encrypted_txs_share =
+            threshold::read_max_encrypted_txs_share(wl_storage);
+        let encrypted_txs_bin = EncryptedTxsBins::new(
+            max_proposal_bytes,
+            max_block_gas,
+            max_encrypted_txs_share,
+        );
         let txs_bin = TxBin::init(max_proposal_bytes);
         Self {
             decrypted_queue_has_remaining_txs: false,
             has_decrypted_txs: false,
             encrypted_txs_bins: encrypted_txs_bin,
             txs_bin,
+            verified_sigs: VerifiedSigs::new(),
         }
     }
 }
@@ -166,6 +175,7 @@ where
         let mut tx_queue_iter = self.wl_storage.storage.tx_queue.iter();
         let mut temp_wl_storage = TempWlStorage::new(&self.wl_storage.storage);
         let mut metadata = ValidationMeta::from(&self.wl_storage);
+        metadata.verified_sigs = batch_verify_wrapper_sigs(txs);
         let mut vp_wasm_cache = self.vp_wasm_cache.clone();
         let mut tx_wasm_cache = self.tx_wasm_cache.clone();
 
@@ -313,7 +323,7 @@ where
             |tx| {
                 let tx_chain_id = tx.header.chain_id.clone();
                 let tx_expiration = tx.header.expiration;
-                if let Err(err) = tx.validate_tx() {
+                if let Err(err) = tx.validate_tx_with(&metadata.verified_sigs) {
                     // This occurs if the wrapper / protocol tx signature is
                     // invalid
                     return Err(TxResult {
@@ -329,7 +339,7 @@ where
             Err(tx_result) => return tx_result,
         };
 
-        if let Err(err) = tx.validate_tx() {
+        if let Err(err) = tx.validate_tx_with(&metadata.verified_sigs) {
             return TxResult {
                 code: ErrorCodes::InvalidSig.into(),
                 info: err.to_string(),
@@ -368,6 +378,16 @@ where
                         };
                     }
                 }
+
+                // Tx signer
+                if let Err(err) = self.validate_protocol_tx_signer(&protocol_tx)
+                {
+                    return TxResult {
+                        code: ErrorCodes::InvalidSig.into(),
+                        info: err,
+                    };
+                }
+
                 match protocol_tx.tx {
                     ProtocolTxType::EthEventsVext => {
                         ethereum_tx_data_variants::EthEventsVext::try_from(&tx)
@@ -645,6 +665,14 @@ where
                     };
                 }
 
+                // Tx code whitelist check
+                if let Err(e) = self.tx_whitelist_check(&tx) {
+                    return TxResult {
+                        code: ErrorCodes::TxNotWhitelisted.into(),
+                        info: e.to_string(),
+                    };
+                }
+
                 // Check that the fee payer has sufficient balance.
                 match self.wrapper_fee_check(
                     &wrapper,
@@ -686,6 +714,41 @@ where
     }
 }
 
+/// Verify the ed25519 signatures of the wrapper txs of a proposal in a batch.
+/// If the batch is valid, the signatures are returned so that they don't get
+/// verified again one by one. Otherwise, none of them are returned and each tx
+/// is verified on its own to find the invalid ones.
+fn batch_verify_wrapper_sigs(txs: &[TxBytes]) -> VerifiedSigs {
+    let signatures: Vec<_> = txs
+        .iter()
+        .filter_map(|tx_bytes| Tx::try_from(tx_bytes.as_ref()).ok())
+        .flat_map(|tx| tx.wrapper_ed25519_signatures())
+        .collect();
+    let batch = signatures
+        .iter()
+        .map(|(pk, hash, sig)| (pk, hash.0.as_slice(), sig));
+    match ed25519::verify_batch(batch, rand::thread_rng()) {
+        Ok(()) => signatures
+            .into_iter()
+            .map(|(pk, hash, sig)| {
+                (
+                    common::PublicKey::Ed25519(pk),
+                    hash,
+                    common::Signature::Ed25519(sig),
+                )
+            })
+            .collect(),
+        Err(err) => {
+            tracing::debug!(
+                %err,
+                "The batch verification of the wrapper signatures failed, \
+                 falling back to verifying them one by one"
+            );
+            VerifiedSigs::new()
+        }
+    }
+}
+
 /// We test the failure cases of [`process_proposal`]. The happy flows
 /// are covered by the e2e tests.
 #[cfg(test)]
@@ -816,10 +879,12 @@ mod test_process_proposal {
     fn check_rejected_eth_events(
         shell: &mut TestShell,
         vote_extension: ethereum_events::SignedVext,
-        protocol_key: common::SecretKey,
     ) {
+        // n.b. the protocol tx is signed by a validator, to only test the
+        // validation of the vote extension
+        let protocol_key = shell.mode.get_protocol_key().expect("Test failed");
         let tx = EthereumTxData::EthEventsVext(vote_extension)
-            .sign(&protocol_key, shell.chain_id.clone())
+            .sign(protocol_key, shell.chain_id.clone())
             .to_bytes();
         let request = ProcessProposal { txs: vec![tx] };
         let response = if let Err(TestError::RejectProposal(resp)) =
@@ -866,7 +931,7 @@ mod test_process_proposal {
             ext.sig = test_utils::invalidate_signature(ext.sig);
             ext
         };
-        check_rejected_eth_events(&mut shell, ext, protocol_key);
+        check_rejected_eth_events(&mut shell, ext);
     }
 
     /// Test that if a proposal contains Ethereum events with
@@ -893,7 +958,7 @@ mod test_process_proposal {
             assert!(ext.verify(&protocol_key.ref_to()).is_ok());
             ext
         };
-        check_rejected_eth_events(&mut shell, ext, protocol_key);
+        check_rejected_eth_events(&mut shell, ext);
     }
 
     /// Test that if a proposal contains Ethereum events with
@@ -922,7 +987,51 @@ mod test_process_proposal {
             assert!(ext.verify(&protocol_key.ref_to()).is_ok());
             ext
         };
-        check_rejected_eth_events(&mut shell, ext, protocol_key);
+        check_rejected_eth_events(&mut shell, ext);
+    }
+
+    /// Test that if a proposal contains a protocol tx which is not signed by
+    /// the protocol key of a validator, we reject it, even if the vote
+    /// extension it carries is valid.
+    #[test]
+    fn test_protocol_tx_non_validator_signer_rejected() {
+        let (shell, _recv, _, _) = test_utils::setup_at_height(3u64);
+        let protocol_key = shell.mode.get_protocol_key().expect("Test failed");
+        let addr = shell.mode.get_validator_address().expect("Test failed");
+        let ext = ethereum_events::Vext {
+            validator_addr: addr.clone(),
+            block_height: shell.wl_storage.storage.get_last_block_height(),
+            ethereum_events: vec![EthereumEvent::TransfersToNamada {
+                nonce: 0u64.into(),
+                transfers: vec![],
+            }],
+        }
+        .sign(protocol_key);
+        let signer = wallet::defaults::bertha_keypair();
+        let tx = EthereumTxData::EthEventsVext(ext)
+            .sign(&signer, shell.chain_id.clone())
+            .to_bytes();
+        let request = ProcessProposal { txs: vec![tx] };
+        let response = if let Err(TestError::RejectProposal(resp)) =
+            shell.process_proposal(request)
+        {
+            if let [resp] = resp.as_slice() {
+                resp.clone()
+            } else {
+                panic!("Test failed")
+            }
+        } else {
+            panic!("Test failed")
+        };
+        assert_eq!(response.result.code, u32::from(ErrorCodes::InvalidSig));
+        assert_eq!(
+            response.result.info,
+            format!(
+                "Protocol tx signer {} is not the protocol key of a consensus \
+                 validator",
+                signer.ref_to()
+            )
+        );
     }
 
     /// Test that if a wrapper tx is not signed, the block is rejected
@@ -1038,6 +1147,72 @@ mod test_process_proposal {
         }
     }
 
+    /// Test that the wrapper signatures of a proposal are verified in a batch
+    /// and that none of them are returned if one is invalid
+    #[test]
+    fn test_batch_verify_wrapper_sigs() {
+        let (shell, _recv, _, _) = test_utils::setup_at_height(3u64);
+        // A wrapper tx whose signature is made over the given targets
+        let wrapper = |keypair: common::SecretKey, tamper: bool| {
+            let mut outer_tx =
+                Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                    Fee {
+                        amount_per_gas_unit: Default::default(),
+                        token: shell.wl_storage.storage.native_token.clone(),
+                    },
+                    keypair.ref_to(),
+                    Epoch(0),
+                    GAS_LIMIT_MULTIPLIER.into(),
+                    None,
+                ))));
+            outer_tx.header.chain_id = shell.chain_id.clone();
+            outer_tx
+                .set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+            outer_tx
+                .set_data(Data::new("transaction data".as_bytes().to_owned()));
+            let targets = if tamper {
+                vec![Default::default()]
+            } else {
+                outer_tx.sechashes()
+            };
+            let mut signature = Signature::new(
+                targets,
+                [(0, keypair)].into_iter().collect(),
+                None,
+            );
+            // The signature is made over other targets than the ones it
+            // claims if tampered
+            signature.targets = outer_tx.sechashes();
+            outer_tx.add_section(Section::Signature(signature));
+            TxBytes::from(outer_tx.to_bytes())
+        };
+
+        let txs =
+            vec![wrapper(gen_keypair(), false), wrapper(gen_keypair(), false)];
+        assert_eq!(batch_verify_wrapper_sigs(&txs).len(), 2);
+
+        let txs =
+            vec![wrapper(gen_keypair(), false), wrapper(gen_keypair(), true)];
+        assert!(batch_verify_wrapper_sigs(&txs).is_empty());
+        // The signatures are then checked individually
+        let request = ProcessProposal {
+            txs: txs.into_iter().map(|tx| tx.to_vec()).collect(),
+        };
+        match shell.process_proposal(request) {
+            Ok(_) => panic!("Test failed"),
+            Err(TestError::RejectProposal(response)) => {
+                assert_ne!(
+                    response[0].result.code,
+                    u32::from(ErrorCodes::InvalidSig)
+                );
+                assert_eq!(
+                    response[1].result.code,
+                    u32::from(ErrorCodes::InvalidSig)
+                );
+            }
+        }
+    }
+
     /// Test that if the account submitting the tx is not known and the fee is
     /// non-zero, [`process_proposal`] rejects that block
     #[test]
@@ -1299,6 +1474,7 @@ mod test_process_proposal {
             epoch: Epoch(0),
             gas_limit: GAS_LIMIT_MULTIPLIER.into(),
             unshield_section_hash: None,
+            sequence: None,
         };
 
         let tx = Tx::from_type(TxType::Wrapper(Box::new(wrapper)));
@@ -1735,6 +1911,55 @@ mod test_process_proposal {
         }
     }
 
+    /// Test that a wrapper transaction whose inner tx code is not in the
+    /// non-empty tx whitelist causes a block rejection
+    #[test]
+    fn test_tx_not_whitelisted() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let keypair = crate::wallet::defaults::daewon_keypair();
+        shell
+            .wl_storage
+            .write(
+                &parameters::storage::get_tx_whitelist_storage_key(),
+                vec!["some_hash".to_string()],
+            )
+            .unwrap();
+
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: 1.into(),
+                    token: shell.wl_storage.storage.native_token.clone(),
+                },
+                keypair.ref_to(),
+                Epoch(0),
+                GAS_LIMIT_MULTIPLIER.into(),
+                None,
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new("wasm_code".as_bytes().to_owned(), None));
+        wrapper.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, keypair)].into_iter().collect(),
+            None,
+        )));
+
+        // Run validation
+        let request = ProcessProposal {
+            txs: vec![wrapper.to_bytes()],
+        };
+        match shell.process_proposal(request) {
+            Ok(_) => panic!("Test failed"),
+            Err(TestError::RejectProposal(response)) => {
+                assert_eq!(
+                    response[0].result.code,
+                    u32::from(ErrorCodes::TxNotWhitelisted)
+                );
+            }
+        }
+    }
+
     /// Check that a tx requiring more gas than the block limit causes a block
     /// rejection
     #[test]
@@ -0,0 +1,163 @@
+//! Removal of the governance and PoS data that is no longer needed at the end
+//! of an epoch. An event with the aggregate of the removed data is emitted
+//! before its removal, so that its history can still be queried off-chain.
+//!
+//! The PoS unbonds and slashes are not pruned: the unbonds are removed once
+//! withdrawn, until then they're needed for the withdrawal, and the slashes of
+//! a validator keep applying to every bond that started before them, however
+//! old, so they're read for as long as these bonds exist.
+
+use std::collections::HashMap;
+
+use namada::core::ledger::governance::storage::keys as gov_storage;
+use namada::core::ledger::governance::utils::ProposalResult;
+use namada::ledger::events::{Event, EventLevel, EventType};
+use namada::ledger::pos::namada_proof_of_stake;
+use namada::ledger::storage::{DBIter, StorageHasher, DB};
+use namada::ledger::storage_api::{governance as gov_api, StorageRead};
+use namada::types::storage::Epoch;
+
+use super::{shim, Result, Shell};
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Remove the votes of the proposals whose grace epoch is older than the
+    /// votes retention epochs and the PoS total unbonded records that are no
+    /// longer read by the slashing.
+    pub(super) fn prune_expired_data(
+        &mut self,
+        current_epoch: Epoch,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        let gov_params = gov_api::get_parameters(&self.wl_storage)?;
+        if let Some(grace_epoch) =
+            current_epoch.checked_sub(Epoch(gov_params.votes_retention_epochs))
+        {
+            for id in gov_api::get_proposal_ids_by_grace_epoch(
+                &self.wl_storage,
+                grace_epoch,
+            )? {
+                let result: Option<ProposalResult> = self
+                    .wl_storage
+                    .read(&gov_storage::get_proposal_result_key(id))?;
+                let pruned =
+                    gov_api::prune_proposal_votes(&mut self.wl_storage, id)?;
+                let mut event = pruned_event("governance");
+                event["proposal_id"] = id.to_string();
+                if let Some(result) = result {
+                    event["tally_result"] = result.result.to_string();
+                }
+                event["yay_votes"] = pruned.yay.to_string();
+                event["nay_votes"] = pruned.nay.to_string();
+                event["abstain_votes"] = pruned.abstain.to_string();
+                response.events.push(event);
+            }
+        }
+
+        let num_pruned = namada_proof_of_stake::prune_total_unbonded(
+            &mut self.wl_storage,
+            current_epoch,
+        )?;
+        if num_pruned > 0 {
+            let mut event = pruned_event("pos");
+            event["total_unbonded_records"] = num_pruned.to_string();
+            response.events.push(event);
+        }
+        Ok(())
+    }
+}
+
+/// An event of the data removed from the storage of the given module
+fn pruned_event(module: &str) -> Event {
+    let mut event = Event {
+        event_type: EventType::Pruned,
+        level: EventLevel::Block,
+        attributes: HashMap::new(),
+    };
+    event["module"] = module.to_string();
+    event
+}
+
+#[cfg(test)]
+mod test_pruning {
+    use namada::core::ledger::governance::storage::vote::{
+        StorageProposalVote, VoteType,
+    };
+    use namada::ledger::storage_api::StorageWrite;
+    use namada::types::transaction::governance::VoteProposalData;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::setup;
+    use crate::wallet::defaults;
+
+    /// Test that the votes of a proposal are only removed once its votes
+    /// retention epochs are over, with an event of the removed votes
+    #[test]
+    fn test_prune_proposal_votes() {
+        let (mut shell, _, _, _) = setup();
+        let retention = gov_api::get_parameters(&shell.wl_storage)
+            .unwrap()
+            .votes_retention_epochs;
+        let grace_epoch = Epoch(2);
+        let id = 0;
+        shell
+            .wl_storage
+            .write(
+                &gov_storage::get_committing_proposals_key(id, grace_epoch.0),
+                (),
+            )
+            .unwrap();
+        for (voter, vote) in [
+            (
+                defaults::albert_address(),
+                StorageProposalVote::Yay(VoteType::Default),
+            ),
+            (defaults::bertha_address(), StorageProposalVote::Nay),
+        ] {
+            gov_api::vote_proposal(
+                &mut shell.wl_storage,
+                VoteProposalData {
+                    id,
+                    vote,
+                    voter,
+                    delegations: vec![defaults::validator_address()],
+                },
+            )
+            .unwrap();
+        }
+
+        // The votes are kept during the retention epochs
+        let mut response = shim::response::FinalizeBlock::default();
+        shell
+            .prune_expired_data(grace_epoch + retention - 1, &mut response)
+            .unwrap();
+        assert_eq!(
+            gov_api::get_proposal_votes(&shell.wl_storage, id)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        shell
+            .prune_expired_data(grace_epoch + retention, &mut response)
+            .unwrap();
+        assert!(gov_api::get_proposal_votes(&shell.wl_storage, id)
+            .unwrap()
+            .is_empty());
+        let event = response
+            .events
+            .iter()
+            .find(|event| {
+                event.event_type == EventType::Pruned
+                    && event["module"] == "governance"
+            })
+            .expect("Test failed");
+        assert_eq!(event["proposal_id"], id.to_string());
+        assert_eq!(event["yay_votes"], "1");
+        assert_eq!(event["nay_votes"], "1");
+        assert_eq!(event["abstain_votes"], "0");
+    }
+}
@@ -0,0 +1,367 @@
+//! Deterministic simulation of block execution. Randomly generated sequences
+//! of operations are applied to two independently initialized shells, which
+//! must agree on the app hash of every block. This catches non-determinism in
+//! the execution of blocks before it can halt a live network.
+//!
+//! Every operation is submitted as a wasm tx and executed by the
+//! `FinalizeBlock` handler through the protocol, the same way as on a live
+//! network.
+
+use std::str::FromStr;
+
+use namada::core::ledger::governance::storage::proposal::ProposalType;
+use namada::core::ledger::governance::storage::vote::{
+    StorageProposalVote, VoteType,
+};
+use namada::core::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
+use namada::ledger::gas::Gas;
+use namada::ledger::storage_api::StorageRead;
+use namada::proto::{Code, Tx};
+use namada::types::account::AccountPublicKeysMap;
+use namada::types::address::Address;
+use namada::types::hash::Hash;
+use namada::types::key::{common, RefTo};
+use namada::types::storage::{Epoch, Key};
+use namada::types::time::{DateTimeUtc, DurationSecs};
+use namada::types::token::{DenominatedAmount, Transfer};
+use namada::types::transaction::governance::{
+    InitProposalData, VoteProposalData,
+};
+use namada::types::transaction::pos::Bond;
+use namada::types::transaction::{DecryptedTx, Fee, TxType, WrapperTx};
+use namada_sdk::tx::{
+    TX_BOND_WASM, TX_INIT_PROPOSAL, TX_TRANSFER_WASM, TX_UNBOND_WASM,
+    TX_VOTE_PROPOSAL,
+};
+use namada_test_utils::tx_data::TxWriteData;
+use namada_test_utils::TestWasms;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::Config;
+
+use super::test_utils::{setup, TestShell};
+use super::ErrorCodes;
+use crate::node::ledger::shims::abcipp_shim_types::shim::request::{
+    FinalizeBlock, ProcessedTx,
+};
+use crate::node::ledger::shims::abcipp_shim_types::shim::response::TxResult;
+use crate::wallet::defaults;
+
+/// The gas limit of the simulated wasm txs
+const GAS_LIMIT: u64 = 100_000_000;
+
+/// The number of default accounts used by the operations
+const NUM_ACCOUNTS: usize = 3;
+
+/// An operation applied to both shells
+#[derive(Clone, Debug)]
+enum Op {
+    /// Transfer native tokens between accounts
+    Transfer {
+        source: usize,
+        target: usize,
+        amount: u64,
+    },
+    /// Delegate native tokens to the genesis validator
+    Bond { source: usize, amount: u64 },
+    /// Unbond delegated native tokens from the genesis validator
+    Unbond { source: usize, amount: u64 },
+    /// Submit a default governance proposal
+    InitProposal { author: usize },
+    /// Vote on a governance proposal
+    Vote { voter: usize, id: u64, yay: bool },
+    /// Write a value with a wasm tx included in the next block
+    WriteTx { key: String, value: Vec<u8> },
+    /// Finalize and commit a block
+    Commit,
+    /// Finalize and commit the blocks up to the start of the next epoch
+    NewEpoch,
+}
+
+fn arb_op() -> impl Strategy<Value = Op> {
+    let account = 0..NUM_ACCOUNTS;
+    let amount = 1..10_000_000_u64;
+    prop_oneof![
+        3 => (account.clone(), account.clone(), amount.clone()).prop_map(
+            |(source, target, amount)| Op::Transfer {
+                source,
+                target,
+                amount,
+            }
+        ),
+        2 => (account.clone(), amount.clone())
+            .prop_map(|(source, amount)| Op::Bond { source, amount }),
+        1 => (account.clone(), amount)
+            .prop_map(|(source, amount)| Op::Unbond { source, amount }),
+        1 => account
+            .clone()
+            .prop_map(|author| Op::InitProposal { author }),
+        1 => (account, 0..3_u64, any::<bool>())
+            .prop_map(|(voter, id, yay)| Op::Vote { voter, id, yay }),
+        2 => ("[a-z]{1,8}", vec(any::<u8>(), 0..64))
+            .prop_map(|(key, value)| Op::WriteTx { key, value }),
+        3 => Just(Op::Commit),
+        1 => Just(Op::NewEpoch),
+    ]
+}
+
+/// A simulated node
+struct Node {
+    shell: TestShell,
+    /// The decrypted txs of the next block
+    txs: Vec<ProcessedTx>,
+}
+
+impl Node {
+    fn new() -> Self {
+        let (shell, _, _, _) = setup();
+        Self { shell, txs: vec![] }
+    }
+
+    /// The app hash of the last committed block
+    fn app_hash(&self) -> [u8; 32] {
+        self.shell.wl_storage.storage.merkle_root().0
+    }
+
+    /// The time of the next block, derived from its height so that both
+    /// nodes see the same time
+    fn next_block_time(&self) -> DateTimeUtc {
+        let height = self.shell.wl_storage.storage.get_last_block_height();
+        DateTimeUtc::from_str("2023-01-01T00:00:00Z").unwrap()
+            + DurationSecs(height.0 + 1)
+    }
+
+    fn current_epoch(&self) -> Epoch {
+        self.shell.wl_storage.storage.get_current_epoch().0
+    }
+
+    fn apply(&mut self, op: &Op) {
+        let accounts = accounts();
+        let native_token = self.shell.wl_storage.storage.native_token.clone();
+        let current_epoch = self.current_epoch();
+        let (tx, signer) = match op {
+            Op::Transfer {
+                source,
+                target,
+                amount,
+            } => {
+                let data = Transfer {
+                    source: accounts[*source].clone(),
+                    target: accounts[*target].clone(),
+                    token: native_token,
+                    amount: DenominatedAmount::native((*amount).into()),
+                    key: None,
+                    shielded: None,
+                };
+                (self.wasm_tx(TX_TRANSFER_WASM, data), Some(*source))
+            }
+            Op::Bond { source, amount } => {
+                let data = Bond {
+                    validator: defaults::validator_address(),
+                    amount: (*amount).into(),
+                    source: Some(accounts[*source].clone()),
+                };
+                (self.wasm_tx(TX_BOND_WASM, data), Some(*source))
+            }
+            Op::Unbond { source, amount } => {
+                let data = Bond {
+                    validator: defaults::validator_address(),
+                    amount: (*amount).into(),
+                    source: Some(accounts[*source].clone()),
+                };
+                (self.wasm_tx(TX_UNBOND_WASM, data), Some(*source))
+            }
+            Op::InitProposal { author } => {
+                let mut tx = Tx::from_type(TxType::Raw);
+                tx.set_code(self.wasm_code(TX_INIT_PROPOSAL));
+                let (_, content) =
+                    tx.add_extra_section(b"simulated proposal".to_vec(), None);
+                tx.add_data(InitProposalData {
+                    id: None,
+                    content,
+                    author: accounts[*author].clone(),
+                    r#type: ProposalType::Default(None),
+                    voting_start_epoch: current_epoch.next(),
+                    voting_end_epoch: current_epoch.next().next(),
+                    grace_epoch: current_epoch.next().next().next(),
+                });
+                (tx, Some(*author))
+            }
+            Op::Vote { voter, id, yay } => {
+                let data = VoteProposalData {
+                    id: *id,
+                    vote: if *yay {
+                        StorageProposalVote::Yay(VoteType::Default)
+                    } else {
+                        StorageProposalVote::Nay
+                    },
+                    voter: accounts[*voter].clone(),
+                    delegations: vec![defaults::validator_address()],
+                };
+                (self.wasm_tx(TX_VOTE_PROPOSAL, data), Some(*voter))
+            }
+            Op::WriteTx { key, value } => {
+                // A key without an owner, which doesn't require a signature
+                let mut tx = Tx::from_type(TxType::Raw);
+                tx.set_code(Code::new(
+                    TestWasms::TxWriteStorageKey.read_bytes(),
+                    None,
+                ));
+                tx.add_data(TxWriteData {
+                    key: Key::parse(format!("simulation/{key}")).unwrap(),
+                    value: value.clone(),
+                });
+                (tx, None)
+            }
+            Op::Commit => {
+                self.commit();
+                return;
+            }
+            Op::NewEpoch => {
+                let next_height = self
+                    .shell
+                    .wl_storage
+                    .storage
+                    .get_last_block_height()
+                    .next_height();
+                self.shell.wl_storage.storage.next_epoch_min_start_height =
+                    next_height;
+                self.shell.wl_storage.storage.next_epoch_min_start_time =
+                    self.next_block_time();
+                for _ in 0..=EPOCH_SWITCH_BLOCKS_DELAY {
+                    self.commit();
+                }
+                return;
+            }
+        };
+        let signer = signer.map(|idx| keypairs()[idx].clone());
+        let tx = self.wrap_tx(tx, signer);
+        self.txs.push(tx);
+    }
+
+    /// The code of the wasm stored in genesis under the given name
+    fn wasm_code(&self, name: &str) -> Code {
+        let code_hash = self
+            .shell
+            .wl_storage
+            .read_bytes(&Key::wasm_hash(name))
+            .unwrap()
+            .expect("The simulated wasm txs must be stored in genesis");
+        Code::from_hash(
+            Hash::try_from(&code_hash[..]).unwrap(),
+            Some(name.to_owned()),
+        )
+    }
+
+    /// Make a raw tx that runs the wasm stored under the given name with the
+    /// given data
+    fn wasm_tx(&self, name: &str, data: impl borsh::BorshSerialize) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(self.wasm_code(name));
+        tx.add_data(data);
+        tx
+    }
+
+    /// Sign the raw tx with the keypair of its signer, if any, enqueue its
+    /// wrapper and make the decrypted tx of the next block
+    fn wrap_tx(
+        &mut self,
+        mut tx: Tx,
+        signer: Option<common::SecretKey>,
+    ) -> ProcessedTx {
+        let keypair = defaults::albert_keypair();
+        tx.header.chain_id = self.shell.chain_id.clone();
+        tx.update_header(TxType::Wrapper(Box::new(WrapperTx::new(
+            Fee {
+                amount_per_gas_unit: 0.into(),
+                token: self.shell.wl_storage.storage.native_token.clone(),
+            },
+            keypair.ref_to(),
+            Epoch(0),
+            GAS_LIMIT.into(),
+            None,
+        ))));
+        if let Some(signer) = signer {
+            let public_keys =
+                AccountPublicKeysMap::from_iter([signer.ref_to()]);
+            tx.sign_raw(vec![signer], public_keys, None);
+        }
+        let gas_limit = Gas::from(GAS_LIMIT)
+            .checked_sub(Gas::from(tx.to_bytes().len() as u64))
+            .unwrap();
+        self.shell.enqueue_tx(tx.clone(), gas_limit);
+        tx.update_header(TxType::Decrypted(DecryptedTx::Decrypted));
+        ProcessedTx {
+            tx: tx.to_bytes().into(),
+            result: TxResult {
+                code: ErrorCodes::Ok.into(),
+                info: "".into(),
+            },
+        }
+    }
+
+    fn commit(&mut self) {
+        let mut req = FinalizeBlock {
+            txs: std::mem::take(&mut self.txs),
+            ..Default::default()
+        };
+        req.header.time = self.next_block_time();
+        self.shell.finalize_block(req).expect("Test failed");
+        self.shell.commit();
+    }
+}
+
+/// The accounts used by the operations
+fn accounts() -> [Address; NUM_ACCOUNTS] {
+    [
+        defaults::albert_address(),
+        defaults::bertha_address(),
+        defaults::christel_address(),
+    ]
+}
+
+/// The keypairs of the accounts used by the operations
+fn keypairs() -> [common::SecretKey; NUM_ACCOUNTS] {
+    [
+        defaults::albert_keypair(),
+        defaults::bertha_keypair(),
+        defaults::christel_keypair(),
+    ]
+}
+
+proptest! {
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    /// Check that two shells applying the same operations agree on the app
+    /// hash of every block
+    #[test]
+    fn test_deterministic_block_execution(ops in vec(arb_op(), 1..40)) {
+        test_deterministic_block_execution_aux(ops)?
+    }
+}
+
+fn test_deterministic_block_execution_aux(
+    ops: Vec<Op>,
+) -> Result<(), TestCaseError> {
+    let mut nodes = [Node::new(), Node::new()];
+    prop_assert_eq!(nodes[0].app_hash(), nodes[1].app_hash());
+    for op in ops.into_iter().chain([Op::Commit]) {
+        for node in nodes.iter_mut() {
+            node.apply(&op);
+        }
+        if matches!(op, Op::Commit | Op::NewEpoch) {
+            let height =
+                nodes[0].shell.wl_storage.storage.get_last_block_height();
+            prop_assert_eq!(
+                nodes[0].app_hash(),
+                nodes[1].app_hash(),
+                "The app hashes diverged at height {} after {:?}",
+                height,
+                op
+            );
+        }
+    }
+    Ok(())
+}
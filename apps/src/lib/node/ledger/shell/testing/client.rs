@@ -2,12 +2,12 @@ use clap::Command as App;
 use eyre::Report;
 use namada::types::io::Io;
 use namada_sdk::error::Error as SdkError;
-use tendermint_config::net::Address as TendermintAddress;
 
 use super::node::MockNode;
 use crate::cli::api::{CliApi, CliClient};
-use crate::cli::args::Global;
+use crate::cli::args::{Global, OutputFormat};
 use crate::cli::{args, cmds, Cmd, Context, NamadaClient, NamadaRelayer};
+use crate::client::failover::LedgerAddresses;
 use crate::node::ledger::shell::testing::utils::{Bin, TestingIo};
 
 pub fn run(
@@ -20,8 +20,10 @@ pub fn run(
         Global {
             is_pre_genesis: false,
             chain_id: Some(locked.chain_id.clone()),
+            profile: None,
             base_dir: locked.base_dir.clone(),
             wasm_dir: Some(locked.wasm_dir.clone()),
+            output: OutputFormat::Text,
         }
     };
     let ctx = Context::new::<TestingIo>(global.clone())?;
@@ -93,7 +95,9 @@ pub fn run(
 
 #[async_trait::async_trait(?Send)]
 impl<'a> CliClient for &'a MockNode {
-    fn from_tendermint_address(_: &mut TendermintAddress) -> Self {
+    fn from_tendermint_address(
+        _: &mut LedgerAddresses,
+    ) -> Result<Self, SdkError> {
         unreachable!("MockNode should always be instantiated at test start.")
     }
 
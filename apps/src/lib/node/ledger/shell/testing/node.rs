@@ -5,6 +5,7 @@ use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::task::Poll;
 
+use borsh::BorshDeserialize;
 use color_eyre::eyre::{Report, Result};
 use data_encoding::HEXUPPER;
 use itertools::Either;
@@ -19,6 +20,7 @@ use namada::ledger::queries::{
 use namada::ledger::storage::{
     LastBlock, Sha256Hasher, EPOCH_SWITCH_BLOCKS_DELAY,
 };
+use namada::ledger::storage_api::StorageRead;
 use namada::proof_of_stake::pos_queries::PosQueries;
 use namada::proof_of_stake::types::WeightedValidator;
 use namada::proof_of_stake::{
@@ -32,8 +34,8 @@ use namada::types::control_flow::time::Duration;
 use namada::types::ethereum_events::EthereumEvent;
 use namada::types::hash::Hash;
 use namada::types::key::tm_consensus_key_raw_hash;
-use namada::types::storage::{BlockHash, BlockHeight, Epoch, Header};
-use namada::types::time::DateTimeUtc;
+use namada::types::storage::{BlockHash, BlockHeight, Epoch, Header, Key};
+use namada::types::time::{DateTimeUtc, DurationSecs};
 use namada_sdk::queries::Client;
 use num_traits::cast::FromPrimitive;
 use regex::Regex;
@@ -249,6 +251,9 @@ pub struct MockNode {
     pub results: Arc<Mutex<Vec<NodeResults>>>,
     pub services: Arc<MockServices>,
     pub auto_drive_services: bool,
+    /// The time of the next blocks, if set by the test. Otherwise, the blocks
+    /// are timestamped with the current time.
+    pub block_time: Arc<Mutex<Option<DateTimeUtc>>>,
 }
 
 impl Drop for MockNode {
@@ -320,7 +325,51 @@ impl MockNode {
         self.shell.lock().unwrap().wl_storage.storage.last_epoch
     }
 
+    /// The height of the last committed block
+    pub fn last_block_height(&self) -> BlockHeight {
+        self.shell
+            .lock()
+            .unwrap()
+            .wl_storage
+            .storage
+            .get_last_block_height()
+    }
+
+    /// The time of the next block
+    pub fn block_time(&self) -> DateTimeUtc {
+        self.block_time
+            .lock()
+            .unwrap()
+            .unwrap_or_else(DateTimeUtc::now)
+    }
+
+    /// Set the time of the next blocks. It only changes when set again or
+    /// advanced with [`MockNode::advance_time`].
+    pub fn set_block_time(&self, time: DateTimeUtc) {
+        *self.block_time.lock().unwrap() = Some(time);
+    }
+
+    /// Advance the time of the next blocks by the given duration
+    pub fn advance_time(&self, duration: DurationSecs) {
+        let time = self.block_time() + duration;
+        self.set_block_time(time);
+    }
+
+    /// Finalize and commit the given number of empty blocks
+    pub fn advance_blocks(&self, num_blocks: u64) {
+        for _ in 0..num_blocks {
+            self.finalize_and_commit();
+        }
+    }
+
+    /// Read a value from the committed storage and the write log
+    pub fn read<T: BorshDeserialize>(&self, key: &Key) -> Option<T> {
+        StorageRead::read(&self.shell.lock().unwrap().wl_storage, key)
+            .expect("Reading from storage shouldn't fail")
+    }
+
     pub fn next_epoch(&mut self) -> Epoch {
+        let block_time = self.block_time();
         {
             let mut locked = self.shell.lock().unwrap();
 
@@ -328,8 +377,7 @@ impl MockNode {
                 locked.wl_storage.storage.get_last_block_height() + 1;
             locked.wl_storage.storage.next_epoch_min_start_height =
                 next_epoch_height;
-            locked.wl_storage.storage.next_epoch_min_start_time =
-                DateTimeUtc::now();
+            locked.wl_storage.storage.next_epoch_min_start_time = block_time;
             let next_epoch_min_start_height =
                 locked.wl_storage.storage.next_epoch_min_start_height;
             if let Some(LastBlock { height, .. }) =
@@ -395,6 +443,7 @@ impl MockNode {
     /// `Commit` handlers.
     pub fn finalize_and_commit(&self) {
         let (proposer_address, votes) = self.prepare_request();
+        let block_time = self.block_time();
 
         let mut locked = self.shell.lock().unwrap();
 
@@ -423,7 +472,7 @@ impl MockNode {
                 hash: BlockHash([0u8; 32]),
                 header: Header {
                     hash: Hash([0; 32]),
-                    time: DateTimeUtc::now(),
+                    time: block_time,
                     next_validators_hash: Hash([0; 32]),
                 },
                 byzantine_validators: vec![],
@@ -453,11 +502,12 @@ impl MockNode {
 
     /// Send a tx through Process Proposal and Finalize Block
     /// and register the results.
-    fn submit_txs(&self, txs: Vec<Vec<u8>>) {
+    pub fn submit_txs(&self, txs: Vec<Vec<u8>>) {
         // The block space allocator disallows encrypted txs in certain blocks.
         // Advance to block height that allows txs.
         self.advance_to_allowed_block();
         let (proposer_address, votes) = self.prepare_request();
+        let block_time = self.block_time();
 
         let req = RequestProcessProposal {
             txs: txs.clone().into_iter().map(|tx| tx.into()).collect(),
@@ -487,7 +537,7 @@ impl MockNode {
             hash: BlockHash([0u8; 32]),
             header: Header {
                 hash: Hash([0; 32]),
-                time: DateTimeUtc::now(),
+                time: block_time,
                 next_validators_hash: Hash([0; 32]),
             },
             byzantine_validators: vec![],
@@ -0,0 +1,181 @@
+//! Reloading of the tx and VP wasms of development chains. When the wasms are
+//! rebuilt, their checksums file is rewritten and the changed wasms are
+//! stored at the beginning of the next block.
+
+use std::time::SystemTime;
+
+use namada::ledger::parameters::{self, Parameters};
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, DB};
+use namada::ledger::storage_api::StorageRead;
+use namada::types::hash::Hash as CodeHash;
+use namada::types::storage::Key;
+
+use super::Shell;
+use crate::config::DEFAULT_WASM_CHECKSUMS_FILE;
+use crate::wasm_loader;
+
+/// The state of the watched wasm directory
+#[derive(Debug, Default)]
+pub struct WasmReload {
+    /// The modification time of the checksums file when the wasms were last
+    /// reloaded
+    last_modified: Option<SystemTime>,
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// If the wasm reload is enabled and the checksums file of the wasm
+    /// directory was rewritten since the last check, store the wasms whose
+    /// code changed. The failures are logged and the previous code is kept.
+    pub(super) fn reload_wasms(&mut self) {
+        let Some(reload) = self.wasm_reload.as_mut() else {
+            return;
+        };
+        let checksums_path = self.wasm_dir.join(DEFAULT_WASM_CHECKSUMS_FILE);
+        let modified = match std::fs::metadata(&checksums_path)
+            .and_then(|metadata| metadata.modified())
+        {
+            Ok(modified) => modified,
+            Err(err) => {
+                tracing::warn!(
+                    "Cannot read the wasm checksums file {}: {err}",
+                    checksums_path.to_string_lossy()
+                );
+                return;
+            }
+        };
+        if reload.last_modified == Some(modified) {
+            return;
+        }
+        reload.last_modified = Some(modified);
+
+        // Keep the manifest of the chain in sync with the rebuilt wasms, so
+        // that they pass its validation on the next start
+        let chain_dir = self.base_dir.join(self.chain_id.as_str());
+        if let Err(err) =
+            wasm_loader::WasmManifest::reload(&chain_dir, &self.wasm_dir)
+        {
+            tracing::warn!("Cannot reload the wasm manifest: {err}");
+        }
+
+        let Parameters {
+            tx_whitelist,
+            vp_whitelist,
+            ..
+        } = parameters::read(&self.wl_storage)
+            .expect("Reading the parameters shouldn't fail");
+        let checksums = wasm_loader::Checksums::read_checksums(&self.wasm_dir);
+        for name in checksums.0.keys() {
+            let code = match wasm_loader::read_wasm(&self.wasm_dir, name) {
+                Ok(code) => code,
+                Err(err) => {
+                    tracing::warn!("Cannot read the wasm {name}: {err}");
+                    continue;
+                }
+            };
+            let code_hash = CodeHash::sha256(&code);
+            let stored_hash = self
+                .wl_storage
+                .read_bytes(&Key::wasm_hash(name))
+                .expect("Reading the wasm hash shouldn't fail");
+            if stored_hash.as_deref() == Some(code_hash.as_ref()) {
+                continue;
+            }
+            let hash = code_hash.to_string().to_lowercase();
+            if !(tx_whitelist.is_empty() && vp_whitelist.is_empty())
+                && !tx_whitelist.contains(&hash)
+                && !vp_whitelist.contains(&hash)
+            {
+                tracing::warn!("The rebuilt wasm {name} isn't whitelisted.");
+                continue;
+            }
+            match self.store_wasm(name, code, code_hash) {
+                Ok(()) => {
+                    tracing::info!("Reloaded the wasm {name} {code_hash}")
+                }
+                Err(err) => {
+                    tracing::warn!("Cannot reload the wasm {name}: {err}")
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_wasm_reload {
+    use namada::ledger::parameters::storage as params_storage;
+    use namada::ledger::storage_api::StorageWrite;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils;
+
+    /// Write a wasm and a checksums file that points to it
+    fn write_wasm(wasm_dir: &std::path::Path, name: &str, code: &[u8]) {
+        let hash = CodeHash::sha256(code).to_string().to_lowercase();
+        let file_name =
+            format!("{}.{hash}.wasm", name.trim_end_matches(".wasm"));
+        std::fs::write(wasm_dir.join(&file_name), code).unwrap();
+        let checksums = serde_json::json!({ name: file_name });
+        std::fs::write(
+            wasm_dir.join(DEFAULT_WASM_CHECKSUMS_FILE),
+            checksums.to_string(),
+        )
+        .unwrap();
+    }
+
+    /// Test that a rebuilt wasm is stored when the checksums file is
+    /// rewritten, and only then
+    #[test]
+    fn test_reload_wasms() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let wasm_dir = tempfile::tempdir().unwrap();
+        shell.wasm_dir = wasm_dir.path().to_path_buf();
+        shell.wasm_reload = Some(WasmReload::default());
+        // Allow any wasm
+        for key in [
+            params_storage::get_tx_whitelist_storage_key(),
+            params_storage::get_vp_whitelist_storage_key(),
+        ] {
+            shell.wl_storage.write(&key, Vec::<String>::new()).unwrap();
+        }
+        let name = "tx_test.wasm";
+        let read_hash = |shell: &test_utils::TestShell| {
+            shell
+                .wl_storage
+                .read_bytes(&Key::wasm_hash(name))
+                .unwrap()
+                .map(|hash| CodeHash::try_from(&hash[..]).unwrap())
+        };
+
+        write_wasm(wasm_dir.path(), name, &[0, 1, 2]);
+        shell.reload_wasms();
+        assert_eq!(read_hash(&shell), Some(CodeHash::sha256([0, 1, 2])));
+
+        // A wasm rebuilt without rewriting the checksums file isn't reloaded
+        write_wasm(wasm_dir.path(), name, &[3, 4, 5]);
+        let modified = std::fs::metadata(
+            wasm_dir.path().join(DEFAULT_WASM_CHECKSUMS_FILE),
+        )
+        .unwrap()
+        .modified()
+        .unwrap();
+        shell.wasm_reload.as_mut().unwrap().last_modified = Some(modified);
+        shell.reload_wasms();
+        assert_eq!(read_hash(&shell), Some(CodeHash::sha256([0, 1, 2])));
+
+        // Once the checksums file is rewritten, the rebuilt wasm is reloaded
+        shell.wasm_reload.as_mut().unwrap().last_modified = None;
+        shell.reload_wasms();
+        assert_eq!(read_hash(&shell), Some(CodeHash::sha256([3, 4, 5])));
+
+        // Without the reload enabled, nothing is stored
+        write_wasm(wasm_dir.path(), name, &[6, 7, 8]);
+        shell.wasm_reload = None;
+        shell.reload_wasms();
+        assert_eq!(read_hash(&shell), Some(CodeHash::sha256([3, 4, 5])));
+    }
+}
@@ -5,14 +5,14 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use futures::future::FutureExt;
+use namada::ledger::events::Event;
 use namada::proof_of_stake::find_validator_by_raw_hash;
 use namada::proto::Tx;
-use namada::types::hash::Hash;
 use namada::types::key::tm_raw_hash_to_string;
 use namada::types::storage::{BlockHash, BlockHeight};
 use namada::types::transaction::hash_tx;
-use tokio::sync::broadcast;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{broadcast, watch};
 use tower::Service;
 
 use super::abcipp_shim_types::shim::request::{FinalizeBlock, ProcessedTx};
@@ -25,6 +25,7 @@ use crate::facade::tendermint::v0_37::abci::{
 };
 use crate::facade::tendermint_proto::v0_37::abci::ResponseDeliverTx;
 use crate::facade::tower_abci::BoxError;
+use crate::node::ledger::config_reload::ReloadableConfig;
 use crate::node::ledger::shell::{EthereumOracleChannels, Shell};
 
 /// The shim wraps the shell, which implements ABCI++.
@@ -85,11 +86,19 @@ impl AbcippShim {
         )
     }
 
-    /// Get the hash of the txs in the block
-    pub fn get_hash(&self) -> Hash {
-        let bytes: Vec<u8> =
-            self.delivered_txs.iter().flat_map(Clone::clone).collect();
-        hash_tx(bytes.as_slice())
+    /// Return a handle to the event bus of the shell, to subscribe to the
+    /// events emitted by the ledger.
+    pub fn event_bus(&self) -> broadcast::Sender<Event> {
+        self.service.event_bus()
+    }
+
+    /// Apply the reloadable settings of the node config received on the given
+    /// channel to the shell, whenever the config is reloaded
+    pub fn watch_config_reload(
+        &mut self,
+        config_reload: watch::Receiver<ReloadableConfig>,
+    ) {
+        self.service.watch_config_reload(config_reload)
     }
 
     /// Run the shell's blocking loop that receives messages from the
@@ -124,44 +133,12 @@ impl AbcippShim {
                 Req::EndBlock(_) => {
                     let begin_block_request =
                         self.begin_block_request.take().unwrap();
-                    let block_time = begin_block_request
-                        .header
-                        .time
-                        .try_into()
-                        .expect("valid RFC3339 block time");
-
-                    let tm_raw_hash_string = tm_raw_hash_to_string(
-                        begin_block_request.header.proposer_address,
-                    );
-                    let block_proposer = find_validator_by_raw_hash(
-                        &self.service.wl_storage,
-                        tm_raw_hash_string,
-                    )
-                    .unwrap()
-                    .expect(
-                        "Unable to find native validator address of block \
-                         proposer from tendermint raw hash",
-                    );
-
-                    let (processing_results, _) = self.service.process_txs(
-                        &self.delivered_txs,
-                        block_time,
-                        &block_proposer,
+                    let delivered = std::mem::take(&mut self.delivered_txs);
+                    let end_block_request = finalize_block_request(
+                        &self.service,
+                        begin_block_request,
+                        delivered,
                     );
-                    let mut txs = Vec::with_capacity(self.delivered_txs.len());
-                    let mut delivered = vec![];
-                    std::mem::swap(&mut self.delivered_txs, &mut delivered);
-                    for (result, tx) in processing_results
-                        .into_iter()
-                        .zip(delivered.into_iter())
-                    {
-                        txs.push(ProcessedTx { tx, result });
-                    }
-                    let mut end_block_request: FinalizeBlock =
-                        begin_block_request.into();
-                    let hash = self.get_hash();
-                    end_block_request.hash = BlockHash::from(hash);
-                    end_block_request.txs = txs;
                     self.service
                         .call(Request::FinalizeBlock(end_block_request))
                         .map_err(Error::from)
@@ -187,9 +164,54 @@ impl AbcippShim {
                 tracing::info!("ABCI response channel is closed")
             }
         }
+        // The channel is closed once the ABCI server has shut down and all
+        // the requests in flight have been handled above
+        tracing::info!("All ABCI requests have been handled");
+        self.service.shutdown();
     }
 }
 
+/// Make the request to finalize a block from its begin block request and
+/// delivered txs. The txs are processed again against the current state to
+/// find out which of them were accepted in the proposal.
+pub(crate) fn finalize_block_request(
+    shell: &Shell,
+    begin_block_request: request::BeginBlock,
+    delivered_txs: Vec<TxBytes>,
+) -> FinalizeBlock {
+    let block_time = begin_block_request
+        .header
+        .time
+        .try_into()
+        .expect("valid RFC3339 block time");
+
+    let tm_raw_hash_string =
+        tm_raw_hash_to_string(begin_block_request.header.proposer_address);
+    let block_proposer =
+        find_validator_by_raw_hash(&shell.wl_storage, tm_raw_hash_string)
+            .unwrap()
+            .expect(
+                "Unable to find native validator address of block proposer \
+                 from tendermint raw hash",
+            );
+
+    let (processing_results, _) =
+        shell.process_txs(&delivered_txs, block_time, &block_proposer);
+    // NOTE: The block hash has always been computed after the delivered txs
+    // were taken out of the shim, i.e. from empty bytes. Changing it would
+    // change the state of the chain, so it's kept as is.
+    let hash = hash_tx(&[]);
+    let txs = processing_results
+        .into_iter()
+        .zip(delivered_txs)
+        .map(|(result, tx)| ProcessedTx { tx, result })
+        .collect();
+    let mut end_block_request: FinalizeBlock = begin_block_request.into();
+    end_block_request.hash = BlockHash::from(hash);
+    end_block_request.txs = txs;
+    end_block_request
+}
+
 /// Indicates how [`AbciService`] should
 /// check whether or not it needs to take
 /// action.
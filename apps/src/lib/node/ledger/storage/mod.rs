@@ -64,6 +64,7 @@ mod tests {
     use namada::ledger::storage::{types, StoreType, WlStorage};
     use namada::ledger::storage_api::{self, StorageWrite};
     use namada::types::chain::ChainId;
+    use namada::types::dec::Dec;
     use namada::types::ethereum_events::Uint;
     use namada::types::hash::Hash;
     use namada::types::keccak::KeccakHash;
@@ -157,6 +158,9 @@ mod tests {
             max_expected_time_per_block: DurationSecs(3600),
             max_proposal_bytes: Default::default(),
             max_block_gas: 100,
+            max_encrypted_txs_share: Dec::one() / 3,
+            max_decrypted_txs_share: Dec::one() / 3,
+            min_protocol_txs_share: Dec::one() / 3,
             vp_whitelist: vec![],
             tx_whitelist: vec![],
             implicit_vp_code_hash: Default::default(),
@@ -169,6 +173,9 @@ mod tests {
             fee_unshielding_gas_limit: 0,
             fee_unshielding_descriptions_limit: 0,
             minimum_gas_price: Default::default(),
+            wasm_limits: Default::default(),
+            storage_deposit_per_byte: Default::default(),
+            blocked_addresses: None,
         };
         params.init_storage(&mut wl_storage).expect("Test failed");
         // insert and commit
@@ -34,9 +34,17 @@
 //!     - `epoch`: block epoch
 //!     - `address_gen`: established address generator
 //!     - `header`: block's header
+//!   - `tx_results/{hash}`: the result of the applied tx with the given hash
+//!   - `tx_results/height/{height}/{hash}`: the height index of the tx
+//!     results, used to prune the results that can no longer be queried
+//!   - `storage_usage`: the bytes of state owned by the addresses
+//!     - `{address}`: owned by the given address
+//!     - `total`: owned by all the addresses
 //! - `replay_protection`: hashes of processed tx
 //!     - `all`: the hashes included up to the last block
 //!     - `last`: the hashes included in the last block
+//!     - `expiration/{timestamp}/{hash}`: the expiration of the hashes, used
+//!       to prune them
 
 use std::fs::File;
 use std::io::{BufWriter, Write};
@@ -49,6 +57,7 @@ use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
 use itertools::Either;
 use namada::core::ledger::masp_conversions::ConversionState;
+use namada::core::ledger::tx_results;
 use namada::core::types::ethereum_structs;
 use namada::eth_bridge::storage::proof::BridgePoolRootProof;
 use namada::ledger::eth_bridge::storage::bridge_pool;
@@ -60,6 +69,7 @@ use namada::ledger::storage::{
     types, BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error,
     MerkleTreeStoresRead, Result, StoreType, DB,
 };
+use namada::types::address::Address;
 use namada::types::ethereum_events::Uint;
 use namada::types::internal::TxQueue;
 use namada::types::storage::{
@@ -70,7 +80,7 @@ use namada::types::time::DateTimeUtc;
 use rayon::prelude::*;
 use rocksdb::{
     BlockBasedOptions, ColumnFamily, ColumnFamilyDescriptor, Direction,
-    FlushOptions, IteratorMode, Options, ReadOptions, WriteBatch,
+    FlushOptions, IteratorMode, Options, ReadOptions, WriteBatch, WriteOptions,
 };
 
 use crate::config::utils::num_of_threads;
@@ -189,9 +199,11 @@ pub fn open(
         replay_protection_cf_opts,
     ));
 
-    rocksdb::DB::open_cf_descriptors(&db_opts, path, cfs)
+    let mut db = rocksdb::DB::open_cf_descriptors(&db_opts, path, cfs)
         .map(RocksDB)
-        .map_err(|e| Error::DBError(e.into_string()))
+        .map_err(|e| Error::DBError(e.into_string()))?;
+    db.repair_partial_commit()?;
+    Ok(db)
 }
 
 impl Drop for RocksDB {
@@ -276,12 +288,107 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Write a batch atomically. The write is synced to the WAL on disk
+    /// before returning, so that a committed block survives a crash.
     fn exec_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        let mut write_opts = WriteOptions::default();
+        write_opts.set_sync(true);
         self.0
-            .write(batch)
+            .write_opt(batch, &write_opts)
             .map_err(|e| Error::DBError(e.into_string()))
     }
 
+    /// Check that the last committed block is complete and discard the writes
+    /// of the next block, if the node stopped before its commit.
+    ///
+    /// A block is committed in a single batch, but the subspace can be written
+    /// to directly while the block is being processed. These writes are
+    /// reverted using the diffs of the uncommitted height.
+    fn repair_partial_commit(&mut self) -> Result<()> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        let height: BlockHeight = match self
+            .0
+            .get_cf(state_cf, "height")
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError)?,
+            None => return Ok(()),
+        };
+
+        let block_cf = self.get_column_family(BLOCK_CF)?;
+        let committed_keys =
+            ["hash", "time", "epoch", "pred_epochs", "address_gen"]
+                .into_iter()
+                .map(|key| format!("{}/{}", height.raw(), key))
+                .chain(std::iter::once(format!("results/{}", height.raw())));
+        for key in committed_keys {
+            if self
+                .0
+                .get_cf(block_cf, &key)
+                .map_err(|e| Error::DBError(e.into_string()))?
+                .is_none()
+            {
+                return Err(Error::DBError(format!(
+                    "The last committed block at height {height} is \
+                     incomplete, the key {key} is missing"
+                )));
+            }
+        }
+
+        let next_height = height.next_height();
+        let prefix = format!("{}/", next_height.raw());
+        let diffs_cf = self.get_column_family(DIFFS_CF)?;
+        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        let mut batch = WriteBatch::default();
+        let mut is_partial = false;
+
+        // Restore the subspace values from before the uncommitted height
+        for (key, old_value, _gas) in
+            iter_diffs_prefix(self, next_height, None, true)
+        {
+            batch.put_cf(subspace_cf, key, old_value);
+        }
+        for (key, _new_value, _gas) in
+            iter_diffs_prefix(self, next_height, None, false)
+        {
+            let old_key = format!("{prefix}old/{key}");
+            if self
+                .0
+                .get_cf(diffs_cf, old_key)
+                .map_err(|e| Error::DBError(e.into_string()))?
+                .is_none()
+            {
+                batch.delete_cf(subspace_cf, key);
+            }
+        }
+
+        // Delete any key prepended with the uncommitted height
+        for cf in [diffs_cf, block_cf] {
+            let read_opts = make_iter_read_opts(Some(prefix.clone()));
+            let iter = self.0.iterator_cf_opt(
+                cf,
+                read_opts,
+                IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+            );
+            for (key, _value, _gas) in PersistentPrefixIterator(
+                // Empty prefix string to prevent stripping
+                PrefixIterator::new(iter, String::default()),
+            ) {
+                is_partial = true;
+                batch.delete_cf(cf, key);
+            }
+        }
+
+        if is_partial {
+            tracing::warn!(
+                "Found a partially committed block at height {next_height}, \
+                 reverting to the last committed height {height}."
+            );
+            self.exec_batch(batch)?;
+        }
+        Ok(())
+    }
+
     /// Dump last known block
     pub fn dump_block(
         &self,
@@ -601,6 +708,9 @@ impl DB for RocksDB {
     }
 
     fn flush(&self, wait: bool) -> Result<()> {
+        self.0
+            .flush_wal(true)
+            .map_err(|e| Error::DBError(e.into_string()))?;
         let mut flush_opts = FlushOptions::default();
         flush_opts.set_wait(wait);
         self.0
@@ -1187,6 +1297,27 @@ impl DB for RocksDB {
             .map_err(|e| Error::DBError(e.into_string()))
     }
 
+    fn read_subspace_val_into(
+        &self,
+        key: &Key,
+        buf: &mut Vec<u8>,
+    ) -> Result<bool> {
+        let subspace_cf = self.get_column_family(SUBSPACE_CF)?;
+        // Copy the pinned value straight into the buffer
+        match self
+            .0
+            .get_pinned_cf(subspace_cf, key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(val) => {
+                buf.clear();
+                buf.extend_from_slice(&val);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     fn read_subspace_val_with_height(
         &self,
         key: &Key,
@@ -1497,6 +1628,96 @@ impl DB for RocksDB {
 
         Ok(())
     }
+
+    fn write_tx_result(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        hash: &namada::types::hash::Hash,
+        result: &[u8],
+    ) -> Result<()> {
+        let block_cf = self.get_column_family(BLOCK_CF)?;
+        batch.0.put_cf(
+            block_cf,
+            tx_results::get_tx_result_key(hash).to_string(),
+            result,
+        );
+        batch.0.put_cf(
+            block_cf,
+            tx_results::get_tx_result_height_key(height, hash).to_string(),
+            vec![],
+        );
+
+        Ok(())
+    }
+
+    fn delete_tx_result(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        hash: &namada::types::hash::Hash,
+    ) -> Result<()> {
+        let block_cf = self.get_column_family(BLOCK_CF)?;
+        batch.0.delete_cf(
+            block_cf,
+            tx_results::get_tx_result_key(hash).to_string(),
+        );
+        batch.0.delete_cf(
+            block_cf,
+            tx_results::get_tx_result_height_key(height, hash).to_string(),
+        );
+
+        Ok(())
+    }
+
+    fn read_tx_result(
+        &self,
+        hash: &namada::types::hash::Hash,
+    ) -> Result<Option<Vec<u8>>> {
+        let block_cf = self.get_column_family(BLOCK_CF)?;
+        self.0
+            .get_cf(block_cf, tx_results::get_tx_result_key(hash).to_string())
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    fn write_storage_usage(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        owner: Option<&Address>,
+        bytes: u64,
+    ) -> Result<()> {
+        let block_cf = self.get_column_family(BLOCK_CF)?;
+        let key = storage_usage_key(owner)?;
+        batch
+            .0
+            .put_cf(block_cf, key.to_string(), types::encode(&bytes));
+
+        Ok(())
+    }
+
+    fn read_storage_usage(&self, owner: Option<&Address>) -> Result<u64> {
+        let block_cf = self.get_column_family(BLOCK_CF)?;
+        let key = storage_usage_key(owner)?;
+        match self
+            .0
+            .get_cf(block_cf, key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError),
+            None => Ok(0),
+        }
+    }
+}
+
+/// The key of the storage usage of the given address, or of all the addresses
+/// if `None`, in the block column family
+fn storage_usage_key(owner: Option<&Address>) -> Result<Key> {
+    let key = Key::parse("storage_usage").map_err(Error::KeyError)?;
+    match owner {
+        Some(owner) => key.push(owner),
+        None => key.push(&"total".to_owned()),
+    }
+    .map_err(Error::KeyError)
 }
 
 impl<'iter> DBIter<'iter> for RocksDB {
@@ -1548,6 +1769,28 @@ impl<'iter> DBIter<'iter> for RocksDB {
 
         iter_prefix(self, replay_protection_cf, "last".to_string(), None)
     }
+
+    fn iter_replay_protection_expirations(&'iter self) -> Self::PrefixIter {
+        let replay_protection_cf = self
+            .get_column_family(REPLAY_PROTECTION_CF)
+            .expect("{REPLAY_PROTECTION_CF} column family should exist");
+
+        iter_prefix(
+            self,
+            replay_protection_cf,
+            "expiration".to_string(),
+            Some("expiration".to_string()),
+        )
+    }
+
+    fn iter_tx_result_heights(&'iter self) -> Self::PrefixIter {
+        let block_cf = self
+            .get_column_family(BLOCK_CF)
+            .expect("{BLOCK_CF} column family should exist");
+        let prefix = tx_results::get_tx_result_height_prefix().to_string();
+
+        iter_prefix(self, block_cf, prefix.clone(), Some(prefix))
+    }
 }
 
 fn iter_subspace_prefix<'iter>(
@@ -1770,6 +2013,61 @@ mod test {
             .expect("Block should have been written");
     }
 
+    /// Test that the storage usage of an address and the total usage are
+    /// read back
+    #[test]
+    fn test_storage_usage() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let owner = gen_established_address("test");
+        assert_eq!(db.read_storage_usage(Some(&owner)).unwrap(), 0);
+        assert_eq!(db.read_storage_usage(None).unwrap(), 0);
+
+        let mut batch = RocksDB::batch();
+        db.write_storage_usage(&mut batch, Some(&owner), 10)
+            .unwrap();
+        db.write_storage_usage(&mut batch, None, 20).unwrap();
+        db.exec_batch(batch.0).unwrap();
+
+        assert_eq!(db.read_storage_usage(Some(&owner)).unwrap(), 10);
+        assert_eq!(db.read_storage_usage(None).unwrap(), 20);
+    }
+
+    /// Test that the result of a tx can be read back by its hash
+    #[test]
+    fn test_tx_result_index() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let hash = namada::types::hash::Hash::sha256(b"tx");
+        assert_eq!(db.read_tx_result(&hash).unwrap(), None);
+
+        let mut batch = RocksDB::batch();
+        db.write_tx_result(&mut batch, BlockHeight(1), &hash, &[1_u8, 2, 3])
+            .unwrap();
+        db.exec_batch(batch.0).unwrap();
+
+        assert_eq!(db.read_tx_result(&hash).unwrap(), Some(vec![1_u8, 2, 3]));
+        let other_hash = namada::types::hash::Hash::sha256(b"other tx");
+        assert_eq!(db.read_tx_result(&other_hash).unwrap(), None);
+        let heights: Vec<_> = db
+            .iter_tx_result_heights()
+            .map(|(key, _, _)| {
+                tx_results::parse_tx_result_height_key(&key).unwrap()
+            })
+            .collect();
+        assert_eq!(heights, vec![(BlockHeight(1), hash)]);
+
+        let mut batch = RocksDB::batch();
+        db.delete_tx_result(&mut batch, BlockHeight(1), &hash)
+            .unwrap();
+        db.exec_batch(batch.0).unwrap();
+
+        assert_eq!(db.read_tx_result(&hash).unwrap(), None);
+        assert_eq!(db.iter_tx_result_heights().count(), 0);
+    }
+
     #[test]
     fn test_read() {
         let dir = tempdir().unwrap();
@@ -2029,6 +2327,63 @@ mod test {
         assert_eq!(conversion_state, types::encode(&conversion_state_0));
     }
 
+    /// Test that the writes of a block that wasn't committed are reverted
+    /// when the DB is opened
+    #[test]
+    fn test_repair_partial_commit() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let add_key = Key::parse("add").unwrap();
+        let overwrite_key = Key::parse("overwrite").unwrap();
+        let overwrite_val = vec![1_u8, 1, 1, 0];
+
+        // Commit a block
+        let mut batch = RocksDB::batch();
+        let height_0 = BlockHeight(100);
+        let mut pred_epochs = Epochs::default();
+        pred_epochs.new_epoch(height_0);
+        db.batch_write_subspace_val(
+            &mut batch,
+            height_0,
+            &overwrite_key,
+            &overwrite_val,
+        )
+        .unwrap();
+        add_block_to_batch(
+            &db,
+            &mut batch,
+            height_0,
+            Epoch(1),
+            pred_epochs,
+            &ConversionState::default(),
+        )
+        .unwrap();
+        db.exec_batch(batch.0).unwrap();
+
+        // Write to the subspace in the next block without committing it
+        let height_1 = BlockHeight(101);
+        db.write_subspace_val(height_1, &add_key, vec![1_u8, 0, 0, 0])
+            .unwrap();
+        db.write_subspace_val(height_1, &overwrite_key, vec![1_u8, 1, 1, 1])
+            .unwrap();
+        drop(db);
+
+        let db = open(dir.path(), None).unwrap();
+        assert_eq!(db.read_subspace_val(&add_key).unwrap(), None);
+        assert_eq!(
+            db.read_subspace_val(&overwrite_key).unwrap(),
+            Some(overwrite_val)
+        );
+        assert_eq!(
+            iter_diffs_prefix(&db, height_1, None, true).count()
+                + iter_diffs_prefix(&db, height_1, None, false).count(),
+            0
+        );
+        let last_block = db.read_last_block().unwrap().unwrap();
+        assert_eq!(last_block.height, height_0);
+    }
+
     /// A test helper to write a block
     fn add_block_to_batch(
         db: &RocksDB,
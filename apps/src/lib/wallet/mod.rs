@@ -41,6 +41,10 @@ impl FsWalletStorage for CliWalletUtils {
 impl WalletIo for CliWalletUtils {
     type Rng = OsRng;
 
+    fn show_message(msg: &str) {
+        println!("{msg}");
+    }
+
     fn read_password(confirm: bool) -> Zeroizing<String> {
         let pwd = match env::var("NAMADA_WALLET_PASSWORD_FILE") {
             Ok(path) => Zeroizing::new(
@@ -1,19 +1,17 @@
 //! A module for loading WASM files and downloading pre-built WASMs.
 use core::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use data_encoding::HEXLOWER;
 use eyre::{eyre, WrapErr};
-use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
-use tokio::io::AsyncReadExt;
 
 use crate::cli::safe_exit;
-use crate::config::DEFAULT_WASM_CHECKSUMS_FILE;
+use crate::config::{DEFAULT_WASM_CHECKSUMS_FILE, DEFAULT_WASM_MANIFEST_FILE};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -25,6 +23,34 @@ pub enum Error {
     WasmNotFound(String),
     #[error("Error while downloading {0}: {1}")]
     ServerError(String, String),
+    #[error("Cannot read the WASM manifest {0}: {1}")]
+    ManifestRead(String, String),
+    #[error("Cannot write the WASM manifest {0}: {1}")]
+    ManifestWrite(String, String),
+    #[error("The WASM artifact {0} at {1} doesn't exist")]
+    ArtifactMissing(String, String),
+    #[error(
+        "The WASM artifact {name} at {path} has the hash {found}, but the \
+         chain's manifest expects {expected}"
+    )]
+    ArtifactHashMismatch {
+        name: String,
+        path: String,
+        found: String,
+        expected: String,
+    },
+    #[error(
+        "The WASM dir {wasm_dir} resolves {name} to {found}, but the chain's \
+         manifest expects {expected}"
+    )]
+    WrongWasmDir {
+        wasm_dir: String,
+        name: String,
+        found: String,
+        expected: String,
+    },
+    #[error("Cannot fetch the WASM artifact {0} from any of the sources")]
+    ArtifactUnavailable(String),
 }
 
 /// A hash map where keys are simple file names and values their full file name
@@ -33,6 +59,28 @@ pub enum Error {
 #[serde(transparent)]
 pub struct Checksums(pub HashMap<String, String>);
 
+/// A WASM artifact of a chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmArtifact {
+    /// The lowercase hex SHA256 hash of the code
+    pub hash: String,
+    /// The path of the file, relative to the chain dir unless absolute
+    pub path: PathBuf,
+}
+
+/// The manifest of the WASM artifacts of a chain, used to resolve them by
+/// their code name and to check that they are present with the expected
+/// hashes before the ledger starts
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmManifest {
+    /// The URL prefixes the missing artifacts are downloaded from, in order.
+    /// When empty, the default WASM server is used.
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// The artifacts by their code name, e.g. `tx_transfer.wasm`
+    pub artifacts: BTreeMap<String, WasmArtifact>,
+}
+
 /// Github URL prefix of released Namada network configs
 pub const ENV_VAR_WASM_SERVER: &str = "NAMADA_NETWORK_WASM_SERVER";
 const DEFAULT_WASM_SERVER: &str = "https://artifacts.heliax.click/namada-wasm";
@@ -67,135 +115,260 @@ impl Checksums {
             wasm_directory.as_ref().join(DEFAULT_WASM_CHECKSUMS_FILE);
         Self::read_checksums_file(checksums_path)
     }
+}
 
-    pub async fn read_checksums_async(
-        wasm_directory: impl AsRef<Path>,
+impl WasmManifest {
+    /// Build a manifest from the checksums of a WASM dir. The paths are
+    /// relative to the chain dir if the WASM dir is inside of it.
+    pub fn from_checksums(
+        checksums: &Checksums,
+        chain_dir: impl AsRef<Path>,
+        wasm_dir: impl AsRef<Path>,
     ) -> Self {
+        let wasm_dir = wasm_dir
+            .as_ref()
+            .strip_prefix(chain_dir.as_ref())
+            .unwrap_or(wasm_dir.as_ref());
+        let artifacts = checksums
+            .0
+            .iter()
+            .map(|(name, full_name)| {
+                let artifact = WasmArtifact {
+                    hash: hash_from_file_name(full_name),
+                    path: wasm_dir.join(full_name),
+                };
+                (name.clone(), artifact)
+            })
+            .collect();
+        Self {
+            sources: vec![],
+            artifacts,
+        }
+    }
+
+    /// Read the manifest of the chain. If the chain doesn't have one yet, it
+    /// is built from the checksums of the WASM dir and written to the chain
+    /// dir.
+    pub fn read_or_init(
+        chain_dir: impl AsRef<Path>,
+        wasm_dir: impl AsRef<Path>,
+    ) -> Result<Self, Error> {
+        let manifest_path = chain_dir.as_ref().join(DEFAULT_WASM_MANIFEST_FILE);
+        let display_path = manifest_path.to_string_lossy().to_string();
+        match fs::read(&manifest_path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|err| {
+                Error::ManifestRead(display_path, err.to_string())
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                let checksums = Checksums::read_checksums(&wasm_dir);
+                let manifest =
+                    Self::from_checksums(&checksums, &chain_dir, &wasm_dir);
+                manifest.write(&chain_dir)?;
+                Ok(manifest)
+            }
+            Err(err) => Err(Error::ManifestRead(display_path, err.to_string())),
+        }
+    }
+
+    /// Rebuild the artifacts of the manifest of the chain from the current
+    /// checksums of the WASM dir, e.g. after the WASMs of a development chain
+    /// were rebuilt. The sources of the existing manifest are kept.
+    pub fn reload(
+        chain_dir: impl AsRef<Path>,
+        wasm_dir: impl AsRef<Path>,
+    ) -> Result<Self, Error> {
+        let sources = Self::read_or_init(&chain_dir, &wasm_dir)?.sources;
+        let checksums = Checksums::read_checksums(&wasm_dir);
+        let manifest = Self {
+            sources,
+            ..Self::from_checksums(&checksums, &chain_dir, &wasm_dir)
+        };
+        manifest.write(&chain_dir)?;
+        Ok(manifest)
+    }
+
+    /// Write the manifest to the chain dir
+    fn write(&self, chain_dir: impl AsRef<Path>) -> Result<(), Error> {
+        let manifest_path = chain_dir.as_ref().join(DEFAULT_WASM_MANIFEST_FILE);
+        let json = serde_json::to_vec_pretty(self).unwrap();
+        fs::write(&manifest_path, json).map_err(|err| {
+            Error::ManifestWrite(
+                manifest_path.to_string_lossy().to_string(),
+                err.to_string(),
+            )
+        })
+    }
+
+    /// Check that all the artifacts are present with the expected hashes and
+    /// that the WASM dir resolves them to the same files. Returns all the
+    /// problems found.
+    pub fn validate(
+        &self,
+        chain_dir: impl AsRef<Path>,
+        wasm_dir: impl AsRef<Path>,
+    ) -> Vec<Error> {
         let checksums_path =
-            wasm_directory.as_ref().join(DEFAULT_WASM_CHECKSUMS_FILE);
-        match tokio::fs::File::open(&checksums_path).await {
-            Ok(mut file) => {
-                let mut contents = vec![];
-                // Ignoring the result, next step will fail if not read
-                let _ = file.read_to_end(&mut contents).await;
-                match serde_json::from_slice(&contents[..]) {
-                    Ok(checksums) => checksums,
-                    Err(err) => {
-                        eprintln!(
-                            "Failed decoding WASM checksums from {}. Failed \
-                             with {}",
-                            checksums_path.to_string_lossy(),
-                            err
-                        );
-                        safe_exit(1);
+            wasm_dir.as_ref().join(DEFAULT_WASM_CHECKSUMS_FILE);
+        let checksums = checksums_path
+            .exists()
+            .then(|| Checksums::read_checksums_file(&checksums_path));
+        let mut errors = vec![];
+        for (name, artifact) in &self.artifacts {
+            let path = chain_dir.as_ref().join(&artifact.path);
+            let display_path = path.to_string_lossy().to_string();
+            match fs::read(&path) {
+                Ok(code) => {
+                    let found = sha256_hex(&code);
+                    if found != artifact.hash {
+                        errors.push(Error::ArtifactHashMismatch {
+                            name: name.clone(),
+                            path: display_path,
+                            found,
+                            expected: artifact.hash.clone(),
+                        });
+                        continue;
                     }
                 }
+                Err(_) => {
+                    errors.push(Error::ArtifactMissing(
+                        name.clone(),
+                        display_path,
+                    ));
+                    continue;
+                }
             }
-            Err(err) => {
-                eprintln!(
-                    "Unable to read WASM checksums from {}. Failed with {}",
-                    checksums_path.to_string_lossy(),
-                    err
-                );
-                safe_exit(1);
+            if let Some(full_name) = checksums
+                .as_ref()
+                .and_then(|checksums| checksums.0.get(name))
+            {
+                let found = hash_from_file_name(full_name);
+                if found != artifact.hash {
+                    errors.push(Error::WrongWasmDir {
+                        wasm_dir: wasm_dir
+                            .as_ref()
+                            .to_string_lossy()
+                            .to_string(),
+                        name: name.clone(),
+                        found,
+                        expected: artifact.hash.clone(),
+                    });
+                }
             }
         }
+        errors
     }
-}
 
-fn wasm_url(wasm_name: &str) -> String {
-    let prefix_url = std::env::var(ENV_VAR_WASM_SERVER)
-        .unwrap_or_else(|_| DEFAULT_WASM_SERVER.to_string());
-    format!("{}/{}", prefix_url, wasm_name)
+    /// Download the artifacts that are missing or don't have the expected
+    /// hash from the sources of the manifest. A downloaded artifact is only
+    /// written if its hash is the expected one.
+    pub async fn fetch_missing(
+        &self,
+        chain_dir: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        let sources = if self.sources.is_empty() {
+            vec![wasm_server()]
+        } else {
+            self.sources.clone()
+        };
+        for (name, artifact) in &self.artifacts {
+            let path = chain_dir.as_ref().join(&artifact.path);
+            if let Ok(code) = tokio::fs::read(&path).await {
+                if sha256_hex(&code) == artifact.hash {
+                    continue;
+                }
+            }
+            let file_name = artifact
+                .path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_else(|| name.clone());
+            let mut fetched = false;
+            for source in &sources {
+                let url = format!("{}/{}", source, file_name);
+                match download_wasm(url).await {
+                    Ok(code) if sha256_hex(&code) == artifact.hash => {
+                        if let Some(parent) = path.parent() {
+                            tokio::fs::create_dir_all(parent).await.map_err(
+                                |_| {
+                                    Error::FileWrite(
+                                        path.to_string_lossy().to_string(),
+                                    )
+                                },
+                            )?;
+                        }
+                        tokio::fs::write(&path, &code).await.map_err(|_| {
+                            Error::FileWrite(path.to_string_lossy().to_string())
+                        })?;
+                        fetched = true;
+                        break;
+                    }
+                    Ok(_) => tracing::warn!(
+                        "The WASM {name} downloaded from {source} doesn't \
+                         have the expected hash {}",
+                        artifact.hash
+                    ),
+                    Err(err) => tracing::warn!("{err}"),
+                }
+            }
+            if !fetched {
+                return Err(Error::ArtifactUnavailable(name.clone()));
+            }
+        }
+        Ok(())
+    }
 }
 
-/// Download all the pre-built wasms, or if they're already downloaded, verify
-/// their checksums.
-pub async fn pre_fetch_wasm(wasm_directory: impl AsRef<Path>) {
-    // load json with wasm hashes
-    let checksums = Checksums::read_checksums_async(&wasm_directory).await;
+/// Read the WASM manifest of the chain, download the missing artifacts and
+/// check that they are all valid. Exits with the problems found otherwise.
+/// With `reload`, the manifest is first rebuilt from the WASM dir, whose WASMs
+/// may have been rebuilt since the last start of a development chain.
+pub async fn prepare_wasm(
+    chain_dir: impl AsRef<Path>,
+    wasm_dir: impl AsRef<Path>,
+    reload: bool,
+) {
+    let manifest = if reload {
+        WasmManifest::reload(&chain_dir, &wasm_dir)
+    } else {
+        WasmManifest::read_or_init(&chain_dir, &wasm_dir)
+    }
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+        safe_exit(1)
+    });
+    if let Err(err) = manifest.fetch_missing(&chain_dir).await {
+        eprintln!("{err}");
+    }
+    let errors = manifest.validate(&chain_dir, &wasm_dir);
+    if !errors.is_empty() {
+        for err in errors {
+            eprintln!("{err}");
+        }
+        eprintln!(
+            "The WASM artifacts don't match the manifest {} of the chain. \
+             Check the configured WASM dir or run `namadac utils \
+             fetch-wasms`.",
+            chain_dir
+                .as_ref()
+                .join(DEFAULT_WASM_MANIFEST_FILE)
+                .to_string_lossy()
+        );
+        safe_exit(1);
+    }
+}
 
-    join_all(checksums.0.into_iter().map(|(name, full_name)| {
-        let wasm_directory = wasm_directory.as_ref().to_owned();
+/// The hash of a WASM file named `<name>.<hash>.wasm`
+fn hash_from_file_name(file_name: &str) -> String {
+    file_name.rsplit('.').nth(1).unwrap_or_default().to_string()
+}
 
-        // Async check and download (if needed) each file
-        tokio::spawn(async move {
-            let wasm_path = wasm_directory.join(&full_name);
-            match tokio::fs::read(&wasm_path).await {
-                // if the file exist, first check the hash. If not matching
-                // download it again.
-                Ok(bytes) => {
-                    let mut hasher = Sha256::new();
-                    hasher.update(bytes);
-                    let result = HEXLOWER.encode(&hasher.finalize());
-                    let derived_name = format!(
-                        "{}.{}.wasm",
-                        &name.split('.').collect::<Vec<&str>>()[0],
-                        result
-                    );
-                    if full_name == derived_name {
-                        return;
-                    }
-                    tracing::info!(
-                        "WASM checksum mismatch: Got {}, expected {}. \
-                         Fetching new version...",
-                        &derived_name,
-                        &full_name
-                    );
+fn sha256_hex(code: &[u8]) -> String {
+    HEXLOWER.encode(&Sha256::digest(code))
+}
 
-                    let url = wasm_url(&full_name);
-                    match download_wasm(url).await {
-                        Ok(bytes) => {
-                            if let Err(e) =
-                                tokio::fs::write(wasm_path, &bytes).await
-                            {
-                                eprintln!(
-                                    "Error while creating file for {}: {}",
-                                    &name, e
-                                );
-                                safe_exit(1);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Error downloading wasm: {}", e);
-                            safe_exit(1);
-                        }
-                    }
-                }
-                // if the doesn't file exist, download it.
-                Err(err) => match err.kind() {
-                    std::io::ErrorKind::NotFound => {
-                        let url = wasm_url(&full_name);
-                        match download_wasm(url).await {
-                            Ok(bytes) => {
-                                if let Err(e) =
-                                    tokio::fs::write(wasm_path, &bytes).await
-                                {
-                                    eprintln!(
-                                        "Error while creating file for {}: {}",
-                                        &name, e
-                                    );
-                                    safe_exit(1);
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Error downloading wasm: {}", e);
-                                safe_exit(1);
-                            }
-                        }
-                    }
-                    _ => {
-                        eprintln!(
-                            "Can't read {}.",
-                            wasm_path.as_os_str().to_string_lossy()
-                        );
-                        safe_exit(1);
-                    }
-                },
-            }
-        })
-    }))
-    .await;
+fn wasm_server() -> String {
+    std::env::var(ENV_VAR_WASM_SERVER)
+        .unwrap_or_else(|_| DEFAULT_WASM_SERVER.to_string())
 }
 
 pub fn read_wasm(
@@ -6,6 +6,7 @@ use namada::core::types::address;
 use namada::core::types::token::{Amount, Transfer};
 use namada::ledger::storage::DB;
 use namada::proto::Signature;
+use namada::vm::wasm::run::ModuleLimits;
 use namada::vm::wasm::TxCache;
 use namada_apps::bench_utils::{
     BenchShell, TX_INIT_PROPOSAL_WASM, TX_REVEAL_PK_WASM, TX_TRANSFER_WASM,
@@ -96,7 +97,7 @@ fn compile_wasm(c: &mut Criterion) {
                 |(shell, _tempdir)| {
                     shell
                         .tx_wasm_cache
-                        .compile_or_fetch(&wasm_code)
+                        .compile_or_fetch(&wasm_code, &ModuleLimits::default())
                         .unwrap()
                         .unwrap()
                 },
@@ -0,0 +1,273 @@
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, Throughput,
+};
+use namada::core::ledger::storage::testing::TestWlStorage;
+use namada::core::ledger::storage_api::collections::lazy_map::LazyMap;
+use namada::core::ledger::storage_api::collections::LazyCollection;
+use namada::core::ledger::storage_api::{self, StorageWrite};
+use namada::core::types::key::{
+    common, ed25519, secp256k1, testing::gen_keypair, RefTo,
+    SecretKey as SecretKeyInterface,
+};
+use namada::core::types::token::{Amount, Transfer};
+use namada::proof_of_stake::parameters::OwnedPosParams;
+use namada::proof_of_stake::test_utils::test_init_genesis;
+use namada::proof_of_stake::types::GenesisValidator;
+use namada::proof_of_stake::{self, read_pos_params};
+use namada::types::address::{self, testing::gen_established_address};
+use namada::types::dec::Dec;
+use namada::types::storage::{Epoch, Key};
+use namada_apps::bench_utils::{BenchShell, TX_TRANSFER_WASM};
+use namada_apps::wallet::defaults;
+
+/// The numbers of entries in storage the benchmarks are run with
+const STATE_SIZES: [u64; 3] = [100, 1_000, 10_000];
+
+/// The numbers of validators the PoS benchmarks are run with
+const VALIDATOR_SET_SIZES: [u64; 3] = [10, 100, 500];
+
+/// The storage prefix of the benchmarked lazy maps
+const MAP_PREFIX: &str = "bench/map";
+
+/// A lazy map with the given number of entries, committed to storage
+fn committed_lazy_map(size: u64) -> (TestWlStorage, LazyMap<u64, u64>) {
+    let mut storage = TestWlStorage::default();
+    let map = LazyMap::open(Key::parse(MAP_PREFIX).unwrap());
+    for i in 0..size {
+        map.insert(&mut storage, i, i).unwrap();
+    }
+    storage.commit_block().unwrap();
+    (storage, map)
+}
+
+// Benchmarks the operations of a `LazyMap` with a growing number of entries
+fn lazy_map(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lazy_map");
+
+    for size in STATE_SIZES {
+        let (mut storage, map) = committed_lazy_map(size);
+
+        group.bench_with_input(
+            BenchmarkId::new("get", size),
+            &size,
+            |b, size| {
+                b.iter(|| map.get(&storage, &(size / 2)).unwrap().unwrap())
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("contains", size),
+            &size,
+            |b, size| b.iter(|| map.contains(&storage, &(size + 1)).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("insert", size),
+            &size,
+            |b, size| b.iter(|| map.insert(&mut storage, size / 2, 0).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+// Benchmarks the iteration over all the keys with a given prefix, both through
+// a `LazyMap` and directly on the storage
+fn prefix_iter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prefix_iter");
+
+    for size in STATE_SIZES {
+        let (storage, map) = committed_lazy_map(size);
+        let prefix = Key::parse(MAP_PREFIX).unwrap();
+        group.throughput(Throughput::Elements(size));
+
+        group.bench_with_input(
+            BenchmarkId::new("lazy_map", size),
+            &size,
+            |b, size| {
+                b.iter(|| {
+                    assert_eq!(
+                        map.iter(&storage).unwrap().count() as u64,
+                        *size
+                    )
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("storage", size),
+            &size,
+            |b, size| {
+                b.iter(|| {
+                    let count =
+                        storage_api::iter_prefix_bytes(&storage, &prefix)
+                            .unwrap()
+                            .count();
+                    assert_eq!(count as u64, *size)
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// Benchmarks the updates of the merkle tree and the computation of its root
+// with a growing number of keys in the tree
+fn merkle_tree(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merkle_tree");
+
+    for size in STATE_SIZES {
+        let mut storage = TestWlStorage::default();
+        for i in 0..size {
+            let key = Key::parse(format!("bench/{i}")).unwrap();
+            storage.write(&key, i).unwrap();
+        }
+        storage.commit_block().unwrap();
+        let tree = &mut storage.storage.block.tree;
+        let key = Key::parse(format!("bench/{}", size / 2)).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("update", size),
+            &size,
+            |b, size| b.iter(|| tree.update(&key, size.to_le_bytes()).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("root", size),
+            &size,
+            |b, _| b.iter(|| tree.root()),
+        );
+    }
+
+    group.finish();
+}
+
+// Benchmarks the execution of a transfer wasm tx with a growing number of keys
+// in storage
+fn wasm_tx(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wasm_tx");
+
+    for size in STATE_SIZES {
+        let mut shell = BenchShell::default();
+        for i in 0..size {
+            let key = Key::parse(format!("bench/{i}")).unwrap();
+            shell.wl_storage.write(&key, i).unwrap();
+        }
+        shell.wl_storage.commit_block().unwrap();
+        let transfer = shell.generate_tx(
+            TX_TRANSFER_WASM,
+            Transfer {
+                source: defaults::albert_address(),
+                target: defaults::bertha_address(),
+                token: address::nam(),
+                amount: Amount::native_whole(1).native_denominated(),
+                key: None,
+                shielded: None,
+            },
+            None,
+            None,
+            vec![&defaults::albert_keypair()],
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("transfer", size),
+            &size,
+            |b, _| b.iter(|| shell.execute_tx(&transfer)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Genesis validators with the given number of validators, with distinct
+/// stakes
+fn genesis_validators(num: u64) -> Vec<GenesisValidator> {
+    (1..=num)
+        .map(|i| {
+            let eth_key = || -> common::PublicKey {
+                gen_keypair::<secp256k1::SigScheme>()
+                    .try_to_sk::<common::SecretKey>()
+                    .unwrap()
+                    .ref_to()
+            };
+            GenesisValidator {
+                address: gen_established_address(),
+                tokens: Amount::native_whole(i),
+                consensus_key: common::SecretKey::Ed25519(gen_keypair::<
+                    ed25519::SigScheme,
+                >())
+                .ref_to(),
+                protocol_key: common::SecretKey::Ed25519(gen_keypair::<
+                    ed25519::SigScheme,
+                >())
+                .ref_to(),
+                eth_cold_key: eth_key(),
+                eth_hot_key: eth_key(),
+                commission_rate: Dec::new(5, 2).unwrap(),
+                max_commission_rate_change: Dec::new(1, 2).unwrap(),
+                metadata: Default::default(),
+            }
+        })
+        .collect()
+}
+
+// Benchmarks the validator set updates triggered by bonds with a growing number
+// of validators. Half of the validators don't fit in the consensus set, so a
+// bond may move a validator between the sets.
+fn validator_set(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validator_set");
+
+    for size in VALIDATOR_SET_SIZES {
+        let mut storage = TestWlStorage::default();
+        let validators = genesis_validators(size);
+        test_init_genesis(
+            &mut storage,
+            OwnedPosParams {
+                max_validator_slots: size / 2,
+                ..Default::default()
+            },
+            validators.clone().into_iter(),
+            Epoch::default(),
+        )
+        .unwrap();
+        storage.commit_block().unwrap();
+        let params = read_pos_params(&storage).unwrap();
+        let staking_token = proof_of_stake::staking_token_address(&storage);
+        // The validator with the median stake
+        let validator = validators[(size / 2) as usize].address.clone();
+        storage_api::token::credit_tokens(
+            &mut storage,
+            &staking_token,
+            &validator,
+            Amount::native_whole(size * size),
+        )
+        .unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("self_bond", size),
+            &size,
+            |b, _| {
+                b.iter(|| {
+                    proof_of_stake::bond_tokens(
+                        &mut storage,
+                        None,
+                        &validator,
+                        Amount::native_whole(1),
+                        Epoch::default(),
+                        Some(params.pipeline_len),
+                    )
+                    .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    storage,
+    lazy_map,
+    prefix_iter,
+    merkle_tree,
+    wasm_tx,
+    validator_set
+);
+criterion_main!(storage);
@@ -385,6 +385,10 @@ fn update_account(c: &mut Criterion) {
         )),
         public_keys: vec![defaults::albert_keypair().ref_to()],
         threshold: None,
+        vp_timelock: None,
+        cancel_vp_update: false,
+        allowed_txs: None,
+        policy: None,
     };
     let vp = shell.generate_tx(
         TX_UPDATE_ACCOUNT_WASM,
@@ -428,6 +432,8 @@ fn init_account(c: &mut Criterion) {
         public_keys: vec![new_account.to_public()],
         vp_code_hash: extra_hash,
         threshold: 1,
+        salt: None,
+        vesting: None,
     };
     let tx = shell.generate_tx(
         TX_INIT_ACCOUNT_WASM,
@@ -86,6 +86,10 @@ fn vp_user(c: &mut Criterion) {
         )),
         public_keys: vec![defaults::albert_keypair().to_public()],
         threshold: None,
+        vp_timelock: None,
+        cancel_vp_update: false,
+        allowed_txs: None,
+        policy: None,
     };
     let vp = shell.generate_tx(
         TX_UPDATE_ACCOUNT_WASM,
@@ -162,6 +166,7 @@ fn vp_user(c: &mut Criterion) {
                         &keys_changed,
                         &verifiers,
                         shell.vp_wasm_cache.clone(),
+                        &mut None,
                     )
                     .unwrap(),
                     "VP \"{bench_name}\" bench call failed"
@@ -309,6 +314,7 @@ fn vp_implicit(c: &mut Criterion) {
                         &keys_changed,
                         &verifiers,
                         shell.vp_wasm_cache.clone(),
+                        &mut None,
                     )
                     .unwrap()
                 )
@@ -373,6 +379,10 @@ fn vp_validator(c: &mut Criterion) {
         )),
         public_keys: vec![defaults::validator_account_keypair().to_public()],
         threshold: None,
+        vp_timelock: None,
+        cancel_vp_update: false,
+        allowed_txs: None,
+        policy: None,
     };
     let vp = shell.generate_tx(
         TX_UPDATE_ACCOUNT_WASM,
@@ -461,6 +471,7 @@ fn vp_validator(c: &mut Criterion) {
                         &keys_changed,
                         &verifiers,
                         shell.vp_wasm_cache.clone(),
+                        &mut None,
                     )
                     .unwrap()
                 );
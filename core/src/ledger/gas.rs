@@ -5,6 +5,7 @@ use std::fmt::Display;
 use std::ops::Div;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::parameters;
@@ -71,7 +72,7 @@ pub fn get_max_block_gas(
 ) -> std::result::Result<u64, storage_api::Error> {
     storage
         .read(&parameters::storage::get_max_block_gas_key())?
-        .ok_or(storage_api::Error::SimpleMessage(
+        .ok_or(storage_api::Error::new_const(
             "Missing max_block_gas parameter from storage",
         ))
 }
@@ -79,6 +80,8 @@ pub fn get_max_block_gas(
 /// Representation of gas in sub-units. This effectively decouples gas metering
 /// from fee payment, allowing higher resolution when accounting for gas while,
 /// at the same time, providing a contained gas value when paying fees.
+///
+/// In JSON, the gas is the number of sub-units.
 #[derive(
     Clone,
     Copy,
@@ -89,7 +92,10 @@ pub fn get_max_block_gas(
     BorshDeserialize,
     BorshSerialize,
     BorshSchema,
+    Serialize,
+    Deserialize,
 )]
+#[serde(transparent)]
 pub struct Gas {
     sub: u64,
 }
@@ -107,7 +113,7 @@ impl Gas {
 
     /// Converts the sub gas units to whole ones. If the sub units are not a
     /// multiple of the `SCALE` than ceil the quotient
-    fn get_whole_gas_units(&self) -> u64 {
+    pub fn get_whole_gas_units(&self) -> u64 {
         let quotient = self.sub / SCALE;
         if self.sub % SCALE == 0 {
             quotient
@@ -223,7 +229,14 @@ pub struct VpGasMeter {
 
 /// Gas meter for VPs parallel runs
 #[derive(
-    Clone, Debug, Default, BorshSerialize, BorshDeserialize, BorshSchema,
+    Clone,
+    Debug,
+    Default,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
 )]
 pub struct VpsGas {
     max: Gas,
@@ -341,6 +354,12 @@ impl VpGasMeter {
             current_gas: Gas::default(),
         }
     }
+
+    /// Get the gas consumed by the VP alone, excluding the gas consumed by
+    /// the transaction before it
+    pub fn get_vp_consumed_gas(&self) -> Gas {
+        self.current_gas
+    }
 }
 
 impl VpsGas {
@@ -371,6 +390,8 @@ impl VpsGas {
             self.rest.push(other.max);
         }
         self.rest.append(&mut other.rest);
+        // Keep the result independent of the order of the merges
+        self.rest.sort_unstable_by_key(|gas| gas.sub);
 
         self.check_limit(tx_gas_meter)
     }
@@ -29,6 +29,12 @@ pub struct GovernanceParameters {
     pub max_proposal_content_size: u64,
     /// Minimum epochs between end and grace epochs
     pub min_proposal_grace_epochs: u64,
+    /// Number of epochs after the grace epoch of a proposal during which its
+    /// votes are kept in storage
+    pub votes_retention_epochs: u64,
+    /// Maximum gas, in whole units, that all the epoch hooks can use at an
+    /// epoch boundary
+    pub max_epoch_hooks_gas: u64,
 }
 
 impl Default for GovernanceParameters {
@@ -40,6 +46,8 @@ impl Default for GovernanceParameters {
             max_proposal_period: 27,
             max_proposal_content_size: 10_000,
             min_proposal_grace_epochs: 6,
+            votes_retention_epochs: 12,
+            max_epoch_hooks_gas: 10_000_000,
         }
     }
 }
@@ -57,6 +65,8 @@ impl GovernanceParameters {
             max_proposal_period,
             max_proposal_content_size,
             min_proposal_grace_epochs,
+            votes_retention_epochs,
+            max_epoch_hooks_gas,
         } = self;
 
         let min_proposal_fund_key =
@@ -88,6 +98,14 @@ impl GovernanceParameters {
         storage
             .write(&min_proposal_grace_epoch_key, min_proposal_grace_epochs)?;
 
+        let votes_retention_epochs_key =
+            goverance_storage::get_votes_retention_epochs_key();
+        storage.write(&votes_retention_epochs_key, votes_retention_epochs)?;
+
+        let max_epoch_hooks_gas_key =
+            goverance_storage::get_max_epoch_hooks_gas_key();
+        storage.write(&max_epoch_hooks_gas_key, max_epoch_hooks_gas)?;
+
         let counter_key = goverance_storage::get_counter_key();
         storage.write(&counter_key, u64::MIN)
     }
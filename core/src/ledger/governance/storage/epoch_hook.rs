@@ -0,0 +1,32 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::hash::Hash;
+
+/// A wasm code registered by an accepted governance proposal and run by the
+/// protocol at every epoch boundary. The hook has the id of the proposal that
+/// registered it and runs with the same authority as that proposal.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct EpochHook {
+    /// The wasm code of the hook
+    pub code: Vec<u8>,
+    /// The maximum gas, in whole units, that the hook can use at every
+    /// epoch boundary
+    pub gas_limit: u64,
+}
+
+impl EpochHook {
+    /// The hash of the code of the hook
+    pub fn code_hash(&self) -> Hash {
+        Hash::sha256(&self.code)
+    }
+}
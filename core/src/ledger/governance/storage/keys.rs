@@ -24,11 +24,19 @@ struct Keys {
     max_period: &'static str,
     max_content: &'static str,
     min_grace_epoch: &'static str,
+    votes_retention: &'static str,
+    max_hooks_gas: &'static str,
+    epoch_hook: &'static str,
     counter: &'static str,
     pending: &'static str,
     result: &'static str,
 }
 
+/// The key segments of the governance storage sub-spaces
+pub fn storage_keys() -> &'static [&'static str] {
+    Keys::ALL
+}
+
 /// Check if key is inside governance address space
 pub fn is_governance_key(key: &Key) -> bool {
     matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &ADDRESS)
@@ -270,6 +278,36 @@ pub fn is_min_grace_epoch_key(key: &Key) -> bool {
                     && min_grace_epoch_param == Keys::VALUES.min_grace_epoch)
 }
 
+/// Check if key is the votes retention epochs key
+pub fn is_votes_retention_epochs_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(votes_retention_param),
+                ] if addr == &ADDRESS
+                    && votes_retention_param == Keys::VALUES.votes_retention)
+}
+
+/// Check if key is the max epoch hooks gas key
+pub fn is_max_epoch_hooks_gas_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(max_hooks_gas_param),
+                ] if addr == &ADDRESS
+                    && max_hooks_gas_param == Keys::VALUES.max_hooks_gas)
+}
+
+/// Check if key is an epoch hook key. If it is, returns the id of the hook.
+pub fn is_epoch_hook_key(key: &Key) -> Option<u64> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(id)]
+            if addr == &ADDRESS && prefix == Keys::VALUES.epoch_hook =>
+        {
+            id.parse::<u64>().ok()
+        }
+        _ => None,
+    }
+}
+
 /// Check if key is parameter key
 pub fn is_parameter_key(key: &Key) -> bool {
     is_min_proposal_fund_key(key)
@@ -278,6 +316,8 @@ pub fn is_parameter_key(key: &Key) -> bool {
         || is_min_proposal_voting_period_key(key)
         || is_max_proposal_period_key(key)
         || is_min_grace_epoch_key(key)
+        || is_votes_retention_epochs_key(key)
+        || is_max_epoch_hooks_gas_key(key)
 }
 
 /// Check if key is start epoch or end epoch key
@@ -334,6 +374,34 @@ pub fn get_min_proposal_grace_epoch_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get the votes retention epochs key
+pub fn get_votes_retention_epochs_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.votes_retention.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the max epoch hooks gas key
+pub fn get_max_epoch_hooks_gas_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.max_hooks_gas.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the epoch hooks prefix key
+pub fn get_epoch_hooks_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.epoch_hook.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of an epoch hook
+pub fn get_epoch_hook_key(id: u64) -> Key {
+    get_epoch_hooks_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get key of proposal ids counter
 pub fn get_counter_key() -> Key {
     Key::from(ADDRESS.to_db_key())
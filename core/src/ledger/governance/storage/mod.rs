@@ -1,3 +1,5 @@
+/// Epoch hook structures
+pub mod epoch_hook;
 /// Governance proposal keys
 pub mod keys;
 /// Proposal structures
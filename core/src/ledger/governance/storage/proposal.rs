@@ -200,7 +200,9 @@ impl TryFrom<PgfRetro> for PGFAction {
     }
 }
 
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
 /// Proposal rappresentation when fetched from the storage
 pub struct StorageProposal {
     /// The proposal id
@@ -22,6 +22,7 @@ const CLIENTS_COUNTER: &str = "clients/counter";
 const CONNECTIONS_COUNTER: &str = "connections/counter";
 const CHANNELS_COUNTER: &str = "channelEnds/counter";
 const DENOM: &str = "ibc_denom";
+const EXECUTED_MSGS: &str = "executed_msgs";
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -69,6 +70,20 @@ pub fn channel_counter_key() -> Key {
         .expect("Creating a key for the channel counter shouldn't fail")
 }
 
+/// Returns a key of the IBC messages executed by the current transaction. It's
+/// only written temporarily, so that the IBC VP can validate the messages
+/// executed by custom transactions.
+pub fn executed_msgs_key() -> Key {
+    let path = EXECUTED_MSGS.to_owned();
+    ibc_key(path)
+        .expect("Creating a key for the executed messages shouldn't fail")
+}
+
+/// Returns true if the given key is the key of the executed IBC messages
+pub fn is_executed_msgs_key(key: &Key) -> bool {
+    *key == executed_msgs_key()
+}
+
 /// Returns a key for the client state
 pub fn client_state_key(client_id: &ClientId) -> Key {
     let path = Path::ClientState(ClientStatePath(client_id.clone()));
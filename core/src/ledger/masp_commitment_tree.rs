@@ -0,0 +1,270 @@
+//! The MASP note commitment tree in storage.
+//!
+//! The tree is stored incrementally so that a shielded tx only writes a
+//! bounded amount of data, however large the tree grows:
+//! - the frontier, i.e. the rightmost path of the tree, from which the next
+//!   notes are appended and the root is computed,
+//! - the note commitments themselves, in buckets of [`BUCKET_SIZE`] leaves of
+//!   which only the last one is rewritten,
+//! - the root of the tree at every block height in which it changed, over
+//!   which the inclusion of a note can be proven.
+
+use masp_primitives::ff::PrimeField;
+use masp_primitives::merkle_tree::CommitmentTree;
+use masp_primitives::sapling::Node;
+use masp_primitives::transaction::Transaction;
+
+use super::masp_nullifiers;
+use super::storage_api::token::MaspTxRecord;
+use super::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::address::MASP;
+use crate::types::storage::{BlockHeight, DbKeySeg, Key, KeySeg};
+use crate::types::token;
+
+/// The number of note commitments stored under a bucket key
+pub const BUCKET_SIZE: u64 = 256;
+
+/// The storage key segments of the note commitment tree
+const TREE: &str = "note_commitment_tree";
+const FRONTIER: &str = "frontier";
+const BUCKET: &str = "bucket";
+const ROOT: &str = "root";
+
+/// The storage key prefix of the note commitment tree
+pub fn tree_prefix() -> Key {
+    Key::from(MASP.to_db_key())
+        .push(&TREE.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a key of the note commitment tree
+pub fn is_tree_key(key: &Key) -> bool {
+    matches!(
+        &key.segments[..],
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), ..]
+            if addr == &MASP && prefix == TREE
+    )
+}
+
+/// The storage key of the frontier of the tree
+pub fn frontier_key() -> Key {
+    tree_prefix()
+        .push(&FRONTIER.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of a bucket of note commitments
+pub fn bucket_key(bucket: u64) -> Key {
+    tree_prefix()
+        .push(&BUCKET.to_owned())
+        .and_then(|key| key.push(&bucket))
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the root of the tree at a block height
+pub fn root_key(height: BlockHeight) -> Key {
+    tree_prefix()
+        .push(&ROOT.to_owned())
+        .and_then(|key| key.push(&height))
+        .expect("Cannot obtain a storage key")
+}
+
+/// The commitments of the notes created by a shielded tx, as the leaves of
+/// the tree
+pub fn note_commitments(shielded: &Transaction) -> Vec<Node> {
+    shielded
+        .sapling_bundle()
+        .map_or(&vec![], |bundle| &bundle.shielded_outputs)
+        .iter()
+        .map(|output| Node::new(output.cmu.to_repr()))
+        .collect()
+}
+
+/// Read the frontier of the tree, which is empty before the first note
+pub fn read_frontier<S>(
+    storage: &S,
+) -> storage_api::Result<CommitmentTree<Node>>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read(&frontier_key())?
+        .unwrap_or_else(CommitmentTree::empty))
+}
+
+/// Read the root of the tree at the given block height, if it changed in the
+/// block at this height
+pub fn read_root<S>(
+    storage: &S,
+    height: BlockHeight,
+) -> storage_api::Result<Option<Node>>
+where
+    S: StorageRead,
+{
+    storage.read(&root_key(height))
+}
+
+/// Read the note commitments from the given position of the tree, up to the
+/// given count or to the end of the tree. The buckets are read into a reused
+/// buffer.
+pub fn read_note_commitments<S>(
+    storage: &S,
+    start: u64,
+    count: u64,
+) -> storage_api::Result<Vec<Node>>
+where
+    S: StorageRead,
+{
+    let end = start + count;
+    let mut notes = Vec::with_capacity(count as usize);
+    let mut buf = Vec::new();
+    let mut position = start;
+    while position < end {
+        let bucket = position / BUCKET_SIZE;
+        let leaves: Vec<Node> = storage
+            .read_with_buf(&bucket_key(bucket), &mut buf)?
+            .unwrap_or_default();
+        let from = (position % BUCKET_SIZE) as usize;
+        let to = (end - bucket * BUCKET_SIZE).min(BUCKET_SIZE) as usize;
+        notes.extend(leaves.iter().take(to).skip(from).copied());
+        if leaves.len() < to {
+            // The tree ends in this bucket
+            break;
+        }
+        position = (bucket + 1) * BUCKET_SIZE;
+    }
+    Ok(notes)
+}
+
+/// Append the given note commitments to a frontier of the tree
+pub fn append_to_frontier(
+    frontier: &mut CommitmentTree<Node>,
+    notes: &[Node],
+) -> storage_api::Result<()> {
+    for note in notes {
+        frontier.append(*note).map_err(|()| {
+            storage_api::Error::new_const("The note commitment tree is full")
+        })?;
+    }
+    Ok(())
+}
+
+/// Append the given note commitments to the tree and write its new root at
+/// the current block height
+pub fn append_note_commitments<S>(
+    storage: &mut S,
+    notes: &[Node],
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if notes.is_empty() {
+        return Ok(());
+    }
+    let mut frontier = read_frontier(storage)?;
+    let mut position = frontier.size() as u64;
+    append_to_frontier(&mut frontier, notes)?;
+
+    // Only the buckets of the new notes are rewritten
+    let mut notes = notes.iter().copied().peekable();
+    let mut buf = Vec::new();
+    while notes.peek().is_some() {
+        let bucket = position / BUCKET_SIZE;
+        let key = bucket_key(bucket);
+        let mut leaves: Vec<Node> =
+            storage.read_with_buf(&key, &mut buf)?.unwrap_or_default();
+        while leaves.len() < BUCKET_SIZE as usize {
+            match notes.next() {
+                Some(note) => leaves.push(note),
+                None => break,
+            }
+        }
+        position = bucket * BUCKET_SIZE + leaves.len() as u64;
+        storage.write(&key, leaves)?;
+    }
+
+    let height = storage.get_block_height()?;
+    storage.write(&root_key(height), frontier.root())?;
+    storage.write(&frontier_key(), frontier)
+}
+
+/// Seed the tree with the notes of the shielded txs that were recorded before
+/// their notes were appended to it, in the order of the records, and reveal
+/// the nullifiers of the notes these txs spent, so that they can't be spent
+/// again. This migrates the storage of a chain upgraded from a version that
+/// kept neither the tree nor the nullifiers. Does nothing if the tree has
+/// already been started.
+pub fn seed_from_recorded_txs<S>(storage: &mut S) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if storage.has_key(&frontier_key())? {
+        return Ok(());
+    }
+    let head_tx_idx: u64 = storage
+        .read(&token::masp_head_tx_key())?
+        .unwrap_or_default();
+    let mut notes = Vec::new();
+    let nullifiers = masp_nullifiers::nullifiers_handle();
+    for idx in 0..head_tx_idx {
+        let record: Option<MaspTxRecord> =
+            storage.read(&token::masp_tx_key(idx))?;
+        if let Some((_epoch, _height, _tx_index, _transfer, shielded)) = record
+        {
+            notes.extend(note_commitments(&shielded));
+            for nullifier in masp_nullifiers::nullifiers(&shielded) {
+                nullifiers.insert(storage, nullifier)?;
+            }
+        }
+    }
+    append_note_commitments(storage, &notes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+
+    /// Test that the tree in storage has the same root as a tree built in
+    /// memory and keeps every note commitment
+    #[test]
+    fn test_append_note_commitments() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let notes: Vec<Node> = (0..BUCKET_SIZE + 10)
+            .map(|i| {
+                let mut repr = [0_u8; 32];
+                repr[..8].copy_from_slice(&i.to_le_bytes());
+                Node::new(repr)
+            })
+            .collect();
+        let mut tree = CommitmentTree::empty();
+        // Append the notes in batches that span the buckets
+        for batch in notes.chunks(100) {
+            append_note_commitments(&mut storage, batch)?;
+            append_to_frontier(&mut tree, batch)?;
+        }
+
+        let frontier = read_frontier(&storage)?;
+        assert_eq!(frontier.size(), notes.len());
+        assert_eq!(frontier.root(), tree.root());
+        let height = storage.get_block_height()?;
+        assert_eq!(read_root(&storage, height)?, Some(tree.root()));
+        assert_eq!(
+            read_note_commitments(&storage, 0, notes.len() as u64)?,
+            notes
+        );
+        assert!(
+            read_note_commitments(&storage, notes.len() as u64, 1)?.is_empty()
+        );
+        assert_eq!(
+            read_note_commitments(&storage, 250, 20)?,
+            notes[250..270].to_vec()
+        );
+        assert_eq!(
+            read_note_commitments(&storage, 260, 100)?,
+            notes[260..].to_vec()
+        );
+        assert!(is_tree_key(&bucket_key(1)));
+        Ok(())
+    }
+}
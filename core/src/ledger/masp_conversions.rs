@@ -214,29 +214,15 @@ where
     };
     use rayon::prelude::ParallelSlice;
 
-    use crate::types::address;
-
     // The derived conversions will be placed in MASP address space
     let masp_addr = MASP;
 
-    let tokens = address::tokens();
-    let mut masp_reward_keys: Vec<_> = tokens
-        .into_keys()
-        .map(|k| {
-            wl_storage
-                .storage
-                .conversion_state
-                .tokens
-                .get(k)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "Could not find token alias {} in MASP conversion \
-                         state.",
-                        k
-                    )
-                })
-                .clone()
-        })
+    let mut masp_reward_keys: Vec<_> = wl_storage
+        .storage
+        .conversion_state
+        .tokens
+        .values()
+        .cloned()
         .collect();
     // Put the native rewards first because other inflation computations depend
     // on it
@@ -545,6 +531,9 @@ mod tests {
             max_expected_time_per_block: DurationSecs(3600),
             max_proposal_bytes: Default::default(),
             max_block_gas: 100,
+            max_encrypted_txs_share: Dec::one() / 3,
+            max_decrypted_txs_share: Dec::one() / 3,
+            min_protocol_txs_share: Dec::one() / 3,
             vp_whitelist: vec![],
             tx_whitelist: vec![],
             implicit_vp_code_hash: Default::default(),
@@ -557,6 +546,9 @@ mod tests {
             fee_unshielding_gas_limit: 0,
             fee_unshielding_descriptions_limit: 0,
             minimum_gas_price: Default::default(),
+            wasm_limits: Default::default(),
+            storage_deposit_per_byte: Default::default(),
+            blocked_addresses: None,
         };
 
         // Initialize the state
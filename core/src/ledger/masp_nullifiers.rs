@@ -0,0 +1,116 @@
+//! The set of the MASP nullifiers revealed by shielded txs.
+//!
+//! A note is spent when its nullifier is revealed. The nullifiers are kept in
+//! a [`LazySet`] at well-known keys, so that each one is a leaf of the merkle
+//! tree of the storage: a light client can check that a note is unspent from
+//! a non-existence proof of its nullifier key, without downloading the whole
+//! set.
+
+use masp_primitives::transaction::Transaction;
+
+use super::storage_api::collections::{LazyCollection, LazySet};
+use super::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::address::MASP;
+use crate::types::hash::Hash;
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
+
+/// The storage key segment of the nullifier set
+const NULLIFIERS: &str = "nullifiers";
+
+/// The storage key prefix of the nullifier set
+pub fn nullifiers_prefix() -> Key {
+    Key::from(MASP.to_db_key())
+        .push(&NULLIFIERS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// The nullifier set
+pub fn nullifiers_handle() -> LazySet<Hash> {
+    LazySet::open(nullifiers_prefix())
+}
+
+/// The storage key of a nullifier, whose existence or non-existence can be
+/// proven against the merkle root of the storage
+pub fn nullifier_key(nullifier: &Hash) -> Key {
+    nullifiers_handle().get_key(nullifier)
+}
+
+/// Check if the given storage key is a key of the nullifier set. If it is,
+/// returns the nullifier.
+pub fn is_nullifier_key(key: &Key) -> Option<Hash> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(nullifier)]
+            if addr == &MASP && prefix == NULLIFIERS =>
+        {
+            Hash::parse(nullifier.clone()).ok()
+        }
+        _ => None,
+    }
+}
+
+/// The nullifiers of the notes spent by a shielded tx
+pub fn nullifiers(shielded: &Transaction) -> Vec<Hash> {
+    shielded
+        .sapling_bundle()
+        .map_or(&vec![], |bundle| &bundle.shielded_spends)
+        .iter()
+        .map(|spend| Hash(spend.nullifier.0))
+        .collect()
+}
+
+/// Check if a nullifier has been revealed, i.e. if its note is spent
+pub fn is_revealed<S>(
+    storage: &S,
+    nullifier: &Hash,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    nullifiers_handle().contains(storage, nullifier)
+}
+
+/// Reveal the given nullifiers. An error is returned if any of them has
+/// already been revealed, as its note would be spent twice.
+pub fn reveal_nullifiers<S>(
+    storage: &mut S,
+    nullifiers: &[Hash],
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let handle = nullifiers_handle();
+    for nullifier in nullifiers {
+        handle.try_insert(storage, *nullifier)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+
+    /// Test that a nullifier can only be revealed once
+    #[test]
+    fn test_reveal_nullifiers() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let nullifiers = [Hash([1; 32]), Hash([2; 32])];
+        assert!(!is_revealed(&storage, &nullifiers[0])?);
+
+        reveal_nullifiers(&mut storage, &nullifiers)?;
+        for nullifier in &nullifiers {
+            assert!(is_revealed(&storage, nullifier)?);
+            assert_eq!(
+                is_nullifier_key(&nullifier_key(nullifier)),
+                Some(*nullifier)
+            );
+        }
+        assert!(reveal_nullifiers(&mut storage, &nullifiers[1..]).is_err());
+        assert!(reveal_nullifiers(
+            &mut storage,
+            &[Hash([3; 32]), Hash([3; 32])]
+        )
+        .is_err());
+        Ok(())
+    }
+}
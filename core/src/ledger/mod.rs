@@ -5,11 +5,17 @@ pub mod gas;
 pub mod governance;
 pub mod ibc;
 pub mod inflation;
+pub mod masp_commitment_tree;
 pub mod masp_conversions;
+pub mod masp_nullifiers;
+pub mod oracle;
 pub mod parameters;
 pub mod pgf;
 pub mod replay_protection;
 pub mod storage;
 pub mod storage_api;
+pub mod testnet_pow;
 pub mod tx_env;
+pub mod tx_results;
+pub mod upgrade;
 pub mod vp_env;
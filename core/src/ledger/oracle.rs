@@ -0,0 +1,334 @@
+//! Oracle of externally attested data.
+//!
+//! The submitters whitelisted by governance post the values of data feeds,
+//! e.g. token prices, with the `tx_oracle_submit` tx. At the end of every
+//! block, the median of the values submitted for a feed in the block becomes
+//! the value of the feed, readable by txs and VPs with [`read_value`]. The
+//! submissions are then removed, so that the next value of a feed is only
+//! made of the submissions of the block in which it's updated.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::address::{Address, InternalAddress};
+use crate::types::dec::Dec;
+use crate::types::storage::{BlockHeight, DbKeySeg, Key, KeySeg};
+
+/// The oracle internal address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::Oracle);
+
+/// The max length of a feed id
+pub const MAX_FEED_ID_LEN: usize = 64;
+
+/// The storage key segments of the oracle
+const SUBMITTERS: &str = "submitters";
+const FEEDS: &str = "feeds";
+const SUBMISSIONS: &str = "submissions";
+const VALUE: &str = "value";
+
+/// Check if the given storage key is an oracle key
+pub fn is_oracle_key(key: &Key) -> bool {
+    key.segments.first() == Some(&DbKeySeg::AddressSeg(ADDRESS))
+}
+
+/// The storage key of a whitelisted submitter
+pub fn submitter_key(submitter: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&SUBMITTERS.to_owned())
+        .and_then(|key| key.push(submitter))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a key of a whitelisted submitter. If it
+/// is, returns the submitter.
+pub fn is_submitter_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::AddressSeg(submitter)]
+            if addr == &ADDRESS && prefix == SUBMITTERS =>
+        {
+            Some(submitter)
+        }
+        _ => None,
+    }
+}
+
+/// The storage key prefix of the feeds
+pub fn feeds_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&FEEDS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the value submitted for a feed by a submitter
+pub fn submission_key(feed_id: &str, submitter: &Address) -> Key {
+    feeds_prefix()
+        .push(&feed_id.to_owned())
+        .and_then(|key| key.push(&SUBMISSIONS.to_owned()))
+        .and_then(|key| key.push(submitter))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a key of a submitted value. If it is,
+/// returns the feed id and the submitter.
+pub fn is_submission_key(key: &Key) -> Option<(&str, &Address)> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(feeds), DbKeySeg::StringSeg(feed_id), DbKeySeg::StringSeg(submissions), DbKeySeg::AddressSeg(submitter)]
+            if addr == &ADDRESS
+                && feeds == FEEDS
+                && submissions == SUBMISSIONS =>
+        {
+            Some((feed_id.as_str(), submitter))
+        }
+        _ => None,
+    }
+}
+
+/// The storage key of the value of a feed
+pub fn value_key(feed_id: &str) -> Key {
+    feeds_prefix()
+        .push(&feed_id.to_owned())
+        .and_then(|key| key.push(&VALUE.to_owned()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a key of the value of a feed. If it is,
+/// returns the feed id.
+pub fn is_value_key(key: &Key) -> Option<&str> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(feeds), DbKeySeg::StringSeg(feed_id), DbKeySeg::StringSeg(value)]
+            if addr == &ADDRESS && feeds == FEEDS && value == VALUE =>
+        {
+            Some(feed_id.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// Check that a feed id is non-empty, not too long and only made of lowercase
+/// ASCII letters, digits, `-`, `_` and `.`, so that it's a valid storage key
+/// segment that cannot be mistaken for an address
+pub fn is_valid_feed_id(feed_id: &str) -> bool {
+    !feed_id.is_empty()
+        && feed_id.len() <= MAX_FEED_ID_LEN
+        && feed_id.chars().all(|c| {
+            c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || matches!(c, '-' | '_' | '.')
+        })
+        && Address::decode(feed_id).is_err()
+}
+
+/// A value observed for a feed at a block height
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct Observation {
+    /// The value
+    pub value: Dec,
+    /// The height of the block in which the value was submitted
+    pub height: BlockHeight,
+}
+
+/// The data of a submission of the value of a feed
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct OracleSubmission {
+    /// The id of the feed
+    pub feed_id: String,
+    /// The whitelisted submitter, whose signature is required
+    pub submitter: Address,
+    /// The submitted value
+    pub value: Dec,
+}
+
+/// Check if the address is a whitelisted submitter
+pub fn is_submitter<S>(
+    storage: &S,
+    submitter: &Address,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    storage.has_key(&submitter_key(submitter))
+}
+
+/// Add a submitter to the whitelist
+pub fn add_submitter<S>(
+    storage: &mut S,
+    submitter: &Address,
+) -> storage_api::Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    storage.write(&submitter_key(submitter), ())
+}
+
+/// Remove a submitter from the whitelist
+pub fn remove_submitter<S>(
+    storage: &mut S,
+    submitter: &Address,
+) -> storage_api::Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    storage.delete(&submitter_key(submitter))
+}
+
+/// Write a submitted value at the current block height
+pub fn submit<S>(
+    storage: &mut S,
+    submission: &OracleSubmission,
+) -> storage_api::Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    if !is_valid_feed_id(&submission.feed_id) {
+        return Err(storage_api::Error::new_const("Invalid oracle feed id"));
+    }
+    let height = storage.get_block_height()?;
+    storage.write(
+        &submission_key(&submission.feed_id, &submission.submitter),
+        Observation {
+            value: submission.value,
+            height,
+        },
+    )
+}
+
+/// Read the value of a feed, if any
+pub fn read_value<S>(
+    storage: &S,
+    feed_id: &str,
+) -> storage_api::Result<Option<Observation>>
+where
+    S: StorageRead,
+{
+    storage.read(&value_key(feed_id))
+}
+
+/// The median of the given values. For an even number of values, the lower
+/// of the two middle values is used, so that the median is always one of the
+/// submitted values.
+pub fn median(mut values: Vec<Dec>) -> Option<Dec> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort();
+    Some(values[(values.len() - 1) / 2])
+}
+
+/// Update the value of every feed with submissions to the median of the
+/// submitted values and remove the submissions. Returns the updated feeds
+/// with their number of submissions.
+pub fn update_values<S>(
+    storage: &mut S,
+) -> storage_api::Result<Vec<(String, usize, Observation)>>
+where
+    S: StorageWrite + StorageRead,
+{
+    let mut submissions: std::collections::BTreeMap<String, Vec<Dec>> =
+        Default::default();
+    let mut keys = vec![];
+    for entry in
+        storage_api::iter_prefix::<Observation>(storage, &feeds_prefix())?
+    {
+        let (key, observation) = entry?;
+        if let Some((feed_id, _submitter)) = is_submission_key(&key) {
+            submissions
+                .entry(feed_id.to_owned())
+                .or_default()
+                .push(observation.value);
+            keys.push(key);
+        }
+    }
+    for key in keys {
+        storage.delete(&key)?;
+    }
+
+    let height = storage.get_block_height()?;
+    let mut updated = vec![];
+    for (feed_id, values) in submissions {
+        let num_submissions = values.len();
+        if let Some(value) = median(values) {
+            let observation = Observation { value, height };
+            storage.write(&value_key(&feed_id), observation)?;
+            updated.push((feed_id, num_submissions, observation));
+        }
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+
+    #[test]
+    fn test_feed_id() {
+        assert!(is_valid_feed_id("nam-usd"));
+        assert!(is_valid_feed_id("eth.usd_1"));
+        assert!(!is_valid_feed_id(""));
+        assert!(!is_valid_feed_id("NAM/USD"));
+        assert!(!is_valid_feed_id(&"a".repeat(MAX_FEED_ID_LEN + 1)));
+        assert!(!is_valid_feed_id(&established_address_1().encode()));
+    }
+
+    #[test]
+    fn test_update_values() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let feed_id = "nam-usd";
+        for (submitter, value) in [
+            (established_address_1(), Dec::new(3, 0)),
+            (established_address_2(), Dec::new(1, 0)),
+            (established_address_3(), Dec::new(2, 0)),
+        ] {
+            let submission = OracleSubmission {
+                feed_id: feed_id.to_owned(),
+                submitter,
+                value: value.unwrap(),
+            };
+            submit(&mut storage, &submission)?;
+            assert_eq!(
+                is_submission_key(&submission_key(
+                    feed_id,
+                    &submission.submitter
+                )),
+                Some((feed_id, &submission.submitter))
+            );
+        }
+        assert_eq!(read_value(&storage, feed_id)?, None);
+
+        let updated = update_values(&mut storage)?;
+        assert_eq!(updated.len(), 1);
+        let value = read_value(&storage, feed_id)?.expect("Test failed");
+        assert_eq!(value.value, Dec::new(2, 0).unwrap());
+        assert_eq!(updated[0], (feed_id.to_owned(), 3, value));
+
+        // The submissions are removed, the value is kept
+        assert!(update_values(&mut storage)?.is_empty());
+        assert_eq!(read_value(&storage, feed_id)?, Some(value));
+        Ok(())
+    }
+}
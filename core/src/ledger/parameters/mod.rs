@@ -1,7 +1,7 @@
 //! Protocol parameters
 pub mod storage;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use thiserror::Error;
@@ -14,7 +14,8 @@ use crate::types::address::{Address, InternalAddress};
 use crate::types::chain::ProposalBytes;
 use crate::types::dec::Dec;
 use crate::types::hash::Hash;
-use crate::types::time::DurationSecs;
+use crate::types::storage::BlockHeight;
+use crate::types::time::{DateTimeUtc, Duration, DurationSecs};
 use crate::types::token;
 
 /// The internal address for storage keys representing parameters than
@@ -45,6 +46,12 @@ pub struct Parameters {
     pub max_proposal_bytes: ProposalBytes,
     /// Max gas for block
     pub max_block_gas: u64,
+    /// Max share of the proposal space that encrypted txs can take up
+    pub max_encrypted_txs_share: Dec,
+    /// Max share of the proposal space that decrypted txs can take up
+    pub max_decrypted_txs_share: Dec,
+    /// Min share of the proposal space reserved for protocol txs
+    pub min_protocol_txs_share: Dec,
     /// Whitelisted validity predicate hashes (read only)
     pub vp_whitelist: Vec<String>,
     /// Whitelisted tx hashes (read only)
@@ -69,6 +76,14 @@ pub struct Parameters {
     pub fee_unshielding_descriptions_limit: u64,
     /// Map of the cost per gas unit for every token allowed for fee payment
     pub minimum_gas_price: BTreeMap<Address, token::Amount>,
+    /// Limits enforced on every tx and VP WASM execution
+    pub wasm_limits: WasmLimits,
+    /// Native token deposit locked per byte of state added to an account,
+    /// zero to disable the storage deposits
+    pub storage_deposit_per_byte: token::Amount,
+    /// The addresses that can neither send nor receive tokens, maintained by
+    /// governance. `None` disables the blocking of the addresses.
+    pub blocked_addresses: Option<BTreeSet<Address>>,
 }
 
 /// Epoch duration. A new epoch begins as soon as both the `min_num_of_blocks`
@@ -92,6 +107,75 @@ pub struct EpochDuration {
     pub min_duration: DurationSecs,
 }
 
+impl EpochDuration {
+    /// The minimum height and time from which the epoch following one that
+    /// began at the given height and time can begin
+    pub fn next_epoch_min_start(
+        &self,
+        height: BlockHeight,
+        time: DateTimeUtc,
+    ) -> (BlockHeight, DateTimeUtc) {
+        (height + self.min_num_of_blocks, time + self.min_duration)
+    }
+
+    /// Estimate the number of blocks until both the minimum number of blocks
+    /// and the minimum duration of an epoch have passed, if the blocks are
+    /// produced every `block_time`
+    pub fn estimate_num_of_blocks(&self, block_time: Duration) -> u64 {
+        let block_time_ms = block_time.num_milliseconds().max(1) as u64;
+        let min_duration_ms = self.min_duration.0.saturating_mul(1000);
+        // Ceiling division that cannot overflow (`u64::div_ceil` isn't
+        // stable on our toolchain yet)
+        let time_blocks = min_duration_ms / block_time_ms
+            + u64::from(min_duration_ms % block_time_ms != 0);
+        self.min_num_of_blocks.max(time_blocks)
+    }
+}
+
+/// Limits enforced on every execution of a tx or VP WASM module. Because these
+/// are consensus parameters, any code exceeding them is rejected by all the
+/// validators alike.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct WasmLimits {
+    /// Maximum number of WASM pages (64 KiB each) a tx memory can grow to
+    pub tx_memory_max_pages: u32,
+    /// Maximum number of WASM pages (64 KiB each) a VP memory can grow to
+    pub vp_memory_max_pages: u32,
+    /// Maximum number of elements in a module's table
+    pub max_table_elements: u32,
+    /// Maximum size, in bytes, of a module's code
+    pub max_code_bytes: u64,
+    /// Maximum stack height of a module's execution, in the units of the
+    /// stack height limiter injected into the code
+    pub max_stack_height: u32,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            tx_memory_max_pages: 200,
+            vp_memory_max_pages: 200,
+            max_table_elements: u16::MAX as u32,
+            max_code_bytes: 4 * 1024 * 1024,
+            max_stack_height: u16::MAX as u32,
+        }
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum ReadError {
@@ -124,6 +208,9 @@ impl Parameters {
             max_expected_time_per_block,
             max_proposal_bytes,
             max_block_gas,
+            max_encrypted_txs_share,
+            max_decrypted_txs_share,
+            min_protocol_txs_share,
             vp_whitelist,
             tx_whitelist,
             implicit_vp_code_hash,
@@ -136,6 +223,9 @@ impl Parameters {
             minimum_gas_price,
             fee_unshielding_gas_limit,
             fee_unshielding_descriptions_limit,
+            wasm_limits,
+            storage_deposit_per_byte,
+            blocked_addresses,
         } = self;
 
         // write max tx bytes parameter
@@ -150,6 +240,21 @@ impl Parameters {
         let max_block_gas_key = storage::get_max_block_gas_key();
         storage.write(&max_block_gas_key, max_block_gas)?;
 
+        // write max encrypted txs share parameter
+        let max_encrypted_txs_share_key =
+            storage::get_max_encrypted_txs_share_key();
+        storage.write(&max_encrypted_txs_share_key, max_encrypted_txs_share)?;
+
+        // write max decrypted txs share parameter
+        let max_decrypted_txs_share_key =
+            storage::get_max_decrypted_txs_share_key();
+        storage.write(&max_decrypted_txs_share_key, max_decrypted_txs_share)?;
+
+        // write min protocol txs share parameter
+        let min_protocol_txs_share_key =
+            storage::get_min_protocol_txs_share_key();
+        storage.write(&min_protocol_txs_share_key, min_protocol_txs_share)?;
+
         // write epoch parameters
         let epoch_key = storage::get_epoch_duration_storage_key();
         storage.write(&epoch_key, epoch_duration)?;
@@ -223,6 +328,20 @@ impl Parameters {
         let gas_cost_key = storage::get_gas_cost_key();
         storage.write(&gas_cost_key, minimum_gas_price)?;
 
+        let wasm_limits_key = storage::get_wasm_limits_key();
+        storage.write(&wasm_limits_key, wasm_limits)?;
+
+        let storage_deposit_per_byte_key =
+            storage::get_storage_deposit_per_byte_key();
+        storage
+            .write(&storage_deposit_per_byte_key, storage_deposit_per_byte)?;
+
+        // The blocked addresses are only written when enabled for the chain
+        if let Some(blocked_addresses) = blocked_addresses {
+            let blocked_addresses_key = storage::get_blocked_addresses_key();
+            storage.write(&blocked_addresses_key, blocked_addresses)?;
+        }
+
         Ok(())
     }
 }
@@ -251,6 +370,66 @@ where
     storage.write(&key, value)
 }
 
+/// Update the max encrypted txs share parameter in storage.
+pub fn update_max_encrypted_txs_share_parameter<S>(
+    storage: &mut S,
+    value: &Dec,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_max_encrypted_txs_share_key();
+    storage.write(&key, value)
+}
+
+/// Update the max decrypted txs share parameter in storage.
+pub fn update_max_decrypted_txs_share_parameter<S>(
+    storage: &mut S,
+    value: &Dec,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_max_decrypted_txs_share_key();
+    storage.write(&key, value)
+}
+
+/// Update the min protocol txs share parameter in storage.
+pub fn update_min_protocol_txs_share_parameter<S>(
+    storage: &mut S,
+    value: &Dec,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_min_protocol_txs_share_key();
+    storage.write(&key, value)
+}
+
+/// Update the max tx bytes parameter in storage.
+pub fn update_max_tx_bytes_parameter<S>(
+    storage: &mut S,
+    value: u32,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_max_tx_bytes_key();
+    storage.write(&key, value)
+}
+
+/// Update the max block gas parameter in storage.
+pub fn update_max_block_gas_parameter<S>(
+    storage: &mut S,
+    value: u64,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_max_block_gas_key();
+    storage.write(&key, value)
+}
+
 /// Update the vp whitelist parameter in storage. Returns the parameters and gas
 /// cost.
 pub fn update_vp_whitelist_parameter<S>(
@@ -395,6 +574,114 @@ where
     storage.write(&key, value)
 }
 
+/// Update the WASM execution limits parameter in storage.
+pub fn update_wasm_limits_parameter<S>(
+    storage: &mut S,
+    value: &WasmLimits,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_wasm_limits_key();
+    storage.write(&key, value)
+}
+
+/// Read the WASM execution limits parameter from store
+pub fn read_wasm_limits<S>(storage: &S) -> storage_api::Result<WasmLimits>
+where
+    S: StorageRead,
+{
+    let key = storage::get_wasm_limits_key();
+    storage
+        .read(&key)?
+        .ok_or(ReadError::ParametersMissing)
+        .into_storage_result()
+}
+
+/// Update the storage deposit per byte parameter in storage.
+pub fn update_storage_deposit_per_byte_parameter<S>(
+    storage: &mut S,
+    value: &token::Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_storage_deposit_per_byte_key();
+    storage.write(&key, value)
+}
+
+/// Read the storage deposit per byte parameter from store. It is zero, i.e.
+/// the deposits are disabled, for the chains initialized before it was
+/// stored.
+pub fn read_storage_deposit_per_byte<S>(
+    storage: &S,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let key = storage::get_storage_deposit_per_byte_key();
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
+/// Update the blocked addresses parameter in storage.
+pub fn update_blocked_addresses_parameter<S>(
+    storage: &mut S,
+    value: &BTreeSet<Address>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_blocked_addresses_key();
+    storage.write(&key, value)
+}
+
+/// Read the blocked addresses parameter from store. It is `None`, i.e. the
+/// blocking is disabled, for the chains that haven't enabled it at genesis.
+pub fn read_blocked_addresses<S>(
+    storage: &S,
+) -> storage_api::Result<Option<BTreeSet<Address>>>
+where
+    S: StorageRead,
+{
+    let key = storage::get_blocked_addresses_key();
+    storage.read(&key)
+}
+
+/// Read the storage deposit locked by the given address
+pub fn read_storage_deposit<S>(
+    storage: &S,
+    owner: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let key = storage::get_storage_deposit_key(owner);
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
+/// Write the address of the native token of the chain. It is only set at
+/// genesis.
+pub fn init_native_token<S>(
+    storage: &mut S,
+    native_token: &Address,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_native_token_key();
+    storage.write(&key, native_token)
+}
+
+/// Read the address of the native token of the chain from store. It is `None`
+/// for the chains initialized before it was stored.
+pub fn read_native_token<S>(storage: &S) -> storage_api::Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    let key = storage::get_native_token_key();
+    storage.read(&key)
+}
+
 /// Read the the epoch duration parameter from store
 pub fn read_epoch_duration_parameter<S>(
     storage: &S,
@@ -410,6 +697,36 @@ where
         .into_storage_result()
 }
 
+/// Read the max share of the proposal space that encrypted txs can take up.
+/// Defaults to one third on chains without this parameter.
+pub fn read_max_encrypted_txs_share<S>(storage: &S) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let key = storage::get_max_encrypted_txs_share_key();
+    Ok(storage.read(&key)?.unwrap_or_else(|| Dec::one() / 3))
+}
+
+/// Read the max share of the proposal space that decrypted txs can take up.
+/// Defaults to the whole proposal space on chains without this parameter.
+pub fn read_max_decrypted_txs_share<S>(storage: &S) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let key = storage::get_max_decrypted_txs_share_key();
+    Ok(storage.read(&key)?.unwrap_or_else(Dec::one))
+}
+
+/// Read the min share of the proposal space reserved for protocol txs.
+/// Defaults to zero on chains without this parameter.
+pub fn read_min_protocol_txs_share<S>(storage: &S) -> storage_api::Result<Dec>
+where
+    S: StorageRead,
+{
+    let key = storage::get_min_protocol_txs_share_key();
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
 /// Read the cost per unit of gas for the provided token
 pub fn read_gas_cost<S>(
     storage: &S,
@@ -449,6 +766,15 @@ where
             .into_storage_result()?
     };
 
+    // read max encrypted txs share
+    let max_encrypted_txs_share = read_max_encrypted_txs_share(storage)?;
+
+    // read max decrypted txs share
+    let max_decrypted_txs_share = read_max_decrypted_txs_share(storage)?;
+
+    // read min protocol txs share
+    let min_protocol_txs_share = read_min_protocol_txs_share(storage)?;
+
     // read epoch duration
     let epoch_duration = read_epoch_duration_parameter(storage)?;
 
@@ -556,12 +882,24 @@ where
         .ok_or(ReadError::ParametersMissing)
         .into_storage_result()?;
 
+    // read WASM execution limits
+    let wasm_limits = read_wasm_limits(storage)?;
+
+    // read storage deposit per byte
+    let storage_deposit_per_byte = read_storage_deposit_per_byte(storage)?;
+
+    // read blocked addresses
+    let blocked_addresses = read_blocked_addresses(storage)?;
+
     Ok(Parameters {
         max_tx_bytes,
         epoch_duration,
         max_expected_time_per_block,
         max_proposal_bytes,
         max_block_gas,
+        max_encrypted_txs_share,
+        max_decrypted_txs_share,
+        min_protocol_txs_share,
         vp_whitelist,
         tx_whitelist,
         implicit_vp_code_hash,
@@ -574,5 +912,46 @@ where
         minimum_gas_price,
         fee_unshielding_gas_limit,
         fee_unshielding_descriptions_limit,
+        wasm_limits,
+        storage_deposit_per_byte,
+        blocked_addresses,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the number of blocks of an epoch is estimated from whichever
+    /// of its minimum number of blocks and minimum duration passes last
+    #[test]
+    fn test_estimate_epoch_num_of_blocks() {
+        let epoch_duration = EpochDuration {
+            min_num_of_blocks: 10,
+            min_duration: DurationSecs(60),
+        };
+        assert_eq!(
+            epoch_duration.estimate_num_of_blocks(Duration::seconds(1)),
+            60
+        );
+        assert_eq!(
+            epoch_duration.estimate_num_of_blocks(Duration::seconds(10)),
+            10
+        );
+        // A started block counts as a whole block
+        assert_eq!(
+            epoch_duration
+                .estimate_num_of_blocks(Duration::milliseconds(5_500)),
+            11
+        );
+        // A huge duration saturates instead of overflowing
+        let epoch_duration = EpochDuration {
+            min_num_of_blocks: 10,
+            min_duration: DurationSecs(u64::MAX),
+        };
+        assert_eq!(
+            epoch_duration.estimate_num_of_blocks(Duration::milliseconds(1)),
+            u64::MAX
+        );
+    }
+}
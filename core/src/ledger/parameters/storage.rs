@@ -4,7 +4,7 @@ use namada_macros::StorageKeys;
 
 use super::ADDRESS;
 use crate::types::address::Address;
-use crate::types::storage::{DbKeySeg, Key};
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
 
 #[derive(StorageKeys)]
 struct Keys {
@@ -42,10 +42,31 @@ struct Keys {
     max_proposal_bytes: &'static str,
     max_tx_bytes: &'static str,
     max_block_gas: &'static str,
+    max_encrypted_txs_share: &'static str,
+    max_decrypted_txs_share: &'static str,
+    min_protocol_txs_share: &'static str,
     minimum_gas_price: &'static str,
     fee_unshielding_gas_limit: &'static str,
     fee_unshielding_descriptions_limit: &'static str,
     max_signatures_per_transaction: &'static str,
+    wasm_limits: &'static str,
+    native_token: &'static str,
+    storage_deposit_per_byte: &'static str,
+    blocked_addresses: &'static str,
+}
+
+/// The sub-key of the storage deposits locked by the accounts. It is not a
+/// protocol parameter, so the deposits can't be changed by a tx.
+const STORAGE_DEPOSIT_KEY: &str = "storage_deposit";
+
+/// The key segments of the parameters storage sub-spaces, including the
+/// storage deposits
+pub fn storage_keys() -> Vec<&'static str> {
+    Keys::ALL
+        .iter()
+        .copied()
+        .chain([STORAGE_DEPOSIT_KEY])
+        .collect()
 }
 
 /// Returns if the key is a parameter key.
@@ -126,6 +147,21 @@ pub fn is_max_tx_bytes_key(key: &Key) -> bool {
     is_max_tx_bytes_key_at_addr(key, &ADDRESS)
 }
 
+/// Returns if the key is the WASM execution limits key.
+pub fn is_wasm_limits_key(key: &Key) -> bool {
+    is_wasm_limits_key_at_addr(key, &ADDRESS)
+}
+
+/// Returns if the key is the storage deposit per byte key.
+pub fn is_storage_deposit_per_byte_key(key: &Key) -> bool {
+    is_storage_deposit_per_byte_key_at_addr(key, &ADDRESS)
+}
+
+/// Returns if the key is the blocked addresses key.
+pub fn is_blocked_addresses_key(key: &Key) -> bool {
+    is_blocked_addresses_key_at_addr(key, &ADDRESS)
+}
+
 /// Storage key used for epoch parameter.
 pub fn get_epoch_duration_storage_key() -> Key {
     get_epoch_duration_key_at_addr(ADDRESS)
@@ -141,6 +177,21 @@ pub fn get_tx_whitelist_storage_key() -> Key {
     get_tx_whitelist_key_at_addr(ADDRESS)
 }
 
+/// Storage key used for the max encrypted txs share
+pub fn get_max_encrypted_txs_share_key() -> Key {
+    get_max_encrypted_txs_share_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the max decrypted txs share
+pub fn get_max_decrypted_txs_share_key() -> Key {
+    get_max_decrypted_txs_share_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the min protocol txs share
+pub fn get_min_protocol_txs_share_key() -> Key {
+    get_min_protocol_txs_share_key_at_addr(ADDRESS)
+}
+
 /// Storage key used for the fee unshielding gas limit
 pub fn get_fee_unshielding_gas_limit_key() -> Key {
     get_fee_unshielding_gas_limit_key_at_addr(ADDRESS)
@@ -210,3 +261,32 @@ pub fn get_gas_cost_key() -> Key {
 pub fn get_max_signatures_per_transaction_key() -> Key {
     get_max_signatures_per_transaction_key_at_addr(ADDRESS)
 }
+
+/// Storage key used for the WASM execution limits
+pub fn get_wasm_limits_key() -> Key {
+    get_wasm_limits_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the address of the native token
+pub fn get_native_token_key() -> Key {
+    get_native_token_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the storage deposit per byte
+pub fn get_storage_deposit_per_byte_key() -> Key {
+    get_storage_deposit_per_byte_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the addresses blocked from token transfers
+pub fn get_blocked_addresses_key() -> Key {
+    get_blocked_addresses_key_at_addr(ADDRESS)
+}
+
+/// Storage key of the storage deposit locked by the given address
+pub fn get_storage_deposit_key(owner: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&STORAGE_DEPOSIT_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&owner.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
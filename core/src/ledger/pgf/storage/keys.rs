@@ -18,6 +18,11 @@ struct Keys {
     steward_inflation_rate: &'static str,
 }
 
+/// The key segments of the pgf storage sub-spaces
+pub fn storage_keys() -> &'static [&'static str] {
+    Keys::ALL
+}
+
 /// Obtain a storage key for stewards key
 pub fn stewards_key_prefix() -> Key {
     Key {
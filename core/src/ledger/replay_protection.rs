@@ -1,7 +1,10 @@
 //! Replay protection storage
 
+use chrono::{TimeZone, Utc};
+
 use crate::types::hash::Hash;
 use crate::types::storage::Key;
+use crate::types::time::DateTimeUtc;
 
 const ERROR_MSG: &str = "Cannot obtain a valid db key";
 
@@ -30,3 +33,69 @@ pub fn get_replay_protection_last_key(hash: &Hash) -> Key {
         .push(&hash.to_string())
         .expect(ERROR_MSG)
 }
+
+/// Get the transaction hash key under the `expiration` subkey. The key is
+/// prefixed by the zero-padded unix timestamp of the expiration, so that
+/// iterating the `expiration` subkey yields the entries sorted by expiration.
+/// The timestamp is rounded up to the next second to never anticipate the
+/// expiration of a transaction.
+pub fn get_replay_protection_expiration_subkey(
+    expiration: &DateTimeUtc,
+    hash: &Hash,
+) -> Key {
+    let mut timestamp = expiration.0.timestamp().max(0);
+    if expiration.0.timestamp_subsec_nanos() > 0 {
+        timestamp += 1;
+    }
+    Key::parse("expiration")
+        .expect(ERROR_MSG)
+        .push(&format!("{:020}", timestamp))
+        .expect(ERROR_MSG)
+        .push(&hash.to_string())
+        .expect(ERROR_MSG)
+}
+
+/// Parse the expiration and the transaction hash out of a key under the
+/// `expiration` subkey. Only the trailing segments are considered, so that
+/// the key may or may not include the column and subkey prefixes.
+pub fn parse_replay_protection_expiration_key(
+    key: &str,
+) -> Option<(DateTimeUtc, Hash)> {
+    let mut segments = key.rsplit('/');
+    let hash = segments.next()?.parse().ok()?;
+    let timestamp: i64 = segments.next()?.parse().ok()?;
+    let expiration = Utc.timestamp_opt(timestamp, 0).single()?;
+    Some((DateTimeUtc(expiration), hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiration_key_roundtrip() {
+        let hash = Hash::sha256(b"tx");
+        let expiration =
+            DateTimeUtc(Utc.timestamp_opt(1_700_000_000, 0).single().unwrap());
+        let key = get_replay_protection_expiration_subkey(&expiration, &hash);
+        assert_eq!(
+            parse_replay_protection_expiration_key(&key.to_string()),
+            Some((expiration, hash))
+        );
+    }
+
+    #[test]
+    fn test_expiration_keys_sorted_by_time() {
+        let hash = Hash::sha256(b"tx");
+        let earlier =
+            DateTimeUtc(Utc.timestamp_opt(999_999_999, 0).single().unwrap());
+        let later =
+            DateTimeUtc(Utc.timestamp_opt(1_000_000_000, 0).single().unwrap());
+        assert!(
+            get_replay_protection_expiration_subkey(&earlier, &hash)
+                .to_string()
+                < get_replay_protection_expiration_subkey(&later, &hash)
+                    .to_string()
+        );
+    }
+}
@@ -18,6 +18,8 @@ use super::{
 };
 use crate::ledger::masp_conversions::ConversionState;
 use crate::ledger::storage::types::{self, KVBytes, PrefixIterator};
+use crate::ledger::tx_results;
+use crate::types::address::Address;
 use crate::types::ethereum_events::Uint;
 use crate::types::ethereum_structs;
 use crate::types::hash::Hash;
@@ -41,10 +43,14 @@ pub struct MockDB(
 // safe.
 unsafe impl Sync for MockDB {}
 
-/// An in-memory write batch is not needed as it just updates values in memory.
-/// It's here to satisfy the storage interface.
+/// An in-memory write batch. Most of the batch writes update the values in
+/// memory directly, only the writes of the tx results are buffered here until
+/// the batch is executed.
 #[derive(Debug, Default)]
-pub struct MockDBWriteBatch;
+pub struct MockDBWriteBatch {
+    /// The pending tx results writes (`Some`) and deletions (`None`)
+    tx_results: Vec<(String, Option<Vec<u8>>)>,
+}
 
 impl DB for MockDB {
     /// There is no cache for MockDB
@@ -486,7 +492,12 @@ impl DB for MockDB {
         value: impl AsRef<[u8]>,
     ) -> Result<i64> {
         // batch_write are directry committed
-        self.batch_write_subspace_val(&mut MockDBWriteBatch, height, key, value)
+        self.batch_write_subspace_val(
+            &mut MockDBWriteBatch::default(),
+            height,
+            key,
+            value,
+        )
     }
 
     fn delete_subspace_val(
@@ -495,16 +506,27 @@ impl DB for MockDB {
         key: &Key,
     ) -> Result<i64> {
         // batch_delete are directry committed
-        self.batch_delete_subspace_val(&mut MockDBWriteBatch, height, key)
+        self.batch_delete_subspace_val(
+            &mut MockDBWriteBatch::default(),
+            height,
+            key,
+        )
     }
 
     fn batch() -> Self::WriteBatch {
-        MockDBWriteBatch
+        MockDBWriteBatch::default()
     }
 
-    fn exec_batch(&mut self, _batch: Self::WriteBatch) -> Result<()> {
-        // Nothing to do - in MockDB, batch writes are committed directly from
+    fn exec_batch(&mut self, batch: Self::WriteBatch) -> Result<()> {
+        // In MockDB, the other batch writes are committed directly from
         // `batch_write_subspace_val` and `batch_delete_subspace_val`.
+        let mut db = self.0.borrow_mut();
+        for (key, value) in batch.tx_results {
+            match value {
+                Some(value) => db.insert(key, value),
+                None => db.remove(&key),
+            };
+        }
         Ok(())
     }
 
@@ -627,6 +649,80 @@ impl DB for MockDB {
 
         Ok(())
     }
+
+    fn write_tx_result(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        hash: &Hash,
+        result: &[u8],
+    ) -> Result<()> {
+        batch.tx_results.push((
+            tx_results::get_tx_result_key(hash).to_string(),
+            Some(result.to_vec()),
+        ));
+        batch.tx_results.push((
+            tx_results::get_tx_result_height_key(height, hash).to_string(),
+            Some(vec![]),
+        ));
+
+        Ok(())
+    }
+
+    fn delete_tx_result(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        hash: &Hash,
+    ) -> Result<()> {
+        batch
+            .tx_results
+            .push((tx_results::get_tx_result_key(hash).to_string(), None));
+        batch.tx_results.push((
+            tx_results::get_tx_result_height_key(height, hash).to_string(),
+            None,
+        ));
+
+        Ok(())
+    }
+
+    fn read_tx_result(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        let key = tx_results::get_tx_result_key(hash);
+        Ok(self.0.borrow().get(&key.to_string()).cloned())
+    }
+
+    fn write_storage_usage(
+        &mut self,
+        _batch: &mut Self::WriteBatch,
+        owner: Option<&Address>,
+        bytes: u64,
+    ) -> Result<()> {
+        let key = storage_usage_key(owner)?;
+        self.0
+            .borrow_mut()
+            .insert(key.to_string(), types::encode(&bytes));
+
+        Ok(())
+    }
+
+    fn read_storage_usage(&self, owner: Option<&Address>) -> Result<u64> {
+        let key = storage_usage_key(owner)?;
+        match self.0.borrow().get(&key.to_string()) {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError),
+            None => Ok(0),
+        }
+    }
+}
+
+/// The key of the storage usage of the given address, or of all the addresses
+/// if `None`
+fn storage_usage_key(owner: Option<&Address>) -> Result<Key> {
+    let key = Key::parse("storage_usage").map_err(Error::KeyError)?;
+    match owner {
+        Some(owner) => key.push(owner),
+        None => key.push(&"total".to_owned()),
+    }
+    .map_err(Error::KeyError)
 }
 
 impl<'iter> DBIter<'iter> for MockDB {
@@ -712,6 +808,30 @@ impl<'iter> DBIter<'iter> for MockDB {
             db_prefix,
         )
     }
+
+    fn iter_replay_protection_expirations(&'iter self) -> Self::PrefixIter {
+        let db_prefix = "replay_protection/".to_owned();
+        let iter = self.0.borrow().clone().into_iter();
+        MockPrefixIterator::new(
+            MockIterator {
+                prefix: "replay_protection/expiration".to_string(),
+                iter,
+            },
+            db_prefix,
+        )
+    }
+
+    fn iter_tx_result_heights(&'iter self) -> Self::PrefixIter {
+        let db_prefix = tx_results::get_tx_result_height_prefix().to_string();
+        let iter = self.0.borrow().clone().into_iter();
+        MockPrefixIterator::new(
+            MockIterator {
+                prefix: db_prefix.clone(),
+                iter,
+            },
+            db_prefix,
+        )
+    }
 }
 
 /// A prefix iterator base for the [`MockPrefixIterator`].
@@ -11,6 +11,7 @@ pub mod write_log;
 
 use core::fmt::Debug;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::format;
 
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -21,7 +22,8 @@ pub use merkle_tree::{
 use thiserror::Error;
 pub use traits::{DummyHasher, KeccakHasher, Sha256Hasher, StorageHasher};
 pub use wl_storage::{
-    iter_prefix_post, iter_prefix_pre, PrefixIter, TempWlStorage, WlStorage,
+    iter_prefix_post, iter_prefix_pre, PrefixIter, TempWlStorage,
+    TxScopedWlStorage, WlStorage,
 };
 
 use super::gas::MEMORY_ACCESS_GAS_PER_BYTE;
@@ -32,7 +34,8 @@ use crate::ledger::gas::{
 pub use crate::ledger::masp_conversions::{
     calculate_masp_rewards, encode_asset_type, ConversionState,
 };
-use crate::ledger::parameters::{self, EpochDuration, Parameters};
+use crate::ledger::parameters::{self, Parameters};
+use crate::ledger::{replay_protection, tx_results};
 use crate::ledger::storage::merkle_tree::{
     Error as MerkleTreeError, MerkleRoot,
 };
@@ -41,12 +44,12 @@ use crate::types::address::{Address, EstablishedAddressGen, InternalAddress};
 use crate::types::chain::{ChainId, CHAIN_ID_LENGTH};
 use crate::types::ethereum_events::Uint;
 use crate::types::ethereum_structs;
-use crate::types::hash::{Error as HashError, Hash};
+use crate::types::hash::{Error as HashError, Hash, HASH_LENGTH};
 use crate::types::internal::{ExpiredTxsQueue, TxQueue};
 use crate::types::storage::{
-    BlockHash, BlockHeight, BlockResults, Epoch, Epochs, EthEventsQueue,
-    Header, Key, KeySeg, MembershipProof, TxIndex, BLOCK_HASH_LENGTH,
-    BLOCK_HEIGHT_LENGTH, EPOCH_TYPE_LENGTH,
+    BlockHash, BlockHeight, BlockResults, DbKeySeg, Epoch, Epochs,
+    EthEventsQueue, Header, Key, KeySeg, MembershipProof, TxIndex,
+    BLOCK_HASH_LENGTH, BLOCK_HEIGHT_LENGTH, EPOCH_TYPE_LENGTH,
 };
 use crate::types::time::DateTimeUtc;
 
@@ -113,6 +116,12 @@ where
     pub eth_events_queue: EthEventsQueue,
     /// How many block heights in the past can the storage be queried
     pub storage_read_past_height_limit: Option<u64>,
+    /// The encoded results of the txs applied in the current block, indexed
+    /// by the tx hash. These are written to the DB on commit.
+    pub indexed_tx_results: BTreeMap<Hash, Vec<u8>>,
+    /// The changes of the bytes of state owned by the addresses in the
+    /// current block. These are added to their storage usage on commit.
+    pub storage_usage_diffs: BTreeMap<Address, i64>,
 }
 
 /// Last committed block
@@ -149,6 +158,11 @@ pub struct BlockStorage<H: StorageHasher> {
     pub results: BlockResults,
     /// Predecessor block epochs
     pub pred_epochs: Epochs,
+    /// During `FinalizeBlock`, this is the randomness of the block that is
+    /// going to be committed, derived from the data of the last block. It is
+    /// predictable and can be biased by the block proposer and by the
+    /// validators choosing whether to sign, so it is only weakly random.
+    pub randomness: Hash,
 }
 
 #[allow(missing_docs)]
@@ -293,6 +307,24 @@ pub trait DB: std::fmt::Debug {
     /// Read the latest value for account subspace key from the DB
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>>;
 
+    /// Read the latest value for account subspace key from the DB into the
+    /// given buffer, replacing its content. Returns `false` if there's no
+    /// value, in which case the buffer is left untouched. A DB may override
+    /// this to reuse the capacity of the buffer for large values.
+    fn read_subspace_val_into(
+        &self,
+        key: &Key,
+        buf: &mut Vec<u8>,
+    ) -> Result<bool> {
+        match self.read_subspace_val(key)? {
+            Some(val) => {
+                *buf = val;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /// Read the value for account subspace key at the given height from the DB.
     /// In our `PersistentStorage` (rocksdb), to find a value from arbitrary
     /// height requires looking for diffs from the given `height`, possibly
@@ -378,6 +410,41 @@ pub trait DB: std::fmt::Debug {
         batch: &mut Self::WriteBatch,
         key: &Key,
     ) -> Result<()>;
+
+    /// Write the encoded result of the tx with the given hash, together with
+    /// its entry in the height index
+    fn write_tx_result(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        hash: &Hash,
+        result: &[u8],
+    ) -> Result<()>;
+
+    /// Delete the result of the tx with the given hash, together with its
+    /// entry in the height index
+    fn delete_tx_result(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        height: BlockHeight,
+        hash: &Hash,
+    ) -> Result<()>;
+
+    /// Read the encoded result of the tx with the given hash
+    fn read_tx_result(&self, hash: &Hash) -> Result<Option<Vec<u8>>>;
+
+    /// Write the number of bytes of state owned by the given address, or by
+    /// all the addresses if `None`
+    fn write_storage_usage(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        owner: Option<&Address>,
+        bytes: u64,
+    ) -> Result<()>;
+
+    /// Read the number of bytes of state owned by the given address, or by
+    /// all the addresses if `None`
+    fn read_storage_usage(&self, owner: Option<&Address>) -> Result<u64>;
 }
 
 /// A database prefix iterator.
@@ -412,6 +479,13 @@ pub trait DBIter<'iter> {
 
     /// Read replay protection storage from the last block
     fn iter_replay_protection(&'iter self) -> Self::PrefixIter;
+
+    /// Read the expiration index of the replay protection storage, ordered
+    /// by expiration time
+    fn iter_replay_protection_expirations(&'iter self) -> Self::PrefixIter;
+
+    /// Read the height index of the tx results, ordered by block height
+    fn iter_tx_result_heights(&'iter self) -> Self::PrefixIter;
 }
 
 /// Atomic batch write.
@@ -437,6 +511,7 @@ where
             epoch: Epoch::default(),
             pred_epochs: Epochs::default(),
             results: BlockResults::default(),
+            randomness: Hash::default(),
         };
         Storage::<D, H> {
             db: D::open(db_path, cache),
@@ -458,6 +533,8 @@ where
             native_token,
             ethereum_height: None,
             eth_events_queue: EthEventsQueue::default(),
+            indexed_tx_results: BTreeMap::new(),
+            storage_usage_diffs: BTreeMap::new(),
             storage_read_past_height_limit,
         }
     }
@@ -561,6 +638,16 @@ where
         };
         self.db
             .add_block_to_batch(state, &mut batch, is_full_commit)?;
+        for (hash, result) in std::mem::take(&mut self.indexed_tx_results) {
+            self.db.write_tx_result(
+                &mut batch,
+                self.block.height,
+                &hash,
+                &result,
+            )?;
+        }
+        self.prune_tx_results(&mut batch)?;
+        self.commit_storage_usage(&mut batch)?;
         let header = self
             .header
             .take()
@@ -683,6 +770,7 @@ where
         let gas = (key.len() + len) as u64 * STORAGE_WRITE_GAS_PER_BYTE;
         let size_diff =
             self.db.write_subspace_val(self.block.height, key, value)?;
+        self.account_storage_usage(key, size_diff);
         Ok((gas, size_diff))
     }
 
@@ -696,6 +784,7 @@ where
             self.block.tree.delete(key)?;
             deleted_bytes_len =
                 self.db.delete_subspace_val(self.block.height, key)?;
+            self.account_storage_usage(key, -deleted_bytes_len);
         }
         let gas = (key.len() + deleted_bytes_len as usize) as u64
             * STORAGE_WRITE_GAS_PER_BYTE;
@@ -722,6 +811,12 @@ where
         Ok(())
     }
 
+    /// Set the randomness of the block. It's not in the Merkle tree as it's
+    /// derived from the data of the last block tracked by Tendermint.
+    pub fn set_block_randomness(&mut self, randomness: Hash) {
+        self.block.randomness = randomness;
+    }
+
     /// Get the hash of a validity predicate for the given account address and
     /// the gas cost for reading it.
     pub fn validity_predicate(
@@ -774,6 +869,14 @@ where
         )
     }
 
+    /// Get the weak randomness of the block
+    pub fn get_block_randomness(&self) -> (Hash, u64) {
+        (
+            self.block.randomness,
+            HASH_LENGTH as u64 * MEMORY_ACCESS_GAS_PER_BYTE,
+        )
+    }
+
     /// Rebuild full Merkle tree after [`read_last_block()`]
     fn rebuild_full_merkle_tree(
         &self,
@@ -1014,12 +1117,12 @@ where
         genesis_time: DateTimeUtc,
         parameters: &Parameters,
     ) -> Result<()> {
-        let EpochDuration {
-            min_num_of_blocks,
-            min_duration,
-        } = parameters.epoch_duration;
-        self.next_epoch_min_start_height = initial_height + min_num_of_blocks;
-        self.next_epoch_min_start_time = genesis_time + min_duration;
+        (
+            self.next_epoch_min_start_height,
+            self.next_epoch_min_start_time,
+        ) = parameters
+            .epoch_duration
+            .next_epoch_min_start(initial_height, genesis_time);
         self.block.pred_epochs = Epochs {
             first_block_heights: vec![initial_height],
         };
@@ -1128,8 +1231,14 @@ where
             // Update the merkle tree
             self.block.tree.update(key, value)?;
         }
-        self.db
-            .batch_write_subspace_val(batch, self.block.height, key, value)
+        let size_diff = self.db.batch_write_subspace_val(
+            batch,
+            self.block.height,
+            key,
+            value,
+        )?;
+        self.account_storage_usage(key, size_diff);
+        Ok(size_diff)
     }
 
     /// Batch delete the value with the given height and account subspace key
@@ -1142,8 +1251,80 @@ where
     ) -> Result<i64> {
         // Update the merkle tree
         self.block.tree.delete(key)?;
-        self.db
-            .batch_delete_subspace_val(batch, self.block.height, key)
+        let deleted_bytes_len =
+            self.db
+                .batch_delete_subspace_val(batch, self.block.height, key)?;
+        self.account_storage_usage(key, -deleted_bytes_len);
+        Ok(deleted_bytes_len)
+    }
+
+    /// Add the size difference of a written or deleted value to the storage
+    /// usage of the address that owns its key, i.e. the address of its first
+    /// segment
+    fn account_storage_usage(&mut self, key: &Key, size_diff: i64) {
+        if let Some(DbKeySeg::AddressSeg(owner)) = key.segments.first() {
+            if size_diff != 0 {
+                *self.storage_usage_diffs.entry(owner.clone()).or_default() +=
+                    size_diff;
+            }
+        }
+    }
+
+    /// Add the storage usage changes of the block to the usage of their
+    /// owners and to the total usage
+    fn commit_storage_usage(
+        &mut self,
+        batch: &mut D::WriteBatch,
+    ) -> Result<()> {
+        let diffs = std::mem::take(&mut self.storage_usage_diffs);
+        if diffs.is_empty() {
+            return Ok(());
+        }
+        let apply = |usage: u64, diff: i64| {
+            u64::try_from(i128::from(usage) + i128::from(diff))
+                .unwrap_or_default()
+        };
+        let mut total = self.db.read_storage_usage(None)?;
+        for (owner, diff) in diffs {
+            let usage = self.db.read_storage_usage(Some(&owner))?;
+            self.db.write_storage_usage(
+                batch,
+                Some(&owner),
+                apply(usage, diff),
+            )?;
+            total = apply(total, diff);
+        }
+        self.db.write_storage_usage(batch, None, total)
+    }
+
+    /// Read the number of bytes of state owned by the given address, or by all
+    /// the addresses if `None`, as of the last committed block
+    pub fn read_storage_usage(&self, owner: Option<&Address>) -> Result<u64> {
+        self.db.read_storage_usage(owner)
+    }
+
+    // Prune the tx results that can no longer be queried, i.e. those older
+    // than the `storage_read_past_height_limit`, if any.
+    fn prune_tx_results(&mut self, batch: &mut D::WriteBatch) -> Result<()> {
+        let Some(limit) = self.storage_read_past_height_limit else {
+            return Ok(());
+        };
+        let Some(oldest_height) = self.block.height.0.checked_sub(limit) else {
+            return Ok(());
+        };
+        let expired: Vec<_> = self
+            .db
+            .iter_tx_result_heights()
+            .map(|(key, _, _)| {
+                tx_results::parse_tx_result_height_key(&key)
+                    .expect("Failed tx result height key conversion")
+            })
+            .take_while(|(height, _)| height.0 < oldest_height)
+            .collect();
+        for (height, hash) in expired {
+            self.db.delete_tx_result(batch, height, &hash)?;
+        }
+        Ok(())
     }
 
     // Prune merkle tree stores. Use after updating self.block.height in the
@@ -1280,6 +1461,12 @@ where
         self.db.delete_replay_protection_entry(batch, key)
     }
 
+    /// Read the encoded result of the tx with the given hash, if it was
+    /// indexed on commit
+    pub fn read_tx_result(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        self.db.read_tx_result(hash)
+    }
+
     /// Iterate the replay protection storage from the last block
     pub fn iter_replay_protection(
         &self,
@@ -1292,6 +1479,21 @@ where
                 .expect("Failed hash conversion")
         }))
     }
+
+    /// Iterate the expiration index of the replay protection storage,
+    /// ordered by expiration time
+    pub fn iter_replay_protection_expirations(
+        &self,
+    ) -> Box<dyn Iterator<Item = (DateTimeUtc, Hash)> + '_> {
+        Box::new(self.db.iter_replay_protection_expirations().map(
+            |(key, _, _)| {
+                replay_protection::parse_replay_protection_expiration_key(
+                    &key,
+                )
+                .expect("Failed replay protection expiration key conversion")
+            },
+        ))
+    }
 }
 
 impl From<MerkleTreeError> for Error {
@@ -1329,6 +1531,7 @@ pub mod testing {
                 epoch: Epoch::default(),
                 pred_epochs: Epochs::default(),
                 results: BlockResults::default(),
+                randomness: Hash::default(),
             };
             Self {
                 db: MockDB::default(),
@@ -1350,6 +1553,8 @@ pub mod testing {
                 native_token: address::nam(),
                 ethereum_height: None,
                 eth_events_queue: EthEventsQueue::default(),
+                indexed_tx_results: BTreeMap::new(),
+                storage_usage_diffs: BTreeMap::new(),
                 storage_read_past_height_limit: Some(1000),
             }
         }
@@ -1376,7 +1581,7 @@ mod tests {
 
     use super::testing::*;
     use super::*;
-    use crate::ledger::parameters::{self, Parameters};
+    use crate::ledger::parameters::{self, EpochDuration, Parameters};
     use crate::types::dec::Dec;
     use crate::types::time::{self, Duration};
     use crate::types::token;
@@ -1453,6 +1658,9 @@ mod tests {
                 max_tx_bytes: 1024 * 1024,
                 max_proposal_bytes: Default::default(),
                 max_block_gas: 20_000_000,
+                max_encrypted_txs_share: Dec::one() / 3,
+                max_decrypted_txs_share: Dec::one() / 3,
+                min_protocol_txs_share: Dec::one() / 3,
                 epoch_duration: epoch_duration.clone(),
                 max_expected_time_per_block: Duration::seconds(max_expected_time_per_block).into(),
                 vp_whitelist: vec![],
@@ -1467,6 +1675,9 @@ mod tests {
                 fee_unshielding_gas_limit: 20_000,
                 fee_unshielding_descriptions_limit: 15,
                 minimum_gas_price: BTreeMap::default(),
+                wasm_limits: Default::default(),
+                storage_deposit_per_byte: Default::default(),
+                blocked_addresses: None,
             };
             parameters.init_storage(&mut wl_storage).unwrap();
             // Initialize pred_epochs to the current height
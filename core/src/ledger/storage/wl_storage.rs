@@ -3,7 +3,6 @@
 use std::iter::Peekable;
 
 use super::EPOCH_SWITCH_BLOCKS_DELAY;
-use crate::ledger::parameters::EpochDuration;
 use crate::ledger::storage::write_log::{self, WriteLog};
 use crate::ledger::storage::{DBIter, Storage, StorageHasher, DB};
 use crate::ledger::storage_api::{ResultExt, StorageRead, StorageWrite};
@@ -79,6 +78,20 @@ where
     }
 }
 
+/// A [`WlStorage`] whose writes go to the write log of the current tx rather
+/// than directly to the one of the block, so that a change made by the
+/// protocol that may fail part way can be dropped as a whole. Obtained from
+/// [`WlStorage::apply_tx_scoped`].
+#[derive(Debug)]
+pub struct TxScopedWlStorage<'a, D, H>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// The storage written through the tx write log
+    wl_storage: &'a mut WlStorage<D, H>,
+}
+
 /// Common trait for [`WlStorage`] and [`TempWlStorage`], used to implement
 /// storage_api traits.
 pub trait WriteLogAndStorage {
@@ -195,6 +208,24 @@ where
         self.write_log.drop_tx()
     }
 
+    /// Apply a change through the write log of a tx, committing it to the
+    /// block if it succeeds or dropping all of its writes if it fails. Must
+    /// not be called while a tx is being applied.
+    pub fn apply_tx_scoped<T>(
+        &mut self,
+        apply: impl FnOnce(
+            &mut TxScopedWlStorage<'_, D, H>,
+        ) -> storage_api::Result<T>,
+    ) -> storage_api::Result<T> {
+        let result = apply(&mut TxScopedWlStorage { wl_storage: self });
+        if result.is_ok() {
+            self.commit_tx();
+        } else {
+            self.drop_tx();
+        }
+        result
+    }
+
     /// Commit the current block's write log to the storage and commit the block
     /// to DB. Starts a new block write log.
     pub fn commit_block(&mut self) -> storage_api::Result<()> {
@@ -250,13 +281,10 @@ where
 
             // Begin a new epoch
             self.storage.block.epoch = self.storage.block.epoch.next();
-            let EpochDuration {
-                min_num_of_blocks,
-                min_duration,
-            } = parameters.epoch_duration;
-            self.storage.next_epoch_min_start_height =
-                height + min_num_of_blocks;
-            self.storage.next_epoch_min_start_time = time + min_duration;
+            (
+                self.storage.next_epoch_min_start_height,
+                self.storage.next_epoch_min_start_time,
+            ) = parameters.epoch_duration.next_epoch_min_start(height, time);
 
             self.storage.block.pred_epochs.new_epoch(height);
             tracing::info!("Began a new epoch {}", self.storage.block.epoch);
@@ -439,11 +467,42 @@ where
                 self.storage()
                     .db
                     .read_subspace_val(key)
-                    .into_storage_result()
+                    .map_err(storage_api::Error::backend)
             }
         }
     }
 
+    fn read_bytes_into(
+        &self,
+        key: &storage::Key,
+        buf: &mut Vec<u8>,
+    ) -> storage_api::Result<bool> {
+        // try to read from the write log first, copying the value into the
+        // buffer rather than cloning it
+        let (log_val, _gas) = self.write_log().read(key);
+        let value = match log_val {
+            Some(write_log::StorageModification::Write { ref value })
+            | Some(write_log::StorageModification::Temp { ref value }) => {
+                value.as_slice()
+            }
+            Some(write_log::StorageModification::InitAccount {
+                ref vp_code_hash,
+            }) => vp_code_hash.as_ref(),
+            Some(write_log::StorageModification::Delete) => return Ok(false),
+            None => {
+                // when not found in write log, try to read from the storage
+                return self
+                    .storage()
+                    .db
+                    .read_subspace_val_into(key, buf)
+                    .map_err(storage_api::Error::backend);
+            }
+        };
+        buf.clear();
+        buf.extend_from_slice(value);
+        Ok(true)
+    }
+
     fn has_key(&self, key: &storage::Key) -> storage_api::Result<bool> {
         // try to read from the write log first
         let (log_val, _gas) = self.write_log().read(key);
@@ -457,7 +516,11 @@ where
             }
             None => {
                 // when not found in write log, try to check the storage
-                Ok(self.storage().has_key(key).into_storage_result()?.0)
+                Ok(self
+                    .storage()
+                    .has_key(key)
+                    .map_err(storage_api::Error::backend)?
+                    .0)
             }
         }
     }
@@ -495,7 +558,7 @@ where
         self.storage()
             .db
             .read_block_header(height)
-            .into_storage_result()
+            .map_err(storage_api::Error::backend)
     }
 
     fn get_block_hash(
@@ -550,6 +613,104 @@ where
     }
 }
 
+impl<D, H> StorageRead for TxScopedWlStorage<'_, D, H>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    type PrefixIter<'iter> = PrefixIter<'iter, D> where Self: 'iter;
+
+    fn read_bytes(
+        &self,
+        key: &storage::Key,
+    ) -> storage_api::Result<Option<Vec<u8>>> {
+        self.wl_storage.read_bytes(key)
+    }
+
+    fn read_bytes_into(
+        &self,
+        key: &storage::Key,
+        buf: &mut Vec<u8>,
+    ) -> storage_api::Result<bool> {
+        self.wl_storage.read_bytes_into(key, buf)
+    }
+
+    fn has_key(&self, key: &storage::Key) -> storage_api::Result<bool> {
+        self.wl_storage.has_key(key)
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        prefix: &storage::Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        self.wl_storage.iter_prefix(prefix)
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        iter: &mut Self::PrefixIter<'iter>,
+    ) -> storage_api::Result<Option<(String, Vec<u8>)>> {
+        self.wl_storage.iter_next(iter)
+    }
+
+    fn get_chain_id(&self) -> storage_api::Result<String> {
+        self.wl_storage.get_chain_id()
+    }
+
+    fn get_block_height(&self) -> storage_api::Result<storage::BlockHeight> {
+        self.wl_storage.get_block_height()
+    }
+
+    fn get_block_header(
+        &self,
+        height: storage::BlockHeight,
+    ) -> storage_api::Result<Option<storage::Header>> {
+        self.wl_storage.get_block_header(height)
+    }
+
+    fn get_block_hash(&self) -> storage_api::Result<storage::BlockHash> {
+        self.wl_storage.get_block_hash()
+    }
+
+    fn get_block_epoch(&self) -> storage_api::Result<storage::Epoch> {
+        self.wl_storage.get_block_epoch()
+    }
+
+    fn get_tx_index(&self) -> storage_api::Result<storage::TxIndex> {
+        self.wl_storage.get_tx_index()
+    }
+
+    fn get_native_token(&self) -> storage_api::Result<Address> {
+        self.wl_storage.get_native_token()
+    }
+}
+
+impl<D, H> StorageWrite for TxScopedWlStorage<'_, D, H>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    fn write_bytes(
+        &mut self,
+        key: &storage::Key,
+        val: impl AsRef<[u8]>,
+    ) -> storage_api::Result<()> {
+        self.wl_storage
+            .write_log
+            .write(key, val.as_ref().to_vec())
+            .into_storage_result()?;
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &storage::Key) -> storage_api::Result<()> {
+        self.wl_storage
+            .write_log
+            .delete(key)
+            .into_storage_result()?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -564,8 +725,127 @@ mod tests {
 
     use super::*;
     use crate::ledger::storage::testing::TestWlStorage;
-    use crate::types::address::InternalAddress;
-    use crate::types::storage::DbKeySeg;
+    use crate::types::address::{self, InternalAddress};
+    use crate::types::storage::{DbKeySeg, KeySeg};
+
+    /// Test that the sizes of the values written and deleted through the
+    /// write log are added to the storage usage of their owner on commit
+    #[test]
+    fn test_storage_usage() {
+        let mut s = TestWlStorage::default();
+        let owner = address::testing::established_address_1();
+        let key = storage::Key::from(owner.to_db_key())
+            .push(&"value".to_owned())
+            .unwrap();
+        let usage = |s: &TestWlStorage| {
+            (
+                s.storage.read_storage_usage(Some(&owner)).unwrap(),
+                s.storage.read_storage_usage(None).unwrap(),
+            )
+        };
+
+        s.write_bytes(&key, [0_u8; 10]).unwrap();
+        s.commit_block().unwrap();
+        assert_eq!(usage(&s), (10, 10));
+
+        s.write_bytes(&key, [0_u8; 4]).unwrap();
+        s.commit_block().unwrap();
+        assert_eq!(usage(&s), (4, 4));
+
+        s.delete(&key).unwrap();
+        s.commit_block().unwrap();
+        assert_eq!(usage(&s), (0, 0));
+    }
+
+    /// Test that the writes of a tx-scoped change are kept only if it succeeds
+    #[test]
+    fn test_apply_tx_scoped() {
+        let mut s = TestWlStorage::default();
+        let key = |name: &str| {
+            storage::Key::from(
+                address::testing::established_address_1().to_db_key(),
+            )
+            .push(&name.to_owned())
+            .unwrap()
+        };
+        s.write(&key("block"), 1_u64).unwrap();
+
+        let result = s.apply_tx_scoped(|scoped| {
+            scoped.write(&key("failed"), 2_u64)?;
+            scoped.delete(&key("block"))?;
+            assert!(!scoped.has_key(&key("block"))?);
+            Err::<(), _>(storage_api::Error::new_const("failed"))
+        });
+        assert!(result.is_err());
+        assert!(!s.has_key(&key("failed")).unwrap());
+        assert_eq!(s.read::<u64>(&key("block")).unwrap(), Some(1));
+
+        s.apply_tx_scoped(|scoped| scoped.write(&key("applied"), 3_u64))
+            .unwrap();
+        assert_eq!(s.read::<u64>(&key("applied")).unwrap(), Some(3));
+        s.commit_block().unwrap();
+        assert_eq!(s.read::<u64>(&key("applied")).unwrap(), Some(3));
+    }
+
+    /// Test that the indexed tx results are pruned once they are older than
+    /// the `storage_read_past_height_limit`
+    #[test]
+    fn test_prune_tx_results() {
+        let mut s = TestWlStorage::default();
+        s.storage.storage_read_past_height_limit = Some(1);
+        let hash = Hash::sha256(b"tx");
+
+        s.storage.block.height = BlockHeight(1);
+        s.storage.indexed_tx_results.insert(hash, vec![1_u8]);
+        s.commit_block().unwrap();
+        assert_eq!(s.storage.read_tx_result(&hash).unwrap(), Some(vec![1_u8]));
+
+        s.storage.block.height = BlockHeight(2);
+        s.commit_block().unwrap();
+        assert_eq!(s.storage.read_tx_result(&hash).unwrap(), Some(vec![1_u8]));
+
+        s.storage.block.height = BlockHeight(3);
+        s.commit_block().unwrap();
+        assert_eq!(s.storage.read_tx_result(&hash).unwrap(), None);
+    }
+
+    /// Test that reading into a buffer gives the same values as reading them,
+    /// from the write log and from the storage
+    #[test]
+    fn test_read_bytes_into() {
+        let mut s = TestWlStorage::default();
+        let key = |name: &str| {
+            storage::Key::from(
+                address::testing::established_address_1().to_db_key(),
+            )
+            .push(&name.to_owned())
+            .unwrap()
+        };
+        s.write(&key("committed"), vec![1_u8; 100]).unwrap();
+        s.commit_block().unwrap();
+        s.write(&key("pending"), vec![2_u8; 10]).unwrap();
+
+        let mut buf = vec![9_u8; 1000];
+        for name in ["committed", "pending"] {
+            assert!(s.read_bytes_into(&key(name), &mut buf).unwrap());
+            assert_eq!(Some(&buf), s.read_bytes(&key(name)).unwrap().as_ref());
+            assert_eq!(
+                s.read_with_buf::<Vec<u8>>(&key(name), &mut buf).unwrap(),
+                s.read::<Vec<u8>>(&key(name)).unwrap()
+            );
+        }
+        // A missing value leaves the buffer as it was
+        let read = buf.clone();
+        assert!(!s.read_bytes_into(&key("missing"), &mut buf).unwrap());
+        assert_eq!(buf, read);
+        s.delete(&key("pending")).unwrap();
+        assert!(!s.read_bytes_into(&key("pending"), &mut buf).unwrap());
+        assert_eq!(
+            s.read_with_buf::<Vec<u8>>(&key("pending"), &mut buf)
+                .unwrap(),
+            None
+        );
+    }
 
     proptest! {
         // Generate arb valid input for `test_prefix_iters_aux`
@@ -11,16 +11,19 @@ use crate::ledger::gas::{
     MEMORY_ACCESS_GAS_PER_BYTE, STORAGE_WRITE_GAS_PER_BYTE,
 };
 use crate::ledger::replay_protection::{
-    get_replay_protection_all_subkey, get_replay_protection_last_subkey,
+    get_replay_protection_all_subkey, get_replay_protection_expiration_subkey,
+    get_replay_protection_last_subkey,
 };
 use crate::ledger::storage::traits::StorageHasher;
 use crate::ledger::storage::Storage;
-use crate::types::address::{Address, EstablishedAddressGen, InternalAddress};
+use crate::types::address::{self, Address, InternalAddress};
 use crate::types::hash::Hash;
 use crate::types::ibc::IbcEvent;
 use crate::types::storage;
+use crate::types::time::DateTimeUtc;
 use crate::types::token::{
-    is_any_minted_balance_key, is_any_minter_key, is_any_token_balance_key,
+    is_any_allowance_key, is_any_minted_balance_key, is_any_minter_key,
+    is_any_token_balance_key,
 };
 
 #[allow(missing_docs)]
@@ -47,7 +50,7 @@ pub enum Error {
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// A storage modification
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum StorageModification {
     /// Write a new value
     Write {
@@ -80,13 +83,21 @@ enum ReProtStorageModification {
     Delete,
     /// Finalize an entry
     Finalize,
+    /// Prune an expired entry, together with its expiration index
+    Prune(DateTimeUtc),
 }
 
-/// The write log storage
+/// The write log storage. It is layered over the storage, so that every tx of
+/// a block sees the state in which the txs before it in the block left the
+/// storage:
+///
+/// - the pre-block state is the committed storage, before any tx of the block;
+/// - the pre-tx state is the pre-block state with the modifications of the
+///   txs accepted before the current one in the block (`block_write_log`);
+/// - the post-tx state is the pre-tx state with the modifications of the
+///   current tx (`tx_precommit_write_log`, then `tx_write_log`).
 #[derive(Debug, Clone)]
 pub struct WriteLog {
-    /// The generator of established addresses
-    address_gen: Option<EstablishedAddressGen>,
     /// All the storage modification accepted by validity predicates are stored
     /// in block write-log, before being committed to the storage
     block_write_log: HashMap<storage::Key, StorageModification>,
@@ -106,6 +117,10 @@ pub struct WriteLog {
     /// Storage modifications for the replay protection storage, always
     /// committed regardless of the result of the transaction
     replay_protection: HashMap<Hash, ReProtStorageModification>,
+    /// Expiration of the transactions' hashes written to the replay
+    /// protection storage, used to prune them once they can no longer be
+    /// replayed
+    replay_protection_expirations: HashMap<Hash, DateTimeUtc>,
 }
 
 /// Write log prefix iterator
@@ -127,12 +142,12 @@ impl Iterator for PrefixIter {
 impl Default for WriteLog {
     fn default() -> Self {
         Self {
-            address_gen: None,
             block_write_log: HashMap::with_capacity(100_000),
             tx_write_log: HashMap::with_capacity(100),
             tx_precommit_write_log: HashMap::with_capacity(100),
             ibc_events: BTreeSet::new(),
             replay_protection: HashMap::with_capacity(1_000),
+            replay_protection_expirations: HashMap::with_capacity(1_000),
         }
     }
 }
@@ -356,18 +371,23 @@ impl WriteLog {
         Ok(())
     }
 
-    /// Initialize a new account and return the gas cost.
+    /// Initialize a new account and return its address and the gas cost. The
+    /// address is derived from the given entropy source and the number of
+    /// accounts already initialized by the current transaction.
     pub fn init_account(
         &mut self,
-        storage_address_gen: &EstablishedAddressGen,
+        entropy_source: &[u8],
         vp_code_hash: Hash,
     ) -> (Address, u64) {
-        // If we've previously generated a new account, we use the local copy of
-        // the generator. Otherwise, we create a new copy from the storage
-        let address_gen =
-            self.address_gen.get_or_insert(storage_address_gen.clone());
-        let addr =
-            address_gen.generate_address("TODO more randomness".as_bytes());
+        let nonce = self
+            .tx_write_log
+            .values()
+            .chain(self.tx_precommit_write_log.values())
+            .filter(|modification| {
+                matches!(modification, StorageModification::InitAccount { .. })
+            })
+            .count() as u64;
+        let addr = address::derive_established_address(entropy_source, nonce);
         let key = storage::Key::validity_predicate(&addr);
         let gas = (key.len() + vp_code_hash.len()) as u64
             * STORAGE_WRITE_GAS_PER_BYTE;
@@ -564,14 +584,37 @@ impl WriteLog {
                         )
                         .map_err(Error::StorageError)?
                 }
+                ReProtStorageModification::Prune(expiration) => {
+                    storage
+                        .delete_replay_protection_entry(
+                            batch,
+                            &get_replay_protection_all_subkey(hash),
+                        )
+                        .map_err(Error::StorageError)?;
+                    storage
+                        .delete_replay_protection_entry(
+                            batch,
+                            &get_replay_protection_expiration_subkey(
+                                expiration, hash,
+                            ),
+                        )
+                        .map_err(Error::StorageError)?
+                }
             }
         }
 
-        if let Some(address_gen) = self.address_gen.take() {
-            storage.address_gen = address_gen
+        for (hash, expiration) in self.replay_protection_expirations.iter() {
+            storage
+                .write_replay_protection_entry(
+                    batch,
+                    &get_replay_protection_expiration_subkey(expiration, hash),
+                )
+                .map_err(Error::StorageError)?
         }
+
         self.block_write_log.clear();
         self.replay_protection.clear();
+        self.replay_protection_expirations.clear();
         Ok(())
     }
 
@@ -604,6 +647,15 @@ impl WriteLog {
                 verifiers
                     .insert(Address::Internal(InternalAddress::Multitoken));
                 verifiers.insert(owner.clone());
+            } else if let Some([_token, owner, spender]) =
+                is_any_allowance_key(key)
+            {
+                // for allowance keys, trigger Multitoken VP and the VPs of
+                // both the owner and the spender
+                verifiers
+                    .insert(Address::Internal(InternalAddress::Multitoken));
+                verifiers.insert(owner.clone());
+                verifiers.insert(spender.clone());
             } else if is_any_minted_balance_key(key).is_some()
                 || is_any_minter_key(key).is_some()
             {
@@ -651,12 +703,14 @@ impl WriteLog {
     pub fn iter_prefix_post(&self, prefix: &storage::Key) -> PrefixIter {
         let mut matches = BTreeMap::new();
 
-        for (key, modification) in &self.block_write_log {
-            if key.split_prefix(prefix).is_some() {
-                matches.insert(key.to_string(), modification.clone());
-            }
-        }
-        for (key, modification) in &self.tx_write_log {
+        // The later layers override the earlier ones, in the same order as
+        // in `read`
+        for (key, modification) in self
+            .block_write_log
+            .iter()
+            .chain(&self.tx_precommit_write_log)
+            .chain(&self.tx_write_log)
+        {
             if key.split_prefix(prefix).is_some() {
                 matches.insert(key.to_string(), modification.clone());
             }
@@ -671,7 +725,13 @@ impl WriteLog {
     pub fn has_replay_protection_entry(&self, hash: &Hash) -> Option<bool> {
         self.replay_protection
             .get(hash)
-            .map(|action| !matches!(action, ReProtStorageModification::Delete))
+            .map(|action| {
+                !matches!(
+                    action,
+                    ReProtStorageModification::Delete
+                        | ReProtStorageModification::Prune(_)
+                )
+            })
     }
 
     /// Write the transaction hash
@@ -691,6 +751,16 @@ impl WriteLog {
         Ok(())
     }
 
+    /// Record the expiration of a transaction hash written to storage, so
+    /// that the hash can be pruned once the transaction has expired
+    pub fn write_tx_hash_expiration(
+        &mut self,
+        hash: Hash,
+        expiration: DateTimeUtc,
+    ) {
+        self.replay_protection_expirations.insert(hash, expiration);
+    }
+
     /// Remove the transaction hash
     pub(crate) fn delete_tx_hash(&mut self, hash: Hash) -> Result<()> {
         match self
@@ -730,6 +800,28 @@ impl WriteLog {
 
         Ok(())
     }
+
+    /// Prune the hash of an expired transaction from the list of all blocks.
+    /// The hash must have been finalized in a previous block.
+    pub fn prune_tx_hash(
+        &mut self,
+        hash: Hash,
+        expiration: DateTimeUtc,
+    ) -> Result<()> {
+        if self
+            .replay_protection
+            .insert(hash, ReProtStorageModification::Prune(expiration))
+            .is_some()
+        {
+            // Cannot prune an hash if other requests have already been
+            // committed for the same hash
+            return Err(Error::ReplayProtection(format!(
+                "Requested a prune on hash {hash} over a previous request"
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -822,12 +914,12 @@ mod tests {
     #[test]
     fn test_crud_account() {
         let mut write_log = WriteLog::default();
-        let address_gen = EstablishedAddressGen::new("test");
 
         // init
         let init_vp = "initialized".as_bytes().to_vec();
         let vp_hash = Hash::sha256(init_vp);
-        let (addr, gas) = write_log.init_account(&address_gen, vp_hash);
+        let (addr, gas) = write_log.init_account(b"test", vp_hash);
+        assert_eq!(addr, address::derive_established_address(b"test", 0));
         let vp_key = storage::Key::validity_predicate(&addr);
         assert_eq!(
             gas,
@@ -851,16 +943,19 @@ mod tests {
         let (_changed_keys, init_accounts) = write_log.get_partitioned_keys();
         assert!(init_accounts.contains(&&addr));
         assert_eq!(init_accounts.len(), 1);
+
+        // another account initialized by the same tx gets a different address
+        let (addr2, _) = write_log.init_account(b"test", vp_hash);
+        assert_eq!(addr2, address::derive_established_address(b"test", 1));
     }
 
     #[test]
     fn test_update_initialized_account_should_fail() {
         let mut write_log = WriteLog::default();
-        let address_gen = EstablishedAddressGen::new("test");
 
         let init_vp = "initialized".as_bytes().to_vec();
         let vp_hash = Hash::sha256(init_vp);
-        let (addr, _) = write_log.init_account(&address_gen, vp_hash);
+        let (addr, _) = write_log.init_account(b"test", vp_hash);
         let vp_key = storage::Key::validity_predicate(&addr);
 
         // update should fail
@@ -875,11 +970,10 @@ mod tests {
     #[test]
     fn test_delete_initialized_account_should_fail() {
         let mut write_log = WriteLog::default();
-        let address_gen = EstablishedAddressGen::new("test");
 
         let init_vp = "initialized".as_bytes().to_vec();
         let vp_hash = Hash::sha256(init_vp);
-        let (addr, _) = write_log.init_account(&address_gen, vp_hash);
+        let (addr, _) = write_log.init_account(b"test", vp_hash);
         let vp_key = storage::Key::validity_predicate(&addr);
 
         // delete should fail
@@ -904,7 +998,6 @@ mod tests {
             crate::ledger::storage::testing::TestStorage::default();
         let mut write_log = WriteLog::default();
         let mut batch = crate::ledger::storage::testing::TestStorage::batch();
-        let address_gen = EstablishedAddressGen::new("test");
 
         let key1 =
             storage::Key::parse("key1").expect("cannot parse the key string");
@@ -917,7 +1010,7 @@ mod tests {
 
         // initialize an account
         let vp1 = Hash::sha256("vp1".as_bytes());
-        let (addr1, _) = write_log.init_account(&address_gen, vp1);
+        let (addr1, _) = write_log.init_account(b"test", vp1);
         write_log.commit_tx();
 
         // write values
@@ -959,6 +1052,66 @@ mod tests {
         assert_eq!(value, None);
     }
 
+    #[test]
+    fn test_inter_tx_visibility() {
+        let mut write_log = WriteLog::default();
+        let prefix =
+            storage::Key::parse("prefix").expect("cannot parse the key string");
+        let key1 = prefix.push(&"key1".to_string()).unwrap();
+        let key2 = prefix.push(&"key2".to_string()).unwrap();
+        let val1 = "val1".as_bytes().to_vec();
+        let val2 = "val2".as_bytes().to_vec();
+        let val3 = "val3".as_bytes().to_vec();
+
+        // A first tx of the block
+        write_log.write(&key1, val1.clone()).unwrap();
+        write_log.commit_tx();
+
+        // The current tx, with a precommitted part
+        write_log.write(&key1, val2.clone()).unwrap();
+        write_log.precommit_tx();
+        write_log.write(&key2, val3.clone()).unwrap();
+
+        // The pre-tx state has the modifications of the first tx only
+        let (value, _) = write_log.read_pre(&key1);
+        assert_eq!(
+            value,
+            Some(&StorageModification::Write {
+                value: val1.clone()
+            })
+        );
+        let (value, _) = write_log.read_pre(&key2);
+        assert!(value.is_none());
+        let pre: Vec<_> = write_log.iter_prefix_pre(&prefix).collect();
+        assert_eq!(
+            pre,
+            vec![(
+                key1.to_string(),
+                StorageModification::Write { value: val1 }
+            )]
+        );
+
+        // The post-tx state has the precommitted modifications too and the
+        // same values are read and iterated
+        let post: Vec<_> = write_log.iter_prefix_post(&prefix).collect();
+        assert_eq!(
+            post,
+            vec![
+                (
+                    key1.to_string(),
+                    StorageModification::Write {
+                        value: val2.clone()
+                    }
+                ),
+                (key2.to_string(), StorageModification::Write { value: val3 }),
+            ]
+        );
+        for (key, modification) in post {
+            let key = storage::Key::parse(key).unwrap();
+            assert_eq!(write_log.read(&key).0, Some(&modification));
+        }
+    }
+
     #[test]
     fn test_replay_protection_commit() {
         let mut storage =
@@ -1051,6 +1204,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_replay_protection_prune() {
+        let mut storage =
+            crate::ledger::storage::testing::TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let mut batch = crate::ledger::storage::testing::TestStorage::batch();
+        let hash = Hash::sha256("tx1".as_bytes());
+        let expiration = DateTimeUtc::now();
+
+        // write a replay protection key with an expiration
+        write_log.write_tx_hash(hash).unwrap();
+        write_log.write_tx_hash_expiration(hash, expiration);
+        write_log
+            .commit_block(&mut storage, &mut batch)
+            .expect("commit failed");
+        assert!(write_log.replay_protection_expirations.is_empty());
+        let expirations: Vec<_> =
+            storage.iter_replay_protection_expirations().collect();
+        assert_eq!(expirations.len(), 1);
+        assert_eq!(expirations[0].1, hash);
+
+        // finalize it
+        write_log.finalize_tx_hash(hash).unwrap();
+        write_log
+            .commit_block(&mut storage, &mut batch)
+            .expect("commit failed");
+        assert!(storage.has_replay_protection_entry(&hash).unwrap());
+
+        // prune it
+        write_log.prune_tx_hash(hash, expirations[0].0).unwrap();
+        assert_eq!(write_log.has_replay_protection_entry(&hash), Some(false));
+        write_log
+            .commit_block(&mut storage, &mut batch)
+            .expect("commit failed");
+        assert!(!storage.has_replay_protection_entry(&hash).unwrap());
+        assert_eq!(storage.iter_replay_protection_expirations().count(), 0);
+    }
+
     prop_compose! {
         fn arb_verifiers_changed_key_tx_all_key()
             (verifiers_from_tx in testing::arb_verifiers_from_tx())
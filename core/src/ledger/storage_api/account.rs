@@ -1,10 +1,17 @@
 //! Cryptographic signature keys storage API
 
+use std::collections::BTreeSet;
+
 use super::*;
-use crate::types::account::AccountPublicKeysMap;
+use crate::types::account::{
+    AccountPolicy, AccountPublicKeysMap, PendingVpUpdate, SpendingRecord,
+    VestingSchedule, VpUpdateTimelock,
+};
 use crate::types::address::Address;
+use crate::types::hash::Hash;
 use crate::types::key::*;
 use crate::types::storage::Key;
+use crate::types::token;
 
 /// Init the subspace of a new account
 pub fn init_account_storage<S>(
@@ -105,3 +112,174 @@ where
     }
     Ok(())
 }
+
+/// Get the authorization policy of an account
+pub fn policy<S>(storage: &S, owner: &Address) -> Result<Option<AccountPolicy>>
+where
+    S: StorageRead,
+{
+    storage.read(&policy_key(owner))
+}
+
+/// Set the authorization policy of an account
+pub fn write_policy<S>(
+    storage: &mut S,
+    owner: &Address,
+    policy: &AccountPolicy,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    storage.write(&policy_key(owner), policy)
+}
+
+/// Get the record of the amount of a token spent by an account
+pub fn spending<S>(
+    storage: &S,
+    owner: &Address,
+    token: &Address,
+) -> Result<Option<SpendingRecord>>
+where
+    S: StorageRead,
+{
+    storage.read(&spending_key(owner, token))
+}
+
+/// Add a debit of a token to the spending record of an account, if its policy
+/// sets a spend limit for the token
+pub fn record_spending<S>(
+    storage: &mut S,
+    owner: &Address,
+    token: &Address,
+    amount: token::Amount,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    update_spending_record(storage, owner, token, amount, token::Amount::zero())
+}
+
+/// Add a credit of a token to the spending record of an account, if its
+/// policy sets a spend limit for the token
+pub fn record_receipt<S>(
+    storage: &mut S,
+    owner: &Address,
+    token: &Address,
+    amount: token::Amount,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    update_spending_record(storage, owner, token, token::Amount::zero(), amount)
+}
+
+fn update_spending_record<S>(
+    storage: &mut S,
+    owner: &Address,
+    token: &Address,
+    debit: token::Amount,
+    credit: token::Amount,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let has_limit = policy(storage, owner)?
+        .map(|policy| policy.spend_limits.contains_key(token))
+        .unwrap_or_default();
+    if !has_limit {
+        return Ok(());
+    }
+    let epoch = storage.get_block_epoch()?;
+    let record = spending(storage, owner, token)?
+        .filter(|record| record.epoch == epoch)
+        .unwrap_or_default();
+    let overflow = || Error::new_const("Spending record overflow");
+    let spent = record.spent.checked_add(debit).ok_or_else(overflow)?;
+    let received = record.received.checked_add(credit).ok_or_else(overflow)?;
+    storage.write(
+        &spending_key(owner, token),
+        SpendingRecord {
+            epoch,
+            spent,
+            received,
+        },
+    )
+}
+
+/// Get the timelock on the VP updates of an account
+pub fn vp_timelock<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<Option<VpUpdateTimelock>>
+where
+    S: StorageRead,
+{
+    storage.read(&vp_timelock_key(owner))
+}
+
+/// Get the VP update scheduled by an account under its timelock
+pub fn pending_vp_update<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<Option<PendingVpUpdate>>
+where
+    S: StorageRead,
+{
+    storage.read(&pending_vp_key(owner))
+}
+
+/// Get the tx code hashes allowed to operate on an account, if it restricts
+/// them
+pub fn allowed_txs<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<Option<BTreeSet<Hash>>>
+where
+    S: StorageRead,
+{
+    storage.read(&allowed_txs_key(owner))
+}
+
+/// Get the vesting schedule of an account
+pub fn vesting_schedule<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<Option<VestingSchedule>>
+where
+    S: StorageRead,
+{
+    storage.read(&vesting_key(owner))
+}
+
+/// Set the vesting schedule of an account. The schedule must be checked with
+/// [`VestingSchedule::is_valid`] first.
+pub fn write_vesting_schedule<S>(
+    storage: &mut S,
+    owner: &Address,
+    schedule: &VestingSchedule,
+) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    storage.write(&vesting_key(owner), schedule)
+}
+
+/// Get the next sequence number expected in a wrapper tx paid by an account
+pub fn sequence<S>(storage: &S, owner: &Address) -> Result<u64>
+where
+    S: StorageRead,
+{
+    Ok(storage.read(&sequence_key(owner))?.unwrap_or_default())
+}
+
+/// Increment the next sequence number expected in a wrapper tx paid by an
+/// account
+pub fn increment_sequence<S>(storage: &mut S, owner: &Address) -> Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let next = sequence(storage, owner)?
+        .checked_add(1)
+        .ok_or_else(|| Error::new_const("Sequence number overflow"))?;
+    storage.write(&sequence_key(owner), next)
+}
@@ -28,6 +28,11 @@ pub const DATA_SUBKEY: &str = "data";
 /// In the [`LazyMap`], the type of key `K` can be anything that implements
 /// [`storage::KeySeg`] and this trait is used to turn the keys into key
 /// segments.
+///
+/// The map is iterated in the order of the storage keys of its elements. For
+/// the keys that implement [`storage::OrderedKeySeg`], this is the order of
+/// the keys `K`, which is guaranteed and consensus-critical. For the other
+/// keys, e.g. a [`String`], it is the order of their raw key segments.
 #[derive(Debug)]
 pub struct LazyMap<K, V, SON = super::Simple> {
     key: storage::Key,
@@ -465,7 +470,8 @@ where
         self.key.push(&DATA_SUBKEY.to_owned()).unwrap()
     }
 
-    /// Get the sub-key of a given element
+    /// Get the sub-key of a given element. The sub-keys of the
+    /// [`storage::OrderedKeySeg`] keys are in the order of the keys.
     pub fn get_data_key(&self, key: &K) -> storage::Key {
         let key_str = key.to_db_key();
         self.get_data_prefix().push(&key_str).unwrap()
@@ -541,6 +547,28 @@ where
         }))
     }
 
+    /// Collect all the key-value elements of the state prior to the current
+    /// tx, i.e. without the tx's own writes. The elements are read eagerly,
+    /// because the VP's view of the prior state only lives in this call.
+    pub fn iter_pre<'view, ENV>(
+        &self,
+        ctx: &'view ENV,
+    ) -> Result<
+        impl Iterator<
+            Item = (
+                <Self as LazyCollection>::SubKey,
+                <Self as LazyCollection>::Value,
+            ),
+        >,
+    >
+    where
+        ENV: VpEnv<'view>,
+    {
+        let pre = ctx.pre();
+        let elements = self.iter(&pre)?.collect::<Result<Vec<_>>>()?;
+        Ok(elements.into_iter())
+    }
+
     /// Returns whether the map contains no elements.
     pub fn is_empty<S>(&self, storage: &S) -> Result<bool>
     where
@@ -661,6 +689,9 @@ where
     /// is `Result<(K, V)>`, because iterator's call to `next` may fail with
     /// e.g. out of gas or data decoding error.
     ///
+    /// The elements are visited in the order of their storage keys, which is
+    /// the order of the keys if they implement [`storage::OrderedKeySeg`].
+    ///
     /// Note that this function shouldn't be used in transactions and VPs code
     /// on unbounded maps to avoid gas usage increasing with the length of the
     /// map.
@@ -680,6 +711,21 @@ where
         }))
     }
 
+    /// Collect all the key-value elements of the state prior to the current
+    /// tx, i.e. without the tx's own writes. The elements are read eagerly,
+    /// because the VP's view of the prior state only lives in this call.
+    pub fn iter_pre<'view, ENV>(
+        &self,
+        ctx: &'view ENV,
+    ) -> Result<impl Iterator<Item = (K, V)>>
+    where
+        ENV: VpEnv<'view>,
+    {
+        let pre = ctx.pre();
+        let elements = self.iter(&pre)?.collect::<Result<Vec<_>>>()?;
+        Ok(elements.into_iter())
+    }
+
     // /// Collect the lazy map into an eager map
     // pub fn collect<M, S>(&self, storage: &S) -> Result<M>
     // where
@@ -720,6 +766,7 @@ mod test {
     use super::*;
     use crate::ledger::storage::testing::TestWlStorage;
     use crate::types::address::{self, Address};
+    use crate::types::storage::LengthPrefixedString;
 
     #[test]
     fn test_lazy_map_basics() -> storage_api::Result<()> {
@@ -1119,4 +1166,43 @@ mod test {
         assert_eq!(exp_simple, simple_eager);
         assert_eq!(exp_nested, nested_eager);
     }
+
+    /// Test that the maps with ordered keys are iterated in the order of the
+    /// keys, also when the elements have nested collections
+    #[test]
+    fn test_lazy_map_iter_order() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+
+        let key = storage::Key::parse("test").unwrap();
+        let ints = LazyMap::<i64, ()>::open(key);
+        let mut keys = vec![i64::MAX, 1000, 1, 0, -1, -1000, i64::MIN];
+        for key in &keys {
+            ints.insert(&mut storage, *key, ())?;
+        }
+        keys.sort();
+        let iter_keys = ints
+            .iter(&storage)?
+            .map(|res| res.map(|(key, _)| key))
+            .collect::<storage_api::Result<Vec<_>>>()?;
+        assert_eq!(iter_keys, keys);
+
+        let key = storage::Key::parse("test_nested").unwrap();
+        let nested =
+            NestedMap::<LengthPrefixedString, LazyMap<u32, u32>>::open(key);
+        let mut keys: Vec<LengthPrefixedString> = ["a-", "ab", "a", "", "b"]
+            .into_iter()
+            .map(|key| LengthPrefixedString(key.to_string()))
+            .collect();
+        for key in &keys {
+            nested.at(key).insert(&mut storage, 0, 0)?;
+        }
+        keys.sort();
+        let iter_keys = nested
+            .iter(&storage)?
+            .map(|res| res.map(|(NestedSubKey::Data { key, .. }, _)| key))
+            .collect::<storage_api::Result<Vec<_>>>()?;
+        assert_eq!(iter_keys, keys);
+
+        Ok(())
+    }
 }
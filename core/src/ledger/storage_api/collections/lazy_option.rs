@@ -0,0 +1,108 @@
+//! Lazy optional value.
+
+use std::marker::PhantomData;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::super::Result;
+use crate::ledger::storage_api::{StorageRead, StorageWrite};
+use crate::types::storage;
+
+/// A lazy optional value.
+///
+/// This can be used as an alternative to `std::option::Option`. The value
+/// doesn't reside in memory but is instead read and written to the storage
+/// `key` used to construct it. Unlike the lazy collections, it has no
+/// sub-keys, so a change of the value is a change of its key.
+#[derive(Debug)]
+pub struct LazyOption<T> {
+    key: storage::Key,
+    phantom: PhantomData<T>,
+}
+
+impl<T> LazyOption<T>
+where
+    T: BorshSerialize + BorshDeserialize + 'static,
+{
+    /// Create or use an existing value with the given storage `key`.
+    pub fn open(key: storage::Key) -> Self {
+        Self {
+            key,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The storage key of the value
+    pub fn key(&self) -> &storage::Key {
+        &self.key
+    }
+
+    /// Read the value, if any.
+    pub fn get<S>(&self, storage: &S) -> Result<Option<T>>
+    where
+        S: StorageRead,
+    {
+        storage.read(&self.key)
+    }
+
+    /// Returns whether the value is set.
+    pub fn is_some<S>(&self, storage: &S) -> Result<bool>
+    where
+        S: StorageRead,
+    {
+        storage.has_key(&self.key)
+    }
+
+    /// Set the value, overriding the previous one, if any.
+    pub fn set<S>(&self, storage: &mut S, val: T) -> Result<()>
+    where
+        S: StorageWrite + StorageRead,
+    {
+        storage.write(&self.key, val)
+    }
+
+    /// Remove the value, returning the previous one, if any.
+    pub fn remove<S>(&self, storage: &mut S) -> Result<Option<T>>
+    where
+        S: StorageWrite + StorageRead,
+    {
+        let previous = self.get(storage)?;
+        if previous.is_some() {
+            storage.delete(&self.key)?;
+        }
+        Ok(previous)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+    use crate::ledger::storage_api;
+
+    #[test]
+    fn test_lazy_option_basics() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+
+        let key = storage::Key::parse("test").unwrap();
+        let lazy_option = LazyOption::<u64>::open(key);
+
+        // The value should be empty at first
+        assert!(!lazy_option.is_some(&storage)?);
+        assert_eq!(lazy_option.get(&storage)?, None);
+        assert_eq!(lazy_option.remove(&mut storage)?, None);
+
+        lazy_option.set(&mut storage, 1)?;
+        assert!(lazy_option.is_some(&storage)?);
+        assert_eq!(lazy_option.get(&storage)?, Some(1));
+
+        lazy_option.set(&mut storage, 2)?;
+        assert_eq!(lazy_option.get(&storage)?, Some(2));
+
+        assert_eq!(lazy_option.remove(&mut storage)?, Some(2));
+        assert!(!lazy_option.is_some(&storage)?);
+        assert_eq!(lazy_option.get(&storage)?, None);
+
+        Ok(())
+    }
+}
@@ -243,6 +243,21 @@ where
             Ok(key)
         }))
     }
+
+    /// Collect all the keys of the state prior to the current tx, i.e.
+    /// without the tx's own writes. The keys are read eagerly, because the
+    /// VP's view of the prior state only lives in this call.
+    pub fn iter_pre<'view, ENV>(
+        &self,
+        ctx: &'view ENV,
+    ) -> Result<impl Iterator<Item = K>>
+    where
+        ENV: VpEnv<'view>,
+    {
+        let pre = ctx.pre();
+        let keys = self.iter(&pre)?.collect::<Result<Vec<_>>>()?;
+        Ok(keys.into_iter())
+    }
 }
 
 /// Determine what action was taken from the pre/post state
@@ -504,6 +504,21 @@ where
             Ok(val)
         }))
     }
+
+    /// Collect all the elements of the state prior to the current tx, i.e.
+    /// without the tx's own writes. The elements are read eagerly, because
+    /// the VP's view of the prior state only lives in this call.
+    pub fn iter_pre<'view, ENV>(
+        &self,
+        ctx: &'view ENV,
+    ) -> Result<impl Iterator<Item = T>>
+    where
+        ENV: VpEnv<'view>,
+    {
+        let pre = ctx.pre();
+        let elements = self.iter(&pre)?.collect::<Result<Vec<_>>>()?;
+        Ok(elements.into_iter())
+    }
 }
 
 #[cfg(test)]
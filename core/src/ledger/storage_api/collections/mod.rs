@@ -6,6 +6,13 @@
 //! iteration over both of the states that also have to be decoded), VPs will
 //! just receive the storage sub-keys that have experienced changes without
 //! having to check any of the unchanged elements.
+//!
+//! The iterators of the collections observe the state of the given storage
+//! when they are created. Inside a tx, this is the tx's own pending writes in
+//! the write log merged over the storage, so deleted elements are skipped and
+//! overridden ones have their new value. The writes made while iterating are
+//! not observed by the iterator. VPs can use `iter_pre` to iterate the state
+//! prior to the tx, without its writes.
 
 use std::fmt::Debug;
 
@@ -14,12 +21,16 @@ use derivative::Derivative;
 use thiserror::Error;
 
 pub mod lazy_map;
+pub mod lazy_option;
 pub mod lazy_set;
 pub mod lazy_vec;
+pub mod schema;
 
 pub use lazy_map::LazyMap;
+pub use lazy_option::LazyOption;
 pub use lazy_set::LazySet;
 pub use lazy_vec::LazyVec;
+pub use schema::SchemaRegistry;
 
 use crate::ledger::storage_api;
 use crate::ledger::vp_env::VpEnv;
@@ -150,3 +161,55 @@ pub struct ValidationBuilder<Change> {
     /// The accumulator of found changes under the vector
     pub changes: Vec<Change>,
 }
+
+#[cfg(test)]
+mod test {
+    use namada_macros::StorageLayout;
+
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+    use crate::types::address::{self, Address};
+    use crate::types::storage::{DbKeySeg, Key};
+
+    /// A storage layout of a module rooted at an address
+    #[derive(StorageLayout)]
+    struct Layout {
+        counter: LazyOption<u64>,
+        balances: LazyMap<Address, u64>,
+        members: LazySet<Address>,
+    }
+
+    /// Test that the sub-spaces of a derived storage layout are rooted at the
+    /// address under the field names
+    #[test]
+    fn test_storage_layout() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        let owner = address::testing::established_address_1();
+        let layout = Layout::open(&owner);
+
+        assert_eq!(Layout::KEYS, &["counter", "balances", "members"]);
+        assert_eq!(
+            Layout::layout(),
+            &[
+                ("counter", "LazyOption<u64>"),
+                ("balances", "LazyMap<Address, u64>"),
+                ("members", "LazySet<Address>"),
+            ]
+        );
+        let counter_key = Key::from(DbKeySeg::AddressSeg(owner.clone()))
+            .push(&Layout::COUNTER_KEY.to_owned())
+            .unwrap();
+        assert_eq!(layout.counter.key(), &counter_key);
+
+        layout.counter.set(&mut storage, 1)?;
+        layout.balances.insert(&mut storage, owner.clone(), 2)?;
+        layout.members.insert(&mut storage, owner.clone())?;
+
+        // A layout opened again at the same address reads the same data
+        let layout = Layout::open(&owner);
+        assert_eq!(layout.counter.get(&storage)?, Some(1));
+        assert_eq!(layout.balances.get(&storage, &owner)?, Some(2));
+        assert!(layout.members.contains(&storage, &owner)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! Registry of the storage schemas of the modules.
+//!
+//! A module's schema is the set of the key segments of the sub-spaces rooted
+//! at its address, e.g. the `KEYS` of a derived `StorageLayout`. The registry
+//! lets the tools that walk the storage of several modules, like the state
+//! export, tell the keys of the known sub-spaces from the stray ones.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::types::address::Address;
+use crate::types::storage::{DbKeySeg, Key};
+
+/// The storage schemas of the registered modules, by their addresses
+#[derive(Debug, Default, Clone)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<Address, BTreeSet<&'static str>>,
+}
+
+impl SchemaRegistry {
+    /// Register the key segments of the sub-spaces rooted at the given
+    /// address. The segments are added to the ones already registered for
+    /// the address, if any.
+    pub fn register(&mut self, owner: Address, keys: &[&'static str]) {
+        self.schemas
+            .entry(owner)
+            .or_default()
+            .extend(keys.iter().copied());
+    }
+
+    /// The addresses of the registered modules
+    pub fn owners(&self) -> impl Iterator<Item = &Address> {
+        self.schemas.keys()
+    }
+
+    /// The key segments of the sub-spaces registered for the given address
+    pub fn schema(&self, owner: &Address) -> Option<&BTreeSet<&'static str>> {
+        self.schemas.get(owner)
+    }
+
+    /// Find the registered sub-space of a storage key. Returns `None` if the
+    /// key isn't rooted at a registered address or if its first segment under
+    /// the address isn't a registered sub-space.
+    pub fn sub_space(&self, key: &Key) -> Option<&'static str> {
+        match &key.segments[..] {
+            [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(segment), ..] => {
+                self.schemas.get(owner)?.get(segment.as_str()).copied()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::address;
+    use crate::types::storage::KeySeg;
+
+    /// Test that the keys are matched with the sub-spaces registered for
+    /// their address
+    #[test]
+    fn test_schema_registry() {
+        let owner = address::testing::established_address_1();
+        let other = address::testing::established_address_2();
+        let mut registry = SchemaRegistry::default();
+        registry.register(owner.clone(), &["params"]);
+        registry.register(owner.clone(), &["balances"]);
+
+        let key = |address: &Address, segment: &str| {
+            Key::from(address.to_db_key())
+                .push(&segment.to_owned())
+                .unwrap()
+        };
+        assert_eq!(registry.sub_space(&key(&owner, "params")), Some("params"));
+        assert_eq!(
+            registry.sub_space(
+                &key(&owner, "balances").push(&other.to_db_key()).unwrap()
+            ),
+            Some("balances")
+        );
+        assert_eq!(registry.sub_space(&key(&owner, "stray")), None);
+        assert_eq!(registry.sub_space(&key(&other, "params")), None);
+        assert_eq!(registry.sub_space(&Key::from(owner.to_db_key())), None);
+        assert_eq!(registry.owners().collect::<Vec<_>>(), vec![&owner]);
+        assert_eq!(registry.schema(&owner).map(BTreeSet::len), Some(2));
+    }
+}
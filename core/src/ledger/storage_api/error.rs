@@ -1,5 +1,10 @@
 //! Storage API error type, extensible with custom user errors and static string
 //! messages.
+//!
+//! The errors of the storage itself are structured with an [`ErrorCode`],
+//! which is kept when an error is passed as an `i64` across the WASM
+//! boundary, so that txs and VPs can e.g. handle a missing value rather than
+//! treating every error as fatal.
 
 use thiserror::Error;
 
@@ -7,11 +12,52 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("{0}")]
-    SimpleMessage(&'static str),
-    #[error("{0}")]
-    Custom(CustomError),
-    #[error("{0}: {1}")]
-    CustomWithMessage(&'static str, CustomError),
+    Other(OtherError),
+    #[error("No value found in storage at {0}")]
+    NotFound(String),
+    #[error("Error decoding a value from storage: {0}")]
+    Decode(String),
+    #[error("Out of gas")]
+    OutOfGas,
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+}
+
+/// The code of an [`enum@Error`], passed across the WASM boundary. The codes
+/// are negative and below `-1`, so that they are distinct from the lengths of
+/// the values read and from the codes of `HostEnvResult`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// No value at a storage key that must have one
+    NotFound = -2,
+    /// A value could not be decoded
+    Decode = -3,
+    /// The gas limit was exceeded
+    OutOfGas = -4,
+    /// An error of the storage backend
+    Backend = -5,
+    /// Any other error
+    Other = -6,
+}
+
+impl ErrorCode {
+    /// Convert the code to `i64`, which can be passed to and from wasm
+    pub fn to_i64(self) -> i64 {
+        self as _
+    }
+
+    /// Convert an `i64` received from wasm back to a code, if it's one
+    pub fn from_i64(code: i64) -> Option<Self> {
+        [
+            Self::NotFound,
+            Self::Decode,
+            Self::OutOfGas,
+            Self::Backend,
+            Self::Other,
+        ]
+        .into_iter()
+        .find(|known| known.to_i64() == code)
+    }
 }
 
 /// Result of a storage API call.
@@ -47,13 +93,67 @@ impl Error {
     where
         E: Into<Box<dyn std::error::Error + Send + Sync>>,
     {
-        Self::Custom(CustomError(error.into()))
+        Self::Other(OtherError {
+            msg: None,
+            source: Some(error.into()),
+        })
     }
 
     /// Create an [`enum@Error`] from a static message.
     #[inline]
     pub const fn new_const(msg: &'static str) -> Self {
-        Self::SimpleMessage(msg)
+        Self::Other(OtherError {
+            msg: Some(msg),
+            source: None,
+        })
+    }
+
+    /// Create an [`enum@Error`] for a missing value at the given storage key.
+    pub fn not_found(key: impl std::fmt::Display) -> Self {
+        Self::NotFound(key.to_string())
+    }
+
+    /// Create an [`enum@Error`] for a value that could not be decoded.
+    pub fn decode(error: impl std::fmt::Display) -> Self {
+        Self::Decode(error.to_string())
+    }
+
+    /// Create an [`enum@Error`] for an error of the storage backend.
+    pub fn backend(error: impl std::fmt::Display) -> Self {
+        Self::Backend(error.to_string())
+    }
+
+    /// The code of this error, kept across the WASM boundary
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::Decode(_) => ErrorCode::Decode,
+            Self::OutOfGas => ErrorCode::OutOfGas,
+            Self::Backend(_) => ErrorCode::Backend,
+            Self::Other(_) => ErrorCode::Other,
+        }
+    }
+
+    /// Re-create an error from a code received across the WASM boundary. The
+    /// message of the original error is not passed along with its code.
+    pub fn from_code(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::NotFound => Self::NotFound("a storage key".to_string()),
+            ErrorCode::Decode => {
+                Self::Decode("the value could not be decoded".to_string())
+            }
+            ErrorCode::OutOfGas => Self::OutOfGas,
+            ErrorCode::Backend => {
+                Self::Backend("the storage backend failed".to_string())
+            }
+            ErrorCode::Other => Self::new_const("A host function failed"),
+        }
+    }
+
+    /// Check if this error is for a missing value, which may be handled
+    /// rather than aborting the tx or VP
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound(_))
     }
 
     /// Wrap another [`std::error::Error`] with a static message.
@@ -61,7 +161,10 @@ impl Error {
     where
         E: Into<Box<dyn std::error::Error + Send + Sync>>,
     {
-        Self::CustomWithMessage(msg, CustomError(error.into()))
+        Self::Other(OtherError {
+            msg: Some(msg),
+            source: Some(error.into()),
+        })
     }
 
     /// Attempt to downgrade the inner error to `E` if any.
@@ -80,10 +183,9 @@ impl Error {
         E: std::error::Error + Send + Sync + 'static,
     {
         match self {
-            Self::Custom(CustomError(b))
-            | Self::CustomWithMessage(_, CustomError(b))
-                if b.is::<E>() =>
-            {
+            Self::Other(OtherError {
+                source: Some(b), ..
+            }) if b.is::<E>() => {
                 let res = b.downcast::<E>();
                 Ok(res.unwrap())
             }
@@ -92,13 +194,22 @@ impl Error {
     }
 }
 
-/// A custom error
+/// An error other than the ones of the storage itself, with a static message,
+/// a custom error or both. Its message isn't passed across the WASM boundary.
 #[derive(Debug)]
-pub struct CustomError(pub Box<dyn std::error::Error + Send + Sync>);
+pub struct OtherError {
+    msg: Option<&'static str>,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
 
-impl std::fmt::Display for CustomError {
+impl std::fmt::Display for OtherError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
+        match (self.msg, &self.source) {
+            (Some(msg), Some(source)) => write!(f, "{msg}: {source}"),
+            (Some(msg), None) => f.write_str(msg),
+            (None, Some(source)) => source.fmt(f),
+            (None, None) => f.write_str("Unknown error"),
+        }
     }
 }
 
@@ -116,3 +227,31 @@ impl<T> OptionExt<T> for Option<T> {
         self.ok_or_else(|| Error::new_const(msg))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_error_code_round_trip() {
+        let errors = [
+            Error::not_found("key"),
+            Error::decode("invalid length"),
+            Error::OutOfGas,
+            Error::backend("db closed"),
+            Error::new_const("custom"),
+        ];
+        for error in errors {
+            let code = error.code().to_i64();
+            // The codes can't be confused with a result of a host function
+            assert!(code < -1);
+            let received = Error::from_code(
+                ErrorCode::from_i64(code).expect("The code must be known"),
+            );
+            assert_eq!(received.code(), error.code());
+        }
+        assert!(Error::not_found("key").is_not_found());
+        assert!(!Error::OutOfGas.is_not_found());
+        assert_eq!(ErrorCode::from_i64(-1), None);
+    }
+}
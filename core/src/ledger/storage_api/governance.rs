@@ -6,6 +6,7 @@ use borsh::BorshDeserialize;
 
 use super::token;
 use crate::ledger::governance::parameters::GovernanceParameters;
+use crate::ledger::governance::storage::epoch_hook::EpochHook;
 use crate::ledger::governance::storage::keys as governance_keys;
 use crate::ledger::governance::storage::proposal::{
     ProposalType, StorageProposal,
@@ -163,6 +164,34 @@ pub fn get_proposal_votes<S>(
     storage: &S,
     proposal_id: u64,
 ) -> storage_api::Result<Vec<Vote>>
+where
+    S: storage_api::StorageRead,
+{
+    Ok(iter_proposal_votes(storage, proposal_id)?.collect())
+}
+
+/// Query a page of at most `limit` votes for a proposal_id, skipping the first
+/// `offset` votes. Only the votes up to the end of the page are read.
+pub fn get_proposal_votes_page<S>(
+    storage: &S,
+    proposal_id: u64,
+    offset: u64,
+    limit: u64,
+) -> storage_api::Result<Vec<Vote>>
+where
+    S: storage_api::StorageRead,
+{
+    Ok(iter_proposal_votes(storage, proposal_id)?
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect())
+}
+
+/// Iterate the votes for a proposal_id, in the order of their keys
+fn iter_proposal_votes<'a, S>(
+    storage: &'a S,
+    proposal_id: u64,
+) -> storage_api::Result<impl Iterator<Item = Vote> + 'a>
 where
     S: storage_api::StorageRead,
 {
@@ -193,12 +222,79 @@ where
             } else {
                 None
             }
-        })
-        .collect::<Vec<Vote>>();
+        });
 
     Ok(votes)
 }
 
+/// Query the ids of the proposals whose grace epoch is the given one, from the
+/// index of the committing proposals
+pub fn get_proposal_ids_by_grace_epoch<S>(
+    storage: &S,
+    grace_epoch: Epoch,
+) -> storage_api::Result<Vec<u64>>
+where
+    S: storage_api::StorageRead,
+{
+    let prefix = governance_keys::get_commiting_proposals_prefix(grace_epoch.0);
+    storage_api::iter_prefix_bytes(storage, &prefix)?
+        .filter_map(|entry| match entry {
+            Ok((key, _)) => {
+                // The prefix also matches the epochs that start with the
+                // digits of the grace epoch
+                (governance_keys::get_commit_proposal_epoch(&key)
+                    == Some(grace_epoch.0))
+                .then(|| governance_keys::get_commit_proposal_id(&key))
+                .flatten()
+                .map(Ok)
+            }
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+/// The number of votes of each kind removed from a proposal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrunedVotes {
+    /// The number of yay votes
+    pub yay: u64,
+    /// The number of nay votes
+    pub nay: u64,
+    /// The number of abstain votes
+    pub abstain: u64,
+}
+
+/// Remove all the votes of a proposal from storage, once it's been tallied and
+/// the votes are no longer needed. Returns the number of removed votes.
+pub fn prune_proposal_votes<S>(
+    storage: &mut S,
+    proposal_id: u64,
+) -> storage_api::Result<PrunedVotes>
+where
+    S: StorageRead + StorageWrite,
+{
+    let vote_prefix_key =
+        governance_keys::get_proposal_vote_prefix_key(proposal_id);
+    let votes = storage_api::iter_prefix::<StorageProposalVote>(
+        storage,
+        &vote_prefix_key,
+    )?
+    .collect::<storage_api::Result<Vec<_>>>()?;
+
+    let mut pruned = PrunedVotes::default();
+    for (vote_key, vote) in votes {
+        if vote.is_yay() {
+            pruned.yay += 1;
+        } else if vote.is_nay() {
+            pruned.nay += 1;
+        } else {
+            pruned.abstain += 1;
+        }
+        storage.delete(&vote_key)?;
+    }
+    Ok(pruned)
+}
+
 /// Check if an accepted proposal is being executed
 pub fn is_proposal_accepted<S>(
     storage: &S,
@@ -218,6 +314,48 @@ where
     }
 }
 
+/// Register an epoch hook with the id of the accepted proposal being
+/// executed. Must be called from the code of that proposal.
+pub fn register_epoch_hook<S>(
+    storage: &mut S,
+    proposal_id: u64,
+    hook: &EpochHook,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = governance_keys::get_epoch_hook_key(proposal_id);
+    storage.write(&key, hook)
+}
+
+/// Remove the epoch hook with the given id
+pub fn remove_epoch_hook<S>(storage: &mut S, id: u64) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = governance_keys::get_epoch_hook_key(id);
+    storage.delete(&key)
+}
+
+/// Get all the epoch hooks by their ids, in the order in which they were
+/// registered
+pub fn get_epoch_hooks<S>(
+    storage: &S,
+) -> storage_api::Result<BTreeMap<u64, EpochHook>>
+where
+    S: StorageRead,
+{
+    let prefix = governance_keys::get_epoch_hooks_prefix();
+    let mut hooks = BTreeMap::new();
+    for entry in storage_api::iter_prefix::<EpochHook>(storage, &prefix)? {
+        let (key, hook) = entry?;
+        if let Some(id) = governance_keys::is_epoch_hook_key(&key) {
+            hooks.insert(id, hook);
+        }
+    }
+    Ok(hooks)
+}
+
 /// Get governance parameters
 pub fn get_parameters<S>(
     storage: &S,
@@ -247,6 +385,19 @@ where
 
     let max_proposal_period: u64 = get_max_proposal_period(storage)?;
 
+    // The parameter was added after genesis of the existing chains, on which
+    // it falls back to its default value
+    let key = governance_keys::get_votes_retention_epochs_key();
+    let votes_retention_epochs: u64 =
+        storage.read(&key)?.unwrap_or_else(|| {
+            GovernanceParameters::default().votes_retention_epochs
+        });
+
+    let key = governance_keys::get_max_epoch_hooks_gas_key();
+    let max_epoch_hooks_gas: u64 = storage
+        .read(&key)?
+        .unwrap_or_else(|| GovernanceParameters::default().max_epoch_hooks_gas);
+
     Ok(GovernanceParameters {
         min_proposal_fund,
         max_proposal_code_size,
@@ -254,6 +405,8 @@ where
         max_proposal_period,
         max_proposal_content_size,
         min_proposal_grace_epochs,
+        votes_retention_epochs,
+        max_epoch_hooks_gas,
     })
 }
 
@@ -13,7 +13,7 @@ pub mod validation;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
-pub use error::{CustomError, Error, OptionExt, Result, ResultExt};
+pub use error::{Error, ErrorCode, OptionExt, OtherError, Result, ResultExt};
 
 use crate::types::address::Address;
 use crate::types::storage::{
@@ -49,7 +49,7 @@ pub trait StorageRead {
         let bytes = self.read_bytes(key)?;
         match bytes {
             Some(bytes) => {
-                let val = T::try_from_slice(&bytes).into_storage_result()?;
+                let val = T::try_from_slice(&bytes).map_err(Error::decode)?;
                 Ok(Some(val))
             }
             None => Ok(None),
@@ -59,11 +59,46 @@ pub trait StorageRead {
     /// Storage read raw bytes. It will try to read from the storage.
     fn read_bytes(&self, key: &storage::Key) -> Result<Option<Vec<u8>>>;
 
+    /// Storage read raw bytes into the given buffer, replacing its content.
+    /// Returns `false` if there's no value, in which case the buffer is left
+    /// untouched. For large values, a storage may override this to reuse
+    /// the capacity of the buffer instead of allocating a new value.
+    fn read_bytes_into(
+        &self,
+        key: &storage::Key,
+        buf: &mut Vec<u8>,
+    ) -> Result<bool> {
+        match self.read_bytes(key)? {
+            Some(bytes) => {
+                *buf = bytes;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Storage read Borsh encoded value, read into the given buffer and
+    /// decoded from it. Reusing a buffer avoids an allocation for every large
+    /// value read, e.g. in a loop.
+    fn read_with_buf<T: BorshDeserialize>(
+        &self,
+        key: &storage::Key,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<T>> {
+        if !self.read_bytes_into(key, buf)? {
+            return Ok(None);
+        }
+        let val = T::try_from_slice(buf).map_err(Error::decode)?;
+        Ok(Some(val))
+    }
+
     /// Storage `has_key` in. It will try to read from the storage.
     fn has_key(&self, key: &storage::Key) -> Result<bool>;
 
     /// Storage prefix iterator ordered by the storage keys. It will try to get
-    /// an iterator from the storage.
+    /// an iterator from the storage. The iterator observes the state at its
+    /// creation, i.e. inside a tx the pending writes of the tx merged over
+    /// the storage, but not the writes made while iterating.
     ///
     /// For a more user-friendly iterator API, use [`fn@iter_prefix`] or
     /// [`fn@iter_prefix_bytes`] instead.
@@ -194,7 +229,7 @@ where
                         return Some(Err(err));
                     }
                 };
-                let val = match T::try_from_slice(&val).into_storage_result() {
+                let val = match T::try_from_slice(&val).map_err(Error::decode) {
                     Ok(val) => val,
                     Err(err) => {
                         // Propagate val encoding errors into Iterator's Item
@@ -252,7 +287,7 @@ where
                         continue;
                     }
                     let val =
-                        match T::try_from_slice(&val).into_storage_result() {
+                        match T::try_from_slice(&val).map_err(Error::decode) {
                             Ok(val) => val,
                             Err(err) => {
                                 // Propagate val encoding errors into Iterator's
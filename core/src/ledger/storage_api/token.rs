@@ -1,8 +1,13 @@
 //! Token storage_api functions
 
+use std::collections::BTreeMap;
+
+use masp_primitives::transaction::Transaction;
+
 use super::{StorageRead, StorageWrite};
-use crate::ledger::storage_api;
+use crate::ledger::{masp_commitment_tree, masp_nullifiers, storage_api};
 use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::{BlockHeight, Epoch, TxIndex};
 use crate::types::token;
 pub use crate::types::token::{
     balance_key, is_any_minted_balance_key, is_balance_key, minted_balance_key,
@@ -23,6 +28,26 @@ where
     Ok(balance)
 }
 
+/// Read the non-zero balances of a given owner for each of the given tokens,
+/// by token address.
+pub fn read_balances<'a, S>(
+    storage: &S,
+    owner: &Address,
+    tokens: impl IntoIterator<Item = &'a Address>,
+) -> storage_api::Result<BTreeMap<Address, token::Amount>>
+where
+    S: StorageRead,
+{
+    let mut balances = BTreeMap::new();
+    for token in tokens {
+        let balance = read_balance(storage, token, owner)?;
+        if !balance.is_zero() {
+            balances.insert(token.clone(), balance);
+        }
+    }
+    Ok(balances)
+}
+
 /// Read the total network supply of a given token.
 pub fn read_total_supply<S>(
     storage: &S,
@@ -36,6 +61,52 @@ where
     Ok(balance)
 }
 
+/// Read the sum of the balances of all the owners of a given token.
+pub fn read_balances_sum<S>(
+    storage: &S,
+    token: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let prefix = token::balance_prefix(token);
+    let mut sum = token::Amount::zero();
+    for entry in storage_api::iter_prefix::<token::Amount>(storage, &prefix)? {
+        let (key, balance) = entry?;
+        // n.b. the total supply is stored under the balance prefix, too
+        if token::is_balance_key(token, &key).is_none() {
+            continue;
+        }
+        sum = sum.checked_add(balance).ok_or_else(|| {
+            storage_api::Error::new_const("Token balances sum overflow")
+        })?;
+    }
+    Ok(sum)
+}
+
+/// Check that the total supply of a given token is equal to the sum of the
+/// balances of all of its owners.
+pub fn check_total_supply_invariant<S>(
+    storage: &S,
+    token: &Address,
+) -> storage_api::Result<()>
+where
+    S: StorageRead,
+{
+    let total_supply = read_total_supply(storage, token)?;
+    let balances_sum = read_balances_sum(storage, token)?;
+    if total_supply != balances_sum {
+        return Err(storage_api::Error::new(format!(
+            "The total supply {} of token {} differs from the sum of its \
+             balances {}",
+            total_supply.to_string_native(),
+            token,
+            balances_sum.to_string_native(),
+        )));
+    }
+    Ok(())
+}
+
 /// Read the denomination of a given token, if any. Note that native
 /// transparent tokens do not have this set and instead use the constant
 /// [`token::NATIVE_MAX_DECIMAL_PLACES`].
@@ -55,7 +126,9 @@ where
             (token::denom_key(&token), true)
         }
         Address::Internal(InternalAddress::IbcToken(_)) => {
-            return Ok(Some(0u8.into()));
+            // IBC tokens that haven't been given a denomination are
+            // represented in their smallest unit
+            (token::denom_key(token), true)
         }
         token => (token::denom_key(token), false),
     };
@@ -88,6 +161,29 @@ where
     storage.write(&key, denom)
 }
 
+/// Read the display metadata of a given token, if any.
+pub fn read_metadata<S>(
+    storage: &S,
+    token: &Address,
+) -> storage_api::Result<Option<token::Metadata>>
+where
+    S: StorageRead,
+{
+    storage.read(&token::metadata_key(token))
+}
+
+/// Write the display metadata of a given token.
+pub fn write_metadata<S>(
+    storage: &mut S,
+    token: &Address,
+    metadata: &token::Metadata,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.write(&token::metadata_key(token), metadata)
+}
+
 /// Transfer `token` from `src` to `dest`. Returns an `Err` if `src` has
 /// insufficient balance or if the transfer the `dest` would overflow (This can
 /// only happen if the total supply does't fit in `token::Amount`).
@@ -113,7 +209,16 @@ where
             match dest_balance.checked_add(amount) {
                 Some(new_dest_balance) => {
                     storage.write(&src_key, new_src_balance)?;
-                    storage.write(&dest_key, new_dest_balance)
+                    storage.write(&dest_key, new_dest_balance)?;
+                    if src != dest {
+                        storage_api::account::record_spending(
+                            storage, src, token, amount,
+                        )?;
+                        storage_api::account::record_receipt(
+                            storage, dest, token, amount,
+                        )?;
+                    }
+                    Ok(())
                 }
                 None => Err(storage_api::Error::new_const(
                     "The transfer would overflow destination balance",
@@ -126,6 +231,76 @@ where
     }
 }
 
+/// Read the amount of `token` that the `spender` is allowed to transfer from
+/// the balance of the `owner`.
+pub fn read_allowance<S>(
+    storage: &S,
+    token: &Address,
+    owner: &Address,
+    spender: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let key = token::allowance_key(token, owner, spender);
+    let allowance = storage.read::<token::Amount>(&key)?.unwrap_or_default();
+    Ok(allowance)
+}
+
+/// Allow the `spender` to transfer up to `amount` of `token` from the balance
+/// of the `owner`, replacing any previous allowance. A zero `amount` revokes
+/// the allowance.
+pub fn approve<S>(
+    storage: &mut S,
+    token: &Address,
+    owner: &Address,
+    spender: &Address,
+    amount: token::Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if owner == spender {
+        return Err(storage_api::Error::new_const(
+            "The owner cannot give an allowance to itself",
+        ));
+    }
+    let key = token::allowance_key(token, owner, spender);
+    if amount.is_zero() {
+        storage.delete(&key)
+    } else {
+        storage.write(&key, amount)
+    }
+}
+
+/// Transfer `token` from `src` to `dest` on behalf of `src`, spending the
+/// allowance given by `src` to the `spender`. Returns an `Err` if the
+/// allowance or the balance of `src` is insufficient.
+pub fn transfer_from<S>(
+    storage: &mut S,
+    token: &Address,
+    spender: &Address,
+    src: &Address,
+    dest: &Address,
+    amount: token::Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let allowance = read_allowance(storage, token, src, spender)?;
+    let new_allowance = allowance.checked_sub(amount).ok_or_else(|| {
+        storage_api::Error::new_const("Insufficient allowance")
+    })?;
+    approve(storage, token, src, spender, new_allowance)?;
+    if src == dest {
+        return Ok(());
+    }
+    transfer(storage, token, src, dest, amount)
+}
+
 /// Credit tokens to an account, to be used only by protocol. In transactions,
 /// this would get rejected by the default `vp_token`.
 pub fn credit_tokens<S>(
@@ -179,10 +354,218 @@ where
         }
     };
 
-    let total_supply = read_total_supply(&*storage, source)?;
+    let total_supply = read_total_supply(&*storage, token)?;
     let new_total_supply =
         total_supply.checked_sub(amount_to_burn).unwrap_or_default();
 
     let total_supply_key = token::minted_balance_key(token);
     storage.write(&total_supply_key, new_total_supply)
 }
+
+/// A shielded transfer recorded for the clients, along with its location
+/// within the blockchain
+pub type MaspTxRecord =
+    (Epoch, BlockHeight, TxIndex, token::Transfer, Transaction);
+
+/// Apply a shielded transfer to the storage of the MASP. The nullifiers of the
+/// spent notes are revealed, the transfer is recorded along with its location
+/// within the blockchain, so that clients don't have to look it up separately,
+/// and its new notes are appended to the note commitment tree. This is shared
+/// by the shielded transfers of txs and the ones received over IBC.
+pub fn handle_masp_tx<S>(
+    storage: &mut S,
+    transfer: &token::Transfer,
+    shielded: &Transaction,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    // Reveal the nullifiers of the spent notes
+    masp_nullifiers::reveal_nullifiers(
+        storage,
+        &masp_nullifiers::nullifiers(shielded),
+    )?;
+    let head_tx_key = token::masp_head_tx_key();
+    let current_tx_idx: u64 = storage.read(&head_tx_key)?.unwrap_or_default();
+    let record: MaspTxRecord = (
+        storage.get_block_epoch()?,
+        storage.get_block_height()?,
+        storage.get_tx_index()?,
+        transfer.clone(),
+        shielded.clone(),
+    );
+    storage.write(&token::masp_tx_key(current_tx_idx), record)?;
+    storage.write(&head_tx_key, current_tx_idx + 1)?;
+    // Append the new notes to the note commitment tree
+    masp_commitment_tree::append_note_commitments(
+        storage,
+        &masp_commitment_tree::note_commitments(shielded),
+    )?;
+    // If storage key has been supplied, then pin this transaction to it
+    if let Some(key) = &transfer.key {
+        storage.write(&token::masp_pin_tx_key(key), current_tx_idx)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+    use crate::types::address::nam;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+        established_address_4,
+    };
+
+    /// Test that minting and burning tokens keeps the total supply equal to
+    /// the sum of the balances
+    #[test]
+    fn test_total_supply_invariant() {
+        let mut storage = TestWlStorage::default();
+        let token = nam();
+        let owner_1 = established_address_1();
+        let owner_2 = established_address_2();
+
+        credit_tokens(&mut storage, &token, &owner_1, Amount::from(100))
+            .unwrap();
+        credit_tokens(&mut storage, &token, &owner_2, Amount::from(50))
+            .unwrap();
+        burn(&mut storage, &token, &owner_1, Amount::from(30)).unwrap();
+        // burning more than the balance only burns the balance
+        burn(&mut storage, &token, &owner_2, Amount::from(80)).unwrap();
+
+        assert_eq!(
+            read_total_supply(&storage, &token).unwrap(),
+            Amount::from(70)
+        );
+        assert_eq!(
+            read_balances_sum(&storage, &token).unwrap(),
+            Amount::from(70)
+        );
+        assert!(check_total_supply_invariant(&storage, &token).is_ok());
+
+        // a balance written without minting breaks the invariant
+        storage
+            .write(&balance_key(&token, &owner_2), Amount::from(1))
+            .unwrap();
+        assert!(check_total_supply_invariant(&storage, &token).is_err());
+    }
+
+    /// Test that the non-zero balances of an owner are read for the given
+    /// tokens
+    #[test]
+    fn test_read_balances() {
+        let mut storage = TestWlStorage::default();
+        let token_1 = nam();
+        let token_2 = established_address_3();
+        let owner = established_address_1();
+        let other_owner = established_address_2();
+
+        credit_tokens(&mut storage, &token_1, &owner, Amount::from(100))
+            .unwrap();
+        credit_tokens(&mut storage, &token_2, &owner, Amount::from(5)).unwrap();
+        credit_tokens(&mut storage, &token_2, &other_owner, Amount::from(7))
+            .unwrap();
+        // an emptied balance isn't read
+        let token_3 = established_address_4();
+        credit_tokens(&mut storage, &token_3, &owner, Amount::from(1)).unwrap();
+        burn(&mut storage, &token_3, &owner, Amount::from(1)).unwrap();
+
+        let tokens = [token_1.clone(), token_2.clone(), token_3];
+        assert_eq!(
+            read_balances(&storage, &owner, &tokens).unwrap(),
+            BTreeMap::from([
+                (token_1, Amount::from(100)),
+                (token_2, Amount::from(5)),
+            ])
+        );
+    }
+
+    /// Test that a spender can transfer tokens from an owner's balance up to
+    /// the approved allowance
+    #[test]
+    fn test_transfer_from_allowance() {
+        let mut storage = TestWlStorage::default();
+        let token = nam();
+        let owner = established_address_1();
+        let spender = established_address_2();
+        let target = established_address_3();
+
+        credit_tokens(&mut storage, &token, &owner, Amount::from(100)).unwrap();
+        assert!(transfer_from(
+            &mut storage,
+            &token,
+            &spender,
+            &owner,
+            &target,
+            Amount::from(10),
+        )
+        .is_err());
+
+        approve(&mut storage, &token, &owner, &spender, Amount::from(30))
+            .unwrap();
+        transfer_from(
+            &mut storage,
+            &token,
+            &spender,
+            &owner,
+            &target,
+            Amount::from(20),
+        )
+        .unwrap();
+        assert_eq!(
+            read_allowance(&storage, &token, &owner, &spender).unwrap(),
+            Amount::from(10)
+        );
+        assert_eq!(
+            read_balance(&storage, &token, &owner).unwrap(),
+            Amount::from(80)
+        );
+        assert_eq!(
+            read_balance(&storage, &token, &target).unwrap(),
+            Amount::from(20)
+        );
+        // the allowance cannot be exceeded
+        assert!(transfer_from(
+            &mut storage,
+            &token,
+            &spender,
+            &owner,
+            &target,
+            Amount::from(20),
+        )
+        .is_err());
+
+        // revoke the allowance
+        approve(&mut storage, &token, &owner, &spender, Amount::zero())
+            .unwrap();
+        assert!(!storage
+            .has_key(&token::allowance_key(&token, &owner, &spender))
+            .unwrap());
+        assert!(
+            approve(&mut storage, &token, &owner, &owner, 1.into()).is_err()
+        );
+    }
+
+    /// Test that IBC tokens use their stored denomination and metadata, and
+    /// are otherwise represented in their smallest unit
+    #[test]
+    fn test_ibc_token_denom_and_metadata() {
+        let mut storage = TestWlStorage::default();
+        let token =
+            crate::ledger::ibc::storage::ibc_token("transfer/channel-0/uatom");
+
+        assert_eq!(read_denom(&storage, &token).unwrap(), Some(0u8.into()));
+        assert_eq!(read_metadata(&storage, &token).unwrap(), None);
+
+        let metadata = token::Metadata {
+            symbol: "ATOM".to_string(),
+            display_name: "Cosmos Hub Atom".to_string(),
+        };
+        write_denom(&mut storage, &token, 6u8.into()).unwrap();
+        write_metadata(&mut storage, &token, &metadata).unwrap();
+        assert_eq!(read_denom(&storage, &token).unwrap(), Some(6u8.into()));
+        assert_eq!(read_metadata(&storage, &token).unwrap(), Some(metadata));
+    }
+}
@@ -0,0 +1,751 @@
+//! Testnet faucet with withdrawal limits and an optional proof-of-work.
+//!
+//! The faucet is an internal account whose native VP guards its balances.
+//! Its storage holds the amounts of every token that can be withdrawn from it
+//! per epoch, by each target address and in total, and optionally the
+//! difficulty of a proof-of-work challenge that must be solved for every
+//! withdrawal. These settings can only be changed by governance.
+//!
+//! A withdrawal is made by the `tx_faucet_withdraw` tx with a
+//! [`FaucetWithdrawal`] in its data. It transfers the amount to its target,
+//! increments the challenge counter of the target, so that a solution cannot
+//! be replayed, and adds its amount to the withdrawal records of the epoch.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
+use serde::{Deserialize, Serialize};
+
+use super::storage_api::token::read_balance;
+use super::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::account::SpendingRecord;
+use crate::types::address::{Address, InternalAddress};
+use crate::types::hash::Hash;
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+use crate::types::token;
+
+/// The testnet faucet internal address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::TestnetFaucet);
+
+/// The storage key segments of the faucet
+const DIFFICULTY: &str = "pow_difficulty";
+const WITHDRAWAL_LIMIT: &str = "withdrawal_limit";
+const GLOBAL_WITHDRAWAL_LIMIT: &str = "global_withdrawal_limit";
+const COUNTERS: &str = "pow_counters";
+const WITHDRAWN: &str = "withdrawn";
+const GLOBAL_WITHDRAWN: &str = "global_withdrawn";
+
+/// Check if the given storage key is a faucet key
+pub fn is_faucet_key(key: &Key) -> bool {
+    key.segments.first() == Some(&DbKeySeg::AddressSeg(ADDRESS))
+}
+
+/// The storage key of the PoW difficulty of the faucet
+pub fn difficulty_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&DIFFICULTY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the amount of a token that can be withdrawn from the
+/// faucet by a target in an epoch
+pub fn withdrawal_limit_key(token: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&WITHDRAWAL_LIMIT.to_owned())
+        .and_then(|key| key.push(token))
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the amount of a token that can be withdrawn from the
+/// faucet by all the targets in an epoch
+pub fn global_withdrawal_limit_key(token: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&GLOBAL_WITHDRAWAL_LIMIT.to_owned())
+        .and_then(|key| key.push(token))
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the PoW challenge counter of a target
+pub fn counter_key(target: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&COUNTERS.to_owned())
+        .and_then(|key| key.push(target))
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the record of the amount of a token withdrawn by a
+/// target
+pub fn withdrawn_key(token: &Address, target: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&WITHDRAWN.to_owned())
+        .and_then(|key| key.push(token))
+        .and_then(|key| key.push(target))
+        .expect("Cannot obtain a storage key")
+}
+
+/// The storage key of the record of the amount of a token withdrawn by all
+/// the targets
+pub fn global_withdrawn_key(token: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&GLOBAL_WITHDRAWN.to_owned())
+        .and_then(|key| key.push(token))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Split the given faucet key into its name and the address segments that
+/// follow it
+fn split_faucet_key(key: &Key) -> Option<(&str, &[DbKeySeg])> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(name), rest @ ..]
+            if addr == &ADDRESS
+                && rest
+                    .iter()
+                    .all(|seg| matches!(seg, DbKeySeg::AddressSeg(_))) =>
+        {
+            Some((name.as_str(), rest))
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is written by a withdrawal from the faucet,
+/// i.e. a challenge counter or a withdrawal record
+pub fn is_withdrawal_record_key(key: &Key) -> bool {
+    match split_faucet_key(key) {
+        Some((COUNTERS | GLOBAL_WITHDRAWN, rest)) => rest.len() == 1,
+        Some((WITHDRAWN, rest)) => rest.len() == 2,
+        _ => false,
+    }
+}
+
+/// Check if the given storage key is a setting of the faucet, i.e. its PoW
+/// difficulty or a withdrawal limit of a token
+pub fn is_settings_key(key: &Key) -> bool {
+    match split_faucet_key(key) {
+        Some((DIFFICULTY, rest)) => rest.is_empty(),
+        Some((WITHDRAWAL_LIMIT | GLOBAL_WITHDRAWAL_LIMIT, rest)) => {
+            rest.len() == 1
+        }
+        _ => false,
+    }
+}
+
+/// The difficulty of a PoW challenge, i.e. the number of leading zero bits
+/// of the hash of its solution
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct Difficulty(u8);
+
+impl Difficulty {
+    /// The max difficulty, above which the challenges cannot practically be
+    /// solved
+    pub const MAX: u8 = 64;
+
+    /// Try to create a difficulty, fails if it's above [`Difficulty::MAX`]
+    pub fn try_new(bits: u8) -> Option<Self> {
+        (bits <= Self::MAX).then_some(Self(bits))
+    }
+
+    /// Check that the given hash has enough leading zero bits
+    pub fn is_satisfied_by(&self, hash: &Hash) -> bool {
+        let mut zero_bits = 0_u32;
+        for byte in hash.0 {
+            if byte == 0 {
+                zero_bits += 8;
+            } else {
+                zero_bits += byte.leading_zeros();
+                break;
+            }
+        }
+        zero_bits >= u32::from(self.0)
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A PoW challenge for a withdrawal to a target
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct Challenge {
+    /// The target of the withdrawal
+    pub target: Address,
+    /// The difficulty of the faucet
+    pub difficulty: Difficulty,
+    /// The challenge counter of the target
+    pub counter: u64,
+}
+
+impl Challenge {
+    /// Obtain the current challenge of the target from the storage of the
+    /// faucet. Returns `None` if the faucet doesn't require a PoW.
+    pub fn new<S>(
+        storage: &S,
+        target: Address,
+    ) -> storage_api::Result<Option<Self>>
+    where
+        S: StorageRead,
+    {
+        let difficulty = match read_difficulty(storage)? {
+            Some(difficulty) => difficulty,
+            None => return Ok(None),
+        };
+        let counter = read_counter(storage, &target)?;
+        Ok(Some(Self {
+            target,
+            difficulty,
+            counter,
+        }))
+    }
+
+    /// Find a solution to the challenge
+    pub fn solve(self) -> Solution {
+        let mut value = 0_u64;
+        loop {
+            if self
+                .difficulty
+                .is_satisfied_by(&solution_hash(&self, value))
+            {
+                return Solution {
+                    challenge: self,
+                    value,
+                };
+            }
+            value += 1;
+        }
+    }
+}
+
+/// A solution to a PoW challenge
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct Solution {
+    /// The solved challenge
+    pub challenge: Challenge,
+    /// The value whose hash with the challenge satisfies its difficulty
+    pub value: u64,
+}
+
+impl Solution {
+    /// Check that the hash of the solution satisfies the difficulty of its
+    /// challenge
+    pub fn verify(&self) -> bool {
+        self.challenge
+            .difficulty
+            .is_satisfied_by(&solution_hash(&self.challenge, self.value))
+    }
+
+    /// Check that the solution solves the current challenge of the target in
+    /// the storage of the faucet
+    pub fn validate<S>(
+        &self,
+        storage: &S,
+        target: Address,
+    ) -> storage_api::Result<bool>
+    where
+        S: StorageRead,
+    {
+        let challenge = Challenge::new(storage, target)?;
+        Ok(challenge.as_ref() == Some(&self.challenge) && self.verify())
+    }
+}
+
+fn solution_hash(challenge: &Challenge, value: u64) -> Hash {
+    Hash::sha256((challenge, value).serialize_to_vec())
+}
+
+/// The data of a withdrawal from the faucet
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct FaucetWithdrawal {
+    /// The target of the withdrawal
+    pub target: Address,
+    /// The withdrawn token
+    pub token: Address,
+    /// The withdrawn amount
+    pub amount: token::DenominatedAmount,
+    /// The solution to the PoW challenge of the target, if the faucet
+    /// requires one
+    pub solution: Option<Solution>,
+}
+
+/// Initialize the storage of the faucet with its PoW difficulty, if it
+/// requires a PoW
+pub fn init_faucet_storage<S>(
+    storage: &mut S,
+    difficulty: Option<Difficulty>,
+) -> storage_api::Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    if let Some(difficulty) = difficulty {
+        storage.write(&difficulty_key(), difficulty)?;
+    }
+    Ok(())
+}
+
+/// Write the withdrawal limits of a token, in its base units
+pub fn write_withdrawal_limits<S>(
+    storage: &mut S,
+    token: &Address,
+    withdrawal_limit: token::Amount,
+    global_withdrawal_limit: Option<token::Amount>,
+) -> storage_api::Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    storage.write(&withdrawal_limit_key(token), withdrawal_limit)?;
+    match global_withdrawal_limit {
+        Some(limit) => {
+            storage.write(&global_withdrawal_limit_key(token), limit)
+        }
+        None => storage.delete(&global_withdrawal_limit_key(token)),
+    }
+}
+
+/// Read the PoW difficulty of the faucet, if it requires a PoW
+pub fn read_difficulty<S>(
+    storage: &S,
+) -> storage_api::Result<Option<Difficulty>>
+where
+    S: StorageRead,
+{
+    storage.read(&difficulty_key())
+}
+
+/// Read the amount of a token that can be withdrawn from the faucet by a
+/// target in an epoch. Nothing can be withdrawn if it's not set.
+pub fn read_withdrawal_limit<S>(
+    storage: &S,
+    token: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read(&withdrawal_limit_key(token))?
+        .unwrap_or_default())
+}
+
+/// Read the amount of a token that can be withdrawn from the faucet by all
+/// the targets in an epoch, if it's limited
+pub fn read_global_withdrawal_limit<S>(
+    storage: &S,
+    token: &Address,
+) -> storage_api::Result<Option<token::Amount>>
+where
+    S: StorageRead,
+{
+    storage.read(&global_withdrawal_limit_key(token))
+}
+
+/// Read the PoW challenge counter of a target
+pub fn read_counter<S>(
+    storage: &S,
+    target: &Address,
+) -> storage_api::Result<u64>
+where
+    S: StorageRead,
+{
+    Ok(storage.read(&counter_key(target))?.unwrap_or_default())
+}
+
+/// Read the amount of a token withdrawn by a target in the given epoch
+pub fn read_withdrawn<S>(
+    storage: &S,
+    token: &Address,
+    target: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read::<SpendingRecord>(&withdrawn_key(token, target))?
+        .map(|record| record.spent_in(epoch))
+        .unwrap_or_default())
+}
+
+/// Read the amount of a token withdrawn by all the targets in the given epoch
+pub fn read_global_withdrawn<S>(
+    storage: &S,
+    token: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read::<SpendingRecord>(&global_withdrawn_key(token))?
+        .map(|record| record.spent_in(epoch))
+        .unwrap_or_default())
+}
+
+/// Increment the challenge counter of the target of a withdrawal and add its
+/// amount to the withdrawal records of the current epoch
+pub fn record_withdrawal<S>(
+    storage: &mut S,
+    withdrawal: &FaucetWithdrawal,
+) -> storage_api::Result<()>
+where
+    S: StorageWrite + StorageRead,
+{
+    let FaucetWithdrawal {
+        target,
+        token,
+        amount,
+        solution: _,
+    } = withdrawal;
+    let epoch = storage.get_block_epoch()?;
+    let counter = read_counter(storage, target)?;
+    storage.write(&counter_key(target), counter + 1)?;
+
+    let withdrawn = read_withdrawn(storage, token, target, epoch)?
+        .checked_add(amount.amount)
+        .ok_or_else(|| {
+            storage_api::Error::new_const("Withdrawal record overflow")
+        })?;
+    storage.write(
+        &withdrawn_key(token, target),
+        SpendingRecord {
+            epoch,
+            spent: withdrawn,
+            received: token::Amount::zero(),
+        },
+    )?;
+    let global_withdrawn = read_global_withdrawn(storage, token, epoch)?
+        .checked_add(amount.amount)
+        .ok_or_else(|| {
+            storage_api::Error::new_const("Withdrawal record overflow")
+        })?;
+    storage.write(
+        &global_withdrawn_key(token),
+        SpendingRecord {
+            epoch,
+            spent: global_withdrawn,
+            received: token::Amount::zero(),
+        },
+    )
+}
+
+/// Check a withdrawal from the faucet against the storage before and after
+/// the tx: the PoW solution must solve the challenge of the target, if the
+/// faucet requires one, the amount must be debited from the faucet and
+/// credited to the target, the withdrawal must be recorded and it must be
+/// within the withdrawal limits of the token for the epoch.
+pub fn is_withdrawal_valid<Pre, Post>(
+    pre: &Pre,
+    post: &Post,
+    withdrawal: &FaucetWithdrawal,
+) -> storage_api::Result<bool>
+where
+    Pre: StorageRead,
+    Post: StorageRead,
+{
+    let FaucetWithdrawal {
+        target,
+        token,
+        amount,
+        solution,
+    } = withdrawal;
+    if read_difficulty(pre)?.is_some() {
+        let solved = match solution {
+            Some(solution) => solution.validate(pre, target.clone())?,
+            None => false,
+        };
+        if !solved {
+            return Ok(false);
+        }
+    }
+    if read_counter(post, target)? != read_counter(pre, target)? + 1 {
+        return Ok(false);
+    }
+
+    let debited = read_balance(pre, token, &ADDRESS)?
+        .checked_sub(read_balance(post, token, &ADDRESS)?);
+    let credited = read_balance(post, token, target)?
+        .checked_sub(read_balance(pre, token, target)?);
+    if debited != Some(amount.amount) || credited != Some(amount.amount) {
+        return Ok(false);
+    }
+
+    let epoch = pre.get_block_epoch()?;
+    let withdrawn = match read_withdrawn(pre, token, target, epoch)?
+        .checked_add(amount.amount)
+    {
+        Some(withdrawn) => withdrawn,
+        None => return Ok(false),
+    };
+    if withdrawn > read_withdrawal_limit(pre, token)?
+        || read_withdrawn(post, token, target, epoch)? != withdrawn
+    {
+        return Ok(false);
+    }
+    let global_withdrawn = match read_global_withdrawn(pre, token, epoch)?
+        .checked_add(amount.amount)
+    {
+        Some(withdrawn) => withdrawn,
+        None => return Ok(false),
+    };
+    let within_global_limit = read_global_withdrawal_limit(pre, token)?
+        .map_or(true, |limit| global_withdrawn <= limit);
+    Ok(within_global_limit
+        && read_global_withdrawn(post, token, epoch)? == global_withdrawn)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+    use crate::ledger::storage_api::token::{credit_tokens, transfer};
+    use crate::types::address::nam;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    fn withdrawal(
+        storage: &TestWlStorage,
+        amount: u64,
+    ) -> storage_api::Result<FaucetWithdrawal> {
+        let target = established_address_2();
+        let solution =
+            Challenge::new(storage, target.clone())?.map(Challenge::solve);
+        Ok(FaucetWithdrawal {
+            target,
+            token: nam(),
+            amount: token::DenominatedAmount {
+                amount: token::Amount::from_u64(amount),
+                denom: token::NATIVE_MAX_DECIMAL_PLACES.into(),
+            },
+            solution,
+        })
+    }
+
+    fn init_storage(storage: &mut TestWlStorage) -> storage_api::Result<()> {
+        init_faucet_storage(storage, Difficulty::try_new(4))?;
+        write_withdrawal_limits(
+            storage,
+            &nam(),
+            token::Amount::from_u64(100),
+            Some(token::Amount::from_u64(150)),
+        )?;
+        credit_tokens(storage, &nam(), &ADDRESS, token::Amount::from_u64(1000))
+    }
+
+    /// Apply the withdrawal to a copy of the pre storage, like the
+    /// `tx_faucet_withdraw` tx
+    fn apply_withdrawal(
+        withdrawal: &FaucetWithdrawal,
+    ) -> storage_api::Result<TestWlStorage> {
+        let mut post = TestWlStorage::default();
+        init_storage(&mut post)?;
+        transfer(
+            &mut post,
+            &withdrawal.token,
+            &ADDRESS,
+            &withdrawal.target,
+            withdrawal.amount.amount,
+        )?;
+        record_withdrawal(&mut post, withdrawal)?;
+        Ok(post)
+    }
+
+    #[test]
+    fn test_faucet_keys() {
+        let target = established_address_2();
+        assert!(is_withdrawal_record_key(&counter_key(&target)));
+        assert!(is_withdrawal_record_key(&withdrawn_key(&nam(), &target)));
+        assert!(is_withdrawal_record_key(&global_withdrawn_key(&nam())));
+        assert!(!is_withdrawal_record_key(&difficulty_key()));
+        assert!(is_settings_key(&difficulty_key()));
+        assert!(is_settings_key(&withdrawal_limit_key(&nam())));
+        assert!(is_settings_key(&global_withdrawal_limit_key(&nam())));
+        assert!(!is_settings_key(&counter_key(&target)));
+        assert!(is_faucet_key(&counter_key(&target)));
+        assert!(!is_faucet_key(&token::balance_key(&nam(), &ADDRESS)));
+    }
+
+    #[test]
+    fn test_solution() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        init_storage(&mut storage)?;
+        assert!(Difficulty::try_new(Difficulty::MAX + 1).is_none());
+
+        let target = established_address_2();
+        let solution = Challenge::new(&storage, target.clone())?
+            .expect("The faucet requires a PoW")
+            .solve();
+        assert!(solution.verify());
+        assert!(solution.validate(&storage, target.clone())?);
+
+        // A solution cannot be reused once the counter is incremented
+        storage.write(&counter_key(&target), 1_u64)?;
+        assert!(!solution.validate(&storage, target)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_withdrawal_limits() -> storage_api::Result<()> {
+        let mut pre = TestWlStorage::default();
+        init_storage(&mut pre)?;
+
+        let valid = withdrawal(&pre, 100)?;
+        let post = apply_withdrawal(&valid)?;
+        assert!(is_withdrawal_valid(&pre, &post, &valid)?);
+
+        // A withdrawal without a solution is rejected
+        let unsolved = FaucetWithdrawal {
+            solution: None,
+            ..valid.clone()
+        };
+        assert!(!is_withdrawal_valid(&pre, &post, &unsolved)?);
+
+        // The recorded amount must match
+        let mut post = apply_withdrawal(&valid)?;
+        record_withdrawal(&mut post, &valid)?;
+        assert!(!is_withdrawal_valid(&pre, &post, &valid)?);
+
+        // A withdrawal over the per-target limit is rejected
+        let over_limit = withdrawal(&pre, 101)?;
+        let post = apply_withdrawal(&over_limit)?;
+        assert!(!is_withdrawal_valid(&pre, &post, &over_limit)?);
+
+        // The limits are per token
+        let other_token = established_address_1();
+        let other_token_withdrawal = FaucetWithdrawal {
+            token: other_token.clone(),
+            ..valid.clone()
+        };
+        credit_tokens(
+            &mut pre,
+            &other_token,
+            &ADDRESS,
+            token::Amount::from_u64(1000),
+        )?;
+        let mut post = TestWlStorage::default();
+        init_storage(&mut post)?;
+        credit_tokens(
+            &mut post,
+            &other_token,
+            &ADDRESS,
+            token::Amount::from_u64(1000),
+        )?;
+        transfer(
+            &mut post,
+            &other_token,
+            &ADDRESS,
+            &valid.target,
+            valid.amount.amount,
+        )?;
+        record_withdrawal(&mut post, &other_token_withdrawal)?;
+        assert!(!is_withdrawal_valid(&pre, &post, &other_token_withdrawal)?);
+        write_withdrawal_limits(
+            &mut pre,
+            &other_token,
+            token::Amount::from_u64(100),
+            None,
+        )?;
+        assert!(is_withdrawal_valid(&pre, &post, &other_token_withdrawal)?);
+
+        // A withdrawal over the global limit is rejected
+        let global_withdrawn = SpendingRecord {
+            epoch: pre.get_block_epoch()?,
+            spent: token::Amount::from_u64(100),
+            received: token::Amount::zero(),
+        };
+        pre.write(&global_withdrawn_key(&nam()), global_withdrawn)?;
+        let mut post = TestWlStorage::default();
+        init_storage(&mut post)?;
+        post.write(&global_withdrawn_key(&nam()), global_withdrawn)?;
+        transfer(
+            &mut post,
+            &nam(),
+            &ADDRESS,
+            &valid.target,
+            valid.amount.amount,
+        )?;
+        record_withdrawal(&mut post, &valid)?;
+        assert!(!is_withdrawal_valid(&pre, &post, &valid)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_withdrawal_transfer() -> storage_api::Result<()> {
+        let mut pre = TestWlStorage::default();
+        init_storage(&mut pre)?;
+        let valid = withdrawal(&pre, 100)?;
+
+        // The withdrawn amount must be credited to the target
+        let mut post = TestWlStorage::default();
+        init_storage(&mut post)?;
+        transfer(
+            &mut post,
+            &nam(),
+            &ADDRESS,
+            &established_address_1(),
+            valid.amount.amount,
+        )?;
+        record_withdrawal(&mut post, &valid)?;
+        assert!(!is_withdrawal_valid(&pre, &post, &valid)?);
+
+        // The credited amount must be debited from the faucet
+        let mut post = TestWlStorage::default();
+        init_storage(&mut post)?;
+        credit_tokens(&mut post, &nam(), &valid.target, valid.amount.amount)?;
+        record_withdrawal(&mut post, &valid)?;
+        assert!(!is_withdrawal_valid(&pre, &post, &valid)?);
+
+        // The transferred amount must match the withdrawal
+        let mut post = TestWlStorage::default();
+        init_storage(&mut post)?;
+        transfer(
+            &mut post,
+            &nam(),
+            &ADDRESS,
+            &valid.target,
+            token::Amount::from_u64(50),
+        )?;
+        record_withdrawal(&mut post, &valid)?;
+        assert!(!is_withdrawal_valid(&pre, &post, &valid)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+//! Index of the results of the applied transactions
+
+use crate::types::hash::Hash;
+use crate::types::storage::{BlockHeight, Key};
+
+const ERROR_MSG: &str = "Cannot obtain a valid db key";
+
+/// Get the key of the result of the transaction with the given hash
+pub fn get_tx_result_key(hash: &Hash) -> Key {
+    Key::parse("tx_results")
+        .expect(ERROR_MSG)
+        .push(&hash.to_string())
+        .expect(ERROR_MSG)
+}
+
+/// Get the prefix of the height index of the transaction results
+pub fn get_tx_result_height_prefix() -> Key {
+    Key::parse("tx_results")
+        .expect(ERROR_MSG)
+        .push(&"height".to_string())
+        .expect(ERROR_MSG)
+}
+
+/// Get the key of the height index entry of the result of the transaction
+/// with the given hash. The key is prefixed by the zero-padded height of the
+/// block the result was committed in, so that iterating the index yields the
+/// entries sorted by height.
+pub fn get_tx_result_height_key(height: BlockHeight, hash: &Hash) -> Key {
+    get_tx_result_height_prefix()
+        .push(&format!("{:020}", height.0))
+        .expect(ERROR_MSG)
+        .push(&hash.to_string())
+        .expect(ERROR_MSG)
+}
+
+/// Parse the height and the transaction hash out of a height index key. Only
+/// the trailing segments are considered, so that the key may or may not
+/// include the index prefix.
+pub fn parse_tx_result_height_key(key: &str) -> Option<(BlockHeight, Hash)> {
+    let mut segments = key.rsplit('/');
+    let hash = segments.next()?.parse().ok()?;
+    let height: u64 = segments.next()?.parse().ok()?;
+    Some((BlockHeight(height), hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_height_key_roundtrip() {
+        let hash = Hash::sha256(b"tx");
+        let key = get_tx_result_height_key(BlockHeight(42), &hash);
+        assert_eq!(
+            parse_tx_result_height_key(&key.to_string()),
+            Some((BlockHeight(42), hash))
+        );
+    }
+
+    #[test]
+    fn test_height_keys_sorted_by_height() {
+        let hash = Hash::sha256(b"tx");
+        assert!(
+            get_tx_result_height_key(BlockHeight(9), &hash).to_string()
+                < get_tx_result_height_key(BlockHeight(10), &hash).to_string()
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Coordination of the upgrades of the chain to a new binary.
+//!
+//! An accepted governance proposal schedules an [`UpgradePlan`]. When the
+//! chain reaches the height of the plan, the nodes running the previous
+//! binary halt before applying the block. The block is then applied by the
+//! new binary, started with the `--upgrade-ready` flag, if its version
+//! matches the name of the plan. The plan is then removed and the height at
+//! which it was applied is recorded.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::governance::ADDRESS;
+use super::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::hash::Hash;
+use crate::types::storage::{BlockHeight, DbKeySeg, Key, KeySeg};
+
+/// The storage key segments of the upgrades
+const UPGRADE_PLAN: &str = "upgrade_plan";
+const APPLIED_UPGRADE: &str = "applied_upgrade";
+
+/// A scheduled upgrade of the chain
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct UpgradePlan {
+    /// The name of the upgrade, which must be the version of the new binary
+    pub name: String,
+    /// The height of the first block applied by the new binary
+    pub height: BlockHeight,
+    /// The hash of the release artifact of the new binary, for the operators
+    /// to check the binary they install
+    pub artifact_hash: Hash,
+}
+
+/// The storage key of the scheduled upgrade plan
+pub fn plan_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&UPGRADE_PLAN.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is the key of the upgrade plan
+pub fn is_plan_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(plan),
+                ] if addr == &ADDRESS && plan == UPGRADE_PLAN)
+}
+
+/// The storage key of the height at which an upgrade was applied
+pub fn applied_upgrade_key(name: &str) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&APPLIED_UPGRADE.to_owned())
+        .and_then(|key| key.push(&name.to_owned()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Read the scheduled upgrade plan, if any
+pub fn read_plan<S>(storage: &S) -> storage_api::Result<Option<UpgradePlan>>
+where
+    S: StorageRead,
+{
+    storage.read(&plan_key())
+}
+
+/// Check that an upgrade plan can be scheduled: its name must be non-empty
+/// and its height must be after the current block and must not be the
+/// height of an already applied upgrade with the same name
+pub fn is_valid_plan<S>(
+    storage: &S,
+    plan: &UpgradePlan,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    let current_height = storage.get_block_height()?;
+    Ok(!plan.name.is_empty()
+        && !plan.name.contains('/')
+        && plan.height > current_height
+        && read_applied_upgrade(storage, &plan.name)?.is_none())
+}
+
+/// Schedule an upgrade, replacing the previously scheduled one, if any. Must
+/// be called from the code of an accepted governance proposal.
+pub fn schedule_upgrade<S>(
+    storage: &mut S,
+    plan: &UpgradePlan,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !is_valid_plan(storage, plan)? {
+        return Err(storage_api::Error::new_const("Invalid upgrade plan"));
+    }
+    storage.write(&plan_key(), plan)
+}
+
+/// Cancel the scheduled upgrade, if any. Must be called from the code of an
+/// accepted governance proposal.
+pub fn cancel_upgrade<S>(storage: &mut S) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.delete(&plan_key())
+}
+
+/// Remove the scheduled upgrade plan once it's applied and record the height
+/// at which it was applied
+pub fn mark_applied<S>(
+    storage: &mut S,
+    plan: &UpgradePlan,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.delete(&plan_key())?;
+    storage.write(&applied_upgrade_key(&plan.name), plan.height)
+}
+
+/// Read the height at which the upgrade with the given name was applied, if
+/// it was
+pub fn read_applied_upgrade<S>(
+    storage: &S,
+    name: &str,
+) -> storage_api::Result<Option<BlockHeight>>
+where
+    S: StorageRead,
+{
+    storage.read(&applied_upgrade_key(name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestWlStorage;
+
+    #[test]
+    fn test_upgrade_plan() -> storage_api::Result<()> {
+        let mut storage = TestWlStorage::default();
+        storage.storage.block.height = BlockHeight(10);
+        let plan = UpgradePlan {
+            name: "v1.0.0".to_owned(),
+            height: BlockHeight(10),
+            artifact_hash: Hash::sha256(b"namada"),
+        };
+        // The plan must be in the future
+        assert!(schedule_upgrade(&mut storage, &plan).is_err());
+        let plan = UpgradePlan {
+            height: BlockHeight(20),
+            ..plan
+        };
+        schedule_upgrade(&mut storage, &plan)?;
+        assert_eq!(read_plan(&storage)?, Some(plan.clone()));
+        assert!(is_plan_key(&plan_key()));
+
+        mark_applied(&mut storage, &plan)?;
+        assert_eq!(read_plan(&storage)?, None);
+        assert_eq!(
+            read_applied_upgrade(&storage, &plan.name)?,
+            Some(BlockHeight(20))
+        );
+
+        // An applied upgrade cannot be scheduled again
+        let plan = UpgradePlan {
+            height: BlockHeight(30),
+            ..plan
+        };
+        assert!(schedule_upgrade(&mut storage, &plan).is_err());
+        Ok(())
+    }
+}
@@ -146,6 +146,34 @@ where
     /// Charge the provided gas for the current vp
     fn charge_gas(&self, used_gas: u64) -> Result<(), storage_api::Error>;
 
+    /// Storage read state raw bytes from before the execution of the block
+    /// the tx belongs to. Unlike [`VpEnv::read_bytes_pre`], the modifications
+    /// of the txs applied before the current one in the same block are not
+    /// visible. It will only read from the storage.
+    fn read_bytes_pre_block(
+        &self,
+        key: &Key,
+    ) -> Result<Option<Vec<u8>>, storage_api::Error>;
+
+    /// Storage read Borsh encoded state from before the execution of the
+    /// block the tx belongs to. It will only read from the storage and then
+    /// decode the value if found.
+    fn read_pre_block<T: BorshDeserialize>(
+        &self,
+        key: &Key,
+    ) -> Result<Option<T>, storage_api::Error> {
+        self.read_bytes_pre_block(key)?
+            .map(|bytes| T::try_from_slice(&bytes))
+            .transpose()
+            .into_storage_result()
+    }
+
+    /// Storage `has_key` in the state from before the execution of the block
+    /// the tx belongs to. It will only check the storage.
+    fn has_key_pre_block(&self, key: &Key) -> Result<bool, storage_api::Error> {
+        Ok(self.read_bytes_pre_block(key)?.is_some())
+    }
+
     // ---- Methods below have default implementation via `pre/post` ----
 
     /// Storage read prior state Borsh encoded value (before tx execution). It
@@ -12,7 +12,7 @@ pub mod ledger;
 pub mod proto;
 pub mod types;
 
-pub use {ibc, tendermint, tendermint_proto};
+pub use {ibc, prost, tendermint, tendermint_proto};
 
 // A handy macro for tests
 #[cfg(test)]
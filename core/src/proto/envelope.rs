@@ -0,0 +1,410 @@
+//! Conversions between the txs and their protobuf messages in
+//! `proto/types.proto`, so that the txs can be constructed and signed by the
+//! clients that don't implement Borsh. The Borsh serialization remains the
+//! encoding of the txs on the wire and the one over which the sections are
+//! hashed.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
+use prost::Message;
+
+use super::generated::types as pb;
+use super::types::{
+    Ciphertext, Code, Commitment, Data, Error, Header, Result, Section,
+    Signature, Signer, Tx,
+};
+use crate::types::address::Address;
+use crate::types::chain::ChainId;
+use crate::types::hash::Hash;
+use crate::types::key::common;
+use crate::types::storage::Epoch;
+use crate::types::time::DateTimeUtc;
+use crate::types::token::Amount;
+use crate::types::transaction::{Fee, TxType, WrapperTx};
+use crate::types::uint::Uint;
+
+impl Tx {
+    /// Encode this transaction as a protobuf [`pb::TxEnvelope`]
+    pub fn to_envelope_bytes(&self) -> Vec<u8> {
+        pb::TxEnvelope::from(self).encode_to_vec()
+    }
+
+    /// Decode a transaction from a protobuf [`pb::TxEnvelope`]
+    pub fn try_from_envelope_bytes(bytes: &[u8]) -> Result<Self> {
+        let envelope =
+            pb::TxEnvelope::decode(bytes).map_err(Error::TxDecodingError)?;
+        Self::try_from(envelope)
+    }
+}
+
+impl From<&Tx> for pb::TxEnvelope {
+    fn from(tx: &Tx) -> Self {
+        Self {
+            header: Some((&tx.header).into()),
+            sections: tx.sections.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::TxEnvelope> for Tx {
+    type Error = Error;
+
+    fn try_from(envelope: pb::TxEnvelope) -> Result<Self> {
+        Ok(Self {
+            header: envelope
+                .header
+                .ok_or_else(|| missing("header"))?
+                .try_into()?,
+            sections: envelope
+                .sections
+                .into_iter()
+                .map(Section::try_from)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl From<&Header> for pb::Header {
+    fn from(header: &Header) -> Self {
+        let tx_type = match &header.tx_type {
+            TxType::Raw => pb::header::TxType::Raw(pb::Raw {}),
+            TxType::Wrapper(wrapper) => {
+                pb::header::TxType::Wrapper(wrapper.as_ref().into())
+            }
+            TxType::Decrypted(decrypted) => {
+                pb::header::TxType::Decrypted(decrypted.serialize_to_vec())
+            }
+            TxType::Protocol(protocol) => {
+                pb::header::TxType::Protocol(protocol.serialize_to_vec())
+            }
+        };
+        Self {
+            chain_id: header.chain_id.0.clone(),
+            expiration: header.expiration.map(Into::into),
+            timestamp: Some(header.timestamp.into()),
+            code_hash: header.code_hash.0.to_vec(),
+            data_hash: header.data_hash.0.to_vec(),
+            tx_type: Some(tx_type),
+        }
+    }
+}
+
+impl TryFrom<pb::Header> for Header {
+    type Error = Error;
+
+    fn try_from(header: pb::Header) -> Result<Self> {
+        let tx_type = match header.tx_type.ok_or_else(|| missing("tx_type"))? {
+            pb::header::TxType::Raw(pb::Raw {}) => TxType::Raw,
+            pb::header::TxType::Wrapper(wrapper) => {
+                TxType::Wrapper(Box::new(wrapper.try_into()?))
+            }
+            pb::header::TxType::Decrypted(bytes) => TxType::Decrypted(
+                BorshDeserialize::try_from_slice(&bytes)
+                    .map_err(Error::TxDeserializingError)?,
+            ),
+            pb::header::TxType::Protocol(bytes) => TxType::Protocol(
+                BorshDeserialize::try_from_slice(&bytes)
+                    .map_err(Error::TxDeserializingError)?,
+            ),
+        };
+        Ok(Self {
+            chain_id: ChainId(header.chain_id),
+            expiration: header.expiration.map(timestamp).transpose()?,
+            timestamp: timestamp(
+                header.timestamp.ok_or(Error::NoTimestampError)?,
+            )?,
+            code_hash: hash(&header.code_hash)?,
+            data_hash: hash(&header.data_hash)?,
+            tx_type,
+        })
+    }
+}
+
+impl From<&WrapperTx> for pb::WrapperTx {
+    fn from(wrapper: &WrapperTx) -> Self {
+        Self {
+            fee: Some(pb::Fee {
+                amount_per_gas_unit: wrapper
+                    .fee
+                    .amount_per_gas_unit
+                    .raw_amount()
+                    .to_string(),
+                token: wrapper.fee.token.encode(),
+            }),
+            pk: wrapper.pk.to_string(),
+            epoch: wrapper.epoch.0,
+            gas_limit: wrapper.gas_limit.into(),
+            unshield_section_hash: wrapper
+                .unshield_section_hash
+                .map(|hash| hash.0.to_vec()),
+            sequence: wrapper.sequence,
+        }
+    }
+}
+
+impl TryFrom<pb::WrapperTx> for WrapperTx {
+    type Error = Error;
+
+    fn try_from(wrapper: pb::WrapperTx) -> Result<Self> {
+        let fee = wrapper.fee.ok_or_else(|| missing("fee"))?;
+        let amount_per_gas_unit = Uint::from_dec_str(&fee.amount_per_gas_unit)
+            .ok()
+            .and_then(|raw| Amount::from_uint(raw, 0).ok())
+            .ok_or_else(|| {
+                invalid(format!(
+                    "amount per gas unit {}",
+                    fee.amount_per_gas_unit
+                ))
+            })?;
+        Ok(Self {
+            fee: Fee {
+                amount_per_gas_unit,
+                token: address(&fee.token)?,
+            },
+            pk: public_key(&wrapper.pk)?,
+            epoch: Epoch(wrapper.epoch),
+            gas_limit: wrapper.gas_limit.into(),
+            unshield_section_hash: wrapper
+                .unshield_section_hash
+                .map(|bytes| hash(&bytes))
+                .transpose()?,
+            sequence: wrapper.sequence,
+        })
+    }
+}
+
+impl From<&Section> for pb::Section {
+    fn from(section: &Section) -> Self {
+        use pb::section::Section as S;
+        let section = match section {
+            Section::Data(data) => S::Data(pb::Data {
+                salt: data.salt.to_vec(),
+                data: data.data.clone(),
+            }),
+            Section::ExtraData(code) => S::ExtraData(code.into()),
+            Section::Code(code) => S::Code(code.into()),
+            Section::Signature(signature) => S::Signature(signature.into()),
+            Section::Ciphertext(ciphertext) => S::Ciphertext(pb::Ciphertext {
+                opaque: ciphertext.opaque.clone(),
+            }),
+            Section::MaspTx(transaction) => {
+                S::MaspTx(transaction.serialize_to_vec())
+            }
+            Section::MaspBuilder(builder) => {
+                S::MaspBuilder(builder.serialize_to_vec())
+            }
+            Section::Header(header) => S::Header(header.into()),
+        };
+        Self {
+            section: Some(section),
+        }
+    }
+}
+
+impl TryFrom<pb::Section> for Section {
+    type Error = Error;
+
+    fn try_from(section: pb::Section) -> Result<Self> {
+        use pb::section::Section as S;
+        Ok(match section.section.ok_or_else(|| missing("section"))? {
+            S::Data(data) => Section::Data(Data {
+                salt: salt(&data.salt)?,
+                data: data.data,
+            }),
+            S::ExtraData(code) => Section::ExtraData(code.try_into()?),
+            S::Code(code) => Section::Code(code.try_into()?),
+            S::Signature(signature) => {
+                Section::Signature(signature.try_into()?)
+            }
+            S::Ciphertext(ciphertext) => Section::Ciphertext(Ciphertext {
+                opaque: ciphertext.opaque,
+            }),
+            S::MaspTx(bytes) => Section::MaspTx(
+                BorshDeserialize::try_from_slice(&bytes)
+                    .map_err(Error::TxDeserializingError)?,
+            ),
+            S::MaspBuilder(bytes) => Section::MaspBuilder(
+                BorshDeserialize::try_from_slice(&bytes)
+                    .map_err(Error::TxDeserializingError)?,
+            ),
+            S::Header(header) => Section::Header(header.try_into()?),
+        })
+    }
+}
+
+impl From<&Code> for pb::Code {
+    fn from(code: &Code) -> Self {
+        Self {
+            salt: code.salt.to_vec(),
+            code: Some(match &code.code {
+                Commitment::Hash(hash) => pb::code::Code::Hash(hash.0.to_vec()),
+                Commitment::Id(bytes) => pb::code::Code::Id(bytes.clone()),
+            }),
+            tag: code.tag.clone(),
+        }
+    }
+}
+
+impl TryFrom<pb::Code> for Code {
+    type Error = Error;
+
+    fn try_from(code: pb::Code) -> Result<Self> {
+        Ok(Self {
+            salt: salt(&code.salt)?,
+            code: match code.code.ok_or_else(|| missing("code"))? {
+                pb::code::Code::Hash(bytes) => Commitment::Hash(hash(&bytes)?),
+                pb::code::Code::Id(bytes) => Commitment::Id(bytes),
+            },
+            tag: code.tag,
+        })
+    }
+}
+
+impl From<&Signature> for pb::Signature {
+    fn from(signature: &Signature) -> Self {
+        let signer = match &signature.signer {
+            Signer::Address(addr) => {
+                pb::signature::Signer::Address(addr.encode())
+            }
+            Signer::PubKeys(pks) => {
+                pb::signature::Signer::PubKeys(pb::PublicKeys {
+                    pub_keys: pks.iter().map(ToString::to_string).collect(),
+                })
+            }
+        };
+        Self {
+            targets: signature
+                .targets
+                .iter()
+                .map(|hash| hash.0.to_vec())
+                .collect(),
+            signer: Some(signer),
+            signatures: signature
+                .signatures
+                .iter()
+                .map(|(index, sig)| (u32::from(*index), sig.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::Signature> for Signature {
+    type Error = Error;
+
+    fn try_from(signature: pb::Signature) -> Result<Self> {
+        let signer = match signature.signer.ok_or_else(|| missing("signer"))? {
+            pb::signature::Signer::Address(addr) => {
+                Signer::Address(address(&addr)?)
+            }
+            pb::signature::Signer::PubKeys(pks) => Signer::PubKeys(
+                pks.pub_keys
+                    .iter()
+                    .map(String::as_str)
+                    .map(public_key)
+                    .collect::<Result<_>>()?,
+            ),
+        };
+        let signatures = signature
+            .signatures
+            .into_iter()
+            .map(|(index, sig)| {
+                let index = u8::try_from(index)
+                    .map_err(|_| invalid(format!("signature index {index}")))?;
+                let sig = common::Signature::from_str(&sig)
+                    .map_err(|err| invalid(format!("signature: {err}")))?;
+                Ok((index, sig))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        Ok(Self {
+            targets: signature
+                .targets
+                .iter()
+                .map(Vec::as_slice)
+                .map(hash)
+                .collect::<Result<_>>()?,
+            signer,
+            signatures,
+        })
+    }
+}
+
+/// A required field of a message is missing
+fn missing(field: &str) -> Error {
+    Error::InvalidEnvelope(format!("missing field {field}"))
+}
+
+/// A field of a message is invalid
+fn invalid(msg: String) -> Error {
+    Error::InvalidEnvelope(format!("invalid {msg}"))
+}
+
+fn timestamp(timestamp: prost_types::Timestamp) -> Result<DateTimeUtc> {
+    DateTimeUtc::try_from(timestamp).map_err(Error::InvalidTimestamp)
+}
+
+fn hash(bytes: &[u8]) -> Result<Hash> {
+    Hash::try_from(bytes).map_err(|err| invalid(format!("hash: {err}")))
+}
+
+fn salt(bytes: &[u8]) -> Result<[u8; 8]> {
+    bytes
+        .try_into()
+        .map_err(|_| invalid(format!("salt of length {}", bytes.len())))
+}
+
+fn address(addr: &str) -> Result<Address> {
+    Address::decode(addr).map_err(|err| invalid(format!("address: {err}")))
+}
+
+fn public_key(pk: &str) -> Result<common::PublicKey> {
+    common::PublicKey::from_str(pk)
+        .map_err(|err| invalid(format!("public key: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::key::testing::keypair_1;
+    use crate::types::key::RefTo;
+
+    /// Test that a signed wrapper tx round trips through its protobuf
+    /// envelope, keeping its section hashes
+    #[test]
+    fn test_envelope_round_trip() {
+        let keypair = keypair_1();
+        let mut tx = Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+            Fee {
+                amount_per_gas_unit: Amount::from_uint(100, 0).unwrap(),
+                token: crate::types::address::nam(),
+            },
+            keypair.ref_to(),
+            Epoch(1),
+            20_000.into(),
+            None,
+        ))));
+        tx.header.chain_id = ChainId("test-chain".to_string());
+        tx.set_code(Code::new(vec![1, 2, 3], Some("tx_test.wasm".into())));
+        tx.set_data(Data::new(vec![4, 5, 6]));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            [(0, keypair)].into_iter().collect(),
+            None,
+        )));
+
+        let bytes = tx.to_envelope_bytes();
+        let decoded = Tx::try_from_envelope_bytes(&bytes).expect("Test failed");
+        assert_eq!(decoded.serialize_to_vec(), tx.serialize_to_vec());
+        assert_eq!(decoded.header_hash(), tx.header_hash());
+
+        // A header without its tx type is rejected
+        let mut envelope = pb::TxEnvelope::from(&tx);
+        envelope.header.as_mut().unwrap().tx_type = None;
+        assert!(matches!(
+            Tx::try_from(envelope),
+            Err(Error::InvalidEnvelope(_))
+        ));
+    }
+}
@@ -1,13 +1,18 @@
 #![allow(missing_docs)]
 
+mod envelope;
 pub mod generated;
+mod partially_signed;
+mod sign_doc;
 mod types;
 
+pub use partially_signed::PartiallySignedTx;
+pub use sign_doc::{canonical_json, SignDoc};
 pub use types::{
     standalone_signature, verify_standalone_sig, Code, Commitment,
     CompressedSignature, Data, Error, Header, MaspBuilder, Section,
     SerializeWithBorsh, Signable, SignableEthMessage, Signature,
-    SignatureIndex, Signed, Signer, Tx, TxError,
+    SignatureIndex, Signed, Signer, Tx, TxError, VerifiedSigs,
 };
 
 #[cfg(test)]
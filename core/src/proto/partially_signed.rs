@@ -0,0 +1,192 @@
+//! Partially signed txs.
+//!
+//! A tx of a multisig account, e.g. a treasury, requires the signatures of
+//! several keys, which are often held on different machines. A
+//! [`PartiallySignedTx`] is a portable file carrying a tx with the signatures
+//! collected so far: every signer adds their signatures to a copy of it, the
+//! copies are merged into one with all the signatures and these are attached
+//! to the tx for its submission.
+
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use borsh_ext::BorshSerializeExt;
+use data_encoding::HEXUPPER;
+use serde::{Deserialize, Serialize};
+
+use super::types::{Error, Result, Signature, SignatureIndex, Signer, Tx};
+use crate::types::hash::Hash;
+use crate::types::key::{common, SigScheme};
+
+/// A tx with some of the signatures of its raw header
+#[derive(
+    Clone,
+    Debug,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct PartiallySignedTx {
+    /// The tx to be signed
+    pub tx: Tx,
+    /// The signatures collected so far
+    pub signatures: Vec<SignatureIndex>,
+}
+
+impl PartiallySignedTx {
+    /// A partially signed tx without signatures
+    pub fn new(tx: Tx) -> Self {
+        Self {
+            tx,
+            signatures: vec![],
+        }
+    }
+
+    /// The hash signed by the signers of the raw header of the tx
+    fn signing_hash(&self) -> Hash {
+        Signature {
+            targets: vec![self.tx.raw_header_hash()],
+            signer: Signer::PubKeys(vec![]),
+            signatures: BTreeMap::new(),
+        }
+        .get_raw_hash()
+    }
+
+    /// Add the given signatures, which must be valid signatures of the tx.
+    /// The signatures already included for the same key and account index are
+    /// skipped.
+    pub fn add_signatures(
+        &mut self,
+        signatures: impl IntoIterator<Item = SignatureIndex>,
+    ) -> Result<()> {
+        let signing_hash = self.signing_hash();
+        for signature in signatures {
+            common::SigScheme::verify_signature(
+                &signature.pubkey,
+                &signing_hash,
+                &signature.signature,
+            )
+            .map_err(|err| {
+                Error::InvalidSectionSignature(format!(
+                    "The signature of {} is not a signature of the tx: {err}",
+                    signature.pubkey
+                ))
+            })?;
+            let is_included = self.signatures.iter().any(|included| {
+                included.pubkey == signature.pubkey
+                    && included.index == signature.index
+            });
+            if !is_included {
+                self.signatures.push(signature);
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge the signatures of another partially signed copy of the same tx
+    pub fn merge(&mut self, other: PartiallySignedTx) -> Result<()> {
+        let (hash, other_hash) =
+            (self.tx.raw_header_hash(), other.tx.raw_header_hash());
+        if hash != other_hash {
+            return Err(Error::PartiallySignedTxMismatch(hash, other_hash));
+        }
+        self.add_signatures(other.signatures)
+    }
+
+    /// Attach the collected signatures to the tx
+    pub fn into_signed_tx(self) -> Tx {
+        let mut tx = self.tx;
+        tx.add_signatures(self.signatures);
+        tx
+    }
+
+    /// Serialize to a hex encoded string, as for a [`Tx`]
+    pub fn serialize(&self) -> String {
+        HEXUPPER.encode(&self.serialize_to_vec())
+    }
+
+    /// Deserialize from a JSON string of the hex encoding
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        let hex = serde_json::from_slice::<String>(data)
+            .map_err(|_| Error::OfflineTxDeserializationError)?;
+        let bytes = HEXUPPER
+            .decode(hex.as_bytes())
+            .map_err(|_| Error::OfflineTxDeserializationError)?;
+        Self::try_from_slice(&bytes).map_err(Error::TxDeserializingError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::Data;
+    use crate::types::account::AccountPublicKeysMap;
+    use crate::types::address::testing::established_address_1;
+    use crate::types::key::testing::{keypair_1, keypair_2};
+    use crate::types::key::RefTo;
+    use crate::types::transaction::TxType;
+
+    /// Test that the signatures of two signers of a multisig account made on
+    /// different machines are merged into a tx signed by both
+    #[test]
+    fn test_merge_partially_signed_txs() {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_data(Data::new(vec![1, 2, 3]));
+        let owner = established_address_1();
+        let public_keys_map = AccountPublicKeysMap::from_iter([
+            keypair_1().ref_to(),
+            keypair_2().ref_to(),
+        ]);
+
+        let mut psnts = [keypair_1(), keypair_2()].map(|keypair| {
+            let mut psnt = PartiallySignedTx::new(tx.clone());
+            let signatures = tx.compute_section_signature(
+                &[keypair],
+                &public_keys_map,
+                Some(owner.clone()),
+            );
+            psnt.add_signatures(signatures).unwrap();
+            // A round trip through the file encoding
+            PartiallySignedTx::deserialize(
+                &serde_json::to_vec(&psnt.serialize()).unwrap(),
+            )
+            .unwrap()
+        });
+        let [mut merged, other] = psnts.clone();
+        merged.merge(other).unwrap();
+        assert_eq!(merged.signatures.len(), 2);
+        // Merging the same signatures again is a no-op
+        merged.merge(psnts[1].clone()).unwrap();
+        assert_eq!(merged.signatures.len(), 2);
+
+        // A copy of another tx can't be merged
+        let mut other_tx = tx.clone();
+        other_tx.set_data(Data::new(vec![4, 5, 6]));
+        psnts[0].tx = other_tx;
+        assert!(matches!(
+            merged.merge(psnts[0].clone()),
+            Err(Error::PartiallySignedTxMismatch(_, _))
+        ));
+        // A signature made with another key is rejected
+        psnts[1].signatures[0].pubkey = keypair_1().ref_to();
+        psnts[1].signatures[0].index = None;
+        psnts[1].signatures[0].signature =
+            common::SigScheme::sign(&keypair_2(), [0; 32]);
+        assert!(merged.merge(psnts[1].clone()).is_err());
+        assert_eq!(merged.signatures.len(), 2);
+
+        let signed_tx = merged.into_signed_tx();
+        assert!(signed_tx
+            .verify_signatures(
+                &[signed_tx.raw_header_hash()],
+                public_keys_map,
+                &Some(owner),
+                2,
+                None,
+                || Ok(()),
+            )
+            .is_ok());
+    }
+}
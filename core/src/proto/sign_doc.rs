@@ -0,0 +1,235 @@
+//! The canonical documents signed offline and by hardware wallets.
+//!
+//! The inner signature of a tx is made over the hash of its raw header, which
+//! commits to the fields of the [`SignDoc`]. The document is encoded as JSON
+//! with sorted keys and without whitespace, so that the bytes displayed by a
+//! signer are the same for every implementation, and its `signing_hash` is
+//! the hash verified by the chain. The data of the txs of the known codes is
+//! decoded in the document, so that a signer can display what it signs. A
+//! signer must check the document against the tx with [`SignDoc::verify`]
+//! before signing its `signing_hash`.
+
+use std::collections::BTreeMap;
+
+use borsh::BorshDeserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::types::{Error, Result, Section, Signature, Signer, Tx};
+use crate::types::key::common;
+use crate::types::token;
+use crate::types::transaction::governance::VoteProposalData;
+use crate::types::transaction::pos;
+
+/// The fields of a tx committed to by its inner signature
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignDoc {
+    /// The chain which the tx is being submitted to
+    pub chain_id: String,
+    /// The hash of the tx's code section
+    pub code_hash: String,
+    /// The tag of the tx's code, e.g. `tx_transfer.wasm`, if the code section
+    /// is included in the tx
+    pub code_tag: Option<String>,
+    /// The tx's data, decoded if the code tag is known
+    pub data: Option<Value>,
+    /// The hash of the tx's data section
+    pub data_hash: String,
+    /// The time at which the tx expires, in RFC3339 format
+    pub expiration: Option<String>,
+    /// The hash signed by the signers and verified by the chain
+    pub signing_hash: String,
+    /// The tx timestamp, in RFC3339 format
+    pub timestamp: String,
+}
+
+impl SignDoc {
+    /// The document of the inner signature of the given tx
+    pub fn new(tx: &Tx) -> Self {
+        let header = tx.header();
+        let code_tag = match tx.get_section(tx.code_sechash()).as_deref() {
+            Some(Section::Code(code)) => code.tag.clone(),
+            _ => None,
+        };
+        // The section whose raw hash is signed by `Tx::sign_raw`
+        let signature = Signature {
+            targets: vec![tx.raw_header_hash()],
+            signer: Signer::PubKeys(vec![]),
+            signatures: BTreeMap::new(),
+        };
+        let data = match (&code_tag, tx.data()) {
+            (Some(tag), Some(data)) => decode_data(tag, &data),
+            _ => None,
+        };
+        Self {
+            chain_id: header.chain_id.to_string(),
+            code_hash: header.code_hash.to_string(),
+            code_tag,
+            data,
+            data_hash: header.data_hash.to_string(),
+            expiration: header.expiration.map(|time| time.to_rfc3339()),
+            signing_hash: signature.get_raw_hash().to_string(),
+            timestamp: header.timestamp.to_rfc3339(),
+        }
+    }
+
+    /// Check that this document is the one of the given tx
+    pub fn verify(&self, tx: &Tx) -> Result<()> {
+        if *self == Self::new(tx) {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignDoc(
+                "the document doesn't match the transaction".to_string(),
+            ))
+        }
+    }
+
+    /// Encode this document in its canonical JSON encoding
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let value = serde_json::to_value(self)
+            .expect("Serializing a sign doc shouldn't fail");
+        canonical_json(&value).into_bytes()
+    }
+
+    /// Decode a document from its canonical JSON encoding. Any other encoding
+    /// of the document is rejected.
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Result<Self> {
+        let doc: Self = serde_json::from_slice(bytes)
+            .map_err(|err| Error::InvalidSignDoc(err.to_string()))?;
+        if doc.to_canonical_bytes() != bytes {
+            return Err(Error::InvalidSignDoc(
+                "the document isn't canonically encoded".to_string(),
+            ));
+        }
+        Ok(doc)
+    }
+}
+
+/// Decode the data of a tx with the given code tag, if the code is known
+fn decode_data(tag: &str, data: &[u8]) -> Option<Value> {
+    fn decode<T: BorshDeserialize + Serialize>(data: &[u8]) -> Option<Value> {
+        let data = T::try_from_slice(data).ok()?;
+        serde_json::to_value(data).ok()
+    }
+    match tag {
+        "tx_transfer.wasm" => decode::<token::Transfer>(data),
+        "tx_bond.wasm" => decode::<pos::Bond>(data),
+        "tx_unbond.wasm" => decode::<pos::Unbond>(data),
+        "tx_withdraw.wasm" => decode::<pos::Withdraw>(data),
+        "tx_claim_rewards.wasm" => decode::<pos::ClaimRewards>(data),
+        "tx_redelegate.wasm" => decode::<pos::Redelegation>(data),
+        "tx_vote_proposal.wasm" => decode::<VoteProposalData>(data),
+        "tx_reveal_pk.wasm" => decode::<common::PublicKey>(data),
+        _ => None,
+    }
+}
+
+/// Encode a JSON value with the keys of its objects sorted and without
+/// whitespace
+pub fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<&String, &Value> = map.iter().collect();
+            let fields: Vec<String> = sorted
+                .into_iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{}:{}",
+                        Value::from(key.as_str()),
+                        canonical_json(value)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(values) => {
+            let values: Vec<String> =
+                values.iter().map(canonical_json).collect();
+            format!("[{}]", values.join(","))
+        }
+        // The scalars have a single compact encoding
+        scalar => scalar.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{Code, Data};
+    use crate::types::address::nam;
+    use crate::types::address::testing::established_address_1;
+    use crate::types::chain::ChainId;
+    use crate::types::key::testing::keypair_1;
+    use crate::types::transaction::TxType;
+
+    /// Test that the canonical encoding sorts the keys of nested objects
+    #[test]
+    fn test_canonical_json() {
+        let value: Value = serde_json::from_str(
+            r#"{ "b": [1, {"d": null, "c": "x"}], "a": true }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":true,"b":[1,{"c":"x","d":null}]}"#
+        );
+    }
+
+    /// Test that the signing hash of the document of a tx is the hash
+    /// verified by the chain, and that the document only decodes from its
+    /// canonical encoding
+    #[test]
+    fn test_sign_doc() {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = ChainId("test-chain".to_string());
+        tx.set_code(Code::new(vec![1, 2, 3], Some("tx_test.wasm".into())));
+        tx.set_data(Data::new(vec![4, 5, 6]));
+        let doc = SignDoc::new(&tx);
+        assert_eq!(doc.code_tag.as_deref(), Some("tx_test.wasm"));
+        assert_eq!(doc.data, None);
+        doc.verify(&tx).expect("Test failed");
+
+        // A signature over the signing hash is the one made by `sign_raw`
+        let signature = Signature::new(
+            vec![tx.raw_header_hash()],
+            [(0, keypair_1())].into_iter().collect(),
+            None,
+        );
+        assert_eq!(signature.get_raw_hash().to_string(), doc.signing_hash);
+
+        let bytes = doc.to_canonical_bytes();
+        assert_eq!(SignDoc::from_canonical_bytes(&bytes).unwrap(), doc);
+        let pretty = serde_json::to_vec_pretty(&doc).unwrap();
+        assert!(SignDoc::from_canonical_bytes(&pretty).is_err());
+
+        // The document no longer matches a modified tx
+        tx.header.chain_id = ChainId("other-chain".to_string());
+        assert!(doc.verify(&tx).is_err());
+    }
+
+    /// Test that the data of a tx with a known code is decoded in its document
+    #[test]
+    fn test_sign_doc_decoded_data() {
+        let bond = pos::Bond {
+            validator: established_address_1(),
+            amount: token::Amount::native_whole(10),
+            source: None,
+        };
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::new(vec![1, 2, 3], Some("tx_bond.wasm".into())));
+        tx.add_data(bond.clone());
+        let doc = SignDoc::new(&tx);
+        assert_eq!(doc.data, Some(serde_json::to_value(&bond).unwrap()));
+
+        // The decoded data is part of the canonical encoding
+        let bytes = doc.to_canonical_bytes();
+        assert_eq!(SignDoc::from_canonical_bytes(&bytes).unwrap(), doc);
+
+        // The data of a tx isn't decoded as the data of another code
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::new(vec![1, 2, 3], Some("tx_transfer.wasm".into())));
+        tx.add_data(nam());
+        assert_eq!(SignDoc::new(&tx).data, None);
+    }
+}
@@ -55,6 +55,18 @@ pub enum Error {
     InvalidWrapperSignature,
     #[error("Signature verification went out of gas: {0}")]
     OutOfGas(gas::Error),
+    #[error("Invalid protobuf transaction: {0}")]
+    InvalidEnvelope(String),
+    #[error("Invalid sign doc: {0}")]
+    InvalidSignDoc(String),
+    #[error(
+        "The partially signed txs are of different txs, with raw header \
+         hashes {0} and {1}"
+    )]
+    PartiallySignedTxMismatch(
+        crate::types::hash::Hash,
+        crate::types::hash::Hash,
+    ),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -472,6 +484,14 @@ pub enum Signer {
     PubKeys(Vec<common::PublicKey>),
 }
 
+/// Signatures that were already verified, e.g. in a batch, given with their
+/// public key and the hash they sign
+pub type VerifiedSigs = HashSet<(
+    common::PublicKey,
+    crate::types::hash::Hash,
+    common::Signature,
+)>;
+
 /// A section representing a multisig over another section
 #[derive(
     Clone,
@@ -570,6 +590,37 @@ impl Signature {
     where
         F: FnMut() -> std::result::Result<(), crate::ledger::gas::Error>,
     {
+        self.verify_signature_with(
+            verified_pks,
+            public_keys_index_map,
+            signer,
+            consume_verify_sig_gas,
+            &VerifiedSigs::new(),
+        )
+    }
+
+    /// Verify that the signature contained in this section is valid, skipping
+    /// the cryptographic verification of the signatures that were already
+    /// verified
+    pub fn verify_signature_with<F>(
+        &self,
+        verified_pks: &mut HashSet<u8>,
+        public_keys_index_map: &AccountPublicKeysMap,
+        signer: &Option<Address>,
+        consume_verify_sig_gas: &mut F,
+        verified_sigs: &VerifiedSigs,
+    ) -> std::result::Result<u8, VerifySigError>
+    where
+        F: FnMut() -> std::result::Result<(), crate::ledger::gas::Error>,
+    {
+        let raw_hash = self.get_raw_hash();
+        let verify = |pk: &common::PublicKey, sig: &common::Signature| {
+            if verified_sigs.contains(&(pk.clone(), raw_hash, sig.clone())) {
+                Ok(())
+            } else {
+                common::SigScheme::verify_signature(pk, &raw_hash, sig)
+            }
+        };
         // Records whether there are any successful verifications
         let mut verifications = 0;
         match &self.signer {
@@ -581,11 +632,7 @@ impl Signature {
                         public_keys_index_map.get_public_key_from_index(*idx)
                     {
                         consume_verify_sig_gas()?;
-                        common::SigScheme::verify_signature(
-                            &pk,
-                            &self.get_raw_hash(),
-                            sig,
-                        )?;
+                        verify(&pk, sig)?;
                         verified_pks.insert(*idx);
                         verifications += 1;
                     }
@@ -602,11 +649,7 @@ impl Signature {
                         public_keys_index_map.get_index_from_public_key(pk)
                     {
                         consume_verify_sig_gas()?;
-                        common::SigScheme::verify_signature(
-                            pk,
-                            &self.get_raw_hash(),
-                            &self.signatures[&(idx as u8)],
-                        )?;
+                        verify(pk, &self.signatures[&(idx as u8)])?;
                         verified_pks.insert(map_idx);
                         verifications += 1;
                     }
@@ -1249,6 +1292,33 @@ impl Tx {
     /// Verify that the section with the given hash has been signed by the given
     /// public key
     pub fn verify_signatures<F>(
+        &self,
+        hashes: &[crate::types::hash::Hash],
+        public_keys_index_map: AccountPublicKeysMap,
+        signer: &Option<Address>,
+        threshold: u8,
+        max_signatures: Option<u8>,
+        consume_verify_sig_gas: F,
+    ) -> std::result::Result<Vec<&Signature>, Error>
+    where
+        F: FnMut() -> std::result::Result<(), crate::ledger::gas::Error>,
+    {
+        self.verify_signatures_with(
+            hashes,
+            public_keys_index_map,
+            signer,
+            threshold,
+            max_signatures,
+            consume_verify_sig_gas,
+            &VerifiedSigs::new(),
+        )
+    }
+
+    /// Verify that the section with the given hash has been signed by the given
+    /// public key, skipping the cryptographic verification of the signatures
+    /// that were already verified
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_signatures_with<F>(
         &self,
         hashes: &[crate::types::hash::Hash],
         public_keys_index_map: AccountPublicKeysMap,
@@ -1256,6 +1326,7 @@ impl Tx {
         threshold: u8,
         max_signatures: Option<u8>,
         mut consume_verify_sig_gas: F,
+        verified_sigs: &VerifiedSigs,
     ) -> std::result::Result<Vec<&Signature>, Error>
     where
         F: FnMut() -> std::result::Result<(), crate::ledger::gas::Error>,
@@ -1286,11 +1357,12 @@ impl Tx {
 
                     // Finally verify that the signature itself is valid
                     let amt_verifieds = signatures
-                        .verify_signature(
+                        .verify_signature_with(
                             &mut verified_pks,
                             &public_keys_index_map,
                             signer,
                             &mut consume_verify_sig_gas,
+                            verified_sigs,
                         )
                         .map_err(|e| {
                             if let VerifySigError::OutOfGas(inner) = e {
@@ -1329,13 +1401,25 @@ impl Tx {
         public_key: &common::PublicKey,
         hashes: &[crate::types::hash::Hash],
     ) -> Result<&Signature> {
-        self.verify_signatures(
+        self.verify_signature_with(public_key, hashes, &VerifiedSigs::new())
+    }
+
+    /// Same as [`Tx::verify_signature`], skipping the cryptographic
+    /// verification of the signatures that were already verified.
+    pub fn verify_signature_with(
+        &self,
+        public_key: &common::PublicKey,
+        hashes: &[crate::types::hash::Hash],
+        verified_sigs: &VerifiedSigs,
+    ) -> Result<&Signature> {
+        self.verify_signatures_with(
             hashes,
             AccountPublicKeysMap::from_iter([public_key.clone()].into_iter()),
             &None,
             1,
             None,
             || Ok(()),
+            verified_sigs,
         )
         .map(|x| *x.first().unwrap())
         .map_err(|_| Error::InvalidWrapperSignature)
@@ -1395,11 +1479,24 @@ impl Tx {
     /// 2. The signature is valid
     pub fn validate_tx(
         &self,
+    ) -> std::result::Result<Option<&Signature>, TxError> {
+        self.validate_tx_with(&VerifiedSigs::new())
+    }
+
+    /// Same as [`Tx::validate_tx`], skipping the cryptographic verification
+    /// of the wrapper signatures that were already verified, e.g. in a batch.
+    pub fn validate_tx_with(
+        &self,
+        verified_sigs: &VerifiedSigs,
     ) -> std::result::Result<Option<&Signature>, TxError> {
         match &self.header.tx_type {
             // verify signature and extract signed data
             TxType::Wrapper(wrapper) => self
-                .verify_signature(&wrapper.pk, &self.sechashes())
+                .verify_signature_with(
+                    &wrapper.pk,
+                    &self.sechashes(),
+                    verified_sigs,
+                )
                 .map(Option::Some)
                 .map_err(|err| {
                     TxError::SigError(format!(
@@ -1424,6 +1521,50 @@ impl Tx {
         }
     }
 
+    /// The ed25519 signatures made with the key of the wrapper in the
+    /// signature sections of this tx, to be verified in a batch. Each one is
+    /// given with the hash it signs.
+    pub fn wrapper_ed25519_signatures(
+        &self,
+    ) -> Vec<(
+        ed25519::PublicKey,
+        crate::types::hash::Hash,
+        ed25519::Signature,
+    )> {
+        let wrapper_pk = match &self.header.tx_type {
+            TxType::Wrapper(wrapper) => match &wrapper.pk {
+                common::PublicKey::Ed25519(pk) => pk,
+                _ => return vec![],
+            },
+            _ => return vec![],
+        };
+        self.sections
+            .iter()
+            .filter_map(|section| match section {
+                Section::Signature(signature) => match &signature.signer {
+                    Signer::PubKeys(pks) => {
+                        Some((signature, pks, signature.get_raw_hash()))
+                    }
+                    Signer::Address(_) => None,
+                },
+                _ => None,
+            })
+            .flat_map(|(signature, pks, raw_hash)| {
+                pks.iter().enumerate().filter_map(move |(idx, pk)| {
+                    match (pk, signature.signatures.get(&(idx as u8))) {
+                        (
+                            common::PublicKey::Ed25519(pk),
+                            Some(common::Signature::Ed25519(sig)),
+                        ) if pk == wrapper_pk => {
+                            Some((pk.clone(), raw_hash, sig.clone()))
+                        }
+                        _ => None,
+                    }
+                })
+            })
+            .collect()
+    }
+
     /// Filter out all the sections that must not be submitted to the protocol
     /// and return them.
     pub fn protocol_filter(&mut self) -> Vec<Section> {
@@ -1553,6 +1694,15 @@ impl Tx {
         self
     }
 
+    /// Set the sequence number of the fee payer in the wrapper of the tx
+    /// builder
+    pub fn set_wrapper_sequence(&mut self, sequence: u64) -> &mut Self {
+        if let TxType::Wrapper(wrapper) = &mut self.header.tx_type {
+            wrapper.sequence = Some(sequence);
+        }
+        self
+    }
+
     /// Add fee payer keypair to the tx builder
     pub fn sign_wrapper(&mut self, keypair: common::SecretKey) -> &mut Self {
         self.protocol_filter();
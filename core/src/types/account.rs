@@ -1,12 +1,15 @@
 //! Helper structures to manage accounts
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 use super::address::Address;
+use super::hash::Hash;
 use super::key::{common, RefTo};
+use super::storage::Epoch;
+use super::token;
 use crate::hints;
 
 #[derive(
@@ -110,3 +113,247 @@ impl AccountPublicKeysMap {
             .collect()
     }
 }
+
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+/// Authorization policy of an account, enforced by the user VP on top of the
+/// signature verification
+pub struct AccountPolicy {
+    /// Maximum amount of a token that can be debited from the account per
+    /// epoch, net of the amount credited to it
+    pub spend_limits: BTreeMap<Address, token::Amount>,
+    /// The only addresses that may be credited with tokens debited from the
+    /// account. An empty set allows any counterparty.
+    pub allowed_counterparties: BTreeSet<Address>,
+    /// Signature thresholds required to debit an amount of a token above a
+    /// value in a single transaction
+    pub high_value_thresholds: BTreeMap<Address, HighValueThreshold>,
+}
+
+impl AccountPolicy {
+    /// Get the signature threshold required to debit the given amount of a
+    /// token, if it's greater than the account's threshold
+    pub fn required_threshold(
+        &self,
+        token: &Address,
+        amount: token::Amount,
+    ) -> Option<u8> {
+        self.high_value_thresholds
+            .get(token)
+            .filter(|high_value| amount > high_value.amount)
+            .map(|high_value| high_value.threshold)
+    }
+
+    /// Get the highest signature threshold set by the policy, if any. Changes
+    /// to the policy must be authorized with at least this threshold.
+    pub fn max_threshold(&self) -> Option<u8> {
+        self.high_value_thresholds
+            .values()
+            .map(|high_value| high_value.threshold)
+            .max()
+    }
+
+    /// Check if the given address may be credited with tokens debited from
+    /// the account
+    pub fn is_counterparty_allowed(&self, counterparty: &Address) -> bool {
+        self.allowed_counterparties.is_empty()
+            || self.allowed_counterparties.contains(counterparty)
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+/// Signature threshold required for debits above an amount
+pub struct HighValueThreshold {
+    /// The amount above which the threshold applies
+    pub amount: token::Amount,
+    /// The number of signatures required
+    pub threshold: u8,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// Amounts of a token debited from and credited to an account within an
+/// epoch, tracked for the tokens with a spend limit in the account's policy
+pub struct SpendingRecord {
+    /// The epoch of the last debit or credit
+    pub epoch: Epoch,
+    /// The amount debited in the epoch
+    pub spent: token::Amount,
+    /// The amount credited in the epoch, which offsets the debits
+    pub received: token::Amount,
+}
+
+impl SpendingRecord {
+    /// Get the amount spent in the given epoch, net of the amount received
+    pub fn spent_in(&self, epoch: Epoch) -> token::Amount {
+        if self.epoch == epoch {
+            self.spent.checked_sub(self.received).unwrap_or_default()
+        } else {
+            token::Amount::zero()
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+/// Timelock on the validity predicate updates of an account, enforced by the
+/// user VP. An update only takes effect after a delay, during which it can be
+/// cancelled with the recovery key.
+pub struct VpUpdateTimelock {
+    /// The number of epochs after which a scheduled update can be applied
+    pub delay: u64,
+    /// The key that can cancel a scheduled update and is required to change
+    /// the timelock
+    pub recovery_key: common::PublicKey,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A validity predicate update scheduled under a timelock
+pub struct PendingVpUpdate {
+    /// The hash of the new VP code
+    pub vp_code_hash: Hash,
+    /// The first epoch in which the update can be applied
+    pub activation_epoch: Epoch,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+/// Release schedule of the native tokens locked in a vesting account,
+/// enforced by the vesting VP. Nothing is released before the cliff epoch,
+/// after which the tokens are released linearly from the start epoch until
+/// the end epoch.
+pub struct VestingSchedule {
+    /// The amount of native tokens locked at the start of the schedule
+    pub total: token::Amount,
+    /// The epoch from which the tokens are released
+    pub start_epoch: Epoch,
+    /// The first epoch in which the released tokens can be spent
+    pub cliff_epoch: Epoch,
+    /// The epoch from which all the tokens are released
+    pub end_epoch: Epoch,
+}
+
+impl VestingSchedule {
+    /// Check that the epochs of the schedule are in order
+    pub fn is_valid(&self) -> bool {
+        self.start_epoch <= self.cliff_epoch
+            && self.cliff_epoch <= self.end_epoch
+    }
+
+    /// Get the amount of tokens that are still locked at the given epoch.
+    /// The released amount is rounded down.
+    pub fn locked_at(&self, epoch: Epoch) -> token::Amount {
+        if epoch < self.cliff_epoch {
+            return self.total;
+        }
+        if epoch >= self.end_epoch {
+            return token::Amount::zero();
+        }
+        let duration = self.end_epoch.0 - self.start_epoch.0;
+        let remaining = self.end_epoch.0 - epoch.0;
+        // total * remaining / duration, rounded up
+        let (locked, rem) = self.total * (remaining, duration);
+        let rem_locked = rem * remaining + token::Amount::from(duration - 1);
+        locked + rem_locked / duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the tokens of a vesting schedule are released linearly after
+    /// the cliff
+    #[test]
+    fn test_vesting_schedule_locked_at() {
+        let schedule = VestingSchedule {
+            total: token::Amount::from(1000_u64),
+            start_epoch: Epoch(10),
+            cliff_epoch: Epoch(12),
+            end_epoch: Epoch(20),
+        };
+        assert!(schedule.is_valid());
+        assert_eq!(schedule.locked_at(Epoch(0)), schedule.total);
+        assert_eq!(schedule.locked_at(Epoch(11)), schedule.total);
+        assert_eq!(schedule.locked_at(Epoch(12)), 800_u64.into());
+        assert_eq!(schedule.locked_at(Epoch(15)), 500_u64.into());
+        assert_eq!(schedule.locked_at(Epoch(20)), token::Amount::zero());
+        assert_eq!(schedule.locked_at(Epoch(30)), token::Amount::zero());
+
+        // The locked amount is rounded up
+        let schedule = VestingSchedule {
+            total: token::Amount::from(10_u64),
+            start_epoch: Epoch(0),
+            cliff_epoch: Epoch(0),
+            end_epoch: Epoch(3),
+        };
+        assert_eq!(schedule.locked_at(Epoch(1)), 7_u64.into());
+        assert_eq!(schedule.locked_at(Epoch(2)), 4_u64.into());
+
+        let schedule = VestingSchedule {
+            cliff_epoch: Epoch(4),
+            ..schedule
+        };
+        assert!(!schedule.is_valid());
+    }
+}
@@ -2,6 +2,7 @@
 //! Addresses](docs/src/explore/design/ledger/accounts.md#addresses).
 
 mod raw;
+pub mod validation;
 
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
@@ -124,6 +125,15 @@ impl From<raw::Address<'_, raw::Validated>> for Address {
                 InternalAddress::IbcToken(IbcTokenHash(*raw_addr.data())),
             ),
             raw::Discriminant::Masp => Address::Internal(InternalAddress::Masp),
+            raw::Discriminant::Oracle => {
+                Address::Internal(InternalAddress::Oracle)
+            }
+            raw::Discriminant::StorageDeposit => {
+                Address::Internal(InternalAddress::StorageDeposit)
+            }
+            raw::Discriminant::TestnetFaucet => {
+                Address::Internal(InternalAddress::TestnetFaucet)
+            }
         }
     }
 }
@@ -218,6 +228,25 @@ impl<'addr> From<&'addr Address> for raw::Address<'addr, raw::Validated> {
                     .validate()
                     .expect("This raw address is valid")
             }
+            Address::Internal(InternalAddress::Oracle) => {
+                raw::Address::from_discriminant(raw::Discriminant::Oracle)
+                    .validate()
+                    .expect("This raw address is valid")
+            }
+            Address::Internal(InternalAddress::StorageDeposit) => {
+                raw::Address::from_discriminant(
+                    raw::Discriminant::StorageDeposit,
+                )
+                .validate()
+                .expect("This raw address is valid")
+            }
+            Address::Internal(InternalAddress::TestnetFaucet) => {
+                raw::Address::from_discriminant(
+                    raw::Discriminant::TestnetFaucet,
+                )
+                .validate()
+                .expect("This raw address is valid")
+            }
         }
     }
 }
@@ -292,6 +321,15 @@ impl Address {
     pub fn is_implicit(&self) -> bool {
         matches!(self, Address::Implicit(_))
     }
+
+    /// The stable name of an internal address, if it's in the registry of
+    /// system accounts
+    pub fn internal_name(&self) -> Option<&'static str> {
+        match self {
+            Address::Internal(internal) => internal.name(),
+            _ => None,
+        }
+    }
 }
 
 impl string_encoding::Format for Address {
@@ -471,6 +509,23 @@ impl EstablishedAddressGen {
     }
 }
 
+/// Derive the established address of an account initialized by a
+/// transaction. The address only depends on the given entropy source, e.g. the
+/// data of the transaction, and on the number of accounts that the transaction
+/// initialized before, so it can be computed before the transaction is
+/// submitted.
+pub fn derive_established_address(
+    entropy_source: impl AsRef<[u8]>,
+    nonce: u64,
+) -> Address {
+    let bytes = [entropy_source.as_ref(), &nonce.to_le_bytes()].concat();
+    let full_hash = Sha256::digest(&bytes);
+    // take first 20 bytes of the hash
+    let mut hash: [u8; HASH_LEN] = Default::default();
+    hash.copy_from_slice(&full_hash[..HASH_LEN]);
+    Address::Established(EstablishedAddress { hash })
+}
+
 /// An implicit address is derived from a cryptographic key
 #[derive(
     Debug,
@@ -543,6 +598,12 @@ pub enum InternalAddress {
     Pgf,
     /// Masp
     Masp,
+    /// Oracle of externally attested data
+    Oracle,
+    /// Escrow of the storage deposits
+    StorageDeposit,
+    /// Faucet of the testnet tokens
+    TestnetFaucet,
 }
 
 impl Display for InternalAddress {
@@ -564,23 +625,47 @@ impl Display for InternalAddress {
                 Self::Multitoken => "Multitoken".to_string(),
                 Self::Pgf => "PublicGoodFundings".to_string(),
                 Self::Masp => "MASP".to_string(),
+                Self::Oracle => "Oracle".to_string(),
+                Self::StorageDeposit => "StorageDeposit".to_string(),
+                Self::TestnetFaucet => "TestnetFaucet".to_string(),
             }
         )
     }
 }
 
+/// The registry of the internal addresses of system accounts with their
+/// stable names. The names are reserved as aliases of the addresses.
+pub const INTERNAL_ADDRESS_NAMES: [(&str, InternalAddress); 13] = [
+    ("pos", InternalAddress::PoS),
+    ("slashpool", InternalAddress::PosSlashPool),
+    ("parameters", InternalAddress::Parameters),
+    ("ibc", InternalAddress::Ibc),
+    ("governance", InternalAddress::Governance),
+    ("ethbridge", InternalAddress::EthBridge),
+    ("bridgepool", InternalAddress::EthBridgePool),
+    ("multitoken", InternalAddress::Multitoken),
+    ("pgf", InternalAddress::Pgf),
+    ("masp", InternalAddress::Masp),
+    ("oracle", InternalAddress::Oracle),
+    ("storagedeposit", InternalAddress::StorageDeposit),
+    ("testnetfaucet", InternalAddress::TestnetFaucet),
+];
+
 impl InternalAddress {
     /// Certain internal addresses have reserved aliases.
     pub fn try_from_alias(alias: &str) -> Option<Self> {
-        match alias {
-            "pos" => Some(InternalAddress::PoS),
-            "ibc" => Some(InternalAddress::Ibc),
-            "ethbridge" => Some(InternalAddress::EthBridge),
-            "bridgepool" => Some(InternalAddress::EthBridgePool),
-            "governance" => Some(InternalAddress::Governance),
-            "masp" => Some(InternalAddress::Masp),
-            _ => None,
-        }
+        INTERNAL_ADDRESS_NAMES
+            .iter()
+            .find(|(name, _)| *name == alias)
+            .map(|(_, address)| address.clone())
+    }
+
+    /// The stable name of the address in the registry of system accounts
+    pub fn name(&self) -> Option<&'static str> {
+        INTERNAL_ADDRESS_NAMES
+            .iter()
+            .find(|(_, address)| address == self)
+            .map(|(name, _)| *name)
     }
 }
 
@@ -665,10 +750,23 @@ pub fn tokens() -> HashMap<&'static str, Denomination> {
 
 #[cfg(test)]
 pub mod tests {
+    use std::collections::HashSet;
+
     use proptest::prelude::*;
 
     use super::*;
 
+    /// Test that the derived established addresses only depend on the entropy
+    /// source and the nonce
+    #[test]
+    fn test_derive_established_address() {
+        let address = derive_established_address(b"tx data", 0);
+        assert!(matches!(address, Address::Established(_)));
+        assert_eq!(address, derive_established_address(b"tx data", 0));
+        assert_ne!(address, derive_established_address(b"tx data", 1));
+        assert_ne!(address, derive_established_address(b"other data", 0));
+    }
+
     /// Run `cargo test gen_established_address -- --nocapture` to generate a
     /// new established address.
     #[test]
@@ -689,6 +787,23 @@ pub mod tests {
         }
     }
 
+    /// Test that the names of the internal addresses are unique and resolve
+    /// back to their addresses
+    #[test]
+    fn test_internal_address_names() {
+        let names: HashSet<_> = INTERNAL_ADDRESS_NAMES
+            .iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names.len(), INTERNAL_ADDRESS_NAMES.len());
+        for (name, address) in INTERNAL_ADDRESS_NAMES {
+            assert_eq!(address.name(), Some(name));
+            assert_eq!(InternalAddress::try_from_alias(name), Some(address));
+        }
+        assert_eq!(GOV.internal_name(), Some("governance"));
+        assert_eq!(nam().internal_name(), None);
+    }
+
     #[test]
     fn test_address_serde_serialize() {
         let original_address =
@@ -864,6 +979,9 @@ pub mod testing {
             InternalAddress::Nut(_) => {}
             InternalAddress::Pgf => {}
             InternalAddress::Masp => {}
+            InternalAddress::Oracle => {}
+            InternalAddress::StorageDeposit => {}
+            InternalAddress::TestnetFaucet => {}
             InternalAddress::Multitoken => {} /* Add new addresses in the
                                                * `prop_oneof` below. */
         };
@@ -881,6 +999,9 @@ pub mod testing {
             Just(InternalAddress::Multitoken),
             Just(InternalAddress::Pgf),
             Just(InternalAddress::Masp),
+            Just(InternalAddress::Oracle),
+            Just(InternalAddress::StorageDeposit),
+            Just(InternalAddress::TestnetFaucet),
         ]
     }
 
@@ -65,6 +65,12 @@ pub enum Discriminant {
     IbcToken = 13,
     /// MASP raw address.
     Masp = 14,
+    /// Oracle raw address.
+    Oracle = 15,
+    /// Storage deposit escrow raw address.
+    StorageDeposit = 16,
+    /// Testnet faucet raw address.
+    TestnetFaucet = 17,
 }
 
 /// Raw address representation.
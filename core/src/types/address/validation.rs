@@ -0,0 +1,204 @@
+//! Validation of user supplied addresses, e.g. the deposit addresses given to
+//! an exchange. Only depends on `namada_core`, so that the addresses can be
+//! validated without the rest of the Namada crates.
+
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use super::{Address, ADDRESS_LEN};
+use crate::types::string_encoding::{ADDRESS_HRP, BECH32M_VARIANT};
+
+/// The coin type of Namada registered in
+/// [SLIP-0044](https://github.com/satoshilabs/slips/blob/master/slip-0044.md),
+/// used in the BIP44 derivation paths of the keys of implicit addresses
+pub const SLIP44_COIN_TYPE: u32 = 877;
+
+/// The kind of an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressKind {
+    /// An implicit address, derived from a public key
+    Implicit,
+    /// An established address, generated on-chain
+    Established,
+    /// An internal address of a native module
+    Internal,
+}
+
+impl Display for AddressKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressKind::Implicit => write!(f, "Implicit"),
+            AddressKind::Established => write!(f, "Established"),
+            AddressKind::Internal => write!(f, "Internal"),
+        }
+    }
+}
+
+impl From<&Address> for AddressKind {
+    fn from(address: &Address) -> Self {
+        match address {
+            Address::Implicit(_) => AddressKind::Implicit,
+            Address::Established(_) => AddressKind::Established,
+            Address::Internal(_) => AddressKind::Internal,
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ValidationError {
+    #[error("Unexpected address length {0}, expected {ADDRESS_LEN}")]
+    Length(usize),
+    #[error("Invalid Bech32m encoding or checksum: {0}")]
+    Checksum(bech32::Error),
+    #[error("Unexpected Bech32m variant {0:?}, expected {BECH32M_VARIANT:?}")]
+    Variant(bech32::Variant),
+    #[error("Unexpected human-readable part {0}, expected {ADDRESS_HRP}")]
+    Hrp(String),
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("{0} addresses cannot receive deposits")]
+    NotDepositAddress(AddressKind),
+}
+
+/// Check the length, human-readable part and Bech32m checksum of an encoded
+/// address, without decoding its contents
+pub fn verify_checksum(address: &str) -> Result<(), ValidationError> {
+    if address.len() != ADDRESS_LEN {
+        return Err(ValidationError::Length(address.len()));
+    }
+    let (hrp, _data, variant) =
+        bech32::decode(address).map_err(ValidationError::Checksum)?;
+    if variant != BECH32M_VARIANT {
+        return Err(ValidationError::Variant(variant));
+    }
+    if hrp != ADDRESS_HRP {
+        return Err(ValidationError::Hrp(hrp));
+    }
+    Ok(())
+}
+
+/// Parse an encoded address. Surrounding whitespace is not accepted.
+pub fn parse(address: &str) -> Result<Address, ValidationError> {
+    verify_checksum(address)?;
+    Address::decode(address)
+        .map_err(|err| ValidationError::InvalidAddress(err.to_string()))
+}
+
+/// Parse an encoded address and return its kind
+pub fn classify(address: &str) -> Result<AddressKind, ValidationError> {
+    parse(address).map(|address| AddressKind::from(&address))
+}
+
+/// Parse an encoded address that can receive deposits from users, i.e. an
+/// implicit or an established address
+pub fn parse_deposit_address(
+    address: &str,
+) -> Result<Address, ValidationError> {
+    let address = parse(address)?;
+    match AddressKind::from(&address) {
+        AddressKind::Implicit | AddressKind::Established => Ok(address),
+        kind => Err(ValidationError::NotDepositAddress(kind)),
+    }
+}
+
+/// Format an address for display in a limited width. The middle of the
+/// encoded address is elided if it's longer than `max_chars`, keeping its
+/// human-readable part and its last characters, e.g. `tnam1qxf...x6tw5`.
+pub fn format_for_display(address: &Address, max_chars: usize) -> String {
+    const ELLIPSIS: &str = "...";
+    const SUFFIX_LEN: usize = 5;
+    let encoded = address.encode();
+    if encoded.len() <= max_chars {
+        return encoded;
+    }
+    // Keep at least the human-readable part and its separator
+    let prefix_len = max_chars
+        .saturating_sub(ELLIPSIS.len() + SUFFIX_LEN)
+        .max(ADDRESS_HRP.len() + 1);
+    format!(
+        "{}{ELLIPSIS}{}",
+        &encoded[..prefix_len],
+        &encoded[encoded.len() - SUFFIX_LEN..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::types::address::testing;
+
+    /// Test the validation errors of malformed addresses
+    #[test]
+    fn test_validation_errors() {
+        let valid = "tnam1q8j5s6xp55p05yznwnftkv3kr9gjtsw3nq7x6tw5";
+        assert_eq!(classify(valid).unwrap(), AddressKind::Established);
+
+        // A typo breaks the checksum
+        let typo = valid.replacen("q8j5", "q8j6", 1);
+        assert!(matches!(parse(&typo), Err(ValidationError::Checksum(_))));
+        assert!(matches!(
+            parse(&format!(" {valid}")),
+            Err(ValidationError::Length(_))
+        ));
+        assert!(matches!(
+            parse(&valid.to_uppercase().replacen('Q', "q", 1)),
+            Err(ValidationError::Checksum(_))
+        ));
+        assert!(matches!(
+            parse_deposit_address(&crate::types::address::POS.encode()),
+            Err(ValidationError::NotDepositAddress(AddressKind::Internal))
+        ));
+    }
+
+    /// Test that the long addresses are shortened to the maximum width
+    #[test]
+    fn test_format_for_display() {
+        let address = testing::established_address_1();
+        let encoded = address.encode();
+        assert_eq!(format_for_display(&address, ADDRESS_LEN), encoded);
+        let short = format_for_display(&address, 16);
+        assert_eq!(short.len(), 16);
+        assert!(short.starts_with("tnam1"));
+        assert!(short.ends_with(&encoded[ADDRESS_LEN - 5..]));
+    }
+
+    proptest! {
+        /// Check that the validation of any string never panics
+        #[test]
+        fn test_parse_arbitrary_string(address in ".*") {
+            let _ = parse(&address);
+            let _ = parse_deposit_address(&address);
+        }
+
+        /// Check that the encoding of any address is valid and classified as
+        /// its kind
+        #[test]
+        fn test_parse_encoded_address(address in testing::arb_address()) {
+            let encoded = address.encode();
+            prop_assert_eq!(parse(&encoded).unwrap(), address.clone());
+            prop_assert_eq!(
+                classify(&encoded).unwrap(),
+                AddressKind::from(&address)
+            );
+        }
+
+        /// Check that corrupting any character of an encoded address is
+        /// detected
+        #[test]
+        fn test_parse_corrupted_address(
+            address in testing::arb_address(),
+            index in 0..ADDRESS_LEN,
+            replacement in proptest::char::range('0', 'z'),
+        ) {
+            let mut encoded: Vec<char> = address.encode().chars().collect();
+            prop_assume!(encoded[index] != replacement);
+            encoded[index] = replacement;
+            let corrupted: String = encoded.into_iter().collect();
+            prop_assert!(parse(&corrupted).is_err());
+        }
+    }
+}
@@ -384,3 +384,23 @@ impl super::SigScheme for SigScheme {
             .map_err(|err| VerifySigError::SigVerifyError(err.to_string()))
     }
 }
+
+/// Verify the given signatures over the given messages in a batch, which is
+/// faster than verifying them one by one. If the batch fails, at least one of
+/// the signatures is invalid, but it is not known which one.
+#[cfg(feature = "rand")]
+pub fn verify_batch<'a, R>(
+    signatures: impl IntoIterator<Item = (&'a PublicKey, &'a [u8], &'a Signature)>,
+    rng: R,
+) -> Result<(), VerifySigError>
+where
+    R: CryptoRng + RngCore,
+{
+    let mut verifier = ed25519_consensus::batch::Verifier::new();
+    for (pk, msg, sig) in signatures {
+        verifier.queue((pk.0.into(), sig.0, msg));
+    }
+    verifier
+        .verify(rng)
+        .map_err(|err| VerifySigError::SigVerifyError(err.to_string()))
+}
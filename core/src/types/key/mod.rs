@@ -31,6 +31,13 @@ struct Keys {
     public_keys: &'static str,
     threshold: &'static str,
     protocol_public_keys: &'static str,
+    policy: &'static str,
+    spending: &'static str,
+    sequence: &'static str,
+    vp_timelock: &'static str,
+    pending_vp: &'static str,
+    allowed_txs: &'static str,
+    vesting: &'static str,
 }
 
 /// Obtain a storage key for user's public key.
@@ -88,6 +95,170 @@ pub fn threshold_key(owner: &Address) -> storage::Key {
     }
 }
 
+/// Obtain the storage key for a user authorization policy
+pub fn policy_key(owner: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.policy.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is a policy key. If it is, returns the
+/// owner.
+pub fn is_policy_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.policy =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key for the amount of a token spent by a user in the
+/// current epoch
+pub fn spending_key(owner: &Address, token: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.spending.to_string()),
+            DbKeySeg::AddressSeg(token.to_owned()),
+        ],
+    }
+}
+
+/// Check if the given storage key is a spending key. If it is, returns the
+/// owner and the token.
+pub fn is_spending_key(key: &Key) -> Option<[&Address; 2]> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix), DbKeySeg::AddressSeg(token)]
+            if prefix.as_str() == Keys::VALUES.spending =>
+        {
+            Some([owner, token])
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key for the next sequence number of a fee payer
+pub fn sequence_key(owner: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.sequence.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is a sequence key. If it is, returns the
+/// owner.
+pub fn is_sequence_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.sequence =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key for the timelock on the VP updates of a user
+pub fn vp_timelock_key(owner: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.vp_timelock.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is a VP timelock key. If it is, returns the
+/// owner.
+pub fn is_vp_timelock_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.vp_timelock =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key for the VP update scheduled by a user
+pub fn pending_vp_key(owner: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.pending_vp.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is a pending VP update key. If it is,
+/// returns the owner.
+pub fn is_pending_vp_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.pending_vp =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key for the tx code hashes allowed to operate on the
+/// account of a user
+pub fn allowed_txs_key(owner: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.allowed_txs.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is an allowed txs key. If it is, returns the
+/// owner.
+pub fn is_allowed_txs_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.allowed_txs =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain the storage key for the vesting schedule of a user
+pub fn vesting_key(owner: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.vesting.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is a vesting key. If it is, returns the
+/// owner.
+pub fn is_vesting_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(prefix)]
+            if prefix.as_str() == Keys::VALUES.vesting =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
 /// Obtain a storage key for user's protocol public key.
 pub fn protocol_pk_key(owner: &Address) -> storage::Key {
     Key {
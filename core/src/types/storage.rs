@@ -78,6 +78,10 @@ pub const WASM_CODE_NAME_PREFIX: &str = "name";
 pub const WASM_CODE_LEN_PREFIX: &str = "len";
 /// The reserved storage key prefix for wasm code hashes
 pub const WASM_HASH_PREFIX: &str = "hash";
+/// The reserved storage key prefix for gas-instrumented wasm codes
+pub const WASM_INSTRUMENTED_CODE_PREFIX: &str = "instrumented_code";
+/// The reserved storage key prefix for gas-instrumented wasm code hashes
+pub const WASM_INSTRUMENTED_HASH_PREFIX: &str = "instrumented_hash";
 
 /// Transaction index within block.
 #[derive(
@@ -350,7 +354,15 @@ impl core::fmt::Debug for BlockHash {
 
 /// The data from Tendermint header
 /// relevant for Namada storage
-#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Default)]
+#[derive(
+    Clone,
+    Debug,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Default,
+)]
 pub struct Header {
     /// Merkle root hash of block
     pub hash: Hash,
@@ -639,6 +651,30 @@ impl Key {
         Key { segments }
     }
 
+    /// Returns a key of the gas-instrumented wasm code of the given raw code
+    /// hash
+    pub fn wasm_instrumented_code(code_hash: &Hash) -> Self {
+        let mut segments =
+            Self::from(WASM_KEY_PREFIX.to_owned().to_db_key()).segments;
+        segments.push(DbKeySeg::StringSeg(
+            WASM_INSTRUMENTED_CODE_PREFIX.to_owned(),
+        ));
+        segments.push(DbKeySeg::StringSeg(code_hash.to_string()));
+        Key { segments }
+    }
+
+    /// Returns a key of the hash of the gas-instrumented wasm code of the
+    /// given raw code hash
+    pub fn wasm_instrumented_hash(code_hash: &Hash) -> Self {
+        let mut segments =
+            Self::from(WASM_KEY_PREFIX.to_owned().to_db_key()).segments;
+        segments.push(DbKeySeg::StringSeg(
+            WASM_INSTRUMENTED_HASH_PREFIX.to_owned(),
+        ));
+        segments.push(DbKeySeg::StringSeg(code_hash.to_string()));
+        Key { segments }
+    }
+
     /// Returns a key of the validity predicate of the given address
     /// Only this function can push "?" segment for validity predicate
     pub fn validity_predicate(addr: &Address) -> Self {
@@ -773,6 +809,17 @@ pub trait KeySeg {
     fn to_db_key(&self) -> DbKeySeg;
 }
 
+/// A key segment whose encoding preserves the ordering of `Self`, i.e. the
+/// storage iterates over the keys with such segments in the order of their
+/// values. The lazy collections iterate in this order, so the encodings are
+/// consensus-critical and must never change. They are pinned by the test
+/// vectors of this module.
+///
+/// The integers are encoded as the base32hex of their fixed-width big-endian
+/// bytes, with the signed integers complemented with their min value, and the
+/// [`LengthPrefixedString`]s with their length encoded as a `u32` first.
+pub trait OrderedKeySeg: KeySeg + Ord {}
+
 /// A storage key segment
 #[derive(
     Clone,
@@ -843,12 +890,8 @@ impl KeySeg for String {
 
 impl KeySeg for BlockHeight {
     fn parse(string: String) -> Result<Self> {
-        let h = string.parse::<u64>().map_err(|e| {
-            Error::ParseKeySeg(format!(
-                "Unexpected height value {}, {}",
-                string, e
-            ))
-        })?;
+        // The height is encoded as a `u64` key segment, like an `Epoch`
+        let h = u64::parse(string)?;
         Ok(BlockHeight(h))
     }
 
@@ -923,20 +966,40 @@ macro_rules! impl_int_key_seg {
     ($unsigned:ty, $signed:ty, $len:literal) => {
         impl KeySeg for $unsigned {
             fn parse(string: String) -> Result<Self> {
-                let bytes =
-                    BASE32HEX_NOPAD.decode(string.as_ref()).map_err(|err| {
-                        Error::ParseKeySeg(format!(
-                            "Failed parsing {} with {}",
-                            string, err
-                        ))
-                    })?;
+                // decode straight into the fixed-width bytes, without an
+                // intermediate allocation
+                let parse_err = |err: &dyn Display| {
+                    Error::ParseKeySeg(format!(
+                        "Failed parsing {} with {}",
+                        string, err
+                    ))
+                };
+                let len = BASE32HEX_NOPAD
+                    .decode_len(string.len())
+                    .map_err(|err| parse_err(&err))?;
+                if len != $len {
+                    return Err(parse_err(&format!(
+                        "unexpected length {len}, expected {}",
+                        $len
+                    )));
+                }
                 let mut fixed_bytes = [0; $len];
-                fixed_bytes.copy_from_slice(&bytes);
+                BASE32HEX_NOPAD
+                    .decode_mut(string.as_bytes(), &mut fixed_bytes)
+                    .map_err(|partial| parse_err(&partial.error))?;
                 Ok(<$unsigned>::from_be_bytes(fixed_bytes))
             }
 
             fn raw(&self) -> String {
-                BASE32HEX_NOPAD.encode(&self.to_be_bytes())
+                // encode into a fixed-width buffer on the stack (26 chars
+                // fit the widest int), allocating only the returned string
+                let mut buf = [0; 26];
+                let len = BASE32HEX_NOPAD.encode_len($len);
+                BASE32HEX_NOPAD
+                    .encode_mut(&self.to_be_bytes(), &mut buf[..len]);
+                std::str::from_utf8(&buf[..len])
+                    .expect("base32hex must be valid ASCII")
+                    .to_owned()
             }
 
             fn to_db_key(&self) -> DbKeySeg {
@@ -964,6 +1027,10 @@ macro_rules! impl_int_key_seg {
                 DbKeySeg::StringSeg(self.raw())
             }
         }
+
+        impl OrderedKeySeg for $unsigned {}
+
+        impl OrderedKeySeg for $signed {}
     };
 }
 
@@ -983,7 +1050,9 @@ impl KeySeg for Epoch {
     }
 
     fn raw(&self) -> String {
-        self.to_string()
+        // The epoch is encoded as a `u64` key segment, so that it's parsed
+        // back from its raw string
+        self.0.raw()
     }
 
     fn to_db_key(&self) -> DbKeySeg {
@@ -991,6 +1060,84 @@ impl KeySeg for Epoch {
     }
 }
 
+impl OrderedKeySeg for Epoch {}
+
+impl OrderedKeySeg for BlockHeight {}
+
+/// A string key segment prefixed with its length, ordered by its length first
+/// and then by its bytes. Unlike a [`String`] segment, the order of its keys
+/// doesn't depend on the sub-keys of a nested collection, e.g. the sub-keys
+/// of `"a"` (`"a/..."`) come after `"a-"`, as `'-' < '/'`, even though
+/// `"a" < "a-"`.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct LengthPrefixedString(pub String);
+
+impl LengthPrefixedString {
+    /// The length of the encoded `u32` length prefix
+    const PREFIX_LEN: usize = 7;
+}
+
+impl Ord for LengthPrefixedString {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for LengthPrefixedString {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<String> for LengthPrefixedString {
+    fn from(string: String) -> Self {
+        Self(string)
+    }
+}
+
+impl KeySeg for LengthPrefixedString {
+    fn parse(string: String) -> Result<Self> {
+        if !string.is_char_boundary(Self::PREFIX_LEN) {
+            return Err(Error::ParseKeySeg(format!(
+                "Missing the length prefix of {string}"
+            )));
+        }
+        let (prefix, value) = string.split_at(Self::PREFIX_LEN);
+        let len = u32::parse(prefix.to_string())?;
+        if len as usize != value.len() {
+            return Err(Error::ParseKeySeg(format!(
+                "The length prefix of {string} doesn't match its length"
+            )));
+        }
+        Ok(Self(value.to_string()))
+    }
+
+    fn raw(&self) -> String {
+        let len = u32::try_from(self.0.len())
+            .expect("A key segment should fit in u32::MAX bytes");
+        format!("{}{}", len.raw(), self.0)
+    }
+
+    fn to_db_key(&self) -> DbKeySeg {
+        DbKeySeg::StringSeg(self.raw())
+    }
+}
+
+impl OrderedKeySeg for LengthPrefixedString {}
+
 impl KeySeg for common::PublicKey {
     fn parse(string: String) -> Result<Self>
     where
@@ -1859,6 +2006,110 @@ mod tests {
     }
 }
 
+/// Test vectors of the encodings of the [`OrderedKeySeg`]s. A failure means
+/// that an encoding changed, which changes the iteration order and the keys
+/// of the existing state.
+#[cfg(test)]
+mod test_key_seg_vectors {
+    use std::fmt::Debug;
+
+    use super::*;
+
+    /// Check that the values are encoded as the expected segments, parsed
+    /// back and that both are sorted in the same order
+    fn check_vectors<T>(vectors: &[(T, &str)])
+    where
+        T: OrderedKeySeg + Debug,
+    {
+        for (value, expected) in vectors {
+            assert_eq!(
+                value.to_db_key(),
+                DbKeySeg::StringSeg(expected.to_string()),
+                "The encoding of {value:?} changed"
+            );
+            assert_eq!(&T::parse(expected.to_string()).unwrap(), value);
+        }
+        for pair in vectors.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+            assert!(pair[0].1 < pair[1].1);
+        }
+    }
+
+    #[test]
+    fn test_unsigned_int_vectors() {
+        check_vectors(&[(0_u8, "00"), (1, "04"), (u8::MAX, "VS")]);
+        check_vectors(&[(1_u16, "000G"), (256, "0400")]);
+        check_vectors(&[(0_u32, "0000000"), (1, "0000008")]);
+        check_vectors(&[
+            (0_u64, "0000000000000"),
+            (1, "0000000000002"),
+            (1000, "00000000001UG"),
+            (u64::MAX, "VVVVVVVVVVVVU"),
+        ]);
+        check_vectors(&[(1_u128, "00000000000000000000000004")]);
+    }
+
+    #[test]
+    fn test_signed_int_vectors() {
+        check_vectors(&[
+            (i64::MIN, "0000000000000"),
+            (-1, "FVVVVVVVVVVVU"),
+            (0, "G000000000000"),
+            (1, "G000000000002"),
+            (i64::MAX, "VVVVVVVVVVVVU"),
+        ]);
+    }
+
+    #[test]
+    fn test_epoch_and_height_vectors() {
+        check_vectors(&[
+            (Epoch(0), "0000000000000"),
+            (Epoch(1000), "00000000001UG"),
+        ]);
+        check_vectors(&[
+            (BlockHeight(1), "0000000000002"),
+            (BlockHeight(1000), "00000000001UG"),
+        ]);
+    }
+
+    #[test]
+    fn test_invalid_int_key_segs() {
+        // The decimal encoding isn't accepted
+        assert!(u64::parse("1000".to_string()).is_err());
+        assert!(BlockHeight::parse("1000".to_string()).is_err());
+        // Neither is the encoding of an int of another width
+        assert!(u64::parse("0000008".to_string()).is_err());
+        assert!(u32::parse("0000000000002".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_int_key_segs_round_trip() {
+        for value in [0, 1, 1000, u64::MAX] {
+            assert_eq!(u64::parse(value.raw()).unwrap(), value);
+            assert_eq!(Epoch::parse(Epoch(value).raw()).unwrap(), Epoch(value));
+            assert_eq!(
+                BlockHeight::parse(BlockHeight(value).raw()).unwrap(),
+                BlockHeight(value)
+            );
+        }
+        assert_eq!(u128::MAX.raw().len(), 26);
+        assert_eq!(i128::parse(i128::MIN.raw()).unwrap(), i128::MIN);
+    }
+
+    #[test]
+    fn test_length_prefixed_string_vectors() {
+        check_vectors(&[
+            (LengthPrefixedString::default(), "0000000"),
+            (LengthPrefixedString("a".to_string()), "0000008a"),
+            (LengthPrefixedString("a-".to_string()), "000000Ga-"),
+            (LengthPrefixedString("ab".to_string()), "000000Gab"),
+            (LengthPrefixedString("zz".to_string()), "000000Gzz"),
+        ]);
+        assert!(LengthPrefixedString::parse("000000Ga".to_string()).is_err());
+        assert!(LengthPrefixedString::parse("a".to_string()).is_err());
+    }
+}
+
 /// Helpers for testing with storage types.
 #[cfg(any(test, feature = "testing"))]
 pub mod testing {
@@ -230,7 +230,7 @@ impl Amount {
         storage: &impl StorageRead,
     ) -> storage_api::Result<DenominatedAmount> {
         let denom = read_denom(storage, token)?.ok_or_else(|| {
-            storage_api::Error::SimpleMessage(
+            storage_api::Error::new_const(
                 "No denomination found in storage for the given token",
             )
         })?;
@@ -528,9 +528,11 @@ impl<'de> serde::Deserialize<'de> for Amount {
     where
         D: serde::Deserializer<'de>,
     {
+        use serde::de::Error;
         let amount_string: String =
             serde::Deserialize::deserialize(deserializer)?;
-        let amt = DenominatedAmount::from_str(&amount_string).unwrap();
+        let amt = DenominatedAmount::from_str(&amount_string)
+            .map_err(D::Error::custom)?;
         Ok(amt.amount)
     }
 }
@@ -896,10 +898,14 @@ impl From<DenominatedAmount> for IbcAmount {
 pub const BALANCE_STORAGE_KEY: &str = "balance";
 /// Key segment for a denomination key
 pub const DENOM_STORAGE_KEY: &str = "denomination";
+/// Key segment for the display metadata of a token
+pub const METADATA_STORAGE_KEY: &str = "metadata";
 /// Key segment for multitoken minter
 pub const MINTER_STORAGE_KEY: &str = "minter";
 /// Key segment for minted balance
 pub const MINTED_STORAGE_KEY: &str = "minted";
+/// Key segment for the allowances given by an owner to a spender
+pub const ALLOWANCE_STORAGE_KEY: &str = "allowance";
 /// Key segment for head shielded transaction pointer keys
 pub const HEAD_TX_KEY: &str = "head-tx";
 /// Key segment prefix for shielded transaction key
@@ -939,20 +945,61 @@ pub fn balance_key(token_addr: &Address, owner: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
-/// Obtain a storage key prefix for all users' balances.
-pub fn balance_prefix(token_addr: &Address) -> Key {
+/// Obtain the storage key prefix of a token in the multitoken account. All
+/// the tokens (native, IBC and wrapped ERC20 tokens) share the same layout
+/// under the `#Multitoken` address, i.e. `#Multitoken/#token/balance/#owner`,
+/// and are validated by the same native VP.
+pub fn multitoken_prefix(token_addr: &Address) -> Key {
     Key::from(Address::Internal(InternalAddress::Multitoken).to_db_key())
         .push(&token_addr.to_db_key())
         .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all users' balances.
+pub fn balance_prefix(token_addr: &Address) -> Key {
+    multitoken_prefix(token_addr)
+        .push(&BALANCE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain the storage key prefix of a sub-token of a token in the multitoken
+/// account, i.e. `#Multitoken/#token/{sub_prefix}`. A sub-token, e.g. a
+/// denomination of an IBC token, has its own balances and supply, laid out
+/// like the ones of its token under the sub-prefix and minted by the minter of
+/// its token. The sub-prefix must only contain string segments.
+pub fn sub_token_prefix(token_addr: &Address, sub_prefix: &Key) -> Key {
+    multitoken_prefix(token_addr).join(sub_prefix)
+}
+
+/// Obtain a storage key for user's balance of a sub-token, i.e.
+/// `#Multitoken/#token/{sub_prefix}/balance/#owner`.
+pub fn sub_token_balance_key(
+    token_addr: &Address,
+    sub_prefix: &Key,
+    owner: &Address,
+) -> Key {
+    sub_token_prefix(token_addr, sub_prefix)
         .push(&BALANCE_STORAGE_KEY.to_owned())
         .expect("Cannot obtain a storage key")
+        .push(&owner.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the minted balance of a sub-token.
+pub fn sub_token_minted_balance_key(
+    token_addr: &Address,
+    sub_prefix: &Key,
+) -> Key {
+    sub_token_prefix(token_addr, sub_prefix)
+        .push(&BALANCE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&MINTED_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
 }
 
 /// Obtain a storage key for the multitoken minter.
 pub fn minter_key(token_addr: &Address) -> Key {
-    Key::from(Address::Internal(InternalAddress::Multitoken).to_db_key())
-        .push(&token_addr.to_db_key())
-        .expect("Cannot obtain a storage key")
+    multitoken_prefix(token_addr)
         .push(&MINTER_STORAGE_KEY.to_owned())
         .expect("Cannot obtain a storage key")
 }
@@ -964,6 +1011,22 @@ pub fn minted_balance_key(token_addr: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Obtain a storage key for the amount of a token that the `spender` is
+/// allowed to transfer from the balance of the `owner`.
+pub fn allowance_key(
+    token_addr: &Address,
+    owner: &Address,
+    spender: &Address,
+) -> Key {
+    multitoken_prefix(token_addr)
+        .push(&ALLOWANCE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&owner.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&spender.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Obtain the nominal proportional key for the given token
 pub fn masp_kp_gain_key(token_addr: &Address) -> Key {
     key_of_token(token_addr, MASP_KP_GAIN_KEY, "nominal proproitonal gains")
@@ -1069,6 +1132,86 @@ impl Default for Parameters {
     }
 }
 
+/// Check if the given storage key belongs to a token in the multitoken
+/// account. If it is, returns the token address.
+pub fn is_any_multitoken_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::AddressSeg(token), ..]
+            if *addr == Address::Internal(InternalAddress::Multitoken) =>
+        {
+            Some(token)
+        }
+        _ => None,
+    }
+}
+
+/// Split a storage key under the balances of a sub-token into its token, its
+/// sub-prefix and the last segment of the key, i.e. the owner or the minted
+/// balance.
+fn split_sub_token_balance_key(
+    key: &Key,
+) -> Option<(&Address, Key, &DbKeySeg)> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::AddressSeg(token), sub_prefix @ .., DbKeySeg::StringSeg(balance), last]
+            if *addr == Address::Internal(InternalAddress::Multitoken)
+                && balance == BALANCE_STORAGE_KEY
+                && !sub_prefix.is_empty()
+                && sub_prefix
+                    .iter()
+                    .all(|seg| matches!(seg, DbKeySeg::StringSeg(_))) =>
+        {
+            let sub_prefix = Key {
+                segments: sub_prefix.to_vec(),
+            };
+            Some((token, sub_prefix, last))
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is a balance key of a sub-token of
+/// unspecified token. If it is, returns the token, the sub-prefix and the
+/// owner.
+pub fn is_any_sub_token_balance_key(
+    key: &Key,
+) -> Option<(&Address, Key, &Address)> {
+    match split_sub_token_balance_key(key)? {
+        (token, sub_prefix, DbKeySeg::AddressSeg(owner)) => {
+            Some((token, sub_prefix, owner))
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is for the total supply of a sub-token of
+/// unspecified token. If it is, returns the token and the sub-prefix.
+pub fn is_any_sub_token_minted_balance_key(
+    key: &Key,
+) -> Option<(&Address, Key)> {
+    match split_sub_token_balance_key(key)? {
+        (token, sub_prefix, DbKeySeg::StringSeg(minted))
+            if minted == MINTED_STORAGE_KEY =>
+        {
+            Some((token, sub_prefix))
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is a balance key of a sub-token of the given
+/// token. If it is, returns the sub-prefix and the owner.
+pub fn is_multitoken_balance_key<'a>(
+    token_addr: &Address,
+    key: &'a Key,
+) -> Option<(Key, &'a Address)> {
+    match is_any_sub_token_balance_key(key)? {
+        (token, sub_prefix, owner) if token == token_addr => {
+            Some((sub_prefix, owner))
+        }
+        _ => None,
+    }
+}
+
 /// Check if the given storage key is balance key for the given token. If it is,
 /// returns the owner. For minted balances, use [`is_any_minted_balance_key()`].
 pub fn is_balance_key<'a>(
@@ -1109,6 +1252,20 @@ pub fn is_any_token_balance_key(key: &Key) -> Option<[&Address; 2]> {
     }
 }
 
+/// Check if the given storage key is an allowance key for unspecified token.
+/// If it is, returns the token, owner and spender addresses.
+pub fn is_any_allowance_key(key: &Key) -> Option<[&Address; 3]> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::AddressSeg(token), DbKeySeg::StringSeg(allowance), DbKeySeg::AddressSeg(owner), DbKeySeg::AddressSeg(spender)]
+            if *addr == Address::Internal(InternalAddress::Multitoken)
+                && allowance == ALLOWANCE_STORAGE_KEY =>
+        {
+            Some([token, owner, spender])
+        }
+        _ => None,
+    }
+}
+
 /// Obtain a storage key denomination of a token.
 pub fn denom_key(token_addr: &Address) -> Key {
     Key::from(token_addr.to_db_key())
@@ -1126,6 +1283,24 @@ pub fn is_denom_key(token_addr: &Address, key: &Key) -> bool {
         ] if key == DENOM_STORAGE_KEY && addr == token_addr)
 }
 
+/// Obtain a storage key for the display metadata of a token.
+pub fn metadata_key(token_addr: &Address) -> Key {
+    key_of_token(
+        token_addr,
+        METADATA_STORAGE_KEY,
+        "Cannot obtain a storage key",
+    )
+}
+
+/// Check if the given storage key is a metadata key for the given token.
+pub fn is_metadata_key(token_addr: &Address, key: &Key) -> bool {
+    matches!(&key.segments[..],
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(key),
+        ] if key == METADATA_STORAGE_KEY && addr == token_addr)
+}
+
 /// Check if the given storage key is a masp key
 pub fn is_masp_key(key: &Key) -> bool {
     matches!(&key.segments[..],
@@ -1136,6 +1311,27 @@ pub fn is_masp_key(key: &Key) -> bool {
                     || key.starts_with(PIN_KEY_PREFIX)))
 }
 
+/// Obtain the storage key of the index of the next MASP tx to be recorded
+pub fn masp_head_tx_key() -> Key {
+    Key::from(MASP.to_db_key())
+        .push(&HEAD_TX_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain the storage key of the MASP tx recorded at the given index
+pub fn masp_tx_key(index: u64) -> Key {
+    Key::from(MASP.to_db_key())
+        .push(&(TX_KEY_PREFIX.to_owned() + &index.to_string()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain the storage key of the index of the MASP tx pinned to the given key
+pub fn masp_pin_tx_key(key: &str) -> Key {
+    Key::from(MASP.to_db_key())
+        .push(&(PIN_KEY_PREFIX.to_owned() + key))
+        .expect("Cannot obtain a storage key")
+}
+
 /// Obtain the storage key for the last locked ratio of a token
 pub fn masp_last_locked_ratio_key(token_address: &Address) -> Key {
     key_of_token(
@@ -1219,6 +1415,78 @@ pub struct Transfer {
     pub shielded: Option<Hash>,
 }
 
+/// The display metadata of a token, used by clients to render amounts
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct Metadata {
+    /// The ticker symbol of the token, e.g. `NAM`
+    pub symbol: String,
+    /// The human readable name of the token
+    pub display_name: String,
+}
+
+/// An approval for a spender to transfer up to the given amount of tokens
+/// from the balance of the owner
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct Approval {
+    /// The owner of the tokens
+    pub owner: Address,
+    /// The address allowed to spend the tokens
+    pub spender: Address,
+    /// Token's address
+    pub token: Address,
+    /// The allowed amount of tokens. It replaces any previous allowance.
+    pub amount: DenominatedAmount,
+}
+
+/// A token transfer made by a spender, on behalf of the source, out of the
+/// allowance given to it by the source
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransferFrom {
+    /// The address spending the allowance
+    pub spender: Address,
+    /// Source address whose tokens are spent
+    pub source: Address,
+    /// Target address will receive the tokens
+    pub target: Address,
+    /// Token's address
+    pub token: Address,
+    /// The amount of tokens
+    pub amount: DenominatedAmount,
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum TransferError {
@@ -1319,6 +1587,9 @@ mod tests {
         );
         let serialized = serde_json::to_string(&amount).unwrap();
         assert_eq!(serialized, r#""1000000000""#);
+
+        // An invalid amount is an error rather than a panic
+        assert!(serde_json::from_str::<Amount>(r#""1a""#).is_err());
     }
 
     #[test]
@@ -1398,6 +1669,78 @@ mod tests {
         assert_eq!(amount, original);
     }
 
+    #[test]
+    fn test_multitoken_keys() {
+        let ibc_token =
+            crate::ledger::ibc::storage::ibc_token("transfer/channel-0/uatom");
+        let owner = crate::types::address::testing::established_address_1();
+        let spender = crate::types::address::testing::established_address_2();
+
+        let balance = balance_key(&ibc_token, &owner);
+        assert_eq!(
+            is_any_token_balance_key(&balance),
+            Some([&ibc_token, &owner])
+        );
+        assert_eq!(is_any_multitoken_key(&balance), Some(&ibc_token));
+        assert!(balance
+            .split_prefix(&multitoken_prefix(&ibc_token))
+            .is_some());
+        assert_eq!(
+            is_any_multitoken_key(&minted_balance_key(&ibc_token)),
+            Some(&ibc_token)
+        );
+        assert_eq!(
+            is_any_multitoken_key(&allowance_key(&ibc_token, &owner, &spender)),
+            Some(&ibc_token)
+        );
+        // token keys outside of the multitoken account are not matched
+        assert_eq!(is_any_multitoken_key(&denom_key(&ibc_token)), None);
+    }
+
+    #[test]
+    fn test_sub_token_keys() {
+        let token = crate::types::address::nam();
+        let sub_prefix = Key::parse("transfer/channel-0/uatom").unwrap();
+        let owner = crate::types::address::testing::established_address_1();
+        let spender = crate::types::address::testing::established_address_2();
+
+        let balance = sub_token_balance_key(&token, &sub_prefix, &owner);
+        assert!(balance
+            .split_prefix(&sub_token_prefix(&token, &sub_prefix))
+            .is_some());
+        assert_eq!(
+            is_any_sub_token_balance_key(&balance),
+            Some((&token, sub_prefix.clone(), &owner))
+        );
+        assert_eq!(
+            is_multitoken_balance_key(&token, &balance),
+            Some((sub_prefix.clone(), &owner))
+        );
+        assert_eq!(is_any_multitoken_key(&balance), Some(&token));
+        // a sub-token balance is not a balance of its token
+        assert_eq!(is_any_token_balance_key(&balance), None);
+        assert_eq!(is_balance_key(&token, &balance), None);
+        assert_eq!(is_any_sub_token_minted_balance_key(&balance), None);
+
+        let minted = sub_token_minted_balance_key(&token, &sub_prefix);
+        assert_eq!(
+            is_any_sub_token_minted_balance_key(&minted),
+            Some((&token, sub_prefix))
+        );
+        assert_eq!(is_any_sub_token_balance_key(&minted), None);
+        assert_eq!(is_any_minted_balance_key(&minted), None);
+
+        // the keys of the token itself have no sub-prefix
+        for key in [
+            balance_key(&token, &owner),
+            minted_balance_key(&token),
+            allowance_key(&token, &owner, &spender),
+        ] {
+            assert_eq!(is_any_sub_token_balance_key(&key), None);
+            assert_eq!(is_any_sub_token_minted_balance_key(&key), None);
+        }
+    }
+
     #[test]
     fn test_amount_is_zero() {
         let zero = Amount::zero();
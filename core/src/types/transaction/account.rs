@@ -1,6 +1,7 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
+use crate::types::account::{AccountPolicy, VestingSchedule, VpUpdateTimelock};
 use crate::types::address::Address;
 use crate::types::hash::Hash;
 use crate::types::key::common;
@@ -25,6 +26,13 @@ pub struct InitAccount {
     pub vp_code_hash: Hash,
     /// The account signature threshold
     pub threshold: u8,
+    /// An optional salt mixed into the tx data, used to initialize distinct
+    /// accounts with otherwise identical data. The address of the new account
+    /// is derived from the tx data.
+    pub salt: Option<String>,
+    /// An optional release schedule of the native tokens locked in the new
+    /// account, enforced by the vesting VP
+    pub vesting: Option<VestingSchedule>,
 }
 
 /// A tx data type to update an account's validity predicate
@@ -49,4 +57,14 @@ pub struct UpdateAccount {
     pub public_keys: Vec<common::PublicKey>,
     /// The account signature threshold
     pub threshold: Option<u8>,
+    /// An optional timelock to set on the VP updates of the account
+    pub vp_timelock: Option<VpUpdateTimelock>,
+    /// Cancel the VP update scheduled under the timelock of the account
+    pub cancel_vp_update: bool,
+    /// The tx code hashes to allow to operate on the account, replacing the
+    /// current ones. An empty list lifts the restriction.
+    pub allowed_txs: Option<Vec<Hash>>,
+    /// The authorization policy to set on the account, replacing the current
+    /// one. A default policy lifts the restrictions.
+    pub policy: Option<AccountPolicy>,
 }
@@ -16,7 +16,7 @@ pub mod protocol;
 /// wrapper txs with encrypted payloads
 pub mod wrapper;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
@@ -39,9 +39,18 @@ pub fn hash_tx(tx_bytes: &[u8]) -> Hash {
     Hash(*digest.as_ref())
 }
 
-/// Transaction application result
+/// Transaction application result. The JSON field names are the names of the
+/// fields and are kept stable for the indexers.
 // TODO derive BorshSchema after <https://github.com/near/borsh-rs/issues/82>
-#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
 pub struct TxResult {
     /// Total gas used by the transaction (includes the gas used by VPs)
     pub gas_used: Gas,
@@ -53,6 +62,8 @@ pub struct TxResult {
     pub initialized_accounts: Vec<Address>,
     /// IBC events emitted by the transaction
     pub ibc_events: BTreeSet<IbcEvent>,
+    /// Debug messages logged by the transaction, only collected in dry-run
+    pub debug_trace: Vec<String>,
 }
 
 impl TxResult {
@@ -64,7 +75,15 @@ impl TxResult {
 
 /// Result of checking a transaction with validity predicates
 // TODO derive BorshSchema after <https://github.com/near/borsh-rs/issues/82>
-#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
 pub struct VpsResult {
     /// The addresses whose VPs accepted the transaction
     pub accepted_vps: BTreeSet<Address>,
@@ -72,17 +91,119 @@ pub struct VpsResult {
     pub rejected_vps: BTreeSet<Address>,
     /// The total gas used by all the VPs
     pub gas_used: VpsGas,
+    /// The gas used by each of the VPs that ran to completion
+    pub vps_gas: BTreeMap<Address, Gas>,
     /// Errors occurred in any of the VPs, if any
     pub errors: Vec<(Address, String)>,
     /// Sentinel to signal an invalid transaction signature
     pub invalid_sig: bool,
+    /// Debug messages logged by the VPs, only collected in dry-run
+    pub debug_trace: Vec<(Address, String)>,
+}
+
+impl VpsResult {
+    /// The addresses whose VPs were triggered by the transaction
+    pub fn verifiers(&self) -> BTreeSet<&Address> {
+        self.accepted_vps
+            .iter()
+            .chain(self.rejected_vps.iter())
+            .collect()
+    }
+
+    /// A short description of why the VPs rejected the transaction, empty if
+    /// it was accepted.
+    pub fn rejection_reason(&self) -> String {
+        if self.invalid_sig {
+            return "Invalid transaction signature".to_string();
+        }
+        let mut reasons: Vec<String> = self
+            .rejected_vps
+            .iter()
+            .map(|addr| format!("Rejected by the VP of {addr}"))
+            .collect();
+        reasons.extend(
+            self.errors
+                .iter()
+                .map(|(addr, err)| format!("Error in the VP of {addr}: {err}")),
+        );
+        reasons.join("; ")
+    }
+}
+
+/// The cause of the result of a tx, attached to its tx event. Unlike the
+/// free-form `info` and `reason` messages, the variants and their JSON encoding
+/// are stable, so that clients can branch on them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultCode {
+    /// The tx was applied
+    Ok,
+    /// The tx ran out of gas
+    OutOfGas,
+    /// The gas limit of the tx is invalid, e.g. above the block's limit
+    InvalidGasLimit,
+    /// The VPs of the given addresses rejected the tx or failed
+    VpRejected(BTreeSet<Address>),
+    /// A signature of the tx is invalid
+    InvalidSig,
+    /// The tx expired before its inclusion in a block
+    Expired,
+    /// The code of the tx isn't in the allowlist of the chain
+    NotAllowlisted,
+    /// The tx was already applied
+    Replayed,
+    /// The tx was made for another chain
+    InvalidChainId,
+    /// The fees of the tx couldn't be paid
+    FeeError,
+    /// The inner tx couldn't be decrypted
+    Undecryptable,
+    /// The code of the tx failed
+    WasmRuntimeError,
+    /// The tx is invalid for another reason
+    InvalidTx,
+}
+
+impl ResultCode {
+    /// The key of the tx event attribute of the result code
+    pub const EVENT_ATTRIBUTE: &'static str = "result_code";
+
+    /// Encode the result code as the value of its tx event attribute
+    pub fn to_event_value(&self) -> String {
+        serde_json::to_string(self)
+            .expect("Encoding a result code shouldn't fail")
+    }
+
+    /// Decode the result code from the value of its tx event attribute
+    pub fn from_event_value(value: &str) -> Option<Self> {
+        serde_json::from_str(value).ok()
+    }
+
+    /// Check if the tx was applied
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+}
+
+impl VpsResult {
+    /// The result code of a tx that the VPs rejected, or `Ok` if it was
+    /// accepted
+    pub fn result_code(&self) -> ResultCode {
+        if self.invalid_sig {
+            ResultCode::InvalidSig
+        } else if self.rejected_vps.is_empty() {
+            ResultCode::Ok
+        } else {
+            ResultCode::VpRejected(self.rejected_vps.clone())
+        }
+    }
 }
 
 impl fmt::Display for TxResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Transaction is {}. Gas used: {};{} VPs result: {}",
+            "Transaction is {}. Gas used: {};{}{} VPs result: {}",
             if self.is_accepted() {
                 "valid"
             } else {
@@ -90,6 +211,7 @@ impl fmt::Display for TxResult {
             },
             self.gas_used,
             iterable_to_string("Changed keys", self.changed_keys.iter()),
+            iterable_to_string("Debug trace", self.debug_trace.iter()),
             self.vps_result,
         )
     }
@@ -99,15 +221,27 @@ impl fmt::Display for VpsResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "{}{}{}",
+            "{}{}{}{}{}",
             iterable_to_string("Accepted", self.accepted_vps.iter()),
             iterable_to_string("Rejected", self.rejected_vps.iter()),
+            iterable_to_string(
+                "Gas used",
+                self.vps_gas
+                    .iter()
+                    .map(|(addr, gas)| format!("{} by {}", gas, addr))
+            ),
             iterable_to_string(
                 "Errors",
                 self.errors
                     .iter()
                     .map(|(addr, err)| format!("{} in {}", err, addr))
             ),
+            iterable_to_string(
+                "Debug trace",
+                self.debug_trace
+                    .iter()
+                    .map(|(addr, msg)| format!("{}: {}", addr, msg))
+            ),
         )
     }
 }
@@ -391,3 +525,94 @@ fn test_process_tx_decrypted_signed() {
         _ => panic!("Test failed"),
     }
 }
+
+/// Test the rejection reason reported for the VPs results of a tx
+#[test]
+fn test_vps_result_rejection_reason() {
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    let mut result = VpsResult::default();
+    assert_eq!(result.rejection_reason(), "");
+
+    result.rejected_vps.insert(established_address_1());
+    result
+        .errors
+        .push((established_address_2(), "out of gas".to_string()));
+    assert_eq!(
+        result.rejection_reason(),
+        format!(
+            "Rejected by the VP of {}; Error in the VP of {}: out of gas",
+            established_address_1(),
+            established_address_2()
+        )
+    );
+
+    result.invalid_sig = true;
+    assert_eq!(result.rejection_reason(), "Invalid transaction signature");
+}
+
+/// Test the stable JSON representation of a tx result
+#[test]
+fn test_tx_result_json() {
+    use crate::types::address::testing::established_address_1;
+
+    let mut result = TxResult {
+        gas_used: 10.into(),
+        ..Default::default()
+    };
+    result
+        .vps_result
+        .accepted_vps
+        .insert(established_address_1());
+    result
+        .vps_result
+        .vps_gas
+        .insert(established_address_1(), 5.into());
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["gas_used"], 10);
+    assert_eq!(
+        json["vps_result"]["accepted_vps"][0],
+        established_address_1().encode()
+    );
+    assert_eq!(
+        json["vps_result"]["vps_gas"][established_address_1().encode()],
+        5
+    );
+    let decoded: TxResult = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.gas_used, result.gas_used);
+    assert_eq!(
+        decoded.vps_result.accepted_vps,
+        result.vps_result.accepted_vps
+    );
+}
+
+/// Test the stable JSON encoding of the result codes
+#[test]
+fn test_result_code_event_value() {
+    use crate::types::address::testing::established_address_1;
+
+    assert_eq!(ResultCode::Ok.to_event_value(), r#""ok""#);
+    assert_eq!(ResultCode::OutOfGas.to_event_value(), r#""out_of_gas""#);
+
+    let mut result = VpsResult::default();
+    assert_eq!(result.result_code(), ResultCode::Ok);
+    result.rejected_vps.insert(established_address_1());
+    let code = result.result_code();
+    assert_eq!(
+        code.to_event_value(),
+        format!(
+            r#"{{"vp_rejected":["{}"]}}"#,
+            established_address_1().encode()
+        )
+    );
+    assert_eq!(
+        ResultCode::from_event_value(&code.to_event_value()),
+        Some(code)
+    );
+    assert_eq!(ResultCode::from_event_value("unknown"), None);
+
+    result.invalid_sig = true;
+    assert_eq!(result.result_code(), ResultCode::InvalidSig);
+}
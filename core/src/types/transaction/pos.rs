@@ -115,6 +115,30 @@ pub struct ClaimRewards {
     pub source: Option<Address>,
 }
 
+/// An opt-in or opt-out of a bond into the automatic re-bonding of its
+/// rewards at every new epoch.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct AutoCompound {
+    /// Validator address
+    pub validator: Address,
+    /// Source address of the bond. For self-bonds, the validator is also the
+    /// source
+    pub source: Option<Address>,
+    /// Whether the rewards are re-bonded
+    pub enabled: bool,
+}
+
 /// A redelegation of bonded tokens from one validator to another.
 #[derive(
     Debug,
@@ -274,4 +274,15 @@ impl ProtocolTxType {
                 | Self::ValSetUpdateVext
         )
     }
+
+    /// Determine if this [`ProtocolTxType`] may be submitted to the mempool.
+    /// Only the vote extensions of a single validator are gossiped, the
+    /// digests of vote extensions are built by the block proposer.
+    #[inline]
+    pub fn is_allowed_in_mempool(&self) -> bool {
+        matches!(
+            self,
+            Self::EthEventsVext | Self::BridgePoolVext | Self::ValSetUpdateVext
+        )
+    }
 }
@@ -189,6 +189,10 @@ pub mod wrapper_tx {
         /// The hash of the optional, unencrypted, unshielding transaction for
         /// fee payment
         pub unshield_section_hash: Option<Hash>,
+        /// The optional sequence number of the fee payer. If set, it must
+        /// match the next sequence number stored for the fee payer's account
+        /// for the wrapper to be included.
+        pub sequence: Option<u64>,
     }
 
     impl WrapperTx {
@@ -210,9 +214,16 @@ pub mod wrapper_tx {
                 epoch,
                 gas_limit,
                 unshield_section_hash: unshield_hash,
+                sequence: None,
             }
         }
 
+        /// Set the sequence number of the fee payer
+        pub fn with_sequence(mut self, sequence: u64) -> Self {
+            self.sequence = Some(sequence);
+            self
+        }
+
         /// Get the address of the implicit account associated
         /// with the public key
         /// NOTE: this is safe in case someone tried to use the masp address to
@@ -16,6 +16,7 @@ use namada_core::ledger::eth_bridge::ADDRESS as BRIDGE_ADDRESS;
 use namada_core::ledger::parameters::read_epoch_duration_parameter;
 use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::storage::{DBIter, WlStorage, DB};
+use namada_core::ledger::storage_api::token::read_denom;
 use namada_core::ledger::storage_api::{StorageRead, StorageWrite};
 use namada_core::types::address::Address;
 use namada_core::types::eth_bridge_pool::{
@@ -114,21 +115,26 @@ where
         let mut changed = if asset != &wrapped_native_erc20 {
             let (asset_count, changed) =
                 mint_eth_assets(wl_storage, asset, receiver, amount)?;
-            // TODO: query denomination of the whitelisted token from storage,
-            // and print this amount with the proper formatting; for now, use
-            // NAM's formatting
             if asset_count.should_mint_erc20s() {
                 tracing::info!(
                     "Minted wrapped ERC20s - (asset - {asset}, receiver - \
                      {receiver}, amount - {})",
-                    asset_count.erc20_amount.to_string_native(),
+                    format_eth_asset_amount(
+                        wl_storage,
+                        &wrapped_erc20s::token(asset),
+                        asset_count.erc20_amount,
+                    ),
                 );
             }
             if asset_count.should_mint_nuts() {
                 tracing::info!(
                     "Minted NUTs - (asset - {asset}, receiver - {receiver}, \
                      amount - {})",
-                    asset_count.nut_amount.to_string_native(),
+                    format_eth_asset_amount(
+                        wl_storage,
+                        &wrapped_erc20s::nut(asset),
+                        asset_count.nut_amount,
+                    ),
                 );
             }
             changed
@@ -223,6 +229,25 @@ where
     ]))
 }
 
+/// Format an amount of a wrapped Ethereum asset with the denomination of
+/// the asset found in storage, falling back to NAM's formatting.
+fn format_eth_asset_amount<D, H>(
+    wl_storage: &WlStorage<D, H>,
+    token: &Address,
+    amount: token::Amount,
+) -> String
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    match read_denom(wl_storage, token) {
+        Ok(Some(denom)) => {
+            token::DenominatedAmount { amount, denom }.to_string()
+        }
+        _ => amount.to_string_native(),
+    }
+}
+
 /// Helper function to mint assets originating from Ethereum
 /// on Namada.
 ///
@@ -1125,7 +1150,7 @@ mod tests {
         init_balance(&mut wl_storage, &pending_transfers);
         wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
         // pending transfers time out
         wl_storage.storage.block.height += 10 + 1;
@@ -1151,7 +1176,7 @@ mod tests {
             .expect("Test failed");
         wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
         wl_storage.storage.block.height += 1;
 
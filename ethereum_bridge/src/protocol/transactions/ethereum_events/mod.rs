@@ -707,7 +707,10 @@ mod tests {
         let prev_keys = vote_tallies::Keys::from(&event);
 
         // commit then update the epoch
-        wl_storage.storage.commit_block(MockDBWriteBatch).unwrap();
+        wl_storage
+            .storage
+            .commit_block(MockDBWriteBatch::default())
+            .unwrap();
         let unbonding_len = namada_proof_of_stake::read_pos_params(&wl_storage)
             .expect("Test failed")
             .unbonding_len
@@ -843,7 +846,10 @@ mod tests {
         });
 
         // commit then update the epoch
-        wl_storage.storage.commit_block(MockDBWriteBatch).unwrap();
+        wl_storage
+            .storage
+            .commit_block(MockDBWriteBatch::default())
+            .unwrap();
         let unbonding_len = namada_proof_of_stake::read_pos_params(&wl_storage)
             .expect("Test failed")
             .unbonding_len
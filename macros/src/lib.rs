@@ -317,6 +317,104 @@ fn derive_storage_keys_inner(struct_def: TokenStream2) -> TokenStream2 {
     }
 }
 
+/// Derive a typed storage layout for a struct of lazy collections and
+/// `LazyOption`s, whose fields are sub-spaces rooted at an address. The key
+/// segment of each field is its name.
+///
+/// The derive generates:
+/// - a `<FIELD>_KEY` constant with the key segment of each field and a
+///   `KEYS` slice with all of them, in declaration order,
+/// - a `fn open(address: &Address) -> Self` constructor that opens every
+///   field under its key segment at the address,
+/// - a `fn layout()` description of the key segment and type of each field.
+///
+/// The `Address`, `DbKeySeg` and `Key` types must be in scope of the struct,
+/// and so must the `LazyCollection` trait if any of the fields is a lazy
+/// collection.
+#[proc_macro_derive(StorageLayout)]
+pub fn derive_storage_layout(struct_def: TokenStream) -> TokenStream {
+    derive_storage_layout_inner(struct_def.into()).into()
+}
+
+#[inline]
+fn derive_storage_layout_inner(struct_def: TokenStream2) -> TokenStream2 {
+    let struct_def: ItemStruct = syn::parse2(struct_def)
+        .expect("Expected a struct in the StorageLayout derive");
+
+    let fields = match &struct_def.fields {
+        syn::Fields::Named(fields) => &fields.named,
+        _ => panic!(
+            "Only named struct fields are accepted in StorageLayout derives"
+        ),
+    };
+
+    let mut key_consts = vec![];
+    let mut key_const_idents = vec![];
+    let mut open_fields = vec![];
+    let mut layout_entries = vec![];
+
+    for field in fields {
+        let ident = field.ident.as_ref().expect("Expected a named field");
+        let ty = &field.ty;
+        let key_seg = ident.to_string();
+        let key_const = syn::Ident::new(
+            &format!("{}_KEY", key_seg.to_uppercase()),
+            ident.span(),
+        );
+        let doc = format!("The key segment of the `{key_seg}` sub-space");
+        key_consts.push(quote! {
+            #[doc = #doc]
+            pub const #key_const: &str = #key_seg;
+        });
+        open_fields.push(quote! {
+            #ident: <#ty>::open(
+                root.push(&Self::#key_const.to_owned())
+                    .expect("Cannot obtain a storage key"),
+            )
+        });
+        let ty_desc = type_description(ty);
+        layout_entries.push(quote! { (Self::#key_const, #ty_desc) });
+        key_const_idents.push(key_const);
+    }
+
+    let struct_def_ident = &struct_def.ident;
+
+    quote! {
+        impl #struct_def_ident {
+            #(#key_consts)*
+
+            /// The key segments of all the sub-spaces, in declaration order
+            pub const KEYS: &[&str] = &[ #(Self::#key_const_idents),* ];
+
+            /// Open the sub-spaces of the layout at the given address
+            pub fn open(address: &Address) -> Self {
+                let root = Key::from(DbKeySeg::AddressSeg(address.clone()));
+                Self {
+                    #(#open_fields),*
+                }
+            }
+
+            /// The key segment and type of each sub-space, in declaration
+            /// order
+            pub fn layout() -> &'static [(&'static str, &'static str)] {
+                &[ #(#layout_entries),* ]
+            }
+        }
+    }
+}
+
+/// A human readable description of a type, without the spacing of its tokens
+fn type_description(ty: &syn::Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(" < ", "<")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" , ", ", ")
+}
+
 #[inline]
 fn create_punctuated<F, M>(
     idents: &[syn::Ident],
@@ -500,6 +598,70 @@ mod test_proc_macros {
         });
     }
 
+    /// Test if we reject tuple structs in `StorageLayout` derives.
+    #[test]
+    #[should_panic(expected = "Only named struct fields are accepted in \
+                               StorageLayout derives")]
+    fn test_storage_layout_panics_on_tuple_structs() {
+        derive_storage_layout_inner(quote! {
+            struct Layout(LazyOption<u64>);
+        });
+    }
+
+    /// Test that the storage layout derive produces the expected code.
+    #[test]
+    fn test_derive_storage_layout() {
+        let test_struct = quote! {
+            struct Layout {
+                params: LazyOption<Params>,
+                balances: LazyMap<Address, token::Amount>,
+            }
+        };
+        let test_impl: File =
+            syn::parse2(derive_storage_layout_inner(test_struct))
+                .expect("Test failed");
+
+        let expected_impl = quote! {
+            impl Layout {
+                #[doc = "The key segment of the `params` sub-space"]
+                pub const PARAMS_KEY: &str = "params";
+                #[doc = "The key segment of the `balances` sub-space"]
+                pub const BALANCES_KEY: &str = "balances";
+
+                /// The key segments of all the sub-spaces, in declaration order
+                pub const KEYS: &[&str] = &[Self::PARAMS_KEY, Self::BALANCES_KEY];
+
+                /// Open the sub-spaces of the layout at the given address
+                pub fn open(address: &Address) -> Self {
+                    let root = Key::from(DbKeySeg::AddressSeg(address.clone()));
+                    Self {
+                        params: <LazyOption<Params> >::open(
+                            root.push(&Self::PARAMS_KEY.to_owned())
+                                .expect("Cannot obtain a storage key"),
+                        ),
+                        balances: <LazyMap<Address, token::Amount> >::open(
+                            root.push(&Self::BALANCES_KEY.to_owned())
+                                .expect("Cannot obtain a storage key"),
+                        )
+                    }
+                }
+
+                /// The key segment and type of each sub-space, in declaration
+                /// order
+                pub fn layout() -> &'static [(&'static str, &'static str)] {
+                    &[
+                        (Self::PARAMS_KEY, "LazyOption<Params>"),
+                        (Self::BALANCES_KEY, "LazyMap<Address, token::Amount>")
+                    ]
+                }
+            }
+        };
+        let expected_impl: File =
+            syn::parse2(expected_impl).expect("Test failed");
+
+        assert_eq!(test_impl, expected_impl);
+    }
+
     /// Test that the create storage keys produces
     /// the expected code.
     #[test]
@@ -43,8 +43,20 @@ pub enum BondError {
          not delegate."
     )]
     SourceMustNotBeAValidator(Address),
+    #[error("No bond of {0} to the validator {1} could be found")]
+    NoBond(Address, Address),
     #[error("The given validator address {0} is inactive")]
     InactiveValidator(Address),
+    #[error(
+        "The validator {0} self-bond of {1} is below the minimum self-bond of \
+         {2} required to receive delegations"
+    )]
+    SelfBondBelowMinimum(Address, String, String),
+    #[error(
+        "The validator {0} stake would exceed the maximum ratio of {1} to its \
+         self-bond"
+    )]
+    StakeToSelfBondRatioExceeded(Address, Dec),
     #[error("Voting power overflow: {0}")]
     VotingPowerOverflow(TryFromIntError),
 }
@@ -66,6 +78,16 @@ pub enum UnbondError {
     VotingPowerOverflow(TryFromIntError),
     #[error("Trying to unbond from a frozen validator: {0}")]
     ValidatorIsFrozen(Address),
+    #[error(
+        "The remaining self-bond of {0} would be below the minimum self-bond \
+         of {1}. The validator must be deactivated to unbond it."
+    )]
+    SelfBondBelowMinimum(String, String),
+    #[error(
+        "The validator stake would exceed the maximum ratio of {0} to its \
+         remaining self-bond"
+    )]
+    StakeToSelfBondRatioExceeded(Dec),
 }
 
 #[allow(missing_docs)]
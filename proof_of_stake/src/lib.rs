@@ -42,14 +42,16 @@ use once_cell::unsync::Lazy;
 pub use parameters::{OwnedPosParams, PosParams};
 use rewards::PosRewardsCalculator;
 use storage::{
+    auto_compound_key, auto_compound_prefix, auto_compound_progress_key,
     bonds_for_source_prefix, bonds_prefix, consensus_keys_key,
-    get_validator_address_from_bond, is_bond_key, is_unbond_key,
-    is_validator_slashes_key, last_block_proposer_key,
-    last_pos_reward_claim_epoch_key, params_key, rewards_counter_key,
-    slashes_prefix, unbonds_for_source_prefix, unbonds_prefix,
-    validator_address_raw_hash_key, validator_description_key,
+    get_validator_address_from_bond, is_auto_compound_key, is_bond_key,
+    is_unbond_key, is_validator_slashes_key, last_block_proposer_key,
+    last_pos_reward_claim_epoch_key, max_stake_to_self_bond_ratio_key,
+    params_key, rewards_counter_key, slashes_prefix, unbonds_for_source_prefix,
+    unbonds_prefix, validator_address_raw_hash_key, validator_description_key,
     validator_discord_key, validator_email_key, validator_last_slash_key,
-    validator_max_commission_rate_change_key, validator_website_key,
+    validator_max_commission_rate_change_key, validator_min_self_bond_key,
+    validator_website_key,
 };
 use types::{
     into_tm_voting_power, BelowCapacityValidatorSet,
@@ -344,10 +346,19 @@ pub fn read_pos_params<S>(storage: &S) -> storage_api::Result<PosParams>
 where
     S: StorageRead,
 {
-    let params = storage
+    let mut params: OwnedPosParams = storage
         .read(&params_key())
         .transpose()
         .expect("PosParams should always exist in storage after genesis")?;
+    // These parameters were added after genesis of the existing chains, on
+    // which they fall back to their default values
+    let defaults = OwnedPosParams::default();
+    params.validator_min_self_bond = storage
+        .read(&validator_min_self_bond_key())?
+        .unwrap_or(defaults.validator_min_self_bond);
+    params.max_stake_to_self_bond_ratio = storage
+        .read(&max_stake_to_self_bond_ratio_key())?
+        .unwrap_or(defaults.max_stake_to_self_bond_ratio);
     read_non_pos_owned_params(storage, params)
 }
 
@@ -376,7 +387,15 @@ where
     S: StorageRead + StorageWrite,
 {
     let key = params_key();
-    storage.write(&key, params)
+    storage.write(&key, params)?;
+    storage.write(
+        &validator_min_self_bond_key(),
+        params.validator_min_self_bond,
+    )?;
+    storage.write(
+        &max_stake_to_self_bond_ratio_key(),
+        params.max_stake_to_self_bond_ratio,
+    )
 }
 
 /// Get the validator address given the raw hash of the Tendermint consensus key
@@ -699,6 +718,36 @@ where
         .collect()
 }
 
+/// Read the state of a validator at the given epoch. Returns `None` for an
+/// address that isn't a validator at the epoch.
+pub fn read_validator_state<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<Option<ValidatorState>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    validator_state_handle(validator).get(storage, epoch, &params)
+}
+
+/// Check if the validator is in the consensus validator set at the given
+/// epoch
+pub fn is_consensus_validator<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    Ok(matches!(
+        read_validator_state(storage, validator, epoch)?,
+        Some(ValidatorState::Consensus)
+    ))
+}
+
 /// Update PoS total deltas.
 /// Note: for EpochedDelta, write the value to change storage by
 pub fn update_total_deltas<S>(
@@ -780,6 +829,99 @@ where
     }
 }
 
+/// Check that a validator whose stake at the given epoch is increased by a
+/// delegation of the given amount has at least the minimum self-bond and
+/// doesn't exceed the maximum ratio of its stake to its self-bond. Returns
+/// the error of the delegation, if any.
+fn delegation_self_bond_error<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+    amount: token::Amount,
+) -> storage_api::Result<Option<BondError>>
+where
+    S: StorageRead,
+{
+    let self_bond = bond_handle(validator, validator)
+        .get_sum(storage, epoch, params)?
+        .unwrap_or_default();
+    if self_bond < params.validator_min_self_bond {
+        return Ok(Some(BondError::SelfBondBelowMinimum(
+            validator.clone(),
+            self_bond.to_string_native(),
+            params.validator_min_self_bond.to_string_native(),
+        )));
+    }
+    if let Some(max_ratio) = params.max_stake_to_self_bond_ratio {
+        let stake = read_validator_stake(storage, params, validator, epoch)?;
+        if stake + amount > max_ratio * self_bond {
+            return Ok(Some(BondError::StakeToSelfBondRatioExceeded(
+                validator.clone(),
+                max_ratio,
+            )));
+        }
+    }
+    Ok(None)
+}
+
+/// Fail with the error of a delegation from [`delegation_self_bond_error`],
+/// if any.
+fn check_delegation_self_bond<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+    amount: token::Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead,
+{
+    match delegation_self_bond_error(storage, params, validator, epoch, amount)?
+    {
+        Some(err) => Err(err.into()),
+        None => Ok(()),
+    }
+}
+
+/// Check that a validator that unbonds the given amount of its self-bond at
+/// the given epoch keeps at least the minimum self-bond and doesn't exceed the
+/// maximum ratio of its stake to its self-bond, unless it's inactive.
+fn check_self_unbond<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    epoch: Epoch,
+    amount: token::Amount,
+    self_bond: token::Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead,
+{
+    let state =
+        validator_state_handle(validator).get(storage, epoch, params)?;
+    if state == Some(ValidatorState::Inactive) {
+        return Ok(());
+    }
+    let remaining_self_bond = self_bond - amount;
+    if remaining_self_bond < params.validator_min_self_bond {
+        return Err(UnbondError::SelfBondBelowMinimum(
+            remaining_self_bond.to_string_native(),
+            params.validator_min_self_bond.to_string_native(),
+        )
+        .into());
+    }
+    if let Some(max_ratio) = params.max_stake_to_self_bond_ratio {
+        let stake = read_validator_stake(storage, params, validator, epoch)?;
+        if stake - amount > max_ratio * remaining_self_bond {
+            return Err(
+                UnbondError::StakeToSelfBondRatioExceeded(max_ratio).into()
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Self-bond tokens to a validator when `source` is `None` or equal to
 /// the `validator` address, or delegate tokens from the `source` to the
 /// `validator`.
@@ -827,6 +969,17 @@ where
         return Err(BondError::NotAValidator(validator.clone()).into());
     }
 
+    // Check the validator's self-bond for a delegation
+    if source != validator {
+        check_delegation_self_bond(
+            storage,
+            &params,
+            validator,
+            offset_epoch,
+            amount,
+        )?;
+    }
+
     let bond_handle = bond_handle(source, validator);
     let total_bonded_handle = total_bonded_handle(validator);
 
@@ -1743,6 +1896,23 @@ where
         .into());
     }
 
+    // Check the validator's remaining self-bond
+    if source == validator {
+        check_self_unbond(
+            storage,
+            &params,
+            validator,
+            pipeline_epoch,
+            amount,
+            remaining_at_pipeline,
+        )?;
+    }
+
+    // A fully unbonded bond has no more rewards to re-bond
+    if amount == remaining_at_pipeline {
+        storage.delete(&auto_compound_key(source, validator))?;
+    }
+
     if tracing::level_enabled!(tracing::Level::DEBUG) {
         let bonds = find_bonds(storage, source, validator)?;
         tracing::debug!("\nBonds before decrementing: {bonds:#?}");
@@ -5317,6 +5487,16 @@ where
 
     let params = read_pos_params(storage)?;
     let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    // Check the dest validator's self-bond for the redelegated tokens
+    check_delegation_self_bond(
+        storage,
+        &params,
+        dest_validator,
+        pipeline_epoch,
+        amount,
+    )?;
+
     let src_redel_end_epoch =
         validator_incoming_redelegations_handle(src_validator)
             .get(storage, delegator)?;
@@ -5724,6 +5904,41 @@ where
     Ok(())
 }
 
+/// Remove the validators' total unbonded records of the unbonds that became
+/// active before the current epoch. The slashes are only ever processed
+/// against the unbonds active at the current epoch or later, so these records
+/// are no longer read. Returns the number of removed records.
+pub fn prune_total_unbonded<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<u64>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut num_pruned = 0_u64;
+    for validator in read_all_validator_addresses(storage, current_epoch)? {
+        let total_unbonded = total_unbonded_handle(&validator);
+        let epochs_to_prune = total_unbonded
+            .iter(storage)?
+            .filter_map(|entry| {
+                let (
+                    NestedSubKey::Data {
+                        key: epoch,
+                        nested_sub_key: _,
+                    },
+                    _,
+                ) = entry.ok()?;
+                (epoch < current_epoch).then_some(epoch)
+            })
+            .collect::<BTreeSet<Epoch>>();
+        for epoch in &epochs_to_prune {
+            total_unbonded.remove_all(storage, epoch)?;
+        }
+        num_pruned += epochs_to_prune.len() as u64;
+    }
+    Ok(num_pruned)
+}
+
 /// Record the liveness data of the consensus validators
 pub fn record_liveness_data<S>(
     storage: &mut S,
@@ -5817,9 +6032,7 @@ where
         * params.liveness_window_check)
         .to_uint()
         .ok_or_else(|| {
-            storage_api::Error::SimpleMessage(
-                "Found negative liveness threshold",
-            )
+            storage_api::Error::new_const("Found negative liveness threshold")
         })?
         .as_u64();
 
@@ -6248,6 +6461,182 @@ where
     Ok(rewards_from_bonds + rewards_from_counter)
 }
 
+/// Opt a bond in or out of the automatic re-bonding of its rewards at every
+/// new epoch. Only an existing bond can opt in. The opt-in is removed when
+/// the bond is fully unbonded.
+pub fn set_auto_compound<S>(
+    storage: &mut S,
+    source: Option<&Address>,
+    validator: &Address,
+    enabled: bool,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let source = source.unwrap_or(validator);
+    let key = auto_compound_key(source, validator);
+    if !enabled {
+        return storage.delete(&key);
+    }
+    if !is_validator(storage, validator)? {
+        return Err(BondError::NotAValidator(validator.clone()).into());
+    }
+    if source != validator && is_validator(storage, source)? {
+        return Err(BondError::SourceMustNotBeAValidator(source.clone()).into());
+    }
+    if bond_handle(source, validator)
+        .get_data_handler()
+        .is_empty(storage)?
+    {
+        return Err(BondError::NoBond(source.clone(), validator.clone()).into());
+    }
+    storage.write(&key, ())
+}
+
+/// Check if the rewards of a bond are automatically re-bonded.
+pub fn is_auto_compound<S>(
+    storage: &S,
+    source: &Address,
+    validator: &Address,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    storage.has_key(&auto_compound_key(source, validator))
+}
+
+/// Find the validators of the bonds of the given source whose rewards are
+/// automatically re-bonded.
+pub fn find_auto_compound_validators<S>(
+    storage: &S,
+    source: &Address,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    S: StorageRead,
+{
+    let prefix = auto_compound_prefix()
+        .push(&source.to_db_key())
+        .expect("Cannot obtain a storage key");
+    let mut validators = BTreeSet::new();
+    for iter_result in storage_api::iter_prefix_bytes(storage, &prefix)? {
+        let (key, _) = iter_result?;
+        if let Some(BondId { validator, .. }) = is_auto_compound_key(&key) {
+            validators.insert(validator);
+        }
+    }
+    Ok(validators)
+}
+
+/// Take the next bonds that opted into auto-compounding whose rewards are yet
+/// to be re-bonded in the current epoch, at most `limit` of them, in the order
+/// of their opt-in keys. The progress is recorded, so that the following
+/// blocks of the epoch continue after the taken bonds until they've all been
+/// taken. The progress is reset at every new epoch.
+pub fn take_auto_compound_bonds<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+    limit: usize,
+) -> storage_api::Result<Vec<BondId>>
+where
+    S: StorageRead + StorageWrite,
+{
+    // The epoch and the opt-in key of the last taken bond, if any are left
+    let progress: Option<(Epoch, Option<String>)> =
+        storage.read(&auto_compound_progress_key())?;
+    let last_key = match progress {
+        Some((epoch, None)) if epoch == current_epoch => return Ok(vec![]),
+        Some((epoch, last_key)) if epoch == current_epoch => last_key,
+        _ => None,
+    };
+    let mut bonds = vec![];
+    for iter_result in
+        storage_api::iter_prefix_bytes(storage, &auto_compound_prefix())?
+    {
+        let (key, _) = iter_result?;
+        if matches!(&last_key, Some(last_key) if &key.to_string() <= last_key) {
+            continue;
+        }
+        if let Some(bond_id) = is_auto_compound_key(&key) {
+            if bonds.len() == limit {
+                break;
+            }
+            bonds.push(bond_id);
+        }
+    }
+    let last_key = bonds.last().filter(|_| bonds.len() == limit).map(
+        |BondId { source, validator }| {
+            auto_compound_key(source, validator).to_string()
+        },
+    );
+    storage.write(&auto_compound_progress_key(), (current_epoch, last_key))?;
+    Ok(bonds)
+}
+
+/// Claim the rewards of a bond that opted into auto-compounding and bond them
+/// to the same validator. This is executed at a new epoch, after the rewards
+/// of the last epoch are distributed, for the bonds returned by
+/// [`take_auto_compound_bonds`]. The bonds whose rewards can't be bonded, e.g.
+/// because the source has since become a validator or the validator's
+/// self-bond is too low for more delegations, are skipped and their rewards
+/// are left to be claimed. Returns `None` if the bond is skipped or has no
+/// rewards.
+pub fn compound_bond_rewards<S>(
+    storage: &mut S,
+    params: &PosParams,
+    bond_id: &BondId,
+    current_epoch: Epoch,
+) -> storage_api::Result<Option<token::Amount>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let BondId { source, validator } = bond_id;
+    if source != validator && is_validator(storage, source)? {
+        tracing::debug!(
+            "Skipping the auto-compound of the rewards of {source} bonded to \
+             {validator}, the source is a validator"
+        );
+        return Ok(None);
+    }
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    if validator_state_handle(validator)
+        .get(storage, pipeline_epoch, params)?
+        .is_none()
+    {
+        return Ok(None);
+    }
+    let amount =
+        query_reward_tokens(storage, Some(source), validator, current_epoch)?;
+    if amount.is_zero() {
+        return Ok(None);
+    }
+    if source != validator {
+        if let Some(err) = delegation_self_bond_error(
+            storage,
+            params,
+            validator,
+            pipeline_epoch,
+            amount,
+        )? {
+            tracing::debug!(
+                "Skipping the auto-compound of the rewards of {source} bonded \
+                 to {validator}: {err}"
+            );
+            return Ok(None);
+        }
+    }
+    let amount =
+        claim_reward_tokens(storage, Some(source), validator, current_epoch)?;
+    bond_tokens(
+        storage,
+        Some(source),
+        validator,
+        amount,
+        current_epoch,
+        None,
+    )?;
+    Ok(Some(amount))
+}
+
 /// Get the last epoch in which rewards were claimed from storage, if any
 pub fn get_last_reward_claim_epoch<S>(
     storage: &S,
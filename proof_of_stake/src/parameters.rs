@@ -1,5 +1,7 @@
 //! Proof-of-Stake system parameters
 
+use std::io::{Read, Write};
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::ledger::governance::parameters::GovernanceParameters;
 use namada_core::types::dec::Dec;
@@ -10,7 +12,7 @@ use thiserror::Error;
 
 /// Proof-of-Stake system parameters. This includes parameters that are used in
 /// PoS but are read from other accounts storage (governance).
-#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+#[derive(Debug, Clone)]
 pub struct PosParams {
     /// PoS-owned params
     pub owned: OwnedPosParams,
@@ -64,6 +66,44 @@ pub struct OwnedPosParams {
     /// The minimum required activity of consesus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The minimum amount of tokens that a validator must self-bond to
+    /// receive delegations. A validator can only unbond below it when it's
+    /// inactive. Stored under its own key, outside of the encoded params, so
+    /// that the params of the existing chains can still be decoded.
+    #[borsh(skip)]
+    pub validator_min_self_bond: token::Amount,
+    /// The maximum ratio of a validator's total stake to its self-bond,
+    /// above which it can't receive delegations, if any. Stored under its
+    /// own key, like the `validator_min_self_bond`.
+    #[borsh(skip)]
+    pub max_stake_to_self_bond_ratio: Option<Dec>,
+}
+
+// The encoding includes the owned params that are stored under their own keys
+// and skipped by the encoding of `OwnedPosParams`, so that they're not lost
+// when the params are sent over RPC
+impl BorshSerialize for PosParams {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.owned.serialize(writer)?;
+        self.max_proposal_period.serialize(writer)?;
+        self.owned.validator_min_self_bond.serialize(writer)?;
+        self.owned.max_stake_to_self_bond_ratio.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for PosParams {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut owned = OwnedPosParams::deserialize_reader(reader)?;
+        let max_proposal_period = u64::deserialize_reader(reader)?;
+        owned.validator_min_self_bond =
+            token::Amount::deserialize_reader(reader)?;
+        owned.max_stake_to_self_bond_ratio =
+            Option::<Dec>::deserialize_reader(reader)?;
+        Ok(Self {
+            owned,
+            max_proposal_period,
+        })
+    }
 }
 
 impl Default for PosParams {
@@ -101,6 +141,8 @@ impl Default for OwnedPosParams {
             validator_stake_threshold: token::Amount::native_whole(1_u64),
             liveness_window_check: 10_000,
             liveness_threshold: Dec::new(9, 1).expect("Test failed"),
+            validator_min_self_bond: token::Amount::zero(),
+            max_stake_to_self_bond_ratio: None,
         }
     }
 }
@@ -122,6 +164,8 @@ pub enum ValidationError {
          pipeline: {1}"
     )]
     UnbondingLenTooShort(u64, u64),
+    #[error("Maximum stake to self-bond ratio must be at least 1, got {0}")]
+    StakeToSelfBondRatioTooSmall(Dec),
 }
 
 /// The number of fundamental units per whole token of the native staking token
@@ -179,6 +223,15 @@ impl OwnedPosParams {
             ))
         }
 
+        // Check that a validator's stake can include its self-bond
+        if let Some(max_ratio) = self.max_stake_to_self_bond_ratio {
+            if max_ratio < Dec::one() {
+                errors.push(ValidationError::StakeToSelfBondRatioTooSmall(
+                    max_ratio,
+                ))
+            }
+        }
+
         errors
     }
 
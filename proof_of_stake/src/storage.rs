@@ -9,6 +9,8 @@ use crate::epoched::LAZY_MAP_SUB_KEY;
 use crate::types::BondId;
 
 const PARAMS_STORAGE_KEY: &str = "params";
+const VALIDATOR_MIN_SELF_BOND_KEY: &str = "validator_min_self_bond";
+const MAX_STAKE_TO_SELF_BOND_RATIO_KEY: &str = "max_stake_to_self_bond_ratio";
 const VALIDATOR_ADDRESSES_KEY: &str = "validator_addresses";
 #[allow(missing_docs)]
 pub const VALIDATOR_STORAGE_PREFIX: &str = "validator";
@@ -43,6 +45,8 @@ const CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY: &str =
     "validator_rewards_accumulator";
 const LAST_REWARD_CLAIM_EPOCH: &str = "last_reward_claim_epoch";
 const REWARDS_COUNTER_KEY: &str = "validator_rewards_commissions";
+const AUTO_COMPOUND_KEY: &str = "auto_compound";
+const AUTO_COMPOUND_PROGRESS_KEY: &str = "auto_compound_progress";
 const VALIDATOR_INCOMING_REDELEGATIONS_KEY: &str = "incoming_redelegations";
 const VALIDATOR_OUTGOING_REDELEGATIONS_KEY: &str = "outgoing_redelegations";
 const VALIDATOR_TOTAL_REDELEGATED_BONDED_KEY: &str = "total_redelegated_bonded";
@@ -58,6 +62,37 @@ const LIVENESS_PREFIX: &str = "liveness";
 const LIVENESS_MISSED_VOTES: &str = "missed_votes";
 const LIVENESS_MISSED_VOTES_SUM: &str = "sum_missed_votes";
 
+/// The key segments of the PoS storage sub-spaces
+pub fn storage_keys() -> &'static [&'static str] {
+    &[
+        PARAMS_STORAGE_KEY,
+        VALIDATOR_MIN_SELF_BOND_KEY,
+        MAX_STAKE_TO_SELF_BOND_RATIO_KEY,
+        VALIDATOR_ADDRESSES_KEY,
+        VALIDATOR_STORAGE_PREFIX,
+        VALIDATOR_ADDRESS_RAW_HASH,
+        REWARDS_COUNTER_KEY,
+        SLASHES_PREFIX,
+        ENQUEUED_SLASHES_KEY,
+        BOND_STORAGE_KEY,
+        UNBOND_STORAGE_KEY,
+        VALIDATOR_TOTAL_BONDED_STORAGE_KEY,
+        VALIDATOR_SETS_STORAGE_PREFIX,
+        TOTAL_CONSENSUS_STAKE_STORAGE_KEY,
+        TOTAL_DELTAS_STORAGE_KEY,
+        VALIDATOR_SET_POSITIONS_KEY,
+        CONSENSUS_KEYS,
+        LAST_BLOCK_PROPOSER_STORAGE_KEY,
+        CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY,
+        LAST_REWARD_CLAIM_EPOCH,
+        AUTO_COMPOUND_KEY,
+        AUTO_COMPOUND_PROGRESS_KEY,
+        DELEGATOR_REDELEGATED_BONDS_KEY,
+        DELEGATOR_REDELEGATED_UNBONDS_KEY,
+        LIVENESS_PREFIX,
+    ]
+}
+
 /// Is the given key a PoS storage key?
 pub fn is_pos_key(key: &Key) -> bool {
     match &key.segments.get(0) {
@@ -73,9 +108,23 @@ pub fn params_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for the PoS parameter of the minimum validator self-bond.
+pub fn validator_min_self_bond_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VALIDATOR_MIN_SELF_BOND_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the PoS parameter of the maximum stake to self-bond ratio.
+pub fn max_stake_to_self_bond_ratio_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&MAX_STAKE_TO_SELF_BOND_RATIO_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Is storage key for PoS parameters?
 pub fn is_params_key(key: &Key) -> bool {
-    matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == PARAMS_STORAGE_KEY)
+    matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && (key == PARAMS_STORAGE_KEY || key == VALIDATOR_MIN_SELF_BOND_KEY || key == MAX_STAKE_TO_SELF_BOND_RATIO_KEY))
 }
 
 /// Storage key prefix for validator data.
@@ -772,6 +821,48 @@ pub fn last_pos_reward_claim_epoch_key(
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage prefix for the bonds whose rewards are automatically re-bonded at
+/// every new epoch.
+pub fn auto_compound_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&AUTO_COMPOUND_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the progress of the automatic re-bonding of the rewards in
+/// the current epoch.
+pub fn auto_compound_progress_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&AUTO_COMPOUND_PROGRESS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the opt-in of a bond into the automatic re-bonding of its
+/// rewards.
+pub fn auto_compound_key(source: &Address, validator: &Address) -> Key {
+    auto_compound_prefix()
+        .push(&source.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the auto-compound opt-in of a bond? If it is, returns
+/// the bond ID.
+pub fn is_auto_compound_key(key: &Key) -> Option<BondId> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::AddressSeg(source), DbKeySeg::AddressSeg(validator)]
+            if addr == &ADDRESS && prefix == AUTO_COMPOUND_KEY =>
+        {
+            Some(BondId {
+                source: source.clone(),
+                validator: validator.clone(),
+            })
+        }
+        _ => None,
+    }
+}
+
 /// Get validator address from bond key
 pub fn get_validator_address_from_bond(key: &Key) -> Option<Address> {
     match key.get_at(3) {
@@ -10,13 +10,14 @@ use std::ops::{Deref, Range};
 use std::str::FromStr;
 
 use assert_matches::assert_matches;
+use borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::ledger::storage::testing::TestWlStorage;
 use namada_core::ledger::storage_api::collections::lazy_map::{
     self, Collectable, NestedMap,
 };
 use namada_core::ledger::storage_api::collections::LazyCollection;
 use namada_core::ledger::storage_api::token::{credit_tokens, read_balance};
-use namada_core::ledger::storage_api::StorageRead;
+use namada_core::ledger::storage_api::{StorageRead, StorageWrite};
 use namada_core::types::address::testing::{
     address_from_simple_seed, arb_established_address, established_address_1,
     established_address_2, established_address_3,
@@ -42,6 +43,7 @@ use crate::epoched::DEFAULT_NUM_PAST_EPOCHS;
 use crate::parameters::testing::arb_pos_params;
 use crate::parameters::{OwnedPosParams, PosParams};
 use crate::rewards::PosRewardsCalculator;
+use crate::storage::params_key;
 use crate::test_utils::test_init_genesis;
 use crate::types::{
     into_tm_voting_power, BondDetails, BondId, BondsAndUnbondsDetails,
@@ -65,8 +67,8 @@ use crate::{
     is_validator, process_slashes,
     read_below_capacity_validator_set_addresses_with_stake,
     read_below_threshold_validator_set_addresses,
-    read_consensus_validator_set_addresses_with_stake, read_total_stake,
-    read_validator_deltas_value, read_validator_stake, slash,
+    read_consensus_validator_set_addresses_with_stake, read_pos_params,
+    read_total_stake, read_validator_deltas_value, read_validator_stake, slash,
     slash_redelegation, slash_validator, slash_validator_redelegation,
     staking_token_address, total_bonded_handle, total_deltas_handle,
     total_unbonded_handle, unbond_handle, unbond_tokens, unjail_validator,
@@ -326,6 +328,38 @@ proptest! {
     }
 }
 
+proptest! {
+    // Generate arb valid input for `test_self_bond_limits`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_self_bond_limits(
+
+    genesis_validators in arb_genesis_validators(1..2, None),
+
+    ) {
+        test_self_bond_limits_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_compound_rewards`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_compound_rewards(
+
+    genesis_validators in arb_genesis_validators(2..3, None),
+
+    ) {
+        test_compound_rewards_aux(genesis_validators)
+    }
+}
+
 fn arb_params_and_genesis_validators(
     num_max_validator_slots: Option<u64>,
     val_size: Range<usize>,
@@ -1362,6 +1396,100 @@ fn test_validator_raw_hash() {
     assert_eq!(found, Some(address));
 }
 
+/// Test that the params encoded before the addition of the self-bond params
+/// can still be read, with the self-bond params set to their defaults
+#[test]
+fn test_read_old_format_pos_params() {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+    // The params in the format written at genesis of the existing chains
+    let mut old_params = vec![];
+    params
+        .max_validator_slots
+        .serialize(&mut old_params)
+        .unwrap();
+    params.pipeline_len.serialize(&mut old_params).unwrap();
+    params.unbonding_len.serialize(&mut old_params).unwrap();
+    params
+        .tm_votes_per_token
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .block_proposer_reward
+        .serialize(&mut old_params)
+        .unwrap();
+    params.block_vote_reward.serialize(&mut old_params).unwrap();
+    params
+        .max_inflation_rate
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .target_staked_ratio
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .duplicate_vote_min_slash_rate
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .light_client_attack_min_slash_rate
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .cubic_slashing_window_length
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .validator_stake_threshold
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .liveness_window_check
+        .serialize(&mut old_params)
+        .unwrap();
+    params
+        .liveness_threshold
+        .serialize(&mut old_params)
+        .unwrap();
+    storage.write_bytes(&params_key(), old_params).unwrap();
+
+    let read = read_pos_params(&storage).unwrap();
+    assert_eq!(read.owned.pipeline_len, params.pipeline_len);
+    assert_eq!(read.owned.liveness_threshold, params.liveness_threshold);
+    assert_eq!(read.owned.validator_min_self_bond, token::Amount::zero());
+    assert_eq!(read.owned.max_stake_to_self_bond_ratio, None);
+
+    // The self-bond params written since are read back
+    let params = OwnedPosParams {
+        validator_min_self_bond: token::Amount::native_whole(10),
+        max_stake_to_self_bond_ratio: Some(Dec::two()),
+        ..params
+    };
+    write_pos_params(&mut storage, &params).unwrap();
+    let read = read_pos_params(&storage).unwrap();
+    assert_eq!(
+        read.owned.validator_min_self_bond,
+        params.validator_min_self_bond
+    );
+    assert_eq!(
+        read.owned.max_stake_to_self_bond_ratio,
+        params.max_stake_to_self_bond_ratio
+    );
+
+    // The encoding of all the params, as sent over RPC, keeps them
+    let mut bytes = vec![];
+    read.serialize(&mut bytes).unwrap();
+    let decoded = PosParams::try_from_slice(&bytes).unwrap();
+    assert_eq!(
+        decoded.owned.validator_min_self_bond,
+        params.validator_min_self_bond
+    );
+    assert_eq!(
+        decoded.owned.max_stake_to_self_bond_ratio,
+        params.max_stake_to_self_bond_ratio
+    );
+}
+
 #[test]
 fn test_validator_sets() {
     let mut s = TestWlStorage::default();
@@ -6881,3 +7009,327 @@ fn test_is_delegator_aux(mut validators: Vec<GenesisValidator>) {
         .unwrap()
     );
 }
+
+fn test_compound_rewards_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
+    let other_validator = validators[1].address.clone();
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+    // Compound the rewards of all the bonds taken in the current epoch
+    let compound_rewards = |storage: &mut TestWlStorage, epoch: Epoch| {
+        super::take_auto_compound_bonds(storage, epoch, usize::MAX)
+            .unwrap()
+            .into_iter()
+            .filter_map(|bond_id| {
+                super::compound_bond_rewards(storage, &params, &bond_id, epoch)
+                    .unwrap()
+                    .map(|amount| (bond_id, amount))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        del_balance,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Only an existing bond to a validator can opt in, by a source that isn't
+    // one
+    let non_validator = address::testing::gen_implicit_address();
+    assert!(super::set_auto_compound(
+        &mut storage,
+        Some(&delegator),
+        &non_validator,
+        true
+    )
+    .is_err());
+    assert!(super::set_auto_compound(
+        &mut storage,
+        Some(&delegator),
+        &other_validator,
+        true
+    )
+    .is_err());
+    super::set_auto_compound(&mut storage, Some(&delegator), &validator, true)
+        .unwrap();
+    assert!(super::is_auto_compound(&storage, &delegator, &validator).unwrap());
+    assert_eq!(
+        super::find_auto_compound_validators(&storage, &delegator).unwrap(),
+        BTreeSet::from([validator.clone()])
+    );
+
+    // Tally some rewards and compound them at the next epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    let rewards = token::Amount::native_whole(10);
+    super::add_rewards_to_counter(
+        &mut storage,
+        &delegator,
+        &validator,
+        rewards,
+    )
+    .unwrap();
+    let compounded = compound_rewards(&mut storage, current_epoch);
+    let bond_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
+    assert_eq!(compounded, vec![(bond_id, rewards)]);
+
+    // The rewards are bonded at the pipeline epoch and not left liquid
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    assert_eq!(
+        bond_handle(&delegator, &validator)
+            .get_sum(&storage, pipeline_epoch, &params)
+            .unwrap()
+            .unwrap_or_default(),
+        del_balance + rewards
+    );
+    assert!(read_balance(&storage, &staking_token, &delegator)
+        .unwrap()
+        .is_zero());
+    assert!(super::query_reward_tokens(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch
+    )
+    .unwrap()
+    .is_zero());
+
+    // After opting out, the rewards are left to be claimed
+    super::set_auto_compound(&mut storage, Some(&delegator), &validator, false)
+        .unwrap();
+    assert!(!super::is_auto_compound(&storage, &delegator, &validator).unwrap());
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::add_rewards_to_counter(
+        &mut storage,
+        &delegator,
+        &validator,
+        rewards,
+    )
+    .unwrap();
+    assert!(compound_rewards(&mut storage, current_epoch).is_empty());
+    assert_eq!(
+        super::query_reward_tokens(
+            &storage,
+            Some(&delegator),
+            &validator,
+            current_epoch
+        )
+        .unwrap(),
+        rewards
+    );
+
+    // The opt-in is removed when the bond is fully unbonded
+    super::set_auto_compound(&mut storage, Some(&delegator), &validator, true)
+        .unwrap();
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let bonded = bond_handle(&delegator, &validator)
+        .get_sum(&storage, pipeline_epoch, &params)
+        .unwrap()
+        .unwrap_or_default();
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        bonded,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    assert!(!super::is_auto_compound(&storage, &delegator, &validator).unwrap());
+
+    // The bonds are taken at most `limit` at a time, continuing after the
+    // last taken one until they've all been taken in the epoch
+    let validator_bond = BondId {
+        source: validator.clone(),
+        validator: validator.clone(),
+    };
+    let other_bond = BondId {
+        source: other_validator.clone(),
+        validator: other_validator.clone(),
+    };
+    for BondId { source, validator } in [&validator_bond, &other_bond] {
+        super::set_auto_compound(&mut storage, Some(source), validator, true)
+            .unwrap();
+    }
+    current_epoch = advance_epoch(&mut storage, &params);
+    let mut taken = vec![];
+    for _ in 0..2 {
+        let bonds =
+            super::take_auto_compound_bonds(&mut storage, current_epoch, 1)
+                .unwrap();
+        assert_eq!(bonds.len(), 1);
+        taken.extend(bonds);
+    }
+    assert!(
+        super::take_auto_compound_bonds(&mut storage, current_epoch, 1)
+            .unwrap()
+            .is_empty()
+    );
+    taken.sort();
+    let mut expected = vec![validator_bond, other_bond];
+    expected.sort();
+    assert_eq!(taken, expected);
+    // The progress is reset at the next epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    assert_eq!(
+        super::take_auto_compound_bonds(&mut storage, current_epoch, 10)
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+fn test_self_bond_limits_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
+    let self_bond = validators[0].tokens;
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        validator_min_self_bond: self_bond,
+        max_stake_to_self_bond_ratio: Some(Dec::two()),
+        ..Default::default()
+    };
+
+    // Genesis
+    let current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    for owner in [&delegator, &validator] {
+        credit_tokens(&mut storage, &staking_token, owner, self_bond * 2)
+            .unwrap();
+    }
+
+    // A delegation can't make the stake exceed twice the self-bond
+    let res = bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        self_bond + token::Amount::from(1_u64),
+        current_epoch,
+        None,
+    );
+    assert!(res.is_err());
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        self_bond,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // A self-bond is not limited and allows more delegations
+    bond_tokens(
+        &mut storage,
+        None,
+        &validator,
+        self_bond,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+    bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        self_bond,
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // An unbond of the self-bond can't make the stake exceed twice the
+    // remaining self-bond
+    let res = unbond_tokens(
+        &mut storage,
+        None,
+        &validator,
+        token::Amount::from(1_u64),
+        current_epoch,
+        false,
+    );
+    assert!(res.is_err());
+
+    // Once the delegations are unbonded, the self-bond can't be unbonded
+    // below the minimum
+    unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        self_bond * 2,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    unbond_tokens(
+        &mut storage,
+        None,
+        &validator,
+        self_bond,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    let res = unbond_tokens(
+        &mut storage,
+        None,
+        &validator,
+        token::Amount::from(1_u64),
+        current_epoch,
+        false,
+    );
+    assert!(res.is_err());
+
+    // An inactive validator can unbond its whole self-bond
+    super::deactivate_validator(&mut storage, &validator, current_epoch)
+        .unwrap();
+    unbond_tokens(
+        &mut storage,
+        None,
+        &validator,
+        self_bond,
+        current_epoch,
+        false,
+    )
+    .unwrap();
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    assert!(bond_handle(&validator, &validator)
+        .get_sum(&storage, pipeline_epoch, &params)
+        .unwrap()
+        .unwrap_or_default()
+        .is_zero());
+}
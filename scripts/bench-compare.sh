@@ -0,0 +1,48 @@
+#!/usr/bin/env bash
+# Compare the benchmarks of the working tree against a baseline git ref and
+# exit with an error if any of them regressed.
+#
+# The benchmarks are first run on a worktree of the baseline ref and saved as a
+# criterion baseline, then run on the working tree and compared against it. The
+# worktree shares the target dir so that criterion finds the saved baseline.
+#
+# The first arg is the baseline ref (default `main`) and the second is the name
+# of the bench to run (default: all the benches). The noise threshold of the
+# comparison can be set with `BENCH_NOISE_THRESHOLD` (default 0.05, i.e. 5%).
+#
+# Usage example:
+# $ scripts/bench-compare.sh main storage
+
+set -eo pipefail
+
+BASE=${1:-main}
+BENCH=${2:+--bench $2}
+NOISE_THRESHOLD=${BENCH_NOISE_THRESHOLD:-0.05}
+SCRIPTPATH="$( cd -- "$(dirname "$0")" >/dev/null 2>&1 ; pwd -P )"
+ROOT="$SCRIPTPATH/.."
+export CARGO_TARGET_DIR=${CARGO_TARGET_DIR:-"$ROOT/target"}
+WORKTREE=$(mktemp -d)
+OUTPUT=$(mktemp)
+
+cleanup() {
+    git -C "$ROOT" worktree remove --force "$WORKTREE"
+    rm -f "$OUTPUT"
+}
+trap cleanup EXIT
+
+echo "Running the benchmarks of $BASE"
+git -C "$ROOT" worktree add --detach "$WORKTREE" "$BASE"
+(cd "$WORKTREE" && cargo bench --package namada_benchmarks $BENCH -- \
+    --save-baseline base)
+
+echo "Running the benchmarks of the working tree"
+(cd "$ROOT" && cargo bench --package namada_benchmarks $BENCH -- \
+    --baseline base --noise-threshold "$NOISE_THRESHOLD") | tee "$OUTPUT"
+
+if grep -q "Performance has regressed" "$OUTPUT"; then
+    echo "Some benchmarks regressed against $BASE:"
+    grep -B 3 "Performance has regressed" "$OUTPUT" | grep "time:" -B 1 \
+        | grep -v "time:\|--"
+    exit 1
+fi
+echo "No benchmark regressed against $BASE"
@@ -7,6 +7,7 @@ use std::time::Duration as StdDuration;
 use namada_core::ledger::governance::cli::onchain::{
     DefaultProposal, PgfFundingProposal, PgfStewardProposal,
 };
+use namada_core::types::account::{AccountPolicy, VestingSchedule};
 use namada_core::types::address::Address;
 use namada_core::types::chain::ChainId;
 use namada_core::types::dec::Dec;
@@ -106,6 +107,15 @@ pub struct Query<C: NamadaTypes = SdkTypes> {
     pub ledger_address: C::TendermintAddress,
 }
 
+/// Estimate the start of an epoch arguments
+#[derive(Clone, Debug)]
+pub struct QueryNextEpoch<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// The epoch whose start to estimate, defaults to the next epoch
+    pub epoch: Option<Epoch>,
+}
+
 /// Transaction associated results arguments
 #[derive(Clone, Debug)]
 pub struct QueryResult<C: NamadaTypes = SdkTypes> {
@@ -115,6 +125,17 @@ pub struct QueryResult<C: NamadaTypes = SdkTypes> {
     pub tx_hash: String,
 }
 
+/// Wait for a transaction to be applied arguments
+#[derive(Clone, Debug)]
+pub struct WaitTx<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Hash of the inner transaction to wait for
+    pub tx_hash: String,
+    /// How long to wait for the transaction to be applied
+    pub timeout: StdDuration,
+}
+
 /// Custom transaction arguments
 #[derive(Clone, Debug)]
 pub struct TxCustom<C: NamadaTypes = SdkTypes> {
@@ -290,6 +311,152 @@ impl TxTransfer {
     }
 }
 
+/// Testnet faucet withdrawal arguments
+#[derive(Clone, Debug)]
+pub struct FaucetRequest<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The target address of the withdrawal
+    pub target: C::Address,
+    /// The withdrawn token address
+    pub token: C::Address,
+    /// The withdrawn token amount
+    pub amount: InputAmount,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for FaucetRequest<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        FaucetRequest {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl FaucetRequest {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        tx::build_faucet_request(context, self).await
+    }
+}
+
+/// Token allowance approval arguments
+#[derive(Clone, Debug)]
+pub struct TxApprove<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The owner of the approved tokens
+    pub owner: C::Address,
+    /// The address allowed to spend the tokens
+    pub spender: C::Address,
+    /// The approved token address
+    pub token: C::Address,
+    /// The allowed amount, replacing any previous allowance
+    pub amount: InputAmount,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxApprove<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxApprove {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl TxApprove {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        tx::build_approve(context, self).await
+    }
+}
+
+/// Transfer from an allowance arguments
+#[derive(Clone, Debug)]
+pub struct TxTransferFrom<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The address spending the allowance
+    pub spender: C::Address,
+    /// The source address whose tokens are spent
+    pub source: C::Address,
+    /// The target address of the transfer
+    pub target: C::Address,
+    /// The transferred token address
+    pub token: C::Address,
+    /// The transferred token amount
+    pub amount: InputAmount,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for TxTransferFrom<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        TxTransferFrom {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl TxTransferFrom {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        tx::build_transfer_from(context, self).await
+    }
+}
+
+/// A transaction of a batch
+#[derive(Clone, Debug)]
+pub enum BatchTx<C: NamadaTypes = SdkTypes> {
+    /// A transfer
+    Transfer(TxTransfer<C>),
+    /// A bond
+    Bond(Bond<C>),
+}
+
+/// Batch of transactions arguments. The transactions are submitted one after
+/// the other, each one in its own wrapper.
+#[derive(Clone, Debug)]
+pub struct TxBatch<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments, shared by all the transactions
+    pub tx: Tx<C>,
+    /// The transactions to submit, in order
+    pub txs: Vec<BatchTx<C>>,
+}
+
+/// Fee and gas estimation arguments
+#[derive(Clone, Debug)]
+pub struct TxEstimate<C: NamadaTypes = SdkTypes> {
+    /// The transaction to estimate the costs of
+    pub tx: BatchTx<C>,
+}
+
 /// IBC transfer transaction arguments
 #[derive(Clone, Debug)]
 pub struct TxIbcTransfer<C: NamadaTypes = SdkTypes> {
@@ -664,6 +831,11 @@ pub struct TxInitAccount<C: NamadaTypes = SdkTypes> {
     pub public_keys: Vec<C::PublicKey>,
     /// The account multisignature threshold
     pub threshold: Option<u8>,
+    /// An optional salt to change the derived address of the new account
+    pub salt: Option<String>,
+    /// An optional release schedule of the native tokens locked in the new
+    /// account
+    pub vesting: Option<VestingSchedule>,
 }
 
 impl<C: NamadaTypes> TxBuilder<C> for TxInitAccount<C> {
@@ -695,6 +867,22 @@ impl<C: NamadaTypes> TxInitAccount<C> {
         }
     }
 
+    /// A salt to change the derived address of the new account
+    pub fn salt(self, salt: String) -> Self {
+        Self {
+            salt: Some(salt),
+            ..self
+        }
+    }
+
+    /// A release schedule of the native tokens locked in the new account
+    pub fn vesting(self, vesting: VestingSchedule) -> Self {
+        Self {
+            vesting: Some(vesting),
+            ..self
+        }
+    }
+
     /// Path to the VP WASM code file
     pub fn vp_code_path(self, vp_code_path: PathBuf) -> Self {
         Self {
@@ -814,6 +1002,18 @@ pub struct TxUpdateAccount<C: NamadaTypes = SdkTypes> {
     pub public_keys: Vec<C::PublicKey>,
     /// The account threshold
     pub threshold: Option<u8>,
+    /// The delay in epochs of the timelock to set on the VP updates
+    pub vp_update_delay: Option<u64>,
+    /// The recovery key of the timelock to set on the VP updates
+    pub recovery_key: Option<C::PublicKey>,
+    /// Cancel the VP update scheduled under the timelock
+    pub cancel_vp_update: bool,
+    /// The paths to the codes of the txs to allow to operate on the account.
+    /// An empty list lifts the restriction.
+    pub allowed_txs: Option<Vec<PathBuf>>,
+    /// The authorization policy to set on the account. A default policy
+    /// lifts the restrictions.
+    pub policy: Option<AccountPolicy>,
 }
 
 impl<C: NamadaTypes> TxBuilder<C> for TxUpdateAccount<C> {
@@ -865,6 +1065,39 @@ impl<C: NamadaTypes> TxUpdateAccount<C> {
             ..self
         }
     }
+
+    /// Set a timelock on the VP updates
+    pub fn vp_timelock(self, delay: u64, recovery_key: C::PublicKey) -> Self {
+        Self {
+            vp_update_delay: Some(delay),
+            recovery_key: Some(recovery_key),
+            ..self
+        }
+    }
+
+    /// Cancel the VP update scheduled under the timelock
+    pub fn cancel_vp_update(self, cancel_vp_update: bool) -> Self {
+        Self {
+            cancel_vp_update,
+            ..self
+        }
+    }
+
+    /// Restrict the txs allowed to operate on the account
+    pub fn allowed_txs(self, allowed_txs: Vec<PathBuf>) -> Self {
+        Self {
+            allowed_txs: Some(allowed_txs),
+            ..self
+        }
+    }
+
+    /// Set the authorization policy of the account
+    pub fn policy(self, policy: AccountPolicy) -> Self {
+        Self {
+            policy: Some(policy),
+            ..self
+        }
+    }
 }
 
 impl TxUpdateAccount {
@@ -1146,6 +1379,17 @@ pub struct QueryProposal<C: NamadaTypes = SdkTypes> {
     pub proposal_id: Option<u64>,
 }
 
+/// Query the votes of a proposal
+#[derive(Clone, Debug)]
+pub struct QueryProposalVotes<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Proposal id
+    pub proposal_id: u64,
+    /// Show the votes one page at a time
+    pub paginate: bool,
+}
+
 /// Query protocol parameters
 #[derive(Clone, Debug)]
 pub struct QueryProtocolParameters<C: NamadaTypes = SdkTypes> {
@@ -1221,6 +1465,22 @@ impl Withdraw {
     }
 }
 
+/// Auto-withdraw arguments
+#[derive(Clone, Debug)]
+pub struct AutoWithdraw<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// The owner of the bonds whose matured unbonds are withdrawn
+    pub owner: C::Address,
+    /// The amount of time to sleep between the checks of the unbonds
+    pub interval: Duration,
+    /// Only withdraw the unbonds that are matured now and exit, instead of
+    /// checking them periodically
+    pub if_matured: bool,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
 /// Claim arguments
 #[derive(Clone, Debug)]
 pub struct ClaimRewards<C: NamadaTypes = SdkTypes> {
@@ -1258,6 +1518,60 @@ impl ClaimRewards {
     }
 }
 
+/// Auto-compound arguments
+#[derive(Clone, Debug)]
+pub struct AutoCompound<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address
+    pub validator: C::Address,
+    /// Source address of the bond. For self-bonds, the validator is also the
+    /// source
+    pub source: Option<C::Address>,
+    /// Whether to re-bond the rewards of the bond at every new epoch
+    pub enabled: bool,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for AutoCompound<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        AutoCompound {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> AutoCompound<C> {
+    /// Source address of the bond
+    pub fn source(self, source: C::Address) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    /// Whether to re-bond the rewards of the bond
+    pub fn enabled(self, enabled: bool) -> Self {
+        Self { enabled, ..self }
+    }
+}
+
+impl AutoCompound {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        tx::build_auto_compound(context, self).await
+    }
+}
+
 /// Query asset conversions
 #[derive(Clone, Debug)]
 pub struct QueryConversions<C: NamadaTypes = SdkTypes> {
@@ -1278,6 +1592,24 @@ pub struct QueryAccount<C: NamadaTypes = SdkTypes> {
     pub owner: C::Address,
 }
 
+/// Query the storage usage of an address
+#[derive(Clone, Debug)]
+pub struct QueryStorageUsage<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// The owner of the storage, all the addresses if not given
+    pub owner: Option<C::Address>,
+}
+
+/// Query the total supply of a token
+#[derive(Clone, Debug)]
+pub struct QueryTotalSupply<C: NamadaTypes = SdkTypes> {
+    /// Common query args
+    pub query: Query<C>,
+    /// Address of a token
+    pub token: C::Address,
+}
+
 /// Query token balance(s)
 #[derive(Clone, Debug)]
 pub struct QueryBalance<C: NamadaTypes = SdkTypes> {
@@ -1770,6 +2102,8 @@ pub struct SignTx<C: NamadaTypes = SdkTypes> {
     pub tx_data: C::Data,
     /// The account address
     pub owner: C::Address,
+    /// Print the canonical document of the signature
+    pub print_sign_doc: bool,
 }
 
 /// Query PoS commission rate
@@ -1848,6 +2182,9 @@ pub struct Tx<C: NamadaTypes = SdkTypes> {
     pub dry_run_wrapper: bool,
     /// Dump the transaction bytes to file
     pub dump_tx: bool,
+    /// Dump the signed transaction as the body of a CometBFT
+    /// `broadcast_tx_sync` JSON-RPC request instead of submitting it
+    pub dump_broadcast_tx: bool,
     /// The output directory path to where serialize the data
     pub output_folder: Option<PathBuf>,
     /// Submit the transaction even if it doesn't pass client checks
@@ -1866,6 +2203,8 @@ pub struct Tx<C: NamadaTypes = SdkTypes> {
     pub fee_amount: Option<InputAmount>,
     /// The fee payer signing key
     pub wrapper_fee_payer: Option<C::PublicKey>,
+    /// The optional sequence number of the fee payer
+    pub sequence: Option<u64>,
     /// The token in which the fee is being paid
     pub fee_token: C::Address,
     /// The optional spending key for fee unshielding
@@ -1889,6 +2228,8 @@ pub struct Tx<C: NamadaTypes = SdkTypes> {
     pub password: Option<Zeroizing<String>>,
     /// Use device to sign the transaction
     pub use_device: bool,
+    /// Sign the transaction without asking for confirmation
+    pub skip_confirmation: bool,
 }
 
 /// Builder functions for Tx
@@ -1912,6 +2253,14 @@ pub trait TxBuilder<C: NamadaTypes>: Sized {
     fn dump_tx(self, dump_tx: bool) -> Self {
         self.tx(|x| Tx { dump_tx, ..x })
     }
+    /// Dump the signed transaction as the body of a CometBFT
+    /// `broadcast_tx_sync` JSON-RPC request instead of submitting it
+    fn dump_broadcast_tx(self, dump_broadcast_tx: bool) -> Self {
+        self.tx(|x| Tx {
+            dump_broadcast_tx,
+            ..x
+        })
+    }
     /// The output directory path to where serialize the data
     fn output_folder(self, output_folder: PathBuf) -> Self {
         self.tx(|x| Tx {
@@ -1970,6 +2319,13 @@ pub trait TxBuilder<C: NamadaTypes>: Sized {
             ..x
         })
     }
+    /// The optional sequence number of the fee payer
+    fn sequence(self, sequence: u64) -> Self {
+        self.tx(|x| Tx {
+            sequence: Some(sequence),
+            ..x
+        })
+    }
     /// The token in which the fee is being paid
     fn fee_token(self, fee_token: C::Address) -> Self {
         self.tx(|x| Tx { fee_token, ..x })
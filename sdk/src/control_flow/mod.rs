@@ -42,6 +42,17 @@ impl Future for ShutdownSignal {
 /// Install a shutdown signal handler, and retrieve the associated
 /// signal's receiver.
 pub fn install_shutdown_signal() -> ShutdownSignal {
+    install_shutdown_signal_aux(true)
+}
+
+/// Like [`install_shutdown_signal`], but a hangup signal doesn't shut down.
+/// This is for the processes that reload their config on a hangup signal.
+pub fn install_shutdown_signal_without_hangup() -> ShutdownSignal {
+    install_shutdown_signal_aux(false)
+}
+
+#[cfg_attr(not(any(unix, windows)), allow(unused_variables))]
+fn install_shutdown_signal_aux(on_hangup: bool) -> ShutdownSignal {
     // #[cfg(target_family = "wasm")]
     // {
     //     compile_error!("WASM shutdown signal not supported");
@@ -53,7 +64,7 @@ pub fn install_shutdown_signal() -> ShutdownSignal {
     {
         let (tx, rx) = oneshot::channel();
         tokio::spawn(async move {
-            shutdown_send(tx).await;
+            shutdown_send(tx, on_hangup).await;
         });
         ShutdownSignal { rx }
     }
@@ -66,7 +77,7 @@ pub fn install_shutdown_signal() -> ShutdownSignal {
 }
 
 #[cfg(unix)]
-async fn shutdown_send(tx: oneshot::Sender<()>) {
+async fn shutdown_send(tx: oneshot::Sender<()>, on_hangup: bool) {
     use tokio::signal::unix::{signal, SignalKind};
     let mut sigterm = signal(SignalKind::terminate()).unwrap();
     let mut sighup = signal(SignalKind::hangup()).unwrap();
@@ -88,7 +99,7 @@ async fn shutdown_send(tx: oneshot::Sender<()>) {
                 }
             }
         },
-        signal = sighup.recv() => {
+        signal = sighup.recv(), if on_hangup => {
             match signal {
                 Some(()) => tracing::info!("Received hangup signal, exiting..."),
                 None => tracing::error!("Hangup signal cannot be caught anymore, exiting..."),
@@ -107,7 +118,7 @@ async fn shutdown_send(tx: oneshot::Sender<()>) {
 }
 
 #[cfg(windows)]
-async fn shutdown_send(tx: oneshot::Sender<()>) {
+async fn shutdown_send(tx: oneshot::Sender<()>, _on_hangup: bool) {
     let mut sigbreak = tokio::signal::windows::ctrl_break().unwrap();
     tokio::select! {
         signal = tokio::signal::ctrl_c() => {
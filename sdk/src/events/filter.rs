@@ -0,0 +1,132 @@
+//! Filters to subscribe to the events emitted by the ledger.
+//!
+//! A filter is sent by a client when opening a subscription, and the events
+//! that match it are then pushed to the client as blocks are finalized.
+
+use namada_core::types::address::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::events::Event;
+
+/// Event attributes that hold the hash of a tx.
+const TX_HASH_ATTRIBUTES: [&str; 2] = ["hash", "inner_hash"];
+
+/// Event attributes that hold a JSON list of the addresses involved in a tx.
+const ADDRESSES_ATTRIBUTES: [&str; 2] = ["verifiers", "initialized_accounts"];
+
+/// A filter for the events emitted by the ledger. Each field that is set
+/// must match the event, an empty filter matches all the events.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Match the events of the tx with the given (wrapper or inner) hash
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    /// Match the events of the given type, e.g. `applied`
+    #[serde(default)]
+    pub event_type: Option<String>,
+    /// Match the events of the txs that involve the given address
+    #[serde(default)]
+    pub address: Option<Address>,
+}
+
+impl EventFilter {
+    /// Check if the given [`Event`] matches this filter.
+    pub fn matches(&self, event: &Event) -> bool {
+        if let Some(event_type) = &self.event_type {
+            if event.event_type.to_string() != *event_type {
+                return false;
+            }
+        }
+
+        if let Some(tx_hash) = &self.tx_hash {
+            let has_hash = TX_HASH_ATTRIBUTES.iter().any(|key| {
+                event
+                    .get(key)
+                    .map(|hash| hash.eq_ignore_ascii_case(tx_hash))
+                    .unwrap_or_default()
+            });
+            if !has_hash {
+                return false;
+            }
+        }
+
+        if let Some(address) = &self.address {
+            let involves_address = ADDRESSES_ATTRIBUTES.iter().any(|key| {
+                event
+                    .get(key)
+                    .and_then(|addresses| {
+                        serde_json::from_str::<Vec<Address>>(addresses).ok()
+                    })
+                    .map(|addresses| addresses.contains(address))
+                    .unwrap_or_default()
+            });
+            if !involves_address {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    use super::*;
+    use crate::events::{EventLevel, EventType};
+
+    fn applied_event() -> Event {
+        let mut event = Event {
+            event_type: EventType::Applied,
+            level: EventLevel::Tx,
+            attributes: HashMap::new(),
+        };
+        event["hash"] = "DEADBEEF".to_string();
+        event["inner_hash"] = "DEADBEEF".to_string();
+        event["verifiers"] =
+            serde_json::to_string(&vec![established_address_1()]).unwrap();
+        event
+    }
+
+    /// Test that an empty filter matches all events
+    #[test]
+    fn test_empty_filter_matches_all() {
+        assert!(EventFilter::default().matches(&applied_event()));
+    }
+
+    /// Test matching events on each of the filter fields
+    #[test]
+    fn test_filter_fields() {
+        let event = applied_event();
+
+        let filter = EventFilter {
+            tx_hash: Some("deadbeef".to_string()),
+            event_type: Some("applied".to_string()),
+            address: Some(established_address_1()),
+        };
+        assert!(filter.matches(&event));
+
+        let filter = EventFilter {
+            event_type: Some("accepted".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&event));
+
+        let filter = EventFilter {
+            tx_hash: Some("CAFEBABE".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&event));
+
+        let filter = EventFilter {
+            address: Some(established_address_2()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&event));
+    }
+}
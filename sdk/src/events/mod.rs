@@ -1,4 +1,5 @@
 //! Logic to do with events emitted by the ledger.
+pub mod filter;
 pub mod log;
 
 use std::collections::HashMap;
@@ -52,6 +53,23 @@ pub enum EventType {
     Proposal,
     /// The pgf payment
     PgfPayment,
+    /// The expired data removed from storage
+    Pruned,
+    /// The summary of the storage usage at the end of an epoch
+    StorageUsage,
+    /// A transfer rejected for changing the balance of a blocked address
+    TransferBlocked,
+    /// The value of an oracle feed updated at the end of a block
+    OracleUpdate,
+    /// The epoch hook run at an epoch boundary
+    EpochHook,
+    /// A governance proposal created by a tx
+    ProposalCreated,
+    /// A vote on a governance proposal cast by a tx
+    ProposalVote,
+    /// The tally of the votes on a governance proposal, at every epoch of its
+    /// voting period and at its end
+    ProposalTally,
 }
 
 impl Display for EventType {
@@ -62,6 +80,14 @@ impl Display for EventType {
             EventType::Ibc(t) => write!(f, "{}", t),
             EventType::Proposal => write!(f, "proposal"),
             EventType::PgfPayment => write!(f, "pgf_payment"),
+            EventType::Pruned => write!(f, "pruned"),
+            EventType::StorageUsage => write!(f, "storage_usage"),
+            EventType::TransferBlocked => write!(f, "transfer_blocked"),
+            EventType::OracleUpdate => write!(f, "oracle_update"),
+            EventType::EpochHook => write!(f, "epoch_hook"),
+            EventType::ProposalCreated => write!(f, "proposal_created"),
+            EventType::ProposalVote => write!(f, "proposal_vote"),
+            EventType::ProposalTally => write!(f, "proposal_tally"),
         }?;
         Ok(())
     }
@@ -76,6 +102,14 @@ impl FromStr for EventType {
             "applied" => Ok(EventType::Applied),
             "proposal" => Ok(EventType::Proposal),
             "pgf_payments" => Ok(EventType::PgfPayment),
+            "pruned" => Ok(EventType::Pruned),
+            "storage_usage" => Ok(EventType::StorageUsage),
+            "transfer_blocked" => Ok(EventType::TransferBlocked),
+            "oracle_update" => Ok(EventType::OracleUpdate),
+            "epoch_hook" => Ok(EventType::EpochHook),
+            "proposal_created" => Ok(EventType::ProposalCreated),
+            "proposal_vote" => Ok(EventType::ProposalVote),
+            "proposal_tally" => Ok(EventType::ProposalTally),
             // IBC
             "update_client" => Ok(EventType::Ibc("update_client".to_string())),
             "send_packet" => Ok(EventType::Ibc("send_packet".to_string())),
@@ -99,6 +133,7 @@ impl Event {
                     attributes: HashMap::new(),
                 };
                 event["hash"] = tx.header_hash().to_string();
+                event["inner_hash"] = tx.raw_header_hash().to_string();
                 event
             }
             TxType::Decrypted(_) => {
@@ -107,11 +142,13 @@ impl Event {
                     level: EventLevel::Tx,
                     attributes: HashMap::new(),
                 };
-                event["hash"] = tx
+                let inner_hash = tx
                     .clone()
                     .update_header(TxType::Raw)
                     .header_hash()
                     .to_string();
+                event["hash"] = inner_hash.clone();
+                event["inner_hash"] = inner_hash;
                 event
             }
             TxType::Protocol(_) => {
@@ -140,6 +177,21 @@ impl Event {
     pub fn get(&self, key: &str) -> Option<&String> {
         self.attributes.get(key)
     }
+
+    /// Encode the event as JSON, in the same format as the events returned
+    /// in Tendermint subscription responses, such that its [`Attributes`] can
+    /// be parsed back.
+    pub fn to_json(&self) -> Value {
+        let attributes: Vec<Value> = self
+            .attributes
+            .iter()
+            .map(|(key, value)| serde_json::json!({"key": key, "value": value}))
+            .collect();
+        serde_json::json!({
+            "type": self.event_type.to_string(),
+            "attributes": attributes,
+        })
+    }
 }
 
 impl Index<&str> for Event {
@@ -46,6 +46,16 @@ pub trait Io {
         eprintln!("{}", output.as_ref());
     }
 
+    /// Print a machine-readable result. The human-readable output is expected
+    /// to be printed separately, so this is ignored unless the handler is
+    /// configured to output JSON.
+    fn print_json(
+        &self,
+        _output: &impl serde::Serialize,
+    ) -> std::io::Result<()> {
+        Ok(())
+    }
+
     /// Read a string from input
     async fn read(&self) -> std::io::Result<String> {
         #[cfg(not(target_family = "wasm"))]
@@ -205,6 +215,22 @@ macro_rules! edisplay_line {
     };
 }
 
+/// Convenience macro for printing a machine-readable result with
+/// [`Io::print_json`] from a command that doesn't return errors. A failure to
+/// print the result is reported on stderr.
+#[macro_export]
+macro_rules! display_json {
+    ($io:expr, $output:expr) => {
+        if let Err(err) = $io.print_json($output) {
+            $crate::edisplay_line!(
+                $io,
+                "Failed to print the JSON output: {}",
+                err
+            );
+        }
+    };
+}
+
 #[macro_export]
 /// A convenience macro for formatting the user prompt before
 /// forwarding it to the [`Io::prompt`] method.
@@ -1,3 +1,18 @@
+//! The Namada SDK, to query a ledger node and to build, sign and submit
+//! transactions without running a node, e.g. from a mobile or browser wallet.
+//!
+//! The SDK makes no assumption about the environment it runs in. The user
+//! interaction goes through the [`io::Io`] and [`wallet::WalletIo`] traits,
+//! the wallet is persisted through the [`wallet::WalletStorage`] trait and the
+//! shielded context through the [`masp::ShieldedUtils`] trait. Implementations
+//! of the storage traits backed by the file system are available with the
+//! `std` feature.
+//!
+//! The SDK compiles to `wasm32-unknown-unknown` without its default features.
+//! The `fetch-client` feature provides an RPC client that uses the browser's
+//! `fetch` API and the `wasm-bindings` feature the JavaScript bindings of the
+//! key types.
+
 extern crate alloc;
 
 pub use namada_core::{ibc, proto, tendermint, tendermint_proto};
@@ -25,6 +40,8 @@ pub(crate) mod internal_macros;
 pub mod io;
 pub mod queries;
 pub mod wallet;
+#[cfg(feature = "wasm-bindings")]
+pub mod wasm_bindings;
 
 use std::collections::HashSet;
 #[cfg(feature = "async-send")]
@@ -55,15 +72,16 @@ use crate::rpc::{
 use crate::signing::SigningTxData;
 use crate::token::DenominatedAmount;
 use crate::tx::{
-    ProcessTxResponse, TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM,
-    TX_BRIDGE_POOL_WASM, TX_CHANGE_COMMISSION_WASM,
-    TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_METADATA_WASM,
-    TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM,
+    ProcessTxResponse, TX_APPROVE_WASM, TX_AUTO_COMPOUND_WASM,
+    TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM, TX_BRIDGE_POOL_WASM,
+    TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
+    TX_CHANGE_METADATA_WASM, TX_CLAIM_REWARDS_WASM,
+    TX_DEACTIVATE_VALIDATOR_WASM, TX_FAUCET_WITHDRAW_WASM, TX_IBC_WASM,
     TX_INIT_ACCOUNT_WASM, TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM,
-    TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM,
-    TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
-    TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-    VP_USER_WASM,
+    TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_FROM_WASM,
+    TX_TRANSFER_WASM, TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM,
+    TX_UPDATE_ACCOUNT_WASM, TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL,
+    TX_WITHDRAW_WASM, VP_USER_WASM,
 };
 use crate::wallet::{Wallet, WalletIo, WalletStorage};
 
@@ -124,6 +142,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             dry_run: false,
             dry_run_wrapper: false,
             dump_tx: false,
+            dump_broadcast_tx: false,
             output_folder: None,
             force: false,
             broadcast_only: false,
@@ -132,6 +151,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             wallet_alias_force: false,
             fee_amount: None,
             wrapper_fee_payer: None,
+            sequence: None,
             fee_token: self.native_token(),
             fee_unshield: None,
             gas_limit: GasLimit::from(20_000),
@@ -143,6 +163,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             tx_reveal_code_path: PathBuf::from(TX_REVEAL_PK),
             password: None,
             use_device: false,
+            skip_confirmation: false,
         }
     }
 
@@ -165,6 +186,60 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a FaucetRequest builder from the given minimum set of arguments
+    fn new_faucet_request(
+        &self,
+        target: Address,
+        token: Address,
+        amount: InputAmount,
+    ) -> args::FaucetRequest {
+        args::FaucetRequest {
+            target,
+            token,
+            amount,
+            tx_code_path: PathBuf::from(TX_FAUCET_WITHDRAW_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a TxApprove builder from the given minimum set of arguments
+    fn new_approve(
+        &self,
+        owner: Address,
+        spender: Address,
+        token: Address,
+        amount: InputAmount,
+    ) -> args::TxApprove {
+        args::TxApprove {
+            owner,
+            spender,
+            token,
+            amount,
+            tx_code_path: PathBuf::from(TX_APPROVE_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a TxTransferFrom builder from the given minimum set of arguments
+    fn new_transfer_from(
+        &self,
+        spender: Address,
+        source: Address,
+        target: Address,
+        token: Address,
+        amount: InputAmount,
+    ) -> args::TxTransferFrom {
+        args::TxTransferFrom {
+            spender,
+            source,
+            target,
+            token,
+            amount,
+            tx_code_path: PathBuf::from(TX_TRANSFER_FROM_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a InitAccount builder from the given minimum set of arguments
     fn new_init_account(
         &self,
@@ -177,6 +252,8 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             tx_code_path: PathBuf::from(TX_INIT_ACCOUNT_WASM),
             public_keys,
             threshold,
+            salt: None,
+            vesting: None,
         }
     }
 
@@ -286,6 +363,11 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             vp_code_path: None,
             public_keys: vec![],
             threshold: None,
+            vp_update_delay: None,
+            recovery_key: None,
+            cancel_vp_update: false,
+            allowed_txs: None,
+            policy: None,
             tx_code_path: PathBuf::from(TX_UPDATE_ACCOUNT_WASM),
             tx: self.tx_builder(),
         }
@@ -476,6 +558,17 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make an Auto-compound builder from the given minimum set of arguments
+    fn new_auto_compound(&self, validator: Address) -> args::AutoCompound {
+        args::AutoCompound {
+            validator,
+            source: None,
+            enabled: true,
+            tx_code_path: PathBuf::from(TX_AUTO_COMPOUND_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a Withdraw builder from the given minimum set of arguments
     fn new_add_erc20_transfer(
         &self,
@@ -641,6 +734,7 @@ where
                 dry_run: false,
                 dry_run_wrapper: false,
                 dump_tx: false,
+                dump_broadcast_tx: false,
                 output_folder: None,
                 force: false,
                 broadcast_only: false,
@@ -649,6 +743,7 @@ where
                 wallet_alias_force: false,
                 fee_amount: None,
                 wrapper_fee_payer: None,
+                sequence: None,
                 fee_token: native_token,
                 fee_unshield: None,
                 gas_limit: GasLimit::from(20_000),
@@ -660,6 +755,7 @@ where
                 tx_reveal_code_path: PathBuf::from(TX_REVEAL_PK),
                 password: None,
                 use_device: false,
+                skip_confirmation: false,
             },
         }
     }
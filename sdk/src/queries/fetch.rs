@@ -0,0 +1,63 @@
+//! A client of the ledger's CometBFT RPC that sends the JSON-RPC requests over
+//! HTTP with `reqwest`. When compiled to `wasm32-unknown-unknown`, the
+//! requests are made with the browser's `fetch` API.
+
+use tendermint_rpc::{
+    Error as RpcError, Request, Response, SimpleRequest, Url,
+};
+
+use super::{abci_request, Client, EncodedResponseQuery, Error};
+use crate::core::types::storage::BlockHeight;
+
+/// A CometBFT RPC client that doesn't depend on a native async runtime
+#[derive(Clone, Debug)]
+pub struct FetchClient {
+    url: Url,
+    client: reqwest::Client,
+}
+
+impl FetchClient {
+    /// Create a client for the RPC at the given URL, e.g.
+    /// `http://127.0.0.1:26657`
+    pub fn new(url: &str) -> Result<Self, RpcError> {
+        Ok(Self {
+            url: url.parse()?,
+            client: reqwest::Client::new(),
+        })
+    }
+}
+
+#[cfg_attr(feature = "async-send", async_trait::async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait::async_trait(?Send))]
+impl Client for FetchClient {
+    type Error = Error;
+
+    async fn request(
+        &self,
+        path: String,
+        data: Option<Vec<u8>>,
+        height: Option<BlockHeight>,
+        prove: bool,
+    ) -> Result<EncodedResponseQuery, Self::Error> {
+        abci_request(self, path, data, height, prove).await
+    }
+
+    async fn perform<R>(&self, request: R) -> Result<R::Output, RpcError>
+    where
+        R: SimpleRequest,
+    {
+        let response = self
+            .client
+            .post(self.url.to_string())
+            .header("Content-Type", "application/json")
+            .body(request.into_json())
+            .send()
+            .await
+            .map_err(|err| RpcError::client_internal(err.to_string()))?;
+        let body = response
+            .text()
+            .await
+            .map_err(|err| RpcError::client_internal(err.to_string()))?;
+        R::Response::from_string(body).map(Into::into)
+    }
+}
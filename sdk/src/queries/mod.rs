@@ -6,8 +6,9 @@ use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::storage::{DBIter, DB};
 use namada_core::ledger::storage_api;
 use namada_core::types::storage::BlockHeight;
-pub use shell::Shell;
+pub use router::{Route, RouteSegment};
 use shell::SHELL;
+pub use shell::{NextEpochStart, Shell};
 pub use types::{
     EncodedResponseQuery, Error, RequestCtx, RequestQuery, ResponseQuery,
     Router,
@@ -22,12 +23,15 @@ use crate::{MaybeSend, MaybeSync};
 
 #[macro_use]
 mod router;
+#[cfg(feature = "fetch-client")]
+pub mod fetch;
 mod shell;
 mod types;
 pub mod vp;
 
-// Most commonly expected patterns should be declared first
-router! {RPC,
+// Most commonly expected patterns should be declared first. The standalone
+// client in `rpc_client` can be used for queries only.
+router! {RPC, client rpc_client,
     // Shell provides storage read access, block metadata and can dry-run a tx
     ( "shell" ) = (sub SHELL),
 
@@ -447,31 +451,7 @@ impl<C: tendermint_rpc::client::Client + std::marker::Sync> Client for C {
         height: Option<BlockHeight>,
         prove: bool,
     ) -> Result<EncodedResponseQuery, Self::Error> {
-        let data = data.unwrap_or_default();
-        let height = height
-            .map(|height| {
-                crate::tendermint::block::Height::try_from(height.0)
-                    .map_err(|_err| Error::InvalidHeight(height))
-            })
-            .transpose()?;
-        let response = self
-            .abci_query(
-                // TODO open the private Path constructor in tendermint-rpc
-                Some(std::str::FromStr::from_str(&path).unwrap()),
-                data,
-                height,
-                prove,
-            )
-            .await?;
-        use crate::tendermint::abci::Code;
-        match response.code {
-            Code::Ok => Ok(EncodedResponseQuery {
-                data: response.value,
-                info: response.info,
-                proof: response.proof,
-            }),
-            Code::Err(code) => Err(Error::Query(response.info, code.into())),
-        }
+        abci_request(self, path, data, height, prove).await
     }
 
     async fn perform<R>(&self, request: R) -> Result<R::Output, RpcError>
@@ -481,3 +461,42 @@ impl<C: tendermint_rpc::client::Client + std::marker::Sync> Client for C {
         tendermint_rpc::client::Client::perform(self, request).await
     }
 }
+
+/// Send a query request at the given path through the `/abci_query` endpoint
+/// of the client.
+async fn abci_request<C>(
+    client: &C,
+    path: String,
+    data: Option<Vec<u8>>,
+    height: Option<BlockHeight>,
+    prove: bool,
+) -> Result<EncodedResponseQuery, Error>
+where
+    C: Client + MaybeSync + ?Sized,
+{
+    let data = data.unwrap_or_default();
+    let height = height
+        .map(|height| {
+            crate::tendermint::block::Height::try_from(height.0)
+                .map_err(|_err| Error::InvalidHeight(height))
+        })
+        .transpose()?;
+    let response = client
+        .abci_query(
+            // TODO open the private Path constructor in tendermint-rpc
+            Some(std::str::FromStr::from_str(&path).unwrap()),
+            data,
+            height,
+            prove,
+        )
+        .await?;
+    use crate::tendermint::abci::Code;
+    match response.code {
+        Code::Ok => Ok(EncodedResponseQuery {
+            data: response.value,
+            info: response.info,
+            proof: response.proof,
+        }),
+        Code::Err(code) => Err(Error::Query(response.info, code.into())),
+    }
+}
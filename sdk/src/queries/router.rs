@@ -30,6 +30,56 @@ pub fn find_next_slash_index(path: &str, start: usize) -> usize {
         .unwrap_or(path.len())
 }
 
+/// A segment of the path of a [`Route`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouteSegment {
+    /// A literal segment
+    Literal(&'static str),
+    /// A dynamic argument
+    Arg {
+        /// The name of the argument
+        name: &'static str,
+        /// The type of the argument, parsed from the segment with `FromStr`.
+        /// It is `str` for an untyped argument.
+        ty: String,
+        /// An optional argument can be omitted from the path
+        optional: bool,
+    },
+}
+
+/// A route of a router, describing a query generated from a pattern and its
+/// handler
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Route {
+    /// The segments of the path of the query
+    pub segments: Vec<RouteSegment>,
+    /// The name of the handler
+    pub handler: &'static str,
+    /// The type of the borsh-encoded response data
+    pub return_type: String,
+}
+
+impl Route {
+    /// The path template of the route, in which the dynamic arguments are
+    /// given by their names in braces, e.g. `/pos/validator/stake/{validator}`
+    pub fn path_template(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                RouteSegment::Literal(literal) => format!("/{literal}"),
+                RouteSegment::Arg { name, .. } => format!("/{{{name}}}"),
+            })
+            .collect()
+    }
+
+    /// The dynamic arguments of the route
+    pub fn args(&self) -> impl Iterator<Item = &RouteSegment> {
+        self.segments
+            .iter()
+            .filter(|segment| matches!(segment, RouteSegment::Arg { .. }))
+    }
+}
+
 /// Invoke the sub-handler or call the handler function with the matched
 /// arguments generated by `try_match_segments`.
 macro_rules! handle_match {
@@ -619,6 +669,146 @@ macro_rules! pattern_and_handler_to_method {
     };
 }
 
+/// TT muncher macro that pushes the [`Route`]s of a pattern and its handler
+/// into the given `Vec`.
+macro_rules! pattern_to_routes {
+    // a sub router - push its routes, prefixed with the pattern
+    (
+        $routes:ident [ $( $segment:expr ),* ],
+        (sub $router:ident),
+        ( $( $pattern:literal )/ * )
+    ) => {
+        for mut route in $router.routes() {
+            let mut segments = vec![
+                $( $segment, )*
+                $( $crate::queries::router::RouteSegment::Literal($pattern) ),*
+            ];
+            segments.append(&mut route.segments);
+            route.segments = segments;
+            $routes.push(route);
+        }
+    };
+
+    // sub-pattern
+    (
+        $routes:ident [ $( $segment:expr ),* ],
+        { $( $sub_pattern:tt $( -> $sub_return_ty:path )? = $handle:tt, )* },
+        $pattern:tt
+    ) => {
+        $(
+            // join pattern with each sub-pattern
+            pattern_to_routes!(
+                $routes [ $( $segment ),* ] $( -> $sub_return_ty )?, $handle,
+                $pattern, $sub_pattern
+            );
+        )*
+    };
+
+    // terminal rule for $handle that uses request (`with_options`)
+    (
+        $routes:ident [ $( $segment:expr ),* ] -> $return_type:path,
+        (with_options $handle:tt),
+        ()
+    ) => {
+        pattern_to_routes!(
+            $routes [ $( $segment ),* ] -> $return_type, $handle, ()
+        );
+    };
+
+    // terminal rule
+    (
+        $routes:ident [ $( $segment:expr ),* ] -> $return_type:path,
+        $handle:tt,
+        ()
+    ) => {
+        $routes.push($crate::queries::router::Route {
+            segments: vec![ $( $segment ),* ],
+            handler: stringify!($handle),
+            return_type: stringify!($return_type).replace(' ', ""),
+        });
+    };
+
+    // literal string arg
+    (
+        $routes:ident [ $( $segment:expr ),* ] $( -> $return_type:path )?,
+        $handle:tt,
+        ( $pattern:literal $( / $tail:tt )* )
+    ) => {
+        pattern_to_routes!(
+            $routes [
+                $( $segment, )*
+                $crate::queries::router::RouteSegment::Literal($pattern)
+            ] $( -> $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+
+    // untyped arg
+    (
+        $routes:ident [ $( $segment:expr ),* ] $( -> $return_type:path )?,
+        $handle:tt,
+        ( [$name:tt] $( / $tail:tt )* )
+    ) => {
+        pattern_to_routes!(
+            $routes [
+                $( $segment, )*
+                $crate::queries::router::RouteSegment::Arg {
+                    name: stringify!($name),
+                    ty: "str".to_owned(),
+                    optional: false,
+                }
+            ] $( -> $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+
+    // typed arg
+    (
+        $routes:ident [ $( $segment:expr ),* ] $( -> $return_type:path )?,
+        $handle:tt,
+        ( [$name:tt: $type:ty] $( / $tail:tt )* )
+    ) => {
+        pattern_to_routes!(
+            $routes [
+                $( $segment, )*
+                $crate::queries::router::RouteSegment::Arg {
+                    name: stringify!($name),
+                    ty: stringify!($type).replace(' ', ""),
+                    optional: false,
+                }
+            ] $( -> $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+
+    // opt typed arg
+    (
+        $routes:ident [ $( $segment:expr ),* ] $( -> $return_type:path )?,
+        $handle:tt,
+        ( [$name:tt: opt $type:ty] $( / $tail:tt )* )
+    ) => {
+        pattern_to_routes!(
+            $routes [
+                $( $segment, )*
+                $crate::queries::router::RouteSegment::Arg {
+                    name: stringify!($name),
+                    ty: stringify!($type).replace(' ', ""),
+                    optional: true,
+                }
+            ] $( -> $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+
+    // join pattern with sub-pattern
+    (
+        $routes:ident [ $( $segment:expr ),* ] $( -> $return_type:path )?,
+        $handle:tt,
+        ( $( $pattern:tt )/ * ), ( $( $sub_pattern:tt )/ * )
+    ) => {
+        pattern_to_routes!(
+            $routes [ $( $segment ),* ] $( -> $return_type )?, $handle,
+            ( $( $pattern / )* $( $sub_pattern )/ * )
+        );
+    };
+}
+
 /// TT muncher macro that generates a `struct $name` with methods for all its
 /// handlers.
 macro_rules! router_type {
@@ -774,8 +964,46 @@ macro_rules! router_type {
 ///     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
 ///     H: 'static + StorageHasher + Sync;
 /// ```
+///
+/// The router can additionally be given a `client` module name, in which a
+/// standalone client of the router is emitted when the `rpc-client` feature
+/// is enabled. The client has the same type-safe path constructors and client
+/// query methods, but it doesn't implement
+/// [`Router`](crate::queries::Router), so it doesn't depend on the handlers
+/// nor on any node feature:
+/// ```rust,ignore
+/// router! {ROOT, client root_client,
+///   ( "pattern" ) -> ReturnType = handler,
+/// }
+///
+/// let result = root_client::ROOT.handler(&client).await?;
+/// ```
+///
+/// The routes of the router, e.g. to document its queries, are described by
+/// its `routes` method.
 #[macro_export]
 macro_rules! router {
+    { $name:ident, client $client:ident, $( $pattern:tt $( -> $return_type:path )? = $handle:tt , )* } => (
+
+    router!{$name, $( $pattern $( -> $return_type )? = $handle , )* }
+
+    // `paste!` is used to convert the $name cases for a derived type name
+    paste::paste! {
+        #[doc = "Standalone client of the `" $name "` router"]
+        #[cfg(any(test, feature = "rpc-client"))]
+        pub mod $client {
+            // Bring the types of the patterns and the sub-routers in scope
+            #[allow(unused_imports)]
+            use super::*;
+
+            router_type!{[<$name:camel>] {}, $( $pattern $( -> $return_type )? = $handle ),* }
+
+            #[doc = "`" $name "` path router client"]
+            pub const $name: [<$name:camel>] = [<$name:camel>]::new();
+        }
+    }
+
+    );
     { $name:ident, $( $pattern:tt $( -> $return_type:path )? = $handle:tt , )* } => (
 
 	// `paste!` is used to convert the $name cases for a derived type and function name
@@ -821,6 +1049,19 @@ macro_rules! router {
 			}
 		}
 
+		impl [<$name:camel>] {
+            #[doc = "The routes of all the queries of this router"]
+            pub fn routes(&self) -> Vec<$crate::queries::router::Route> {
+                let mut routes = Vec::new();
+                $(
+                    pattern_to_routes!(
+                        routes [] $( -> $return_type )?, $handle, $pattern
+                    );
+                )*
+                routes
+            }
+        }
+
 		#[doc = "`" $name "` path router"]
 		pub const $name: [<$name:camel>] = [<$name:camel>]::new();
 	}
@@ -969,7 +1210,7 @@ mod test_rpc {
     use super::test_rpc_handlers::*;
 
     // Setup an RPC router for testing
-    router! {TEST_RPC,
+    router! {TEST_RPC, client test_rpc_client,
         ( "sub" ) = (sub TEST_SUB_RPC),
         ( "a" ) -> String = a,
         ( "b" ) = {
@@ -1007,7 +1248,8 @@ mod test {
     use namada_core::types::token;
     use namada_core::types::token::NATIVE_MAX_DECIMAL_PLACES;
 
-    use super::test_rpc::TEST_RPC;
+    use super::test_rpc::{test_rpc_client, TEST_RPC};
+    use super::RouteSegment;
     use crate::queries::testing::TestClient;
     use crate::queries::{RequestCtx, RequestQuery, Router};
 
@@ -1117,4 +1359,99 @@ mod test {
 
         Ok(())
     }
+
+    /// Test that the standalone client of `TEST_RPC` router queries the same
+    /// paths as the router.
+    #[tokio::test]
+    async fn test_router_client() -> storage_api::Result<()> {
+        let client = TestClient::new(TEST_RPC);
+        let rpc_client = test_rpc_client::TEST_RPC;
+
+        let result = rpc_client.a(&client).await.unwrap();
+        assert_eq!(result, "a");
+
+        let a1 = token::DenominatedAmount {
+            amount: token::Amount::native_whole(345),
+            denom: NATIVE_MAX_DECIMAL_PLACES.into(),
+        };
+        let a2 = token::DenominatedAmount {
+            amount: token::Amount::native_whole(123_000),
+            denom: NATIVE_MAX_DECIMAL_PLACES.into(),
+        };
+        assert_eq!(
+            rpc_client.b3iii_path(&a1, &a2, &None),
+            TEST_RPC.b3iii_path(&a1, &a2, &None)
+        );
+        let result = rpc_client.b3iii(&client, &a1, &a2, &None).await.unwrap();
+        assert_eq!(result, format!("b3iii/{a1}/{a2}"));
+
+        let result = rpc_client.c(&client, None, None, false).await.unwrap();
+        assert_eq!(result.data, format!("c"));
+
+        let arg = "test123";
+        let result = rpc_client.test_sub_rpc().y(&client, arg).await.unwrap();
+        assert_eq!(result, format!("y/{arg}"));
+
+        Ok(())
+    }
+
+    /// Test that the routes of `TEST_RPC` router describe all its paths.
+    #[test]
+    fn test_router_routes() {
+        let routes = TEST_RPC.routes();
+        let templates: Vec<_> =
+            routes.iter().map(|route| route.path_template()).collect();
+        assert_eq!(
+            templates,
+            [
+                "/sub/x",
+                "/sub/y/{untyped_arg}",
+                "/sub/z/{untyped_arg}",
+                "/a",
+                "/b/0/i",
+                "/b/0/ii",
+                "/b/1",
+                "/b/2/i/{balance}",
+                "/b/3/{a1}/{a2}/i/{a3}",
+                "/b/3/{a1}/{a2}/{a3}",
+                "/b/3/{a1}/{a2}/{a3}/ii",
+                "/b/3/{a1}/{a2}/{a3}/iii",
+                "/b/3/{a1}/{a2}/iiii/{a3}/xyz/{a4}",
+                "/c",
+            ]
+        );
+
+        let b3iiii = routes
+            .iter()
+            .find(|route| route.handler == "b3iiii")
+            .unwrap();
+        assert_eq!(b3iiii.return_type, "String");
+        assert_eq!(
+            b3iiii.args().collect::<Vec<_>>(),
+            [
+                &RouteSegment::Arg {
+                    name: "a1",
+                    ty: "token::DenominatedAmount".to_owned(),
+                    optional: false,
+                },
+                &RouteSegment::Arg {
+                    name: "a2",
+                    ty: "token::DenominatedAmount".to_owned(),
+                    optional: false,
+                },
+                &RouteSegment::Arg {
+                    name: "a3",
+                    ty: "token::DenominatedAmount".to_owned(),
+                    optional: true,
+                },
+                &RouteSegment::Arg {
+                    name: "a4",
+                    ty: "Epoch".to_owned(),
+                    optional: true,
+                },
+            ]
+        );
+        let c = routes.iter().find(|route| route.handler == "c").unwrap();
+        assert_eq!(c.path_template(), "/c");
+    }
 }
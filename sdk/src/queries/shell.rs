@@ -2,21 +2,23 @@ use std::collections::BTreeMap;
 
 pub(super) mod eth_bridge;
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::sapling::Node;
 use namada_core::hints;
+use namada_core::ledger::masp_nullifiers;
 use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::storage::{DBIter, LastBlock, DB};
 use namada_core::ledger::storage_api::{self, ResultExt, StorageRead};
 use namada_core::types::account::{Account, AccountPublicKeysMap};
-use namada_core::types::address::Address;
+use namada_core::types::address::{Address, INTERNAL_ADDRESS_NAMES};
 use namada_core::types::hash::Hash;
 use namada_core::types::storage::{
     self, BlockHeight, BlockResults, Epoch, KeySeg, PrefixValue,
 };
+use namada_core::types::time::DateTimeUtc;
 use namada_core::types::token::MaspDenom;
 #[cfg(any(test, feature = "async-client"))]
 use namada_core::types::transaction::TxResult;
@@ -45,6 +47,18 @@ type Conversion = (
     MerklePath<Node>,
 );
 
+/// The conditions for the start of the next epoch
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct NextEpochStart {
+    /// The minimum height of the first block of the next epoch
+    pub min_start_height: BlockHeight,
+    /// The minimum time of the first block of the next epoch
+    pub min_start_time: DateTimeUtc,
+    /// Once the minimums have been reached, the number of blocks left until
+    /// the epoch switch
+    pub blocks_until_switch: Option<u32>,
+}
+
 router! {SHELL,
     // Shell provides storage read access, block metadata and can dry-run a tx
 
@@ -57,12 +71,18 @@ router! {SHELL,
     // The address of the native token
     ( "native_token" ) -> Address = native_token,
 
+    // The internal addresses of system accounts by their stable names
+    ( "internal_addresses" ) -> BTreeMap<String, Address> = internal_addresses,
+
     // Epoch of the input block height
     ( "epoch_at_height" / [height: BlockHeight]) -> Option<Epoch> = epoch_at_height,
 
     // Query the last committed block
     ( "last_block" ) -> Option<LastBlock> = last_block,
 
+    // The conditions for the start of the next epoch
+    ( "next_epoch_start" ) -> NextEpochStart = next_epoch_start,
+
     // Raw storage access - read value
     ( "value" / [storage_key: storage::Key] )
         -> Vec<u8> = (with_options storage_value),
@@ -78,6 +98,11 @@ router! {SHELL,
     ( "has_key" / [storage_key: storage::Key] )
         -> bool = storage_has_key,
 
+    // Is the MASP nullifier revealed? With a proof of its key, or of the
+    // absence of its key, in the merkle tree of the storage
+    ( "nullifier" / [nullifier: Hash] )
+        -> bool = (with_options masp_nullifier),
+
     // Conversion state access - read conversion
     ( "conv" / [asset_type: AssetType] ) -> Conversion = read_conversion,
 
@@ -93,9 +118,16 @@ router! {SHELL,
     // was the transaction applied?
     ( "applied" / [tx_hash: Hash] ) -> Option<Event> = applied,
 
+    // the result of an applied transaction, indexed on commit
+    ( "tx_result" / [tx_hash: Hash] ) -> Option<Event> = tx_result,
+
     // Query account subspace
     ( "account" / [owner: Address] ) -> Option<Account> = account,
 
+    // The bytes of state owned by an address, or by all the addresses if
+    // not given, as of the last committed block
+    ( "storage_usage" / [owner: opt Address] ) -> u64 = storage_usage,
+
     // Query public key revealad
     ( "revealed" / [owner: Address] ) -> bool = revealed,
 
@@ -232,6 +264,20 @@ where
     Ok(data)
 }
 
+fn internal_addresses<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<BTreeMap<String, Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let data = INTERNAL_ADDRESS_NAMES
+        .into_iter()
+        .map(|(name, address)| (name.to_string(), Address::Internal(address)))
+        .collect();
+    Ok(data)
+}
+
 fn epoch_at_height<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     height: BlockHeight,
@@ -253,6 +299,21 @@ where
     Ok(ctx.wl_storage.storage.last_block.clone())
 }
 
+fn next_epoch_start<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<NextEpochStart>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let storage = &ctx.wl_storage.storage;
+    Ok(NextEpochStart {
+        min_start_height: storage.next_epoch_min_start_height,
+        min_start_time: storage.next_epoch_min_start_time,
+        blocks_until_switch: storage.update_epoch_blocks_delay,
+    })
+}
+
 /// Returns data with `vec![]` when the storage key is not found. For all
 /// borsh-encoded types, it is safe to check `data.is_empty()` to see if the
 /// value was found, except for unit - see `fn query_storage_value` in
@@ -266,30 +327,7 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let last_committed_height = ctx.wl_storage.storage.get_last_block_height();
-    let queried_height = {
-        let height: BlockHeight = request.height.into();
-        let is_last_height_query = height.0 == 0;
-
-        if hints::likely(is_last_height_query) {
-            last_committed_height
-        } else {
-            height
-        }
-    };
-
-    if let Some(past_height_limit) = ctx.storage_read_past_height_limit {
-        if queried_height + past_height_limit < last_committed_height {
-            return Err(storage_api::Error::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                format!(
-                    "Cannot query more than {past_height_limit} blocks in the \
-                     past (configured via \
-                     `shell.storage_read_past_height_limit`)."
-                ),
-            )));
-        }
-    }
+    let queried_height = queried_height(&ctx, request)?;
 
     match ctx
         .wl_storage
@@ -334,6 +372,87 @@ where
     }
 }
 
+/// The height of a storage query, which is the last committed height unless
+/// another one is given. Checked against the configured limit of the past
+/// heights that can be queried.
+fn queried_height<D, H, V, T>(
+    ctx: &RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+) -> storage_api::Result<BlockHeight>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let last_committed_height = ctx.wl_storage.storage.get_last_block_height();
+    let queried_height = {
+        let height: BlockHeight = request.height.into();
+        let is_last_height_query = height.0 == 0;
+
+        if hints::likely(is_last_height_query) {
+            last_committed_height
+        } else {
+            height
+        }
+    };
+
+    if let Some(past_height_limit) = ctx.storage_read_past_height_limit {
+        if queried_height + past_height_limit < last_committed_height {
+            return Err(storage_api::Error::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Cannot query more than {past_height_limit} blocks in the \
+                     past (configured via \
+                     `shell.storage_read_past_height_limit`)."
+                ),
+            )));
+        }
+    }
+    Ok(queried_height)
+}
+
+/// Returns whether a MASP nullifier has been revealed. Unlike with
+/// `storage_value`, the revealed and unrevealed cases are told apart even
+/// though the nullifier set stores unit values.
+fn masp_nullifier<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+    nullifier: Hash,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let queried_height = queried_height(&ctx, request)?;
+    let storage_key = masp_nullifiers::nullifier_key(&nullifier);
+    let (value, _gas) = ctx
+        .wl_storage
+        .storage
+        .read_with_height(&storage_key, queried_height)
+        .into_storage_result()?;
+    let proof = if request.prove {
+        let proof = match &value {
+            Some(value) => ctx.wl_storage.storage.get_existence_proof(
+                &storage_key,
+                value,
+                queried_height,
+            ),
+            None => ctx
+                .wl_storage
+                .storage
+                .get_non_existence_proof(&storage_key, queried_height),
+        }
+        .into_storage_result()?;
+        Some(proof)
+    } else {
+        None
+    };
+    Ok(EncodedResponseQuery {
+        data: value.is_some().serialize_to_vec(),
+        proof,
+        info: Default::default(),
+    })
+}
+
 fn storage_prefix<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     request: &RequestQuery,
@@ -436,6 +555,37 @@ where
         .cloned())
 }
 
+fn tx_result<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    tx_hash: Hash,
+) -> storage_api::Result<Option<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    ctx.wl_storage
+        .storage
+        .read_tx_result(&tx_hash)
+        .into_storage_result()?
+        .map(|bytes| Event::try_from_slice(&bytes))
+        .transpose()
+        .into_storage_result()
+}
+
+fn storage_usage<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Option<Address>,
+) -> storage_api::Result<u64>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    ctx.wl_storage
+        .storage
+        .read_storage_usage(owner.as_ref())
+        .into_storage_result()
+}
+
 fn ibc_client_update<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     client_id: ClientId,
@@ -528,6 +678,7 @@ where
 
 #[cfg(test)]
 mod test {
+    use namada_core::types::hash::Hash;
     use namada_core::types::{address, token};
 
     use crate::queries::RPC;
@@ -537,6 +688,9 @@ mod test {
         let path = RPC.shell().epoch_path();
         assert_eq!("/shell/epoch", path);
 
+        let path = RPC.shell().next_epoch_start_path();
+        assert_eq!("/shell/next_epoch_start", path);
+
         let token_addr = address::testing::established_address_1();
         let owner = address::testing::established_address_2();
         let key = token::balance_key(&token_addr, &owner);
@@ -546,6 +700,10 @@ mod test {
         let path = RPC.shell().dry_run_tx_path();
         assert_eq!("/shell/dry_run_tx", path);
 
+        let nullifier = Hash([1; 32]);
+        let path = RPC.shell().masp_nullifier_path(&nullifier);
+        assert_eq!(format!("/shell/nullifier/{}", nullifier), path);
+
         let path = RPC.shell().storage_prefix_path(&key);
         assert_eq!(format!("/shell/prefix/{}", key), path);
 
@@ -9,9 +9,7 @@ use borsh_ext::BorshSerializeExt;
 use namada_core::ledger::eth_bridge::storage::bridge_pool::get_key_from_hash;
 use namada_core::ledger::storage::merkle_tree::StoreRef;
 use namada_core::ledger::storage::{DBIter, StorageHasher, StoreType, DB};
-use namada_core::ledger::storage_api::{
-    self, CustomError, ResultExt, StorageRead,
-};
+use namada_core::ledger::storage_api::{self, ResultExt, StorageRead};
 use namada_core::types::address::Address;
 use namada_core::types::eth_abi::{Encode, EncodeCell};
 use namada_core::types::eth_bridge_pool::{
@@ -211,7 +209,7 @@ where
     T: BorshDeserialize,
 {
     let Some(contract) = StorageRead::read(ctx.wl_storage, key)? else {
-        return Err(storage_api::Error::SimpleMessage(
+        return Err(storage_api::Error::new_const(
             "Failed to read contract: The Ethereum bridge \
              storage is not initialized",
         ));
@@ -274,7 +272,7 @@ where
         .wl_storage
         .ethbridge_queries()
         .get_signed_bridge_pool_root()
-        .ok_or(storage_api::Error::SimpleMessage(
+        .ok_or(storage_api::Error::new_const(
             "No signed root for the Ethereum bridge pool exists in storage.",
         ))
         .into_storage_result()?;
@@ -340,7 +338,7 @@ where
             .wl_storage
             .ethbridge_queries()
             .get_signed_bridge_pool_root()
-            .ok_or(storage_api::Error::SimpleMessage(
+            .ok_or(storage_api::Error::new_const(
                 "No signed root for the Ethereum bridge pool exists in \
                  storage.",
             ))
@@ -351,13 +349,10 @@ where
         let latest_bp_nonce =
             ctx.wl_storage.ethbridge_queries().get_bridge_pool_nonce();
         if latest_bp_nonce != signed_root.data.1 {
-            return Err(storage_api::Error::Custom(CustomError(
-                format!(
-                    "Mismatch between the nonce in the Bridge pool root proof \
-                     ({}) and the latest Bridge pool nonce in storage ({})",
-                    signed_root.data.1, latest_bp_nonce,
-                )
-                .into(),
+            return Err(storage_api::Error::new(format!(
+                "Mismatch between the nonce in the Bridge pool root proof \
+                 ({}) and the latest Bridge pool nonce in storage ({})",
+                signed_root.data.1, latest_bp_nonce,
             )));
         }
 
@@ -383,13 +378,10 @@ where
             })
             .unzip();
         if !missing_hashes.is_empty() {
-            return Err(storage_api::Error::Custom(CustomError(
-                format!(
-                    "One or more of the provided hashes had no corresponding \
-                     transfer in storage: {:?}",
-                    missing_hashes
-                )
-                .into(),
+            return Err(storage_api::Error::new(format!(
+                "One or more of the provided hashes had no corresponding \
+                 transfer in storage: {:?}",
+                missing_hashes
             )));
         }
         let (transfers, appendices) = values.iter().fold(
@@ -445,7 +437,7 @@ where
             Err(e) => Err(storage_api::Error::new(e)),
         }
     } else {
-        Err(storage_api::Error::SimpleMessage(
+        Err(storage_api::Error::new_const(
             "Could not deserialize transfers",
         ))
     }
@@ -537,30 +529,23 @@ where
     H: 'static + StorageHasher + Sync,
 {
     if epoch.0 == 0 {
-        return Err(storage_api::Error::Custom(CustomError(
+        return Err(storage_api::Error::new(
             "Validator set update proofs should only be requested from epoch \
-             1 onwards"
-                .into(),
-        )));
+             1 onwards",
+        ));
     }
     let current_epoch = ctx.wl_storage.storage.last_epoch;
     if epoch > current_epoch.next() {
-        return Err(storage_api::Error::Custom(CustomError(
-            format!(
-                "Requesting validator set update proof for {epoch:?}, but the \
-                 last installed epoch is still {current_epoch:?}"
-            )
-            .into(),
+        return Err(storage_api::Error::new(format!(
+            "Requesting validator set update proof for {epoch:?}, but the \
+             last installed epoch is still {current_epoch:?}"
         )));
     }
 
     if !ctx.wl_storage.ethbridge_queries().valset_upd_seen(epoch) {
-        return Err(storage_api::Error::Custom(CustomError(
-            format!(
-                "Validator set update proof is not yet available for the \
-                 queried epoch: {epoch:?}"
-            )
-            .into(),
+        return Err(storage_api::Error::new(format!(
+            "Validator set update proof is not yet available for the \
+             queried epoch: {epoch:?}"
         )));
     }
 
@@ -588,12 +573,9 @@ where
 {
     let current_epoch = ctx.wl_storage.storage.last_epoch;
     if epoch > current_epoch.next() {
-        Err(storage_api::Error::Custom(CustomError(
-            format!(
-                "Requesting Bridge validator set at {epoch:?}, but the last \
-                 installed epoch is still {current_epoch:?}"
-            )
-            .into(),
+        Err(storage_api::Error::new(format!(
+            "Requesting Bridge validator set at {epoch:?}, but the last \
+             installed epoch is still {current_epoch:?}"
         )))
     } else {
         Ok(ctx
@@ -618,12 +600,9 @@ where
 {
     let current_epoch = ctx.wl_storage.storage.last_epoch;
     if epoch > current_epoch.next() {
-        Err(storage_api::Error::Custom(CustomError(
-            format!(
-                "Requesting Governance validator set at {epoch:?}, but the \
-                 last installed epoch is still {current_epoch:?}"
-            )
-            .into(),
+        Err(storage_api::Error::new(format!(
+            "Requesting Governance validator set at {epoch:?}, but the \
+             last installed epoch is still {current_epoch:?}"
         )))
     } else {
         Ok(ctx
@@ -646,7 +625,7 @@ where
 {
     let maybe_epoch = ctx.wl_storage.pos_queries().get_epoch(height);
     let Some(epoch) = maybe_epoch else {
-        return Err(storage_api::Error::SimpleMessage(
+        return Err(storage_api::Error::new_const(
             "The epoch of the requested height does not exist",
         ));
     };
@@ -665,7 +644,7 @@ where
 {
     let current_epoch = ctx.wl_storage.storage.get_current_epoch().0;
     if epoch > current_epoch + 1u64 {
-        return Err(storage_api::Error::SimpleMessage(
+        return Err(storage_api::Error::new_const(
             "The requested epoch cannot be queried",
         ));
     }
@@ -725,7 +704,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
 
         // check the response
@@ -785,7 +764,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
 
         // check the response
@@ -838,7 +817,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
 
         // check the response
@@ -879,7 +858,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
 
         // check the response
@@ -1163,7 +1142,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
         client.wl_storage.storage.block.height += 1;
 
@@ -1191,7 +1170,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
         client.wl_storage.storage.block.height += 1;
 
@@ -1355,7 +1334,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
         client.wl_storage.storage.block.height += 1;
 
@@ -1374,7 +1353,7 @@ mod test_ethbridge_router {
         client
             .wl_storage
             .storage
-            .commit_block(MockDBWriteBatch)
+            .commit_block(MockDBWriteBatch::default())
             .expect("Test failed");
         client.wl_storage.storage.block.height += 1;
         let resp = RPC
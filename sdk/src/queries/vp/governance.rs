@@ -12,6 +12,8 @@ use crate::queries::types::RequestCtx;
 router! {GOV,
     ( "proposal" / [id: u64 ] ) -> Option<StorageProposal> = proposal_id,
     ( "proposal" / [id: u64 ] / "votes" ) -> Vec<Vote> = proposal_id_votes,
+    ( "proposal" / [id: u64 ] / "votes" / [offset: u64] / [limit: u64] )
+        -> Vec<Vote> = proposal_id_votes_page,
     ( "parameters" ) -> GovernanceParameters = parameters,
 }
 
@@ -39,6 +41,25 @@ where
     storage_api::governance::get_proposal_votes(ctx.wl_storage, id)
 }
 
+/// Get a page of the votes of a proposal, skipping the first `offset` votes.
+fn proposal_id_votes_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    id: u64,
+    offset: u64,
+    limit: u64,
+) -> storage_api::Result<Vec<Vote>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    storage_api::governance::get_proposal_votes_page(
+        ctx.wl_storage,
+        id,
+        offset,
+        limit,
+    )
+}
+
 /// Get the governane parameters
 fn parameters<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -122,6 +122,9 @@ router! {POS,
     ( "has_bonds" / [source: Address] )
         -> bool = has_bonds,
 
+    ( "auto_compound" / [source: Address] )
+        -> BTreeSet<Address> = auto_compound_validators,
+
 }
 
 /// Enriched bonds data with extra information calculated from the data queried
@@ -648,6 +651,22 @@ where
     namada_proof_of_stake::has_bonds(ctx.wl_storage, &source)
 }
 
+/// Find the validators of the bonds of the given `source` whose rewards are
+/// automatically re-bonded
+fn auto_compound_validators<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    source: Address,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    namada_proof_of_stake::find_auto_compound_validators(
+        ctx.wl_storage,
+        &source,
+    )
+}
+
 /// Client-only methods for the router type are composed from router functions.
 #[cfg(any(test, feature = "async-client"))]
 pub mod client_only_methods {
@@ -1,15 +1,28 @@
 //! Token validity predicate queries
 
+use std::collections::BTreeMap;
+
 use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
-use namada_core::ledger::storage_api;
-use namada_core::ledger::storage_api::token::read_denom;
+use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
+use namada_core::ledger::storage_api::{self, ResultExt};
+use namada_core::ledger::storage_api::token::{
+    read_balances, read_denom, read_metadata, read_total_supply,
+};
 use namada_core::types::address::Address;
 use namada_core::types::token;
 
-use crate::queries::RequestCtx;
+use crate::queries::{
+    require_latest_height, require_no_proof, EncodedResponseQuery, RequestCtx,
+    RequestQuery,
+};
 
 router! {TOKEN,
     ( "denomination" / [addr: Address] ) -> Option<token::Denomination> = denomination,
+    ( "total_supply" / [addr: Address] ) -> token::Amount = total_supply,
+    ( "metadata" / [addr: Address] ) -> Option<token::Metadata> = metadata,
+    ( "balances" / [owner: Address] )
+        -> BTreeMap<Address, token::Amount> = (with_options balances),
 }
 
 /// Get the number of decimal places (in base 10) for a
@@ -25,6 +38,53 @@ where
     read_denom(ctx.wl_storage, &addr)
 }
 
+/// Get the total supply of a token specified by `addr`.
+fn total_supply<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    addr: Address,
+) -> storage_api::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_total_supply(ctx.wl_storage, &addr)
+}
+
+/// Get the display metadata (symbol and name) of a token specified by
+/// `addr`, if any.
+fn metadata<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    addr: Address,
+) -> storage_api::Result<Option<token::Metadata>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_metadata(ctx.wl_storage, &addr)
+}
+
+/// Get the non-zero balances of the given `owner` for the list of tokens
+/// borsh-encoded in the request data.
+fn balances<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+    owner: Address,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    require_latest_height(&ctx, request)?;
+    require_no_proof(request)?;
+    let tokens = Vec::<Address>::try_from_slice(&request.data)
+        .into_storage_result()?;
+    let balances = read_balances(ctx.wl_storage, &owner, &tokens)?;
+    Ok(EncodedResponseQuery {
+        data: balances.serialize_to_vec(),
+        ..Default::default()
+    })
+}
+
 #[cfg(any(test, feature = "async-client"))]
 pub mod client_only_methods {
     use borsh::BorshDeserialize;
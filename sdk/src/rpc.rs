@@ -5,6 +5,7 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::ControlFlow;
 
 use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::sapling::Node;
@@ -14,23 +15,30 @@ use namada_core::ledger::governance::utils::Vote;
 use namada_core::ledger::ibc::storage::{
     ibc_denom_key, ibc_denom_key_prefix, is_ibc_denom_key,
 };
+use namada_core::ledger::parameters::{
+    storage as param_storage, EpochDuration,
+};
 use namada_core::ledger::storage::LastBlock;
-use namada_core::types::account::Account;
+use namada_core::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
+use namada_core::ledger::{oracle, testnet_pow, upgrade};
+use namada_core::types::account::{Account, VpUpdateTimelock};
 use namada_core::types::address::{Address, InternalAddress};
 use namada_core::types::hash::Hash;
-use namada_core::types::key::common;
+use namada_core::types::key::{common, sequence_key, vp_timelock_key};
 use namada_core::types::storage::{
     BlockHeight, BlockResults, Epoch, Key, PrefixValue,
 };
+use namada_core::types::time::{DateTimeUtc, Duration, DurationSecs};
 use namada_core::types::token::{
     Amount, DenominatedAmount, Denomination, MaspDenom,
 };
+use namada_core::types::transaction::ResultCode;
 use namada_core::types::{storage, token};
 use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::types::{
     BondsAndUnbondsDetails, CommissionPair, ValidatorMetaData, ValidatorState,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::args::InputAmount;
 use crate::control_flow::time;
@@ -116,6 +124,13 @@ pub async fn query_native_token<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().native_token(client).await)
 }
 
+/// Query the internal addresses of system accounts by their stable names
+pub async fn query_internal_addresses<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<BTreeMap<String, Address>, error::Error> {
+    convert_response::<C, _>(RPC.shell().internal_addresses(client).await)
+}
+
 /// Query the epoch of the given block height, if it exists.
 /// Will return none if the input block height is greater than
 /// the latest committed block height.
@@ -135,6 +150,153 @@ pub async fn query_block<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().last_block(client).await)
 }
 
+/// The number of recent blocks used to estimate the block time
+const RECENT_BLOCKS: u64 = 20;
+
+/// An estimate of the start of an epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochStartEstimate {
+    /// The epoch of the last committed block
+    pub current_epoch: Epoch,
+    /// The height of the last committed block
+    pub last_block_height: BlockHeight,
+    /// The epoch whose start is estimated
+    pub epoch: Epoch,
+    /// The number of blocks left until the first block of the epoch
+    pub blocks_remaining: u64,
+    /// The estimated height of the first block of the epoch
+    pub start_height: BlockHeight,
+    /// The estimated time of the first block of the epoch
+    pub start_time: DateTimeUtc,
+    /// The average time between the recent blocks, in milliseconds
+    pub average_block_time_ms: i64,
+}
+
+/// Estimate when the next epoch starts, from the conditions for its start and
+/// the average time between the recent blocks
+pub async fn query_next_epoch_estimate<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<EpochStartEstimate, error::Error> {
+    let current_epoch = query_epoch(client).await?;
+    query_epoch_start_estimate(client, current_epoch.next()).await
+}
+
+/// Estimate when the given future epoch starts, from the conditions for the
+/// start of the next epoch and the average time between the recent blocks.
+/// Every epoch after the next one is estimated to last until both its minimum
+/// number of blocks and its minimum duration have passed.
+pub async fn query_epoch_start_estimate<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Epoch,
+) -> Result<EpochStartEstimate, error::Error> {
+    let current_epoch = query_epoch(client).await?;
+    if epoch <= current_epoch {
+        return Err(Error::from(QueryError::General(format!(
+            "The epoch {epoch} has already started, the current epoch is \
+             {current_epoch}"
+        ))));
+    }
+    let last_block = query_block(client).await?.ok_or_else(|| {
+        Error::from(QueryError::General(
+            "No block has been committed yet".to_string(),
+        ))
+    })?;
+    let next_epoch_start =
+        convert_response::<C, _>(RPC.shell().next_epoch_start(client).await)?;
+
+    // Average the time between the recent blocks
+    let last_height = last_block.height.0;
+    let first_height = last_height.saturating_sub(RECENT_BLOCKS - 1).max(1);
+    let to_u32 = |height: u64| {
+        u32::try_from(height).map_err(|_| {
+            Error::from(QueryError::General(format!(
+                "The block height {height} is out of range"
+            )))
+        })
+    };
+    let blocks = client
+        .blockchain(to_u32(first_height)?, to_u32(last_height)?)
+        .await
+        .map_err(|err| Error::from(QueryError::General(err.to_string())))?;
+    let times: Vec<DateTimeUtc> = blocks
+        .block_metas
+        .iter()
+        .filter_map(|meta| DateTimeUtc::try_from(meta.header.time).ok())
+        .collect();
+    let overflow = || {
+        Error::from(QueryError::General(
+            "Overflow in the estimate of the epoch start".to_string(),
+        ))
+    };
+    let average_block_time = match (times.iter().min(), times.iter().max()) {
+        (Some(first), Some(last)) if times.len() > 1 => {
+            let intervals =
+                i32::try_from(times.len() - 1).map_err(|_| overflow())?;
+            (last.0 - first.0) / intervals
+        }
+        _ => Duration::zero(),
+    };
+
+    let mut blocks_remaining = match next_epoch_start.blocks_until_switch {
+        Some(blocks_until_switch) => u64::from(blocks_until_switch),
+        None => {
+            // The first block that reaches both the minimum height and time
+            // queues the switch, which then happens after a delay
+            let remaining = EpochDuration {
+                min_num_of_blocks: next_epoch_start
+                    .min_start_height
+                    .0
+                    .saturating_sub(last_height),
+                min_duration: DurationSecs(
+                    (next_epoch_start.min_start_time.0 - last_block.time.0)
+                        .num_seconds()
+                        .max(0) as u64,
+                ),
+            };
+            remaining
+                .estimate_num_of_blocks(average_block_time)
+                .max(1)
+                .checked_add(u64::from(EPOCH_SWITCH_BLOCKS_DELAY))
+                .ok_or_else(overflow)?
+        }
+    };
+    let following_epochs = epoch.0 - current_epoch.0 - 1;
+    if following_epochs > 0 {
+        let epoch_duration: EpochDuration = query_storage_value(
+            client,
+            &param_storage::get_epoch_duration_storage_key(),
+        )
+        .await?;
+        let epoch_blocks = epoch_duration
+            .estimate_num_of_blocks(average_block_time)
+            .checked_add(u64::from(EPOCH_SWITCH_BLOCKS_DELAY))
+            .ok_or_else(overflow)?;
+        blocks_remaining = following_epochs
+            .checked_mul(epoch_blocks)
+            .and_then(|blocks| blocks_remaining.checked_add(blocks))
+            .ok_or_else(overflow)?;
+    }
+    let start_height = last_height
+        .checked_add(blocks_remaining)
+        .ok_or_else(overflow)?;
+    let start_time = i32::try_from(blocks_remaining)
+        .ok()
+        .and_then(|blocks| average_block_time.checked_mul(blocks))
+        .and_then(|duration| last_block.time.0.checked_add_signed(duration))
+        .map(DateTimeUtc)
+        .ok_or_else(overflow)?;
+
+    Ok(EpochStartEstimate {
+        current_epoch,
+        last_block_height: last_block.height,
+        epoch,
+        blocks_remaining,
+        start_height: BlockHeight(start_height),
+        start_time,
+        average_block_time_ms: average_block_time.num_milliseconds(),
+    })
+}
+
 /// A helper to unwrap client's response. Will shut down process on error.
 fn unwrap_client_response<C: crate::queries::Client, T>(
     response: Result<T, C::Error>,
@@ -171,6 +333,38 @@ pub async fn get_token_balance<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query the non-zero balances of the owner for each of the given tokens.
+pub async fn get_token_balances<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+    tokens: &[Address],
+) -> Result<BTreeMap<Address, token::Amount>, error::Error> {
+    let data = Some(tokens.serialize_to_vec());
+    Ok(convert_response::<C, _>(
+        RPC.vp()
+            .token()
+            .balances(client, data, None, false, owner)
+            .await,
+    )?
+    .data)
+}
+
+/// Query the total supply of the given token.
+pub async fn get_token_total_supply<C: crate::queries::Client + Sync>(
+    client: &C,
+    token: &Address,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(RPC.vp().token().total_supply(client, token).await)
+}
+
+/// Query the display metadata (symbol and name) of the given token, if any.
+pub async fn get_token_metadata<C: crate::queries::Client + Sync>(
+    client: &C,
+    token: &Address,
+) -> Result<Option<token::Metadata>, error::Error> {
+    convert_response::<C, _>(RPC.vp().token().metadata(client, token).await)
+}
+
 /// Check if the given address is a known validator.
 pub async fn is_validator<C: crate::queries::Client + Sync>(
     client: &C,
@@ -283,6 +477,24 @@ pub async fn query_conversions<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().read_conversions(client).await)
 }
 
+/// Query whether a MASP nullifier has been revealed, i.e. whether its note is
+/// spent. With `prove`, the response includes a proof of the existence or
+/// non-existence of the nullifier key against the merkle root of the storage.
+pub async fn query_nullifier<C: crate::queries::Client + Sync>(
+    client: &C,
+    nullifier: &Hash,
+    height: Option<BlockHeight>,
+    prove: bool,
+) -> Result<(bool, Option<ProofOps>), error::Error> {
+    let data = None;
+    let response = convert_response::<C, _>(
+        RPC.shell()
+            .masp_nullifier(client, data, height, prove, nullifier)
+            .await,
+    )?;
+    Ok((response.data, response.proof))
+}
+
 /// Query a wasm code hash
 pub async fn query_wasm_code_hash(
     context: &impl Namada,
@@ -471,19 +683,49 @@ pub async fn query_tx_events<C: crate::queries::Client + Sync>(
     }
 }
 
+/// Dry run a transaction against the latest state without displaying the
+/// result
+pub async fn query_dry_run_tx<C: crate::queries::Client + Sync>(
+    client: &C,
+    tx_bytes: Vec<u8>,
+) -> Result<namada_core::types::transaction::TxResult, Error> {
+    let (data, height, prove) = (Some(tx_bytes), None, false);
+    Ok(convert_response::<C, _>(
+        RPC.shell().dry_run_tx(client, data, height, prove).await,
+    )?
+    .data)
+}
+
 /// Dry run a transaction
 pub async fn dry_run_tx<N: Namada>(
     context: &N,
     tx_bytes: Vec<u8>,
 ) -> Result<namada_core::types::transaction::TxResult, Error> {
-    let (data, height, prove) = (Some(tx_bytes), None, false);
-    let result = convert_response::<N::Client, _>(
-        RPC.shell()
-            .dry_run_tx(context.client(), data, height, prove)
-            .await,
-    )?
-    .data;
+    let result = query_dry_run_tx(context.client(), tx_bytes).await?;
     display_line!(context.io(), "Dry-run result: {}", result);
+    context
+        .io()
+        .print_json(&serde_json::json!({
+                "accepted": result.is_accepted(),
+            "gas_used": result.gas_used.to_string(),
+            "changed_keys": result
+                .changed_keys
+                .iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>(),
+            "accepted_vps": result.vps_result.accepted_vps,
+            "rejected_vps": result.vps_result.rejected_vps,
+            "vps_gas": result
+                .vps_result
+                .vps_gas
+                .iter()
+                .map(|(addr, gas)| (addr.to_string(), gas.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+            "errors": result.vps_result.errors,
+        }))
+        .map_err(|err| {
+            Error::Other(format!("Failed to print the JSON output: {err}"))
+        })?;
     Ok(result)
 }
 
@@ -509,7 +751,7 @@ pub enum TxBroadcastData {
 }
 
 /// A parsed event from tendermint relating to a transaction
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TxResponse {
     /// Response information
     pub info: String,
@@ -519,8 +761,15 @@ pub struct TxResponse {
     pub height: String,
     /// Transaction height
     pub hash: String,
+    /// Hash of the inner transaction, if any
+    pub inner_hash: Option<String>,
     /// Response code
     pub code: String,
+    /// Reason for the rejection of the transaction, if any
+    pub reason: Option<String>,
+    /// Stable code of the result of the transaction, if any. Unlike the
+    /// `code`, it distinguishes the causes of a failure.
+    pub result_code: Option<ResultCode>,
     /// Gas used
     pub gas_used: String,
     /// Initialized accounts
@@ -559,6 +808,11 @@ impl TryFrom<Event> for TxResponse {
             .get("gas_used")
             .ok_or_else(|| missing_field_err("gas_used"))?
             .clone();
+        let inner_hash = event.get("inner_hash").cloned();
+        let reason = event.get("reason").cloned();
+        let result_code = event
+            .get(ResultCode::EVENT_ATTRIBUTE)
+            .and_then(|value| ResultCode::from_event_value(value));
         let initialized_accounts = event
             .get("initialized_accounts")
             .map(String::as_str)
@@ -571,10 +825,13 @@ impl TryFrom<Event> for TxResponse {
 
         Ok(TxResponse {
             hash,
+            inner_hash,
             info,
             log,
             height,
             code,
+            reason,
+            result_code,
             gas_used,
             initialized_accounts,
         })
@@ -651,7 +908,12 @@ pub async fn query_tx_response<C: crate::queries::Client + Sync>(
         log: event_map["log"].to_string(),
         height: event_map["height"].to_string(),
         hash: event_map["hash"].to_string(),
+        inner_hash: event_map.get("inner_hash").map(ToString::to_string),
         code: event_map["code"].to_string(),
+        reason: event_map.get("reason").map(ToString::to_string),
+        result_code: event_map
+            .get(ResultCode::EVENT_ATTRIBUTE)
+            .and_then(|value| ResultCode::from_event_value(value)),
         gas_used: event_map["gas_used"].to_string(),
         initialized_accounts: serde_json::from_str(
             event_map["initialized_accounts"],
@@ -730,6 +992,17 @@ pub async fn get_delegators_delegation<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get the validators of the delegator's bonds whose rewards are
+/// automatically re-bonded
+pub async fn get_auto_compound_validators<C: crate::queries::Client + Sync>(
+    client: &C,
+    address: &Address,
+) -> Result<BTreeSet<Address>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().auto_compound(client, address).await,
+    )
+}
+
 /// Get the delegator's delegation at some epoh
 pub async fn get_delegators_delegation_at<C: crate::queries::Client + Sync>(
     client: &C,
@@ -846,6 +1119,116 @@ pub async fn is_public_key_revealed<C: crate::queries::Client + Sync>(
     convert_response::<C, bool>(RPC.shell().revealed(client, owner).await)
 }
 
+/// Query the next sequence number expected in a wrapper tx paid by the account
+pub async fn query_sequence<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+) -> Result<u64, error::Error> {
+    let (bytes, _proof) =
+        query_storage_value_bytes(client, &sequence_key(owner), None, false)
+            .await?;
+    match bytes {
+        Some(bytes) => u64::try_from_slice(&bytes).map_err(|err| {
+            Error::from(EncodingError::Decoding(err.to_string()))
+        }),
+        None => Ok(0),
+    }
+}
+
+/// Query the timelock on the VP updates of an account, if any
+pub async fn query_vp_timelock<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+) -> Result<Option<VpUpdateTimelock>, error::Error> {
+    let (bytes, _proof) =
+        query_storage_value_bytes(client, &vp_timelock_key(owner), None, false)
+            .await?;
+    bytes
+        .map(|bytes| {
+            VpUpdateTimelock::try_from_slice(&bytes).map_err(|err| {
+                Error::from(EncodingError::Decoding(err.to_string()))
+            })
+        })
+        .transpose()
+}
+
+/// Query the current PoW challenge of a withdrawal from the testnet faucet to
+/// the target, if the faucet requires one
+pub async fn query_faucet_challenge<C: crate::queries::Client + Sync>(
+    client: &C,
+    target: &Address,
+) -> Result<Option<testnet_pow::Challenge>, error::Error> {
+    let (bytes, _proof) = query_storage_value_bytes(
+        client,
+        &testnet_pow::difficulty_key(),
+        None,
+        false,
+    )
+    .await?;
+    let difficulty = match bytes {
+        Some(bytes) => testnet_pow::Difficulty::try_from_slice(&bytes)
+            .map_err(|err| {
+                Error::from(EncodingError::Decoding(err.to_string()))
+            })?,
+        None => return Ok(None),
+    };
+    let (bytes, _proof) = query_storage_value_bytes(
+        client,
+        &testnet_pow::counter_key(target),
+        None,
+        false,
+    )
+    .await?;
+    let counter = match bytes {
+        Some(bytes) => u64::try_from_slice(&bytes).map_err(|err| {
+            Error::from(EncodingError::Decoding(err.to_string()))
+        })?,
+        None => 0,
+    };
+    Ok(Some(testnet_pow::Challenge {
+        target: target.clone(),
+        difficulty,
+        counter,
+    }))
+}
+
+/// Query the current value of an oracle feed, if any
+pub async fn query_oracle_value<C: crate::queries::Client + Sync>(
+    client: &C,
+    feed_id: &str,
+) -> Result<Option<oracle::Observation>, error::Error> {
+    let (bytes, _proof) = query_storage_value_bytes(
+        client,
+        &oracle::value_key(feed_id),
+        None,
+        false,
+    )
+    .await?;
+    bytes
+        .map(|bytes| {
+            oracle::Observation::try_from_slice(&bytes).map_err(|err| {
+                Error::from(EncodingError::Decoding(err.to_string()))
+            })
+        })
+        .transpose()
+}
+
+/// Query the upgrade of the chain scheduled by governance, if any
+pub async fn query_upgrade_plan<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Option<upgrade::UpgradePlan>, error::Error> {
+    let (bytes, _proof) =
+        query_storage_value_bytes(client, &upgrade::plan_key(), None, false)
+            .await?;
+    bytes
+        .map(|bytes| {
+            upgrade::UpgradePlan::try_from_slice(&bytes).map_err(|err| {
+                Error::from(EncodingError::Decoding(err.to_string()))
+            })
+        })
+        .transpose()
+}
+
 /// Query an account substorage at a specific index
 pub async fn get_public_key_at<C: crate::queries::Client + Sync>(
     client: &C,
@@ -949,6 +1332,22 @@ pub async fn query_proposal_votes<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get a page of at most `limit` votes of a proposal, starting from the vote
+/// at `offset`
+pub async fn query_proposal_votes_page<C: crate::queries::Client + Sync>(
+    client: &C,
+    proposal_id: u64,
+    offset: u64,
+    limit: u64,
+) -> Result<Vec<Vote>, error::Error> {
+    convert_response::<C, Vec<Vote>>(
+        RPC.vp()
+            .gov()
+            .proposal_id_votes_page(client, &proposal_id, &offset, &limit)
+            .await,
+    )
+}
+
 /// Get the bond amount at the given epoch
 pub async fn get_bond_amount_at<C: crate::queries::Client + Sync>(
     client: &C,
@@ -11,7 +11,7 @@ use masp_primitives::transaction::components::sapling::fees::{
     InputView, OutputView,
 };
 use namada_core::ledger::parameters::storage as parameter_storage;
-use namada_core::proto::SignatureIndex;
+use namada_core::proto::{PartiallySignedTx, SignatureIndex};
 use namada_core::types::account::AccountPublicKeysMap;
 use namada_core::types::address::{
     masp_tx_key, Address, ImplicitAddress, InternalAddress, MASP,
@@ -228,8 +228,19 @@ where
         let signatures = args
             .signatures
             .iter()
-            .map(|bytes| {
-                let sigidx = SignatureIndex::deserialize(bytes).unwrap();
+            .flat_map(|bytes| {
+                // A file is either a single signature or a partially signed
+                // tx with the signatures of several keys
+                match SignatureIndex::deserialize(bytes) {
+                    Ok(sigidx) => vec![sigidx],
+                    Err(_) => {
+                        PartiallySignedTx::deserialize(bytes)
+                            .unwrap()
+                            .signatures
+                    }
+                }
+            })
+            .map(|sigidx| {
                 used_pubkeys.insert(sigidx.pubkey.clone());
                 sigidx
             })
@@ -675,6 +686,9 @@ pub async fn wrap_tx<N: Namada>(
         args.gas_limit,
         unshield_section_hash,
     );
+    if let Some(sequence) = args.sequence {
+        tx.set_wrapper_sequence(sequence);
+    }
 
     Ok(unshielding_epoch)
 }
@@ -1007,6 +1021,35 @@ impl<'a> Display for LedgerProposalType<'a> {
 pub async fn to_ledger_vector(
     context: &impl Namada,
     tx: &Tx,
+) -> Result<LedgerVector, Error> {
+    let mut tv = decode_tx(context, tx).await?;
+    // Finally, index each line and break those that are too long
+    format_outputs(&mut tv.output);
+    format_outputs(&mut tv.output_expert);
+    Ok(tv)
+}
+
+/// Describes the given transaction in human-readable lines, for the user to
+/// review it before signing
+pub async fn to_tx_summary(
+    context: &impl Namada,
+    tx: &Tx,
+) -> Result<Vec<String>, Error> {
+    let tv = decode_tx(context, tx).await?;
+    // The first line is the type of the transaction, the expert lines contain
+    // all of its details
+    let mut summary: Vec<String> = tv.output.into_iter().take(1).collect();
+    summary.extend(tv.output_expert);
+    if let Some(expiration) = tx.header.expiration {
+        summary.push(format!("Expiration : {}", expiration));
+    }
+    Ok(summary)
+}
+
+/// Decodes the given transaction into the unformatted lines that describe it
+async fn decode_tx(
+    context: &impl Namada,
+    tx: &Tx,
 ) -> Result<LedgerVector, Error> {
     // To facilitate lookups of human-readable token names
     let tokens: HashMap<Address, String> = context
@@ -1313,6 +1356,63 @@ pub async fn to_ledger_vector(
             }
             None => (),
         };
+        if let Some(timelock) = &update_account.vp_timelock {
+            let timelock = vec![
+                format!("VP update delay : {}", timelock.delay),
+                format!("Recovery key : {}", timelock.recovery_key),
+            ];
+            tv.output.extend(timelock.clone());
+            tv.output_expert.extend(timelock);
+        }
+        if update_account.cancel_vp_update {
+            tv.output.push("Cancel VP update : true".to_string());
+            tv.output_expert.push("Cancel VP update : true".to_string());
+        }
+        if let Some(allowed_txs) = &update_account.allowed_txs {
+            let allowed_txs = if allowed_txs.is_empty() {
+                vec!["Allowed txs : any".to_string()]
+            } else {
+                allowed_txs
+                    .iter()
+                    .map(|hash| format!("Allowed tx : {}", hash))
+                    .collect()
+            };
+            tv.output.extend(allowed_txs.clone());
+            tv.output_expert.extend(allowed_txs);
+        }
+        if let Some(policy) = &update_account.policy {
+            let mut policy_lines: Vec<String> = policy
+                .spend_limits
+                .iter()
+                .map(|(token, limit)| {
+                    format!(
+                        "Spend limit : {} {}",
+                        limit.to_string_native(),
+                        token
+                    )
+                })
+                .collect();
+            policy_lines.extend(policy.allowed_counterparties.iter().map(
+                |counterparty| {
+                    format!("Allowed counterparty : {}", counterparty)
+                },
+            ));
+            policy_lines.extend(policy.high_value_thresholds.iter().map(
+                |(token, high_value)| {
+                    format!(
+                        "High value threshold : {} {} : {}",
+                        high_value.amount.to_string_native(),
+                        token,
+                        high_value.threshold
+                    )
+                },
+            ));
+            if policy_lines.is_empty() {
+                policy_lines.push("Policy : none".to_string());
+            }
+            tv.output.extend(policy_lines.clone());
+            tv.output_expert.extend(policy_lines);
+        }
     } else if code_sec.tag == Some(TX_TRANSFER_WASM.to_string()) {
         let transfer = Transfer::try_from_slice(
             &tx.data()
@@ -1719,8 +1819,5 @@ pub async fn to_ledger_vector(
         }
     }
 
-    // Finally, index each line and break those that are too long
-    format_outputs(&mut tv.output);
-    format_outputs(&mut tv.output_expert);
     Ok(tv)
 }
@@ -5,7 +5,7 @@ use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::transaction::builder;
 use masp_primitives::transaction::builder::Builder;
@@ -30,8 +30,11 @@ use namada_core::ledger::governance::cli::onchain::{
 use namada_core::ledger::governance::storage::proposal::ProposalType;
 use namada_core::ledger::governance::storage::vote::StorageProposalVote;
 use namada_core::ledger::ibc::storage::channel_key;
+use namada_core::ledger::parameters::storage as param_storage;
 use namada_core::ledger::pgf::cli::steward::Commission;
-use namada_core::types::address::{Address, InternalAddress, MASP};
+use namada_core::ledger::testnet_pow;
+use namada_core::types::account::{AccountPublicKeysMap, VpUpdateTimelock};
+use namada_core::types::address::{self, Address, InternalAddress, MASP};
 use namada_core::types::dec::Dec;
 use namada_core::types::hash::Hash;
 use namada_core::types::ibc::IbcShieldedTransfer;
@@ -56,15 +59,16 @@ use crate::error::{EncodingError, Error, QueryError, Result, TxError};
 use crate::io::Io;
 use crate::masp::TransferErr::Build;
 use crate::masp::{make_asset_type, ShieldedContext, ShieldedTransfer};
-use crate::proto::{MaspBuilder, Tx};
+use crate::proto::{MaspBuilder, SignDoc, Tx};
 use crate::queries::Client;
 use crate::rpc::{
     self, query_wasm_code_hash, validate_amount, TxBroadcastData, TxResponse,
 };
 use crate::signing::{self, SigningTxData, TxSourcePostBalance};
-use crate::tendermint_rpc::endpoint::broadcast::tx_sync::Response;
+use crate::tendermint_rpc::endpoint::broadcast::tx_sync::{self, Response};
 use crate::tendermint_rpc::error::Error as RpcError;
-use crate::wallet::WalletIo;
+use crate::tendermint_rpc::Request as _;
+use crate::wallet::{TxAmount, TxRecord, TxStatus, WalletIo};
 use crate::{display_line, edisplay_line, Namada};
 
 /// Initialize account transaction WASM
@@ -87,10 +91,20 @@ pub const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 pub const TX_UPDATE_ACCOUNT_WASM: &str = "tx_update_account.wasm";
 /// Transfer transaction WASM path
 pub const TX_TRANSFER_WASM: &str = "tx_transfer.wasm";
+/// Testnet faucet withdrawal transaction WASM path
+pub const TX_FAUCET_WITHDRAW_WASM: &str = "tx_faucet_withdraw.wasm";
+/// Oracle feed value submission transaction WASM path
+pub const TX_ORACLE_SUBMIT_WASM: &str = "tx_oracle_submit.wasm";
+/// Token allowance approval transaction WASM path
+pub const TX_APPROVE_WASM: &str = "tx_approve.wasm";
+/// Transfer from an allowance transaction WASM path
+pub const TX_TRANSFER_FROM_WASM: &str = "tx_transfer_from.wasm";
 /// IBC transaction WASM path
 pub const TX_IBC_WASM: &str = "tx_ibc.wasm";
 /// User validity predicate WASM path
 pub const VP_USER_WASM: &str = "vp_user.wasm";
+/// Vesting account validity predicate WASM path
+pub const VP_VESTING_WASM: &str = "vp_vesting.wasm";
 /// Bond WASM path
 pub const TX_BOND_WASM: &str = "tx_bond.wasm";
 /// Unbond WASM path
@@ -99,6 +113,8 @@ pub const TX_UNBOND_WASM: &str = "tx_unbond.wasm";
 pub const TX_WITHDRAW_WASM: &str = "tx_withdraw.wasm";
 /// Claim-rewards WASM path
 pub const TX_CLAIM_REWARDS_WASM: &str = "tx_claim_rewards.wasm";
+/// Auto-compound WASM path
+pub const TX_AUTO_COMPOUND_WASM: &str = "tx_auto_compound.wasm";
 /// Bridge pool WASM path
 pub const TX_BRIDGE_POOL_WASM: &str = "tx_bridge_pool.wasm";
 /// Change commission WASM path
@@ -159,6 +175,18 @@ pub fn dump_tx<IO: Io>(io: &IO, args: &args::Tx, tx: Tx) {
                 "Transaction serialized to {}.",
                 tx_path.to_string_lossy()
             );
+            // The canonical document to display to offline signers
+            let sign_doc_path = path.join(format!("{}.signdoc.json", tx_id));
+            std::fs::write(
+                &sign_doc_path,
+                SignDoc::new(&tx).to_canonical_bytes(),
+            )
+            .expect("Should be able to write to file.");
+            display_line!(
+                io,
+                "Signing document written to {}.",
+                sign_doc_path.to_string_lossy()
+            );
         }
         None => {
             display_line!(io, "Below the serialized transaction: \n");
@@ -167,6 +195,30 @@ pub fn dump_tx<IO: Io>(io: &IO, args: &args::Tx, tx: Tx) {
     }
 }
 
+/// Dump a signed transaction as the body of a CometBFT `broadcast_tx_sync`
+/// JSON-RPC request, either to file or to screen, so that it can be broadcast
+/// to any node without the client
+pub fn dump_broadcast_tx<IO: Io>(io: &IO, args: &args::Tx, tx: &Tx) {
+    let request_body = tx_sync::Request::new(tx.to_bytes()).into_json();
+    match args.output_folder.to_owned() {
+        Some(path) => {
+            let request_path =
+                path.join(format!("{}.broadcast.json", tx.header_hash()));
+            std::fs::write(&request_path, request_body)
+                .expect("Should be able to write to file.");
+            display_line!(
+                io,
+                "Broadcast request written to {}.",
+                request_path.to_string_lossy()
+            );
+        }
+        None => {
+            display_line!(io, "Below the broadcast request: \n");
+            display_line!(io, "{}", request_body)
+        }
+    }
+}
+
 /// Prepare a transaction for signing and submission by adding a wrapper header
 /// to it.
 #[allow(clippy::too_many_arguments)]
@@ -204,6 +256,13 @@ pub async fn process_tx(
     // let request_body = request.into_json();
     // println!("HTTP request body: {}", request_body);
 
+    warn_on_tx_limits(context, &tx).await;
+
+    if args.dump_broadcast_tx {
+        dump_broadcast_tx(context.io(), args, &tx);
+        return Ok(ProcessTxResponse::Dump);
+    }
+
     if args.dry_run || args.dry_run_wrapper {
         expect_dry_broadcast(TxBroadcastData::DryRun(tx), context).await
     } else {
@@ -212,6 +271,8 @@ pub async fn process_tx(
         // We use this to determine when the decrypted inner tx makes it
         // on-chain
         let decrypted_hash = tx.raw_header_hash().to_string();
+        let mut record =
+            tx_record(context, &tx, &wrapper_hash, &decrypted_hash).await;
         let to_broadcast = TxBroadcastData::Live {
             tx,
             wrapper_hash,
@@ -221,12 +282,48 @@ pub async fn process_tx(
         // of masp epoch Either broadcast or submit transaction and
         // collect result into sum type
         if args.broadcast_only {
-            broadcast_tx(context, &to_broadcast)
-                .await
-                .map(ProcessTxResponse::Broadcast)
+            let response = broadcast_tx(context, &to_broadcast).await?;
+            record_tx(context, record).await;
+            if let TxBroadcastData::Live {
+                wrapper_hash,
+                decrypted_hash,
+                ..
+            } = &to_broadcast
+            {
+                context
+                    .io()
+                    .print_json(&serde_json::json!({
+                        "hash": wrapper_hash,
+                        "inner_hash": decrypted_hash,
+                    }))
+                    .map_err(|err| {
+                        Error::Other(format!(
+                            "Failed to print the JSON output: {err}"
+                        ))
+                    })?;
+            }
+            Ok(ProcessTxResponse::Broadcast(response))
         } else {
-            match submit_tx(context, to_broadcast).await {
+            let result = submit_tx(context, to_broadcast).await;
+            // A tx that timed out stays pending, as it may still be applied,
+            // while a tx that failed to be broadcast is not recorded
+            if let Ok(response) = &result {
+                record.status = if response.code == 0.to_string() {
+                    TxStatus::Applied
+                } else {
+                    TxStatus::Rejected
+                };
+            }
+            if !matches!(result, Err(Error::Tx(TxError::TxBroadcast(_)))) {
+                record_tx(context, record).await;
+            }
+            match result {
                 Ok(x) => {
+                    context.io().print_json(&x).map_err(|err| {
+                        Error::Other(format!(
+                            "Failed to print the JSON output: {err}"
+                        ))
+                    })?;
                     save_initialized_accounts(
                         context,
                         args,
@@ -241,6 +338,115 @@ pub async fn process_tx(
     }
 }
 
+/// A pending record of a submitted tx for the local tx history. The parties
+/// and amounts are decoded from the data of the transfers and bonds.
+async fn tx_record(
+    context: &impl Namada,
+    tx: &Tx,
+    wrapper_hash: &str,
+    decrypted_hash: &str,
+) -> TxRecord {
+    let tx_type = tx
+        .get_section(tx.code_sechash())
+        .and_then(|section| section.code_sec())
+        .and_then(|code| code.tag)
+        .unwrap_or_else(|| "custom".to_string());
+    let mut parties = vec![];
+    if let Some(wrapper) = tx.header().wrapper() {
+        parties.push(wrapper.fee_payer());
+    }
+    let mut amounts = vec![];
+    let data = tx.data().unwrap_or_default();
+    match tx_type.as_str() {
+        TX_TRANSFER_WASM => {
+            if let Ok(transfer) = token::Transfer::try_from_slice(&data) {
+                parties.extend([transfer.source, transfer.target]);
+                amounts.push(TxAmount {
+                    token: transfer.token,
+                    amount: transfer.amount.to_string(),
+                });
+            }
+        }
+        TX_BOND_WASM | TX_UNBOND_WASM => {
+            if let Ok(bond) = pos::Bond::try_from_slice(&data) {
+                parties.extend(bond.source);
+                parties.push(bond.validator);
+                if let Ok(native_token) =
+                    rpc::query_native_token(context.client()).await
+                {
+                    amounts.push(TxAmount {
+                        token: native_token,
+                        amount: bond.amount.to_string_native(),
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+    let mut seen = HashSet::new();
+    parties.retain(|party| seen.insert(party.clone()));
+    TxRecord {
+        hash: decrypted_hash.to_string(),
+        wrapper_hash: wrapper_hash.to_string(),
+        tx_type,
+        parties,
+        amounts,
+        status: TxStatus::Pending,
+        submitted_at: DateTimeUtc::now(),
+    }
+}
+
+/// Record a submitted tx in the local tx history of the wallet. A failure to
+/// record it doesn't fail the submission.
+async fn record_tx(context: &impl Namada, record: TxRecord) {
+    if let Err(err) = context.wallet().await.record_tx(record) {
+        edisplay_line!(
+            context.io(),
+            "WARNING: failed to record the transaction in the history: {err}"
+        );
+    }
+}
+
+/// Warn if the tx exceeds the max tx size or if its gas limit exceeds the max
+/// block gas of the chain, as the ledger would reject it
+async fn warn_on_tx_limits(context: &impl Namada, tx: &Tx) {
+    let max_tx_bytes: Option<u32> = rpc::query_storage_value(
+        context.client(),
+        &param_storage::get_max_tx_bytes_key(),
+    )
+    .await
+    .ok();
+    let tx_bytes = tx.to_bytes().len();
+    if let Some(max_tx_bytes) =
+        max_tx_bytes.filter(|max| tx_bytes > *max as usize)
+    {
+        edisplay_line!(
+            context.io(),
+            "WARNING: the transaction is {tx_bytes} bytes long, which exceeds \
+             the maximum transaction size of {max_tx_bytes} bytes. It will be \
+             rejected by the ledger."
+        );
+    }
+    let gas_limit = match tx.header().wrapper() {
+        Some(wrapper) => u64::from(wrapper.gas_limit),
+        None => return,
+    };
+    let max_block_gas: Option<u64> = rpc::query_storage_value(
+        context.client(),
+        &param_storage::get_max_block_gas_key(),
+    )
+    .await
+    .ok();
+    if let Some(max_block_gas) = max_block_gas.filter(|max| gas_limit > *max) {
+        edisplay_line!(
+            context.io(),
+            "WARNING: the gas limit of the transaction {gas_limit} exceeds the \
+             maximum block gas of {max_block_gas}. It will be rejected by the \
+             ledger."
+        );
+    }
+}
+
 /// Check if a reveal public key transaction is needed
 pub async fn is_reveal_pk_needed<C: crate::queries::Client + Sync>(
     client: &C,
@@ -587,7 +793,8 @@ pub async fn build_validator_commission_change(
     } else {
         edisplay_line!(
             context.io(),
-            "The given address {validator} is not a validator."
+            "The given address {} is not a validator.",
+            context.wallet().await.format_address(&validator)
         );
         if !tx_args.force {
             return Err(Error::from(TxError::InvalidValidatorAddress(
@@ -766,7 +973,7 @@ pub async fn build_update_steward_commission(
         edisplay_line!(
             context.io(),
             "The given address {} is not a steward.",
-            &steward
+            context.wallet().await.format_address(&steward)
         );
         return Err(Error::from(TxError::InvalidSteward(steward.clone())));
     };
@@ -824,7 +1031,7 @@ pub async fn build_resign_steward(
         edisplay_line!(
             context.io(),
             "The given address {} is not a steward.",
-            &steward
+            context.wallet().await.format_address(&steward)
         );
         return Err(Error::from(TxError::InvalidSteward(steward.clone())));
     };
@@ -864,7 +1071,7 @@ pub async fn build_unjail_validator(
         edisplay_line!(
             context.io(),
             "The given address {} is not a validator.",
-            &validator
+            context.wallet().await.format_address(&validator)
         );
         if !tx_args.force {
             return Err(Error::from(TxError::InvalidValidatorAddress(
@@ -888,7 +1095,7 @@ pub async fn build_unjail_validator(
             context.io(),
             "The given validator address {} is not jailed at the pipeline \
              epoch when it would be restored to one of the validator sets.",
-            &validator
+            context.wallet().await.format_address(&validator)
         );
         if !tx_args.force {
             return Err(Error::from(TxError::ValidatorNotCurrentlyJailed(
@@ -909,7 +1116,7 @@ pub async fn build_unjail_validator(
                     context.io(),
                     "The given validator address {} is currently frozen and \
                      will be eligible to be unjailed starting at epoch {}.",
-                    &validator,
+                    context.wallet().await.format_address(&validator),
                     eligible_epoch
                 );
                 if !tx_args.force {
@@ -967,7 +1174,7 @@ pub async fn build_deactivate_validator(
         edisplay_line!(
             context.io(),
             "The given address {} is not a validator.",
-            &validator
+            context.wallet().await.format_address(&validator)
         );
         if !tx_args.force {
             return Err(Error::from(TxError::InvalidValidatorAddress(
@@ -991,7 +1198,7 @@ pub async fn build_deactivate_validator(
             context.io(),
             "The given validator address {} is already inactive at the \
              pipeline epoch {}.",
-            &validator,
+            context.wallet().await.format_address(&validator),
             &pipeline_epoch
         );
         if !tx_args.force {
@@ -1038,7 +1245,7 @@ pub async fn build_reactivate_validator(
         edisplay_line!(
             context.io(),
             "The given address {} is not a validator.",
-            &validator
+            context.wallet().await.format_address(&validator)
         );
         if !tx_args.force {
             return Err(Error::from(TxError::InvalidValidatorAddress(
@@ -1060,7 +1267,7 @@ pub async fn build_reactivate_validator(
             edisplay_line!(
                 context.io(),
                 "The given validator address {} is not inactive at epoch {}.",
-                &validator,
+                context.wallet().await.format_address(&validator),
                 &epoch
             );
             if !tx_args.force {
@@ -1125,7 +1332,7 @@ pub async fn build_redelegation(
             context.io(),
             "The given address {} is a validator. A validator is prohibited \
              from redelegating its own bonds.",
-            &owner
+            context.wallet().await.format_address(&owner)
         );
         if !tx_args.force {
             return Err(Error::from(TxError::RedelegatorIsValidator(
@@ -1163,14 +1370,16 @@ pub async fn build_redelegation(
         true
     };
     if !is_not_chained {
+        let wallet = context.wallet().await;
         edisplay_line!(
             context.io(),
             "The source validator {} has an incoming redelegation from the \
              delegator {} that may still be subject to future slashing. \
              Redelegation is not allowed until this is no longer the case.",
-            &src_validator,
-            &owner
+            wallet.format_address(&src_validator),
+            wallet.format_address(&owner)
         );
+        drop(wallet);
         if !tx_args.force {
             return Err(Error::from(TxError::IncomingRedelIsStillSlashable(
                 src_validator.clone(),
@@ -1196,7 +1405,7 @@ pub async fn build_redelegation(
             "WARNING: the given destination validator address {} is inactive \
              at the pipeline epoch {}. If you would still like to bond to the \
              inactive validator, use the --force option.",
-            &dest_validator,
+            context.wallet().await.format_address(&dest_validator),
             &pipeline_epoch
         );
         return Err(Error::from(TxError::ValidatorInactive(
@@ -1396,6 +1605,81 @@ pub async fn build_claim_rewards(
     .map(|(tx, epoch)| (tx, signing_data, epoch))
 }
 
+/// Build a tx to opt a bond in or out of the automatic re-bonding of its
+/// rewards
+pub async fn build_auto_compound(
+    context: &impl Namada,
+    args::AutoCompound {
+        tx: tx_args,
+        validator,
+        source,
+        enabled,
+        tx_code_path,
+    }: &args::AutoCompound,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_address = source.clone().unwrap_or(validator.clone());
+    let default_signer = Some(default_address.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(default_address),
+        default_signer,
+    )
+    .await?;
+
+    // Check that the validator address is actually a validator
+    let validator =
+        known_validator_or_err(validator.clone(), tx_args.force, context)
+            .await?;
+
+    // Check that the source address exists on chain
+    let source = match source.clone() {
+        Some(source) => source_exists_or_err(source, tx_args.force, context)
+            .await
+            .map(Some),
+        None => Ok(source.clone()),
+    }?;
+
+    // Check that there is a bond to opt in
+    if *enabled {
+        let bond_source = source.as_ref().unwrap_or(&validator);
+        let bond_amount =
+            rpc::query_bond(context.client(), bond_source, &validator, None)
+                .await?;
+        if bond_amount.is_zero() {
+            let wallet = context.wallet().await;
+            edisplay_line!(
+                context.io(),
+                "No bond of {} to the validator {} could be found.",
+                wallet.format_address(bond_source),
+                wallet.format_address(&validator),
+            );
+            drop(wallet);
+            if !tx_args.force {
+                return Err(Error::from(TxError::NoBondFound));
+            }
+        }
+    }
+
+    let data = pos::AutoCompound {
+        validator,
+        source,
+        enabled: *enabled,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
 /// Submit a transaction to unbond
 pub async fn build_unbond(
     context: &impl Namada,
@@ -1464,7 +1748,7 @@ pub async fn build_unbond(
             context.io(),
             "The total bonds of the source {} is lower than the amount to be \
              unbonded. Amount to unbond is {} and the total bonds is {}.",
-            bond_source,
+            context.wallet().await.format_address(&bond_source),
             amount.to_string_native(),
             bond_amount.to_string_native(),
         );
@@ -1629,7 +1913,7 @@ pub async fn build_bond(
             "WARNING: the given validator address {} is inactive at the \
              pipeline epoch {}. If you would still like to bond to the \
              inactive validator, use the --force option.",
-            &validator,
+            context.wallet().await.format_address(&validator),
             &pipeline_epoch
         );
         return Err(Error::from(TxError::ValidatorInactive(
@@ -1790,7 +2074,11 @@ pub async fn build_vote_proposal(
 
     if !proposal.can_be_voted(epoch, is_validator) {
         if tx.force {
-            eprintln!("Invalid proposal {} vote period.", proposal_id);
+            edisplay_line!(
+                context.io(),
+                "Invalid proposal {} vote period.",
+                proposal_id
+            );
         } else {
             return Err(Error::from(TxError::InvalidProposalVotingPeriod(
                 proposal_id,
@@ -2171,6 +2459,147 @@ async fn used_asset_types<P, R, K, N>(
     Ok(asset_types)
 }
 
+/// Submit a withdrawal from the testnet faucet, solving its PoW challenge if
+/// it requires one
+pub async fn build_faucet_request(
+    context: &impl Namada,
+    args::FaucetRequest {
+        tx: tx_args,
+        target,
+        token,
+        amount,
+        tx_code_path,
+    }: &args::FaucetRequest,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    // The faucet doesn't sign the withdrawal, the target pays the fees
+    let signing_data =
+        signing::aux_signing_data(context, tx_args, None, Some(target.clone()))
+            .await?;
+
+    let amount =
+        validate_amount(context, *amount, token, tx_args.force).await?;
+
+    let solution = rpc::query_faucet_challenge(context.client(), target)
+        .await?
+        .map(|challenge| {
+            display_line!(
+                context.io(),
+                "Solving the PoW challenge of the faucet with difficulty {}...",
+                challenge.difficulty
+            );
+            challenge.solve()
+        });
+    let data = testnet_pow::FaucetWithdrawal {
+        target: target.clone(),
+        token: token.clone(),
+        amount,
+        solution,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
+/// Submit an approval of a token allowance, signed by the owner
+pub async fn build_approve(
+    context: &impl Namada,
+    args::TxApprove {
+        tx: tx_args,
+        owner,
+        spender,
+        token,
+        amount,
+        tx_code_path,
+    }: &args::TxApprove,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_signer = Some(owner.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(owner.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let amount =
+        validate_amount(context, *amount, token, tx_args.force).await?;
+
+    let data = token::Approval {
+        owner: owner.clone(),
+        spender: spender.clone(),
+        token: token.clone(),
+        amount,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
+/// Submit a transfer out of the allowance given to the spender by the source,
+/// signed by the spender
+pub async fn build_transfer_from(
+    context: &impl Namada,
+    args::TxTransferFrom {
+        tx: tx_args,
+        spender,
+        source,
+        target,
+        token,
+        amount,
+        tx_code_path,
+    }: &args::TxTransferFrom,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_signer = Some(spender.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(spender.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let amount =
+        validate_amount(context, *amount, token, tx_args.force).await?;
+
+    let data = token::TransferFrom {
+        spender: spender.clone(),
+        source: source.clone(),
+        target: target.clone(),
+        token: token.clone(),
+        amount,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
 /// Submit an ordinary transfer
 pub async fn build_transfer<N: Namada>(
     context: &N,
@@ -2351,8 +2780,16 @@ pub async fn build_init_account(
         tx_code_path,
         public_keys,
         threshold,
+        salt,
+        vesting,
     }: &args::TxInitAccount,
 ) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    if matches!(vesting, Some(vesting) if !vesting.is_valid()) {
+        return Err(Error::Other(
+            "The epochs of a vesting schedule must be in order".to_string(),
+        ));
+    }
+
     let signing_data =
         signing::aux_signing_data(context, tx_args, None, None).await?;
 
@@ -2374,6 +2811,8 @@ pub async fn build_init_account(
         // We will add the hash inside the add_code_hash function
         vp_code_hash: Hash::zero(),
         threshold,
+        salt: salt.clone(),
+        vesting: *vesting,
     };
 
     let add_code_hash = |tx: &mut Tx, data: &mut InitAccount| {
@@ -2397,6 +2836,14 @@ pub async fn build_init_account(
     .map(|(tx, epoch)| (tx, signing_data, epoch))
 }
 
+/// Get the address of the account that an init account transaction will
+/// initialize. The address is derived from the transaction's data, so it is
+/// known before the transaction is submitted.
+pub fn init_account_address(tx: &Tx) -> Option<Address> {
+    tx.data()
+        .map(|data| address::derive_established_address(data, 0))
+}
+
 /// Submit a transaction to update a VP
 pub async fn build_update_account(
     context: &impl Namada,
@@ -2407,10 +2854,15 @@ pub async fn build_update_account(
         addr,
         public_keys,
         threshold,
+        vp_update_delay,
+        recovery_key,
+        cancel_vp_update,
+        allowed_txs,
+        policy,
     }: &args::TxUpdateAccount,
 ) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
     let default_signer = Some(addr.clone());
-    let signing_data = signing::aux_signing_data(
+    let mut signing_data = signing::aux_signing_data(
         context,
         tx_args,
         Some(addr.clone()),
@@ -2421,6 +2873,14 @@ pub async fn build_update_account(
     let addr = if let Some(account) =
         rpc::get_account_info(context.client(), addr).await?
     {
+        // Changing the authorization policy must be signed with all the keys
+        // of the account
+        if policy.is_some() {
+            let keys = account.public_keys_map.idx_to_pk.len();
+            signing_data.threshold = signing_data
+                .threshold
+                .max(u8::try_from(keys).unwrap_or(u8::MAX));
+        }
         account.address
     } else if tx_args.force {
         addr.clone()
@@ -2428,6 +2888,31 @@ pub async fn build_update_account(
         return Err(Error::from(TxError::LocationDoesNotExist(addr.clone())));
     };
 
+    let vp_timelock = vp_update_delay.zip(recovery_key.clone()).map(
+        |(delay, recovery_key)| VpUpdateTimelock {
+            delay,
+            recovery_key,
+        },
+    );
+    // Cancelling a scheduled VP update or changing an existing timelock must
+    // be signed with the recovery key of the timelock
+    if *cancel_vp_update || vp_timelock.is_some() {
+        if let Some(timelock) =
+            rpc::query_vp_timelock(context.client(), &addr).await?
+        {
+            if !signing_data.public_keys.contains(&timelock.recovery_key) {
+                signing_data.public_keys.push(timelock.recovery_key);
+            }
+            // The recovery key is not one of the account's keys, so the
+            // signatures are made over the public keys instead
+            signing_data.account_public_keys_map =
+                Some(AccountPublicKeysMap::from_iter(
+                    signing_data.public_keys.clone(),
+                ));
+            signing_data.owner = None;
+        }
+    }
+
     let vp_code_hash = match vp_code_path {
         Some(code_path) => {
             let vp_hash = query_wasm_code_hash_buf(context, code_path).await?;
@@ -2436,6 +2921,18 @@ pub async fn build_update_account(
         None => None,
     };
 
+    let allowed_txs = match allowed_txs {
+        Some(code_paths) => {
+            let mut hashes = Vec::with_capacity(code_paths.len());
+            for code_path in code_paths {
+                hashes
+                    .push(query_wasm_code_hash_buf(context, code_path).await?);
+            }
+            Some(hashes)
+        }
+        None => None,
+    };
+
     let chain_id = tx_args.chain_id.clone().unwrap();
     let mut tx = Tx::new(chain_id, tx_args.expiration);
     let extra_section_hash = vp_code_path.as_ref().zip(vp_code_hash).map(
@@ -2452,6 +2949,10 @@ pub async fn build_update_account(
         vp_code_hash: extra_section_hash,
         public_keys: public_keys.clone(),
         threshold: *threshold,
+        vp_timelock,
+        cancel_vp_update: *cancel_vp_update,
+        allowed_txs,
+        policy: policy.clone(),
     };
 
     let add_code_hash = |tx: &mut Tx, data: &mut UpdateAccount| {
@@ -2682,7 +3183,7 @@ async fn known_validator_or_err(
             edisplay_line!(
                 context.io(),
                 "The address {} doesn't belong to any known validator account.",
-                validator
+                context.wallet().await.format_address(&validator)
             );
             Ok(validator)
         } else {
@@ -2727,8 +3228,10 @@ async fn source_exists_or_err(
     force: bool,
     context: &impl Namada,
 ) -> Result<Address> {
-    let message =
-        format!("The source address {} doesn't exist on chain.", token);
+    let message = format!(
+        "The source address {} doesn't exist on chain.",
+        context.wallet().await.format_address(&token)
+    );
     address_exists_or_err(token, force, context, message, |err| {
         Error::from(TxError::SourceDoesNotExist(err))
     })
@@ -2743,8 +3246,10 @@ async fn target_exists_or_err(
     force: bool,
     context: &impl Namada,
 ) -> Result<Address> {
-    let message =
-        format!("The target address {} doesn't exist on chain.", token);
+    let message = format!(
+        "The target address {} doesn't exist on chain.",
+        context.wallet().await.format_address(&token)
+    );
     address_exists_or_err(token, force, context, message, |err| {
         Error::from(TxError::TargetLocationDoesNotExist(err))
     })
@@ -2772,13 +3277,20 @@ async fn check_balance_too_low_err<N: Namada>(
             Some(diff) => Ok(diff),
             None => {
                 if force {
+                    let (source, token_name) = {
+                        let wallet = context.wallet().await;
+                        (
+                            wallet.format_address(source),
+                            wallet.format_address(token),
+                        )
+                    };
                     edisplay_line!(
                         context.io(),
                         "The balance of the source {} of token {} is lower \
                          than the amount to be transferred. Amount to \
                          transfer is {} and the balance is {}.",
                         source,
-                        token,
+                        token_name,
                         context.format_amount(token, amount).await,
                         context.format_amount(token, balance).await,
                     );
@@ -2797,11 +3309,12 @@ async fn check_balance_too_low_err<N: Namada>(
             QueryError::General(_) | QueryError::NoSuchKey(_),
         )) => {
             if force {
+                let wallet = context.wallet().await;
                 edisplay_line!(
                     context.io(),
                     "No balance found for the source {} of token {}",
-                    source,
-                    token
+                    wallet.format_address(source),
+                    wallet.format_address(token)
                 );
                 Ok(token::Amount::zero())
             } else {
@@ -2,6 +2,7 @@ use core::fmt;
 use std::str::FromStr;
 
 use derivation_path::{ChildIndex, DerivationPath as DerivationPathInner};
+use namada_core::types::address::validation::SLIP44_COIN_TYPE;
 use namada_core::types::key::SchemeType;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
@@ -12,7 +13,7 @@ use tiny_hderive::bip44::{
 use tiny_hderive::Error as HDeriveError;
 
 const ETH_COIN_TYPE: u32 = 60;
-const NAMADA_COIN_TYPE: u32 = 877;
+const NAMADA_COIN_TYPE: u32 = SLIP44_COIN_TYPE;
 
 #[derive(Error, Debug)]
 pub enum DerivationPathError {
@@ -137,6 +138,7 @@ impl IntoHDeriveDerivationPath for DerivationPath {
 
 #[cfg(test)]
 mod tests {
+    use namada_core::types::address::validation::SLIP44_COIN_TYPE;
     use namada_core::types::key::SchemeType;
 
     use super::DerivationPath;
@@ -0,0 +1,147 @@
+//! The local history of the txs submitted by the client, persisted by the
+//! wallet's storage next to the wallet store.
+
+use namada_core::types::address::Address;
+use namada_core::types::time::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+
+/// The status of a submitted tx
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxStatus {
+    /// The tx was broadcast, but it hasn't been seen applied yet
+    Pending,
+    /// The tx was applied
+    Applied,
+    /// The tx was rejected or its application failed
+    Rejected,
+}
+
+impl std::fmt::Display for TxStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxStatus::Pending => write!(f, "pending"),
+            TxStatus::Applied => write!(f, "applied"),
+            TxStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// An amount of tokens moved by a tx
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxAmount {
+    /// The token
+    pub token: Address,
+    /// The denominated amount
+    pub amount: String,
+}
+
+/// A record of a submitted tx
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxRecord {
+    /// The hash of the inner tx
+    pub hash: String,
+    /// The hash of the wrapper tx
+    pub wrapper_hash: String,
+    /// The type of the tx, i.e. the tag of its code
+    pub tx_type: String,
+    /// The addresses involved in the tx, starting with its fee payer
+    pub parties: Vec<Address>,
+    /// The amounts of tokens moved by the tx, if known
+    pub amounts: Vec<TxAmount>,
+    /// The last known status of the tx
+    pub status: TxStatus,
+    /// The time of the submission of the tx
+    pub submitted_at: DateTimeUtc,
+}
+
+/// The history of the submitted txs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxHistory {
+    /// The records in the order of submission
+    #[serde(default)]
+    pub records: Vec<TxRecord>,
+}
+
+impl TxHistory {
+    /// Insert the record of a tx, replacing the previous record of the same
+    /// tx, if any
+    pub fn insert(&mut self, record: TxRecord) {
+        match self
+            .records
+            .iter_mut()
+            .find(|existing| existing.hash == record.hash)
+        {
+            Some(existing) => *existing = record,
+            None => self.records.push(record),
+        }
+    }
+
+    /// The records of the txs involving the given address
+    pub fn records_of<'a>(
+        &'a self,
+        owner: &'a Address,
+    ) -> impl Iterator<Item = &'a TxRecord> + 'a {
+        self.records
+            .iter()
+            .filter(move |record| record.parties.contains(owner))
+    }
+
+    /// Decode a history from a string of bytes
+    pub fn decode(data: Vec<u8>) -> Result<Self, toml::de::Error> {
+        toml::from_slice(&data)
+    }
+
+    /// Encode a history into a string of bytes
+    pub fn encode(&self) -> Vec<u8> {
+        toml::to_vec(self).expect("Serializing of tx history shouldn't fail")
+    }
+}
+
+#[cfg(test)]
+mod test_tx_history {
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    use super::*;
+
+    fn record(hash: &str, parties: Vec<Address>) -> TxRecord {
+        TxRecord {
+            hash: hash.to_string(),
+            wrapper_hash: format!("wrapper-{hash}"),
+            tx_type: "tx_transfer.wasm".to_string(),
+            parties,
+            amounts: vec![],
+            status: TxStatus::Pending,
+            submitted_at: DateTimeUtc::now(),
+        }
+    }
+
+    #[test]
+    fn test_tx_history() {
+        let alice = established_address_1();
+        let bob = established_address_2();
+        let mut history = TxHistory::default();
+        history.insert(record("a", vec![alice.clone()]));
+        history.insert(record("b", vec![alice.clone(), bob.clone()]));
+
+        // A record of the same tx replaces the previous one
+        let mut applied = record("a", vec![alice.clone()]);
+        applied.status = TxStatus::Applied;
+        applied.amounts.push(TxAmount {
+            token: bob.clone(),
+            amount: "1.5".to_string(),
+        });
+        history.insert(applied.clone());
+        assert_eq!(history.records.len(), 2);
+        assert_eq!(history.records[0], applied);
+
+        assert_eq!(history.records_of(&alice).count(), 2);
+        let of_bob: Vec<_> = history.records_of(&bob).collect();
+        assert_eq!(of_bob.len(), 1);
+        assert_eq!(of_bob[0].hash, "b");
+
+        let decoded = TxHistory::decode(history.encode()).unwrap();
+        assert_eq!(decoded, history);
+    }
+}
@@ -1,6 +1,7 @@
 //! Provides functionality for managing keys and addresses for a user
 pub mod alias;
 mod derivation_path;
+pub mod history;
 mod keys;
 pub mod pre_genesis;
 pub mod store;
@@ -25,6 +26,7 @@ use thiserror::Error;
 use zeroize::Zeroizing;
 
 pub use self::derivation_path::{DerivationPath, DerivationPathError};
+pub use self::history::{TxAmount, TxHistory, TxRecord, TxStatus};
 pub use self::keys::{DecryptionError, StoredKeypair};
 pub use self::store::{ConfirmationResponse, ValidatorData, ValidatorKeys};
 use crate::wallet::store::derive_hd_secret_key;
@@ -88,6 +90,10 @@ pub trait WalletIo: Sized + Clone {
         panic!("attempted to prompt for alias in non-interactive mode");
     }
 
+    /// Show a message about a wallet operation to the user. The messages are
+    /// discarded in non-interactive mode.
+    fn show_message(_msg: &str) {}
+
     /// The given alias has been selected but conflicts with another alias in
     /// the store. Offer the user to either replace existing mapping, alter the
     /// chosen alias to a name of their choice, or cancel the aliasing.
@@ -121,13 +127,38 @@ pub trait WalletStorage: Sized + Clone {
 
     /// Load a wallet from the store file.
     fn load<U>(&self, wallet: &mut Wallet<U>) -> Result<(), LoadStoreError>;
+
+    /// Save the local history of the submitted txs. The history isn't
+    /// persisted by default.
+    fn save_tx_history(
+        &self,
+        _history: &TxHistory,
+    ) -> Result<(), LoadStoreError> {
+        Ok(())
+    }
+
+    /// Load the local history of the submitted txs
+    fn load_tx_history(&self) -> Result<TxHistory, LoadStoreError> {
+        Ok(TxHistory::default())
+    }
+
+    /// Update the local history of the submitted txs. The history must not
+    /// be modified by another client in between its load and its save.
+    fn update_tx_history(
+        &self,
+        update: impl FnOnce(&mut TxHistory),
+    ) -> Result<(), LoadStoreError> {
+        let mut history = self.load_tx_history()?;
+        update(&mut history);
+        self.save_tx_history(&history)
+    }
 }
 
 #[cfg(feature = "std")]
 /// Implementation of wallet functionality depending on a standard filesystem
 pub mod fs {
     use std::fs;
-    use std::io::{Read, Write};
+    use std::io::{Read, Seek, Write};
     use std::path::PathBuf;
 
     use fd_lock::RwLock;
@@ -144,6 +175,9 @@ pub mod fs {
     /// Wallet file name
     const FILE_NAME: &str = "wallet.toml";
 
+    /// Tx history file name
+    const TX_HISTORY_FILE_NAME: &str = "tx_history.toml";
+
     impl<F: FsWalletStorage> WalletStorage for F {
         fn save<U>(&self, wallet: &Wallet<U>) -> Result<(), LoadStoreError> {
             let data = wallet.store.encode();
@@ -199,6 +233,62 @@ pub mod fs {
                 Store::decode(store).map_err(LoadStoreError::Decode)?;
             Ok(())
         }
+
+        fn save_tx_history(
+            &self,
+            history: &TxHistory,
+        ) -> Result<(), LoadStoreError> {
+            self.update_tx_history(|stored| *stored = history.clone())
+        }
+
+        fn load_tx_history(&self) -> Result<TxHistory, LoadStoreError> {
+            let history_file = self.store_dir().join(TX_HISTORY_FILE_NAME);
+            if !history_file.exists() {
+                return Ok(TxHistory::default());
+            }
+            let read_err = |err: std::io::Error| {
+                LoadStoreError::ReadWallet(
+                    history_file.to_string_lossy().into_owned(),
+                    err.to_string(),
+                )
+            };
+            let mut options = fs::OpenOptions::new();
+            options.read(true).write(false);
+            let lock =
+                RwLock::new(options.open(&history_file).map_err(read_err)?);
+            let guard = lock.read().map_err(read_err)?;
+            let mut history = Vec::<u8>::new();
+            (&*guard).read_to_end(&mut history).map_err(read_err)?;
+            TxHistory::decode(history).map_err(LoadStoreError::Decode)
+        }
+
+        fn update_tx_history(
+            &self,
+            update: impl FnOnce(&mut TxHistory),
+        ) -> Result<(), LoadStoreError> {
+            let store_err = |err: std::io::Error| {
+                LoadStoreError::StoreNewWallet(err.to_string())
+            };
+            fs::create_dir_all(self.store_dir()).map_err(store_err)?;
+            // The file is locked from the read of the history until its write,
+            // so that the concurrent clients don't lose each other's records
+            let mut options = fs::OpenOptions::new();
+            options.create(true).read(true).write(true);
+            let mut lock = RwLock::new(
+                options
+                    .open(self.store_dir().join(TX_HISTORY_FILE_NAME))
+                    .map_err(store_err)?,
+            );
+            let mut guard = lock.write().map_err(store_err)?;
+            let mut data = Vec::<u8>::new();
+            guard.read_to_end(&mut data).map_err(store_err)?;
+            let mut history =
+                TxHistory::decode(data).map_err(LoadStoreError::Decode)?;
+            update(&mut history);
+            guard.set_len(0).map_err(store_err)?;
+            guard.rewind().map_err(store_err)?;
+            guard.write_all(&history.encode()).map_err(store_err)
+        }
     }
 
     /// For a non-interactive filesystem based wallet
@@ -241,6 +331,20 @@ pub fn gen_secret_key(
     .unwrap()
 }
 
+/// Derive the public key at the given derivation path from a mnemonic code
+/// and passphrase read with the wallet IO, without storing anything. This
+/// doesn't require a wallet, e.g. to derive addresses offline.
+pub fn derive_public_key_from_mnemonic_code<U: WalletIo>(
+    scheme: SchemeType,
+    derivation_path: DerivationPath,
+) -> Result<common::PublicKey, GenRestoreKeyError> {
+    let mnemonic = U::read_mnemonic_code()?;
+    let passphrase = U::read_mnemonic_passphrase(false);
+    let seed = Seed::new(&mnemonic, &passphrase);
+    let sk = derive_hd_secret_key(scheme, seed.as_bytes(), derivation_path);
+    Ok(sk.ref_to())
+}
+
 fn gen_spending_key(
     csprng: &mut (impl CryptoRng + RngCore),
 ) -> ExtendedSpendingKey {
@@ -390,11 +494,31 @@ impl<U> Wallet<U> {
     }
 
     /// Try to find an alias for a given address from the wallet. If not found,
-    /// formats the address into a string.
+    /// uses the stable name of an internal address or formats the address
+    /// into a string.
     pub fn lookup_alias(&self, addr: &Address) -> String {
         match self.find_alias(addr) {
             Some(alias) => format!("{}", alias),
-            None => format!("{}", addr),
+            None => match addr.internal_name() {
+                Some(name) => name.to_string(),
+                None => format!("{}", addr),
+            },
+        }
+    }
+
+    /// Format an address for display with its alias in the wallet or the
+    /// stable name of an internal address, if any, followed by the address,
+    /// e.g. `validator-1 (tnam1q...)`. The aliases are looked up in the
+    /// reverse index of the wallet's address book, which is kept up to date
+    /// with the wallet's changes.
+    pub fn format_address(&self, addr: &Address) -> String {
+        let name = self
+            .find_alias(addr)
+            .map(|alias| alias.to_string())
+            .or_else(|| addr.internal_name().map(|name| name.to_string()));
+        match name {
+            Some(name) => format!("{name} ({addr})"),
+            None => addr.to_string(),
         }
     }
 
@@ -489,6 +613,34 @@ impl<U: WalletStorage> Wallet<U> {
     pub fn save(&self) -> Result<(), LoadStoreError> {
         self.utils.save(self)
     }
+
+    /// Load the local history of the submitted txs
+    pub fn tx_history(&self) -> Result<TxHistory, LoadStoreError> {
+        self.utils.load_tx_history()
+    }
+
+    /// Save the local history of the submitted txs
+    pub fn save_tx_history(
+        &self,
+        history: &TxHistory,
+    ) -> Result<(), LoadStoreError> {
+        self.utils.save_tx_history(history)
+    }
+
+    /// Record a submitted tx in the local tx history, replacing the previous
+    /// record of the same tx, if any
+    pub fn record_tx(&self, record: TxRecord) -> Result<(), LoadStoreError> {
+        self.utils.update_tx_history(|history| history.insert(record))
+    }
+
+    /// Update the local history of the submitted txs, locking it from its
+    /// load until its save
+    pub fn update_tx_history(
+        &self,
+        update: impl FnOnce(&mut TxHistory),
+    ) -> Result<(), LoadStoreError> {
+        self.utils.update_tx_history(update)
+    }
 }
 
 impl<U: WalletIo> Wallet<U> {
@@ -591,11 +743,11 @@ impl<U: WalletIo> Wallet<U> {
     ) -> Result<(Mnemonic, Seed), GenRestoreKeyError> {
         const MNEMONIC_TYPE: MnemonicType = MnemonicType::Words24;
         let mnemonic = U::generate_mnemonic_code(MNEMONIC_TYPE, rng)?;
-        println!(
+        U::show_message(&format!(
             "Safely store your {} words mnemonic.",
             MNEMONIC_TYPE.word_count()
-        );
-        println!("{}", mnemonic.clone().into_phrase());
+        ));
+        U::show_message(mnemonic.phrase());
 
         let passphrase = if unsafe_dont_encrypt {
             Zeroizing::new(String::new())
@@ -668,7 +820,9 @@ impl<U: WalletIo> Wallet<U> {
             )
             .expect("Failed to initialize disposable keypair");
 
-        println!("Created disposable keypair with alias {alias}");
+        U::show_message(&format!(
+            "Created disposable keypair with alias {alias}"
+        ));
         disposable_keypair
     }
 
@@ -302,27 +302,30 @@ impl Store {
             .unwrap_or_else(|| Address::Implicit(ImplicitAddress(pkh.clone())));
         if !force {
             if self.pkhs.contains_key(&pkh) {
-                println!("The key already exists.");
+                U::show_message("The key already exists.");
                 return None;
             } else if let Some(alias) = self.addresses.get_by_right(&address) {
-                println!(
+                U::show_message(&format!(
                     "Address {} already exists in the wallet with alias {}",
                     address.encode(),
                     alias,
-                );
+                ));
                 return None;
             }
         }
 
         // abort if the alias is reserved
         if Alias::is_reserved(&alias).is_some() {
-            println!("The alias {} is reserved", alias);
+            U::show_message(&format!("The alias {} is reserved", alias));
             return None;
         }
 
         if alias.is_empty() {
             alias = pkh.to_string().into();
-            println!("Empty alias given, defaulting to {}.", alias);
+            U::show_message(&format!(
+                "Empty alias given, defaulting to {}.",
+                alias
+            ));
         }
         if self.contains_alias(&alias) && !force {
             match U::show_overwrite_confirmation(&alias, "a key") {
@@ -362,12 +365,12 @@ impl Store {
     ) -> Option<Alias> {
         // abort if the alias is reserved
         if Alias::is_reserved(&alias).is_some() {
-            println!("The alias {} is reserved", alias);
+            U::show_message(&format!("The alias {} is reserved", alias));
             return None;
         }
 
         if alias.is_empty() {
-            eprintln!("Empty alias given.");
+            U::show_message("Empty alias given.");
             return None;
         }
         if self.contains_alias(&alias) && !force {
@@ -400,12 +403,12 @@ impl Store {
     ) -> Option<Alias> {
         // abort if the alias is reserved
         if Alias::is_reserved(&alias).is_some() {
-            println!("The alias {} is reserved", alias);
+            U::show_message(&format!("The alias {} is reserved", alias));
             return None;
         }
 
         if alias.is_empty() {
-            eprintln!("Empty alias given.");
+            U::show_message("Empty alias given.");
             return None;
         }
         if self.contains_alias(&alias) && !force {
@@ -437,20 +440,23 @@ impl Store {
             .unwrap_or_else(|| Address::Implicit(ImplicitAddress(pkh.clone())));
         if !force {
             if self.pkhs.contains_key(&pkh) {
-                println!("The key already exists.");
+                U::show_message("The key already exists.");
                 return None;
             } else if let Some(alias) = self.addresses.get_by_right(&address) {
-                println!(
+                U::show_message(&format!(
                     "Address {} already exists in the wallet with alias {}",
                     address.encode(),
                     alias,
-                );
+                ));
                 return None;
             }
         }
         if alias.is_empty() {
             alias = pkh.to_string().into();
-            println!("Empty alias given, defaulting to {}.", alias);
+            U::show_message(&format!(
+                "Empty alias given, defaulting to {}.",
+                alias
+            ));
         }
         if self.contains_alias(&alias) && !force {
             match U::show_overwrite_confirmation(&alias, "a public key") {
@@ -484,12 +490,12 @@ impl Store {
     ) -> Option<Alias> {
         // abort if the alias is reserved
         if Alias::is_reserved(&alias).is_some() {
-            println!("The alias {} is reserved", alias);
+            U::show_message(&format!("The alias {} is reserved", alias));
             return None;
         }
 
         if alias.is_empty() {
-            eprintln!("Empty alias given.");
+            U::show_message("Empty alias given.");
             return None;
         }
         if self.contains_alias(&alias) && !force {
@@ -522,22 +528,25 @@ impl Store {
     ) -> Option<Alias> {
         // abort if the alias is reserved
         if Alias::is_reserved(&alias).is_some() {
-            println!("The alias {} is reserved", alias);
+            U::show_message(&format!("The alias {} is reserved", alias));
             return None;
         }
         // abort if the address already exists in the wallet
         if self.addresses.contains_right(&address) && !force {
-            println!(
+            U::show_message(&format!(
                 "Address {} already exists in the wallet with alias {}",
                 address.encode(),
                 self.addresses.get_by_right(&address).unwrap()
-            );
+            ));
             return None;
         }
 
         if alias.is_empty() {
             alias = address.encode().into();
-            println!("Empty alias given, defaulting to {}.", alias);
+            U::show_message(&format!(
+                "Empty alias given, defaulting to {}.",
+                alias
+            ));
         }
         if self.contains_alias(&alias) && !force {
             match U::show_overwrite_confirmation(&alias, "an address") {
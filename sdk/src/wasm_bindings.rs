@@ -0,0 +1,41 @@
+//! JavaScript bindings of the SDK's key types, for browser wallets. The types
+//! are passed to and from JavaScript in their string encodings.
+
+use std::str::FromStr;
+
+use namada_core::proto::Tx;
+use namada_core::types::address::Address;
+use namada_core::types::key::common;
+use namada_core::types::token::Amount;
+use wasm_bindgen::prelude::*;
+
+/// Check that a string is a valid bech32m encoded address
+#[wasm_bindgen(js_name = isValidAddress)]
+pub fn is_valid_address(address: &str) -> bool {
+    Address::decode(address).is_ok()
+}
+
+/// The implicit address of a bech32m encoded public key
+#[wasm_bindgen(js_name = implicitAddress)]
+pub fn implicit_address(public_key: &str) -> Result<String, JsError> {
+    let public_key = common::PublicKey::from_str(public_key)
+        .map_err(|err| JsError::new(&err.to_string()))?;
+    Ok(Address::from(&public_key).encode())
+}
+
+/// The hash of the header of a serialized tx, which identifies the tx on chain
+#[wasm_bindgen(js_name = txHash)]
+pub fn tx_hash(tx_bytes: &[u8]) -> Result<String, JsError> {
+    let tx =
+        Tx::try_from(tx_bytes).map_err(|err| JsError::new(&err.to_string()))?;
+    Ok(tx.header_hash().to_string())
+}
+
+/// The raw amount of a decimal amount of a token with the given number of
+/// decimal places, e.g. `"1.5"` with 6 decimal places is `"1500000"`
+#[wasm_bindgen(js_name = parseAmount)]
+pub fn parse_amount(amount: &str, denom: u8) -> Result<String, JsError> {
+    let amount = Amount::from_str(amount, denom)
+        .map_err(|err| JsError::new(&err.to_string()))?;
+    Ok(amount.raw_amount().to_string())
+}
@@ -14,6 +14,7 @@ use namada_core::ledger::governance::utils::is_valid_validator_voting_period;
 use namada_core::ledger::storage;
 use namada_core::ledger::storage_api::account;
 use namada_core::ledger::storage_api::governance::is_proposal_accepted;
+use namada_core::ledger::upgrade;
 use namada_core::ledger::vp_env::VpEnv;
 use namada_core::proto::Tx;
 use namada_proof_of_stake::is_validator;
@@ -120,6 +121,10 @@ where
                     self.is_valid_proposal_commit()
                 }
                 (KeyType::PARAMETER, _) => self.is_valid_parameter(tx_data),
+                (KeyType::EPOCH_HOOK, _) => self.is_valid_epoch_hook(tx_data),
+                (KeyType::UPGRADE_PLAN, _) => {
+                    self.is_valid_upgrade_plan(tx_data)
+                }
                 (KeyType::BALANCE, _) => self.is_valid_balance(&native_token),
                 (KeyType::UNKNOWN_GOVERNANCE, _) => Ok(false),
                 (KeyType::UNKNOWN, _) => Ok(true),
@@ -606,6 +611,35 @@ where
         }
     }
 
+    /// Validate a change of the epoch hooks, which can only be made by an
+    /// accepted proposal
+    pub fn is_valid_epoch_hook(&self, tx: &Tx) -> Result<bool> {
+        self.is_from_accepted_proposal(tx)
+    }
+
+    /// Validate a change of the scheduled upgrade, which can only be made by
+    /// an accepted proposal and must be valid, if it's not a cancellation
+    pub fn is_valid_upgrade_plan(&self, tx: &Tx) -> Result<bool> {
+        if !self.is_from_accepted_proposal(tx)? {
+            return Ok(false);
+        }
+        match upgrade::read_plan(&self.ctx.post())? {
+            Some(plan) => upgrade::is_valid_plan(&self.ctx.post(), &plan)
+                .map_err(Error::NativeVpError),
+            None => Ok(true),
+        }
+    }
+
+    /// Check that the tx is the execution of an accepted proposal, which
+    /// carries the proposal id as its data
+    fn is_from_accepted_proposal(&self, tx: &Tx) -> Result<bool> {
+        match tx.data() {
+            Some(data) => is_proposal_accepted(&self.ctx.pre(), data.as_ref())
+                .map_err(Error::NativeVpError),
+            None => Ok(false),
+        }
+    }
+
     /// Check if a vote is from a validator
     pub fn is_validator(
         &self,
@@ -711,6 +745,10 @@ enum KeyType {
     #[allow(non_camel_case_types)]
     PARAMETER,
     #[allow(non_camel_case_types)]
+    EPOCH_HOOK,
+    #[allow(non_camel_case_types)]
+    UPGRADE_PLAN,
+    #[allow(non_camel_case_types)]
     UNKNOWN_GOVERNANCE,
     #[allow(non_camel_case_types)]
     UNKNOWN,
@@ -742,6 +780,10 @@ impl KeyType {
             KeyType::COUNTER
         } else if gov_storage::is_parameter_key(key) {
             KeyType::PARAMETER
+        } else if gov_storage::is_epoch_hook_key(key).is_some() {
+            KeyType::EPOCH_HOOK
+        } else if upgrade::is_plan_key(key) {
+            KeyType::UPGRADE_PLAN
         } else if token::is_balance_key(native_token, key).is_some() {
             KeyType::BALANCE
         } else if gov_storage::is_governance_key(key) {
@@ -15,7 +15,8 @@ pub mod vp_host_fns;
 #[cfg(feature = "wasm-runtime")]
 pub use dry_run_tx::dry_run_tx;
 pub use namada_core::ledger::{
-    gas, parameters, replay_protection, storage_api, tx_env, vp_env,
+    gas, oracle, parameters, replay_protection, storage_api, testnet_pow,
+    tx_env, upgrade, vp_env,
 };
 
 #[cfg(feature = "wasm-runtime")]
@@ -54,6 +55,8 @@ mod dry_run_tx {
         let mut temp_wl_storage = TempWlStorage::new(&ctx.wl_storage.storage);
         let mut cumulated_gas = Gas::default();
 
+        // The fee payer of the wrapper, if any, pays the storage deposits
+        let mut storage_deposit_payer = None;
         // Wrapper dry run to allow estimating the gas cost of a transaction
         let mut tx_gas_meter = match tx.header().tx_type {
             TxType::Wrapper(wrapper) => {
@@ -78,6 +81,7 @@ mod dry_run_tx {
                 cumulated_gas = tx_gas_meter.get_tx_consumed_gas();
 
                 tx.update_header(TxType::Decrypted(DecryptedTx::Decrypted));
+                storage_deposit_payer = Some(wrapper.fee_payer());
                 TxGasMeter::new_from_sub_limit(tx_gas_meter.get_available_gas())
             }
             TxType::Protocol(_) | TxType::Decrypted(_) => {
@@ -103,20 +107,21 @@ mod dry_run_tx {
             }
         };
 
-        let mut data = protocol::apply_wasm_tx(
-            tx,
-            &TxIndex(0),
-            ShellParams::new(
-                &mut tx_gas_meter,
-                &mut temp_wl_storage,
-                &mut ctx.vp_wasm_cache,
-                &mut ctx.tx_wasm_cache,
-            ),
+        let mut shell_params = ShellParams::new(
+            &mut tx_gas_meter,
+            &mut temp_wl_storage,
+            &mut ctx.vp_wasm_cache,
+            &mut ctx.tx_wasm_cache,
         )
-        .into_storage_result()?;
+        .with_debug_trace();
+        if let Some(payer) = storage_deposit_payer.as_ref() {
+            shell_params = shell_params.with_storage_deposit_payer(payer);
+        }
+        let mut data = protocol::apply_wasm_tx(tx, &TxIndex(0), shell_params)
+            .into_storage_result()?;
         cumulated_gas = cumulated_gas
             .checked_add(tx_gas_meter.get_tx_consumed_gas())
-            .ok_or(namada_core::ledger::storage_api::Error::SimpleMessage(
+            .ok_or(namada_core::ledger::storage_api::Error::new_const(
                 "Overflow in gas",
             ))?;
         // Account gas for both inner and wrapper (if available)
@@ -202,6 +207,19 @@ mod test {
                 .expect(
                     "Max block gas parameter must be initialized in storage",
                 );
+            // Initialize the WASM limits
+            let wasm_limits_key =
+                namada_core::ledger::parameters::storage::get_wasm_limits_key(
+                );
+            wl_storage
+                .storage
+                .write(
+                    &wasm_limits_key,
+                    namada_core::ledger::storage::types::encode(
+                        &namada_core::ledger::parameters::WasmLimits::default(),
+                    ),
+                )
+                .expect("WASM limits parameter must be initialized in storage");
             let event_log = EventLog::default();
             let (vp_wasm_cache, vp_cache_dir) =
                 wasm::compilation_cache::common::testing::cache();
@@ -23,7 +23,10 @@ use namada_proof_of_stake::read_pos_params;
 use thiserror::Error;
 
 use crate::ibc::core::host::types::identifiers::ChainId as IbcChainId;
-use crate::ledger::ibc::storage::{calc_hash, is_ibc_denom_key, is_ibc_key};
+use crate::ledger::ibc::storage::{
+    calc_hash, executed_msgs_key, is_executed_msgs_key, is_ibc_denom_key,
+    is_ibc_key,
+};
 use crate::ledger::native_vp::{self, Ctx, NativeVp, VpEnv};
 use crate::ledger::parameters::read_epoch_duration_parameter;
 use crate::vm::WasmCacheAccess;
@@ -73,14 +76,15 @@ where
         keys_changed: &BTreeSet<Key>,
         _verifiers: &BTreeSet<Address>,
     ) -> VpResult<bool> {
-        let signed = tx_data;
-        let tx_data = signed.data().ok_or(Error::NoTxData)?;
+        let msgs = self.executed_msgs(tx_data)?;
 
         // Pseudo execution and compare them
-        self.validate_state(&tx_data, keys_changed)?;
+        self.validate_state(&msgs, keys_changed)?;
 
-        // Validate the state according to the given IBC message
-        self.validate_with_msg(&tx_data)?;
+        // Validate the state according to the given IBC messages
+        for msg in &msgs {
+            self.validate_with_msg(msg)?;
+        }
 
         // Validate the denom store if a denom key has been changed
         self.validate_denom(keys_changed)?;
@@ -95,9 +99,23 @@ where
     H: 'static + StorageHasher,
     CA: 'static + WasmCacheAccess,
 {
+    /// Get the IBC messages executed by the transaction. These are the
+    /// messages recorded by a custom transaction, if any, or otherwise the
+    /// IBC message in the tx data.
+    fn executed_msgs(&self, tx: &Tx) -> VpResult<Vec<Vec<u8>>> {
+        let recorded: Option<Vec<Vec<u8>>> = self
+            .ctx
+            .read_temp(&executed_msgs_key())
+            .map_err(Error::NativeVpError)?;
+        match recorded {
+            Some(msgs) => Ok(msgs),
+            None => Ok(vec![tx.data().ok_or(Error::NoTxData)?]),
+        }
+    }
+
     fn validate_state(
         &self,
-        tx_data: &[u8],
+        msgs: &[Vec<u8>],
         keys_changed: &BTreeSet<Key>,
     ) -> VpResult<()> {
         let exec_ctx = PseudoExecutionContext::new(self.ctx.pre());
@@ -107,13 +125,18 @@ where
         let module = TransferModule::new(ctx.clone());
         actions.add_transfer_module(module.module_id(), module);
         // Charge gas for the expensive execution
-        self.ctx
-            .charge_gas(IBC_ACTION_EXECUTE_GAS)
-            .map_err(Error::NativeVpError)?;
-        actions.execute(tx_data)?;
+        for msg in msgs {
+            self.ctx
+                .charge_gas(IBC_ACTION_EXECUTE_GAS)
+                .map_err(Error::NativeVpError)?;
+            actions.execute(msg)?;
+        }
 
-        let changed_ibc_keys: HashSet<&Key> =
-            keys_changed.iter().filter(|k| is_ibc_key(k)).collect();
+        // The record of the executed messages isn't written by the execution
+        let changed_ibc_keys: HashSet<&Key> = keys_changed
+            .iter()
+            .filter(|k| is_ibc_key(k) && !is_executed_msgs_key(k))
+            .collect();
         if changed_ibc_keys.len() != ctx.borrow().get_changed_keys().len() {
             return Err(Error::StateChange(format!(
                 "The changed keys mismatched: Actual {:?}, Expected {:?}",
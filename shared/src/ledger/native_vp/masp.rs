@@ -5,14 +5,17 @@ use std::collections::BTreeSet;
 
 use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
+use masp_primitives::sapling::Node;
 use masp_primitives::transaction::components::I128Sum;
 use namada_core::ledger::gas::MASP_VERIFY_SHIELDED_TX_GAS;
 use namada_core::ledger::storage;
 use namada_core::ledger::storage_api::OptionExt;
 use namada_core::ledger::vp_env::VpEnv;
+use namada_core::ledger::{masp_commitment_tree, masp_nullifiers};
 use namada_core::proto::Tx;
 use namada_core::types::address::Address;
 use namada_core::types::address::InternalAddress::Masp;
+use namada_core::types::hash::Hash;
 use namada_core::types::storage::{Epoch, Key};
 use namada_core::types::token;
 use namada_sdk::masp::verify_shielded_tx;
@@ -108,6 +111,104 @@ fn convert_amount(
     (asset_type, amount)
 }
 
+impl<'a, DB, H, CA> MaspVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that the nullifiers of the notes spent by the shielded tx had not
+    /// been revealed before and are revealed by the tx, and that the tx
+    /// doesn't reveal any other nullifier
+    fn valid_nullifiers(
+        &self,
+        nullifiers: &[Hash],
+        keys_changed: &BTreeSet<Key>,
+    ) -> Result<bool> {
+        let unique: BTreeSet<_> = nullifiers.iter().collect();
+        if unique.len() != nullifiers.len() {
+            tracing::debug!("The transaction spends the same note twice");
+            return Ok(false);
+        }
+        for nullifier in nullifiers {
+            if masp_nullifiers::is_revealed(&self.ctx.pre(), nullifier)? {
+                tracing::debug!(
+                    "The note of the nullifier {} has already been spent",
+                    nullifier
+                );
+                return Ok(false);
+            }
+            if !masp_nullifiers::is_revealed(&self.ctx.post(), nullifier)? {
+                tracing::debug!(
+                    "The nullifier {} must be revealed by the transaction",
+                    nullifier
+                );
+                return Ok(false);
+            }
+        }
+        // Revealing another nullifier would make its note unspendable
+        if let Some(nullifier) = keys_changed
+            .iter()
+            .filter_map(masp_nullifiers::is_nullifier_key)
+            .find(|nullifier| !unique.contains(nullifier))
+        {
+            tracing::debug!(
+                "The nullifier {} is not one of the notes spent by the \
+                 transaction",
+                nullifier
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that the notes created by the shielded tx have been appended to
+    /// the note commitment tree in storage
+    fn valid_note_commitment_tree(&self, notes: &[Node]) -> Result<bool> {
+        let mut expected =
+            masp_commitment_tree::read_frontier(&self.ctx.pre())?;
+        let position = expected.size() as u64;
+        masp_commitment_tree::append_to_frontier(&mut expected, notes)?;
+        let frontier = masp_commitment_tree::read_frontier(&self.ctx.post())?;
+        if frontier.size() != expected.size()
+            || frontier.root() != expected.root()
+        {
+            tracing::debug!(
+                "The notes of the transaction must be appended to the \
+                 frontier of the note commitment tree"
+            );
+            return Ok(false);
+        }
+        let stored = masp_commitment_tree::read_note_commitments(
+            &self.ctx.post(),
+            position,
+            notes.len() as u64,
+        )?;
+        if stored != notes {
+            tracing::debug!(
+                "The note commitments from position {} are not the ones of \
+                 the transaction",
+                position
+            );
+            return Ok(false);
+        }
+        if !notes.is_empty() {
+            let height = self.ctx.get_block_height()?;
+            let root =
+                masp_commitment_tree::read_root(&self.ctx.post(), height)?;
+            if root != Some(frontier.root()) {
+                tracing::debug!(
+                    "The root of the note commitment tree must be recorded at \
+                     the height {}",
+                    height
+                );
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
 impl<'a, DB, H, CA> NativeVp for MaspVp<'a, DB, H, CA>
 where
     DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
@@ -119,7 +220,7 @@ where
     fn validate_tx(
         &self,
         tx_data: &Tx,
-        _keys_changed: &BTreeSet<Key>,
+        keys_changed: &BTreeSet<Key>,
         _verifiers: &BTreeSet<Address>,
     ) -> Result<bool> {
         let epoch = self.ctx.get_block_epoch()?;
@@ -285,6 +386,14 @@ where
             }
             _ => {}
         }
+        if !self.valid_nullifiers(
+            &masp_nullifiers::nullifiers(&shielded_tx),
+            keys_changed,
+        )? || !self.valid_note_commitment_tree(
+            &masp_commitment_tree::note_commitments(&shielded_tx),
+        )? {
+            return Ok(false);
+        }
         // Verify the proofs and charge the gas for the expensive execution
         self.ctx
             .charge_gas(MASP_VERIFY_SHIELDED_TX_GAS)
@@ -298,3 +407,173 @@ impl From<native_vp::Error> for Error {
         Self::NativeVpError(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use masp_primitives::merkle_tree::CommitmentTree;
+    use namada_core::ledger::gas::TxGasMeter;
+    use namada_core::ledger::masp_commitment_tree::{
+        bucket_key, frontier_key, root_key,
+    };
+
+    use super::*;
+    use crate::core::ledger::storage::testing::TestWlStorage;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::mockdb::MockDB;
+    use crate::ledger::storage::traits::Sha256Hasher;
+    use crate::types::storage::TxIndex;
+    use crate::types::transaction::TxType;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+    use crate::vm::WasmCacheRwAccess;
+
+    type TestMaspVp<'a> = MaspVp<'a, MockDB, Sha256Hasher, WasmCacheRwAccess>;
+
+    fn note(i: u8) -> Node {
+        Node::new([i; 32])
+    }
+
+    /// Run a check of the VP on the state of the given storage, with the
+    /// given keys changed by the tx
+    fn check_vp(
+        wl_storage: &TestWlStorage,
+        keys_changed: &BTreeSet<Key>,
+        check: impl FnOnce(&TestMaspVp<'_>) -> Result<bool>,
+    ) -> bool {
+        let address = Address::Internal(Masp);
+        let tx = Tx::from_type(TxType::Raw);
+        let tx_index = TxIndex::default();
+        let verifiers = BTreeSet::new();
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &address,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+        let vp = MaspVp { ctx };
+        check(&vp).expect("The check must not fail")
+    }
+
+    /// Run the note commitment tree check of the VP on the given notes
+    fn valid_note_commitment_tree(
+        wl_storage: &TestWlStorage,
+        notes: &[Node],
+    ) -> bool {
+        check_vp(wl_storage, &BTreeSet::new(), |vp| {
+            vp.valid_note_commitment_tree(notes)
+        })
+    }
+
+    /// Run the nullifiers check of the VP on the given nullifiers
+    fn valid_nullifiers(
+        wl_storage: &TestWlStorage,
+        nullifiers: &[Hash],
+        keys_changed: &BTreeSet<Key>,
+    ) -> bool {
+        check_vp(wl_storage, keys_changed, |vp| {
+            vp.valid_nullifiers(nullifiers, keys_changed)
+        })
+    }
+
+    /// Test that a shielded tx must reveal the nullifiers of the notes it
+    /// spends, and only those
+    #[test]
+    fn test_valid_nullifiers() {
+        let mut wl_storage = TestWlStorage::default();
+        // The note of a previous tx has been spent
+        let spent = Hash([1; 32]);
+        masp_nullifiers::reveal_nullifiers(&mut wl_storage, &[spent]).unwrap();
+        wl_storage.commit_block().unwrap();
+
+        let nullifiers = [Hash([2; 32]), Hash([3; 32])];
+        let mut keys_changed: BTreeSet<Key> = nullifiers
+            .iter()
+            .map(masp_nullifiers::nullifier_key)
+            .collect();
+        // The nullifiers are yet to be revealed
+        assert!(!valid_nullifiers(&wl_storage, &nullifiers, &keys_changed));
+
+        // Reveal the nullifiers in the write log of the tx
+        for key in &keys_changed {
+            wl_storage
+                .write_log
+                .write(key, ().serialize_to_vec())
+                .unwrap();
+        }
+        assert!(valid_nullifiers(&wl_storage, &nullifiers, &keys_changed));
+        // A note can't be spent twice
+        assert!(!valid_nullifiers(
+            &wl_storage,
+            &[nullifiers[0], nullifiers[0]],
+            &keys_changed
+        ));
+        assert!(!valid_nullifiers(&wl_storage, &[spent], &BTreeSet::new()));
+
+        // The tx can't reveal a nullifier of a note it doesn't spend
+        let other = masp_nullifiers::nullifier_key(&Hash([4; 32]));
+        wl_storage
+            .write_log
+            .write(&other, ().serialize_to_vec())
+            .unwrap();
+        keys_changed.insert(other);
+        assert!(!valid_nullifiers(&wl_storage, &nullifiers, &keys_changed));
+    }
+
+    /// Test that the notes of a shielded tx must be appended to the note
+    /// commitment tree, with its new root recorded at the current height
+    #[test]
+    fn test_valid_note_commitment_tree() {
+        let mut wl_storage = TestWlStorage::default();
+        // Start the tree with the notes of a previous tx
+        masp_commitment_tree::append_note_commitments(
+            &mut wl_storage,
+            &[note(1), note(2)],
+        )
+        .unwrap();
+        wl_storage.commit_block().unwrap();
+
+        let notes = [note(3), note(4)];
+        assert!(valid_note_commitment_tree(&wl_storage, &[]));
+        assert!(!valid_note_commitment_tree(&wl_storage, &notes));
+
+        // Append the notes in the write log of the tx
+        let mut frontier: CommitmentTree<Node> =
+            masp_commitment_tree::read_frontier(&wl_storage).unwrap();
+        masp_commitment_tree::append_to_frontier(&mut frontier, &notes)
+            .unwrap();
+        let leaves = vec![note(1), note(2), note(3), note(4)];
+        wl_storage
+            .write_log
+            .write(&bucket_key(0), leaves.serialize_to_vec())
+            .unwrap();
+        wl_storage
+            .write_log
+            .write(&frontier_key(), frontier.serialize_to_vec())
+            .unwrap();
+        // The new root is yet to be recorded
+        assert!(!valid_note_commitment_tree(&wl_storage, &notes));
+
+        let (height, _gas) = wl_storage.storage.get_block_height();
+        wl_storage
+            .write_log
+            .write(&root_key(height), frontier.root().serialize_to_vec())
+            .unwrap();
+        assert!(valid_note_commitment_tree(&wl_storage, &notes));
+        // The tree doesn't match the notes of another tx
+        assert!(!valid_note_commitment_tree(&wl_storage, &[]));
+        assert!(!valid_note_commitment_tree(
+            &wl_storage,
+            &[note(4), note(3)]
+        ));
+    }
+}
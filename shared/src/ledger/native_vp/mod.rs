@@ -5,7 +5,9 @@ pub mod ethereum_bridge;
 pub mod ibc;
 pub mod masp;
 pub mod multitoken;
+pub mod oracle;
 pub mod parameters;
+pub mod testnet_faucet;
 
 use std::cell::RefCell;
 use std::collections::BTreeSet;
@@ -16,7 +18,7 @@ use namada_core::ledger::gas::GasMetering;
 pub use namada_core::ledger::vp_env::VpEnv;
 use namada_core::types::validity_predicate::VpSentinel;
 
-use super::storage_api::{self, ResultExt, StorageRead};
+use super::storage_api::{self, StorageRead};
 use super::vp_host_fns;
 use crate::ledger::gas::VpGasMeter;
 use crate::ledger::storage;
@@ -190,7 +192,7 @@ where
             key,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn has_key(
@@ -204,7 +206,7 @@ where
             key,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn iter_prefix<'iter>(
@@ -218,7 +220,7 @@ where
             prefix,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     // ---- Methods below are implemented in `self.ctx`, because they are
@@ -233,7 +235,7 @@ where
             iter,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_chain_id(&self) -> Result<String, storage_api::Error> {
@@ -260,7 +262,7 @@ where
     }
 
     fn get_tx_index(&self) -> Result<TxIndex, storage_api::Error> {
-        self.ctx.get_tx_index().into_storage_result()
+        self.ctx.get_tx_index().map_err(Into::into)
     }
 
     fn get_native_token(&self) -> Result<Address, storage_api::Error> {
@@ -288,7 +290,7 @@ where
             key,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn has_key(
@@ -302,7 +304,7 @@ where
             key,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn iter_prefix<'iter>(
@@ -316,7 +318,7 @@ where
             prefix,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     // ---- Methods below are implemented in `self.ctx`, because they are
@@ -331,7 +333,7 @@ where
             iter,
             &mut self.ctx.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_chain_id(&self) -> Result<String, storage_api::Error> {
@@ -358,7 +360,7 @@ where
     }
 
     fn get_tx_index(&self) -> Result<TxIndex, storage_api::Error> {
-        self.ctx.get_tx_index().into_storage_result()
+        self.ctx.get_tx_index().map_err(Into::into)
     }
 
     fn get_native_token(&self) -> Result<Address, storage_api::Error> {
@@ -395,7 +397,7 @@ where
             &mut self.sentinel.borrow_mut(),
         )
         .map(|data| data.and_then(|t| T::try_from_slice(&t[..]).ok()))
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn read_bytes_temp(
@@ -408,7 +410,7 @@ where
             key,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_chain_id(&self) -> Result<String, storage_api::Error> {
@@ -417,7 +419,7 @@ where
             self.storage,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_block_height(&self) -> Result<BlockHeight, storage_api::Error> {
@@ -426,7 +428,7 @@ where
             self.storage,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_block_header(
@@ -439,7 +441,7 @@ where
             height,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_block_hash(&self) -> Result<BlockHash, storage_api::Error> {
@@ -448,7 +450,7 @@ where
             self.storage,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_block_epoch(&self) -> Result<Epoch, storage_api::Error> {
@@ -457,7 +459,7 @@ where
             self.storage,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_tx_index(&self) -> Result<TxIndex, storage_api::Error> {
@@ -466,7 +468,7 @@ where
             self.tx_index,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_native_token(&self) -> Result<Address, storage_api::Error> {
@@ -475,7 +477,7 @@ where
             self.storage,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn get_ibc_events(
@@ -487,7 +489,7 @@ where
             self.write_log,
             event_type,
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn iter_prefix<'iter>(
@@ -501,7 +503,7 @@ where
             prefix,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
     }
 
     fn eval(
@@ -524,6 +526,7 @@ where
             let mut iterators: PrefixIterators<'_, DB> =
                 PrefixIterators::default();
             let mut result_buffer: Option<Vec<u8>> = None;
+            let mut debug_trace = None;
             let mut vp_wasm_cache = self.vp_wasm_cache.clone();
 
             let ctx = VpCtx::new(
@@ -537,6 +540,7 @@ where
                 &mut iterators,
                 self.verifiers,
                 &mut result_buffer,
+                &mut debug_trace,
                 self.keys_changed,
                 &eval_runner,
                 &mut vp_wasm_cache,
@@ -566,9 +570,10 @@ where
     }
 
     fn charge_gas(&self, used_gas: u64) -> Result<(), storage_api::Error> {
-        self.gas_meter.borrow_mut().consume(used_gas).map_err(|_| {
-            Error::SimpleMessage("Gas limit exceeded in native vp")
-        })
+        self.gas_meter
+            .borrow_mut()
+            .consume(used_gas)
+            .map_err(|_| Error::OutOfGas)
     }
 
     fn get_tx_code_hash(&self) -> Result<Option<Hash>, storage_api::Error> {
@@ -577,7 +582,20 @@ where
             self.tx,
             &mut self.sentinel.borrow_mut(),
         )
-        .into_storage_result()
+        .map_err(Into::into)
+    }
+
+    fn read_bytes_pre_block(
+        &self,
+        key: &Key,
+    ) -> Result<Option<Vec<u8>>, storage_api::Error> {
+        vp_host_fns::read_pre_block(
+            &mut self.gas_meter.borrow_mut(),
+            self.storage,
+            key,
+            &mut self.sentinel.borrow_mut(),
+        )
+        .map_err(Into::into)
     }
 
     fn read_pre<T: borsh::BorshDeserialize>(
@@ -5,14 +5,16 @@ use std::collections::{BTreeSet, HashMap};
 use thiserror::Error;
 
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::parameters::read_blocked_addresses;
 use crate::ledger::storage;
 use crate::ledger::vp_env::VpEnv;
 use crate::proto::Tx;
 use crate::types::address::{Address, InternalAddress};
 use crate::types::storage::{Key, KeySeg};
 use crate::types::token::{
-    is_any_minted_balance_key, is_any_minter_key, is_any_token_balance_key,
-    minter_key, Amount, Change,
+    is_any_allowance_key, is_any_minted_balance_key, is_any_minter_key,
+    is_any_sub_token_balance_key, is_any_sub_token_minted_balance_key,
+    is_any_token_balance_key, minter_key, Amount, Change,
 };
 use crate::vm::WasmCacheAccess;
 
@@ -51,32 +53,70 @@ where
         keys_changed: &BTreeSet<Key>,
         verifiers: &BTreeSet<Address>,
     ) -> Result<bool> {
+        // Reject the changes of the balances of the addresses blocked by
+        // governance, if the blocking is enabled for the chain
+        if let Some(blocked) = read_blocked_addresses(&self.ctx.pre())? {
+            if !blocked_balance_owners(keys_changed, &blocked).is_empty() {
+                return Ok(false);
+            }
+        }
+
+        // The changes and mints are tracked per token and sub-prefix, as
+        // the sub-tokens of a token are distinct tokens
         let mut changes = HashMap::new();
         let mut mints = HashMap::new();
         for key in keys_changed {
-            if let Some([token, _]) = is_any_token_balance_key(key) {
+            let balance_token = is_any_token_balance_key(key)
+                .map(|[token, _]| (token, None))
+                .or_else(|| {
+                    is_any_sub_token_balance_key(key)
+                        .map(|(token, sub_prefix, _)| (token, Some(sub_prefix)))
+                });
+            let minted_token = is_any_minted_balance_key(key)
+                .map(|token| (token, None))
+                .or_else(|| {
+                    is_any_sub_token_minted_balance_key(key)
+                        .map(|(token, sub_prefix)| (token, Some(sub_prefix)))
+                });
+            if let Some(token) = balance_token {
                 let pre: Amount = self.ctx.read_pre(key)?.unwrap_or_default();
                 let post: Amount = self.ctx.read_post(key)?.unwrap_or_default();
                 let diff = post.change() - pre.change();
-                match changes.get_mut(token) {
+                match changes.get_mut(&token) {
                     Some(change) => *change += diff,
                     None => _ = changes.insert(token, diff),
                 }
-            } else if let Some(token) = is_any_minted_balance_key(key) {
+            } else if let Some(token) = minted_token {
                 let pre: Amount = self.ctx.read_pre(key)?.unwrap_or_default();
                 let post: Amount = self.ctx.read_post(key)?.unwrap_or_default();
                 let diff = post.change() - pre.change();
-                match mints.get_mut(token) {
+                // Check if the minter is set. The sub-tokens are minted by
+                // the minter of their token.
+                if !self.is_valid_minter(token.0, verifiers)? {
+                    return Ok(false);
+                }
+
+                match mints.get_mut(&token) {
                     Some(mint) => *mint += diff,
                     None => _ = mints.insert(token, diff),
                 }
-
-                // Check if the minter is set
+            } else if let Some(token) = is_any_minter_key(key) {
                 if !self.is_valid_minter(token, verifiers)? {
                     return Ok(false);
                 }
-            } else if let Some(token) = is_any_minter_key(key) {
-                if !self.is_valid_minter(token, verifiers)? {
+            } else if let Some([_token, owner, spender]) =
+                is_any_allowance_key(key)
+            {
+                // The approvals and the spending of an allowance are
+                // authorized by the VPs of the owner and of the spender
+                if owner == spender
+                    || !verifiers.contains(owner)
+                    || !verifiers.contains(spender)
+                {
+                    return Ok(false);
+                }
+                // The allowance must be a valid amount
+                if self.ctx.read_post::<Amount>(key).is_err() {
                     return Ok(false);
                 }
             } else if key.segments.get(0)
@@ -100,6 +140,23 @@ where
     }
 }
 
+/// The blocked addresses whose token balances are changed by the given keys
+pub fn blocked_balance_owners<'a>(
+    keys_changed: impl IntoIterator<Item = &'a Key>,
+    blocked: &BTreeSet<Address>,
+) -> BTreeSet<Address> {
+    keys_changed
+        .into_iter()
+        .filter_map(|key| {
+            let owner = match is_any_token_balance_key(key) {
+                Some([_token, owner]) => owner,
+                None => is_any_sub_token_balance_key(key)?.2,
+            };
+            blocked.contains(owner).then(|| owner.clone())
+        })
+        .collect()
+}
+
 impl<'a, DB, H, CA> MultitokenVp<'a, DB, H, CA>
 where
     DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
@@ -150,12 +207,14 @@ mod tests {
     };
     use crate::ledger::gas::VpGasMeter;
     use crate::ledger::ibc::storage::ibc_token;
+    use crate::ledger::parameters::storage::get_blocked_addresses_key;
     use crate::proto::{Code, Data, Section, Signature, Tx};
     use crate::types::address::{Address, InternalAddress};
     use crate::types::key::testing::keypair_1;
     use crate::types::storage::TxIndex;
     use crate::types::token::{
-        balance_key, minted_balance_key, minter_key, Amount,
+        allowance_key, balance_key, minted_balance_key, minter_key,
+        sub_token_balance_key, Amount,
     };
     use crate::types::transaction::TxType;
     use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
@@ -233,6 +292,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_blocked_transfer() {
+        let mut wl_storage = TestWlStorage::default();
+        let mut keys_changed = BTreeSet::new();
+
+        let sender = established_address_1();
+        let sender_key = balance_key(&nam(), &sender);
+        let amount = Amount::native_whole(100);
+        wl_storage
+            .storage
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+        let receiver = established_address_2();
+        let blocked = BTreeSet::from([receiver.clone()]);
+        wl_storage
+            .storage
+            .write(&get_blocked_addresses_key(), blocked.serialize_to_vec())
+            .expect("write failed");
+
+        // transfer 10 to the blocked receiver
+        let amount = Amount::native_whole(90);
+        wl_storage
+            .write_log
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(sender_key);
+        let receiver_key = balance_key(&nam(), &receiver);
+        let amount = Amount::native_whole(10);
+        wl_storage
+            .write_log
+            .write(&receiver_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(receiver_key);
+        assert_eq!(blocked_balance_owners(&keys_changed, &blocked), blocked);
+
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(&wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let mut verifiers = BTreeSet::new();
+        verifiers.insert(sender);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
+        let vp = MultitokenVp { ctx };
+        assert!(!vp
+            .validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed"));
+    }
+
     #[test]
     fn test_invalid_transfer() {
         let mut wl_storage = TestWlStorage::default();
@@ -282,6 +402,121 @@ mod tests {
             vp_wasm_cache,
         );
 
+        let vp = MultitokenVp { ctx };
+        assert!(!vp
+            .validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed"));
+    }
+
+    #[test]
+    fn test_valid_sub_token_transfer() {
+        let mut wl_storage = TestWlStorage::default();
+        let mut keys_changed = BTreeSet::new();
+
+        let sub_prefix = Key::parse("transfer/channel-0/uatom").unwrap();
+        let sender = established_address_1();
+        let sender_key = sub_token_balance_key(&nam(), &sub_prefix, &sender);
+        let amount = Amount::native_whole(100);
+        wl_storage
+            .storage
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+
+        // transfer 10
+        let amount = Amount::native_whole(90);
+        wl_storage
+            .write_log
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(sender_key);
+        let receiver = established_address_2();
+        let receiver_key =
+            sub_token_balance_key(&nam(), &sub_prefix, &receiver);
+        let amount = Amount::native_whole(10);
+        wl_storage
+            .write_log
+            .write(&receiver_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(receiver_key);
+
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(&wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let mut verifiers = BTreeSet::new();
+        verifiers.insert(sender);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
+        let vp = MultitokenVp { ctx };
+        assert!(vp
+            .validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed"));
+    }
+
+    #[test]
+    fn test_sub_token_transfer_from_token() {
+        let mut wl_storage = TestWlStorage::default();
+        let mut keys_changed = BTreeSet::new();
+
+        let sub_prefix = Key::parse("transfer/channel-0/uatom").unwrap();
+        let sender = established_address_1();
+        let sender_key = balance_key(&nam(), &sender);
+        let amount = Amount::native_whole(100);
+        wl_storage
+            .storage
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+
+        // transfer 10
+        let amount = Amount::native_whole(90);
+        wl_storage
+            .write_log
+            .write(&sender_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(sender_key);
+        let receiver = established_address_2();
+        // the sub-token is a distinct token from its token
+        let receiver_key =
+            sub_token_balance_key(&nam(), &sub_prefix, &receiver);
+        let amount = Amount::native_whole(10);
+        wl_storage
+            .write_log
+            .write(&receiver_key, amount.serialize_to_vec())
+            .expect("write failed");
+        keys_changed.insert(receiver_key);
+
+        let tx_index = TxIndex::default();
+        let tx = dummy_tx(&wl_storage);
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let mut verifiers = BTreeSet::new();
+        verifiers.insert(sender);
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+
         let vp = MultitokenVp { ctx };
         assert!(
             !vp.validate_tx(&tx, &keys_changed, &verifiers)
@@ -610,4 +845,49 @@ mod tests {
                 .expect("validation failed")
         );
     }
+
+    #[test]
+    fn test_allowance() {
+        let owner = established_address_1();
+        let spender = established_address_2();
+        // an allowance given by an owner to itself is rejected
+        for (spender, expected) in [(&spender, true), (&owner, false)] {
+            let mut wl_storage = TestWlStorage::default();
+            let mut keys_changed = BTreeSet::new();
+
+            let key = allowance_key(&nam(), &owner, spender);
+            let amount = Amount::native_whole(10);
+            wl_storage
+                .write_log
+                .write(&key, amount.serialize_to_vec())
+                .expect("write failed");
+            keys_changed.insert(key);
+
+            let tx_index = TxIndex::default();
+            let tx = dummy_tx(&wl_storage);
+            let gas_meter = VpGasMeter::new_from_tx_meter(
+                &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+            );
+            let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+            let verifiers = BTreeSet::from([owner.clone(), spender.clone()]);
+            let ctx = Ctx::new(
+                &ADDRESS,
+                &wl_storage.storage,
+                &wl_storage.write_log,
+                &tx,
+                &tx_index,
+                gas_meter,
+                &keys_changed,
+                &verifiers,
+                vp_wasm_cache,
+            );
+
+            let vp = MultitokenVp { ctx };
+            assert_eq!(
+                vp.validate_tx(&tx, &keys_changed, &verifiers)
+                    .expect("validation failed"),
+                expected
+            );
+        }
+    }
 }
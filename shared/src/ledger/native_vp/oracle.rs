@@ -0,0 +1,241 @@
+//! Native VP for the oracle of externally attested data
+
+use std::collections::BTreeSet;
+
+use namada_core::ledger::oracle::{self, Observation};
+use namada_core::ledger::storage;
+use namada_core::proto::Tx;
+use namada_core::types::address::Address;
+use namada_core::types::storage::Key;
+use thiserror::Error;
+
+use crate::core::ledger::storage_api::{governance, StorageRead};
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Oracle functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Oracle VP
+pub struct OracleVp<'a, DB, H, CA>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: storage::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for OracleVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        tx_data: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let result = keys_changed.iter().all(|key| {
+            let result = match KeyType::from(key) {
+                // The whitelist can only be changed by governance
+                KeyType::Submitter => match tx_data.data() {
+                    Some(data) => {
+                        governance::is_proposal_accepted(&self.ctx.pre(), &data)
+                            .map_err(Error::from)
+                    }
+                    None => Ok(false),
+                },
+                KeyType::Submission(feed_id, submitter) => {
+                    self.is_valid_submission(key, feed_id, submitter, verifiers)
+                }
+                // The values are only updated at the end of a block
+                KeyType::Value | KeyType::UnknownOracle => Ok(false),
+                KeyType::Unknown => Ok(true),
+            };
+            result.unwrap_or(false)
+        });
+        Ok(result)
+    }
+}
+
+impl<'a, DB, H, CA> OracleVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that a value is submitted for a valid feed by a whitelisted
+    /// submitter who authorized it, at the current block height
+    fn is_valid_submission(
+        &self,
+        key: &Key,
+        feed_id: &str,
+        submitter: &Address,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        if !oracle::is_valid_feed_id(feed_id)
+            || !verifiers.contains(submitter)
+            || !oracle::is_submitter(&self.ctx.pre(), submitter)?
+        {
+            return Ok(false);
+        }
+        let height = self.ctx.post().get_block_height()?;
+        let observation: Option<Observation> = self.ctx.post().read(key)?;
+        Ok(observation
+            .map(|observation| observation.height == height)
+            .unwrap_or_default())
+    }
+}
+
+enum KeyType<'a> {
+    Submitter,
+    Submission(&'a str, &'a Address),
+    Value,
+    UnknownOracle,
+    Unknown,
+}
+
+impl<'a> From<&'a Key> for KeyType<'a> {
+    fn from(key: &'a Key) -> Self {
+        if oracle::is_submitter_key(key).is_some() {
+            Self::Submitter
+        } else if let Some((feed_id, submitter)) =
+            oracle::is_submission_key(key)
+        {
+            Self::Submission(feed_id, submitter)
+        } else if oracle::is_value_key(key).is_some() {
+            Self::Value
+        } else if oracle::is_oracle_key(key) {
+            Self::UnknownOracle
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::ledger::gas::TxGasMeter;
+    use namada_core::ledger::oracle::OracleSubmission;
+    use namada_core::ledger::storage_api::StorageWrite;
+
+    use super::*;
+    use crate::core::ledger::storage::testing::TestWlStorage;
+    use crate::core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::ledger::gas::VpGasMeter;
+    use crate::proto::{Code, Data};
+    use crate::types::dec::Dec;
+    use crate::types::storage::TxIndex;
+    use crate::types::transaction::TxType;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    const FEED_ID: &str = "nam-usd";
+
+    /// Whitelist the given submitter, as the state before the tx
+    fn init_storage(submitter: &Address) -> TestWlStorage {
+        let mut wl_storage = TestWlStorage::default();
+        oracle::add_submitter(&mut wl_storage, submitter).unwrap();
+        wl_storage.commit_tx();
+        wl_storage
+    }
+
+    /// Write the changes of the `tx_oracle_submit` tx
+    fn apply_submission(wl_storage: &mut TestWlStorage, submitter: &Address) {
+        let submission = OracleSubmission {
+            feed_id: FEED_ID.to_owned(),
+            submitter: submitter.clone(),
+            value: Dec::new(2, 0).unwrap(),
+        };
+        oracle::submit(wl_storage, &submission).unwrap();
+    }
+
+    /// Run the oracle VP on the changes of the current tx
+    fn run_vp(
+        wl_storage: &TestWlStorage,
+        verifiers: BTreeSet<Address>,
+    ) -> bool {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = wl_storage.storage.chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(vec![]));
+        let tx_index = TxIndex::default();
+        let keys_changed = wl_storage.write_log.get_keys();
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &oracle::ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+        let vp = OracleVp { ctx };
+        vp.validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("validation failed")
+    }
+
+    #[test]
+    fn test_valid_submission() {
+        let submitter = established_address_1();
+        let mut wl_storage = init_storage(&submitter);
+        apply_submission(&mut wl_storage, &submitter);
+        assert!(run_vp(&wl_storage, BTreeSet::from([submitter])));
+    }
+
+    /// Test that a submission from a submitter that isn't whitelisted is
+    /// rejected, even if signed by the submitter
+    #[test]
+    fn test_submission_not_whitelisted_rejected() {
+        let mut wl_storage = init_storage(&established_address_1());
+        let submitter = established_address_2();
+        apply_submission(&mut wl_storage, &submitter);
+        assert!(!run_vp(&wl_storage, BTreeSet::from([submitter])));
+    }
+
+    /// Test that a submission that isn't signed by the whitelisted submitter
+    /// is rejected
+    #[test]
+    fn test_submission_without_signature_rejected() {
+        let submitter = established_address_1();
+        let mut wl_storage = init_storage(&submitter);
+        apply_submission(&mut wl_storage, &submitter);
+        assert!(!run_vp(&wl_storage, BTreeSet::new()));
+    }
+
+    /// Test that a tx cannot write the value of a feed, even if signed by a
+    /// whitelisted submitter
+    #[test]
+    fn test_value_change_rejected() {
+        let submitter = established_address_1();
+        let mut wl_storage = init_storage(&submitter);
+        let observation = Observation {
+            value: Dec::new(2, 0).unwrap(),
+            height: wl_storage.storage.get_last_block_height(),
+        };
+        wl_storage
+            .write(&oracle::value_key(FEED_ID), observation)
+            .unwrap();
+        assert!(!run_vp(&wl_storage, BTreeSet::from([submitter])));
+    }
+}
@@ -0,0 +1,374 @@
+//! Native VP for the testnet faucet
+
+use std::collections::BTreeSet;
+
+use borsh::BorshDeserialize;
+use namada_core::ledger::storage;
+use namada_core::ledger::testnet_pow::{self, FaucetWithdrawal};
+use namada_core::proto::Tx;
+use namada_core::types::address::Address;
+use namada_core::types::storage::Key;
+use namada_core::types::token;
+use thiserror::Error;
+
+use crate::core::ledger::storage_api::{governance, StorageRead};
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Testnet faucet functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Testnet faucet VP
+pub struct FaucetVp<'a, DB, H, CA>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: storage::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for FaucetVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        tx_data: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        _verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let withdrawal = self.valid_withdrawal(tx_data)?;
+        let result = keys_changed.iter().all(|key| {
+            let result = match KeyType::from(key) {
+                KeyType::Balance(token) => {
+                    self.is_valid_balance_change(key, token, &withdrawal)
+                }
+                KeyType::WithdrawalRecord => Ok(withdrawal.is_some()),
+                // The settings can only be changed by governance
+                KeyType::Settings => match tx_data.data() {
+                    Some(data) => {
+                        governance::is_proposal_accepted(&self.ctx.pre(), &data)
+                            .map_err(Error::from)
+                    }
+                    None => Ok(false),
+                },
+                KeyType::UnknownFaucet => Ok(false),
+                KeyType::Unknown => Ok(true),
+            };
+            result.unwrap_or(false)
+        });
+        Ok(result)
+    }
+}
+
+impl<'a, DB, H, CA> FaucetVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Decode the withdrawal in the tx data, if it's valid against the
+    /// storage before and after the tx
+    fn valid_withdrawal(
+        &self,
+        tx_data: &Tx,
+    ) -> Result<Option<FaucetWithdrawal>> {
+        let withdrawal = match tx_data
+            .data()
+            .and_then(|data| FaucetWithdrawal::try_from_slice(&data[..]).ok())
+        {
+            Some(withdrawal) => withdrawal,
+            None => return Ok(None),
+        };
+        let is_valid = testnet_pow::is_withdrawal_valid(
+            &self.ctx.pre(),
+            &self.ctx.post(),
+            &withdrawal,
+        )?;
+        Ok(is_valid.then_some(withdrawal))
+    }
+
+    /// Check that a balance of the faucet is credited or debited by the
+    /// amount of a valid withdrawal of its token
+    fn is_valid_balance_change(
+        &self,
+        key: &Key,
+        token: &Address,
+        withdrawal: &Option<FaucetWithdrawal>,
+    ) -> Result<bool> {
+        let pre: token::Amount = self.ctx.pre().read(key)?.unwrap_or_default();
+        let post: token::Amount =
+            self.ctx.post().read(key)?.unwrap_or_default();
+        if post >= pre {
+            return Ok(true);
+        }
+        Ok(withdrawal.as_ref().map_or(false, |withdrawal| {
+            &withdrawal.token == token
+                && pre.checked_sub(post) == Some(withdrawal.amount.amount)
+        }))
+    }
+}
+
+enum KeyType<'a> {
+    Balance(&'a Address),
+    WithdrawalRecord,
+    Settings,
+    UnknownFaucet,
+    Unknown,
+}
+
+impl<'a> From<&'a Key> for KeyType<'a> {
+    fn from(key: &'a Key) -> Self {
+        if let Some([token, owner]) = token::is_any_token_balance_key(key) {
+            if owner == &testnet_pow::ADDRESS {
+                return Self::Balance(token);
+            }
+        }
+        if testnet_pow::is_withdrawal_record_key(key) {
+            Self::WithdrawalRecord
+        } else if testnet_pow::is_settings_key(key) {
+            Self::Settings
+        } else if testnet_pow::is_faucet_key(key) {
+            Self::UnknownFaucet
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh_ext::BorshSerializeExt;
+    use namada_core::ledger::gas::TxGasMeter;
+    use namada_core::ledger::governance::storage::keys::get_proposal_execution_key;
+    use namada_core::ledger::storage_api::token::{credit_tokens, transfer};
+    use namada_core::ledger::storage_api::StorageWrite;
+
+    use super::*;
+    use crate::core::ledger::storage::testing::TestWlStorage;
+    use crate::core::types::address::nam;
+    use crate::core::types::address::testing::established_address_1;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::proto::{Code, Data};
+    use crate::types::storage::{KeySeg, TxIndex};
+    use crate::types::transaction::TxType;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    /// Initialize the faucet with a PoW, a withdrawal limit of NAM and a
+    /// balance, as the state before the tx
+    fn init_storage() -> TestWlStorage {
+        let mut wl_storage = TestWlStorage::default();
+        testnet_pow::init_faucet_storage(
+            &mut wl_storage,
+            testnet_pow::Difficulty::try_new(4),
+        )
+        .unwrap();
+        testnet_pow::write_withdrawal_limits(
+            &mut wl_storage,
+            &nam(),
+            token::Amount::from_u64(100),
+            None,
+        )
+        .unwrap();
+        credit_tokens(
+            &mut wl_storage,
+            &nam(),
+            &testnet_pow::ADDRESS,
+            token::Amount::from_u64(1000),
+        )
+        .unwrap();
+        wl_storage.commit_tx();
+        wl_storage
+    }
+
+    fn withdrawal(wl_storage: &TestWlStorage, amount: u64) -> FaucetWithdrawal {
+        let target = established_address_1();
+        let solution = testnet_pow::Challenge::new(wl_storage, target.clone())
+            .unwrap()
+            .map(testnet_pow::Challenge::solve);
+        FaucetWithdrawal {
+            target,
+            token: nam(),
+            amount: token::DenominatedAmount {
+                amount: token::Amount::from_u64(amount),
+                denom: token::NATIVE_MAX_DECIMAL_PLACES.into(),
+            },
+            solution,
+        }
+    }
+
+    /// Write the changes of the `tx_faucet_withdraw` tx
+    fn apply_withdrawal(
+        wl_storage: &mut TestWlStorage,
+        withdrawal: &FaucetWithdrawal,
+    ) {
+        transfer(
+            wl_storage,
+            &withdrawal.token,
+            &testnet_pow::ADDRESS,
+            &withdrawal.target,
+            withdrawal.amount.amount,
+        )
+        .unwrap();
+        testnet_pow::record_withdrawal(wl_storage, withdrawal).unwrap();
+    }
+
+    fn tx_with_data(wl_storage: &TestWlStorage, data: Vec<u8>) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.header.chain_id = wl_storage.storage.chain_id.clone();
+        tx.set_code(Code::new(vec![], None));
+        tx.set_data(Data::new(data));
+        tx
+    }
+
+    /// Run the faucet VP on the changes of the current tx
+    fn run_vp(wl_storage: &TestWlStorage, tx: &Tx) -> bool {
+        let tx_index = TxIndex::default();
+        let keys_changed = wl_storage.write_log.get_keys();
+        let verifiers = BTreeSet::new();
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &testnet_pow::ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+        let vp = FaucetVp { ctx };
+        vp.validate_tx(tx, &keys_changed, &verifiers)
+            .expect("validation failed")
+    }
+
+    #[test]
+    fn test_valid_withdrawal() {
+        let mut wl_storage = init_storage();
+        let withdrawal = withdrawal(&wl_storage, 100);
+        apply_withdrawal(&mut wl_storage, &withdrawal);
+        let tx = tx_with_data(&wl_storage, withdrawal.serialize_to_vec());
+        assert!(run_vp(&wl_storage, &tx));
+    }
+
+    #[test]
+    fn test_invalid_withdrawal() {
+        // A withdrawal over the limit is rejected
+        let mut wl_storage = init_storage();
+        let over_limit = withdrawal(&wl_storage, 101);
+        apply_withdrawal(&mut wl_storage, &over_limit);
+        let tx = tx_with_data(&wl_storage, over_limit.serialize_to_vec());
+        assert!(!run_vp(&wl_storage, &tx));
+
+        // A withdrawal that isn't recorded is rejected
+        let mut wl_storage = init_storage();
+        let withdrawal = withdrawal(&wl_storage, 100);
+        transfer(
+            &mut wl_storage,
+            &nam(),
+            &testnet_pow::ADDRESS,
+            &withdrawal.target,
+            withdrawal.amount.amount,
+        )
+        .unwrap();
+        let tx = tx_with_data(&wl_storage, withdrawal.serialize_to_vec());
+        assert!(!run_vp(&wl_storage, &tx));
+
+        // A debit of more than the withdrawn amount is rejected
+        let mut wl_storage = init_storage();
+        apply_withdrawal(&mut wl_storage, &withdrawal);
+        transfer(
+            &mut wl_storage,
+            &nam(),
+            &testnet_pow::ADDRESS,
+            &withdrawal.target,
+            token::Amount::from_u64(1),
+        )
+        .unwrap();
+        let tx = tx_with_data(&wl_storage, withdrawal.serialize_to_vec());
+        assert!(!run_vp(&wl_storage, &tx));
+
+        // A debit without a withdrawal is rejected
+        let mut wl_storage = init_storage();
+        transfer(
+            &mut wl_storage,
+            &nam(),
+            &testnet_pow::ADDRESS,
+            &withdrawal.target,
+            withdrawal.amount.amount,
+        )
+        .unwrap();
+        let tx = tx_with_data(&wl_storage, vec![]);
+        assert!(!run_vp(&wl_storage, &tx));
+    }
+
+    #[test]
+    fn test_credit() {
+        let mut wl_storage = init_storage();
+        credit_tokens(
+            &mut wl_storage,
+            &nam(),
+            &testnet_pow::ADDRESS,
+            token::Amount::from_u64(100),
+        )
+        .unwrap();
+        let tx = tx_with_data(&wl_storage, vec![]);
+        assert!(run_vp(&wl_storage, &tx));
+    }
+
+    #[test]
+    fn test_settings_change() {
+        let mut wl_storage = init_storage();
+        let proposal_id = 0_u64;
+        wl_storage
+            .write(&get_proposal_execution_key(proposal_id), ())
+            .unwrap();
+        wl_storage.commit_tx();
+        testnet_pow::write_withdrawal_limits(
+            &mut wl_storage,
+            &nam(),
+            token::Amount::from_u64(1000),
+            None,
+        )
+        .unwrap();
+
+        // A change of the settings without a governance proposal is rejected
+        let tx = tx_with_data(&wl_storage, vec![]);
+        assert!(!run_vp(&wl_storage, &tx));
+        let tx =
+            tx_with_data(&wl_storage, (proposal_id + 1).serialize_to_vec());
+        assert!(!run_vp(&wl_storage, &tx));
+
+        // A change of the settings by an accepted proposal is accepted
+        let tx = tx_with_data(&wl_storage, proposal_id.serialize_to_vec());
+        assert!(run_vp(&wl_storage, &tx));
+    }
+
+    #[test]
+    fn test_unknown_faucet_key() {
+        let mut wl_storage = init_storage();
+        let key = Key::from(testnet_pow::ADDRESS.to_db_key())
+            .push(&"unknown".to_owned())
+            .unwrap();
+        wl_storage.write(&key, 1_u64).unwrap();
+        let tx = tx_with_data(&wl_storage, vec![]);
+        assert!(!run_vp(&wl_storage, &tx));
+    }
+}
@@ -1,10 +1,14 @@
 //! The ledger's protocol
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use borsh_ext::BorshSerializeExt;
 use eyre::{eyre, WrapErr};
 use masp_primitives::transaction::Transaction;
-use namada_core::ledger::gas::TxGasMeter;
+use namada_core::ledger::gas::{TxGasMeter, STORAGE_ACCESS_GAS_PER_BYTE};
+use namada_core::ledger::parameters::{
+    read_storage_deposit, read_storage_deposit_per_byte,
+    storage as params_storage,
+};
 use namada_core::ledger::storage::wl_storage::WriteLogAndStorage;
 use namada_core::ledger::storage_api::StorageRead;
 use namada_core::proto::Section;
@@ -24,11 +28,13 @@ use crate::ledger::native_vp::ethereum_bridge::vp::EthBridge;
 use crate::ledger::native_vp::ibc::Ibc;
 use crate::ledger::native_vp::masp::MaspVp;
 use crate::ledger::native_vp::multitoken::MultitokenVp;
+use crate::ledger::native_vp::oracle::OracleVp;
 use crate::ledger::native_vp::parameters::{self, ParametersVp};
+use crate::ledger::native_vp::testnet_faucet::FaucetVp;
 use crate::ledger::native_vp::{self, NativeVp};
 use crate::ledger::pgf::PgfVp;
 use crate::ledger::pos::{self, PosVP};
-use crate::ledger::storage::write_log::WriteLog;
+use crate::ledger::storage::write_log::{StorageModification, WriteLog};
 use crate::ledger::storage::{DBIter, Storage, StorageHasher, WlStorage, DB};
 use crate::ledger::storage_api;
 use crate::proto::{self, Tx};
@@ -93,8 +99,14 @@ pub enum Error {
     NutNativeVpError(native_vp::ethereum_bridge::nut::Error),
     #[error("MASP native VP error: {0}")]
     MaspNativeVpError(native_vp::masp::Error),
+    #[error("Oracle native VP error: {0}")]
+    OracleNativeVpError(native_vp::oracle::Error),
+    #[error("Testnet faucet native VP error: {0}")]
+    FaucetNativeVpError(native_vp::testnet_faucet::Error),
     #[error("Access to an internal address {0:?} is forbidden")]
     AccessForbidden(InternalAddress),
+    #[error("Storage deposit error: {0}")]
+    StorageDepositError(String),
 }
 
 /// Shell parameters for running wasm transactions.
@@ -108,6 +120,8 @@ where
     wl_storage: &'a mut WLS,
     vp_wasm_cache: &'a mut VpCache<CA>,
     tx_wasm_cache: &'a mut TxCache<CA>,
+    debug_trace: bool,
+    storage_deposit_payer: Option<&'a Address>,
 }
 
 impl<'a, CA, WLS> ShellParams<'a, CA, WLS>
@@ -127,8 +141,25 @@ where
             wl_storage,
             vp_wasm_cache,
             tx_wasm_cache,
+            debug_trace: false,
+            storage_deposit_payer: None,
         }
     }
+
+    /// Collect the debug messages logged by the tx and VPs, as in dry-run.
+    /// The trace must not be collected when applying txs in a block.
+    pub fn with_debug_trace(mut self) -> Self {
+        self.debug_trace = true;
+        self
+    }
+
+    /// Charge the storage deposits of the tx to the given payer, the fee
+    /// payer of its wrapper. Without a payer, e.g. for the code of the
+    /// governance proposals, the tx is exempt from the deposits.
+    pub fn with_storage_deposit_payer(mut self, payer: &'a Address) -> Self {
+        self.storage_deposit_payer = Some(payer);
+        self
+    }
 }
 
 /// Result of applying a transaction
@@ -151,6 +182,7 @@ pub fn dispatch_tx<'a, D, H, CA>(
     vp_wasm_cache: &'a mut VpCache<CA>,
     tx_wasm_cache: &'a mut TxCache<CA>,
     block_proposer: Option<&'a Address>,
+    storage_deposit_payer: Option<&'a Address>,
 ) -> Result<TxResult>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
@@ -167,6 +199,8 @@ where
                 wl_storage,
                 vp_wasm_cache,
                 tx_wasm_cache,
+                debug_trace: false,
+                storage_deposit_payer,
             },
         ),
         TxType::Protocol(protocol_tx) => {
@@ -185,6 +219,8 @@ where
                     wl_storage,
                     vp_wasm_cache,
                     tx_wasm_cache,
+                    debug_trace: false,
+                    storage_deposit_payer: None,
                 },
                 block_proposer,
             )?;
@@ -194,6 +230,7 @@ where
                 vps_result: VpsResult::default(),
                 initialized_accounts: vec![],
                 ibc_events: BTreeSet::default(),
+                debug_trace: vec![],
             })
         }
         TxType::Decrypted(DecryptedTx::Undecryptable) => {
@@ -221,6 +258,7 @@ where
 /// Performs the required operation on a wrapper transaction:
 ///  - replay protection
 ///  - fee payment
+///  - sequence number increment
 ///  - gas accounting
 ///
 /// Returns the set of changed storage keys.
@@ -255,6 +293,18 @@ where
         &mut changed_keys,
     )?;
 
+    // Increment the sequence number of the fee payer, if the wrapper carries
+    // one. It has already been checked against the stored one in the proposal
+    if wrapper.sequence.is_some() {
+        let fee_payer = wrapper.fee_payer();
+        storage_api::account::increment_sequence(
+            shell_params.wl_storage,
+            &fee_payer,
+        )
+        .expect("Error while writing the sequence number to storage");
+        changed_keys.insert(namada_core::types::key::sequence_key(&fee_payer));
+    }
+
     // Account for gas
     shell_params
         .tx_gas_meter
@@ -306,6 +356,8 @@ where
         wl_storage,
         vp_wasm_cache,
         tx_wasm_cache,
+        debug_trace: _,
+        storage_deposit_payer: _,
     } = shell_params;
 
     // Unshield funds if requested
@@ -344,6 +396,8 @@ where
                         wl_storage: *wl_storage,
                         vp_wasm_cache,
                         tx_wasm_cache,
+                        debug_trace: false,
+                        storage_deposit_payer: None,
                     },
                 ) {
                     Ok(result) => {
@@ -557,6 +611,8 @@ where
         wl_storage,
         vp_wasm_cache,
         tx_wasm_cache,
+        debug_trace,
+        storage_deposit_payer,
     } = shell_params;
 
     let (tx_gas_meter, storage, write_log, vp_wasm_cache, tx_wasm_cache) = {
@@ -577,6 +633,7 @@ where
         return Err(Error::ReplayAttempt(tx_hash));
     }
 
+    let mut tx_debug_trace = debug_trace.then(Vec::new);
     let verifiers = execute_tx(
         &tx,
         tx_index,
@@ -585,8 +642,22 @@ where
         write_log,
         vp_wasm_cache,
         tx_wasm_cache,
+        &mut tx_debug_trace,
     )?;
 
+    // The deposits are charged before the VPs run, so that the VP of the
+    // payer validates its debit
+    let deposit_keys = match storage_deposit_payer {
+        Some(payer) => {
+            let tx_keys = wl_storage.write_log().get_keys();
+            let deposit_keys =
+                apply_storage_deposits(wl_storage, payer, tx_gas_meter)?;
+            deposit_keys.difference(&tx_keys).cloned().collect()
+        }
+        None => BTreeSet::new(),
+    };
+
+    let (write_log, storage) = wl_storage.split_borrow();
     let vps_result = check_vps(CheckVps {
         tx: &tx,
         tx_index,
@@ -594,10 +665,13 @@ where
         tx_gas_meter,
         write_log,
         verifiers_from_tx: &verifiers,
+        deposit_keys: &deposit_keys,
         vp_wasm_cache,
+        debug_trace,
     })?;
 
     let gas_used = tx_gas_meter.get_tx_consumed_gas();
+    let write_log = wl_storage.write_log_mut();
     let initialized_accounts = write_log.get_initialized_accounts();
     let changed_keys = write_log.get_keys();
     let ibc_events = write_log.take_ibc_events();
@@ -608,9 +682,171 @@ where
         vps_result,
         initialized_accounts,
         ibc_events,
+        debug_trace: tx_debug_trace.unwrap_or_default(),
     })
 }
 
+/// Lock a native token deposit proportional to the bytes of state that the
+/// executed tx adds to the subspace of every account, paid by the `payer`,
+/// and refund the deposit of the bytes that it removes to the account. A tx
+/// fails if the payer cannot afford the deposits. The subspaces of the
+/// internal addresses are protocol state, exempt from the deposits. The
+/// deposits are held by the [`InternalAddress::StorageDeposit`] escrow.
+/// The reads of the state sizes, deposits and balances are charged to the tx
+/// gas meter. Returns the keys written for the deposits, which must be
+/// validated by the VPs of the payer and of the refunded accounts, but not
+/// by the VPs of the escrow and of the parameters.
+pub fn apply_storage_deposits<WLS>(
+    wl_storage: &mut WLS,
+    payer: &Address,
+    tx_gas_meter: &mut TxGasMeter,
+) -> Result<BTreeSet<Key>>
+where
+    WLS: WriteLogAndStorage + StorageRead,
+{
+    let deposit_per_byte = read_storage_deposit_per_byte(wl_storage)
+        .map_err(|e| Error::StorageDepositError(e.to_string()))?;
+    if deposit_per_byte.is_zero() {
+        return Ok(BTreeSet::new());
+    }
+
+    // The size difference of the state of every account changed by the tx
+    let mut size_diffs: BTreeMap<Address, i64> = BTreeMap::new();
+    for key in wl_storage.write_log().get_keys() {
+        let owner = match key.segments.first() {
+            Some(storage::DbKeySeg::AddressSeg(
+                owner @ (Address::Established(_) | Address::Implicit(_)),
+            )) => owner.clone(),
+            _ => continue,
+        };
+        let (post, post_gas) = wl_storage.write_log().read(&key);
+        let post_len = match post {
+            Some(StorageModification::Write { value }) => value.len(),
+            Some(StorageModification::InitAccount { vp_code_hash }) => {
+                vp_code_hash.len()
+            }
+            Some(StorageModification::Delete)
+            | Some(StorageModification::Temp { .. })
+            | None => 0,
+        };
+        let (pre, mut pre_gas) = wl_storage.write_log().read_pre(&key);
+        let pre_len = match pre {
+            Some(StorageModification::Write { value }) => value.len(),
+            Some(StorageModification::InitAccount { vp_code_hash }) => {
+                vp_code_hash.len()
+            }
+            Some(StorageModification::Delete)
+            | Some(StorageModification::Temp { .. }) => 0,
+            None => {
+                let (value, gas) = wl_storage
+                    .storage()
+                    .read(&key)
+                    .map_err(Error::StorageError)?;
+                pre_gas += gas;
+                value.map(|value| value.len()).unwrap_or_default()
+            }
+        };
+        tx_gas_meter
+            .consume(post_gas + pre_gas)
+            .map_err(|e| Error::GasError(e.to_string()))?;
+        *size_diffs.entry(owner).or_default() +=
+            post_len as i64 - pre_len as i64;
+    }
+
+    let native_token = wl_storage.storage().native_token.clone();
+    let escrow = Address::Internal(InternalAddress::StorageDeposit);
+    // The gas of the read of an amount at the given key
+    let amount_len = Amount::zero().serialize_to_vec().len() as u64;
+    let read_amount_gas = |key: &Key| {
+        (key.len() as u64 + amount_len) * STORAGE_ACCESS_GAS_PER_BYTE
+    };
+    let escrow_balance_key =
+        namada_core::types::token::balance_key(&native_token, &escrow);
+    let payer_balance_key =
+        namada_core::types::token::balance_key(&native_token, payer);
+    let mut deposit_keys = BTreeSet::new();
+    for (owner, size_diff) in size_diffs {
+        let deposit_key = params_storage::get_storage_deposit_key(&owner);
+        let balance_key =
+            namada_core::types::token::balance_key(&native_token, &owner);
+        tx_gas_meter
+            .consume(read_amount_gas(&deposit_key))
+            .map_err(|e| Error::GasError(e.to_string()))?;
+        let deposit = read_storage_deposit(wl_storage, &owner)
+            .map_err(|e| Error::StorageDepositError(e.to_string()))?;
+        let change = deposit_per_byte
+            .checked_mul(Amount::from(size_diff.unsigned_abs()))
+            .ok_or_else(|| {
+                Error::StorageDepositError(
+                    "The storage deposit overflows".to_string(),
+                )
+            })?;
+        let new_deposit = if size_diff > 0 {
+            deposit.checked_add(change).ok_or_else(|| {
+                Error::StorageDepositError(
+                    "The storage deposit overflows".to_string(),
+                )
+            })?
+        } else {
+            // The deposit may be short of the refund for the state added
+            // before the deposits were enabled or at a lower price
+            deposit - std::cmp::min(change, deposit)
+        };
+        if new_deposit == deposit {
+            continue;
+        }
+
+        // The transfer reads the balances of the payer or of the refunded
+        // owner and of the escrow
+        if size_diff > 0 {
+            tx_gas_meter
+                .consume(
+                    read_amount_gas(&payer_balance_key)
+                        + read_amount_gas(&escrow_balance_key),
+                )
+                .map_err(|e| Error::GasError(e.to_string()))?;
+            token_transfer(wl_storage, &native_token, payer, &escrow, change)
+                .map_err(|e| {
+                Error::StorageDepositError(format!(
+                    "Failed to lock the deposit of {} for {size_diff} \
+                         bytes of state of {owner} from {payer}: {e}",
+                    change.to_string_native()
+                ))
+            })?;
+            deposit_keys.insert(payer_balance_key.clone());
+        } else {
+            tx_gas_meter
+                .consume(
+                    read_amount_gas(&balance_key)
+                        + read_amount_gas(&escrow_balance_key),
+                )
+                .map_err(|e| Error::GasError(e.to_string()))?;
+            token_transfer(
+                wl_storage,
+                &native_token,
+                &escrow,
+                &owner,
+                deposit - new_deposit,
+            )
+            .map_err(|e| Error::StorageDepositError(e.to_string()))?;
+            deposit_keys.insert(balance_key);
+        }
+        deposit_keys.insert(escrow_balance_key.clone());
+        let write_log = wl_storage.write_log_mut();
+        if new_deposit.is_zero() {
+            write_log
+                .delete(&deposit_key)
+                .map_err(|e| Error::StorageDepositError(e.to_string()))?;
+        } else {
+            write_log
+                .write(&deposit_key, new_deposit.serialize_to_vec())
+                .map_err(|e| Error::StorageDepositError(e.to_string()))?;
+        }
+        deposit_keys.insert(deposit_key);
+    }
+    Ok(deposit_keys)
+}
+
 /// Apply a derived transaction to storage based on some protocol transaction.
 /// The logic here must be completely deterministic and will be executed by all
 /// full nodes every time a protocol transaction is included in a block. Storage
@@ -697,6 +933,7 @@ fn execute_tx<D, H, CA>(
     write_log: &mut WriteLog,
     vp_wasm_cache: &mut VpCache<CA>,
     tx_wasm_cache: &mut TxCache<CA>,
+    debug_trace: &mut Option<Vec<String>>,
 ) -> Result<BTreeSet<Address>>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
@@ -711,6 +948,7 @@ where
         tx,
         vp_wasm_cache,
         tx_wasm_cache,
+        debug_trace,
     )
     .map_err(|err| match err {
         wasm::run::Error::GasError(msg) => Error::GasError(msg),
@@ -732,7 +970,11 @@ where
     tx_gas_meter: &'a mut TxGasMeter,
     write_log: &'a WriteLog,
     verifiers_from_tx: &'a BTreeSet<Address>,
+    /// The keys written only for the storage deposits of the tx
+    deposit_keys: &'a BTreeSet<Key>,
     vp_wasm_cache: &'a mut VpCache<CA>,
+    /// Whether to collect the debug messages logged by the VPs
+    debug_trace: bool,
 }
 
 /// Check the acceptance of a transaction by validity predicates
@@ -744,7 +986,9 @@ fn check_vps<D, H, CA>(
         tx_gas_meter,
         write_log,
         verifiers_from_tx,
+        deposit_keys,
         vp_wasm_cache,
+        debug_trace,
     }: CheckVps<'_, D, H, CA>,
 ) -> Result<VpsResult>
 where
@@ -752,8 +996,14 @@ where
     H: 'static + StorageHasher + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
-    let (verifiers, keys_changed) =
+    let (mut verifiers, keys_changed) =
         write_log.verifiers_and_changed_keys(verifiers_from_tx);
+    remove_deposit_verifiers(
+        &mut verifiers,
+        &keys_changed,
+        verifiers_from_tx,
+        deposit_keys,
+    );
 
     let vps_result = execute_vps(
         verifiers,
@@ -764,6 +1014,7 @@ where
         write_log,
         tx_gas_meter,
         vp_wasm_cache,
+        debug_trace,
     )?;
     tracing::debug!("Total VPs gas cost {:?}", vps_result.gas_used);
 
@@ -774,6 +1025,29 @@ where
     Ok(vps_result)
 }
 
+/// Remove the escrow of the storage deposits and the parameters from the
+/// verifiers if they're only changed by the protocol for the storage deposits,
+/// and not by the tx or requested as verifiers by it
+fn remove_deposit_verifiers(
+    verifiers: &mut BTreeSet<Address>,
+    keys_changed: &BTreeSet<Key>,
+    verifiers_from_tx: &BTreeSet<Address>,
+    deposit_keys: &BTreeSet<Key>,
+) {
+    for addr in [
+        Address::Internal(InternalAddress::StorageDeposit),
+        Address::Internal(InternalAddress::Parameters),
+    ] {
+        let changed_by_tx = keys_changed
+            .iter()
+            .filter(|key| !deposit_keys.contains(key))
+            .any(|key| key.iter_addresses().any(|key_addr| key_addr == &addr));
+        if !changed_by_tx && !verifiers_from_tx.contains(&addr) {
+            verifiers.remove(&addr);
+        }
+    }
+}
+
 /// Execute verifiers' validity predicates
 #[allow(clippy::too_many_arguments)]
 fn execute_vps<D, H, CA>(
@@ -785,15 +1059,22 @@ fn execute_vps<D, H, CA>(
     write_log: &WriteLog,
     tx_gas_meter: &TxGasMeter,
     vp_wasm_cache: &mut VpCache<CA>,
+    debug_trace: bool,
 ) -> Result<VpsResult>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
+    // The VPs run on other threads, so their spans must be explicitly
+    // attached to the span of the tx
+    let tx_span = tracing::Span::current();
     let vps_result = verifiers
         .par_iter()
         .try_fold(VpsResult::default, |mut result, addr| {
+            let _vp_span =
+                tracing::debug_span!(parent: &tx_span, "vp", address = %addr)
+                    .entered();
             let mut gas_meter = VpGasMeter::new_from_tx_meter(tx_gas_meter);
             let accept = match &addr {
                 Address::Implicit(_) | Address::Established(_) => {
@@ -812,7 +1093,8 @@ where
                     // env functions,    the first
                     // signature verification (if any) is accounted
                     // twice
-                    wasm::run::vp(
+                    let mut vp_debug_trace = debug_trace.then(Vec::new);
+                    let accept = wasm::run::vp(
                         vp_code_hash,
                         tx,
                         tx_index,
@@ -823,6 +1105,7 @@ where
                         &keys_changed,
                         &verifiers,
                         vp_wasm_cache.clone(),
+                        &mut vp_debug_trace,
                     )
                     .map_err(|err| match err {
                         wasm::run::Error::GasError(msg) => Error::GasError(msg),
@@ -830,7 +1113,14 @@ where
                             Error::InvalidTxSignature
                         }
                         _ => Error::VpRunnerError(err),
-                    })
+                    });
+                    result.debug_trace.extend(
+                        vp_debug_trace
+                            .into_iter()
+                            .flatten()
+                            .map(|msg| (addr.clone(), msg)),
+                    );
+                    accept
                 }
                 Address::Internal(internal_addr) => {
                     let ctx = native_vp::Ctx::new(
@@ -894,7 +1184,8 @@ where
                                     parameters.ctx.gas_meter.into_inner();
                                 (result, parameters.ctx.sentinel.into_inner())
                             }
-                            InternalAddress::PosSlashPool => {
+                            InternalAddress::PosSlashPool
+                            | InternalAddress::StorageDeposit => {
                                 // Take the gas meter and the sentinel
                                 // back
                                 // out of the context
@@ -1006,6 +1297,26 @@ where
                                 gas_meter = masp.ctx.gas_meter.into_inner();
                                 (result, masp.ctx.sentinel.into_inner())
                             }
+                            InternalAddress::Oracle => {
+                                let oracle = OracleVp { ctx };
+                                let result = oracle
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::OracleNativeVpError);
+                                // Take the gas meter and the sentinel back out
+                                // of the context
+                                gas_meter = oracle.ctx.gas_meter.into_inner();
+                                (result, oracle.ctx.sentinel.into_inner())
+                            }
+                            InternalAddress::TestnetFaucet => {
+                                let faucet = FaucetVp { ctx };
+                                let result = faucet
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::FaucetNativeVpError);
+                                // Take the gas meter and the sentinel back out
+                                // of the context
+                                gas_meter = faucet.ctx.gas_meter.into_inner();
+                                (result, faucet.ctx.sentinel.into_inner())
+                            }
                         };
 
                     accepted.map_err(|err| {
@@ -1051,6 +1362,9 @@ where
                 },
             }
 
+            result
+                .vps_gas
+                .insert(addr.clone(), gas_meter.get_vp_consumed_gas());
             result
                 .gas_used
                 .set(gas_meter)
@@ -1077,7 +1391,16 @@ fn merge_vp_results(
     rejected_vps.extend(b.rejected_vps);
     let mut errors = a.errors;
     errors.append(&mut b.errors);
+    // The order in which the parallel runs are merged is not deterministic,
+    // sort the errors so that all the nodes report the same result
+    errors.sort();
+    // Keep the messages of each VP in the order they were logged
+    let mut debug_trace = a.debug_trace;
+    debug_trace.append(&mut b.debug_trace);
+    debug_trace.sort_by(|(addr_a, _), (addr_b, _)| addr_a.cmp(addr_b));
     let invalid_sig = a.invalid_sig || b.invalid_sig;
+    let mut vps_gas = a.vps_gas;
+    vps_gas.extend(b.vps_gas);
     let mut gas_used = a.gas_used;
 
     gas_used
@@ -1088,17 +1411,20 @@ fn merge_vp_results(
         accepted_vps,
         rejected_vps,
         gas_used,
+        vps_gas,
         errors,
         invalid_sig,
+        debug_trace,
     })
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
 
     use borsh::BorshDeserialize;
     use eyre::Result;
+    use namada_core::ledger::parameters::update_storage_deposit_per_byte_parameter;
     use namada_core::ledger::storage_api::StorageRead;
     use namada_core::proto::{SignableEthMessage, Signed};
     use namada_core::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
@@ -1121,6 +1447,8 @@ mod tests {
     use namada_ethereum_bridge::{bridge_pool_vp, test_utils};
 
     use super::*;
+    use crate::core::ledger::storage::testing::TestWlStorage;
+    use crate::types::storage::KeySeg;
 
     fn apply_eth_tx<D, H>(
         tx: EthereumTxData,
@@ -1252,4 +1580,209 @@ mod tests {
 
         Ok(())
     }
+
+    /// Test that merging the results of parallel VP runs doesn't depend on
+    /// the order in which they are merged
+    #[test]
+    fn test_merge_vp_results_is_deterministic() {
+        let tx_gas_meter = TxGasMeter::new_from_sub_limit(u64::MAX.into());
+        let addr_1 = address::testing::established_address_1();
+        let addr_2 = address::testing::established_address_2();
+        let addr_3 = address::testing::established_address_3();
+        let result = |accepted: &Address, rejected: &Address| VpsResult {
+            accepted_vps: BTreeSet::from([accepted.clone()]),
+            rejected_vps: BTreeSet::from([rejected.clone()]),
+            vps_gas: BTreeMap::from([
+                (accepted.clone(), 10.into()),
+                (rejected.clone(), 20.into()),
+            ]),
+            errors: vec![(rejected.clone(), format!("error {rejected}"))],
+            debug_trace: vec![
+                (rejected.clone(), "first".to_string()),
+                (rejected.clone(), "second".to_string()),
+            ],
+            ..Default::default()
+        };
+        let a = result(&addr_1, &addr_2);
+        let b = result(&addr_1, &addr_3);
+
+        let ab = merge_vp_results(a.clone(), b.clone(), &tx_gas_meter)
+            .expect("Test failed");
+        let ba = merge_vp_results(b, a, &tx_gas_meter).expect("Test failed");
+        assert_eq!(ab.accepted_vps, ba.accepted_vps);
+        assert_eq!(ab.rejected_vps, ba.rejected_vps);
+        assert_eq!(ab.errors, ba.errors);
+        assert_eq!(ab.errors.len(), 2);
+        assert_eq!(ab.debug_trace, ba.debug_trace);
+        assert_eq!(ab.vps_gas, ba.vps_gas);
+        assert_eq!(ab.vps_gas.len(), 3);
+    }
+
+    /// Test that a tx that grows the state of an account locks a deposit from
+    /// the balance of the payer, which is refunded to the account when the
+    /// state is deleted, and that the tx fails if the balance can't cover the
+    /// deposit
+    #[test]
+    fn test_storage_deposits() {
+        let mut wl_storage = TestWlStorage::default();
+        let native_token = wl_storage.storage.native_token.clone();
+        let owner = address::testing::established_address_1();
+        update_storage_deposit_per_byte_parameter(
+            &mut wl_storage,
+            &Amount::from(2),
+        )
+        .expect("Test failed");
+        storage_api::token::credit_tokens(
+            &mut wl_storage,
+            &native_token,
+            &owner,
+            Amount::from(100),
+        )
+        .expect("Test failed");
+        let read_deposit = |wl_storage: &TestWlStorage| {
+            read_storage_deposit(wl_storage, &owner).expect("Test failed")
+        };
+        let read_balance = |wl_storage: &TestWlStorage, owner: &Address| {
+            storage_api::token::read_balance(wl_storage, &native_token, owner)
+                .expect("Test failed")
+        };
+        let escrow = Address::Internal(InternalAddress::StorageDeposit);
+        let mut gas_meter = TxGasMeter::new_from_sub_limit(u64::MAX.into());
+
+        // Adding 10 bytes of state locks a deposit of 20
+        let key = Key::from(owner.to_db_key())
+            .push(&"data".to_string())
+            .expect("Test failed");
+        wl_storage
+            .write_log
+            .write(&key, vec![0; 10])
+            .expect("Test failed");
+        apply_storage_deposits(&mut wl_storage, &owner, &mut gas_meter)
+            .expect("Test failed");
+        assert_eq!(read_deposit(&wl_storage), Amount::from(20));
+        assert_eq!(read_balance(&wl_storage, &owner), Amount::from(80));
+        assert_eq!(read_balance(&wl_storage, &escrow), Amount::from(20));
+        assert!(gas_meter.get_tx_consumed_gas() > 0.into());
+        wl_storage.commit_tx();
+
+        // Adding 50 more bytes of state needs a deposit above the balance
+        wl_storage
+            .write_log
+            .write(&key, vec![0; 60])
+            .expect("Test failed");
+        assert!(matches!(
+            apply_storage_deposits(&mut wl_storage, &owner, &mut gas_meter),
+            Err(Error::StorageDepositError(_))
+        ));
+        wl_storage.drop_tx();
+
+        // Deleting the state refunds the deposit
+        wl_storage.write_log.delete(&key).expect("Test failed");
+        apply_storage_deposits(&mut wl_storage, &owner, &mut gas_meter)
+            .expect("Test failed");
+        assert_eq!(read_deposit(&wl_storage), Amount::zero());
+        assert_eq!(read_balance(&wl_storage, &owner), Amount::from(100));
+        assert_eq!(read_balance(&wl_storage, &escrow), Amount::zero());
+    }
+
+    /// Test that the deposit of the state of an initialized account is paid
+    /// by the payer, and that only the VPs of the payer and of the token, but
+    /// not the escrow and the parameters, validate the deposit
+    #[test]
+    fn test_storage_deposit_of_init_account() {
+        let mut wl_storage = TestWlStorage::default();
+        let native_token = wl_storage.storage.native_token.clone();
+        let payer = address::testing::established_address_1();
+        update_storage_deposit_per_byte_parameter(
+            &mut wl_storage,
+            &Amount::from(2),
+        )
+        .expect("Test failed");
+        storage_api::token::credit_tokens(
+            &mut wl_storage,
+            &native_token,
+            &payer,
+            Amount::from(100),
+        )
+        .expect("Test failed");
+        let mut gas_meter = TxGasMeter::new_from_sub_limit(u64::MAX.into());
+
+        // The state of the new account is its VP code hash, of 32 bytes
+        let (account, _) = wl_storage
+            .write_log
+            .init_account(&[0], Hash::sha256(b"vp_user"));
+        let deposit_keys =
+            apply_storage_deposits(&mut wl_storage, &payer, &mut gas_meter)
+                .expect("Test failed");
+        assert_eq!(
+            read_storage_deposit(&wl_storage, &account).expect("Test failed"),
+            Amount::from(64)
+        );
+        assert_eq!(
+            storage_api::token::read_balance(
+                &wl_storage,
+                &native_token,
+                &payer
+            )
+            .expect("Test failed"),
+            Amount::from(36)
+        );
+        assert!(deposit_keys.contains(
+            &namada_core::types::token::balance_key(&native_token, &payer)
+        ));
+
+        let escrow = Address::Internal(InternalAddress::StorageDeposit);
+        let parameters = Address::Internal(InternalAddress::Parameters);
+        let (mut verifiers, keys_changed) = wl_storage
+            .write_log
+            .verifiers_and_changed_keys(&BTreeSet::new());
+        assert!(verifiers.contains(&escrow));
+        assert!(verifiers.contains(&parameters));
+        remove_deposit_verifiers(
+            &mut verifiers,
+            &keys_changed,
+            &BTreeSet::new(),
+            &deposit_keys,
+        );
+        assert!(!verifiers.contains(&escrow));
+        assert!(!verifiers.contains(&parameters));
+        assert!(verifiers.contains(&payer));
+        assert!(
+            verifiers.contains(&Address::Internal(InternalAddress::Multitoken))
+        );
+
+        // The escrow validates the changes of its balance by the tx
+        let (mut verifiers, keys_changed) = wl_storage
+            .write_log
+            .verifiers_and_changed_keys(&BTreeSet::new());
+        let mut tx_changed_keys = deposit_keys.clone();
+        tx_changed_keys.remove(&namada_core::types::token::balance_key(
+            &native_token,
+            &escrow,
+        ));
+        remove_deposit_verifiers(
+            &mut verifiers,
+            &keys_changed,
+            &BTreeSet::new(),
+            &tx_changed_keys,
+        );
+        assert!(verifiers.contains(&escrow));
+        assert!(!verifiers.contains(&parameters));
+
+        // Without the balance to pay the deposit, the account can't be
+        // initialized
+        wl_storage.drop_tx();
+        let other_payer = address::testing::established_address_2();
+        wl_storage
+            .write_log
+            .init_account(&[0], Hash::sha256(b"vp_user"));
+        assert!(matches!(
+            apply_storage_deposits(
+                &mut wl_storage,
+                &other_payer,
+                &mut gas_meter
+            ),
+            Err(Error::StorageDepositError(_))
+        ));
+    }
 }
@@ -15,6 +15,7 @@ use crate::ledger::gas;
 use crate::ledger::gas::{GasMetering, VpGasMeter};
 use crate::ledger::storage::write_log::WriteLog;
 use crate::ledger::storage::{self, write_log, Storage, StorageHasher};
+use crate::ledger::storage_api;
 use crate::proto::{Section, Tx};
 use crate::types::ibc::IbcEvent;
 
@@ -45,6 +46,17 @@ pub enum RuntimeError {
 /// VP environment function result
 pub type EnvResult<T> = std::result::Result<T, RuntimeError>;
 
+impl From<RuntimeError> for storage_api::Error {
+    fn from(err: RuntimeError) -> Self {
+        match err {
+            RuntimeError::OutOfGas(_) => Self::OutOfGas,
+            RuntimeError::StorageError(err) => Self::backend(err),
+            RuntimeError::EncodingError(err) => Self::decode(err),
+            err => Self::new(err),
+        }
+    }
+}
+
 /// Add a gas cost incured in a validity predicate
 pub fn add_gas(
     gas_meter: &mut VpGasMeter,
@@ -100,6 +112,24 @@ where
     }
 }
 
+/// Storage read state before the execution of the block the tx belongs to. It
+/// will only read from the storage, so the modifications of the txs before it
+/// in the block are not visible.
+pub fn read_pre_block<DB, H>(
+    gas_meter: &mut VpGasMeter,
+    storage: &Storage<DB, H>,
+    key: &Key,
+    sentinel: &mut VpSentinel,
+) -> EnvResult<Option<Vec<u8>>>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (value, gas) = storage.read(key).map_err(RuntimeError::StorageError)?;
+    add_gas(gas_meter, gas, sentinel)?;
+    Ok(value)
+}
+
 /// Storage read posterior state (after tx execution). It will try to read from
 /// the write log first and if no entry found then from the storage.
 pub fn read_post<DB, H>(
@@ -296,6 +326,22 @@ where
     Ok(hash)
 }
 
+/// Getting the weak randomness of the block to which the current transaction
+/// is being applied.
+pub fn get_block_randomness<DB, H>(
+    gas_meter: &mut VpGasMeter,
+    storage: &Storage<DB, H>,
+    sentinel: &mut VpSentinel,
+) -> EnvResult<Hash>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (randomness, gas) = storage.get_block_randomness();
+    add_gas(gas_meter, gas, sentinel)?;
+    Ok(randomness)
+}
+
 /// Getting the block hash. The height is that of the block to which the
 /// current transaction is being applied.
 pub fn get_tx_code_hash(
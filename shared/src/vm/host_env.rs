@@ -6,11 +6,10 @@ use std::num::TryFromIntError;
 
 use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
-use masp_primitives::transaction::Transaction;
 use namada_core::ledger::gas::{
     GasMetering, TxGasMeter, MEMORY_ACCESS_GAS_PER_BYTE,
 };
-use namada_core::types::address::{ESTABLISHED_ADDRESS_BYTES_LEN, MASP};
+use namada_core::types::address::ESTABLISHED_ADDRESS_BYTES_LEN;
 use namada_core::types::internal::KeyVal;
 use namada_core::types::storage::TX_INDEX_LENGTH;
 use namada_core::types::transaction::TxSentinel;
@@ -25,17 +24,16 @@ use super::WasmCacheAccess;
 use crate::ledger::gas::{self, VpGasMeter};
 use crate::ledger::storage::write_log::{self, WriteLog};
 use crate::ledger::storage::{self, Storage, StorageHasher};
-use crate::ledger::storage_api::{self, ResultExt};
+use crate::ledger::storage_api::{self, ErrorCode, ResultExt};
 use crate::ledger::vp_host_fns;
 use crate::proto::Tx;
 use crate::types::address::{self, Address};
 use crate::types::hash::Hash;
 use crate::types::ibc::{IbcEvent, IbcShieldedTransfer};
 use crate::types::internal::HostEnvResult;
-use crate::types::storage::{BlockHeight, Epoch, Key, KeySeg, TxIndex};
+use crate::types::storage::{BlockHeight, Epoch, Key, TxIndex};
 use crate::types::token::{
     is_any_minted_balance_key, is_any_minter_key, is_any_token_balance_key,
-    Transfer, HEAD_TX_KEY, PIN_KEY_PREFIX, TX_KEY_PREFIX,
 };
 use crate::vm::memory::VmMemory;
 use crate::vm::prefix_iter::{PrefixIteratorId, PrefixIterators};
@@ -49,12 +47,16 @@ pub enum TxRuntimeError {
     OutOfGas(gas::Error),
     #[error("Trying to modify storage for an address that doesn't exit {0}")]
     UnknownAddressStorageModification(Address),
+    #[error("Trying to initialize an account that already exists {0}")]
+    AccountAlreadyExists(Address),
     #[error(
         "Trying to use a validity predicate with an invalid WASM code hash {0}"
     )]
     InvalidVpCodeHash(String),
     #[error("A validity predicate of an account cannot be deleted")]
     CannotDeleteVp,
+    #[error("The validity predicate code hash {0} is not whitelisted")]
+    VpNotWhitelisted(Hash),
     #[error("Storage modification error: {0}")]
     StorageModificationError(write_log::Error),
     #[error("Storage error: {0}")]
@@ -73,10 +75,42 @@ pub enum TxRuntimeError {
     MissingTxData,
     #[error("IBC: {0}")]
     Ibc(#[from] namada_core::ledger::ibc::Error),
+    #[error("Trying to invoke a transaction with an invalid code hash {0}")]
+    InvalidTxCodeHash(String),
+    #[error("The transaction code hash {0} is not whitelisted")]
+    TxNotWhitelisted(Hash),
+    #[error("Exceeded the maximum depth of {0} nested transaction calls")]
+    TxCallDepthExceeded(u8),
+    #[cfg(feature = "wasm-runtime")]
+    #[error("Nested transaction call failed: {0}")]
+    NestedTxError(Box<crate::vm::wasm::run::Error>),
 }
 
 type TxResult<T> = std::result::Result<T, TxRuntimeError>;
 
+/// Parse a storage key read from the wasm memory. A key that cannot be parsed
+/// is returned to the wasm as the [`ErrorCode::Decode`], which is
+/// deterministic, so that it can be handled there. The errors of the storage
+/// itself may differ across nodes and abort the execution instead.
+fn parse_key(key: String) -> std::result::Result<Key, i64> {
+    Key::parse(key).map_err(|err| {
+        tracing::debug!("Returning a key decoding error to the wasm: {}", err);
+        ErrorCode::Decode.to_i64()
+    })
+}
+
+/// The maximum depth of transactions invoked from other transactions with
+/// [`tx_invoke`]
+pub const MAX_TX_CALL_DEPTH: u8 = 4;
+
+#[cfg(feature = "wasm-runtime")]
+thread_local! {
+    /// The depth of the currently executing nested transaction call. Txs are
+    /// executed on a single thread, so this is only ever modified by the tx
+    /// that's being applied.
+    static TX_CALL_DEPTH: std::cell::Cell<u8> = std::cell::Cell::new(0);
+}
+
 /// A transaction's host environment
 pub struct TxVmEnv<'a, MEM, DB, H, CA>
 where
@@ -118,6 +152,9 @@ where
     pub verifiers: MutHostRef<'a, &'a BTreeSet<Address>>,
     /// Cache for 2-step reads from host environment.
     pub result_buffer: MutHostRef<'a, &'a Option<Vec<u8>>>,
+    /// Debug messages logged by the transaction, only collected when the
+    /// trace is enabled
+    pub debug_trace: MutHostRef<'a, &'a Option<Vec<String>>>,
     /// VP WASM compilation cache (this is available in tx context, because
     /// we're pre-compiling VPs from [`tx_init_account`])
     #[cfg(feature = "wasm-runtime")]
@@ -156,6 +193,7 @@ where
         tx_index: &TxIndex,
         verifiers: &mut BTreeSet<Address>,
         result_buffer: &mut Option<Vec<u8>>,
+        debug_trace: &mut Option<Vec<String>>,
         #[cfg(feature = "wasm-runtime")] vp_wasm_cache: &mut VpCache<CA>,
         #[cfg(feature = "wasm-runtime")] tx_wasm_cache: &mut TxCache<CA>,
     ) -> Self {
@@ -168,6 +206,7 @@ where
         let tx_index = unsafe { HostRef::new(tx_index) };
         let verifiers = unsafe { MutHostRef::new(verifiers) };
         let result_buffer = unsafe { MutHostRef::new(result_buffer) };
+        let debug_trace = unsafe { MutHostRef::new(debug_trace) };
         #[cfg(feature = "wasm-runtime")]
         let vp_wasm_cache = unsafe { MutHostRef::new(vp_wasm_cache) };
         #[cfg(feature = "wasm-runtime")]
@@ -182,6 +221,7 @@ where
             tx_index,
             verifiers,
             result_buffer,
+            debug_trace,
             #[cfg(feature = "wasm-runtime")]
             vp_wasm_cache,
             #[cfg(feature = "wasm-runtime")]
@@ -226,6 +266,7 @@ where
             tx_index: self.tx_index.clone(),
             verifiers: self.verifiers.clone(),
             result_buffer: self.result_buffer.clone(),
+            debug_trace: self.debug_trace.clone(),
             #[cfg(feature = "wasm-runtime")]
             vp_wasm_cache: self.vp_wasm_cache.clone(),
             #[cfg(feature = "wasm-runtime")]
@@ -280,6 +321,9 @@ where
     pub eval_runner: HostRef<'a, &'a EVAL>,
     /// Cache for 2-step reads from host environment.
     pub result_buffer: MutHostRef<'a, &'a Option<Vec<u8>>>,
+    /// Debug messages logged by the VP, only collected when the trace is
+    /// enabled
+    pub debug_trace: MutHostRef<'a, &'a Option<Vec<String>>>,
     /// The storage keys that have been changed. Used for calls to `eval`.
     pub keys_changed: HostRef<'a, &'a BTreeSet<Key>>,
     /// The verifiers whose validity predicates should be triggered. Used for
@@ -345,6 +389,7 @@ where
         iterators: &mut PrefixIterators<'a, DB>,
         verifiers: &BTreeSet<Address>,
         result_buffer: &mut Option<Vec<u8>>,
+        debug_trace: &mut Option<Vec<String>>,
         keys_changed: &BTreeSet<Key>,
         eval_runner: &EVAL,
         #[cfg(feature = "wasm-runtime")] vp_wasm_cache: &mut VpCache<CA>,
@@ -360,6 +405,7 @@ where
             iterators,
             verifiers,
             result_buffer,
+            debug_trace,
             keys_changed,
             eval_runner,
             #[cfg(feature = "wasm-runtime")]
@@ -412,6 +458,7 @@ where
         iterators: &mut PrefixIterators<'a, DB>,
         verifiers: &BTreeSet<Address>,
         result_buffer: &mut Option<Vec<u8>>,
+        debug_trace: &mut Option<Vec<String>>,
         keys_changed: &BTreeSet<Key>,
         eval_runner: &EVAL,
         #[cfg(feature = "wasm-runtime")] vp_wasm_cache: &mut VpCache<CA>,
@@ -426,6 +473,7 @@ where
         let sentinel = unsafe { MutHostRef::new(sentinel) };
         let verifiers = unsafe { HostRef::new(verifiers) };
         let result_buffer = unsafe { MutHostRef::new(result_buffer) };
+        let debug_trace = unsafe { MutHostRef::new(debug_trace) };
         let keys_changed = unsafe { HostRef::new(keys_changed) };
         let eval_runner = unsafe { HostRef::new(eval_runner) };
         #[cfg(feature = "wasm-runtime")]
@@ -441,6 +489,7 @@ where
             tx_index,
             eval_runner,
             result_buffer,
+            debug_trace,
             keys_changed,
             verifiers,
             #[cfg(feature = "wasm-runtime")]
@@ -470,6 +519,7 @@ where
             tx_index: self.tx_index.clone(),
             eval_runner: self.eval_runner.clone(),
             result_buffer: self.result_buffer.clone(),
+            debug_trace: self.debug_trace.clone(),
             keys_changed: self.keys_changed.clone(),
             verifiers: self.verifiers.clone(),
             #[cfg(feature = "wasm-runtime")]
@@ -524,6 +574,8 @@ where
 
 /// Storage `has_key` function exposed to the wasm VM Tx environment. It will
 /// try to check the write log first and if no entry found then the storage.
+///
+/// Returns the [`ErrorCode`] of an invalid key, if any.
 pub fn tx_has_key<MEM, DB, H, CA>(
     env: &TxVmEnv<MEM, DB, H, CA>,
     key_ptr: u64,
@@ -543,7 +595,10 @@ where
 
     tracing::debug!("tx_has_key {}, key {}", key, key_ptr,);
 
-    let key = Key::parse(key).map_err(TxRuntimeError::StorageDataError)?;
+    let key = match parse_key(key) {
+        Ok(key) => key,
+        Err(code) => return Ok(code),
+    };
 
     // try to read from the write log first
     let write_log = unsafe { env.ctx.write_log.get() };
@@ -578,8 +633,9 @@ where
 /// Storage read function exposed to the wasm VM Tx environment. It will try to
 /// read from the write log first and if no entry found then from the storage.
 ///
-/// Returns `-1` when the key is not present, or the length of the data when
-/// the key is present (the length may be `0`).
+/// Returns `-1` when the key is not present, the length of the data when the
+/// key is present (the length may be `0`) or the [`ErrorCode`] of an invalid
+/// key.
 pub fn tx_read<MEM, DB, H, CA>(
     env: &TxVmEnv<MEM, DB, H, CA>,
     key_ptr: u64,
@@ -599,7 +655,10 @@ where
 
     tracing::debug!("tx_read {}, key {}", key, key_ptr,);
 
-    let key = Key::parse(key).map_err(TxRuntimeError::StorageDataError)?;
+    let key = match parse_key(key) {
+        Ok(key) => key,
+        Err(code) => return Ok(code),
+    };
 
     // try to read from the write log first
     let write_log = unsafe { env.ctx.write_log.get() };
@@ -662,6 +721,71 @@ where
     })
 }
 
+/// Batched storage read function exposed to the wasm VM Tx environment. The
+/// keys are given as a borsh encoded `Vec<String>`. For each key, it will try
+/// to read from the write log first and if no entry found then from the
+/// storage.
+///
+/// The values are placed in the result buffer as a borsh encoded
+/// `Vec<Option<Vec<u8>>>`, in the order of the keys. Returns the length of the
+/// encoded values.
+pub fn tx_read_batch<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    keys_ptr: u64,
+    keys_len: u64,
+) -> TxResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let (keys, gas) = env
+        .memory
+        .read_bytes(keys_ptr, keys_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+    let keys = Vec::<String>::try_from_slice(&keys)
+        .map_err(TxRuntimeError::EncodingError)?;
+
+    tracing::debug!("tx_read_batch of {} keys", keys.len());
+
+    let write_log = unsafe { env.ctx.write_log.get() };
+    let storage = unsafe { env.ctx.storage.get() };
+    let mut values: Vec<Option<Vec<u8>>> = Vec::with_capacity(keys.len());
+    for key in keys {
+        let key = Key::parse(key).map_err(TxRuntimeError::StorageDataError)?;
+        let (log_val, gas) = write_log.read(&key);
+        tx_charge_gas(env, gas)?;
+        let value = match log_val {
+            Some(write_log::StorageModification::Write { value })
+            | Some(write_log::StorageModification::Temp { value }) => {
+                Some(value.clone())
+            }
+            Some(write_log::StorageModification::Delete) => None,
+            Some(write_log::StorageModification::InitAccount {
+                vp_code_hash,
+            }) => Some(vp_code_hash.to_vec()),
+            None => {
+                let (value, gas) =
+                    storage.read(&key).map_err(TxRuntimeError::StorageError)?;
+                tx_charge_gas(env, gas)?;
+                value
+            }
+        };
+        values.push(value);
+    }
+
+    let values = values.serialize_to_vec();
+    let len: i64 = values
+        .len()
+        .try_into()
+        .map_err(TxRuntimeError::NumConversionError)?;
+    let result_buffer = unsafe { env.ctx.result_buffer.get() };
+    result_buffer.replace(values);
+    Ok(len)
+}
+
 /// This function is a helper to handle the first step of reading var-len
 /// values from the host.
 ///
@@ -691,7 +815,9 @@ where
 
 /// Storage prefix iterator function exposed to the wasm VM Tx environment.
 /// It will try to get an iterator from the storage and return the corresponding
-/// ID of the iterator, ordered by storage keys.
+/// ID of the iterator, ordered by storage keys. The iterator observes the
+/// writes of the block and of the current tx made before its creation, merged
+/// over the storage.
 pub fn tx_iter_prefix<MEM, DB, H, CA>(
     env: &TxVmEnv<MEM, DB, H, CA>,
     prefix_ptr: u64,
@@ -724,8 +850,7 @@ where
 }
 
 /// Storage prefix iterator next function exposed to the wasm VM Tx environment.
-/// It will try to read from the write log first and if no entry found then from
-/// the storage.
+/// The writes made after the creation of the iterator are not observed.
 ///
 /// Returns `-1` when the key is not present, or the length of the data when
 /// the key is present (the length may be `0`).
@@ -741,64 +866,22 @@ where
 {
     tracing::debug!("tx_iter_next iter_id {}", iter_id,);
 
-    let write_log = unsafe { env.ctx.write_log.get() };
+    // The iterator merges the write log as it was when the iterator was
+    // created over the storage, so its items are already those of the tx's
+    // view of the state
     let iterators = unsafe { env.ctx.iterators.get() };
     let iter_id = PrefixIteratorId::new(iter_id);
-    while let Some((key, val, iter_gas)) = iterators.next(iter_id) {
-        let (log_val, log_gas) = write_log.read(
-            &Key::parse(key.clone())
-                .map_err(TxRuntimeError::StorageDataError)?,
-        );
-        tx_charge_gas(env, iter_gas + log_gas)?;
-        match log_val {
-            Some(write_log::StorageModification::Write { ref value }) => {
-                let key_val = borsh::to_vec(&KeyVal {
-                    key,
-                    val: value.clone(),
-                })
-                .map_err(TxRuntimeError::EncodingError)?;
-                let len: i64 = key_val
-                    .len()
-                    .try_into()
-                    .map_err(TxRuntimeError::NumConversionError)?;
-                let result_buffer = unsafe { env.ctx.result_buffer.get() };
-                result_buffer.replace(key_val);
-                return Ok(len);
-            }
-            Some(&write_log::StorageModification::Delete) => {
-                // check the next because the key has already deleted
-                continue;
-            }
-            Some(&write_log::StorageModification::InitAccount { .. }) => {
-                // a VP of a new account doesn't need to be iterated
-                continue;
-            }
-            Some(write_log::StorageModification::Temp { ref value }) => {
-                let key_val = borsh::to_vec(&KeyVal {
-                    key,
-                    val: value.clone(),
-                })
-                .map_err(TxRuntimeError::EncodingError)?;
-                let len: i64 = key_val
-                    .len()
-                    .try_into()
-                    .map_err(TxRuntimeError::NumConversionError)?;
-                let result_buffer = unsafe { env.ctx.result_buffer.get() };
-                result_buffer.replace(key_val);
-                return Ok(len);
-            }
-            None => {
-                let key_val = borsh::to_vec(&KeyVal { key, val })
-                    .map_err(TxRuntimeError::EncodingError)?;
-                let len: i64 = key_val
-                    .len()
-                    .try_into()
-                    .map_err(TxRuntimeError::NumConversionError)?;
-                let result_buffer = unsafe { env.ctx.result_buffer.get() };
-                result_buffer.replace(key_val);
-                return Ok(len);
-            }
-        }
+    if let Some((key, val, iter_gas)) = iterators.next(iter_id) {
+        tx_charge_gas(env, iter_gas)?;
+        let key_val = borsh::to_vec(&KeyVal { key, val })
+            .map_err(TxRuntimeError::EncodingError)?;
+        let len: i64 = key_val
+            .len()
+            .try_into()
+            .map_err(TxRuntimeError::NumConversionError)?;
+        let result_buffer = unsafe { env.ctx.result_buffer.get() };
+        result_buffer.replace(key_val);
+        return Ok(len);
     }
     Ok(HostEnvResult::Fail.to_i64())
 }
@@ -1033,8 +1116,9 @@ where
 /// Storage read prior state (before tx execution) function exposed to the wasm
 /// VM VP environment. It will try to read from the storage.
 ///
-/// Returns `-1` when the key is not present, or the length of the data when
-/// the key is present (the length may be `0`).
+/// Returns `-1` when the key is not present, the length of the data when the
+/// key is present (the length may be `0`) or the [`ErrorCode`] of an invalid
+/// key.
 pub fn vp_read_pre<MEM, DB, H, EVAL, CA>(
     env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
     key_ptr: u64,
@@ -1056,8 +1140,10 @@ where
     vp_host_fns::add_gas(gas_meter, gas, sentinel)?;
 
     // try to read from the storage
-    let key =
-        Key::parse(key).map_err(vp_host_fns::RuntimeError::StorageDataError)?;
+    let key = match parse_key(key) {
+        Ok(key) => key,
+        Err(code) => return Ok(code),
+    };
     let storage = unsafe { env.ctx.storage.get() };
     let write_log = unsafe { env.ctx.write_log.get() };
     let value =
@@ -1082,12 +1168,66 @@ where
     })
 }
 
+/// Storage read state before the execution of the current block function
+/// exposed to the wasm VM VP environment. It will only read from the storage.
+///
+/// Returns `-1` when the key is not present, the length of the data when the
+/// key is present (the length may be `0`) or the [`ErrorCode`] of an invalid
+/// key.
+pub fn vp_read_pre_block<MEM, DB, H, EVAL, CA>(
+    env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
+    key_ptr: u64,
+    key_len: u64,
+) -> vp_host_fns::EnvResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let (key, gas) = env
+        .memory
+        .read_string(key_ptr, key_len as _)
+        .map_err(|e| vp_host_fns::RuntimeError::MemoryError(Box::new(e)))?;
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let sentinel = unsafe { env.ctx.sentinel.get() };
+    vp_host_fns::add_gas(gas_meter, gas, sentinel)?;
+
+    let key = match parse_key(key) {
+        Ok(key) => key,
+        Err(code) => return Ok(code),
+    };
+    let storage = unsafe { env.ctx.storage.get() };
+    let value =
+        vp_host_fns::read_pre_block(gas_meter, storage, &key, sentinel)?;
+    tracing::debug!(
+        "vp_read_pre_block addr {}, key {}, value {:?}",
+        unsafe { env.ctx.address.get() },
+        key,
+        value,
+    );
+    Ok(match value {
+        Some(value) => {
+            let len: i64 = value
+                .len()
+                .try_into()
+                .map_err(vp_host_fns::RuntimeError::NumConversionError)?;
+            let result_buffer = unsafe { env.ctx.result_buffer.get() };
+            result_buffer.replace(value);
+            len
+        }
+        None => HostEnvResult::Fail.to_i64(),
+    })
+}
+
 /// Storage read posterior state (after tx execution) function exposed to the
 /// wasm VM VP environment. It will try to read from the write log first and if
 /// no entry found then from the storage.
 ///
-/// Returns `-1` when the key is not present, or the length of the data when
-/// the key is present (the length may be `0`).
+/// Returns `-1` when the key is not present, the length of the data when the
+/// key is present (the length may be `0`) or the [`ErrorCode`] of an invalid
+/// key.
 pub fn vp_read_post<MEM, DB, H, EVAL, CA>(
     env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
     key_ptr: u64,
@@ -1111,8 +1251,10 @@ where
     tracing::debug!("vp_read_post {}, key {}", key, key_ptr,);
 
     // try to read from the write log first
-    let key =
-        Key::parse(key).map_err(vp_host_fns::RuntimeError::StorageDataError)?;
+    let key = match parse_key(key) {
+        Ok(key) => key,
+        Err(code) => return Ok(code),
+    };
     let storage = unsafe { env.ctx.storage.get() };
     let write_log = unsafe { env.ctx.write_log.get() };
     let value =
@@ -1131,6 +1273,105 @@ where
     })
 }
 
+/// Batched storage read prior state (before tx execution) function exposed to
+/// the wasm VM VP environment. The keys are given as a borsh encoded
+/// `Vec<String>`.
+///
+/// The values are placed in the result buffer as a borsh encoded
+/// `Vec<Option<Vec<u8>>>`, in the order of the keys. Returns the length of the
+/// encoded values.
+pub fn vp_read_pre_batch<MEM, DB, H, EVAL, CA>(
+    env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
+    keys_ptr: u64,
+    keys_len: u64,
+) -> vp_host_fns::EnvResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    vp_read_batch(env, keys_ptr, keys_len, vp_host_fns::read_pre)
+}
+
+/// Batched storage read posterior state (after tx execution) function exposed
+/// to the wasm VM VP environment. The keys are given as a borsh encoded
+/// `Vec<String>`.
+///
+/// The values are placed in the result buffer as a borsh encoded
+/// `Vec<Option<Vec<u8>>>`, in the order of the keys. Returns the length of the
+/// encoded values.
+pub fn vp_read_post_batch<MEM, DB, H, EVAL, CA>(
+    env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
+    keys_ptr: u64,
+    keys_len: u64,
+) -> vp_host_fns::EnvResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    vp_read_batch(env, keys_ptr, keys_len, vp_host_fns::read_post)
+}
+
+/// Type of the functions that read a value from the prior or posterior state
+type VpReadFn<DB, H> = fn(
+    &mut VpGasMeter,
+    &Storage<DB, H>,
+    &WriteLog,
+    &Key,
+    &mut VpSentinel,
+) -> vp_host_fns::EnvResult<Option<Vec<u8>>>;
+
+/// Read the values of a batch of keys with the given read function, shared
+/// by [`vp_read_pre_batch`] and [`vp_read_post_batch`].
+fn vp_read_batch<MEM, DB, H, EVAL, CA>(
+    env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
+    keys_ptr: u64,
+    keys_len: u64,
+    read: VpReadFn<DB, H>,
+) -> vp_host_fns::EnvResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let (keys, gas) = env
+        .memory
+        .read_bytes(keys_ptr, keys_len as _)
+        .map_err(|e| vp_host_fns::RuntimeError::MemoryError(Box::new(e)))?;
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let sentinel = unsafe { env.ctx.sentinel.get() };
+    vp_host_fns::add_gas(gas_meter, gas, sentinel)?;
+    let keys = Vec::<String>::try_from_slice(&keys)
+        .map_err(vp_host_fns::RuntimeError::EncodingError)?;
+
+    tracing::debug!("vp_read_batch of {} keys", keys.len());
+
+    let storage = unsafe { env.ctx.storage.get() };
+    let write_log = unsafe { env.ctx.write_log.get() };
+    let mut values: Vec<Option<Vec<u8>>> = Vec::with_capacity(keys.len());
+    for key in keys {
+        let key = Key::parse(key)
+            .map_err(vp_host_fns::RuntimeError::StorageDataError)?;
+        values.push(read(gas_meter, storage, write_log, &key, sentinel)?);
+    }
+
+    let values = values.serialize_to_vec();
+    let len: i64 = values
+        .len()
+        .try_into()
+        .map_err(vp_host_fns::RuntimeError::NumConversionError)?;
+    let result_buffer = unsafe { env.ctx.result_buffer.get() };
+    result_buffer.replace(values);
+    Ok(len)
+}
+
 /// Storage read temporary state (after tx execution) function exposed to the
 /// wasm VM VP environment. It will try to read from only the write log.
 ///
@@ -1209,6 +1450,8 @@ where
 
 /// Storage `has_key` in prior state (before tx execution) function exposed to
 /// the wasm VM VP environment. It will try to read from the storage.
+///
+/// Returns the [`ErrorCode`] of an invalid key, if any.
 pub fn vp_has_key_pre<MEM, DB, H, EVAL, CA>(
     env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
     key_ptr: u64,
@@ -1231,8 +1474,10 @@ where
 
     tracing::debug!("vp_has_key_pre {}, key {}", key, key_ptr,);
 
-    let key =
-        Key::parse(key).map_err(vp_host_fns::RuntimeError::StorageDataError)?;
+    let key = match parse_key(key) {
+        Ok(key) => key,
+        Err(code) => return Ok(code),
+    };
     let storage = unsafe { env.ctx.storage.get() };
     let write_log = unsafe { env.ctx.write_log.get() };
     let present = vp_host_fns::has_key_pre(
@@ -1244,6 +1489,8 @@ where
 /// Storage `has_key` in posterior state (after tx execution) function exposed
 /// to the wasm VM VP environment. It will try to check the write log first and
 /// if no entry found then the storage.
+///
+/// Returns the [`ErrorCode`] of an invalid key, if any.
 pub fn vp_has_key_post<MEM, DB, H, EVAL, CA>(
     env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
     key_ptr: u64,
@@ -1266,8 +1513,10 @@ where
 
     tracing::debug!("vp_has_key_post {}, key {}", key, key_ptr,);
 
-    let key =
-        Key::parse(key).map_err(vp_host_fns::RuntimeError::StorageDataError)?;
+    let key = match parse_key(key) {
+        Ok(key) => key,
+        Err(code) => return Ok(code),
+    };
     let storage = unsafe { env.ctx.storage.get() };
     let write_log = unsafe { env.ctx.write_log.get() };
     let present = vp_host_fns::has_key_post(
@@ -1466,6 +1715,9 @@ where
     tx_charge_gas(env, gas)?;
 
     tx_validate_vp_code_hash(env, &code_hash, &code_tag)?;
+    let vp_hash = Hash::try_from(&code_hash[..])
+        .map_err(|e| TxRuntimeError::InvalidVpCodeHash(e.to_string()))?;
+    tx_check_vp_whitelisted(env, &vp_hash)?;
 
     let write_log = unsafe { env.ctx.write_log.get() };
     let (gas, _size_diff) = write_log
@@ -1511,9 +1763,25 @@ where
     let write_log = unsafe { env.ctx.write_log.get() };
     let code_hash = Hash::try_from(&code_hash[..])
         .map_err(|e| TxRuntimeError::InvalidVpCodeHash(e.to_string()))?;
-    let (addr, gas) = write_log.init_account(&storage.address_gen, code_hash);
-    let addr_bytes = addr.serialize_to_vec();
+    tx_check_vp_whitelisted(env, &code_hash)?;
+    // The address is derived from the data of the tx, so that it can be
+    // computed before the tx is submitted
+    let tx = unsafe { env.ctx.tx.get() };
+    let entropy_source = tx.data().unwrap_or_default();
+    let (addr, gas) = write_log.init_account(&entropy_source, code_hash);
+    tx_charge_gas(env, gas)?;
+    // The same data cannot initialize an account twice
+    let vp_key = Key::validity_predicate(&addr);
+    let (pre_val, gas) = write_log.read_pre(&vp_key);
     tx_charge_gas(env, gas)?;
+    let (present, gas) = storage
+        .has_key(&vp_key)
+        .map_err(TxRuntimeError::StorageError)?;
+    tx_charge_gas(env, gas)?;
+    if pre_val.is_some() || present {
+        return Err(TxRuntimeError::AccountAlreadyExists(addr));
+    }
+    let addr_bytes = addr.serialize_to_vec();
     let gas = env
         .memory
         .write_bytes(result_ptr, addr_bytes)
@@ -1619,6 +1887,30 @@ where
     tx_charge_gas(env, gas)
 }
 
+/// Getting the block randomness function exposed to the wasm VM Tx
+/// environment. The randomness is that of the block to which the current
+/// transaction is being applied. It is derived from the data of the last
+/// block, so it is only weakly random.
+pub fn tx_get_block_randomness<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    result_ptr: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let storage = unsafe { env.ctx.storage.get() };
+    let (randomness, gas) = storage.get_block_randomness();
+    tx_charge_gas(env, gas)?;
+    let gas = env
+        .memory
+        .write_bytes(result_ptr, randomness.0)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)
+}
+
 /// Getting the block epoch function exposed to the wasm VM Tx
 /// environment. The epoch is that of the block to which the current
 /// transaction is being applied.
@@ -1795,6 +2087,33 @@ where
     vp_host_fns::add_gas(gas_meter, gas, sentinel)
 }
 
+/// Getting the block randomness function exposed to the wasm VM VP
+/// environment. The randomness is that of the block to which the current
+/// transaction is being applied. It is derived from the data of the last
+/// block, so it is only weakly random.
+pub fn vp_get_block_randomness<MEM, DB, H, EVAL, CA>(
+    env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
+    result_ptr: u64,
+) -> vp_host_fns::EnvResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let sentinel = unsafe { env.ctx.sentinel.get() };
+    let storage = unsafe { env.ctx.storage.get() };
+    let randomness =
+        vp_host_fns::get_block_randomness(gas_meter, storage, sentinel)?;
+    let gas = env
+        .memory
+        .write_bytes(result_ptr, randomness.0)
+        .map_err(|e| vp_host_fns::RuntimeError::MemoryError(Box::new(e)))?;
+    vp_host_fns::add_gas(gas_meter, gas, sentinel)
+}
+
 /// Getting the transaction hash function exposed to the wasm VM VP environment.
 pub fn vp_get_tx_code_hash<MEM, DB, H, EVAL, CA>(
     env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
@@ -1987,6 +2306,33 @@ where
     Ok(())
 }
 
+/// Add a message to the debug trace of the transaction. The trace is only
+/// collected when it's enabled, as in dry-run, and this function is a no-op
+/// otherwise. No gas is charged so that dry-run gas estimates match the
+/// execution in a block.
+pub fn tx_debug_log<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    str_ptr: u64,
+    str_len: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let debug_trace = unsafe { env.ctx.debug_trace.get() };
+    let Some(debug_trace) = debug_trace.as_mut() else {
+        return Ok(());
+    };
+    let (str, _gas) = env
+        .memory
+        .read_string(str_ptr, str_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    debug_trace.push(str);
+    Ok(())
+}
+
 /// Execute IBC tx.
 // Temporarily the IBC tx execution is implemented via a host function to
 // workaround wasm issue.
@@ -2018,6 +2364,57 @@ where
     Ok(())
 }
 
+/// Execute the given IBC message, e.g. a token transfer or a packet
+/// acknowledgement, from a custom transaction. The message is encoded as a
+/// protobuf `Any`, like the data of an IBC tx. The executed messages are
+/// recorded in a temporary storage key for the IBC VP to validate them in place
+/// of the tx data.
+pub fn tx_ibc_execute_msg<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    msg_ptr: u64,
+    msg_len: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use namada_core::ledger::ibc::storage::executed_msgs_key;
+    use namada_core::ledger::ibc::{IbcActions, TransferModule};
+
+    let (msg, gas) = env
+        .memory
+        .read_bytes(msg_ptr, msg_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+
+    let ctx = Rc::new(RefCell::new(env.ctx.clone()));
+    let mut actions = IbcActions::new(ctx.clone());
+    let module = TransferModule::new(ctx);
+    actions.add_transfer_module(module.module_id(), module);
+    actions.execute(&msg)?;
+
+    let write_log = unsafe { env.ctx.write_log.get() };
+    let key = executed_msgs_key();
+    let (modification, gas) = write_log.read(&key);
+    let mut msgs: Vec<Vec<u8>> = match modification {
+        Some(write_log::StorageModification::Temp { value }) => {
+            Vec::try_from_slice(value).map_err(TxRuntimeError::EncodingError)?
+        }
+        _ => vec![],
+    };
+    tx_charge_gas(env, gas)?;
+    msgs.push(msg);
+    let (gas, _size_diff) = write_log
+        .write_temp(&key, msgs.serialize_to_vec())
+        .map_err(TxRuntimeError::StorageModificationError)?;
+    tx_charge_gas(env, gas)
+}
+
 /// Validate a VP WASM code hash in a tx environment.
 fn tx_validate_vp_code_hash<MEM, DB, H, CA>(
     env: &TxVmEnv<MEM, DB, H, CA>,
@@ -2079,6 +2476,38 @@ where
     Ok(())
 }
 
+/// Check that the given VP code hash is allowed by the `vp_whitelist`
+/// protocol parameter. An empty whitelist allows any VP.
+fn tx_check_vp_whitelisted<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    code_hash: &Hash,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let storage = unsafe { env.ctx.storage.get() };
+    let key =
+        crate::ledger::parameters::storage::get_vp_whitelist_storage_key();
+    let (value, gas) =
+        storage.read(&key).map_err(TxRuntimeError::StorageError)?;
+    tx_charge_gas(env, gas)?;
+    let whitelist: Vec<String> = match value {
+        Some(bytes) => Vec::try_from_slice(&bytes[..])
+            .map_err(TxRuntimeError::EncodingError)?,
+        None => vec![],
+    };
+    if whitelist.is_empty()
+        || whitelist.contains(&code_hash.to_string().to_lowercase())
+    {
+        Ok(())
+    } else {
+        Err(TxRuntimeError::VpNotWhitelisted(*code_hash))
+    }
+}
+
 /// Set the sentinel for an invalid tx section commitment
 pub fn tx_set_commitment_sentinel<MEM, DB, H, CA>(env: &TxVmEnv<MEM, DB, H, CA>)
 where
@@ -2091,6 +2520,122 @@ where
     sentinel.set_invalid_commitment();
 }
 
+/// Invoke another transaction's WASM code, referenced by its hash, with the
+/// given data within the current execution. The invoked transaction shares
+/// the write log, gas meter and verifiers with the caller and it sees the
+/// caller's tx sections, except for the code and data which are replaced. Any
+/// error in the invoked transaction aborts the caller.
+#[cfg(feature = "wasm-runtime")]
+pub fn tx_invoke<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    code_hash_ptr: u64,
+    code_hash_len: u64,
+    data_ptr: u64,
+    data_len: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    use crate::proto::{Code, Data};
+
+    let (code_hash, gas) = env
+        .memory
+        .read_bytes(code_hash_ptr, code_hash_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+    let code_hash = Hash::try_from(&code_hash[..])
+        .map_err(|e| TxRuntimeError::InvalidTxCodeHash(e.to_string()))?;
+    let (data, gas) = env
+        .memory
+        .read_bytes(data_ptr, data_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_charge_gas(env, gas)?;
+
+    tracing::debug!("tx_invoke code hash {}", code_hash);
+
+    tx_check_tx_whitelisted(env, &code_hash)?;
+
+    let depth = TX_CALL_DEPTH.with(|depth| depth.get());
+    if depth >= MAX_TX_CALL_DEPTH {
+        return Err(TxRuntimeError::TxCallDepthExceeded(MAX_TX_CALL_DEPTH));
+    }
+
+    let mut nested_tx = unsafe { env.ctx.tx.get() }.clone();
+    nested_tx.set_code(Code::from_hash(code_hash, None));
+    nested_tx.set_data(Data::new(data));
+
+    let storage = unsafe { env.ctx.storage.get() };
+    let write_log = unsafe { env.ctx.write_log.get() };
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let tx_index = unsafe { env.ctx.tx_index.get() };
+    let vp_wasm_cache = unsafe { env.ctx.vp_wasm_cache.get() };
+    let tx_wasm_cache = unsafe { env.ctx.tx_wasm_cache.get() };
+    let debug_trace = unsafe { env.ctx.debug_trace.get() };
+
+    TX_CALL_DEPTH.with(|cell| cell.set(depth + 1));
+    let result = crate::vm::wasm::run::tx(
+        storage,
+        write_log,
+        gas_meter,
+        tx_index,
+        &nested_tx,
+        vp_wasm_cache,
+        tx_wasm_cache,
+        debug_trace,
+    );
+    TX_CALL_DEPTH.with(|cell| cell.set(depth));
+
+    match result {
+        Ok(nested_verifiers) => {
+            let verifiers = unsafe { env.ctx.verifiers.get() };
+            verifiers.extend(nested_verifiers);
+            Ok(())
+        }
+        Err(err) => {
+            if let crate::vm::wasm::run::Error::GasError(_) = &err {
+                let sentinel = unsafe { env.ctx.sentinel.get() };
+                sentinel.set_out_of_gas();
+            }
+            Err(TxRuntimeError::NestedTxError(Box::new(err)))
+        }
+    }
+}
+
+/// Check that the given tx code hash is allowed by the tx whitelist parameter
+#[cfg(feature = "wasm-runtime")]
+fn tx_check_tx_whitelisted<MEM, DB, H, CA>(
+    env: &TxVmEnv<MEM, DB, H, CA>,
+    code_hash: &Hash,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let storage = unsafe { env.ctx.storage.get() };
+    let key =
+        crate::ledger::parameters::storage::get_tx_whitelist_storage_key();
+    let (value, gas) =
+        storage.read(&key).map_err(TxRuntimeError::StorageError)?;
+    tx_charge_gas(env, gas)?;
+    let whitelist: Vec<String> = match value {
+        Some(bytes) => Vec::try_from_slice(&bytes[..])
+            .map_err(TxRuntimeError::EncodingError)?,
+        None => vec![],
+    };
+    if whitelist.is_empty()
+        || whitelist.contains(&code_hash.to_string().to_lowercase())
+    {
+        Ok(())
+    } else {
+        Err(TxRuntimeError::TxNotWhitelisted(*code_hash))
+    }
+}
+
 /// Verify a transaction signature
 #[allow(clippy::too_many_arguments)]
 pub fn tx_verify_tx_section_signature<MEM, DB, H, CA>(
@@ -2256,6 +2801,34 @@ where
     Ok(())
 }
 
+/// Add a message to the debug trace of the VP. The trace is only collected
+/// when it's enabled, as in dry-run, and this function is a no-op otherwise.
+/// No gas is charged so that dry-run gas estimates match the execution in a
+/// block.
+pub fn vp_debug_log<MEM, DB, H, EVAL, CA>(
+    env: &VpVmEnv<MEM, DB, H, EVAL, CA>,
+    str_ptr: u64,
+    str_len: u64,
+) -> vp_host_fns::EnvResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let debug_trace = unsafe { env.ctx.debug_trace.get() };
+    let Some(debug_trace) = debug_trace.as_mut() else {
+        return Ok(());
+    };
+    let (str, _gas) = env
+        .memory
+        .read_string(str_ptr, str_len as _)
+        .map_err(|e| vp_host_fns::RuntimeError::MemoryError(Box::new(e)))?;
+    debug_trace.push(str);
+    Ok(())
+}
+
 // Temp. workaround for <https://github.com/anoma/namada/issues/1831>
 use namada_core::ledger::storage_api::StorageRead;
 
@@ -2520,35 +3093,11 @@ where
         &mut self,
         shielded: &IbcShieldedTransfer,
     ) -> Result<(), storage_api::Error> {
-        let masp_addr = MASP;
-        let head_tx_key = Key::from(masp_addr.to_db_key())
-            .push(&HEAD_TX_KEY.to_owned())
-            .expect("Cannot obtain a storage key");
-        let current_tx_idx =
-            self.read::<u64>(&head_tx_key).unwrap_or(None).unwrap_or(0);
-        let current_tx_key = Key::from(masp_addr.to_db_key())
-            .push(&(TX_KEY_PREFIX.to_owned() + &current_tx_idx.to_string()))
-            .expect("Cannot obtain a storage key");
-        // Save the Transfer object and its location within the blockchain
-        // so that clients do not have to separately look these
-        // up
-        let record: (Epoch, BlockHeight, TxIndex, Transfer, Transaction) = (
-            self.get_block_epoch()?,
-            self.get_block_height()?,
-            self.get_tx_index()?,
-            shielded.transfer.clone(),
-            shielded.masp_tx.clone(),
-        );
-        self.write(&current_tx_key, record)?;
-        self.write(&head_tx_key, current_tx_idx + 1)?;
-        // If storage key has been supplied, then pin this transaction to it
-        if let Some(key) = &shielded.transfer.key {
-            let pin_key = Key::from(masp_addr.to_db_key())
-                .push(&(PIN_KEY_PREFIX.to_owned() + key))
-                .expect("Cannot obtain a storage key");
-            self.write(&pin_key, current_tx_idx)?;
-        }
-        Ok(())
+        storage_api::token::handle_masp_tx(
+            self,
+            &shielded.transfer,
+            &shielded.masp_tx,
+        )
     }
 
     fn mint_token(
@@ -2662,6 +3211,7 @@ pub mod testing {
         tx: &Tx,
         tx_index: &TxIndex,
         result_buffer: &mut Option<Vec<u8>>,
+        debug_trace: &mut Option<Vec<String>>,
         #[cfg(feature = "wasm-runtime")] vp_wasm_cache: &mut VpCache<CA>,
         #[cfg(feature = "wasm-runtime")] tx_wasm_cache: &mut TxCache<CA>,
     ) -> TxVmEnv<'static, NativeMemory, DB, H, CA>
@@ -2681,6 +3231,7 @@ pub mod testing {
             tx_index,
             verifiers,
             result_buffer,
+            debug_trace,
             #[cfg(feature = "wasm-runtime")]
             vp_wasm_cache,
             #[cfg(feature = "wasm-runtime")]
@@ -2701,6 +3252,7 @@ pub mod testing {
         tx_index: &TxIndex,
         verifiers: &BTreeSet<Address>,
         result_buffer: &mut Option<Vec<u8>>,
+        debug_trace: &mut Option<Vec<String>>,
         keys_changed: &BTreeSet<Key>,
         eval_runner: &EVAL,
         #[cfg(feature = "wasm-runtime")] vp_wasm_cache: &mut VpCache<CA>,
@@ -2723,6 +3275,7 @@ pub mod testing {
             iterators,
             verifiers,
             result_buffer,
+            debug_trace,
             keys_changed,
             eval_runner,
             #[cfg(feature = "wasm-runtime")]
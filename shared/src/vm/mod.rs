@@ -9,9 +9,13 @@ use wasmparser::{Validator, WasmFeatures};
 pub mod host_env;
 pub mod memory;
 pub mod prefix_iter;
+#[cfg(feature = "wasm-runtime")]
+pub mod simulate;
 pub mod types;
 #[cfg(feature = "wasm-runtime")]
 pub mod wasm;
+#[cfg(feature = "wasm-runtime")]
+pub use simulate::{simulate_tx, SimulationResult};
 use thiserror::Error;
 
 const UNTRUSTED_WASM_FEATURES: WasmFeatures = WasmFeatures {
@@ -0,0 +1,150 @@
+//! Sandboxed execution of transactions for off-chain simulation.
+
+use namada_core::ledger::gas::{self, Gas, GasMetering, TxGasMeter};
+use namada_core::ledger::storage::write_log::WriteLog;
+use namada_core::ledger::storage::{
+    DBIter, Storage, StorageHasher, TempWlStorage, DB,
+};
+use namada_core::proto::Tx;
+use namada_core::types::storage::TxIndex;
+use namada_core::types::transaction::{DecryptedTx, TxResult, TxType};
+
+use crate::ledger::protocol::{self, ShellParams};
+use crate::vm::wasm::{TxCache, VpCache};
+use crate::vm::WasmCacheRoAccess;
+
+/// The outcome of a simulated transaction
+#[derive(Debug)]
+pub struct SimulationResult {
+    /// The storage modifications that the transaction would have applied
+    pub write_log: WriteLog,
+    /// The gas used, changed keys and the results of the validity predicates
+    /// triggered by the transaction
+    pub tx_result: TxResult,
+}
+
+/// Run a transaction against a snapshot of the storage entirely in-process,
+/// without any consensus side effects. The transaction is applied as a
+/// decrypted transaction, so the fee of a wrapper is not paid. The storage is
+/// only read from and the compilation caches are accessed read-only. If no gas
+/// limit is given, the maximum block gas parameter is used.
+pub fn simulate_tx<D, H>(
+    storage: &Storage<D, H>,
+    mut tx: Tx,
+    gas_limit: Option<Gas>,
+    vp_wasm_cache: &mut VpCache<WasmCacheRoAccess>,
+    tx_wasm_cache: &mut TxCache<WasmCacheRoAccess>,
+) -> protocol::Result<SimulationResult>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut wl_storage = TempWlStorage::new(storage);
+    let mut tx_gas_meter = match gas_limit {
+        Some(gas_limit) => TxGasMeter::new_from_sub_limit(gas_limit),
+        None => TxGasMeter::new(
+            gas::get_max_block_gas(&wl_storage)
+                .map_err(|err| protocol::Error::GasError(err.to_string()))?
+                .into(),
+        ),
+    };
+    tx.update_header(TxType::Decrypted(DecryptedTx::Decrypted));
+
+    let mut tx_result = protocol::apply_wasm_tx(
+        tx,
+        &TxIndex(0),
+        ShellParams::new(
+            &mut tx_gas_meter,
+            &mut wl_storage,
+            vp_wasm_cache,
+            tx_wasm_cache,
+        )
+        .with_debug_trace(),
+    )?;
+    tx_result.gas_used = tx_gas_meter.get_tx_consumed_gas();
+
+    Ok(SimulationResult {
+        write_log: wl_storage.write_log,
+        tx_result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh_ext::BorshSerializeExt;
+    use namada_core::ledger::parameters::storage::get_wasm_limits_key;
+    use namada_core::ledger::parameters::WasmLimits;
+    use namada_core::ledger::storage::testing::TestStorage;
+    use namada_core::ledger::storage::write_log::StorageModification;
+    use namada_core::proto::{Code, Data};
+    use namada_core::types::hash::Hash;
+    use namada_core::types::storage::Key;
+    use namada_test_utils::tx_data::TxWriteData;
+    use namada_test_utils::TestWasms;
+
+    use super::*;
+    use crate::vm::wasm::compilation_cache::common::testing::cache;
+
+    /// Make a storage with the given tx code and the WASM limits
+    fn storage_with_tx_code(tx_code: Vec<u8>) -> TestStorage {
+        let mut storage = TestStorage::default();
+        let code_hash = Hash::sha256(&tx_code);
+        let code_len = (tx_code.len() as u64).serialize_to_vec();
+        storage.write(&Key::wasm_code(&code_hash), tx_code).unwrap();
+        storage
+            .write(&Key::wasm_code_len(&code_hash), code_len)
+            .unwrap();
+        storage
+            .write(
+                &get_wasm_limits_key(),
+                WasmLimits::default().serialize_to_vec(),
+            )
+            .unwrap();
+        storage
+    }
+
+    /// Test that a simulated tx doesn't modify the storage, but returns its
+    /// writes and reports the gas it used
+    #[test]
+    fn test_simulate_tx() {
+        let tx_code = TestWasms::TxWriteStorageKey.read_bytes();
+        let code_hash = Hash::sha256(&tx_code);
+        let storage = storage_with_tx_code(tx_code.clone());
+        // The root of an identical storage, in which nothing is simulated
+        let expected_root = storage_with_tx_code(tx_code).merkle_root();
+
+        let (vp_cache, _vp_dir) = cache();
+        let (tx_cache, _tx_dir) = cache();
+        let key = Key::parse("simulation/key").unwrap();
+        let value = b"value".to_vec();
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::from_hash(code_hash, None));
+        tx.set_data(Data::new(
+            TxWriteData {
+                key: key.clone(),
+                value: value.clone(),
+            }
+            .serialize_to_vec(),
+        ));
+
+        let result = simulate_tx(
+            &storage,
+            tx,
+            Some(u64::MAX.into()),
+            &mut vp_cache.read_only(),
+            &mut tx_cache.read_only(),
+        )
+        .expect("Simulation failed");
+        assert!(result.tx_result.gas_used > Gas::default());
+        assert!(result.tx_result.vps_result.accepted_vps.is_empty());
+        assert!(result.tx_result.vps_result.rejected_vps.is_empty());
+        assert!(result.tx_result.changed_keys.contains(&key));
+        assert!(matches!(
+            result.write_log.read(&key).0,
+            Some(StorageModification::Write { value: written }) if written == &value
+        ));
+
+        assert_eq!(storage.read(&key).unwrap().0, None);
+        assert_eq!(storage.merkle_root(), expected_root);
+    }
+}
@@ -5,11 +5,13 @@
 //! `universal` module).
 
 use std::collections::hash_map::RandomState;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use std::thread::sleep;
 use std::time::Duration;
@@ -20,10 +22,14 @@ use wasmer_cache::{FileSystemCache, Hash as CacheHash};
 
 use crate::core::types::hash::Hash;
 use crate::types::control_flow::time::{ExponentialBackoff, SleepStrategy};
-use crate::vm::wasm::run::untrusted_wasm_store;
+use crate::vm::wasm::run::{untrusted_wasm_store, ModuleLimits};
 use crate::vm::wasm::{self, memory};
 use crate::vm::{WasmCacheAccess, WasmCacheRoAccess};
 
+/// The name of the file listing the modules that were completely written to
+/// the cache directory
+const INDEX_FILE: &str = "index";
+
 /// Cache handle. Thread-safe.
 #[derive(Debug, Clone)]
 pub struct Cache<N: CacheName, A: WasmCacheAccess> {
@@ -33,6 +39,8 @@ pub struct Cache<N: CacheName, A: WasmCacheAccess> {
     progress: Arc<RwLock<HashMap<Hash, Compilation>>>,
     /// In-memory LRU cache of compiled modules
     in_memory: Arc<RwLock<MemoryCache>>,
+    /// Cache hit and miss counters
+    counters: Arc<Counters>,
     /// The cache's name
     name: PhantomData<N>,
     /// Cache access level
@@ -45,6 +53,24 @@ pub trait CacheName: Clone + std::fmt::Debug {
     fn name() -> &'static str;
 }
 
+/// Number of lookups of the in-memory cache that found a compiled module
+/// (hits) and of modules that had to be loaded from a file or compiled
+/// (misses).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Modules found in the in-memory cache
+    pub hits: u64,
+    /// Modules loaded from a file or compiled
+    pub misses: u64,
+}
+
+/// Thread-safe counters behind [`CacheStats`]
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
 /// In-memory LRU cache of compiled modules
 type MemoryCache = CLruCache<Hash, Module, RandomState, ModuleCacheScale>;
 
@@ -82,11 +108,13 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
 
         fs::create_dir_all(&dir)
             .expect("Couldn't create the wasm cache directory");
+        prune_unindexed_modules(&dir);
 
         Self {
             dir,
             progress: Default::default(),
             in_memory,
+            counters: Default::default(),
             name: Default::default(),
             access: Default::default(),
         }
@@ -117,6 +145,23 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
         self.in_memory.read().unwrap().weight()
     }
 
+    /// Get the number of cache hits and misses since the cache was created.
+    /// The counters are shared with the read-only handles of this cache.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_hit(&self) {
+        self.counters.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_miss(&self) {
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Get a WASM module from LRU cache, from a file or compile it and cache
     /// it. Updates the position in the LRU cache.
     fn get(
@@ -130,6 +175,7 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                 N::name(),
                 hash.to_string()
             );
+            self.record_hit();
             return Ok(Some((module.clone(), store())));
         }
         drop(in_memory);
@@ -153,6 +199,7 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                             N::name(),
                             hash.to_string()
                         );
+                        self.record_hit();
                         return Ok(Some((module.clone(), store())));
                     }
 
@@ -164,6 +211,7 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                             N::name(),
                             hash.to_string()
                         );
+                        self.record_miss();
                         // Put into cache, ignore result if it's full
                         let _ =
                             in_memory.put_with_weight(*hash, module.clone());
@@ -202,6 +250,8 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                         return Ok(None);
                     };
 
+                    self.record_miss();
+
                     // Update progress
                     let mut progress = self.progress.write().unwrap();
                     progress.insert(*hash, Compilation::Done);
@@ -230,6 +280,7 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                 N::name(),
                 hash.to_string()
             );
+            self.record_hit();
             return Ok(Some((module.clone(), store())));
         }
         drop(in_memory);
@@ -253,6 +304,7 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                             N::name(),
                             hash.to_string()
                         );
+                        self.record_hit();
                         return Ok(Some((module.clone(), store())));
                     }
 
@@ -264,6 +316,7 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                             N::name(),
                             hash.to_string()
                         );
+                        self.record_miss();
                         return Ok(Some((module, store)));
                     } else {
                         return Ok(None);
@@ -290,6 +343,7 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                             hash.to_string()
                         );
                         if let Ok(res) = file_load_module(&self.dir, hash) {
+                            self.record_miss();
                             return Ok(Some(res));
                         } else {
                             return Ok(None);
@@ -302,20 +356,47 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
         }
     }
 
-    /// Compile a WASM module and persist the compiled modules to files.
+    /// Compile a WASM module instrumented with the given limits and persist
+    /// the compiled modules to files.
     pub fn compile_or_fetch(
         &mut self,
         code: impl AsRef<[u8]>,
+        limits: &ModuleLimits,
     ) -> Result<Option<(Module, Store)>, wasm::run::Error> {
-        let hash = hash_of_code(&code);
+        let hash = module_hash(&hash_of_code(&code), limits);
+        self.compile_or_fetch_aux(hash, || {
+            wasm::run::prepare_wasm_code(code, limits)
+        })
+    }
 
+    /// Compile a WASM module that has already been gas-metered at deploy
+    /// time, instrumented with the given limits, and persist the compiled
+    /// modules to files. The module is cached under the hash of the raw code
+    /// it was instrumented from.
+    pub fn compile_instrumented_or_fetch(
+        &mut self,
+        hash: &Hash,
+        instrumented_code: impl AsRef<[u8]>,
+        limits: &ModuleLimits,
+    ) -> Result<Option<(Module, Store)>, wasm::run::Error> {
+        self.compile_or_fetch_aux(module_hash(hash, limits), || {
+            wasm::run::instrument_limits(instrumented_code, limits)
+        })
+    }
+
+    fn compile_or_fetch_aux(
+        &mut self,
+        hash: Hash,
+        prepare: impl FnOnce() -> Result<Vec<u8>, wasm::run::Error>,
+    ) -> Result<Option<(Module, Store)>, wasm::run::Error> {
         if !A::is_read_write() {
             // It doesn't update the cache and files
             let progress = self.progress.read().unwrap();
             match progress.get(&hash) {
                 Some(_) => return self.peek(&hash),
                 None => {
-                    let code = wasm::run::prepare_wasm_code(code)?;
+                    self.record_miss();
+                    let code = prepare()?;
                     return Ok(Some(compile(code)?));
                 }
             }
@@ -330,8 +411,9 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
         drop(progress);
 
         tracing::info!("Compiling {} {}.", N::name(), hash.to_string());
+        self.record_miss();
 
-        match wasm::run::prepare_wasm_code(code) {
+        match prepare() {
             Ok(code) => match compile(code) {
                 Ok((module, store)) => {
                     // Write the file
@@ -371,8 +453,9 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
         }
     }
 
-    /// Pre-compile a WASM module to a file. The compilation runs in a new OS
-    /// thread and the function returns immediately.
+    /// Pre-compile a WASM module, instrumented with the default limits, to a
+    /// file. The compilation runs in a new OS thread and the function returns
+    /// immediately.
     pub fn pre_compile(&mut self, code: impl AsRef<[u8]>) {
         if A::is_read_write() {
             let hash = hash_of_code(&code);
@@ -395,7 +478,10 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
                         tracing::info!("Compiling WASM {}.", hash.to_string());
 
                         let (_module, _store) =
-                            match wasm::run::prepare_wasm_code(code) {
+                            match wasm::run::prepare_wasm_code(
+                                code,
+                                &ModuleLimits::default(),
+                            ) {
                                 Ok(code) => match compile(code) {
                                     Ok((module, store)) => {
                                         let mut progress =
@@ -454,18 +540,127 @@ impl<N: CacheName, A: WasmCacheAccess> Cache<N, A> {
         }
     }
 
+    /// Write the index of the compiled modules to the cache directory,
+    /// waiting for the compilations in progress to finish first. On start, the
+    /// module files that are not in the index are removed, as they might have
+    /// been only partially written if the node didn't shut down cleanly.
+    pub fn persist_index(&self) {
+        if !A::is_read_write() {
+            return;
+        }
+        let mut iter = 0;
+        let exponential_backoff = ExponentialBackoff {
+            base: 2,
+            as_duration: |backoff: u64| {
+                Duration::from_millis(backoff.saturating_mul(10))
+            },
+        };
+        // Holding the lock while writing the index prevents new compilations
+        let progress = loop {
+            let progress = self.progress.read().unwrap();
+            if progress
+                .values()
+                .all(|compilation| matches!(compilation, Compilation::Done))
+            {
+                break progress;
+            }
+            drop(progress);
+            tracing::info!(
+                "Waiting for the {} to finish compiling...",
+                N::name()
+            );
+            sleep(exponential_backoff.backoff(&iter));
+            iter += 1;
+        };
+
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to read the {} cache directory: {err}",
+                    N::name()
+                );
+                return;
+            }
+        };
+        let index: String = entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                let hash = Hash::from_str(&name).ok()?;
+                module_file_exists(&self.dir, &hash).then(|| name + "\n")
+            })
+            .collect();
+        let tmp_path = self.dir.join(format!("{INDEX_FILE}.tmp"));
+        let res = fs::write(&tmp_path, index)
+            .and_then(|()| fs::rename(&tmp_path, self.dir.join(INDEX_FILE)));
+        if let Err(err) = res {
+            tracing::error!("Failed to write the {} index: {err}", N::name());
+        }
+        drop(progress);
+    }
+
     /// Get a read-only cache handle.
     pub fn read_only(&self) -> Cache<N, WasmCacheRoAccess> {
         Cache {
             dir: self.dir.clone(),
             progress: self.progress.clone(),
             in_memory: self.in_memory.clone(),
+            counters: self.counters.clone(),
             name: Default::default(),
             access: Default::default(),
         }
     }
 }
 
+/// Remove the module files that are not listed in the index of the cache
+/// directory. Nothing is removed if there is no index yet.
+fn prune_unindexed_modules(dir: &Path) {
+    let index = match fs::read_to_string(dir.join(INDEX_FILE)) {
+        Ok(index) => index,
+        Err(_) => return,
+    };
+    let indexed: HashSet<&str> = index.lines().collect();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let is_unindexed_module = path.is_dir()
+            && entry
+                .file_name()
+                .to_str()
+                .map(|name| !indexed.contains(name))
+                .unwrap_or_default();
+        if is_unindexed_module {
+            tracing::warn!(
+                "Removing the WASM module {} that is missing from the cache \
+                 index.",
+                path.to_string_lossy()
+            );
+            if let Err(err) = fs::remove_dir_all(&path) {
+                tracing::error!(
+                    "Failed to remove {}: {err}",
+                    path.to_string_lossy()
+                );
+            }
+        }
+    }
+}
+
+/// The hash under which the module compiled from the code of the given hash,
+/// instrumented with the given limits, is cached. The modules instrumented
+/// with the default limits are cached under the hash of their code.
+pub fn module_hash(code_hash: &Hash, limits: &ModuleLimits) -> Hash {
+    if *limits == ModuleLimits::default() {
+        return *code_hash;
+    }
+    let mut bytes = code_hash.0.to_vec();
+    bytes.extend(limits.max_stack_height.to_be_bytes());
+    bytes.extend(limits.memory_max_pages.to_be_bytes());
+    Hash::sha256(bytes)
+}
+
 fn hash_of_code(code: impl AsRef<[u8]>) -> Hash {
     Hash::sha256(code.as_ref())
 }
@@ -648,8 +843,12 @@ mod test {
                     "The module should not be in cache"
                 );
 
-                let fetched =
-                    cache.compile_or_fetch(&tx_read_storage_key.code).unwrap();
+                let fetched = cache
+                    .compile_or_fetch(
+                        &tx_read_storage_key.code,
+                        &ModuleLimits::default(),
+                    )
+                    .unwrap();
                 assert_matches!(
                     fetched,
                     Some(_),
@@ -686,7 +885,9 @@ mod test {
                     "The module must not be in cache"
                 );
 
-                let fetched = cache.compile_or_fetch(&tx_no_op.code).unwrap();
+                let fetched = cache
+                    .compile_or_fetch(&tx_no_op.code, &ModuleLimits::default())
+                    .unwrap();
                 assert_matches!(
                     fetched,
                     Some(_),
@@ -828,7 +1029,9 @@ mod test {
                 );
 
                 // Fetching with read-only should not modify the in-memory cache
-                let fetched = cache.compile_or_fetch(&tx_no_op.code).unwrap();
+                let fetched = cache
+                    .compile_or_fetch(&tx_no_op.code, &ModuleLimits::default())
+                    .unwrap();
                 assert_matches!(
                     fetched,
                     Some(_),
@@ -852,6 +1055,62 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_cache_stats() {
+        let tx_no_op = load_wasm(TestWasms::TxNoOp.path());
+        let (mut cache, _tmp_dir) = cache(tx_no_op.size * 2);
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        // A module that is not cached is neither a hit nor a miss
+        let fetched = cache.fetch(&tx_no_op.hash).unwrap();
+        assert_matches!(fetched, None);
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        // Compiling the module is a miss
+        let fetched = cache
+            .compile_or_fetch(&tx_no_op.code, &ModuleLimits::default())
+            .unwrap();
+        assert_matches!(fetched, Some(_));
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 1 });
+
+        // Fetching it again is a hit, visible from the read-only handle too
+        let fetched = cache.fetch(&tx_no_op.hash).unwrap();
+        assert_matches!(fetched, Some(_));
+        let expected = CacheStats { hits: 1, misses: 1 };
+        assert_eq!(cache.stats(), expected);
+        assert_eq!(cache.read_only().stats(), expected);
+    }
+
+    /// Test that the module files missing from a persisted index are removed
+    /// when the cache is opened again
+    #[test]
+    fn test_persist_index() {
+        let tx_no_op = load_wasm(TestWasms::TxNoOp.path());
+        let (mut cache, tmp_dir) = cache(tx_no_op.size * 2);
+        let fetched = cache
+            .compile_or_fetch(&tx_no_op.code, &ModuleLimits::default())
+            .unwrap();
+        assert_matches!(fetched, Some(_));
+
+        // Without an index, no module is removed
+        let partial = Hash::sha256(b"partially written module");
+        let partial_dir =
+            tmp_dir.path().join(partial.to_string().to_lowercase());
+        fs::create_dir_all(&partial_dir).unwrap();
+        let _cache: Cache<TestCache, WasmCacheRwAccess> =
+            Cache::new(tmp_dir.path(), tx_no_op.size * 2);
+        assert!(partial_dir.exists());
+
+        // Only the complete modules are in the index
+        fs::remove_dir_all(&partial_dir).unwrap();
+        cache.persist_index();
+        fs::create_dir_all(&partial_dir).unwrap();
+        let cache: Cache<TestCache, WasmCacheRwAccess> =
+            Cache::new(tmp_dir.path(), tx_no_op.size * 2);
+        assert!(!partial_dir.exists());
+        assert!(module_file_exists(&cache.dir, &tx_no_op.hash));
+    }
+
     #[test]
     fn test_fetch_or_compile_invalid_wasm() {
         // Some random bytes
@@ -861,7 +1120,7 @@ mod test {
 
         // Try to compile it
         let error = cache
-            .compile_or_fetch(&invalid_wasm)
+            .compile_or_fetch(&invalid_wasm, &ModuleLimits::default())
             .expect_err("Compilation should fail");
         println!("Error: {}", error);
 
@@ -1052,8 +1311,10 @@ mod test {
                 // No in-memory cache needed, but must be non-zero
                 1,
             );
-            let (_module, _store) =
-                cache.compile_or_fetch(&code).unwrap().unwrap();
+            let (_module, _store) = cache
+                .compile_or_fetch(&code, &ModuleLimits::default())
+                .unwrap()
+                .unwrap();
             1
         };
         println!(
@@ -52,9 +52,9 @@ pub fn tx_imports<DB, H, CA>(
     env: TxVmEnv<'static, WasmMemory, DB, H, CA>,
 ) -> ImportObject
 where
-    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
-    H: StorageHasher,
-    CA: WasmCacheAccess,
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
 {
     wasmer::imports! {
         // default namespace
@@ -65,6 +65,7 @@ where
             // Whitelisted gas exposed function, we need two different functions just because of colliding names in the vm_host_env macro to generate implementations
             "namada_tx_charge_gas" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_charge_gas),
             "namada_tx_read" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_read),
+            "namada_tx_read_batch" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_read_batch),
             "namada_tx_result_buffer" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_result_buffer),
             "namada_tx_has_key" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_has_key),
             "namada_tx_write" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_write),
@@ -82,10 +83,14 @@ where
             "namada_tx_get_block_height" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_height),
             "namada_tx_get_block_header" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_header),
             "namada_tx_get_block_hash" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_hash),
+            "namada_tx_get_block_randomness" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_randomness),
             "namada_tx_get_block_epoch" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_epoch),
             "namada_tx_get_native_token" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_native_token),
             "namada_tx_log_string" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_log_string),
+            "namada_tx_debug_log" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_debug_log),
+            "namada_tx_invoke" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_invoke),
             "namada_tx_ibc_execute" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_ibc_execute),
+            "namada_tx_ibc_execute_msg" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_ibc_execute_msg),
             "namada_tx_set_commitment_sentinel" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_set_commitment_sentinel),
             "namada_tx_verify_tx_section_signature" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_verify_tx_section_signature),
         },
@@ -115,6 +120,9 @@ where
             "namada_vp_charge_gas" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_charge_gas),
             "namada_vp_read_pre" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_read_pre),
             "namada_vp_read_post" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_read_post),
+            "namada_vp_read_pre_block" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_read_pre_block),
+            "namada_vp_read_pre_batch" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_read_pre_batch),
+            "namada_vp_read_post_batch" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_read_post_batch),
             "namada_vp_read_temp" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_read_temp),
             "namada_vp_result_buffer" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_result_buffer),
             "namada_vp_has_key_pre" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_has_key_pre),
@@ -127,6 +135,7 @@ where
             "namada_vp_get_block_height" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_height),
             "namada_vp_get_block_header" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_header),
             "namada_vp_get_block_hash" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_hash),
+            "namada_vp_get_block_randomness" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_randomness),
             "namada_vp_get_tx_code_hash" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_tx_code_hash),
             "namada_vp_get_block_epoch" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_epoch),
             "namada_vp_get_ibc_events" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_ibc_events),
@@ -134,6 +143,7 @@ where
             "namada_vp_eval" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_eval),
             "namada_vp_get_native_token" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_native_token),
             "namada_vp_log_string" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_log_string),
+            "namada_vp_debug_log" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_debug_log),
         },
     }
 }
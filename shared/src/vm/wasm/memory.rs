@@ -43,28 +43,38 @@ pub type Result<T> = std::result::Result<T, Error>;
 // TODO set bounds to accommodate for wasm env size
 /// Initial pages in tx memory
 pub const TX_MEMORY_INIT_PAGES: u32 = 100; // 6.4 MiB
-/// Mamixmum pages in tx memory
+/// Default maximum pages in tx memory, as set by the default `WasmLimits`
 pub const TX_MEMORY_MAX_PAGES: u32 = 200; // 12.8 MiB
 /// Initial pages in VP memory
 pub const VP_MEMORY_INIT_PAGES: u32 = 100; // 6.4 MiB
-/// Mamixmum pages in VP memory
+/// Default maximum pages in VP memory, as set by the default `WasmLimits`
 pub const VP_MEMORY_MAX_PAGES: u32 = 200; // 12.8 MiB
-
-/// Prepare memory for instantiating a transaction module
-pub fn prepare_tx_memory(store: &wasmer::Store) -> Result<wasmer::Memory> {
+/// Maximum pages of a 32-bit WASM memory
+pub const WASM32_MAX_PAGES: u32 = 65_536; // 4 GiB
+
+/// Prepare memory for instantiating a transaction module. The memory can grow
+/// up to `max_pages`.
+pub fn prepare_tx_memory(
+    store: &wasmer::Store,
+    max_pages: u32,
+) -> Result<wasmer::Memory> {
     let mem_type = wasmer::MemoryType::new(
-        TX_MEMORY_INIT_PAGES,
-        Some(TX_MEMORY_MAX_PAGES),
+        TX_MEMORY_INIT_PAGES.min(max_pages),
+        Some(max_pages),
         false,
     );
     Memory::new(store, mem_type).map_err(Error::InitMemoryError)
 }
 
-/// Prepare memory for instantiating a validity predicate module
-pub fn prepare_vp_memory(store: &wasmer::Store) -> Result<wasmer::Memory> {
+/// Prepare memory for instantiating a validity predicate module. The memory
+/// can grow up to `max_pages`.
+pub fn prepare_vp_memory(
+    store: &wasmer::Store,
+    max_pages: u32,
+) -> Result<wasmer::Memory> {
     let mem_type = wasmer::MemoryType::new(
-        VP_MEMORY_INIT_PAGES,
-        Some(VP_MEMORY_MAX_PAGES),
+        VP_MEMORY_INIT_PAGES.min(max_pages),
+        Some(max_pages),
         false,
     );
     let memory =
@@ -302,16 +312,21 @@ pub struct Limit<T: Tunables> {
 }
 
 /// A [`Limit`] with memory limit setup for validity predicate WASM
-/// execution.
+/// execution. The maximum memory of a module is set by the `WasmLimits`
+/// parameter when its code is instrumented, so this only bounds the memories
+/// to the 32-bit WASM ceiling.
 pub fn vp_limit() -> Limit<BaseTunables> {
     let base = BaseTunables::for_target(&Target::default());
-    let limit = Pages(VP_MEMORY_MAX_PAGES);
+    let limit = Pages(WASM32_MAX_PAGES);
     Limit { limit, base }
 }
-/// A [`Limit`] with memory limit setup for transaction WASM execution.
+/// A [`Limit`] with memory limit setup for transaction WASM execution. The
+/// maximum memory of a module is set by the `WasmLimits` parameter when its
+/// code is instrumented, so this only bounds the memories to the 32-bit WASM
+/// ceiling.
 pub fn tx_limit() -> Limit<BaseTunables> {
     let base = BaseTunables::for_target(&Target::default());
-    let limit = Pages(TX_MEMORY_MAX_PAGES);
+    let limit = Pages(WASM32_MAX_PAGES);
     Limit { limit, base }
 }
 
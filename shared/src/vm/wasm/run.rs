@@ -1,5 +1,6 @@
 //! Wasm runners
 
+use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::marker::PhantomData;
 
@@ -15,6 +16,8 @@ use wasmer::{BaseTunables, Module, Store};
 use super::memory::{Limit, WasmMemory};
 use super::TxCache;
 use crate::ledger::gas::VpGasMeter;
+use crate::ledger::parameters::storage::get_wasm_limits_key;
+use crate::ledger::parameters::WasmLimits;
 use crate::ledger::storage::write_log::WriteLog;
 use crate::ledger::storage::{self, Storage, StorageHasher};
 use crate::proto::{Commitment, Section, Tx};
@@ -25,6 +28,7 @@ use crate::types::storage::{Key, TxIndex};
 use crate::vm::host_env::{TxVmEnv, VpCtx, VpEvaluator, VpVmEnv};
 use crate::vm::prefix_iter::PrefixIterators;
 use crate::vm::types::VpInput;
+use crate::vm::wasm::compilation_cache::common::module_hash;
 use crate::vm::wasm::host_env::{tx_imports, vp_imports};
 use crate::vm::wasm::{memory, Cache, CacheName, VpCache};
 use crate::vm::{
@@ -33,7 +37,6 @@ use crate::vm::{
 
 const TX_ENTRYPOINT: &str = "_apply_tx";
 const VP_ENTRYPOINT: &str = "_validate_tx";
-const WASM_STACK_LIMIT: u32 = u16::MAX as u32;
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -83,6 +86,8 @@ pub enum Error {
     ConversionError(String),
     #[error("Invalid transaction signature")]
     InvalidTxSignature,
+    #[error("Wasm limit exceeded: {0}")]
+    WasmLimitExceeded(String),
 }
 
 /// Result for functions that may fail
@@ -99,6 +104,7 @@ pub fn tx<DB, H, CA>(
     tx: &Tx,
     vp_wasm_cache: &mut VpCache<CA>,
     tx_wasm_cache: &mut TxCache<CA>,
+    debug_trace: &mut Option<Vec<String>>,
 ) -> Result<BTreeSet<Address>>
 where
     DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
@@ -145,13 +151,17 @@ where
         }
     }
 
+    let wasm_limits = read_wasm_limits(write_log, storage)?;
     let (module, store) = fetch_or_compile(
         tx_wasm_cache,
         &tx_code.code,
         write_log,
         storage,
         gas_meter,
+        &wasm_limits,
+        &ModuleLimits::tx(&wasm_limits),
     )?;
+    check_module_limits(&module, &wasm_limits)?;
 
     let mut iterators: PrefixIterators<'_, DB> = PrefixIterators::default();
     let mut verifiers = BTreeSet::new();
@@ -169,12 +179,14 @@ where
         tx_index,
         &mut verifiers,
         &mut result_buffer,
+        debug_trace,
         vp_wasm_cache,
         tx_wasm_cache,
     );
 
     let initial_memory =
-        memory::prepare_tx_memory(&store).map_err(Error::MemoryError)?;
+        memory::prepare_tx_memory(&store, wasm_limits.tx_memory_max_pages)
+            .map_err(Error::MemoryError)?;
     let imports = tx_imports(&store, initial_memory, env);
 
     // Instantiate the wasm module
@@ -230,6 +242,7 @@ pub fn vp<DB, H, CA>(
     keys_changed: &BTreeSet<Key>,
     verifiers: &BTreeSet<Address>,
     mut vp_wasm_cache: VpCache<CA>,
+    debug_trace: &mut Option<Vec<String>>,
 ) -> Result<bool>
 where
     DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
@@ -237,13 +250,17 @@ where
     CA: 'static + WasmCacheAccess,
 {
     // Compile the wasm module
+    let wasm_limits = read_wasm_limits(write_log, storage)?;
     let (module, store) = fetch_or_compile(
         &mut vp_wasm_cache,
         &Commitment::Hash(vp_code_hash),
         write_log,
         storage,
         gas_meter,
+        &wasm_limits,
+        &ModuleLimits::vp(&wasm_limits),
     )?;
+    check_module_limits(&module, &wasm_limits)?;
 
     let mut iterators: PrefixIterators<'_, DB> = PrefixIterators::default();
     let mut result_buffer: Option<Vec<u8>> = None;
@@ -266,13 +283,15 @@ where
         &mut iterators,
         verifiers,
         &mut result_buffer,
+        debug_trace,
         keys_changed,
         &eval_runner,
         &mut vp_wasm_cache,
     );
 
     let initial_memory =
-        memory::prepare_vp_memory(&store).map_err(Error::MemoryError)?;
+        memory::prepare_vp_memory(&store, wasm_limits.vp_memory_max_pages)
+            .map_err(Error::MemoryError)?;
     let imports = vp_imports(&store, initial_memory, env);
 
     match run_vp(
@@ -448,16 +467,21 @@ where
         };
 
         // Compile the wasm module
+        let wasm_limits = read_wasm_limits(write_log, storage)?;
         let (module, store) = fetch_or_compile(
             vp_wasm_cache,
             &Commitment::Hash(vp_code_hash),
             write_log,
             storage,
             gas_meter,
+            &wasm_limits,
+            &ModuleLimits::vp(&wasm_limits),
         )?;
+        check_module_limits(&module, &wasm_limits)?;
 
         let initial_memory =
-            memory::prepare_vp_memory(&store).map_err(Error::MemoryError)?;
+            memory::prepare_vp_memory(&store, wasm_limits.vp_memory_max_pages)
+                .map_err(Error::MemoryError)?;
 
         let imports = vp_imports(&store, initial_memory, env);
 
@@ -484,8 +508,48 @@ pub fn untrusted_wasm_store(limit: Limit<BaseTunables>) -> wasmer::Store {
     )
 }
 
-/// Inject gas counter and stack-height limiter into the given wasm code
-pub fn prepare_wasm_code<T: AsRef<[u8]>>(code: T) -> Result<Vec<u8>> {
+/// The limits instrumented into the code of a module before it's compiled,
+/// as set by the `WasmLimits` parameter. The compiled modules are cached per
+/// code and limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModuleLimits {
+    /// Maximum stack height of the execution
+    pub max_stack_height: u32,
+    /// Maximum number of pages the memory of the module can grow to
+    pub memory_max_pages: u32,
+}
+
+impl ModuleLimits {
+    /// The limits of a tx module
+    pub fn tx(wasm_limits: &WasmLimits) -> Self {
+        Self {
+            max_stack_height: wasm_limits.max_stack_height,
+            memory_max_pages: wasm_limits.tx_memory_max_pages,
+        }
+    }
+
+    /// The limits of a VP module
+    pub fn vp(wasm_limits: &WasmLimits) -> Self {
+        Self {
+            max_stack_height: wasm_limits.max_stack_height,
+            memory_max_pages: wasm_limits.vp_memory_max_pages,
+        }
+    }
+}
+
+impl Default for ModuleLimits {
+    /// The limits set by the default `WasmLimits`, which are the same for the
+    /// tx and VP modules
+    fn default() -> Self {
+        Self::tx(&WasmLimits::default())
+    }
+}
+
+/// Inject gas counter into the given wasm code. The instrumentation only
+/// depends on the code and the gas rules, so it produces the same output on
+/// every node and it's applied once at deploy time to the codes stored on
+/// chain.
+pub fn inject_gas_metering<T: AsRef<[u8]>>(code: T) -> Result<Vec<u8>> {
     let module: elements::Module = elements::deserialize_buffer(code.as_ref())
         .map_err(Error::DeserializationError)?;
     let module = wasm_instrument::gas_metering::inject(
@@ -496,20 +560,185 @@ pub fn prepare_wasm_code<T: AsRef<[u8]>>(code: T) -> Result<Vec<u8>> {
         &get_gas_rules(),
     )
     .map_err(|_original_module| Error::GasMeterInjection)?;
+    elements::serialize(module).map_err(Error::SerializationError)
+}
+
+/// Inject stack-height limiter into the given wasm code and cap the maximum of
+/// its memory. The limits are consensus parameters that can change, so they
+/// are instrumented when the code is compiled rather than at deploy time.
+pub fn instrument_limits<T: AsRef<[u8]>>(
+    code: T,
+    limits: &ModuleLimits,
+) -> Result<Vec<u8>> {
+    let mut module: elements::Module =
+        elements::deserialize_buffer(code.as_ref())
+            .map_err(Error::DeserializationError)?;
+    if let Some(section) = module.memory_section_mut() {
+        for entry in section.entries_mut() {
+            let initial = entry.limits().initial();
+            if initial > limits.memory_max_pages {
+                return Err(Error::WasmLimitExceeded(format!(
+                    "Memory of {} pages exceeds the limit of {} pages",
+                    initial, limits.memory_max_pages
+                )));
+            }
+            let maximum = entry
+                .limits()
+                .maximum()
+                .map_or(limits.memory_max_pages, |maximum| {
+                    maximum.min(limits.memory_max_pages)
+                });
+            *entry = elements::MemoryType::new(initial, Some(maximum));
+        }
+    }
     let module =
-        wasm_instrument::inject_stack_limiter(module, WASM_STACK_LIMIT)
+        wasm_instrument::inject_stack_limiter(module, limits.max_stack_height)
             .map_err(|_original_module| Error::StackLimiterInjection)?;
     elements::serialize(module).map_err(Error::SerializationError)
 }
 
-// Fetch or compile a WASM code from the cache or storage. Account for the
-// loading and code compilation gas costs.
+/// Inject gas counter, stack-height limiter and memory cap into the given
+/// wasm code.
+pub fn prepare_wasm_code<T: AsRef<[u8]>>(
+    code: T,
+    limits: &ModuleLimits,
+) -> Result<Vec<u8>> {
+    instrument_limits(inject_gas_metering(code)?, limits)
+}
+
+/// Read the gas-instrumented code of the given raw code hash, if it was
+/// instrumented at deploy time, and check it against its stored hash.
+fn read_instrumented_code<DB, H>(
+    code_hash: &Hash,
+    write_log: &WriteLog,
+    storage: &Storage<DB, H>,
+) -> Result<Option<Vec<u8>>>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    let read = |key: &Key| -> Result<Option<Vec<u8>>> {
+        match write_log.read(key).0 {
+            Some(StorageModification::Write { value }) => {
+                Ok(Some(value.clone()))
+            }
+            _ => Ok(storage
+                .read(key)
+                .map_err(|e| {
+                    Error::LoadWasmCode(format!(
+                        "Read instrumented wasm code failed from storage: key \
+                         {}, error {}",
+                        key, e
+                    ))
+                })?
+                .0),
+        }
+    };
+    let Some(code) = read(&Key::wasm_instrumented_code(code_hash))? else {
+        return Ok(None);
+    };
+    let hash_key = Key::wasm_instrumented_hash(code_hash);
+    let expected_hash = match read(&hash_key)? {
+        Some(v) => Hash::try_from_slice(&v)
+            .map_err(|e| Error::ConversionError(e.to_string()))?,
+        None => {
+            return Err(Error::LoadWasmCode(format!(
+                "No instrumented wasm code hash in storage: key {}",
+                hash_key
+            )));
+        }
+    };
+    let instrumented_hash = Hash::sha256(&code);
+    if instrumented_hash != expected_hash {
+        return Err(Error::LoadWasmCode(format!(
+            "Instrumented wasm code hash mismatch for code {}: expected {}, \
+             got {}",
+            code_hash, expected_hash, instrumented_hash
+        )));
+    }
+    Ok(Some(code))
+}
+
+/// Read the WASM execution limits parameter. Every execution must be subject
+/// to the same limits on all the nodes, so a missing parameter is an error.
+fn read_wasm_limits<DB, H>(
+    write_log: &WriteLog,
+    storage: &Storage<DB, H>,
+) -> Result<WasmLimits>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    let key = get_wasm_limits_key();
+    let value = match write_log.read(&key).0 {
+        Some(StorageModification::Write { value }) => Some(value.clone()),
+        _ => {
+            storage
+                .read(&key)
+                .map_err(|e| {
+                    Error::LoadWasmCode(format!(
+                        "Read wasm limits failed from storage: key {}, error \
+                         {}",
+                        key, e
+                    ))
+                })?
+                .0
+        }
+    };
+    match value {
+        Some(bytes) => WasmLimits::try_from_slice(&bytes)
+            .map_err(|e| Error::ConversionError(e.to_string())),
+        None => Err(Error::LoadWasmCode(format!(
+            "No wasm limits in storage: key {}",
+            key
+        ))),
+    }
+}
+
+/// Check the size of a WASM code against the limit
+fn check_code_size(code_len: u64, wasm_limits: &WasmLimits) -> Result<()> {
+    if code_len > wasm_limits.max_code_bytes {
+        return Err(Error::WasmLimitExceeded(format!(
+            "Code size of {} bytes exceeds the limit of {} bytes",
+            code_len, wasm_limits.max_code_bytes
+        )));
+    }
+    Ok(())
+}
+
+/// Check the tables declared by a compiled module against the limits. Tables
+/// cannot grow at runtime as the reference types feature is not allowed, so
+/// checking their initial size is sufficient. The memories are capped when
+/// the code is instrumented.
+fn check_module_limits(
+    module: &Module,
+    wasm_limits: &WasmLimits,
+) -> Result<()> {
+    let info = module.info();
+    if let Some(ty) = info
+        .tables
+        .values()
+        .find(|ty| ty.minimum > wasm_limits.max_table_elements)
+    {
+        return Err(Error::WasmLimitExceeded(format!(
+            "Table of {} elements exceeds the limit of {} elements",
+            ty.minimum, wasm_limits.max_table_elements
+        )));
+    }
+    Ok(())
+}
+
+// Fetch or compile a WASM code from the cache or storage, instrumented with the
+// given module limits. Account for the loading and code compilation gas costs.
+// Code exceeding the size limit is rejected before being compiled.
 fn fetch_or_compile<DB, H, CN, CA>(
     wasm_cache: &mut Cache<CN, CA>,
     code_or_hash: &Commitment,
     write_log: &WriteLog,
     storage: &Storage<DB, H>,
     gas_meter: &mut dyn GasMetering,
+    wasm_limits: &WasmLimits,
+    module_limits: &ModuleLimits,
 ) -> Result<(Module, Store)>
 where
     DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
@@ -519,7 +748,9 @@ where
 {
     match code_or_hash {
         Commitment::Hash(code_hash) => {
-            let (module, store, tx_len) = match wasm_cache.fetch(code_hash)? {
+            let (module, store, tx_len) = match wasm_cache
+                .fetch(&module_hash(code_hash, module_limits))?
+            {
                 Some((module, store)) => {
                     // Gas accounting even if the compiled module is in cache
                     let key = Key::wasm_code_len(code_hash);
@@ -549,14 +780,17 @@ where
                             ))),
                         },
                     }?;
+                    check_code_size(tx_len, wasm_limits)?;
 
                     (module, store, tx_len)
                 }
                 None => {
                     let key = Key::wasm_code(code_hash);
-                    let code = match write_log.read(&key).0 {
+                    // Borrow the code of the write log rather than cloning
+                    // the possibly multi-megabyte value
+                    let code: Cow<'_, [u8]> = match write_log.read(&key).0 {
                         Some(StorageModification::Write { value }) => {
-                            value.clone()
+                            Cow::Borrowed(value)
                         }
                         _ => match storage
                             .read(&key)
@@ -569,7 +803,7 @@ where
                             })?
                             .0
                         {
-                            Some(v) => v,
+                            Some(v) => Cow::Owned(v),
                             None => {
                                 return Err(Error::LoadWasmCode(format!(
                                     "No wasm code in storage: key {}",
@@ -580,8 +814,24 @@ where
                     };
                     let tx_len = u64::try_from(code.len())
                         .map_err(|e| Error::ConversionError(e.to_string()))?;
-
-                    match wasm_cache.compile_or_fetch(code)? {
+                    check_code_size(tx_len, wasm_limits)?;
+
+                    // Prefer the code gas-metered at deploy time, if any,
+                    // and only gas-meter the raw code otherwise
+                    let compiled = match read_instrumented_code(
+                        code_hash, write_log, storage,
+                    )? {
+                        Some(instrumented_code) => wasm_cache
+                            .compile_instrumented_or_fetch(
+                                code_hash,
+                                instrumented_code,
+                                module_limits,
+                            )?,
+                        None => {
+                            wasm_cache.compile_or_fetch(code, module_limits)?
+                        }
+                    };
+                    match compiled {
                         Some((module, store)) => (module, store, tx_len),
                         None => return Err(Error::NoCompiledWasmCode),
                     }
@@ -598,6 +848,7 @@ where
         }
         Commitment::Id(code) => {
             let tx_len = code.len() as u64;
+            check_code_size(tx_len, wasm_limits)?;
             gas_meter
                 .add_wasm_validation_gas(tx_len)
                 .map_err(|e| Error::GasError(e.to_string()))?;
@@ -606,7 +857,7 @@ where
             gas_meter
                 .add_compiling_gas(tx_len)
                 .map_err(|e| Error::GasError(e.to_string()))?;
-            match wasm_cache.compile_or_fetch(code)? {
+            match wasm_cache.compile_or_fetch(code, module_limits)? {
                 Some((module, store)) => Ok((module, store)),
                 None => Err(Error::NoCompiledWasmCode),
             }
@@ -646,6 +897,18 @@ mod tests {
 
     const TX_GAS_LIMIT: u64 = 10_000_000_000;
 
+    /// A test storage with the default WASM limits parameter
+    fn test_storage() -> TestStorage {
+        let mut storage = TestStorage::default();
+        storage
+            .write(
+                &get_wasm_limits_key(),
+                WasmLimits::default().serialize_to_vec(),
+            )
+            .unwrap();
+        storage
+    }
+
     /// Test that when a transaction wasm goes over the stack-height limit, the
     /// execution is aborted.
     #[test]
@@ -657,7 +920,7 @@ mod tests {
         // Because each call into `$loop` inside the wasm consumes 5 stack
         // heights except for the terminal call, this should hit the stack
         // limit.
-        let loops = WASM_STACK_LIMIT / 5 - 1;
+        let loops = WasmLimits::default().max_stack_height / 5 - 1;
 
         let error = loop_in_tx_wasm(loops).expect_err(&format!(
             "Expecting runtime error \"unreachable\" caused by stack-height \
@@ -682,7 +945,7 @@ mod tests {
         // Because each call into `$loop` inside the wasm consumes 5 stack
         // heights except for the terminal call, this should hit the stack
         // limit.
-        let loops = WASM_STACK_LIMIT / 5 - 1;
+        let loops = WasmLimits::default().max_stack_height / 5 - 1;
 
         let error = loop_in_vp_wasm(loops).expect_err(
             "Expecting runtime error caused by stack-height overflow. Got",
@@ -698,7 +961,7 @@ mod tests {
     /// wasm execution, the execution is aborted.
     #[test]
     fn test_tx_memory_limiter_in_guest() {
-        let storage = TestStorage::default();
+        let storage = test_storage();
         let mut write_log = WriteLog::default();
         let mut gas_meter = TxGasMeter::new_from_sub_limit(TX_GAS_LIMIT.into());
         let tx_index = TxIndex::default();
@@ -734,6 +997,7 @@ mod tests {
             &outer_tx,
             &mut vp_cache,
             &mut tx_cache,
+            &mut None,
         );
         assert!(result.is_ok(), "Expected success, got {:?}", result);
 
@@ -751,10 +1015,199 @@ mod tests {
             &outer_tx,
             &mut vp_cache,
             &mut tx_cache,
+            &mut None,
+        )
+        .expect_err("Expected to run out of memory");
+
+        assert_stack_overflow(&error);
+    }
+
+    /// Test that the WASM limits parameter read from storage is enforced on
+    /// tx execution.
+    #[test]
+    fn test_tx_wasm_limits_parameter() {
+        let storage = test_storage();
+        let mut write_log = WriteLog::default();
+        let mut gas_meter = TxGasMeter::new_from_sub_limit(TX_GAS_LIMIT.into());
+        let tx_index = TxIndex::default();
+        let (mut vp_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        let (mut tx_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+
+        // This code will allocate memory of the given size
+        let tx_code = TestWasms::TxMemoryLimit.read_bytes();
+
+        // Restrict the code size below the size of the tx code
+        let wasm_limits = WasmLimits {
+            max_code_bytes: tx_code.len() as u64 - 1,
+            ..Default::default()
+        };
+        write_log
+            .write(&get_wasm_limits_key(), wasm_limits.serialize_to_vec())
+            .unwrap();
+        let tx_data = 2_usize.pow(23).serialize_to_vec();
+        let mut outer_tx = Tx::from_type(TxType::Raw);
+        outer_tx.set_code(Code::new(tx_code.clone(), None));
+        outer_tx.set_data(Data::new(tx_data));
+        let error = tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+            &mut None,
+        )
+        .expect_err("Expected the code size limit to be exceeded");
+        assert!(matches!(error, Error::WasmLimitExceeded(_)));
+
+        // Allocating `2^23` (8 MiB) is below the default memory limit, but
+        // must fail with the memory restricted to 100 pages (6.4 MiB)
+        let wasm_limits = WasmLimits {
+            tx_memory_max_pages: 100,
+            ..Default::default()
+        };
+        write_log
+            .write(&get_wasm_limits_key(), wasm_limits.serialize_to_vec())
+            .unwrap();
+        let error = tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+            &mut None,
         )
         .expect_err("Expected to run out of memory");
+        assert_out_of_memory(&error);
 
+        // Allocating `2^24` (16 MiB) is above the default memory limit, but
+        // must succeed with the memory raised to 400 pages (25.6 MiB)
+        let wasm_limits = WasmLimits {
+            tx_memory_max_pages: 400,
+            ..Default::default()
+        };
+        write_log
+            .write(&get_wasm_limits_key(), wasm_limits.serialize_to_vec())
+            .unwrap();
+        let tx_data = 2_usize.pow(24).serialize_to_vec();
+        let mut outer_tx = Tx::from_type(TxType::Raw);
+        outer_tx.set_code(Code::new(tx_code, None));
+        outer_tx.set_data(Data::new(tx_data));
+        let result = tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+            &mut None,
+        );
+        assert!(result.is_ok(), "Expected success, got {:?}", result);
+    }
+
+    /// Test that the stack height limit of the WASM limits parameter is
+    /// enforced on tx execution.
+    #[test]
+    // NB: Disabled on aarch64 macOS since a fix for
+    // https://github.com/wasmerio/wasmer/issues/4072
+    // reduced the available stack space on mac
+    #[cfg_attr(all(target_arch = "aarch64", target_os = "macos"), ignore)]
+    fn test_tx_stack_limit_parameter() {
+        let wasm_limits = WasmLimits {
+            max_stack_height: 1_000,
+            ..Default::default()
+        };
+        // Because each call into `$loop` inside the wasm consumes 5 stack
+        // heights except for the terminal call, this should hit the stack
+        // limit.
+        let loops = wasm_limits.max_stack_height / 5 - 1;
+
+        let error = loop_in_tx_wasm_with_limits(loops, &wasm_limits)
+            .expect_err("Expected a stack-height overflow");
         assert_stack_overflow(&error);
+
+        // one less loop shouldn't go over the limit
+        let result = loop_in_tx_wasm_with_limits(loops - 1, &wasm_limits);
+        assert!(result.is_ok(), "Expected success. Got {:?}", result);
+    }
+
+    /// Test that the gas instrumentation is deterministic and that the code
+    /// instrumented at deploy time is checked against its stored hash.
+    #[test]
+    fn test_tx_instrumented_code_from_storage() {
+        let mut storage = test_storage();
+        let mut write_log = WriteLog::default();
+        let mut gas_meter = TxGasMeter::new_from_sub_limit(TX_GAS_LIMIT.into());
+        let tx_index = TxIndex::default();
+
+        let tx_code = TestWasms::TxNoOp.read_bytes();
+        let instrumented_code = inject_gas_metering(&tx_code).unwrap();
+        assert_eq!(instrumented_code, inject_gas_metering(&tx_code).unwrap());
+        assert_ne!(instrumented_code, tx_code);
+
+        // store the raw and instrumented tx codes
+        let code_hash = Hash::sha256(&tx_code);
+        let code_len = (tx_code.len() as u64).serialize_to_vec();
+        storage.write(&Key::wasm_code(&code_hash), tx_code).unwrap();
+        storage
+            .write(&Key::wasm_code_len(&code_hash), code_len)
+            .unwrap();
+        storage
+            .write(&Key::wasm_instrumented_code(&code_hash), &instrumented_code)
+            .unwrap();
+        storage
+            .write(
+                &Key::wasm_instrumented_hash(&code_hash),
+                Hash::sha256(&instrumented_code).serialize_to_vec(),
+            )
+            .unwrap();
+
+        let mut outer_tx = Tx::from_type(TxType::Raw);
+        outer_tx.set_code(Code::from_hash(code_hash, None));
+        let (mut vp_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        let (mut tx_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        let result = tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+            &mut None,
+        );
+        assert!(result.is_ok(), "Expected success, got {:?}", result);
+
+        // An instrumented code that doesn't match its stored hash must be
+        // rejected
+        write_log
+            .write(
+                &Key::wasm_instrumented_hash(&code_hash),
+                Hash::sha256(b"tampered").serialize_to_vec(),
+            )
+            .unwrap();
+        let (mut tx_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        let error = tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+            &mut None,
+        )
+        .expect_err("Expected the instrumented code hash to mismatch");
+        assert!(matches!(error, Error::LoadWasmCode(_)));
     }
 
     /// Test that when a validity predicate wasm goes over the memory limit
@@ -762,7 +1215,7 @@ mod tests {
     /// fails and hence returns `false`.
     #[test]
     fn test_vp_memory_limiter_in_guest_calling_eval() {
-        let mut storage = TestStorage::default();
+        let mut storage = test_storage();
         let addr = storage.address_gen.generate_address("rng seed");
         let write_log = WriteLog::default();
         let mut gas_meter = VpGasMeter::new_from_tx_meter(
@@ -823,6 +1276,7 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache.clone(),
+            &mut None,
         )
         .unwrap();
         assert!(passed);
@@ -855,6 +1309,7 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache,
+            &mut None,
         )
         .unwrap();
 
@@ -865,7 +1320,7 @@ mod tests {
     /// inside the wasm execution, the execution is aborted.
     #[test]
     fn test_vp_memory_limiter_in_guest() {
-        let mut storage = TestStorage::default();
+        let mut storage = test_storage();
         let addr = storage.address_gen.generate_address("rng seed");
         let write_log = WriteLog::default();
         let mut gas_meter = VpGasMeter::new_from_tx_meter(
@@ -907,6 +1362,7 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache.clone(),
+            &mut None,
         );
         assert!(result.is_ok(), "Expected success, got {:?}", result);
 
@@ -927,6 +1383,7 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache,
+            &mut None,
         )
         .expect_err("Expected to run out of memory");
 
@@ -937,7 +1394,7 @@ mod tests {
     /// host input, the execution fails.
     #[test]
     fn test_tx_memory_limiter_in_host_input() {
-        let storage = TestStorage::default();
+        let storage = test_storage();
         let mut write_log = WriteLog::default();
         let mut gas_meter = TxGasMeter::new_from_sub_limit(TX_GAS_LIMIT.into());
         let tx_index = TxIndex::default();
@@ -973,6 +1430,7 @@ mod tests {
             &outer_tx,
             &mut vp_cache,
             &mut tx_cache,
+            &mut None,
         );
         // Depending on platform, we get a different error from the running out
         // of memory
@@ -997,7 +1455,7 @@ mod tests {
     /// in the host input, the execution fails.
     #[test]
     fn test_vp_memory_limiter_in_host_input() {
-        let mut storage = TestStorage::default();
+        let mut storage = test_storage();
         let addr = storage.address_gen.generate_address("rng seed");
         let write_log = WriteLog::default();
         let mut gas_meter = VpGasMeter::new_from_tx_meter(
@@ -1039,6 +1497,7 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache,
+            &mut None,
         );
         // Depending on platform, we get a different error from the running out
         // of memory
@@ -1066,7 +1525,7 @@ mod tests {
     /// execution is aborted.
     #[test]
     fn test_tx_memory_limiter_in_host_env() {
-        let mut storage = TestStorage::default();
+        let mut storage = test_storage();
         let mut write_log = WriteLog::default();
         let mut gas_meter = TxGasMeter::new_from_sub_limit(TX_GAS_LIMIT.into());
         let tx_index = TxIndex::default();
@@ -1107,6 +1566,7 @@ mod tests {
             &outer_tx,
             &mut vp_cache,
             &mut tx_cache,
+            &mut None,
         )
         .expect_err("Expected to run out of memory");
 
@@ -1118,7 +1578,7 @@ mod tests {
     /// execution, the execution is aborted.
     #[test]
     fn test_vp_memory_limiter_in_host_env() {
-        let mut storage = TestStorage::default();
+        let mut storage = test_storage();
         let addr = storage.address_gen.generate_address("rng seed");
         let write_log = WriteLog::default();
         let mut gas_meter = VpGasMeter::new_from_tx_meter(
@@ -1165,6 +1625,7 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache,
+            &mut None,
         )
         .expect_err("Expected to run out of memory");
 
@@ -1177,7 +1638,7 @@ mod tests {
     /// and hence returns `false`.
     #[test]
     fn test_vp_memory_limiter_in_host_env_inside_guest_calling_eval() {
-        let mut storage = TestStorage::default();
+        let mut storage = test_storage();
         let addr = storage.address_gen.generate_address("rng seed");
         let write_log = WriteLog::default();
         let mut gas_meter = VpGasMeter::new_from_tx_meter(
@@ -1242,12 +1703,20 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache,
+            &mut None,
         )
         .unwrap();
         assert!(!passed);
     }
 
     fn loop_in_tx_wasm(loops: u32) -> Result<BTreeSet<Address>> {
+        loop_in_tx_wasm_with_limits(loops, &WasmLimits::default())
+    }
+
+    fn loop_in_tx_wasm_with_limits(
+        loops: u32,
+        wasm_limits: &WasmLimits,
+    ) -> Result<BTreeSet<Address>> {
         // A transaction with a recursive loop.
         // The boilerplate code is generated from tx_template.wasm using
         // `wasm2wat` and the loop code is hand-written.
@@ -1284,8 +1753,11 @@ mod tests {
 
         let tx_data = vec![];
         let tx_index = TxIndex::default();
-        let storage = TestStorage::default();
+        let storage = test_storage();
         let mut write_log = WriteLog::default();
+        write_log
+            .write(&get_wasm_limits_key(), wasm_limits.serialize_to_vec())
+            .unwrap();
         let mut gas_meter = TxGasMeter::new_from_sub_limit(TX_GAS_LIMIT.into());
         let (mut vp_cache, _) =
             wasm::compilation_cache::common::testing::cache();
@@ -1312,6 +1784,7 @@ mod tests {
             &outer_tx,
             &mut vp_cache,
             &mut tx_cache,
+            &mut None,
         )
     }
 
@@ -1346,7 +1819,7 @@ mod tests {
 
         let outer_tx = Tx::from_type(TxType::Raw);
         let tx_index = TxIndex::default();
-        let mut storage = TestStorage::default();
+        let mut storage = test_storage();
         let addr = storage.address_gen.generate_address("rng seed");
         let write_log = WriteLog::default();
         let mut gas_meter = VpGasMeter::new_from_tx_meter(
@@ -1374,6 +1847,7 @@ mod tests {
             &keys_changed,
             &verifiers,
             vp_cache,
+            &mut None,
         )
     }
 
@@ -1389,6 +1863,15 @@ mod tests {
         }
     }
 
+    fn assert_out_of_memory(error: &Error) {
+        // When the memory cannot grow any further, the allocator of the guest
+        // aborts the execution
+        assert_eq!(
+            get_trap_code(error),
+            Either::Left(wasmer_vm::TrapCode::UnreachableCodeReached)
+        );
+    }
+
     fn assert_stack_overflow(error: &Error) {
         let trap_code = get_trap_code(error);
         // Depending on platform, we get a different error from the overflow
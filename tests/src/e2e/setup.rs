@@ -19,7 +19,7 @@ use expectrl::{ControlCode, Eof, WaitStatus};
 use eyre::eyre;
 use itertools::{Either, Itertools};
 use namada::types::chain::ChainId;
-use namada_apps::cli::context::ENV_VAR_CHAIN_ID;
+use namada_apps::cli::context::{ENV_VAR_CHAIN_ID, ENV_VAR_SKIP_CONFIRMATION};
 use namada_apps::client::utils::{
     self, validator_pre_genesis_dir, validator_pre_genesis_txs_file,
 };
@@ -551,6 +551,8 @@ pub fn network(
 
     // Set the chain id
     std::env::set_var(ENV_VAR_CHAIN_ID, net.chain_id.to_string());
+    // Sign the txs submitted by the tests without asking for confirmation
+    std::env::set_var(ENV_VAR_SKIP_CONFIRMATION, "true");
 
     Ok(Test {
         working_dir,
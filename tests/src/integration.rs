@@ -1,2 +1,3 @@
+mod ledger_tests;
 mod masp;
 mod setup;
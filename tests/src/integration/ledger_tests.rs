@@ -0,0 +1,42 @@
+use color_eyre::eyre::Result;
+use namada::ledger::parameters::storage::get_epoch_duration_storage_key;
+use namada::ledger::parameters::EpochDuration;
+use namada::types::time::{DateTimeUtc, DurationSecs};
+use test_log::test;
+
+use super::setup;
+
+/// In this test we verify that the epochs of the in-process node only advance
+/// when both the minimum number of blocks and the minimum duration of an
+/// epoch have passed, as controlled by the test.
+#[test]
+fn epochs_follow_block_time() -> Result<()> {
+    let (node, _services) = setup::setup()?;
+    let EpochDuration {
+        min_num_of_blocks,
+        min_duration,
+    } = node
+        .read(&get_epoch_duration_storage_key())
+        .expect("The epoch duration must be in storage");
+
+    // Let the genesis epoch end
+    node.set_block_time(DateTimeUtc::now());
+    node.advance_blocks(2 * min_num_of_blocks + 2);
+    let epoch = node.current_epoch();
+
+    // Without the time passing, the epoch doesn't change
+    let height = node.last_block_height();
+    node.advance_blocks(2 * min_num_of_blocks + 2);
+    assert_eq!(node.current_epoch(), epoch);
+    assert_eq!(
+        node.last_block_height().0,
+        height.0 + 2 * min_num_of_blocks + 2
+    );
+
+    // Once the minimum duration has passed, the next epoch starts
+    node.advance_time(DurationSecs(min_duration.0 + 1));
+    node.advance_blocks(min_num_of_blocks + 2);
+    assert_eq!(node.current_epoch(), epoch.next());
+
+    Ok(())
+}
@@ -2,6 +2,13 @@ use std::path::PathBuf;
 
 use color_eyre::eyre::Result;
 use color_eyre::owo_colors::OwoColorize;
+use namada::core::ledger::{masp_commitment_tree, masp_nullifiers};
+use namada::ledger::storage_api::token::MaspTxRecord;
+use namada::ledger::storage_api::{self, StorageRead, StorageWrite};
+use namada::types::token::{masp_head_tx_key, masp_tx_key};
+use namada_apps::node::ledger::shell::migrations::{
+    migrations, MASP_NOTES_UPGRADE,
+};
 use namada_apps::node::ledger::shell::testing::client::run;
 use namada_apps::node::ledger::shell::testing::utils::{Bin, CapturedOutput};
 use namada_sdk::masp::fs::FsShieldedUtils;
@@ -1248,3 +1255,104 @@ fn wrapper_fee_unshielding() -> Result<()> {
     assert!(tx_run);
     Ok(())
 }
+
+/// In this test we verify that the migrations of the upgrade from which the
+/// MASP nullifiers are kept reveal the nullifiers of the recorded shielded
+/// txs, so that the notes they spent can't be spent again after the upgrade.
+#[test]
+fn masp_nullifiers_seeded_on_upgrade() -> Result<()> {
+    // This address doesn't matter for tests. But an argument is required.
+    let validator_one_rpc = "127.0.0.1:26567";
+    // Download the shielded pool parameters before starting node
+    let _ = FsShieldedUtils::new(PathBuf::new());
+
+    let (mut node, _services) = setup::setup()?;
+    _ = node.next_epoch();
+    // Shield 20 BTC to PA(A), then spend 7 BTC of it to PA(B)
+    for tx_args in [
+        vec![
+            "transfer",
+            "--source",
+            ALBERT,
+            "--target",
+            AA_PAYMENT_ADDRESS,
+            "--token",
+            BTC,
+            "--amount",
+            "20",
+            "--node",
+            validator_one_rpc,
+        ],
+        vec![
+            "transfer",
+            "--source",
+            A_SPENDING_KEY,
+            "--target",
+            AB_PAYMENT_ADDRESS,
+            "--token",
+            BTC,
+            "--amount",
+            "7",
+            "--gas-payer",
+            CHRISTEL_KEY,
+            "--node",
+            validator_one_rpc,
+        ],
+    ] {
+        node.next_epoch();
+        let captured =
+            CapturedOutput::of(|| run(&node, Bin::Client, tx_args.clone()));
+        assert!(captured.result.is_ok());
+        assert!(captured.contains("Transaction is valid"));
+    }
+
+    let mut locked = node.shell.lock().unwrap();
+    let head_tx_idx: u64 = locked
+        .wl_storage
+        .read(&masp_head_tx_key())?
+        .unwrap_or_default();
+    let mut spent = Vec::new();
+    for idx in 0..head_tx_idx {
+        let record: Option<MaspTxRecord> =
+            locked.wl_storage.read(&masp_tx_key(idx))?;
+        if let Some((_epoch, _height, _tx_index, _transfer, shielded)) = record
+        {
+            spent.extend(masp_nullifiers::nullifiers(&shielded));
+        }
+    }
+    assert!(!spent.is_empty());
+
+    // Drop the note commitment tree and the nullifiers, as if the txs had
+    // been applied by the previous binary
+    for prefix in [
+        masp_commitment_tree::tree_prefix(),
+        masp_nullifiers::nullifiers_prefix(),
+    ] {
+        let keys = storage_api::iter_prefix_bytes(&locked.wl_storage, &prefix)?
+            .map(|res| res.map(|(key, _val)| key))
+            .collect::<storage_api::Result<Vec<_>>>()?;
+        for key in keys {
+            locked.wl_storage.delete(&key)?;
+        }
+    }
+    for nullifier in &spent {
+        assert!(!masp_nullifiers::is_revealed(
+            &locked.wl_storage,
+            nullifier
+        )?);
+    }
+
+    for migration in migrations(MASP_NOTES_UPGRADE) {
+        migration(&mut locked.wl_storage)?;
+    }
+
+    // The notes spent before the upgrade can't be spent again
+    for nullifier in &spent {
+        assert!(masp_nullifiers::is_revealed(&locked.wl_storage, nullifier)?);
+    }
+    assert!(
+        masp_nullifiers::reveal_nullifiers(&mut locked.wl_storage, &spent)
+            .is_err()
+    );
+    Ok(())
+}
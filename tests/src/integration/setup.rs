@@ -5,6 +5,7 @@ use std::sync::{Arc, Mutex};
 
 use color_eyre::eyre::{eyre, Result};
 use namada_apps::cli::args;
+use namada_apps::cli::context::ENV_VAR_SKIP_CONFIRMATION;
 use namada_apps::client::utils::PRE_GENESIS_DIR;
 use namada_apps::config;
 use namada_apps::config::genesis::chain::Finalized;
@@ -42,6 +43,8 @@ pub fn initialize_genesis() -> Result<(MockNode, MockServicesController)> {
     };
     let test_dir = TestDir::new();
     let template_dir = working_dir.join(SINGLE_NODE_NET_GENESIS);
+    // Sign the txs submitted by the tests without asking for confirmation
+    std::env::set_var(ENV_VAR_SKIP_CONFIRMATION, "true");
 
     // Copy genesis files to test directory.
     let templates = templates::All::read_toml_files(&template_dir)
@@ -72,8 +75,10 @@ pub fn initialize_genesis() -> Result<(MockNode, MockServicesController)> {
     let global_args = args::Global {
         is_pre_genesis: true,
         chain_id: Some(chain_id.clone()),
+        profile: None,
         base_dir: test_dir.path().to_path_buf(),
         wasm_dir: Some(test_dir.path().join(chain_id.as_str()).join("wasm")),
+        output: args::OutputFormat::Text,
     };
     // setup genesis file
     namada_apps::client::utils::init_network(
@@ -191,6 +196,7 @@ fn create_node(
         services: Arc::new(services),
         results: Arc::new(Mutex::new(vec![])),
         auto_drive_services,
+        block_time: Arc::new(Mutex::new(None)),
     };
     let init_req =
         namada_apps::facade::tendermint::v0_37::abci::request::InitChain {
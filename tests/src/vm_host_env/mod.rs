@@ -28,11 +28,20 @@ mod tests {
     use namada::ledger::native_vp::ibc::{
         get_dummy_header as tm_dummy_header, Error as IbcError,
     };
+    use namada::ledger::storage_api::collections::LazyMap;
+    use namada::ledger::storage_api::ErrorCode;
     use namada::ledger::tx_env::TxEnv;
+    use namada::proof_of_stake::parameters::OwnedPosParams;
+    use namada::proof_of_stake::types::{
+        GenesisValidator, ValidatorState, WeightedValidator,
+    };
     use namada::proto::Tx;
+    use namada::types::dec::Dec;
     use namada::types::hash::Hash;
     use namada::types::key::*;
-    use namada::types::storage::{self, BlockHash, BlockHeight, Key, KeySeg};
+    use namada::types::storage::{
+        self, BlockHash, BlockHeight, Epoch, Key, KeySeg,
+    };
     use namada::types::time::DateTimeUtc;
     use namada::types::token::{self, Amount};
     use namada::types::{address, key};
@@ -41,13 +50,15 @@ mod tests {
     use namada_test_utils::TestWasms;
     use namada_tx_prelude::address::InternalAddress;
     use namada_tx_prelude::chain::ChainId;
-    use namada_tx_prelude::{Address, StorageRead, StorageWrite};
+    use namada_tx_prelude::proof_of_stake as tx_pos;
+    use namada_tx_prelude::{gov_storage, Address, StorageRead, StorageWrite};
     use namada_vp_prelude::account::AccountPublicKeysMap;
-    use namada_vp_prelude::VpEnv;
+    use namada_vp_prelude::{pos_views, VpEnv};
     use prost::Message;
     use test_log::test;
 
     use super::{ibc, tx, vp};
+    use crate::native_vp::pos::init_pos;
     use crate::tx::{tx_host_env, TestTxEnv};
     use crate::vp::{vp_host_env, TestVpEnv};
 
@@ -85,6 +96,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tx_read_batch() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        let key_1 = storage::Key::parse("key_1").unwrap();
+        let key_2 = storage::Key::parse("key_2").unwrap();
+        let value = "test".repeat(4);
+        tx::ctx().write(&key_1, value.clone()).unwrap();
+
+        let values = tx::ctx().read_batch(&[key_1, key_2]).unwrap();
+        assert_eq!(
+            values,
+            vec![Some(value.serialize_to_vec()), None],
+            "The values must be read in the order of the keys"
+        );
+        assert!(tx::ctx().read_batch(&[]).unwrap().is_empty());
+    }
+
     #[test]
     fn test_tx_has_key() {
         // The environment must be initialized first
@@ -106,6 +136,28 @@ mod tests {
         );
     }
 
+    /// Test that an invalid key is returned to the tx and VP as the code of a
+    /// decoding error, rather than aborting the execution
+    #[test]
+    fn test_storage_error_code_round_trip() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        // A key without any segments can't be parsed back by the host
+        let key = storage::Key::default();
+        let err = tx::ctx().has_key(&key).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Decode);
+        let err = tx::ctx().read_bytes(&key).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Decode);
+
+        let addr = address::testing::established_address_1();
+        vp_host_env::init_from_tx(addr, TestTxEnv::default(), |_addr| {});
+        let err = vp::CTX.has_key_pre(&key).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Decode);
+        let err = vp::CTX.read_bytes_post(&key).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Decode);
+    }
+
     #[test]
     fn test_tx_delete() {
         // The environment must be initialized first
@@ -184,6 +236,55 @@ mod tests {
         itertools::assert_equal(iter, expected);
     }
 
+    /// Test that the iteration of a lazy map in a tx observes the tx's pending
+    /// writes merged over the storage as they were at the creation of the
+    /// iterator
+    #[test]
+    fn test_tx_lazy_map_iter_snapshot() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        let map = LazyMap::<u64, String>::open(Key::parse("map").unwrap());
+        // Commit some elements in a previous block
+        tx_host_env::with(|env| {
+            for i in 0..3 {
+                map.insert(&mut env.wl_storage, i, format!("committed {i}"))
+                    .unwrap();
+            }
+            env.commit_tx_and_block();
+        });
+
+        // Override, delete and add an element in the tx
+        map.insert(tx::ctx(), 0, "tx 0".to_string()).unwrap();
+        map.remove(tx::ctx(), &1).unwrap();
+        map.insert(tx::ctx(), 3, "tx 3".to_string()).unwrap();
+
+        let mut iter = map.iter(tx::ctx()).unwrap();
+        // The writes made while iterating aren't observed
+        map.insert(tx::ctx(), 4, "tx 4".to_string()).unwrap();
+        map.remove(tx::ctx(), &2).unwrap();
+        let mut elements = vec![];
+        for element in iter.by_ref() {
+            elements.push(element.unwrap());
+        }
+        assert_eq!(
+            elements,
+            vec![
+                (0, "tx 0".to_string()),
+                (2, "committed 2".to_string()),
+                (3, "tx 3".to_string()),
+            ]
+        );
+
+        // A new iterator observes all the writes of the tx
+        let keys = map
+            .iter(tx::ctx())
+            .unwrap()
+            .map(|element| element.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![0, 3, 4]);
+    }
+
     #[test]
     fn test_tx_insert_verifier() {
         // The environment must be initialized first
@@ -231,6 +332,31 @@ mod tests {
         tx::ctx().init_account(code_hash, &None).unwrap();
     }
 
+    #[test]
+    fn test_tx_invoke() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        // The invoked tx writes the min proposal grace epoch
+        let code = TestWasms::TxProposalCode.read_bytes();
+        let code_hash = Hash::sha256(&code);
+        tx_host_env::with(|env| {
+            // store wasm code
+            let key = Key::wasm_code(&code_hash);
+            let len_key = Key::wasm_code_len(&code_hash);
+            let code_len = (code.len() as u64).serialize_to_vec();
+            env.wl_storage.storage.write(&key, code.clone()).unwrap();
+            env.wl_storage.storage.write(&len_key, code_len).unwrap();
+        });
+        tx::ctx().invoke_tx(&code_hash, &()).unwrap();
+
+        // The storage modification of the invoked tx must be visible to the
+        // caller
+        let key = gov_storage::keys::get_min_proposal_grace_epoch_key();
+        let value: Option<u64> = tx::ctx().read(&key).unwrap();
+        assert_eq!(value, Some(9));
+    }
+
     #[test]
     fn test_tx_get_metadata() {
         // The environment must be initialized first
@@ -252,6 +378,15 @@ mod tests {
             tx::ctx().get_block_hash().unwrap(),
             tx_host_env::with(|env| env.wl_storage.storage.get_block_hash().0)
         );
+        tx_host_env::with(|env| {
+            env.wl_storage
+                .storage
+                .set_block_randomness(Hash::sha256(b"randomness"))
+        });
+        assert_eq!(
+            tx::ctx().get_block_randomness().unwrap(),
+            Hash::sha256(b"randomness")
+        );
         assert_eq!(
             tx::ctx().get_block_epoch().unwrap(),
             tx_host_env::with(|env| env
@@ -270,6 +405,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tx_debug_trace() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        namada_tx_prelude::debug_trace("traced");
+        tx_host_env::with(|env| {
+            assert_eq!(env.debug_trace, Some(vec!["traced".to_owned()]));
+            // Disable the trace, as when a tx is applied in a block
+            env.debug_trace = None;
+        });
+
+        namada_tx_prelude::debug_trace("not traced");
+        tx_host_env::with(|env| assert_eq!(env.debug_trace, None));
+    }
+
     /// An example how to write a VP host environment integration test
     #[test]
     fn test_vp_host_env() {
@@ -372,6 +523,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vp_read_batch() {
+        let mut tx_env = TestTxEnv::default();
+
+        let addr = address::testing::established_address_1();
+        let addr_key = storage::Key::from(addr.to_db_key());
+
+        // Write some value to storage and commit it
+        let existing_key = addr_key.join(&Key::parse("existing_key").unwrap());
+        let existing_value = "existing".to_string();
+        tx_env
+            .wl_storage
+            .write(&existing_key, &existing_value)
+            .unwrap();
+        tx_env.wl_storage.commit_tx();
+
+        // In a transaction, override the existing key and add a new one
+        let override_value = "override".to_string();
+        let new_key = addr_key.join(&Key::parse("new_key").unwrap());
+        let new_value = "new".to_string();
+        vp_host_env::init_from_tx(addr, tx_env, |_addr| {
+            tx::ctx().write(&existing_key, &override_value).unwrap();
+            tx::ctx().write(&new_key, &new_value).unwrap();
+        });
+
+        let keys = [existing_key, new_key];
+        assert_eq!(
+            vp::CTX.pre().read_batch(&keys).unwrap(),
+            vec![Some(existing_value.serialize_to_vec()), None]
+        );
+        assert_eq!(
+            vp::CTX.post().read_batch(&keys).unwrap(),
+            vec![
+                Some(override_value.serialize_to_vec()),
+                Some(new_value.serialize_to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vp_read_pre_block() {
+        let mut tx_env = TestTxEnv::default();
+
+        let addr = address::testing::established_address_1();
+        let addr_key = storage::Key::from(addr.to_db_key());
+        let key = addr_key.join(&Key::parse("key").unwrap());
+
+        // Write a value and commit it in a previous block
+        let committed_value = "committed".to_string();
+        tx_env.wl_storage.write(&key, &committed_value).unwrap();
+        tx_env.commit_tx_and_block();
+
+        // Override it in a tx before the current one in the block
+        let block_value = "block".to_string();
+        tx_env.wl_storage.write(&key, &block_value).unwrap();
+        tx_env.wl_storage.commit_tx();
+
+        // Override it again in the current tx
+        let tx_value = "tx".to_string();
+        vp_host_env::init_from_tx(addr, tx_env, |_addr| {
+            tx::ctx().write(&key, &tx_value).unwrap();
+        });
+
+        let pre_block: Option<String> = vp::CTX.read_pre_block(&key).unwrap();
+        assert_eq!(pre_block, Some(committed_value));
+        let pre: Option<String> = vp::CTX.read_pre(&key).unwrap();
+        assert_eq!(pre, Some(block_value));
+        let post: Option<String> = vp::CTX.read_post(&key).unwrap();
+        assert_eq!(post, Some(tx_value));
+        assert!(vp::CTX.has_key_pre_block(&key).unwrap());
+    }
+
     #[test]
     fn test_vp_iter_prefix() {
         let mut tx_env = TestTxEnv::default();
@@ -433,6 +656,120 @@ mod tests {
         itertools::assert_equal(iter_post, expected_post);
     }
 
+    /// Test that a VP can iterate a lazy map without the writes of the tx
+    #[test]
+    fn test_vp_lazy_map_iter_pre() {
+        let mut tx_env = TestTxEnv::default();
+
+        let addr = address::testing::established_address_1();
+        let map = LazyMap::<u64, u64>::open(
+            Key::from(addr.to_db_key()).join(&Key::parse("map").unwrap()),
+        );
+        for i in 0..3 {
+            map.insert(&mut tx_env.wl_storage, i, i).unwrap();
+        }
+        tx_env.commit_tx_and_block();
+
+        vp_host_env::init_from_tx(addr, tx_env, |_addr| {
+            map.insert(tx::ctx(), 0, 100).unwrap();
+            map.remove(tx::ctx(), &1).unwrap();
+        });
+
+        let pre = map.iter_pre(&vp::CTX).unwrap().collect::<Vec<_>>();
+        assert_eq!(pre, vec![(0, 0), (1, 1), (2, 2)]);
+        let post = map
+            .iter(&vp::CTX.post())
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(post, vec![(0, 100), (2, 2)]);
+    }
+
+    /// Test that the read-only views of the PoS state in the tx and VP
+    /// preludes read the genesis validator
+    #[test]
+    fn test_pos_views() {
+        let validator = address::testing::established_address_2();
+        let stake = token::Amount::native_whole(1_000);
+        let genesis_validators = [GenesisValidator {
+            address: validator.clone(),
+            tokens: stake,
+            consensus_key: key::testing::keypair_1().ref_to(),
+            protocol_key: key::testing::keypair_2().ref_to(),
+            eth_cold_key: key::testing::keypair_3().ref_to(),
+            eth_hot_key: key::testing::keypair_4().ref_to(),
+            commission_rate: Dec::new(5, 2).unwrap(),
+            max_commission_rate_change: Dec::new(1, 2).unwrap(),
+            metadata: Default::default(),
+        }];
+        let epoch = Epoch(0);
+        init_pos(&genesis_validators, &OwnedPosParams::default(), epoch);
+        let non_validator = address::testing::established_address_3();
+        let weighted = WeightedValidator {
+            bonded_stake: stake,
+            address: validator.clone(),
+        };
+
+        let ctx = tx::ctx();
+        assert_eq!(
+            tx_pos::validator_state(ctx, &validator, epoch).unwrap(),
+            Some(ValidatorState::Consensus)
+        );
+        assert_eq!(
+            tx_pos::validator_state(ctx, &non_validator, epoch).unwrap(),
+            None
+        );
+        assert!(tx_pos::is_in_consensus_set(ctx, &validator, epoch).unwrap());
+        assert_eq!(
+            tx_pos::validator_stake(ctx, &validator, epoch).unwrap(),
+            stake
+        );
+        assert_eq!(tx_pos::total_stake(ctx, epoch).unwrap(), stake);
+        assert_eq!(
+            tx_pos::bonded_amount(ctx, &validator, &validator, epoch).unwrap(),
+            stake
+        );
+        assert_eq!(
+            tx_pos::consensus_validators(ctx, epoch).unwrap(),
+            BTreeSet::from([weighted.clone()])
+        );
+
+        vp_host_env::init_from_tx(
+            validator.clone(),
+            tx_host_env::take(),
+            |_addr| {},
+        );
+        assert_eq!(
+            pos_views::pre_validator_state(&vp::CTX, &validator, epoch)
+                .unwrap(),
+            Some(ValidatorState::Consensus)
+        );
+        let is_consensus =
+            pos_views::pre_is_in_consensus_set(&vp::CTX, &non_validator, epoch)
+                .unwrap();
+        assert!(!is_consensus);
+        assert_eq!(
+            pos_views::pre_validator_stake(&vp::CTX, &validator, epoch)
+                .unwrap(),
+            stake
+        );
+        assert_eq!(pos_views::pre_total_stake(&vp::CTX, epoch).unwrap(), stake);
+        assert_eq!(
+            pos_views::pre_bonded_amount(
+                &vp::CTX,
+                &validator,
+                &validator,
+                epoch
+            )
+            .unwrap(),
+            stake
+        );
+        assert_eq!(
+            pos_views::pre_consensus_validators(&vp::CTX, epoch).unwrap(),
+            BTreeSet::from([weighted])
+        );
+    }
+
     #[test]
     fn test_vp_verify_tx_signature() {
         let mut env = TestVpEnv::default();
@@ -522,6 +859,15 @@ mod tests {
             vp::CTX.get_block_hash().unwrap(),
             vp_host_env::with(|env| env.wl_storage.storage.get_block_hash().0)
         );
+        vp_host_env::with(|env| {
+            env.wl_storage
+                .storage
+                .set_block_randomness(Hash::sha256(b"randomness"))
+        });
+        assert_eq!(
+            vp::CTX.get_block_randomness().unwrap(),
+            Hash::sha256(b"randomness")
+        );
         assert_eq!(
             vp::CTX.get_block_epoch().unwrap(),
             vp_host_env::with(|env| env
@@ -540,6 +886,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vp_debug_trace() {
+        // The environment must be initialized first
+        vp_host_env::init();
+
+        namada_vp_prelude::debug_trace("traced");
+        vp_host_env::with(|env| {
+            assert_eq!(env.debug_trace, Some(vec!["traced".to_owned()]));
+            // Disable the trace, as when a tx is applied in a block
+            env.debug_trace = None;
+        });
+
+        namada_vp_prelude::debug_trace("not traced");
+        vp_host_env::with(|env| assert_eq!(env.debug_trace, None));
+    }
+
     #[test]
     fn test_vp_eval() {
         // The environment must be initialized first
@@ -1093,6 +1455,59 @@ mod tests {
         assert!(result.expect("validation failed unexpectedly"));
     }
 
+    #[test]
+    fn test_ibc_send_token_from_custom_tx() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        // Set the initial state before starting transactions
+        let (token, sender) = ibc::init_storage();
+        let (client_id, _client_state, mut writes) = ibc::prepare_client();
+        let (conn_id, conn_writes) = ibc::prepare_opened_connection(&client_id);
+        writes.extend(conn_writes);
+        let (port_id, channel_id, channel_writes) =
+            ibc::prepare_opened_channel(&conn_id, false);
+        writes.extend(channel_writes);
+        writes.into_iter().for_each(|(key, val)| {
+            tx_host_env::with(|env| {
+                env.wl_storage
+                    .storage
+                    .write(&key, &val)
+                    .expect("write error");
+            });
+        });
+
+        let keypair = key::testing::keypair_1();
+        let keypairs = vec![keypair.clone()];
+        let pks_map = AccountPublicKeysMap::from_iter([
+            key::testing::keypair_1().ref_to(),
+        ]);
+
+        // The data of a custom tx isn't an IBC message
+        let mut tx = Tx::new(ChainId::default(), None);
+        tx.add_code(vec![], None)
+            .add_serialized_data(vec![1, 2, 3])
+            .sign_raw(keypairs, pks_map, None)
+            .sign_wrapper(keypair);
+        // send the token from the custom tx
+        let msg =
+            ibc::msg_transfer(port_id, channel_id, token.to_string(), &sender);
+        namada_tx_prelude::ibc::send_transfer(msg)
+            .expect("sending a token failed");
+
+        // Check that the IBC VP validates the executed message
+        let env = tx_host_env::take();
+        let result = ibc::validate_ibc_vp_from_tx(&env, &tx);
+        assert!(result.expect("validation failed unexpectedly"));
+        let escrow = token::balance_key(
+            &token,
+            &address::Address::Internal(address::InternalAddress::Ibc),
+        );
+        let token_vp_result =
+            ibc::validate_multitoken_vp_from_tx(&env, &tx, &escrow);
+        assert!(token_vp_result.expect("token validation failed unexpectedly"));
+    }
+
     #[test]
     fn test_ibc_send_token() {
         // The environment must be initialized first
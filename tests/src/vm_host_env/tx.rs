@@ -55,6 +55,7 @@ pub struct TestTxEnv {
     pub sentinel: TxSentinel,
     pub tx_index: TxIndex,
     pub result_buffer: Option<Vec<u8>>,
+    pub debug_trace: Option<Vec<String>>,
     pub vp_wasm_cache: VpCache<WasmCacheRwAccess>,
     pub vp_cache_dir: TempDir,
     pub tx_wasm_cache: TxCache<WasmCacheRwAccess>,
@@ -67,10 +68,18 @@ impl Default for TestTxEnv {
             wasm::compilation_cache::common::testing::cache();
         let (tx_wasm_cache, tx_cache_dir) =
             wasm::compilation_cache::common::testing::cache();
-        let wl_storage = WlStorage {
+        let mut wl_storage = WlStorage {
             storage: TestStorage::default(),
             write_log: WriteLog::default(),
         };
+        // The WASM limits are required to run any wasm
+        wl_storage
+            .storage
+            .write(
+                &parameters::storage::get_wasm_limits_key(),
+                parameters::WasmLimits::default().serialize_to_vec(),
+            )
+            .unwrap();
         let mut tx = Tx::from_type(TxType::Raw);
         tx.header.chain_id = wl_storage.storage.chain_id.clone();
         Self {
@@ -81,6 +90,7 @@ impl Default for TestTxEnv {
             tx_index: TxIndex::default(),
             verifiers: BTreeSet::default(),
             result_buffer: None,
+            debug_trace: Some(Vec::new()),
             vp_wasm_cache,
             vp_cache_dir,
             tx_wasm_cache,
@@ -234,6 +244,7 @@ impl TestTxEnv {
             &self.tx,
             &mut self.vp_wasm_cache,
             &mut self.tx_wasm_cache,
+            &mut self.debug_trace,
         )
         .and(Ok(()))
     }
@@ -349,6 +360,7 @@ mod native_tx_host_env {
                                 gas_meter,
                                 sentinel,
                                 result_buffer,
+                                debug_trace,
                                 tx_index,
                                 vp_wasm_cache,
                                 vp_cache_dir: _,
@@ -367,6 +379,7 @@ mod native_tx_host_env {
                                 tx,
                                 tx_index,
                                 result_buffer,
+                                debug_trace,
                                 vp_wasm_cache,
                                 tx_wasm_cache,
                             );
@@ -392,6 +405,7 @@ mod native_tx_host_env {
                                 gas_meter,
                                 sentinel,
                                 result_buffer,
+                                debug_trace,
                                 vp_wasm_cache,
                                 vp_cache_dir: _,
                                 tx_wasm_cache,
@@ -409,6 +423,7 @@ mod native_tx_host_env {
                                 tx,
                                 tx_index,
                                 result_buffer,
+                                debug_trace,
                                 vp_wasm_cache,
                                 tx_wasm_cache,
                             );
@@ -433,6 +448,7 @@ mod native_tx_host_env {
                                 gas_meter,
                                 sentinel,
                                 result_buffer,
+                                debug_trace,
                                 tx_index,
                                 vp_wasm_cache,
                                 vp_cache_dir: _,
@@ -451,6 +467,7 @@ mod native_tx_host_env {
                                 tx,
                                 tx_index,
                                 result_buffer,
+                                debug_trace,
                                 vp_wasm_cache,
                                 tx_wasm_cache,
                             );
@@ -466,6 +483,7 @@ mod native_tx_host_env {
     // Implement all the exported functions from
     // [`namada_vm_env::imports::tx`] `extern "C"` section.
     native_host_fn!(tx_read(key_ptr: u64, key_len: u64) -> i64);
+    native_host_fn!(tx_read_batch(keys_ptr: u64, keys_len: u64) -> i64);
     native_host_fn!(tx_result_buffer(result_ptr: u64));
     native_host_fn!(tx_has_key(key_ptr: u64, key_len: u64) -> i64);
     native_host_fn!(tx_write(
@@ -506,9 +524,18 @@ mod native_tx_host_env {
     native_host_fn!(tx_get_tx_index() -> u32);
     native_host_fn!(tx_get_block_header(height: u64) -> i64);
     native_host_fn!(tx_get_block_hash(result_ptr: u64));
+    native_host_fn!(tx_get_block_randomness(result_ptr: u64));
     native_host_fn!(tx_get_block_epoch() -> u64);
     native_host_fn!(tx_get_native_token(result_ptr: u64));
     native_host_fn!(tx_log_string(str_ptr: u64, str_len: u64));
+    native_host_fn!(tx_debug_log(str_ptr: u64, str_len: u64));
+    native_host_fn!(tx_ibc_execute_msg(msg_ptr: u64, msg_len: u64));
+    native_host_fn!(tx_invoke(
+        code_hash_ptr: u64,
+        code_hash_len: u64,
+        data_ptr: u64,
+        data_len: u64,
+    ));
     native_host_fn!(tx_charge_gas(used_gas: u64));
     native_host_fn!("non-result", tx_set_commitment_sentinel());
     native_host_fn!(tx_verify_tx_section_signature(
@@ -1,6 +1,7 @@
 use std::collections::BTreeSet;
 
 use namada::ledger::gas::VpGasMeter;
+use namada::ledger::parameters;
 use namada::ledger::storage::mockdb::MockDB;
 use namada::ledger::storage::testing::TestStorage;
 use namada::ledger::storage::write_log::WriteLog;
@@ -13,6 +14,7 @@ use namada::vm::prefix_iter::PrefixIterators;
 use namada::vm::wasm::{self, VpCache};
 use namada::vm::{self, WasmCacheRwAccess};
 use namada_core::ledger::gas::TxGasMeter;
+use namada_tx_prelude::borsh_ext::BorshSerializeExt;
 use namada_tx_prelude::validity_predicate::VpSentinel;
 use namada_vp_prelude::Ctx;
 use tempfile::TempDir;
@@ -52,6 +54,7 @@ pub struct TestVpEnv {
     pub verifiers: BTreeSet<Address>,
     pub eval_runner: native_vp_host_env::VpEval,
     pub result_buffer: Option<Vec<u8>>,
+    pub debug_trace: Option<Vec<String>>,
     pub vp_wasm_cache: VpCache<WasmCacheRwAccess>,
     pub vp_cache_dir: TempDir,
 }
@@ -66,10 +69,18 @@ impl Default for TestVpEnv {
         let (vp_wasm_cache, vp_cache_dir) =
             wasm::compilation_cache::common::testing::cache();
 
-        let wl_storage = WlStorage {
+        let mut wl_storage = WlStorage {
             storage: TestStorage::default(),
             write_log: WriteLog::default(),
         };
+        // The WASM limits are required to run any wasm
+        wl_storage
+            .storage
+            .write(
+                &parameters::storage::get_wasm_limits_key(),
+                parameters::WasmLimits::default().serialize_to_vec(),
+            )
+            .unwrap();
         let mut tx = Tx::from_type(TxType::Raw);
         tx.header.chain_id = wl_storage.storage.chain_id.clone();
         Self {
@@ -86,6 +97,7 @@ impl Default for TestVpEnv {
             verifiers: BTreeSet::default(),
             eval_runner,
             result_buffer: None,
+            debug_trace: Some(Vec::new()),
             vp_wasm_cache,
             vp_cache_dir,
         }
@@ -268,6 +280,7 @@ mod native_vp_host_env {
                                 verifiers,
                                 eval_runner,
                                 result_buffer,
+                                debug_trace,
                                 vp_wasm_cache,
                                 vp_cache_dir: _,
                             }: &mut TestVpEnv| {
@@ -283,6 +296,7 @@ mod native_vp_host_env {
                                 tx_index,
                                 verifiers,
                                 result_buffer,
+                                debug_trace,
                                 keys_changed,
                                 eval_runner,
                                 vp_wasm_cache,
@@ -313,6 +327,7 @@ mod native_vp_host_env {
                                 verifiers,
                                 eval_runner,
                                 result_buffer,
+                                debug_trace,
                                 vp_wasm_cache,
                                 vp_cache_dir: _,
                             }: &mut TestVpEnv| {
@@ -328,6 +343,7 @@ mod native_vp_host_env {
                                 tx_index,
                                 verifiers,
                                 result_buffer,
+                                debug_trace,
                                 keys_changed,
                                 eval_runner,
                                 vp_wasm_cache,
@@ -346,6 +362,9 @@ mod native_vp_host_env {
     // [`namada_vm_env::imports::vp`] `extern "C"` section.
     native_host_fn!(vp_read_pre(key_ptr: u64, key_len: u64) -> i64);
     native_host_fn!(vp_read_post(key_ptr: u64, key_len: u64) -> i64);
+    native_host_fn!(vp_read_pre_block(key_ptr: u64, key_len: u64) -> i64);
+    native_host_fn!(vp_read_pre_batch(keys_ptr: u64, keys_len: u64) -> i64);
+    native_host_fn!(vp_read_post_batch(keys_ptr: u64, keys_len: u64) -> i64);
     native_host_fn!(vp_read_temp(key_ptr: u64, key_len: u64) -> i64);
     native_host_fn!(vp_result_buffer(result_ptr: u64));
     native_host_fn!(vp_has_key_pre(key_ptr: u64, key_len: u64) -> i64);
@@ -358,6 +377,7 @@ mod native_vp_host_env {
     native_host_fn!(vp_get_tx_index() -> u32);
     native_host_fn!(vp_get_block_header(height: u64) -> i64);
     native_host_fn!(vp_get_block_hash(result_ptr: u64));
+    native_host_fn!(vp_get_block_randomness(result_ptr: u64));
     native_host_fn!(vp_get_tx_code_hash(result_ptr: u64));
     native_host_fn!(vp_get_block_epoch() -> u64);
     native_host_fn!(vp_get_native_token(result_ptr: u64));
@@ -368,6 +388,7 @@ mod native_vp_host_env {
             input_data_len: u64,
         ) -> i64);
     native_host_fn!(vp_log_string(str_ptr: u64, str_len: u64));
+    native_host_fn!(vp_debug_log(str_ptr: u64, str_len: u64));
     native_host_fn!(vp_verify_tx_section_signature(
         hash_list_ptr: u64,
         hash_list_len: u64,
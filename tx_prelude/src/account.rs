@@ -1,3 +1,4 @@
+pub use namada_core::types::account::PendingVpUpdate;
 use namada_core::types::transaction::account::InitAccount;
 
 use super::*;
@@ -13,6 +14,14 @@ pub fn init_account(
         &data.public_keys,
         data.threshold,
     )?;
+    if let Some(vesting) = &data.vesting {
+        if !vesting.is_valid() {
+            return Err(Error::new_const(
+                "The epochs of a vesting schedule must be in order",
+            ));
+        }
+        storage_api::account::write_vesting_schedule(ctx, owner, vesting)?;
+    }
 
     Ok(owner.to_owned())
 }
@@ -3,16 +3,19 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+pub use namada_core::ibc::apps::transfer::types::msgs::transfer::MsgTransfer;
+use namada_core::ibc::primitives::Msg;
 pub use namada_core::ledger::ibc::{
     IbcActions, IbcCommonContext, IbcStorageContext, ProofSpec, TransferModule,
 };
 use namada_core::ledger::tx_env::TxEnv;
+use namada_core::prost::Message;
 use namada_core::types::address::{Address, InternalAddress};
 pub use namada_core::types::ibc::{IbcEvent, IbcShieldedTransfer};
 use namada_core::types::token::DenominatedAmount;
 
 use crate::token::{burn, handle_masp_tx, mint, transfer};
-use crate::{Ctx, Error};
+use crate::{Ctx, Error, TxResult};
 
 /// IBC actions to handle an IBC message
 pub fn ibc_actions(ctx: &mut Ctx) -> IbcActions<Ctx> {
@@ -23,6 +26,25 @@ pub fn ibc_actions(ctx: &mut Ctx) -> IbcActions<Ctx> {
     actions
 }
 
+/// Execute an IBC message, e.g. a token transfer or a packet acknowledgement,
+/// encoded as a protobuf `Any`. This allows custom transactions to compose IBC
+/// actions with other state changes atomically.
+pub fn execute_msg(msg: impl AsRef<[u8]>) -> TxResult {
+    let msg = msg.as_ref();
+    unsafe {
+        namada_vm_env::tx::namada_tx_ibc_execute_msg(
+            msg.as_ptr() as _,
+            msg.len() as _,
+        )
+    };
+    Ok(())
+}
+
+/// Send an IBC token transfer
+pub fn send_transfer(msg: MsgTransfer) -> TxResult {
+    execute_msg(msg.to_any().encode_to_vec())
+}
+
 impl IbcStorageContext for Ctx {
     fn emit_ibc_event(
         &mut self,
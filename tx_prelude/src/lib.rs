@@ -27,7 +27,9 @@ pub use namada_core::ledger::storage_api::{
     ResultExt, StorageRead, StorageWrite,
 };
 pub use namada_core::ledger::tx_env::TxEnv;
-pub use namada_core::ledger::{eth_bridge, parameters};
+pub use namada_core::ledger::{
+    eth_bridge, oracle, parameters, testnet_pow, upgrade,
+};
 pub use namada_core::proto::{Section, Tx};
 use namada_core::types::account::AccountPublicKeysMap;
 pub use namada_core::types::address::Address;
@@ -42,7 +44,9 @@ pub use namada_core::types::storage::{
 pub use namada_core::types::{eth_bridge_pool, *};
 pub use namada_macros::transaction;
 use namada_vm_env::tx::*;
-use namada_vm_env::{read_from_buffer, read_key_val_bytes_from_buffer};
+use namada_vm_env::{
+    host_result, read_from_buffer, read_key_val_bytes_from_buffer,
+};
 
 /// Log a string. The message will be printed at the `tracing::Level::Info`.
 pub fn log_string<T: AsRef<str>>(msg: T) {
@@ -52,6 +56,15 @@ pub fn log_string<T: AsRef<str>>(msg: T) {
     }
 }
 
+/// Add a string to the debug trace. The trace is returned to the client in
+/// dry-run and this is a no-op when the transaction is executed in a block.
+pub fn debug_trace<T: AsRef<str>>(msg: T) {
+    let msg = msg.as_ref();
+    unsafe {
+        namada_tx_debug_log(msg.as_ptr() as _, msg.len() as _);
+    }
+}
+
 /// Format and log a string in a debug build.
 ///
 /// In WASM target debug build, the message will be printed at the
@@ -102,6 +115,71 @@ impl Ctx {
     pub const unsafe fn new() -> Self {
         Self(())
     }
+
+    /// Get the randomness of the current block, derived from the hash of the
+    /// last block and the signatures of its commit.
+    ///
+    /// This value is only weakly random: it is known before the txs of the
+    /// block are applied and it can be biased by the proposer of the last
+    /// block and by the validators choosing whether to sign it. It is fit for
+    /// e.g. a fair ordering of queue draws, but not to protect anything of
+    /// more value than the cost of such a manipulation.
+    pub fn get_block_randomness(&self) -> Result<hash::Hash, Error> {
+        let result = Vec::with_capacity(hash::HASH_LENGTH);
+        unsafe {
+            namada_tx_get_block_randomness(result.as_ptr() as _);
+        }
+        let slice = unsafe {
+            slice::from_raw_parts(result.as_ptr(), hash::HASH_LENGTH)
+        };
+        Ok(hash::Hash(
+            slice.try_into().expect("Cannot convert the randomness"),
+        ))
+    }
+
+    /// Read the values of a batch of storage keys in a single call to the
+    /// host, in the order of the keys. Cheaper than reading each of many
+    /// keys with [`StorageRead::read_bytes`].
+    pub fn read_batch(
+        &self,
+        keys: &[storage::Key],
+    ) -> EnvResult<Vec<Option<Vec<u8>>>> {
+        let keys = keys
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<_>>()
+            .serialize_to_vec();
+        let read_result = unsafe {
+            namada_tx_read_batch(keys.as_ptr() as _, keys.len() as _)
+        };
+        let values = read_from_buffer(read_result, namada_tx_result_buffer)
+            .unwrap_or_default();
+        Vec::<Option<Vec<u8>>>::try_from_slice(&values[..])
+            .into_storage_result()
+    }
+
+    /// Invoke another transaction's WASM code registered on chain by its
+    /// hash, with the given data encoded with Borsh as its tx data. The
+    /// invoked transaction is executed within the current execution and its
+    /// storage modifications are applied to the same write log, so that
+    /// complex flows can be composed from existing transactions. If the
+    /// invoked transaction fails, the current transaction is aborted.
+    pub fn invoke_tx<T: BorshSerialize>(
+        &mut self,
+        code_hash: &hash::Hash,
+        data: &T,
+    ) -> TxResult {
+        let data = data.serialize_to_vec();
+        unsafe {
+            namada_tx_invoke(
+                code_hash.0.as_ptr() as _,
+                code_hash.0.len() as _,
+                data.as_ptr() as _,
+                data.len() as _,
+            )
+        };
+        Ok(())
+    }
 }
 
 /// Result of `TxEnv`, `storage_api::StorageRead` or `storage_api::StorageWrite`
@@ -119,15 +197,17 @@ impl StorageRead for Ctx {
 
     fn read_bytes(&self, key: &storage::Key) -> Result<Option<Vec<u8>>, Error> {
         let key = key.to_string();
-        let read_result =
-            unsafe { namada_tx_read(key.as_ptr() as _, key.len() as _) };
+        let read_result = host_result(unsafe {
+            namada_tx_read(key.as_ptr() as _, key.len() as _)
+        })?;
         Ok(read_from_buffer(read_result, namada_tx_result_buffer))
     }
 
     fn has_key(&self, key: &storage::Key) -> Result<bool, Error> {
         let key = key.to_string();
-        let found =
-            unsafe { namada_tx_has_key(key.as_ptr() as _, key.len() as _) };
+        let found = host_result(unsafe {
+            namada_tx_has_key(key.as_ptr() as _, key.len() as _)
+        })?;
         Ok(HostEnvResult::is_success(found))
     }
 
@@ -1,16 +1,23 @@
-//! Proof of Stake system integration with functions for transactions
+//! Proof of Stake system integration with functions for transactions, and
+//! read-only views of the PoS state for custom transactions
+
+use std::collections::BTreeSet;
 
 use namada_core::types::dec::Dec;
 use namada_core::types::key::common;
 use namada_core::types::transaction::pos::BecomeValidator;
 use namada_core::types::{key, token};
 pub use namada_proof_of_stake::parameters::PosParams;
-use namada_proof_of_stake::types::ValidatorMetaData;
+use namada_proof_of_stake::types::{
+    BondId, ValidatorMetaData, ValidatorState, WeightedValidator,
+};
 use namada_proof_of_stake::{
     become_validator, bond_tokens, change_consensus_key,
     change_validator_commission_rate, change_validator_metadata,
     claim_reward_tokens, deactivate_validator, reactivate_validator,
-    read_pos_params, redelegate_tokens, unbond_tokens, unjail_validator,
+    read_consensus_validator_set_addresses_with_stake, read_pos_params,
+    read_total_stake, read_validator_stake, read_validator_state,
+    redelegate_tokens, set_auto_compound, unbond_tokens, unjail_validator,
     withdraw_tokens,
 };
 pub use namada_proof_of_stake::{parameters, types, ResultSlashing};
@@ -111,6 +118,16 @@ impl Ctx {
         claim_reward_tokens(self, source, validator, current_epoch)
     }
 
+    /// Opt a bond in or out of the automatic re-bonding of its rewards
+    pub fn set_auto_compound(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        enabled: bool,
+    ) -> TxResult {
+        set_auto_compound(self, source, validator, enabled)
+    }
+
     /// Attempt to initialize a validator account. On success, returns the
     /// initialized validator account's address.
     pub fn become_validator(
@@ -195,3 +212,64 @@ impl Ctx {
         )
     }
 }
+
+/// Read the state of a validator at the given epoch. Returns
+/// `None` if the address isn't a validator at the epoch.
+pub fn validator_state(
+    ctx: &Ctx,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<Option<ValidatorState>> {
+    read_validator_state(ctx, validator, epoch)
+}
+
+/// Check if the validator is in the consensus validator set at the given
+/// epoch.
+pub fn is_in_consensus_set(
+    ctx: &Ctx,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<bool> {
+    namada_proof_of_stake::is_consensus_validator(ctx, validator, epoch)
+}
+
+/// Read the consensus validators with their bonded stake at the given
+/// epoch.
+pub fn consensus_validators(
+    ctx: &Ctx,
+    epoch: Epoch,
+) -> EnvResult<BTreeSet<WeightedValidator>> {
+    read_consensus_validator_set_addresses_with_stake(ctx, epoch)
+}
+
+/// Read the bonded stake of a validator at the given epoch. The
+/// stake of an address that isn't a validator is zero.
+pub fn validator_stake(
+    ctx: &Ctx,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<token::Amount> {
+    let params = read_pos_params(ctx)?;
+    read_validator_stake(ctx, &params, validator, epoch)
+}
+
+/// Read the total bonded stake at the given epoch.
+pub fn total_stake(ctx: &Ctx, epoch: Epoch) -> EnvResult<token::Amount> {
+    let params = read_pos_params(ctx)?;
+    read_total_stake(ctx, &params, epoch)
+}
+
+/// Read the amount bonded by the source to the validator at the given
+/// epoch, after slashing.
+pub fn bonded_amount(
+    ctx: &Ctx,
+    source: &Address,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<token::Amount> {
+    let bond_id = BondId {
+        source: source.clone(),
+        validator: validator.clone(),
+    };
+    namada_proof_of_stake::bond_amount(ctx, &bond_id, epoch)
+}
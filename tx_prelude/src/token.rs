@@ -1,6 +1,5 @@
 use masp_primitives::transaction::Transaction;
 use namada_core::types::address::{Address, MASP};
-use namada_core::types::storage::KeySeg;
 use namada_core::types::token;
 pub use namada_core::types::token::*;
 
@@ -28,46 +27,52 @@ pub fn transfer(
         dest_bal.receive(&amount.amount);
         ctx.write(&src_key, src_bal)?;
         ctx.write(&dest_key, dest_bal)?;
+        storage_api::account::record_spending(ctx, src, token, amount.amount)?;
+        storage_api::account::record_receipt(ctx, dest, token, amount.amount)?;
     }
     Ok(())
 }
 
+/// Allow a spender to transfer tokens from the balance of the owner, that
+/// can be used in a transaction.
+pub fn approve(
+    ctx: &mut Ctx,
+    owner: &Address,
+    spender: &Address,
+    token: &Address,
+    amount: DenominatedAmount,
+) -> TxResult {
+    storage_api::token::approve(ctx, token, owner, spender, amount.amount)
+}
+
+/// A token transfer made by a spender out of the allowance given to it by
+/// the source, that can be used in a transaction.
+pub fn transfer_from(
+    ctx: &mut Ctx,
+    spender: &Address,
+    src: &Address,
+    dest: &Address,
+    token: &Address,
+    amount: DenominatedAmount,
+) -> TxResult {
+    storage_api::token::transfer_from(
+        ctx,
+        token,
+        spender,
+        src,
+        dest,
+        amount.amount,
+    )
+}
+
 /// Handle a MASP transaction.
 pub fn handle_masp_tx(
     ctx: &mut Ctx,
     transfer: &Transfer,
     shielded: &Transaction,
 ) -> TxResult {
-    let masp_addr = MASP;
-    ctx.insert_verifier(&masp_addr)?;
-    let head_tx_key = storage::Key::from(masp_addr.to_db_key())
-        .push(&HEAD_TX_KEY.to_owned())
-        .expect("Cannot obtain a storage key");
-    let current_tx_idx: u64 =
-        ctx.read(&head_tx_key).unwrap_or(None).unwrap_or(0);
-    let current_tx_key = storage::Key::from(masp_addr.to_db_key())
-        .push(&(TX_KEY_PREFIX.to_owned() + &current_tx_idx.to_string()))
-        .expect("Cannot obtain a storage key");
-    // Save the Transfer object and its location within the blockchain
-    // so that clients do not have to separately look these
-    // up
-    let record: (Epoch, BlockHeight, TxIndex, Transfer, Transaction) = (
-        ctx.get_block_epoch()?,
-        ctx.get_block_height()?,
-        ctx.get_tx_index()?,
-        transfer.clone(),
-        shielded.clone(),
-    );
-    ctx.write(&current_tx_key, record)?;
-    ctx.write(&head_tx_key, current_tx_idx + 1)?;
-    // If storage key has been supplied, then pin this transaction to it
-    if let Some(key) = &transfer.key {
-        let pin_key = storage::Key::from(masp_addr.to_db_key())
-            .push(&(PIN_KEY_PREFIX.to_owned() + key))
-            .expect("Cannot obtain a storage key");
-        ctx.write(&pin_key, current_tx_idx)?;
-    }
-    Ok(())
+    ctx.insert_verifier(&MASP)?;
+    storage_api::token::handle_masp_tx(ctx, transfer, shielded)
 }
 
 /// Mint that can be used in a transaction.
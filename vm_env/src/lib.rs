@@ -8,6 +8,7 @@
 use std::mem::ManuallyDrop;
 
 use borsh::BorshDeserialize;
+use namada_core::ledger::storage_api::{self, ErrorCode};
 use namada_core::types::internal::{HostEnvResult, KeyVal};
 
 /// Transaction environment imports
@@ -22,6 +23,12 @@ pub mod tx {
         // its size.
         pub fn namada_tx_read(key_ptr: u64, key_len: u64) -> i64;
 
+        // Read the values of a batch of keys given as a borsh encoded
+        // `Vec<String>`. The values are placed in the result buffer as a
+        // borsh encoded `Vec<Option<Vec<u8>>>` and the length of the encoded
+        // values is returned.
+        pub fn namada_tx_read_batch(keys_ptr: u64, keys_len: u64) -> i64;
+
         // Read a value from result buffer.
         pub fn namada_tx_result_buffer(result_ptr: u64);
 
@@ -100,6 +107,9 @@ pub mod tx {
         // Get the current block hash
         pub fn namada_tx_get_block_hash(result_ptr: u64);
 
+        // Get the weak randomness of the current block
+        pub fn namada_tx_get_block_randomness(result_ptr: u64);
+
         // Get the current block epoch
         pub fn namada_tx_get_block_epoch() -> u64;
 
@@ -112,6 +122,18 @@ pub mod tx {
         // Requires a node running with "Info" log level
         pub fn namada_tx_log_string(str_ptr: u64, str_len: u64);
 
+        // Add a message to the debug trace, only collected in dry-run
+        pub fn namada_tx_debug_log(str_ptr: u64, str_len: u64);
+
+        // Invoke another tx code by its hash with the given data, sharing the
+        // write log with the caller
+        pub fn namada_tx_invoke(
+            code_hash_ptr: u64,
+            code_hash_len: u64,
+            data_ptr: u64,
+            data_len: u64,
+        );
+
         /// Charge the provided amount of gas for the current tx
         pub fn namada_tx_charge_gas(used_gas: u64);
 
@@ -119,6 +141,9 @@ pub mod tx {
         // Temp. workaround for <https://github.com/anoma/namada/issues/1831>
         pub fn namada_tx_ibc_execute();
 
+        /// Execute the given IBC message.
+        pub fn namada_tx_ibc_execute_msg(msg_ptr: u64, msg_len: u64);
+
         /// Set the sentinel for a wrong tx section commitment
         pub fn namada_tx_set_commitment_sentinel();
 
@@ -155,6 +180,23 @@ pub mod vp {
         // we know its size.
         pub fn namada_vp_read_post(key_ptr: u64, key_len: u64) -> i64;
 
+        // Read variable-length state from before the execution of the
+        // current block, without the modifications of the txs before the
+        // current one in the block. Returns the size of the value (can be 0),
+        // or -1 if the key is not present. If a value is found, it will be
+        // placed in the result buffer.
+        pub fn namada_vp_read_pre_block(key_ptr: u64, key_len: u64) -> i64;
+
+        // Read the prior state of a batch of keys given as a borsh encoded
+        // `Vec<String>`. The values are placed in the result buffer as a
+        // borsh encoded `Vec<Option<Vec<u8>>>` and the length of the encoded
+        // values is returned.
+        pub fn namada_vp_read_pre_batch(keys_ptr: u64, keys_len: u64) -> i64;
+
+        // Read the posterior state of a batch of keys, like
+        // `namada_vp_read_pre_batch`.
+        pub fn namada_vp_read_post_batch(keys_ptr: u64, keys_len: u64) -> i64;
+
         // Read variable-length temporary state when we don't know the size
         // up-front, returns the size of the value (can be 0), or -1 if
         // the key is not present. If a value is found, it will be placed in the
@@ -204,6 +246,9 @@ pub mod vp {
         // Get the current block hash
         pub fn namada_vp_get_block_hash(result_ptr: u64);
 
+        // Get the weak randomness of the current block
+        pub fn namada_vp_get_block_randomness(result_ptr: u64);
+
         // Get the current tx hash
         pub fn namada_vp_get_tx_code_hash(result_ptr: u64);
 
@@ -225,6 +270,9 @@ pub mod vp {
         // Requires a node running with "Info" log level
         pub fn namada_vp_log_string(str_ptr: u64, str_len: u64);
 
+        // Add a message to the debug trace, only collected in dry-run
+        pub fn namada_vp_debug_log(str_ptr: u64, str_len: u64);
+
         // Verify the signatures of a tx
         pub fn namada_vp_verify_tx_section_signature(
             hash_list_ptr: u64,
@@ -278,6 +326,16 @@ pub fn read_from_buffer(
     }
 }
 
+/// Decode the code of a [`storage_api::Error`] that a host function returned
+/// in place of its result. Any other result, i.e. the length of a value or a
+/// [`HostEnvResult`], is returned unchanged.
+pub fn host_result(result: i64) -> storage_api::Result<i64> {
+    match ErrorCode::from_i64(result) {
+        Some(code) => Err(storage_api::Error::from_code(code)),
+        None => Ok(result),
+    }
+}
+
 /// This function is a helper to handle the second step of reading var-len
 /// values in a key-value pair from the host.
 pub fn read_key_val_bytes_from_buffer(
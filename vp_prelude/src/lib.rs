@@ -6,6 +6,9 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::private_intra_doc_links)]
 
+pub mod pos_views;
+pub mod token;
+
 // used in the VP input
 use core::convert::AsRef;
 use core::slice;
@@ -23,6 +26,7 @@ pub use namada_core::ledger::storage_api::{
     StorageRead,
 };
 pub use namada_core::ledger::vp_env::VpEnv;
+pub use namada_core::ledger::{oracle, testnet_pow};
 pub use namada_core::proto::{Section, Tx};
 pub use namada_core::types::address::Address;
 use namada_core::types::chain::CHAIN_ID_LENGTH;
@@ -34,7 +38,9 @@ use namada_core::types::storage::{
 pub use namada_core::types::*;
 pub use namada_macros::validity_predicate;
 use namada_vm_env::vp::*;
-use namada_vm_env::{read_from_buffer, read_key_val_bytes_from_buffer};
+use namada_vm_env::{
+    host_result, read_from_buffer, read_key_val_bytes_from_buffer,
+};
 pub use sha2::{Digest, Sha256, Sha384, Sha512};
 pub use {borsh_ext, namada_proof_of_stake as proof_of_stake};
 
@@ -71,6 +77,15 @@ pub fn log_string<T: AsRef<str>>(msg: T) {
     }
 }
 
+/// Add a string to the debug trace. The trace is returned to the client in
+/// dry-run and this is a no-op when the VP is executed in a block.
+pub fn debug_trace<T: AsRef<str>>(msg: T) {
+    let msg = msg.as_ref();
+    unsafe {
+        namada_vp_debug_log(msg.as_ptr() as _, msg.len() as _);
+    }
+}
+
 /// Checks if a proposal id is being executed
 pub fn is_proposal_accepted(ctx: &Ctx, proposal_id: u64) -> VpResult {
     let proposal_execution_key =
@@ -81,13 +96,41 @@ pub fn is_proposal_accepted(ctx: &Ctx, proposal_id: u64) -> VpResult {
 
 /// Verify section signatures
 pub fn verify_signatures(ctx: &Ctx, tx: &Tx, owner: &Address) -> VpResult {
-    let max_signatures_per_transaction =
-        parameters::max_signatures_per_transaction(&ctx.pre())?;
+    let threshold =
+        storage_api::account::threshold(&ctx.pre(), owner)?.unwrap_or(1);
+    verify_signatures_with_threshold(ctx, tx, owner, threshold)
+}
 
+/// Verify section signatures against the account's public keys, requiring the
+/// given threshold instead of the account's one
+pub fn verify_signatures_with_threshold(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    threshold: u8,
+) -> VpResult {
     let public_keys_index_map =
         storage_api::account::public_keys_index_map(&ctx.pre(), owner)?;
-    let threshold =
-        storage_api::account::threshold(&ctx.pre(), owner)?.unwrap_or(1);
+    verify_signatures_with_keys(
+        ctx,
+        tx,
+        owner,
+        public_keys_index_map,
+        threshold,
+    )
+}
+
+/// Verify section signatures against the given public keys instead of the
+/// account's ones, e.g. a recovery key stored by the account
+pub fn verify_signatures_with_keys(
+    ctx: &Ctx,
+    tx: &Tx,
+    owner: &Address,
+    public_keys_index_map: account::AccountPublicKeysMap,
+    threshold: u8,
+) -> VpResult {
+    let max_signatures_per_transaction =
+        parameters::max_signatures_per_transaction(&ctx.pre())?;
 
     // Serialize parameters
     let max_signatures = max_signatures_per_transaction.serialize_to_vec();
@@ -179,6 +222,24 @@ impl Ctx {
         Self(())
     }
 
+    /// Get the randomness of the current block, derived from the hash of the
+    /// last block and the signatures of its commit.
+    ///
+    /// This value is only weakly random: it is known before the txs of the
+    /// block are applied and it can be biased by the proposer of the last
+    /// block and by the validators choosing whether to sign it.
+    pub fn get_block_randomness(&self) -> Result<Hash, Error> {
+        let result = Vec::with_capacity(HASH_LENGTH);
+        unsafe {
+            namada_vp_get_block_randomness(result.as_ptr() as _);
+        }
+        let slice =
+            unsafe { slice::from_raw_parts(result.as_ptr(), HASH_LENGTH) };
+        Ok(Hash(
+            slice.try_into().expect("Cannot convert the randomness"),
+        ))
+    }
+
     /// Read access to the prior storage (state before tx execution)
     /// via [`trait@StorageRead`].
     pub fn pre(&self) -> CtxPreStorageRead<'_> {
@@ -206,6 +267,28 @@ pub struct CtxPostStorageRead<'a> {
     _ctx: &'a Ctx,
 }
 
+impl CtxPreStorageRead<'_> {
+    /// Read the prior values of a batch of storage keys in a single call to
+    /// the host, in the order of the keys.
+    pub fn read_batch(
+        &self,
+        keys: &[storage::Key],
+    ) -> EnvResult<Vec<Option<Vec<u8>>>> {
+        read_batch_impl(keys, namada_vp_read_pre_batch)
+    }
+}
+
+impl CtxPostStorageRead<'_> {
+    /// Read the posterior values of a batch of storage keys in a single call
+    /// to the host, in the order of the keys.
+    pub fn read_batch(
+        &self,
+        keys: &[storage::Key],
+    ) -> EnvResult<Vec<Option<Vec<u8>>>> {
+        read_batch_impl(keys, namada_vp_read_post_batch)
+    }
+}
+
 /// Result of `VpEnv` or `storage_api::StorageRead` method call
 pub type EnvResult<T> = Result<T, Error>;
 
@@ -355,6 +438,17 @@ impl<'view> VpEnv<'view> for Ctx {
         unsafe { namada_vp_charge_gas(used_gas) };
         Ok(())
     }
+
+    fn read_bytes_pre_block(
+        &self,
+        key: &storage::Key,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let key = key.to_string();
+        let read_result = host_result(unsafe {
+            namada_vp_read_pre_block(key.as_ptr() as _, key.len() as _)
+        })?;
+        Ok(read_from_buffer(read_result, namada_vp_result_buffer))
+    }
 }
 
 impl StorageRead for CtxPreStorageRead<'_> {
@@ -362,15 +456,17 @@ impl StorageRead for CtxPreStorageRead<'_> {
 
     fn read_bytes(&self, key: &storage::Key) -> Result<Option<Vec<u8>>, Error> {
         let key = key.to_string();
-        let read_result =
-            unsafe { namada_vp_read_pre(key.as_ptr() as _, key.len() as _) };
+        let read_result = host_result(unsafe {
+            namada_vp_read_pre(key.as_ptr() as _, key.len() as _)
+        })?;
         Ok(read_from_buffer(read_result, namada_vp_result_buffer))
     }
 
     fn has_key(&self, key: &storage::Key) -> Result<bool, Error> {
         let key = key.to_string();
-        let found =
-            unsafe { namada_vp_has_key_pre(key.as_ptr() as _, key.len() as _) };
+        let found = host_result(unsafe {
+            namada_vp_has_key_pre(key.as_ptr() as _, key.len() as _)
+        })?;
         Ok(HostEnvResult::is_success(found))
     }
 
@@ -431,16 +527,17 @@ impl StorageRead for CtxPostStorageRead<'_> {
 
     fn read_bytes(&self, key: &storage::Key) -> Result<Option<Vec<u8>>, Error> {
         let key = key.to_string();
-        let read_result =
-            unsafe { namada_vp_read_post(key.as_ptr() as _, key.len() as _) };
+        let read_result = host_result(unsafe {
+            namada_vp_read_post(key.as_ptr() as _, key.len() as _)
+        })?;
         Ok(read_from_buffer(read_result, namada_vp_result_buffer))
     }
 
     fn has_key(&self, key: &storage::Key) -> Result<bool, Error> {
         let key = key.to_string();
-        let found = unsafe {
+        let found = host_result(unsafe {
             namada_vp_has_key_post(key.as_ptr() as _, key.len() as _)
-        };
+        })?;
         Ok(HostEnvResult::is_success(found))
     }
 
@@ -496,6 +593,22 @@ impl StorageRead for CtxPostStorageRead<'_> {
     }
 }
 
+fn read_batch_impl(
+    keys: &[storage::Key],
+    read_batch: unsafe extern "C" fn(u64, u64) -> i64,
+) -> EnvResult<Vec<Option<Vec<u8>>>> {
+    let keys = keys
+        .iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>()
+        .serialize_to_vec();
+    let read_result =
+        unsafe { read_batch(keys.as_ptr() as _, keys.len() as _) };
+    let values = read_from_buffer(read_result, namada_vp_result_buffer)
+        .unwrap_or_default();
+    Vec::<Option<Vec<u8>>>::try_from_slice(&values[..]).into_storage_result()
+}
+
 fn iter_prefix_pre_impl(
     prefix: &storage::Key,
 ) -> Result<KeyValIterator<(String, Vec<u8>)>, Error> {
@@ -0,0 +1,71 @@
+//! Read-only views of the Proof of Stake state for validity predicates. The
+//! views read the state prior to the tx execution.
+
+use namada_proof_of_stake::types::{BondId, ValidatorState, WeightedValidator};
+
+use super::*;
+
+/// Read the state of a validator at the given epoch in the state prior to the
+/// tx. Returns `None` if the address isn't a validator at the epoch.
+pub fn pre_validator_state(
+    ctx: &Ctx,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<Option<ValidatorState>> {
+    proof_of_stake::read_validator_state(&ctx.pre(), validator, epoch)
+}
+
+/// Check if the validator is in the consensus validator set at the given
+/// epoch in the state prior to the tx.
+pub fn pre_is_in_consensus_set(
+    ctx: &Ctx,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<bool> {
+    proof_of_stake::is_consensus_validator(&ctx.pre(), validator, epoch)
+}
+
+/// Read the consensus validators with their bonded stake at the given
+/// epoch in the state prior to the tx.
+pub fn pre_consensus_validators(
+    ctx: &Ctx,
+    epoch: Epoch,
+) -> EnvResult<BTreeSet<WeightedValidator>> {
+    proof_of_stake::read_consensus_validator_set_addresses_with_stake(
+        &ctx.pre(),
+        epoch,
+    )
+}
+
+/// Read the bonded stake of a validator at the given epoch in the state prior
+/// to the tx. The stake of an address that isn't a validator is zero.
+pub fn pre_validator_stake(
+    ctx: &Ctx,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<token::Amount> {
+    let params = proof_of_stake::read_pos_params(&ctx.pre())?;
+    proof_of_stake::read_validator_stake(&ctx.pre(), &params, validator, epoch)
+}
+
+/// Read the total bonded stake at the given epoch in the state prior to the
+/// tx.
+pub fn pre_total_stake(ctx: &Ctx, epoch: Epoch) -> EnvResult<token::Amount> {
+    let params = proof_of_stake::read_pos_params(&ctx.pre())?;
+    proof_of_stake::read_total_stake(&ctx.pre(), &params, epoch)
+}
+
+/// Read the amount bonded by the source to the validator at the given
+/// epoch in the state prior to the tx, after slashing.
+pub fn pre_bonded_amount(
+    ctx: &Ctx,
+    source: &Address,
+    validator: &Address,
+    epoch: Epoch,
+) -> EnvResult<token::Amount> {
+    let bond_id = BondId {
+        source: source.clone(),
+        validator: validator.clone(),
+    };
+    proof_of_stake::bond_amount(&ctx.pre(), &bond_id, epoch)
+}
@@ -1,60 +1,42 @@
-//! A fungible token validity predicate.
+//! A fungible token validity predicate and token helpers for validity
+//! predicates, on top of the re-exported token types.
 
-use std::collections::BTreeSet;
+use std::collections::BTreeMap;
 
-use namada_core::types::address::{self, Address, InternalAddress};
-use namada_core::types::storage::Key;
-/// Vp imports and functions.
-use namada_core::types::storage::KeySeg;
-use namada_core::types::token;
+use namada_core::types::storage;
 pub use namada_core::types::token::*;
 
 use super::*;
 
-/// A token validity predicate.
+/// A token validity predicate. The balances of the token and of each of its
+/// sub-tokens must be conserved.
 pub fn vp(
     ctx: &Ctx,
     token: &Address,
-    keys_changed: &BTreeSet<Key>,
+    keys_changed: &BTreeSet<storage::Key>,
     verifiers: &BTreeSet<Address>,
 ) -> VpResult {
-    let mut change: Change = Change::default();
+    let mut changes: BTreeMap<Option<storage::Key>, Change> = BTreeMap::new();
     for key in keys_changed.iter() {
-        let owner: Option<&Address> =
-            match token::is_multitoken_balance_key(token, key) {
-                Some((_, o)) => Some(o),
-                None => token::is_balance_key(token, key),
+        let owner: Option<(Option<storage::Key>, &Address)> =
+            match is_multitoken_balance_key(token, key) {
+                Some((sub_prefix, o)) => Some((Some(sub_prefix), o)),
+                None => is_balance_key(token, key).map(|o| (None, o)),
             };
         match owner {
             None => {
                 // Unknown changes to this address space are disallowed, but
                 // unknown changes anywhere else are permitted
-                if key.segments.get(0) == Some(&token.to_db_key()) {
+                if is_any_multitoken_key(key) == Some(token) {
                     return reject();
                 }
             }
-            Some(owner) => {
+            Some((sub_prefix, owner)) => {
                 // accumulate the change
-                let pre: Amount = match owner {
-                    Address::Internal(InternalAddress::IbcMint) => {
-                        Amount::max_signed()
-                    }
-                    Address::Internal(InternalAddress::IbcBurn) => {
-                        Amount::default()
-                    }
-                    _ => ctx.read_pre(key)?.unwrap_or_default(),
-                };
-                let post: Amount = match owner {
-                    Address::Internal(InternalAddress::IbcMint) => {
-                        ctx.read_temp(key)?.unwrap_or_else(Amount::max_signed)
-                    }
-                    Address::Internal(InternalAddress::IbcBurn) => {
-                        ctx.read_temp(key)?.unwrap_or_default()
-                    }
-                    _ => ctx.read_post(key)?.unwrap_or_default(),
-                };
+                let pre: Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: Amount = ctx.read_post(key)?.unwrap_or_default();
                 let this_change = post.change() - pre.change();
-                change += this_change;
+                *changes.entry(sub_prefix).or_default() += this_change;
                 // make sure that the spender approved the transaction
                 if !(this_change.non_negative()
                     || verifiers.contains(owner)
@@ -65,5 +47,48 @@ pub fn vp(
             }
         }
     }
-    Ok(change.is_zero())
+    Ok(changes.values().all(|change| change.is_zero()))
+}
+
+/// Check if the balance of `token` of the `owner` decreased in this tx.
+pub fn is_debited(
+    ctx: &Ctx,
+    token: &Address,
+    owner: &Address,
+) -> EnvResult<bool> {
+    let key = balance_key(token, owner);
+    let pre: Amount = ctx.read_pre(&key)?.unwrap_or_default();
+    let post: Amount = ctx.read_post(&key)?.unwrap_or_default();
+    Ok(post < pre)
+}
+
+/// Check if the decrease of the balance of `token` of the `owner` in this tx
+/// is covered by the decrease of the allowances given by the `owner`, i.e. the
+/// tokens were transferred by spenders out of their allowances.
+pub fn is_debit_covered_by_allowances(
+    ctx: &Ctx,
+    token: &Address,
+    owner: &Address,
+    keys_changed: &BTreeSet<storage::Key>,
+) -> EnvResult<bool> {
+    let key = balance_key(token, owner);
+    let pre: Amount = ctx.read_pre(&key)?.unwrap_or_default();
+    let post: Amount = ctx.read_post(&key)?.unwrap_or_default();
+    let debit = pre.checked_sub(post).unwrap_or_default();
+
+    let mut spent = Amount::zero();
+    for key in keys_changed {
+        if let Some([allowance_token, allowance_owner, _]) =
+            is_any_allowance_key(key)
+        {
+            if allowance_token == token && allowance_owner == owner {
+                let pre: Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: Amount = ctx.read_post(key)?.unwrap_or_default();
+                spent = spent
+                    .checked_add(pre.checked_sub(post).unwrap_or_default())
+                    .ok_or_else(|| Error::new_const("Allowances overflow"))?;
+            }
+        }
+    }
+    Ok(debit <= spent)
 }
@@ -0,0 +1,195 @@
+//! Allowance tests shared by the user VPs. The VP under test is given by its
+//! `validate_tx` function and the address of an account holding the keys of
+//! `key::testing::keypair_1`.
+
+use namada::proto::Data;
+use namada::types::transaction::TxType;
+use namada_tests::tx::{self, tx_host_env, TestTxEnv};
+use namada_tests::vp::*;
+use namada_tx_prelude::storage_api;
+use namada_vp_prelude::key::RefTo;
+use namada_vp_prelude::*;
+
+/// The `validate_tx` function of a VP
+pub type ValidateTx = fn(
+    &Ctx,
+    Tx,
+    Address,
+    BTreeSet<storage::Key>,
+    BTreeSet<Address>,
+) -> VpResult;
+
+/// Run an unsigned tx in which the `spender` transfers `spent` tokens out
+/// of an allowance of `allowance` tokens given by the `owner`, followed by
+/// a direct debit of `extra_debit` tokens from the `owner`, and validate it
+/// with the VP of `vp_owner`.
+fn validate_unsigned_allowance_spending(
+    validate_tx: ValidateTx,
+    owner: &Address,
+    spender: &Address,
+    vp_owner: &Address,
+    allowance: u64,
+    spent: u64,
+    extra_debit: u64,
+) -> bool {
+    // Initialize a tx environment
+    let mut tx_env = TestTxEnv::default();
+
+    let public_key = key::testing::keypair_1().ref_to();
+    let target = address::testing::established_address_3();
+    let token = address::nam();
+
+    // Spawn the accounts to be able to modify their storage
+    tx_env.spawn_accounts([owner, spender, &target, &token]);
+    tx_env.init_account_storage(vp_owner, vec![public_key], 1);
+
+    // Credit the tokens to the owner and approve the allowance before
+    // running the transaction
+    tx_env.credit_tokens(owner, &token, token::Amount::from(100));
+    storage_api::token::write_denom(
+        &mut tx_env.wl_storage,
+        &token,
+        token::NATIVE_MAX_DECIMAL_PLACES.into(),
+    )
+    .unwrap();
+    storage_api::token::approve(
+        &mut tx_env.wl_storage,
+        &token,
+        owner,
+        spender,
+        token::Amount::from(allowance),
+    )
+    .unwrap();
+
+    let denominated = |amount: u64| token::DenominatedAmount {
+        amount: token::Amount::from(amount),
+        denom: token::NATIVE_MAX_DECIMAL_PLACES.into(),
+    };
+    // Initialize VP environment from a transaction
+    vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |_address| {
+        tx_host_env::token::transfer_from(
+            tx::ctx(),
+            spender,
+            owner,
+            &target,
+            &token,
+            denominated(spent),
+        )
+        .unwrap();
+        if extra_debit > 0 {
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                owner,
+                &target,
+                &token,
+                denominated(extra_debit),
+            )
+            .unwrap();
+        }
+    });
+
+    let vp_env = vp_host_env::take();
+    let mut tx_data = Tx::from_type(TxType::Raw);
+    tx_data.set_data(Data::new(vec![]));
+    let keys_changed: BTreeSet<storage::Key> =
+        vp_env.all_touched_storage_keys();
+    let verifiers: BTreeSet<Address> = BTreeSet::default();
+    vp_host_env::set(vp_env);
+    validate_tx(&CTX, tx_data, vp_owner.clone(), keys_changed, verifiers)
+        .unwrap()
+}
+
+/// Test that an unsigned debit made by a spender out of an allowance is
+/// accepted by the owner's VP.
+pub fn unsigned_debit_from_allowance_accepted(
+    validate_tx: ValidateTx,
+    owner: Address,
+) {
+    let spender = address::testing::established_address_2();
+    assert!(validate_unsigned_allowance_spending(
+        validate_tx,
+        &owner,
+        &spender,
+        &owner,
+        30,
+        20,
+        0
+    ));
+}
+
+/// Test that an unsigned debit exceeding the allowances spent in the tx is
+/// rejected by the owner's VP.
+pub fn unsigned_debit_exceeding_allowance_rejected(
+    validate_tx: ValidateTx,
+    owner: Address,
+) {
+    let spender = address::testing::established_address_2();
+    assert!(!validate_unsigned_allowance_spending(
+        validate_tx,
+        &owner,
+        &spender,
+        &owner,
+        30,
+        20,
+        5
+    ));
+}
+
+/// Test that spending an allowance without the spender's signature is
+/// rejected by the spender's VP.
+pub fn unsigned_allowance_spending_rejected(
+    validate_tx: ValidateTx,
+    spender: Address,
+) {
+    let owner = address::testing::established_address_2();
+    assert!(!validate_unsigned_allowance_spending(
+        validate_tx,
+        &owner,
+        &spender,
+        &spender,
+        30,
+        20,
+        0
+    ));
+}
+
+/// Test that an approval without the owner's signature is rejected.
+pub fn unsigned_approval_rejected(validate_tx: ValidateTx, vp_owner: Address) {
+    // Initialize a tx environment
+    let mut tx_env = TestTxEnv::default();
+
+    let public_key = key::testing::keypair_1().ref_to();
+    let spender = address::testing::established_address_2();
+    let token = address::nam();
+
+    // Spawn the accounts to be able to modify their storage
+    tx_env.spawn_accounts([&vp_owner, &spender, &token]);
+    tx_env.init_account_storage(&vp_owner, vec![public_key], 1);
+
+    let amount = token::DenominatedAmount {
+        amount: token::Amount::from(10),
+        denom: token::NATIVE_MAX_DECIMAL_PLACES.into(),
+    };
+    // Initialize VP environment from a transaction
+    vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+        tx_host_env::token::approve(
+            tx::ctx(),
+            address,
+            &spender,
+            &token,
+            amount,
+        )
+        .unwrap();
+    });
+
+    let vp_env = vp_host_env::take();
+    let mut tx_data = Tx::from_type(TxType::Raw);
+    tx_data.set_data(Data::new(vec![]));
+    let keys_changed: BTreeSet<storage::Key> =
+        vp_env.all_touched_storage_keys();
+    let verifiers: BTreeSet<Address> = BTreeSet::default();
+    vp_host_env::set(vp_env);
+    assert!(
+        !validate_tx(&CTX, tx_data, vp_owner, keys_changed, verifiers).unwrap()
+    );
+}
@@ -1,3 +1,9 @@
+#[cfg(all(test, any(feature = "vp_implicit", feature = "vp_user")))]
+mod allowance_tests;
+#[cfg(feature = "tx_approve")]
+pub mod tx_approve;
+#[cfg(feature = "tx_auto_compound")]
+pub mod tx_auto_compound;
 #[cfg(feature = "tx_become_validator")]
 pub mod tx_become_validator;
 #[cfg(feature = "tx_bond")]
@@ -14,12 +20,16 @@ pub mod tx_change_validator_metadata;
 pub mod tx_claim_rewards;
 #[cfg(feature = "tx_deactivate_validator")]
 pub mod tx_deactivate_validator;
+#[cfg(feature = "tx_faucet_withdraw")]
+pub mod tx_faucet_withdraw;
 #[cfg(feature = "tx_ibc")]
 pub mod tx_ibc;
 #[cfg(feature = "tx_init_account")]
 pub mod tx_init_account;
 #[cfg(feature = "tx_init_proposal")]
 pub mod tx_init_proposal;
+#[cfg(feature = "tx_oracle_submit")]
+pub mod tx_oracle_submit;
 #[cfg(feature = "tx_reactivate_validator")]
 pub mod tx_reactivate_validator;
 #[cfg(feature = "tx_redelegate")]
@@ -30,6 +40,8 @@ pub mod tx_resign_steward;
 pub mod tx_reveal_pk;
 #[cfg(feature = "tx_transfer")]
 pub mod tx_transfer;
+#[cfg(feature = "tx_transfer_from")]
+pub mod tx_transfer_from;
 #[cfg(feature = "tx_unbond")]
 pub mod tx_unbond;
 #[cfg(feature = "tx_unjail_validator")]
@@ -47,3 +59,5 @@ pub mod tx_withdraw;
 pub mod vp_implicit;
 #[cfg(feature = "vp_user")]
 pub mod vp_user;
+#[cfg(feature = "vp_vesting")]
+pub mod vp_vesting;
@@ -0,0 +1,25 @@
+//! A tx to allow a spender to transfer tokens from the balance of the owner.
+//! This tx uses `token::Approval` wrapped inside `SignedTxData`
+//! as its input as declared in `shared` crate.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1703358)]
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let approval = token::Approval::try_from_slice(&data[..])
+        .wrap_err("failed to decode token::Approval")?;
+    debug_log!("apply_tx called with approval: {:#?}", approval);
+
+    token::approve(
+        ctx,
+        &approval.owner,
+        &approval.spender,
+        &approval.token,
+        approval.amount,
+    )
+}
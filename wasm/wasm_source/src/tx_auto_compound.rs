@@ -0,0 +1,19 @@
+//! A tx for a user to opt a bond in or out of the automatic re-bonding of its
+//! PoS rewards at every new epoch.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let auto_compound =
+        transaction::pos::AutoCompound::try_from_slice(&data[..])
+            .wrap_err("failed to decode AutoCompound")?;
+
+    ctx.set_auto_compound(
+        auto_compound.source.as_ref(),
+        &auto_compound.validator,
+        auto_compound.enabled,
+    )
+}
@@ -0,0 +1,27 @@
+//! A tx for a withdrawal from the testnet faucet.
+//! This tx uses `testnet_pow::FaucetWithdrawal` wrapped inside `SignedTxData`
+//! as its input as declared in `shared` crate.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1703358)]
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let withdrawal =
+        testnet_pow::FaucetWithdrawal::try_from_slice(&data[..])
+            .wrap_err("failed to decode testnet_pow::FaucetWithdrawal")?;
+    debug_log!("apply_tx called with faucet withdrawal: {:#?}", withdrawal);
+
+    token::transfer(
+        ctx,
+        &testnet_pow::ADDRESS,
+        &withdrawal.target,
+        &withdrawal.token,
+        withdrawal.amount,
+    )?;
+    testnet_pow::record_withdrawal(ctx, &withdrawal)
+}
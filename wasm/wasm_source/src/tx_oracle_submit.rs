@@ -0,0 +1,21 @@
+//! A tx for a whitelisted submitter to submit the value of an oracle feed.
+//! This tx uses `oracle::OracleSubmission` wrapped inside `SignedTxData`
+//! as its input as declared in `shared` crate.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 220000)]
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let submission = oracle::OracleSubmission::try_from_slice(&data[..])
+        .wrap_err("failed to decode oracle::OracleSubmission")?;
+    debug_log!("apply_tx called with oracle submission: {:#?}", submission);
+
+    // The submitter must authorize the submission
+    ctx.insert_verifier(&submission.submitter)?;
+    oracle::submit(ctx, &submission)
+}
@@ -0,0 +1,27 @@
+//! A tx for a token transfer made by a spender out of the allowance given to
+//! it by the source.
+//! This tx uses `token::TransferFrom` wrapped inside `SignedTxData`
+//! as its input as declared in `shared` crate.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1703358)]
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let transfer = token::TransferFrom::try_from_slice(&data[..])
+        .wrap_err("failed to decode token::TransferFrom")?;
+    debug_log!("apply_tx called with transfer from: {:#?}", transfer);
+
+    token::transfer_from(
+        ctx,
+        &transfer.spender,
+        &transfer.source,
+        &transfer.target,
+        &transfer.token,
+        transfer.amount,
+    )
+}
@@ -2,6 +2,8 @@
 //! This tx wraps the validity predicate inside `SignedTxData` as
 //! its input as declared in `shared` crate.
 
+use std::collections::BTreeSet;
+
 use namada_tx_prelude::key::pks_handle;
 use namada_tx_prelude::*;
 
@@ -34,11 +36,78 @@ fn apply_tx(ctx: &mut Ctx, tx: Tx) -> TxResult {
                 err
             })?;
 
-        ctx.update_validity_predicate(
-            owner,
-            vp_code_sec.code.hash(),
-            &vp_code_sec.tag,
-        )?;
+        let vp_code_hash = vp_code_sec.code.hash();
+        match storage_api::account::vp_timelock(ctx, owner)? {
+            Some(timelock) => {
+                // Under a timelock, the update is first scheduled and can only
+                // be applied by a later tx once its activation epoch is reached
+                let pending_key = key::pending_vp_key(owner);
+                let epoch = ctx.get_block_epoch()?;
+                match storage_api::account::pending_vp_update(ctx, owner)? {
+                    Some(pending) if pending.vp_code_hash == vp_code_hash => {
+                        if pending.activation_epoch > epoch {
+                            debug_log!(
+                                "VP update can only be applied from epoch {}",
+                                pending.activation_epoch
+                            );
+                            return Err(Error::new_const(
+                                "The scheduled VP update is not active yet",
+                            ));
+                        }
+                        ctx.update_validity_predicate(
+                            owner,
+                            vp_code_hash,
+                            &vp_code_sec.tag,
+                        )?;
+                        ctx.delete(&pending_key)?;
+                    }
+                    _ => {
+                        let pending = account::PendingVpUpdate {
+                            vp_code_hash,
+                            activation_epoch: epoch + timelock.delay,
+                        };
+                        debug_log!("schedule VP update: {:#?}", pending);
+                        ctx.write(&pending_key, pending)?;
+                    }
+                }
+            }
+            None => {
+                ctx.update_validity_predicate(
+                    owner,
+                    vp_code_hash,
+                    &vp_code_sec.tag,
+                )?;
+            }
+        }
+    }
+
+    if tx_data.cancel_vp_update {
+        ctx.delete(&key::pending_vp_key(owner))?;
+    }
+
+    if let Some(timelock) = tx_data.vp_timelock {
+        ctx.write(&key::vp_timelock_key(owner), timelock)?;
+    }
+
+    if let Some(allowed_txs) = tx_data.allowed_txs {
+        let allowed_txs_key = key::allowed_txs_key(owner);
+        if allowed_txs.is_empty() {
+            ctx.delete(&allowed_txs_key)?;
+        } else {
+            let allowed_txs: BTreeSet<hash::Hash> =
+                allowed_txs.into_iter().collect();
+            debug_log!("restrict the allowed txs to: {:#?}", allowed_txs);
+            ctx.write(&allowed_txs_key, allowed_txs)?;
+        }
+    }
+
+    if let Some(policy) = tx_data.policy {
+        if policy == account::AccountPolicy::default() {
+            ctx.delete(&key::policy_key(owner))?;
+        } else {
+            debug_log!("set the account policy: {:#?}", policy);
+            storage_api::account::write_policy(ctx, owner, &policy)?;
+        }
     }
 
     if let Some(threshold) = tx_data.threshold {
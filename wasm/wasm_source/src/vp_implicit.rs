@@ -1,7 +1,9 @@
 //! Implicit account VP. All implicit accounts share this same VP.
 //!
 //! This VP currently provides a signature verification against a public key for
-//! sending tokens (receiving tokens is permissive).
+//! sending tokens (receiving tokens is permissive). Tokens can also be sent
+//! without a signature by a spender, out of an allowance that was approved with
+//! a valid signature.
 //!
 //! It allows to reveal a PK, as long as its address matches with the address
 //! that can be derived from the PK.
@@ -9,6 +11,8 @@
 //! It allows to bond, unbond and withdraw tokens to and from PoS system with a
 //! valid signature.
 //!
+//! The sequence number of the account cannot be changed by a tx.
+//!
 //! Any other storage key changes are allowed only with a valid signature.
 
 use namada_vp_prelude::storage::KeySeg;
@@ -19,10 +23,19 @@ enum KeyType<'a> {
     /// Public key - written once revealed
     Pk(&'a Address),
     Token {
+        token: &'a Address,
+        owner: &'a Address,
+    },
+    Allowance {
+        token: &'a Address,
         owner: &'a Address,
+        spender: &'a Address,
     },
     PoS,
     GovernanceVote(&'a Address),
+    OracleSubmission(&'a Address),
+    /// Sequence number - only written by the protocol for wrapper txs
+    Sequence(&'a Address),
     Unknown,
 }
 
@@ -30,8 +43,18 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
     fn from(key: &'a storage::Key) -> KeyType<'a> {
         if let Some(address) = key::is_pks_key(key) {
             Self::Pk(address)
-        } else if let Some([_, owner]) = token::is_any_token_balance_key(key) {
-            Self::Token { owner }
+        } else if let Some([token, owner]) =
+            token::is_any_token_balance_key(key)
+        {
+            Self::Token { token, owner }
+        } else if let Some([token, owner, spender]) =
+            token::is_any_allowance_key(key)
+        {
+            Self::Allowance {
+                token,
+                owner,
+                spender,
+            }
         } else if proof_of_stake::storage::is_pos_key(key) {
             Self::PoS
         } else if gov_storage::keys::is_vote_key(key) {
@@ -41,6 +64,12 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             } else {
                 Self::Unknown
             }
+        } else if let Some((_feed_id, submitter)) =
+            oracle::is_submission_key(key)
+        {
+            Self::OracleSubmission(submitter)
+        } else if let Some(address) = key::is_sequence_key(key) {
+            Self::Sequence(address)
         } else {
             Self::Unknown
         }
@@ -98,15 +127,26 @@ fn validate_tx(
                 }
                 true
             }
-            KeyType::Token { owner, .. } => {
+            KeyType::Token {
+                token: token_addr,
+                owner,
+            } => {
                 if owner == &addr {
                     let pre: token::Amount =
                         ctx.read_pre(key)?.unwrap_or_default();
                     let post: token::Amount =
                         ctx.read_post(key)?.unwrap_or_default();
                     let change = post.change() - pre.change();
-                    // debit has to signed, credit doesn't
-                    let valid = change.non_negative() || *valid_sig;
+                    // debit has to signed, unless it is spent out of
+                    // allowances, credit doesn't
+                    let valid = change.non_negative()
+                        || *valid_sig
+                        || token::is_debit_covered_by_allowances(
+                            ctx,
+                            token_addr,
+                            &addr,
+                            &keys_changed,
+                        )?;
                     let sign = if change.non_negative() { "" } else { "-" };
                     debug_log!(
                         "token key: {}, change: {}{:?}, valid_sig: {}, valid \
@@ -130,6 +170,27 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::Allowance {
+                token: token_addr,
+                owner,
+                spender,
+            } => {
+                let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: token::Amount =
+                    ctx.read_post(key)?.unwrap_or_default();
+                let is_spent =
+                    post < pre && token::is_debited(ctx, token_addr, owner)?;
+                if owner == &addr {
+                    // approvals and revocations have to be signed by the
+                    // owner, spending is authorized by the spender
+                    is_spent || *valid_sig
+                } else if spender == &addr {
+                    // spending has to be signed by the spender
+                    !is_spent || *valid_sig
+                } else {
+                    true
+                }
+            }
             KeyType::PoS => {
                 // Allow the account to be used in PoS
                 let bond_id = proof_of_stake::storage::is_bond_key(key)
@@ -137,11 +198,14 @@ fn validate_tx(
                     .or_else(|| {
                         proof_of_stake::storage::is_unbond_key(key)
                             .map(|(bond_id, _, _)| bond_id)
+                    })
+                    .or_else(|| {
+                        proof_of_stake::storage::is_auto_compound_key(key)
                     });
                 let valid = match bond_id {
                     Some(bond_id) => {
-                        // Bonds and unbonds changes for this address
-                        // must be signed
+                        // Bonds, unbonds and auto-compound changes for
+                        // this address must be signed
                         bond_id.source != addr || *valid_sig
                     }
                     None => {
@@ -163,6 +227,14 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::OracleSubmission(submitter) => {
+                if submitter == &addr {
+                    *valid_sig
+                } else {
+                    true
+                }
+            }
+            KeyType::Sequence(owner) => owner != &addr,
             KeyType::Unknown => {
                 if key.segments.get(0) == Some(&addr.to_db_key()) {
                     // Unknown changes to this address space require a valid
@@ -204,6 +276,7 @@ mod tests {
     use storage::testing::arb_account_storage_key_no_vp;
 
     use super::*;
+    use crate::allowance_tests;
 
     /// Test that no-op transaction (i.e. no storage modifications) accepted.
     #[test]
@@ -687,6 +760,45 @@ mod tests {
         );
     }
 
+    /// Test that an unsigned debit made by a spender out of an allowance is
+    /// accepted by the owner's VP.
+    #[test]
+    fn test_unsigned_debit_from_allowance_accepted() {
+        allowance_tests::unsigned_debit_from_allowance_accepted(
+            validate_tx,
+            Address::from(&key::testing::keypair_1().ref_to()),
+        );
+    }
+
+    /// Test that an unsigned debit exceeding the allowances spent in the tx is
+    /// rejected by the owner's VP.
+    #[test]
+    fn test_unsigned_debit_exceeding_allowance_rejected() {
+        allowance_tests::unsigned_debit_exceeding_allowance_rejected(
+            validate_tx,
+            Address::from(&key::testing::keypair_1().ref_to()),
+        );
+    }
+
+    /// Test that spending an allowance without the spender's signature is
+    /// rejected by the spender's VP.
+    #[test]
+    fn test_unsigned_allowance_spending_rejected() {
+        allowance_tests::unsigned_allowance_spending_rejected(
+            validate_tx,
+            Address::from(&key::testing::keypair_1().ref_to()),
+        );
+    }
+
+    /// Test that an approval without the owner's signature is rejected.
+    #[test]
+    fn test_unsigned_approval_rejected() {
+        allowance_tests::unsigned_approval_rejected(
+            validate_tx,
+            Address::from(&key::testing::keypair_1().ref_to()),
+        );
+    }
+
     /// Test that a transfer on with accounts other than self is accepted.
     #[test]
     fn test_transfer_between_other_parties_accepted() {
@@ -887,6 +999,59 @@ mod tests {
         );
     }
 
+    /// Test that a signed tx cannot change the sequence number of the account.
+    #[test]
+    fn test_signed_sequence_write_rejected() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let secret_key = key::testing::keypair_1();
+        let public_key = secret_key.ref_to();
+        let vp_owner: Address = (&public_key).into();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner]);
+
+        let _ = storage_api::account::set_public_key_at(
+            tx_host_env::ctx(),
+            &vp_owner,
+            &public_key,
+            0,
+        );
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Reset the sequence number in a transaction
+            tx::ctx().write(&key::sequence_key(address), 0_u64).unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![secret_key]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(!validate_tx(
+            &CTX,
+            signed_tx,
+            vp_owner,
+            keys_changed,
+            verifiers
+        )
+        .unwrap());
+    }
+
     /// Test that a tx is rejected if not whitelisted
     #[test]
     fn test_tx_not_whitelisted_rejected() {
@@ -1,7 +1,9 @@
 //! A basic user VP supports both non-validator and validator accounts.
 //!
 //! This VP currently provides a signature verification against a public key for
-//! sending tokens (receiving tokens is permissive).
+//! sending tokens (receiving tokens is permissive). Tokens can also be sent
+//! without a signature by a spender, out of an allowance that was approved with
+//! a valid signature.
 //!
 //! It allows to bond, unbond and withdraw tokens to and from PoS system with a
 //! valid signature(s).
@@ -9,6 +11,20 @@
 //! For validator a tx to change a validator's commission rate or metadata
 //! requires a valid signature(s) only from the validator.
 //!
+//! An account can store an authorization policy that is enforced on debits
+//! of its tokens on top of the signature verification: spend limits per token
+//! per epoch, allowlisted counterparties and a higher signature threshold
+//! required above a debited amount. Changes to the policy must be signed with
+//! all the keys of the account.
+//!
+//! An account can also restrict the txs allowed to operate on it to a list of
+//! tx code hashes. Any other tx can only credit its balances.
+//!
+//! An account can also store a timelock on its VP updates. An update must then
+//! first be scheduled and can only be applied once its activation epoch is
+//! reached. A scheduled update can be cancelled with the recovery key of the
+//! timelock, which must also sign any change of the timelock.
+//!
 //! Any other storage key changes are allowed only with a valid signature.
 
 use namada_vp_prelude::storage::KeySeg;
@@ -17,19 +33,43 @@ use once_cell::unsync::Lazy;
 use proof_of_stake::types::ValidatorState;
 
 enum KeyType<'a> {
-    Token { owner: &'a Address },
+    Token {
+        token: &'a Address,
+        owner: &'a Address,
+    },
+    Allowance {
+        token: &'a Address,
+        owner: &'a Address,
+        spender: &'a Address,
+    },
     PoS,
     Vp(&'a Address),
     Masp,
     PgfStward(&'a Address),
     GovernanceVote(&'a Address),
+    OracleSubmission(&'a Address),
+    Policy(&'a Address),
+    Spending {
+        owner: &'a Address,
+        token: &'a Address,
+    },
+    VpTimelock(&'a Address),
+    PendingVp(&'a Address),
     Unknown,
 }
 
 impl<'a> From<&'a storage::Key> for KeyType<'a> {
     fn from(key: &'a storage::Key) -> KeyType<'a> {
-        if let Some([_, owner]) = token::is_any_token_balance_key(key) {
-            Self::Token { owner }
+        if let Some([token, owner]) = token::is_any_token_balance_key(key) {
+            Self::Token { token, owner }
+        } else if let Some([token, owner, spender]) =
+            token::is_any_allowance_key(key)
+        {
+            Self::Allowance {
+                token,
+                owner,
+                spender,
+            }
         } else if proof_of_stake::storage::is_pos_key(key) {
             Self::PoS
         } else if gov_storage::keys::is_vote_key(key) {
@@ -39,8 +79,20 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             } else {
                 Self::Unknown
             }
+        } else if let Some((_feed_id, submitter)) =
+            oracle::is_submission_key(key)
+        {
+            Self::OracleSubmission(submitter)
         } else if let Some(address) = pgf_storage::keys::is_stewards_key(key) {
             Self::PgfStward(address)
+        } else if let Some(address) = key::is_policy_key(key) {
+            Self::Policy(address)
+        } else if let Some([owner, token]) = key::is_spending_key(key) {
+            Self::Spending { owner, token }
+        } else if let Some(address) = key::is_vp_timelock_key(key) {
+            Self::VpTimelock(address)
+        } else if let Some(address) = key::is_pending_vp_key(key) {
+            Self::PendingVp(address)
         } else if let Some(address) = key.is_validity_predicate() {
             Self::Vp(address)
         } else if token::is_masp_key(key) {
@@ -74,18 +126,41 @@ fn validate_tx(
         return reject();
     }
 
+    if !is_tx_allowed_by_account(ctx, &addr, &keys_changed)? {
+        debug_log!("The tx is not allowed to operate on the account");
+        return reject();
+    }
+
     for key in keys_changed.iter() {
         let key_type: KeyType = key.into();
         let is_valid = match key_type {
-            KeyType::Token { owner, .. } => {
+            KeyType::Token {
+                token: token_addr,
+                owner,
+            } => {
                 if owner == &addr {
                     let pre: token::Amount =
                         ctx.read_pre(key)?.unwrap_or_default();
                     let post: token::Amount =
                         ctx.read_post(key)?.unwrap_or_default();
                     let change = post.change() - pre.change();
-                    // debit has to signed, credit doesn't
-                    let valid = change.non_negative() || *valid_sig;
+                    // debit has to signed, unless it is spent out of
+                    // allowances, credit doesn't
+                    let valid = (change.non_negative()
+                        || *valid_sig
+                        || token::is_debit_covered_by_allowances(
+                            ctx,
+                            token_addr,
+                            &addr,
+                            &keys_changed,
+                        )?)
+                        && is_debit_allowed_by_policy(
+                            ctx,
+                            &tx_data,
+                            token_addr,
+                            &addr,
+                            &keys_changed,
+                        )?;
                     debug_log!(
                         "token key: {}, change: {:?}, valid_sig: {}, valid \
                          modification: {}",
@@ -107,18 +182,42 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::Allowance {
+                token: token_addr,
+                owner,
+                spender,
+            } => {
+                let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: token::Amount =
+                    ctx.read_post(key)?.unwrap_or_default();
+                let is_spent =
+                    post < pre && token::is_debited(ctx, token_addr, owner)?;
+                if owner == &addr {
+                    // approvals and revocations have to be signed by the
+                    // owner, spending is authorized by the spender
+                    is_spent || *valid_sig
+                } else if spender == &addr {
+                    // spending has to be signed by the spender
+                    !is_spent || *valid_sig
+                } else {
+                    true
+                }
+            }
             KeyType::PoS => {
-                // Bond or unbond
+                // Bond, unbond or auto-compound opt-in
                 let bond_id = proof_of_stake::storage::is_bond_key(key)
                     .map(|(bond_id, _)| bond_id)
                     .or_else(|| {
                         proof_of_stake::storage::is_unbond_key(key)
                             .map(|(bond_id, _, _)| bond_id)
+                    })
+                    .or_else(|| {
+                        proof_of_stake::storage::is_auto_compound_key(key)
                     });
                 let valid_bond_or_unbond_change = match bond_id {
                     Some(bond_id) => {
-                        // Bonds and unbonds changes for this address
-                        // must be signed
+                        // Bonds, unbonds and auto-compound changes for
+                        // this address must be signed
                         bond_id.source != addr || *valid_sig
                     }
                     None => {
@@ -213,6 +312,13 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::OracleSubmission(submitter) => {
+                if submitter == &addr {
+                    *valid_sig
+                } else {
+                    true
+                }
+            }
             KeyType::PgfStward(address) => {
                 if address == &addr {
                     *valid_sig
@@ -220,13 +326,78 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::Policy(owner) => {
+                if owner == &addr {
+                    // Policy changes must be signed with all the keys of the
+                    // account, or with the highest threshold set by the
+                    // current policy if it's higher
+                    let threshold = policy_change_threshold(ctx, &addr)?;
+                    *valid_sig
+                        && matches!(
+                            verify_signatures_with_threshold(
+                                ctx, &tx_data, &addr, threshold
+                            ),
+                            Ok(true)
+                        )
+                } else {
+                    true
+                }
+            }
+            KeyType::Spending { owner, token } => {
+                if owner == &addr {
+                    // The spending record can only be updated with the debits
+                    // and credits of the balance
+                    is_spending_record_valid(ctx, token, &addr)?
+                } else {
+                    true
+                }
+            }
+            KeyType::VpTimelock(owner) => {
+                if owner == &addr {
+                    // Changing an existing timelock requires the recovery key
+                    let has_pre: bool = ctx.has_key_pre(key)?;
+                    *valid_sig
+                        && (!has_pre
+                            || is_signed_by_recovery_key(ctx, &tx_data, &addr)?)
+                } else {
+                    true
+                }
+            }
+            KeyType::PendingVp(owner) => {
+                if owner == &addr {
+                    match storage_api::account::pending_vp_update(
+                        &ctx.post(),
+                        &addr,
+                    )? {
+                        Some(pending) => {
+                            *valid_sig
+                                && is_vp_update_scheduled(ctx, &addr, &pending)?
+                        }
+                        // A scheduled update is removed either when it's
+                        // applied or when it's cancelled with the recovery key
+                        None => {
+                            (*valid_sig
+                                && keys_changed.contains(
+                                    &storage::Key::validity_predicate(&addr),
+                                ))
+                                || is_signed_by_recovery_key(
+                                    ctx, &tx_data, &addr,
+                                )?
+                        }
+                    }
+                } else {
+                    true
+                }
+            }
             KeyType::Vp(owner) => {
                 let has_post: bool = ctx.has_key_post(key)?;
                 if owner == &addr {
                     if has_post {
                         let vp_hash: Vec<u8> =
                             ctx.read_bytes_post(key)?.unwrap();
-                        *valid_sig && is_vp_whitelisted(ctx, &vp_hash)?
+                        *valid_sig
+                            && is_vp_whitelisted(ctx, &vp_hash)?
+                            && is_vp_update_unlocked(ctx, &addr, &vp_hash)?
                     } else {
                         false
                     }
@@ -256,12 +427,250 @@ fn validate_tx(
     accept()
 }
 
+/// Check that the tx is allowed to operate on the account of the `owner`, if
+/// it restricts the allowed tx code hashes. A tx that isn't allowed can still
+/// credit the balances of the account, but not change anything else of it.
+fn is_tx_allowed_by_account(
+    ctx: &Ctx,
+    owner: &Address,
+    keys_changed: &BTreeSet<storage::Key>,
+) -> EnvResult<bool> {
+    let allowed_txs =
+        match storage_api::account::allowed_txs(&ctx.pre(), owner)? {
+            Some(allowed_txs) => allowed_txs,
+            None => return Ok(true),
+        };
+    if let Some(tx_hash) = ctx.get_tx_code_hash()? {
+        if allowed_txs.contains(&tx_hash) {
+            return Ok(true);
+        }
+    }
+    for key in keys_changed {
+        if !key.find_addresses().contains(owner) {
+            continue;
+        }
+        let is_credit = match token::is_any_token_balance_key(key) {
+            Some([_, balance_owner]) if balance_owner == owner => {
+                let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: token::Amount =
+                    ctx.read_post(key)?.unwrap_or_default();
+                post >= pre
+            }
+            _ => false,
+        };
+        if !is_credit {
+            debug_log!("key {} cannot be changed by the tx", key);
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Check that the decrease of the balance of `token` of the `owner` in this tx
+/// satisfies the authorization policy of the `owner`, if any.
+fn is_debit_allowed_by_policy(
+    ctx: &Ctx,
+    tx_data: &Tx,
+    token: &Address,
+    owner: &Address,
+    keys_changed: &BTreeSet<storage::Key>,
+) -> EnvResult<bool> {
+    let debit = read_debit(ctx, token, owner)?;
+    if debit.is_zero() {
+        return Ok(true);
+    }
+    let policy = match storage_api::account::policy(&ctx.pre(), owner)? {
+        Some(policy) => policy,
+        None => return Ok(true),
+    };
+
+    if let Some(limit) = policy.spend_limits.get(token) {
+        if !is_spending_record_valid(ctx, token, owner)? {
+            return Ok(false);
+        }
+        let epoch = ctx.get_block_epoch()?;
+        let spent = storage_api::account::spending(&ctx.post(), owner, token)?
+            .map(|record| record.spent_in(epoch))
+            .unwrap_or_default();
+        if spent > *limit {
+            debug_log!(
+                "Spent {} of token {} in this epoch, over the limit {}",
+                spent.to_string_native(),
+                token,
+                limit.to_string_native()
+            );
+            return Ok(false);
+        }
+    }
+
+    for key in keys_changed {
+        if let Some([credited_token, counterparty]) =
+            token::is_any_token_balance_key(key)
+        {
+            if credited_token == token
+                && counterparty != owner
+                && !policy.is_counterparty_allowed(counterparty)
+            {
+                let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: token::Amount =
+                    ctx.read_post(key)?.unwrap_or_default();
+                if post > pre {
+                    debug_log!(
+                        "Counterparty {} is not allowed by the policy",
+                        counterparty
+                    );
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    Ok(match policy.required_threshold(token, debit) {
+        Some(threshold) => matches!(
+            verify_signatures_with_threshold(ctx, tx_data, owner, threshold),
+            Ok(true)
+        ),
+        None => true,
+    })
+}
+
+/// The signature threshold required to change the authorization policy of the
+/// `owner`, so that a single leaked key of a multisig account cannot lift the
+/// policy
+fn policy_change_threshold(ctx: &Ctx, owner: &Address) -> EnvResult<u8> {
+    let keys = storage_api::account::public_keys(&ctx.pre(), owner)?.len();
+    let keys = u8::try_from(keys).unwrap_or(u8::MAX);
+    let max_threshold = storage_api::account::policy(&ctx.pre(), owner)?
+        .and_then(|policy| policy.max_threshold())
+        .unwrap_or_default();
+    Ok(keys.max(max_threshold).max(1))
+}
+
+/// Check that the debits and credits of `token` added to the spending record
+/// of the `owner` in this tx, within the current epoch, add up to the change
+/// of its balance.
+fn is_spending_record_valid(
+    ctx: &Ctx,
+    token: &Address,
+    owner: &Address,
+) -> EnvResult<bool> {
+    let epoch = ctx.get_block_epoch()?;
+    let record_pre = storage_api::account::spending(&ctx.pre(), owner, token)?
+        .filter(|record| record.epoch == epoch)
+        .unwrap_or_default();
+    let record_post =
+        match storage_api::account::spending(&ctx.post(), owner, token)? {
+            Some(record) if record.epoch == epoch => record,
+            _ => return Ok(false),
+        };
+    if record_post.spent < record_pre.spent
+        || record_post.received < record_pre.received
+    {
+        return Ok(false);
+    }
+    // The recorded debits net of the recorded credits must match the net
+    // debit of the balance: `spent_post - spent_pre - (received_post -
+    // received_pre) == balance_pre - balance_post`
+    let key = token::balance_key(token, owner);
+    let balance_pre: token::Amount = ctx.read_pre(&key)?.unwrap_or_default();
+    let balance_post: token::Amount = ctx.read_post(&key)?.unwrap_or_default();
+    let lhs = record_post
+        .spent
+        .checked_add(record_pre.received)
+        .and_then(|amount| amount.checked_add(balance_post));
+    let rhs = record_pre
+        .spent
+        .checked_add(record_post.received)
+        .and_then(|amount| amount.checked_add(balance_pre));
+    Ok(lhs.is_some() && lhs == rhs)
+}
+
+/// Check that a VP update of the `owner` is allowed by its timelock, if any,
+/// i.e. the update with the same code was scheduled and is active.
+fn is_vp_update_unlocked(
+    ctx: &Ctx,
+    owner: &Address,
+    vp_hash: &[u8],
+) -> EnvResult<bool> {
+    if storage_api::account::vp_timelock(&ctx.pre(), owner)?.is_none() {
+        return Ok(true);
+    }
+    let epoch = ctx.get_block_epoch()?;
+    Ok(
+        match storage_api::account::pending_vp_update(&ctx.pre(), owner)? {
+            Some(pending) => {
+                pending.vp_code_hash.0.as_slice() == vp_hash
+                    && pending.activation_epoch <= epoch
+            }
+            None => false,
+        },
+    )
+}
+
+/// Check that a scheduled VP update of the `owner` doesn't become active
+/// before the delay of its timelock.
+fn is_vp_update_scheduled(
+    ctx: &Ctx,
+    owner: &Address,
+    pending: &account::PendingVpUpdate,
+) -> EnvResult<bool> {
+    let epoch = ctx.get_block_epoch()?;
+    Ok(
+        match storage_api::account::vp_timelock(&ctx.pre(), owner)? {
+            Some(timelock) => {
+                pending.activation_epoch >= epoch + timelock.delay
+            }
+            None => false,
+        },
+    )
+}
+
+/// Check if the tx is signed with the recovery key of the VP timelock of the
+/// `owner`.
+fn is_signed_by_recovery_key(
+    ctx: &Ctx,
+    tx_data: &Tx,
+    owner: &Address,
+) -> EnvResult<bool> {
+    Ok(
+        match storage_api::account::vp_timelock(&ctx.pre(), owner)? {
+            Some(timelock) => matches!(
+                verify_signatures_with_keys(
+                    ctx,
+                    tx_data,
+                    owner,
+                    account::AccountPublicKeysMap::from_iter([
+                        timelock.recovery_key
+                    ]),
+                    1
+                ),
+                Ok(true)
+            ),
+            None => false,
+        },
+    )
+}
+
+/// Read the decrease of the balance of `token` of the `owner` in this tx.
+fn read_debit(
+    ctx: &Ctx,
+    token: &Address,
+    owner: &Address,
+) -> EnvResult<token::Amount> {
+    let key = token::balance_key(token, owner);
+    let pre: token::Amount = ctx.read_pre(&key)?.unwrap_or_default();
+    let post: token::Amount = ctx.read_post(&key)?.unwrap_or_default();
+    Ok(pre.checked_sub(post).unwrap_or_default())
+}
+
 #[cfg(test)]
 mod tests {
     use address::testing::arb_non_internal_address;
+    use namada::core::ledger::storage::testing::TestWlStorage;
     use namada::ledger::pos::{GenesisValidator, PosParams};
     use namada::proto::{Code, Data, Signature};
     use namada::types::dec::Dec;
+    use namada::types::hash::Hash;
     use namada::types::storage::Epoch;
     use namada::types::transaction::{self, TxType};
     use namada_test_utils::TestWasms;
@@ -273,11 +682,13 @@ mod tests {
     use namada_tests::vp::*;
     use namada_tx_prelude::{StorageWrite, TxEnv};
     use namada_vp_prelude::account::AccountPublicKeysMap;
+    use namada_vp_prelude::borsh_ext::BorshSerializeExt;
     use namada_vp_prelude::key::RefTo;
     use proptest::prelude::*;
     use storage::testing::arb_account_storage_key_no_vp;
 
     use super::*;
+    use crate::allowance_tests;
 
     /// Test that no-op transaction (i.e. no storage modifications) accepted.
     #[test]
@@ -475,6 +886,323 @@ mod tests {
         );
     }
 
+    /// Test that an unsigned debit made by a spender out of an allowance is
+    /// accepted by the owner's VP.
+    #[test]
+    fn test_unsigned_debit_from_allowance_accepted() {
+        allowance_tests::unsigned_debit_from_allowance_accepted(
+            validate_tx,
+            address::testing::established_address_1(),
+        );
+    }
+
+    /// Test that an unsigned debit exceeding the allowances spent in the tx is
+    /// rejected by the owner's VP.
+    #[test]
+    fn test_unsigned_debit_exceeding_allowance_rejected() {
+        allowance_tests::unsigned_debit_exceeding_allowance_rejected(
+            validate_tx,
+            address::testing::established_address_1(),
+        );
+    }
+
+    /// Test that spending an allowance without the spender's signature is
+    /// rejected by the spender's VP.
+    #[test]
+    fn test_unsigned_allowance_spending_rejected() {
+        allowance_tests::unsigned_allowance_spending_rejected(
+            validate_tx,
+            address::testing::established_address_1(),
+        );
+    }
+
+    /// Test that an approval without the owner's signature is rejected.
+    #[test]
+    fn test_unsigned_approval_rejected() {
+        allowance_tests::unsigned_approval_rejected(
+            validate_tx,
+            address::testing::established_address_1(),
+        );
+    }
+
+    /// Set up the given authorization policy on the account.
+    fn with_policy(
+        policy: account::AccountPolicy,
+    ) -> impl FnOnce(&mut TestWlStorage, &Address) {
+        move |wl_storage, vp_owner| {
+            storage_api::account::write_policy(wl_storage, vp_owner, &policy)
+                .unwrap();
+        }
+    }
+
+    /// Run a signed transfer of `amount` from an account whose storage is set
+    /// up with the given function and return the result of its VP.
+    fn validate_signed_transfer(
+        amount: token::Amount,
+        setup: impl FnOnce(&mut TestWlStorage, &Address),
+    ) -> bool {
+        validate_signed_transfers(amount, token::Amount::zero(), setup)
+    }
+
+    /// Run a signed tx that transfers `sent` from an account whose storage is
+    /// set up with the given function and `received` back to it, and return
+    /// the result of its VP.
+    fn validate_signed_transfers(
+        sent: token::Amount,
+        received: token::Amount,
+        setup: impl FnOnce(&mut TestWlStorage, &Address),
+    ) -> bool {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let target = address::testing::established_address_2();
+        let token = address::nam();
+        let balance = token::Amount::from_uint(10_098_123, 0).unwrap();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &target, &token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        setup(&mut tx_env.wl_storage, &vp_owner);
+
+        // Credit the tokens to the VP owner before running the transaction to
+        // be able to transfer from it
+        tx_env.credit_tokens(&vp_owner, &token, balance);
+        tx_env.credit_tokens(&target, &token, balance);
+        // write the denomination of NAM into storage
+        storage_api::token::write_denom(
+            &mut tx_env.wl_storage,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        let denominated = |amount| token::DenominatedAmount {
+            amount,
+            denom: token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        };
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfers in a transaction
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                address,
+                &target,
+                &token,
+                denominated(sent),
+            )
+            .unwrap();
+            if !received.is_zero() {
+                tx_host_env::token::transfer(
+                    tx::ctx(),
+                    &target,
+                    address,
+                    &token,
+                    denominated(received),
+                )
+                .unwrap();
+            }
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Test that a signed debit within the policy's spend limit is accepted
+    /// and that one over the limit is rejected.
+    #[test]
+    fn test_policy_spend_limit() {
+        let limit = token::Amount::from_uint(1_000, 0).unwrap();
+        let policy = account::AccountPolicy {
+            spend_limits: [(address::nam(), limit)].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(validate_signed_transfer(limit, with_policy(policy.clone())));
+        assert!(!validate_signed_transfer(
+            limit + token::Amount::from_uint(1, 0).unwrap(),
+            with_policy(policy)
+        ));
+    }
+
+    /// Test that the credits to the account in the same tx count against its
+    /// debits for the policy's spend limit.
+    #[test]
+    fn test_policy_spend_limit_net_of_credits() {
+        let limit = token::Amount::from_uint(1_000, 0).unwrap();
+        let policy = account::AccountPolicy {
+            spend_limits: [(address::nam(), limit)].into_iter().collect(),
+            ..Default::default()
+        };
+        let sent = limit + limit;
+        assert!(validate_signed_transfers(
+            sent,
+            limit,
+            with_policy(policy.clone())
+        ));
+        assert!(!validate_signed_transfers(
+            sent,
+            limit - token::Amount::from_uint(1, 0).unwrap(),
+            with_policy(policy)
+        ));
+    }
+
+    /// Test that a policy change must be signed with all the keys of the
+    /// account, even if the policy has no high value thresholds.
+    #[test]
+    fn test_policy_change_requires_all_keys() {
+        let validate_policy_change = |signing_keys| {
+            // Initialize a tx environment
+            let mut tx_env = TestTxEnv::default();
+
+            let vp_owner = address::testing::established_address_1();
+            let public_keys = vec![
+                key::testing::keypair_1().ref_to(),
+                key::testing::keypair_2().ref_to(),
+            ];
+            let policy = account::AccountPolicy {
+                spend_limits: [(
+                    address::nam(),
+                    token::Amount::from_uint(1_000, 0).unwrap(),
+                )]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            };
+
+            // Spawn the accounts to be able to modify their storage
+            tx_env.spawn_accounts([&vp_owner]);
+            tx_env.init_account_storage(&vp_owner, public_keys.clone(), 1);
+            with_policy(policy)(&mut tx_env.wl_storage, &vp_owner);
+
+            // Initialize VP environment from a transaction
+            vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+                // Drop the spend limits in a transaction
+                tx::ctx()
+                    .write(
+                        &key::policy_key(address),
+                        account::AccountPolicy::default(),
+                    )
+                    .unwrap();
+            });
+
+            let pks_map = AccountPublicKeysMap::from_iter(public_keys);
+
+            let mut vp_env = vp_host_env::take();
+            let mut tx = vp_env.tx.clone();
+            tx.set_data(Data::new(vec![]));
+            tx.set_code(Code::new(vec![], None));
+            tx.add_section(Section::Signature(Signature::new(
+                vec![tx.raw_header_hash()],
+                pks_map.index_secret_keys(signing_keys),
+                None,
+            )));
+            let signed_tx = tx.clone();
+            vp_env.tx = signed_tx.clone();
+            let keys_changed: BTreeSet<storage::Key> =
+                vp_env.all_touched_storage_keys();
+            let verifiers: BTreeSet<Address> = BTreeSet::default();
+            vp_host_env::set(vp_env);
+            validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers)
+                .unwrap()
+        };
+        assert!(!validate_policy_change(vec![key::testing::keypair_1()]));
+        assert!(validate_policy_change(vec![
+            key::testing::keypair_1(),
+            key::testing::keypair_2()
+        ]));
+    }
+
+    /// Test that a signed debit to a counterparty that is not allowlisted by
+    /// the policy is rejected.
+    #[test]
+    fn test_policy_counterparty_not_allowed_rejected() {
+        let amount = token::Amount::from_uint(1_000, 0).unwrap();
+        let policy = account::AccountPolicy {
+            allowed_counterparties: [address::testing::established_address_2()]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        assert!(validate_signed_transfer(amount, with_policy(policy)));
+
+        let policy = account::AccountPolicy {
+            allowed_counterparties: [address::testing::established_address_3()]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        assert!(!validate_signed_transfer(amount, with_policy(policy)));
+    }
+
+    /// Test that a signed debit above the policy's high value amount is
+    /// rejected without enough signatures.
+    #[test]
+    fn test_policy_high_value_threshold() {
+        let high_value = token::Amount::from_uint(1_000, 0).unwrap();
+        let policy = account::AccountPolicy {
+            high_value_thresholds: [(
+                address::nam(),
+                account::HighValueThreshold {
+                    amount: high_value,
+                    threshold: 2,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        assert!(validate_signed_transfer(
+            high_value,
+            with_policy(policy.clone())
+        ));
+        assert!(!validate_signed_transfer(
+            high_value + token::Amount::from_uint(1, 0).unwrap(),
+            with_policy(policy)
+        ));
+    }
+
+    /// Test that a signed debit from an account that restricts the allowed txs
+    /// is only accepted from an allowed tx.
+    #[test]
+    fn test_allowed_txs() {
+        let amount = token::Amount::from_uint(1_000, 0).unwrap();
+        let transfer_hash = Code::new(vec![], None).hash();
+        let with_allowed_txs = |allowed_txs: BTreeSet<Hash>| {
+            move |wl_storage: &mut TestWlStorage, vp_owner: &Address| {
+                wl_storage
+                    .write(&key::allowed_txs_key(vp_owner), allowed_txs)
+                    .unwrap();
+            }
+        };
+        assert!(validate_signed_transfer(
+            amount,
+            with_allowed_txs(BTreeSet::from([transfer_hash]))
+        ));
+        assert!(!validate_signed_transfer(
+            amount,
+            with_allowed_txs(BTreeSet::from([Hash::sha256(b"tx_bond")]))
+        ));
+    }
+
     /// Test that a non-validator PoS action that must be authorized is rejected
     /// without a valid signature.
     #[test]
@@ -1274,12 +2002,7 @@ mod tests {
     fn test_signed_vp_update_not_whitelisted_rejected() {
         // Initialize a tx environment
         let mut tx_env = TestTxEnv::default();
-        tx_env.init_parameters(
-            None,
-            Some(vec!["some_hash".to_string()]),
-            None,
-            None,
-        );
+        tx_env.init_parameters(None, None, None, None);
 
         let vp_owner = address::testing::established_address_1();
         let keypair = key::testing::keypair_1();
@@ -1304,6 +2027,17 @@ mod tests {
         let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
 
         let mut vp_env = vp_host_env::take();
+        // Restrict the whitelist in the state prior to the tx, as the host
+        // env already rejects the update of a VP that is not whitelisted, to
+        // check the VP's own check
+        vp_env
+            .wl_storage
+            .storage
+            .write(
+                &parameters::storage::get_vp_whitelist_storage_key(),
+                vec!["some_hash".to_string()].serialize_to_vec(),
+            )
+            .unwrap();
         let mut tx = vp_env.tx.clone();
         tx.set_data(Data::new(vec![]));
         tx.set_code(Code::new(vec![], None));
@@ -1318,10 +2052,45 @@ mod tests {
             vp_env.all_touched_storage_keys();
         let verifiers: BTreeSet<Address> = BTreeSet::default();
         vp_host_env::set(vp_env);
-        assert!(
-            !validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers)
-                .unwrap()
+        assert!(!validate_tx(
+            &CTX,
+            signed_tx,
+            vp_owner,
+            keys_changed,
+            verifiers
+        )
+        .unwrap());
+    }
+
+    /// Test that a tx cannot update a validity predicate to one that is not
+    /// whitelisted
+    #[test]
+    fn test_vp_update_not_whitelisted_fails() {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+        tx_env.init_parameters(
+            None,
+            Some(vec!["some_hash".to_string()]),
+            None,
+            None,
         );
+
+        let vp_owner = address::testing::established_address_1();
+        let vp_code = TestWasms::VpAlwaysTrue.read_bytes();
+        let vp_hash = sha256(&vp_code);
+        // for the update
+        tx_env.store_wasm_code(vp_code);
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner]);
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner, tx_env, |address| {
+            // Update VP in a transaction
+            assert!(tx::ctx()
+                .update_validity_predicate(address, vp_hash, &None)
+                .is_err());
+        });
     }
 
     /// Test that a validity predicate update is accepted if whitelisted
@@ -1380,6 +2149,123 @@ mod tests {
         );
     }
 
+    /// Run a tx on an account with a VP timelock and the given scheduled VP
+    /// update, signed with the given keys, and return the result of its VP.
+    /// The tx applies the VP update if `apply_update` is set, otherwise it
+    /// cancels the scheduled update.
+    fn validate_tx_with_vp_timelock(
+        pending_activation: Option<Epoch>,
+        apply_update: bool,
+        signing_keys: Vec<key::common::SecretKey>,
+    ) -> bool {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+        tx_env.init_parameters(None, None, None, None);
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let recovery_key = key::testing::keypair_2().ref_to();
+        let vp_code = TestWasms::VpAlwaysTrue.read_bytes();
+        let vp_hash = sha256(&vp_code);
+        // for the update
+        tx_env.store_wasm_code(vp_code);
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        tx_env
+            .wl_storage
+            .write(
+                &key::vp_timelock_key(&vp_owner),
+                account::VpUpdateTimelock {
+                    delay: 2,
+                    recovery_key: recovery_key.clone(),
+                },
+            )
+            .unwrap();
+        if let Some(activation_epoch) = pending_activation {
+            tx_env
+                .wl_storage
+                .write(
+                    &key::pending_vp_key(&vp_owner),
+                    account::PendingVpUpdate {
+                        vp_code_hash: vp_hash,
+                        activation_epoch,
+                    },
+                )
+                .unwrap();
+        }
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            if apply_update {
+                tx::ctx()
+                    .update_validity_predicate(address, vp_hash, &None)
+                    .unwrap();
+            }
+            tx::ctx().delete(&key::pending_vp_key(address)).unwrap();
+        });
+
+        let pks_map =
+            AccountPublicKeysMap::from_iter(vec![public_key, recovery_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(signing_keys),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Test that a VP update under a timelock is only accepted once it was
+    /// scheduled and its activation epoch is reached.
+    #[test]
+    fn test_timelocked_vp_update() {
+        let keypair = key::testing::keypair_1();
+        assert!(!validate_tx_with_vp_timelock(
+            None,
+            true,
+            vec![keypair.clone()]
+        ));
+        assert!(!validate_tx_with_vp_timelock(
+            Some(Epoch(1)),
+            true,
+            vec![keypair.clone()]
+        ));
+        assert!(validate_tx_with_vp_timelock(
+            Some(Epoch(0)),
+            true,
+            vec![keypair]
+        ));
+    }
+
+    /// Test that a scheduled VP update can only be cancelled with the recovery
+    /// key.
+    #[test]
+    fn test_vp_update_cancel_requires_recovery_key() {
+        assert!(!validate_tx_with_vp_timelock(
+            Some(Epoch(1)),
+            false,
+            vec![key::testing::keypair_1()]
+        ));
+        assert!(validate_tx_with_vp_timelock(
+            Some(Epoch(1)),
+            false,
+            vec![key::testing::keypair_2()]
+        ));
+    }
+
     /// Test that a tx is rejected if not whitelisted
     #[test]
     fn test_tx_not_whitelisted_rejected() {
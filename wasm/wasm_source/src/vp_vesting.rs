@@ -0,0 +1,457 @@
+//! A vesting account VP.
+//!
+//! The native tokens of a vesting account are locked by the vesting schedule
+//! stored under the account. Nothing is released before the cliff epoch,
+//! after which the tokens are released linearly until the end epoch. Only the
+//! released portion can be transferred or unbonded: the locked tokens can be
+//! bonded, but the balance of the account together with its bonds must cover
+//! the amount still locked. The vesting schedule cannot be changed and the VP
+//! cannot be replaced until all the tokens are released.
+//!
+//! Debits and bonds require a valid signature, as with the user VP. Any other
+//! storage key changes are allowed only with a valid signature.
+
+use namada_vp_prelude::*;
+use once_cell::unsync::Lazy;
+
+#[validity_predicate(gas = 137325)]
+fn validate_tx(
+    ctx: &Ctx,
+    tx_data: Tx,
+    addr: Address,
+    keys_changed: BTreeSet<storage::Key>,
+    verifiers: BTreeSet<Address>,
+) -> VpResult {
+    debug_log!(
+        "vp_vesting called with user addr: {}, key_changed: {:?}, verifiers: \
+         {:?}",
+        addr,
+        keys_changed,
+        verifiers
+    );
+
+    let valid_sig = Lazy::new(|| {
+        matches!(verify_signatures(ctx, &tx_data, &addr), Ok(true))
+    });
+    let vesting_respected =
+        Lazy::new(|| matches!(is_vesting_respected(ctx, &addr), Ok(true)));
+
+    if !is_valid_tx(ctx, &tx_data)? {
+        return reject();
+    }
+
+    for key in keys_changed.iter() {
+        let is_valid = if let Some([_token, owner]) =
+            token::is_any_token_balance_key(key)
+        {
+            if owner == &addr {
+                let pre: token::Amount = ctx.read_pre(key)?.unwrap_or_default();
+                let post: token::Amount =
+                    ctx.read_post(key)?.unwrap_or_default();
+                // A debit has to be signed and must leave the locked tokens
+                // in the account, a credit doesn't
+                post >= pre || (*valid_sig && *vesting_respected)
+            } else {
+                // Balance changes of other accounts
+                true
+            }
+        } else if proof_of_stake::storage::is_pos_key(key) {
+            let bond_id = proof_of_stake::storage::is_bond_key(key)
+                .map(|(bond_id, _)| bond_id)
+                .or_else(|| {
+                    proof_of_stake::storage::is_unbond_key(key)
+                        .map(|(bond_id, _, _)| bond_id)
+                });
+            match bond_id {
+                // Bonds and unbonds of this address must be signed and the
+                // locked tokens can only be unbonded once released
+                Some(bond_id) if bond_id.source == addr => {
+                    *valid_sig && *vesting_respected
+                }
+                // Any other PoS changes are allowed without signature
+                _ => true,
+            }
+        } else if let Some(owner) = key::is_vesting_key(key) {
+            if owner == &addr {
+                // The vesting schedule cannot be changed once set, and its
+                // epochs must be in order
+                let pre =
+                    storage_api::account::vesting_schedule(&ctx.pre(), owner)?;
+                let post =
+                    storage_api::account::vesting_schedule(&ctx.post(), owner)?;
+                *valid_sig
+                    && pre.is_none()
+                    && post.map_or(false, |schedule| schedule.is_valid())
+            } else {
+                true
+            }
+        } else if let Some(owner) = key.is_validity_predicate() {
+            let has_post: bool = ctx.has_key_post(key)?;
+            if owner == &addr {
+                if has_post {
+                    let vp_hash: Vec<u8> = ctx.read_bytes_post(key)?.unwrap();
+                    // The VP cannot be replaced while any tokens are still
+                    // locked, as it would lift the vesting
+                    *valid_sig
+                        && !is_vesting_locked(ctx, &addr)?
+                        && is_vp_whitelisted(ctx, &vp_hash)?
+                } else {
+                    false
+                }
+            } else {
+                let vp_hash: Vec<u8> = ctx.read_bytes_post(key)?.unwrap();
+                is_vp_whitelisted(ctx, &vp_hash)?
+            }
+        } else if key.segments.get(0) == Some(&addr.to_db_key()) {
+            // Any other changes to this address space require a valid
+            // signature
+            *valid_sig
+        } else {
+            // Any other changes anywhere else are permitted
+            true
+        };
+
+        if !is_valid {
+            debug_log!("key {} modification failed vp", key);
+            return reject();
+        }
+    }
+
+    accept()
+}
+
+/// Check if any native tokens are still locked by the vesting schedule of the
+/// `owner` at the current epoch
+fn is_vesting_locked(ctx: &Ctx, owner: &Address) -> EnvResult<bool> {
+    let schedule =
+        match storage_api::account::vesting_schedule(&ctx.pre(), owner)? {
+            Some(schedule) => schedule,
+            None => return Ok(false),
+        };
+    Ok(!schedule.locked_at(ctx.get_block_epoch()?).is_zero())
+}
+
+/// Check that the native tokens still locked by the vesting schedule of the
+/// `owner` are covered by its balance and its bonds after the tx
+fn is_vesting_respected(ctx: &Ctx, owner: &Address) -> EnvResult<bool> {
+    let schedule =
+        match storage_api::account::vesting_schedule(&ctx.pre(), owner)? {
+            Some(schedule) => schedule,
+            None => return Ok(true),
+        };
+    let locked = schedule.locked_at(ctx.get_block_epoch()?);
+    if locked.is_zero() {
+        return Ok(true);
+    }
+
+    let native_token = ctx.get_native_token()?;
+    let balance: token::Amount = ctx
+        .read_post(&token::balance_key(&native_token, owner))?
+        .unwrap_or_default();
+    let mut bonded = token::Amount::zero();
+    for validator in
+        proof_of_stake::find_delegation_validators(&ctx.post(), owner)?
+    {
+        for amount in
+            proof_of_stake::find_bonds(&ctx.post(), owner, &validator)?
+                .into_values()
+        {
+            bonded += amount;
+        }
+    }
+    debug_log!(
+        "vesting locked: {}, balance: {}, bonded: {}",
+        locked.to_string_native(),
+        balance.to_string_native(),
+        bonded.to_string_native()
+    );
+    Ok(balance
+        .checked_add(bonded)
+        .map_or(true, |covered| covered >= locked))
+}
+
+#[cfg(test)]
+mod tests {
+    use namada::ledger::gas::TxGasMeter;
+    use namada::ledger::parameters::update_storage_deposit_per_byte_parameter;
+    use namada::ledger::protocol::apply_storage_deposits;
+    use namada::proto::{Code, Data, Signature};
+    use namada::types::account::VestingSchedule;
+    use namada::types::storage::Epoch;
+    use namada_test_utils::TestWasms;
+    // Use this as `#[test]` annotation to enable logging
+    use namada_tests::log::test;
+    use namada_tests::tx::{self, tx_host_env, TestTxEnv};
+    use namada_tests::vp::*;
+    use namada_tx_prelude::{StorageWrite, TxEnv};
+    use namada_vp_prelude::account::AccountPublicKeysMap;
+    use namada_vp_prelude::key::RefTo;
+
+    use super::*;
+
+    /// Run a signed transfer of `amount` from a vesting account with the
+    /// given balance and schedule and return the result of its VP.
+    fn validate_vesting_transfer(
+        balance: token::Amount,
+        schedule: VestingSchedule,
+        amount: token::Amount,
+    ) -> bool {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let target = address::testing::established_address_2();
+        let token = address::nam();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &target, &token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        storage_api::account::write_vesting_schedule(
+            &mut tx_env.wl_storage,
+            &vp_owner,
+            &schedule,
+        )
+        .unwrap();
+        tx_env.credit_tokens(&vp_owner, &token, balance);
+        storage_api::token::write_denom(
+            &mut tx_env.wl_storage,
+            &token,
+            token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        )
+        .unwrap();
+
+        let amount = token::DenominatedAmount {
+            amount,
+            denom: token::NATIVE_MAX_DECIMAL_PLACES.into(),
+        };
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfer in a transaction
+            tx_host_env::token::transfer(
+                tx::ctx(),
+                address,
+                &target,
+                &token,
+                amount,
+            )
+            .unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Test that only the tokens that are not locked by the vesting schedule
+    /// can be transferred.
+    #[test]
+    fn test_vesting_transfer() {
+        let balance = token::Amount::from_uint(10_000, 0).unwrap();
+        let locked = token::Amount::from_uint(6_000, 0).unwrap();
+        let released = balance - locked;
+        let schedule = VestingSchedule {
+            total: locked,
+            start_epoch: Epoch(0),
+            cliff_epoch: Epoch(0),
+            end_epoch: Epoch(10),
+        };
+        assert!(validate_vesting_transfer(balance, schedule, released));
+        assert!(!validate_vesting_transfer(
+            balance,
+            schedule,
+            released + token::Amount::from_uint(1, 0).unwrap()
+        ));
+
+        // All the tokens are locked before the cliff
+        let schedule = VestingSchedule {
+            total: balance,
+            cliff_epoch: Epoch(5),
+            ..schedule
+        };
+        assert!(!validate_vesting_transfer(
+            balance,
+            schedule,
+            token::Amount::from_uint(1, 0).unwrap()
+        ));
+    }
+
+    /// Run a signed tx that writes 10 bytes of state of a vesting account
+    /// with the given balance and schedule, which pays the storage deposit of
+    /// 100 per byte, and return the result of its VP.
+    fn validate_vesting_storage_deposit(
+        balance: token::Amount,
+        schedule: VestingSchedule,
+    ) -> bool {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let token = tx_env.wl_storage.storage.native_token.clone();
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner, &token]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        storage_api::account::write_vesting_schedule(
+            &mut tx_env.wl_storage,
+            &vp_owner,
+            &schedule,
+        )
+        .unwrap();
+        tx_env.credit_tokens(&vp_owner, &token, balance);
+        update_storage_deposit_per_byte_parameter(
+            &mut tx_env.wl_storage,
+            &token::Amount::from_uint(100, 0).unwrap(),
+        )
+        .unwrap();
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Write some state in a transaction
+            let key = storage::Key::from(address.to_db_key())
+                .push(&"data".to_string())
+                .unwrap();
+            tx::ctx().write_bytes(&key, [0; 10]).unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        // The protocol locks the deposit before the VPs run
+        apply_storage_deposits(
+            &mut vp_env.wl_storage,
+            &vp_owner,
+            &mut TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        )
+        .unwrap();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Test that a vesting account can only pay the storage deposits with the
+    /// tokens that are not locked by the vesting schedule.
+    #[test]
+    fn test_vesting_storage_deposit() {
+        let balance = token::Amount::from_uint(10_000, 0).unwrap();
+        let deposit = token::Amount::from_uint(1_000, 0).unwrap();
+        let schedule = VestingSchedule {
+            total: balance - deposit,
+            start_epoch: Epoch(0),
+            cliff_epoch: Epoch(0),
+            end_epoch: Epoch(10),
+        };
+        assert!(validate_vesting_storage_deposit(balance, schedule));
+
+        // The deposit can't be paid with the locked tokens
+        let schedule = VestingSchedule {
+            total: balance,
+            ..schedule
+        };
+        assert!(!validate_vesting_storage_deposit(balance, schedule));
+    }
+
+    /// Run a signed update of the VP of a vesting account with the given
+    /// schedule and return the result of its VP.
+    fn validate_vesting_vp_update(schedule: VestingSchedule) -> bool {
+        // Initialize a tx environment
+        let mut tx_env = TestTxEnv::default();
+        tx_env.init_parameters(None, None, None, None);
+
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+        let vp_code = TestWasms::VpAlwaysTrue.read_bytes();
+        let vp_hash = sha256(&vp_code);
+        // for the update
+        tx_env.store_wasm_code(vp_code);
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([&vp_owner]);
+        tx_env.init_account_storage(&vp_owner, vec![public_key.clone()], 1);
+        storage_api::account::write_vesting_schedule(
+            &mut tx_env.wl_storage,
+            &vp_owner,
+            &schedule,
+        )
+        .unwrap();
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Update VP in a transaction
+            tx::ctx()
+                .update_validity_predicate(address, vp_hash, &None)
+                .unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![keypair]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        validate_tx(&CTX, signed_tx, vp_owner, keys_changed, verifiers).unwrap()
+    }
+
+    /// Test that the VP of a vesting account can only be replaced once all
+    /// its tokens are released.
+    #[test]
+    fn test_vesting_vp_update() {
+        let schedule = VestingSchedule {
+            total: token::Amount::from_uint(6_000, 0).unwrap(),
+            start_epoch: Epoch(0),
+            cliff_epoch: Epoch(0),
+            end_epoch: Epoch(10),
+        };
+        assert!(!validate_vesting_vp_update(schedule));
+
+        // All the tokens are released from the end epoch
+        let schedule = VestingSchedule {
+            end_epoch: Epoch(0),
+            ..schedule
+        };
+        assert!(validate_vesting_vp_update(schedule));
+    }
+}
@@ -16,7 +16,7 @@ pub mod main {
 
     #[transaction(gas = 1000)]
     fn apply_tx(_ctx: &mut Ctx, _tx_data: Tx) -> TxResult {
-        Err(Error::SimpleMessage("failed tx"))
+        Err(Error::new_const("failed tx"))
     }
 }
 